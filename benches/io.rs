@@ -3,9 +3,16 @@ use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_m
 use bevy::prelude::Transform;
 use bevy_gaussian_splatting::{
     CloudSettings, Gaussian3d, Gaussian4d, GaussianPrimitiveMetadata, PlanarGaussian3d,
-    PlanarGaussian4d, SceneExportCloud, io::codec::CloudCodec,
-    io::scene::encode_khr_gaussian_scene_gltf_bytes, random_gaussians_3d, random_gaussians_4d,
+    PlanarGaussian4d, SceneExportCloud,
+    io::codec::CloudCodec,
+    io::gcloud::{
+        entropy::GcloudEntropySettings,
+        header::{GcloudContainer, GcloudMetadata},
+    },
+    io::scene::encode_khr_gaussian_scene_gltf_bytes,
+    random_gaussians_3d, random_gaussians_4d,
 };
+use bevy_interleave::prelude::Planar;
 
 const GAUSSIAN_COUNTS: [usize; 4] = [
     1000, 10000, 84_348, 1_244_819,
@@ -20,7 +27,7 @@ fn gaussian_cloud_3d_decode_benchmark(c: &mut Criterion) {
         ));
         group.bench_with_input(BenchmarkId::new("decode/3d", count), &count, |b, &count| {
             let gaussians = random_gaussians_3d(*count);
-            let bytes = gaussians.encode();
+            let bytes = gaussians.encode().expect("failed to encode cloud");
 
             b.iter(|| PlanarGaussian3d::decode(bytes.as_slice()));
         });
@@ -35,13 +42,74 @@ fn gaussian_cloud_4d_decode_benchmark(c: &mut Criterion) {
         ));
         group.bench_with_input(BenchmarkId::new("decode/4d", count), &count, |b, &count| {
             let gaussians = random_gaussians_4d(*count);
-            let bytes = gaussians.encode();
+            let bytes = gaussians.encode().expect("failed to encode cloud");
 
             b.iter(|| PlanarGaussian4d::decode(bytes.as_slice()));
         });
     }
 }
 
+fn entropy_encode(cloud: &PlanarGaussian3d, settings: &GcloudEntropySettings) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    cloud
+        .encode_entropy_container(&mut bytes, &GcloudMetadata::new(), settings)
+        .expect("failed to entropy encode cloud");
+    bytes
+}
+
+fn gaussian_cloud_3d_entropy_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("entropy coded 3d gaussian clouds");
+    let settings = [
+        ("lossless", GcloudEntropySettings::lossless()),
+        ("16bit", GcloudEntropySettings::default()),
+        (
+            "12bit_sh8",
+            GcloudEntropySettings::default()
+                .bits(12)
+                .plane_bits("spherical_harmonic", 8),
+        ),
+    ];
+
+    for count in GAUSSIAN_COUNTS.iter() {
+        let gaussians = random_gaussians_3d(*count);
+        let raw_size = *count * std::mem::size_of::<Gaussian3d>();
+
+        group.throughput(Throughput::Bytes(raw_size as u64));
+        for (name, settings) in settings.iter() {
+            let bytes = entropy_encode(&gaussians, settings);
+            let (_, decoded) = PlanarGaussian3d::decode_container(bytes.as_slice())
+                .expect("failed to decode entropy cloud");
+
+            // measured once per settings and count, outside the timed closures
+            let original = gaussians.to_interleaved();
+            let decoded = decoded.to_interleaved();
+            let errors = bytemuck::cast_slice::<Gaussian3d, f32>(&original)
+                .iter()
+                .zip(bytemuck::cast_slice::<Gaussian3d, f32>(&decoded))
+                .map(|(a, b)| (a - b).abs());
+            let max_error = errors.clone().fold(0.0_f32, f32::max);
+            let mean_error = errors.sum::<f32>() / (raw_size / 4).max(1) as f32;
+
+            eprintln!(
+                "entropy/3d/{name}/{count}: {:.2}x compression ({raw_size} -> {} bytes), max error {max_error:.3e}, mean error {mean_error:.3e}",
+                raw_size as f64 / bytes.len() as f64,
+                bytes.len(),
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("encode/entropy_{name}"), count),
+                &gaussians,
+                |b, gaussians| b.iter(|| entropy_encode(gaussians, settings)),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("decode/entropy_{name}"), count),
+                &bytes,
+                |b, bytes| b.iter(|| PlanarGaussian3d::decode_container(bytes.as_slice())),
+            );
+        }
+    }
+}
+
 fn khr_gltf_scene_encode_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("encode khr gltf gaussian scenes");
     for count in GAUSSIAN_COUNTS.iter() {
@@ -54,7 +122,7 @@ fn khr_gltf_scene_encode_benchmark(c: &mut Criterion) {
             |b, &count| {
                 let cloud = random_gaussians_3d(*count);
                 let export_cloud = SceneExportCloud {
-                    cloud,
+                    cloud: cloud.into(),
                     name: "benchmark_cloud".to_owned(),
                     settings: CloudSettings::default(),
                     transform: Transform::default(),
@@ -75,6 +143,7 @@ criterion_group! {
     config = Criterion::default().sample_size(10);
     targets = gaussian_cloud_3d_decode_benchmark,
               gaussian_cloud_4d_decode_benchmark,
+              gaussian_cloud_3d_entropy_benchmark,
               khr_gltf_scene_encode_benchmark,
 }
 criterion_main!(io_benches);
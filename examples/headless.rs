@@ -106,6 +106,9 @@ fn setup_gaussian_cloud(
         gaussian_mode: args.gaussian_mode,
         playback_mode: args.playback_mode,
         rasterize_mode: args.rasterization_mode,
+        rasterizer: args.rasterizer,
+        oit_weight: args.oit_weight,
+        depth_mode: args.depth_mode,
         radix_sort_depth_bits: args.radix_sort_depth_bits,
         ..default()
     };
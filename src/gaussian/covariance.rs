@@ -1,4 +1,4 @@
-use bevy::math::{Mat3, Vec3, Vec4};
+use bevy::math::{Mat3, Quat, Vec3, Vec4};
 
 #[allow(non_snake_case)]
 pub fn compute_covariance_3d(rotation: Vec4, scale: Vec3) -> [f32; 6] {
@@ -39,3 +39,59 @@ pub fn compute_covariance_3d(rotation: Vec4, scale: Vec3) -> [f32; 6] {
         Sigma.row(2).z,
     ]
 }
+
+/// eigendecomposition of a symmetric covariance into a `[w, x, y, z]` rotation and per-axis scale
+pub fn decompose_covariance_3d(sigma: Mat3) -> (Vec4, Vec3) {
+    let mut a = sigma.to_cols_array_2d();
+    let mut v = Mat3::IDENTITY.to_cols_array_2d();
+
+    // cyclic jacobi sweeps, converges in a handful of iterations for 3x3
+    for _ in 0..16 {
+        let off_diagonal = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+        if off_diagonal < 1e-20 {
+            break;
+        }
+
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() < 1e-20 {
+                continue;
+            }
+
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            for row in a.iter_mut() {
+                let (akp, akq) = (row[p], row[q]);
+                row[p] = c * akp - s * akq;
+                row[q] = s * akp + c * akq;
+            }
+            let [row_p, row_q] = a.get_disjoint_mut([p, q]).unwrap();
+            for (apk, aqk) in row_p.iter_mut().zip(row_q.iter_mut()) {
+                (*apk, *aqk) = (c * *apk - s * *aqk, s * *apk + c * *aqk);
+            }
+            for column in v.iter_mut() {
+                let (vp, vq) = (column[p], column[q]);
+                column[p] = c * vp - s * vq;
+                column[q] = s * vp + c * vq;
+            }
+        }
+    }
+
+    // `v` is row-major, transpose so the eigenvectors become columns
+    let mut basis = Mat3::from_cols_array_2d(&v).transpose();
+    if basis.determinant() < 0.0 {
+        basis.z_axis = -basis.z_axis;
+    }
+
+    let scale = Vec3::new(a[0][0], a[1][1], a[2][2])
+        .max(Vec3::ZERO)
+        .map(f32::sqrt);
+    let rotation = Quat::from_mat3(&basis).normalize();
+
+    (
+        Vec4::new(rotation.w, rotation.x, rotation.y, rotation.z),
+        scale,
+    )
+}
@@ -8,7 +8,7 @@ use bytemuck::{Pod, Zeroable};
 use serde::{Deserialize, Serialize};
 
 use crate::gaussian::{
-    f32::{Covariance3dOpacity, Rotation, ScaleOpacity},
+    f32::{Covariance3dOpacity, Rotation, ScaleOpacity, TimestampTimescale},
     formats::{planar_3d::Gaussian3d, planar_4d::Gaussian4d},
 };
 
@@ -241,6 +241,90 @@ impl From<[u32; 4]> for IsotropicRotations {
     }
 }
 
+#[allow(dead_code)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    Reflect,
+    ShaderType,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[repr(C)]
+pub struct ScaleOpacityPacked64 {
+    pub scale_opacity: [u32; 2],
+}
+
+impl From<&ScaleOpacity> for ScaleOpacityPacked64 {
+    fn from(scale_opacity: &ScaleOpacity) -> Self {
+        Self {
+            scale_opacity: [
+                pack_f32s_to_u32(scale_opacity.scale[0], scale_opacity.scale[1]),
+                pack_f32s_to_u32(scale_opacity.scale[2], scale_opacity.opacity),
+            ],
+        }
+    }
+}
+
+impl ScaleOpacityPacked64 {
+    pub fn scale_opacity(&self) -> ScaleOpacity {
+        let (u0, l0) = unpack_u32_to_f32s(self.scale_opacity[0]);
+        let (u1, l1) = unpack_u32_to_f32s(self.scale_opacity[1]);
+
+        ScaleOpacity {
+            scale: [u0, l0, u1],
+            opacity: l1,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    Reflect,
+    ShaderType,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[repr(C)]
+pub struct TimestampTimescalePacked32 {
+    pub timestamp_timescale: u32,
+}
+
+impl From<&TimestampTimescale> for TimestampTimescalePacked32 {
+    fn from(timestamp_timescale: &TimestampTimescale) -> Self {
+        Self {
+            timestamp_timescale: pack_f32s_to_u32(
+                timestamp_timescale.timestamp,
+                timestamp_timescale.timescale,
+            ),
+        }
+    }
+}
+
+impl TimestampTimescalePacked32 {
+    pub fn timestamp_timescale(&self) -> TimestampTimescale {
+        let (timestamp, timescale) = unpack_u32_to_f32s(self.timestamp_timescale);
+
+        TimestampTimescale {
+            timestamp,
+            timescale,
+            _pad: [0.0, 0.0],
+        }
+    }
+}
+
 pub fn pack_f32s_to_u32(upper: f32, lower: f32) -> u32 {
     pack_f16s_to_u32(f16::from_f32(upper), f16::from_f32(lower))
 }
@@ -214,6 +214,55 @@ impl From<[f32; 4]> for TimestampTimescale {
     }
 }
 
+/// cubic position polynomial and temporal radial basis of a spacetime gaussian
+#[allow(dead_code)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    Reflect,
+    ShaderType,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[repr(C)]
+pub struct SpacetimeMotion {
+    /// xyz coefficients of dt, dt^2 and dt^3
+    pub motion: [f32; 9],
+    pub trbf_center: f32,
+    pub trbf_scale: f32,
+    pub _pad: f32,
+}
+
+#[allow(dead_code)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    Reflect,
+    ShaderType,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[repr(C)]
+pub struct AngularVelocity {
+    pub omega: [f32; 4],
+}
+
+impl From<[f32; 4]> for AngularVelocity {
+    fn from(omega: [f32; 4]) -> Self {
+        Self { omega }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(
     Clone,
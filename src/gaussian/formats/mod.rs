@@ -2,6 +2,7 @@
 
 pub mod planar_3d;
 pub mod planar_3d_chunked;
+pub mod planar_3d_codebook;
 pub mod planar_3d_lod;
 pub mod planar_3d_quantized;
 pub mod planar_3d_spz;
@@ -270,4 +270,15 @@ impl PlanarGaussian3d {
                 },
             )
     }
+
+    /// 2dgs clouds hold flat surfels, every non-empty gaussian has a zero third scale
+    pub fn is_gaussian_2d(&self) -> bool {
+        let mut surfels = self
+            .scale_opacity
+            .iter()
+            .filter(|scale_opacity| scale_opacity.scale[0] > 0.0 || scale_opacity.scale[1] > 0.0)
+            .peekable();
+
+        surfels.peek().is_some() && surfels.all(|scale_opacity| scale_opacity.scale[2] == 0.0)
+    }
 }
@@ -1 +1,364 @@
+use bevy::{math::bounding::Aabb3d, prelude::*};
+use serde::{Deserialize, Serialize};
 
+use crate::{
+    gaussian::formats::{
+        planar_3d::{Gaussian3d, PlanarGaussian3d},
+        planar_3d_quantized::QuantizedRotation,
+    },
+    material::spherical_harmonics::{
+        SH_CHANNELS, SH_COEFF_COUNT, SH_COEFF_COUNT_PER_CHANNEL, clamp_sh_degree,
+    },
+    math::morton_3d,
+};
+
+/// gaussians per chunk, fixed by the PlayCanvas compressed ply layout
+pub const CHUNK_SIZE: usize = 256;
+
+/// highest spherical harmonic degree stored by the compressed ply layout
+pub const CHUNKED_MAX_SH_DEGREE: usize = 3;
+
+const SH_C0: f32 = 0.282_094_8;
+const LOG_SCALE_LIMIT: f32 = 20.0;
+const SH_REST_RANGE: f32 = 8.0;
+
+/// per-chunk bounds and dequantization ranges
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct GaussianChunk {
+    pub min_position: [f32; 3],
+    pub max_position: [f32; 3],
+    /// log-space scale range
+    pub min_scale: [f32; 3],
+    pub max_scale: [f32; 3],
+    /// base color range, `0.5 + SH_C0 * f_dc`
+    pub min_color: [f32; 3],
+    pub max_color: [f32; 3],
+}
+
+impl GaussianChunk {
+    pub fn aabb(&self) -> Aabb3d {
+        Aabb3d {
+            min: Vec3::from(self.min_position).into(),
+            max: Vec3::from(self.max_position).into(),
+        }
+    }
+}
+
+/// gaussians grouped into morton ordered chunks of `CHUNK_SIZE`, mirroring the PlayCanvas compressed ply layout
+#[derive(Asset, Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct PlanarGaussian3dChunked {
+    pub chunks: Vec<GaussianChunk>,
+    /// 11-10-11 bit unorm position within the chunk bounds
+    pub packed_position: Vec<u32>,
+    /// smallest-three quaternion, see `QuantizedRotation`
+    pub packed_rotation: Vec<u32>,
+    /// 11-10-11 bit unorm log-scale within the chunk scale range
+    pub packed_scale: Vec<u32>,
+    /// 8-8-8 bit unorm base color within the chunk color range, 8 bit opacity
+    pub packed_color: Vec<u32>,
+    /// `sh_coefficients` bytes per gaussian, channel planar like the ply `f_rest_*` properties
+    pub spherical_harmonics: Vec<u8>,
+    pub sh_coefficients: usize,
+}
+
+impl PlanarGaussian3dChunked {
+    pub fn len(&self) -> usize {
+        self.packed_position.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packed_position.is_empty()
+    }
+
+    pub fn chunk_range(&self, chunk: usize) -> std::ops::Range<usize> {
+        let start = chunk * CHUNK_SIZE;
+        start..(start + CHUNK_SIZE).min(self.len())
+    }
+
+    pub fn sh_degree(&self) -> usize {
+        let per_channel = self.sh_coefficients / SH_CHANNELS + 1;
+        (per_channel as f32).sqrt() as usize - 1
+    }
+
+    pub fn get(&self, index: usize) -> Gaussian3d {
+        let chunk = &self.chunks[index / CHUNK_SIZE];
+        let mut gaussian = Gaussian3d::default();
+
+        let position = unpack_111011(self.packed_position[index]);
+        let scale = unpack_111011(self.packed_scale[index]);
+        let color = unpack_8888(self.packed_color[index]);
+        for axis in 0..3 {
+            gaussian.position_visibility.position[axis] = lerp(
+                chunk.min_position[axis],
+                chunk.max_position[axis],
+                position[axis],
+            );
+            gaussian.scale_opacity.scale[axis] =
+                lerp(chunk.min_scale[axis], chunk.max_scale[axis], scale[axis]).exp();
+
+            let base_color = lerp(chunk.min_color[axis], chunk.max_color[axis], color[axis]);
+            gaussian
+                .spherical_harmonic
+                .set(axis, (base_color - 0.5) / SH_C0);
+        }
+        gaussian.scale_opacity.opacity = color[3];
+
+        gaussian.rotation = QuantizedRotation {
+            rotation: self.packed_rotation[index],
+        }
+        .dequantize();
+
+        let rest_per_channel = self.sh_coefficients / SH_CHANNELS;
+        let offset = index * self.sh_coefficients;
+        for channel in 0..SH_CHANNELS {
+            for coefficient in (1..=rest_per_channel).take(SH_COEFF_COUNT_PER_CHANNEL - 1) {
+                let byte =
+                    self.spherical_harmonics[offset + channel * rest_per_channel + coefficient - 1];
+                let interleaved_idx = coefficient * SH_CHANNELS + channel;
+
+                if interleaved_idx < SH_COEFF_COUNT {
+                    gaussian
+                        .spherical_harmonic
+                        .set(interleaved_idx, unpack_sh_byte(byte));
+                }
+            }
+        }
+
+        gaussian
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Gaussian3d> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+}
+
+impl From<&PlanarGaussian3dChunked> for PlanarGaussian3d {
+    fn from(cloud: &PlanarGaussian3dChunked) -> Self {
+        cloud.iter().collect()
+    }
+}
+
+impl From<&PlanarGaussian3d> for PlanarGaussian3dChunked {
+    fn from(cloud: &PlanarGaussian3d) -> Self {
+        ChunkedCloudBuilder::default().build(cloud)
+    }
+}
+
+/// converts a `PlanarGaussian3d` into morton ordered, per-chunk quantized storage
+#[derive(Clone, Debug)]
+pub struct ChunkedCloudBuilder {
+    pub morton_order: bool,
+    pub sh_degree: usize,
+}
+
+impl Default for ChunkedCloudBuilder {
+    fn default() -> Self {
+        Self {
+            morton_order: true,
+            sh_degree: clamp_sh_degree(CHUNKED_MAX_SH_DEGREE),
+        }
+    }
+}
+
+impl ChunkedCloudBuilder {
+    pub fn morton_order(mut self, morton_order: bool) -> Self {
+        self.morton_order = morton_order;
+        self
+    }
+
+    pub fn sh_degree(mut self, sh_degree: usize) -> Self {
+        self.sh_degree = clamp_sh_degree(sh_degree.min(CHUNKED_MAX_SH_DEGREE));
+        self
+    }
+
+    pub fn build(&self, cloud: &PlanarGaussian3d) -> PlanarGaussian3dChunked {
+        let mut gaussians = cloud.iter().collect::<Vec<_>>();
+        if self.morton_order {
+            sort_morton(&mut gaussians);
+        }
+
+        // the field is public, clamp degrees the setter would have rejected
+        let sh_degree = clamp_sh_degree(self.sh_degree.min(CHUNKED_MAX_SH_DEGREE));
+        let rest_per_channel = (sh_degree + 1).pow(2) - 1;
+        let mut chunked = PlanarGaussian3dChunked {
+            sh_coefficients: rest_per_channel * SH_CHANNELS,
+            ..default()
+        };
+
+        for group in gaussians.chunks(CHUNK_SIZE) {
+            let chunk = chunk_bounds(group);
+
+            for gaussian in group {
+                let position = std::array::from_fn(|axis| {
+                    inverse_lerp(
+                        chunk.min_position[axis],
+                        chunk.max_position[axis],
+                        gaussian.position_visibility.position[axis],
+                    )
+                });
+                let scale = std::array::from_fn(|axis| {
+                    inverse_lerp(
+                        chunk.min_scale[axis],
+                        chunk.max_scale[axis],
+                        log_scale(gaussian.scale_opacity.scale[axis]),
+                    )
+                });
+                let color = std::array::from_fn(|axis| {
+                    if axis == 3 {
+                        return gaussian.scale_opacity.opacity;
+                    }
+
+                    inverse_lerp(
+                        chunk.min_color[axis],
+                        chunk.max_color[axis],
+                        base_color(gaussian, axis),
+                    )
+                });
+
+                chunked.packed_position.push(pack_111011(position));
+                chunked.packed_scale.push(pack_111011(scale));
+                chunked.packed_color.push(pack_8888(color));
+                chunked
+                    .packed_rotation
+                    .push(QuantizedRotation::quantize(&gaussian.rotation).rotation);
+
+                for channel in 0..SH_CHANNELS {
+                    for coefficient in 1..=rest_per_channel {
+                        let value = gaussian
+                            .spherical_harmonic
+                            .get(coefficient * SH_CHANNELS + channel);
+                        chunked.spherical_harmonics.push(pack_sh_byte(value));
+                    }
+                }
+            }
+
+            chunked.chunks.push(chunk);
+        }
+
+        chunked
+    }
+}
+
+fn chunk_bounds(group: &[Gaussian3d]) -> GaussianChunk {
+    let mut chunk = GaussianChunk {
+        min_position: [f32::INFINITY; 3],
+        max_position: [f32::NEG_INFINITY; 3],
+        min_scale: [f32::INFINITY; 3],
+        max_scale: [f32::NEG_INFINITY; 3],
+        min_color: [f32::INFINITY; 3],
+        max_color: [f32::NEG_INFINITY; 3],
+    };
+
+    for gaussian in group {
+        for axis in 0..3 {
+            let position = gaussian.position_visibility.position[axis];
+            chunk.min_position[axis] = chunk.min_position[axis].min(position);
+            chunk.max_position[axis] = chunk.max_position[axis].max(position);
+
+            let scale = log_scale(gaussian.scale_opacity.scale[axis]);
+            chunk.min_scale[axis] = chunk.min_scale[axis].min(scale);
+            chunk.max_scale[axis] = chunk.max_scale[axis].max(scale);
+
+            let color = base_color(gaussian, axis);
+            chunk.min_color[axis] = chunk.min_color[axis].min(color);
+            chunk.max_color[axis] = chunk.max_color[axis].max(color);
+        }
+    }
+
+    chunk
+}
+
+pub(crate) fn sort_morton(gaussians: &mut [Gaussian3d]) {
+    let mut min = Vec3::splat(f32::INFINITY);
+    let mut max = Vec3::splat(f32::NEG_INFINITY);
+    for gaussian in gaussians.iter() {
+        let position = Vec3::from(gaussian.position_visibility.position);
+        min = min.min(position);
+        max = max.max(position);
+    }
+
+    let extent = (max - min).max(Vec3::splat(f32::EPSILON));
+    gaussians.sort_by_cached_key(|gaussian| {
+        let normalized = (Vec3::from(gaussian.position_visibility.position) - min) / extent;
+        let cell = (normalized * 1023.0)
+            .round()
+            .clamp(Vec3::ZERO, Vec3::splat(1023.0));
+
+        morton_3d(cell.x as u32, cell.y as u32, cell.z as u32)
+    });
+}
+
+fn log_scale(scale: f32) -> f32 {
+    scale
+        .max(f32::MIN_POSITIVE)
+        .ln()
+        .clamp(-LOG_SCALE_LIMIT, LOG_SCALE_LIMIT)
+}
+
+fn base_color(gaussian: &Gaussian3d, channel: usize) -> f32 {
+    0.5 + SH_C0 * gaussian.spherical_harmonic.get(channel)
+}
+
+fn lerp(min: f32, max: f32, t: f32) -> f32 {
+    min + (max - min) * t
+}
+
+fn inverse_lerp(min: f32, max: f32, value: f32) -> f32 {
+    if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+fn pack_unorm(value: f32, bits: u32) -> u32 {
+    let max = ((1 << bits) - 1) as f32;
+    (value.clamp(0.0, 1.0) * max).round() as u32
+}
+
+fn unpack_unorm(value: u32, bits: u32) -> f32 {
+    let max = (1 << bits) - 1;
+    (value & max) as f32 / max as f32
+}
+
+pub(crate) fn pack_111011(value: [f32; 3]) -> u32 {
+    (pack_unorm(value[0], 11) << 21) | (pack_unorm(value[1], 10) << 11) | pack_unorm(value[2], 11)
+}
+
+pub(crate) fn unpack_111011(value: u32) -> [f32; 3] {
+    [
+        unpack_unorm(value >> 21, 11),
+        unpack_unorm(value >> 11, 10),
+        unpack_unorm(value, 11),
+    ]
+}
+
+pub(crate) fn pack_8888(value: [f32; 4]) -> u32 {
+    value.iter().fold(0, |packed, &component| {
+        (packed << 8) | pack_unorm(component, 8)
+    })
+}
+
+pub(crate) fn unpack_8888(value: u32) -> [f32; 4] {
+    [
+        unpack_unorm(value >> 24, 8),
+        unpack_unorm(value >> 16, 8),
+        unpack_unorm(value >> 8, 8),
+        unpack_unorm(value, 8),
+    ]
+}
+
+fn pack_sh_byte(value: f32) -> u8 {
+    let normalized = value / SH_REST_RANGE + 0.5;
+    (normalized * 256.0).floor().clamp(0.0, 255.0) as u8
+}
+
+fn unpack_sh_byte(value: u8) -> f32 {
+    let normalized = if value == 0 {
+        0.0
+    } else {
+        (value as f32 + 0.5) / 256.0
+    };
+
+    (normalized - 0.5) * SH_REST_RANGE
+}
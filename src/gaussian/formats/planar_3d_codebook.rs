@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, render::sync_component::SyncComponent};
+use bevy_interleave::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use half::f16;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+#[cfg(feature = "sort_rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gaussian::{
+        f32::{PositionVisibility, Rotation, ScaleOpacity},
+        formats::planar_3d::{Gaussian3d, PlanarGaussian3d},
+        interface::{CommonCloud, PlanarStorageFormat},
+        iter::PositionIter,
+    },
+    material::spherical_harmonics::{
+        SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL, SphericalHarmonicCoefficients,
+    },
+};
+
+/// codebook indices are stored as u16
+pub const SH_CODEBOOK_MAX_SIZE: usize = 1 << 16;
+
+pub const SH_CODEBOOK_REST_COUNT: usize = (SH_COEFF_COUNT_PER_CHANNEL - 1) * SH_CHANNELS;
+
+const SH_REST: std::ops::Range<usize> = SH_CHANNELS..SH_CHANNELS + SH_CODEBOOK_REST_COUNT;
+
+#[derive(
+    Clone, Debug, Default, Copy, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct CodebookSphericalHarmonics {
+    /// f16 DC coefficients and the codebook index: r | g << 16, b | index << 16
+    pub coefficients: [u32; 2],
+}
+
+impl CodebookSphericalHarmonics {
+    pub fn new(dc: [f32; SH_CHANNELS], index: usize) -> Self {
+        let [r, g, b] = dc.map(|value| f16::from_f32(value).to_bits() as u32);
+
+        Self {
+            coefficients: [r | (g << 16), b | ((index as u32 & 0xffff) << 16)],
+        }
+    }
+
+    pub fn dc(&self) -> [f32; SH_CHANNELS] {
+        [
+            self.coefficients[0] & 0xffff,
+            self.coefficients[0] >> 16,
+            self.coefficients[1] & 0xffff,
+        ]
+        .map(|bits| f16::from_bits(bits as u16).to_f32())
+    }
+
+    pub fn index(&self) -> usize {
+        (self.coefficients[1] >> 16) as usize
+    }
+
+    pub fn with_index(&self, index: usize) -> Self {
+        Self::new(self.dc(), index)
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    ReflectInterleaved,
+    StorageBindings,
+    Reflect,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[serde(default)]
+#[repr(C)]
+pub struct Gaussian3dCodebook {
+    #[serde(default)]
+    pub position_visibility: PositionVisibility,
+    #[serde(default)]
+    pub spherical_harmonic: CodebookSphericalHarmonics,
+    #[serde(default)]
+    pub rotation: Rotation,
+    #[serde(default)]
+    pub scale_opacity: ScaleOpacity,
+    /// rest-band coefficients of the codebook entry this gaussian references, the DC terms are unused
+    #[serde(default)]
+    pub codebook: SphericalHarmonicCoefficients,
+}
+
+impl From<Gaussian3dCodebook> for Gaussian3d {
+    fn from(gaussian: Gaussian3dCodebook) -> Self {
+        let mut spherical_harmonic = gaussian.codebook;
+        spherical_harmonic.coefficients[..SH_CHANNELS]
+            .copy_from_slice(&gaussian.spherical_harmonic.dc());
+
+        Self {
+            position_visibility: gaussian.position_visibility,
+            spherical_harmonic,
+            rotation: gaussian.rotation,
+            scale_opacity: gaussian.scale_opacity,
+        }
+    }
+}
+
+// the codebook plane holds one entry per cluster, so the planar type is not derived
+#[derive(Asset, Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct PlanarGaussian3dCodebook {
+    pub position_visibility: Vec<PositionVisibility>,
+    pub spherical_harmonic: Vec<CodebookSphericalHarmonics>,
+    pub rotation: Vec<Rotation>,
+    pub scale_opacity: Vec<ScaleOpacity>,
+    pub codebook: Vec<SphericalHarmonicCoefficients>,
+}
+
+impl Planar for PlanarGaussian3dCodebook {
+    type PackedType = Gaussian3dCodebook;
+
+    fn get(&self, index: usize) -> Gaussian3dCodebook {
+        let spherical_harmonic = self.spherical_harmonic[index];
+
+        Gaussian3dCodebook {
+            position_visibility: self.position_visibility[index],
+            spherical_harmonic,
+            rotation: self.rotation[index],
+            scale_opacity: self.scale_opacity[index],
+            codebook: self.codebook[spherical_harmonic.index()],
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position_visibility.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.position_visibility.len()
+    }
+
+    fn set(&mut self, index: usize, value: Gaussian3dCodebook) {
+        let entry = value.spherical_harmonic.index();
+        let spherical_harmonic = if self.codebook.get(entry) == Some(&value.codebook) {
+            value.spherical_harmonic
+        } else {
+            let rest = &value.codebook.coefficients[SH_REST];
+            value
+                .spherical_harmonic
+                .with_index(nearest_entry(&self.codebook, rest))
+        };
+
+        self.position_visibility[index] = value.position_visibility;
+        self.spherical_harmonic[index] = spherical_harmonic;
+        self.rotation[index] = value.rotation;
+        self.scale_opacity[index] = value.scale_opacity;
+    }
+
+    fn to_interleaved(&self) -> Vec<Gaussian3dCodebook> {
+        (0..self.len()).map(|index| self.get(index)).collect()
+    }
+
+    fn from_interleaved(packed: Vec<Gaussian3dCodebook>) -> Self {
+        // reuse the packed entries as the codebook when they fit, otherwise cluster again
+        let mut entries = HashMap::new();
+        let mut codebook = Vec::new();
+        let mut indices = Vec::with_capacity(packed.len());
+        for gaussian in &packed {
+            let key = gaussian.codebook.coefficients.map(f32::to_bits);
+            let index = *entries.entry(key).or_insert_with(|| {
+                codebook.push(gaussian.codebook);
+                codebook.len() - 1
+            });
+            indices.push(index);
+        }
+
+        if codebook.len() > SH_CODEBOOK_MAX_SIZE {
+            let gaussians = packed
+                .into_iter()
+                .map(Gaussian3d::from)
+                .collect::<PlanarGaussian3d>();
+            return ShCodebookBuilder::default().build(&gaussians);
+        }
+
+        Self {
+            position_visibility: packed.iter().map(|g| g.position_visibility).collect(),
+            spherical_harmonic: packed
+                .iter()
+                .zip(indices)
+                .map(|(g, index)| g.spherical_harmonic.with_index(index))
+                .collect(),
+            rotation: packed.iter().map(|g| g.rotation).collect(),
+            scale_opacity: packed.iter().map(|g| g.scale_opacity).collect(),
+            codebook,
+        }
+    }
+
+    fn subset(&self, indices: &[usize]) -> Self {
+        Self {
+            position_visibility: indices
+                .iter()
+                .map(|&i| self.position_visibility[i])
+                .collect(),
+            spherical_harmonic: indices
+                .iter()
+                .map(|&i| self.spherical_harmonic[i])
+                .collect(),
+            rotation: indices.iter().map(|&i| self.rotation[i]).collect(),
+            scale_opacity: indices.iter().map(|&i| self.scale_opacity[i]).collect(),
+            codebook: self.codebook.clone(),
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[require(Transform, Visibility)]
+pub struct PlanarGaussian3dCodebookHandle(pub Handle<PlanarGaussian3dCodebook>);
+
+impl PlanarHandle<PlanarGaussian3dCodebook> for PlanarGaussian3dCodebookHandle {
+    fn handle(&self) -> &Handle<PlanarGaussian3dCodebook> {
+        &self.0
+    }
+}
+
+impl SyncComponent for PlanarGaussian3dCodebookHandle {
+    type Target = Self;
+}
+
+impl CommonCloud for PlanarGaussian3dCodebook {
+    type PackedType = Gaussian3dCodebook;
+
+    fn storage_format() -> PlanarStorageFormat {
+        PlanarStorageFormat::Codebook
+    }
+
+    fn visibility(&self, index: usize) -> f32 {
+        self.position_visibility[index].visibility
+    }
+
+    fn visibility_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.position_visibility[index].visibility
+    }
+
+    fn position_iter(&self) -> PositionIter<'_> {
+        PositionIter::new(&self.position_visibility)
+    }
+
+    #[cfg(feature = "sort_rayon")]
+    fn position_par_iter(&self) -> crate::gaussian::iter::PositionParIter<'_> {
+        crate::gaussian::iter::PositionParIter::new(&self.position_visibility)
+    }
+}
+
+impl From<&PlanarGaussian3d> for PlanarGaussian3dCodebook {
+    fn from(cloud: &PlanarGaussian3d) -> Self {
+        ShCodebookBuilder::default().build(cloud)
+    }
+}
+
+impl From<&PlanarGaussian3dCodebook> for PlanarGaussian3d {
+    fn from(cloud: &PlanarGaussian3dCodebook) -> Self {
+        (0..cloud.len())
+            .map(|index| Gaussian3d::from(cloud.get(index)))
+            .collect()
+    }
+}
+
+/// clusters the rest-band coefficients offline with seeded k-means
+#[derive(Clone, Debug)]
+pub struct ShCodebookBuilder {
+    pub codebook_size: usize,
+    pub iterations: usize,
+    /// gaussians sampled to train the centroids, every gaussian is assigned afterwards
+    pub training_samples: usize,
+    pub seed: u64,
+}
+
+impl Default for ShCodebookBuilder {
+    fn default() -> Self {
+        Self {
+            codebook_size: 4096,
+            iterations: 16,
+            training_samples: 1 << 16,
+            seed: 0,
+        }
+    }
+}
+
+impl ShCodebookBuilder {
+    pub fn codebook_size(mut self, codebook_size: usize) -> Self {
+        self.codebook_size = codebook_size.clamp(1, SH_CODEBOOK_MAX_SIZE);
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn training_samples(mut self, training_samples: usize) -> Self {
+        self.training_samples = training_samples.max(1);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn build(&self, cloud: &PlanarGaussian3d) -> PlanarGaussian3dCodebook {
+        if cloud.is_empty() {
+            return PlanarGaussian3dCodebook::default();
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let rest = |index: usize| &cloud.spherical_harmonic[index].coefficients[SH_REST];
+
+        let mut samples = (0..cloud.len()).collect::<Vec<_>>();
+        samples.shuffle(&mut rng);
+        samples.truncate(self.training_samples.max(1));
+
+        // initial centroids are distinct training samples
+        let codebook_size = self.codebook_size.clamp(1, SH_CODEBOOK_MAX_SIZE);
+        let mut centroids = Vec::<Vec<f32>>::with_capacity(codebook_size);
+        for &sample in &samples {
+            if centroids.len() == codebook_size {
+                break;
+            }
+            if !centroids.iter().any(|centroid| centroid == rest(sample)) {
+                centroids.push(rest(sample).to_vec());
+            }
+        }
+        if centroids.is_empty() {
+            centroids.push(vec![0.0; SH_CODEBOOK_REST_COUNT]);
+        }
+
+        let mut codebook = centroids
+            .into_iter()
+            .map(|centroid| {
+                let mut entry = SphericalHarmonicCoefficients::default();
+                entry.coefficients[SH_REST].copy_from_slice(&centroid);
+                entry
+            })
+            .collect::<Vec<_>>();
+
+        for _ in 0..self.iterations {
+            let assignments = assign(&codebook, &samples, rest);
+
+            let mut sums = vec![[0.0_f64; SH_CODEBOOK_REST_COUNT]; codebook.len()];
+            let mut counts = vec![0usize; codebook.len()];
+            for (&sample, &entry) in samples.iter().zip(&assignments) {
+                counts[entry] += 1;
+                for (sum, &value) in sums[entry].iter_mut().zip(rest(sample)) {
+                    *sum += value as f64;
+                }
+            }
+
+            let mut moved = false;
+            for (entry, (sum, count)) in codebook.iter_mut().zip(sums.iter().zip(counts)) {
+                let centroid = &mut entry.coefficients[SH_REST];
+                let previous = centroid.to_vec();
+
+                if count == 0 {
+                    // reseed empty clusters so every entry stays useful
+                    let sample = samples[rng.random_range(0..samples.len())];
+                    centroid.copy_from_slice(rest(sample));
+                } else {
+                    for (value, &sum) in centroid.iter_mut().zip(sum.iter()) {
+                        *value = (sum / count as f64) as f32;
+                    }
+                }
+
+                moved |= centroid[..] != previous[..];
+            }
+
+            if !moved {
+                break;
+            }
+        }
+
+        let indices = (0..cloud.len()).collect::<Vec<_>>();
+        let assignments = assign(&codebook, &indices, rest);
+
+        PlanarGaussian3dCodebook {
+            position_visibility: cloud.position_visibility.clone(),
+            spherical_harmonic: cloud
+                .spherical_harmonic
+                .iter()
+                .zip(assignments)
+                .map(|(spherical_harmonic, entry)| {
+                    let dc =
+                        std::array::from_fn(|channel| spherical_harmonic.coefficients[channel]);
+                    CodebookSphericalHarmonics::new(dc, entry)
+                })
+                .collect(),
+            rotation: cloud.rotation.clone(),
+            scale_opacity: cloud.scale_opacity.clone(),
+            codebook,
+        }
+    }
+}
+
+fn assign<'a>(
+    codebook: &[SphericalHarmonicCoefficients],
+    indices: &[usize],
+    rest: impl Fn(usize) -> &'a [f32] + Sync,
+) -> Vec<usize> {
+    #[cfg(feature = "sort_rayon")]
+    let indices = indices.par_iter();
+    #[cfg(not(feature = "sort_rayon"))]
+    let indices = indices.iter();
+
+    indices
+        .map(|&index| nearest_entry(codebook, rest(index)))
+        .collect()
+}
+
+fn nearest_entry(codebook: &[SphericalHarmonicCoefficients], rest: &[f32]) -> usize {
+    let mut nearest = (0, f32::INFINITY);
+    for (index, entry) in codebook.iter().enumerate() {
+        let distance = entry.coefficients[SH_REST]
+            .iter()
+            .zip(rest)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>();
+
+        if distance < nearest.1 {
+            nearest = (index, distance);
+        }
+    }
+
+    nearest.0
+}
@@ -1 +1,340 @@
-// TODO: gaussian cloud 3d with level of detail
+use bevy::prelude::*;
+use bevy_interleave::prelude::Planar;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gaussian::{
+        covariance::decompose_covariance_3d,
+        formats::{
+            planar_3d::{Gaussian3d, PlanarGaussian3d},
+            planar_3d_chunked::sort_morton,
+        },
+    },
+    material::spherical_harmonics::SH_COEFF_COUNT,
+};
+
+pub const LOD_NO_PARENT: u32 = u32::MAX;
+
+/// radius of a leaf gaussian in standard deviations
+const LEAF_SIGMA_RADIUS: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct GaussianLodNode {
+    pub parent: u32,
+    pub first_child: u32,
+    pub child_count: u32,
+    pub level: u32,
+    /// bounding radius of every leaf merged into this node
+    pub radius: f32,
+    /// furthest any finer node drifts from this node when it is drawn in their place, 0 for leaves
+    #[serde(default)]
+    pub error: f32,
+}
+
+impl Default for GaussianLodNode {
+    fn default() -> Self {
+        Self {
+            parent: LOD_NO_PARENT,
+            first_child: 0,
+            child_count: 0,
+            level: 0,
+            radius: 0.0,
+            error: 0.0,
+        }
+    }
+}
+
+impl GaussianLodNode {
+    pub fn is_leaf(&self) -> bool {
+        self.child_count == 0
+    }
+
+    pub fn children(&self) -> std::ops::Range<usize> {
+        let start = self.first_child as usize;
+        start..start + self.child_count as usize
+    }
+}
+
+/// hierarchy of progressively merged gaussians, level 0 holds the source cloud
+#[derive(Asset, Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct PlanarGaussian3dLod {
+    /// one gaussian per node, ordered finest level first
+    pub gaussians: PlanarGaussian3d,
+    pub nodes: Vec<GaussianLodNode>,
+    /// node offset of each level, followed by the node count
+    pub level_offsets: Vec<u32>,
+}
+
+impl PlanarGaussian3dLod {
+    pub fn level_count(&self) -> usize {
+        self.level_offsets.len().saturating_sub(1)
+    }
+
+    pub fn level_range(&self, level: usize) -> std::ops::Range<usize> {
+        self.level_offsets[level] as usize..self.level_offsets[level + 1] as usize
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        if self.level_count() == 0 {
+            return 0;
+        }
+
+        self.level_range(0).len()
+    }
+
+    pub fn center(&self, node: usize) -> Vec3 {
+        Vec3::from(self.gaussians.position_visibility[node].position)
+    }
+
+    /// every node of a single level, clamped to the coarsest level
+    pub fn level_cut(&self, level: usize) -> Vec<u32> {
+        if self.level_count() == 0 {
+            return Vec::new();
+        }
+
+        let level = level.min(self.level_count() - 1);
+        self.level_range(level).map(|node| node as u32).collect()
+    }
+
+    /// walks down from the roots, replacing a node by its children while `refine` holds
+    pub fn cut_by(&self, mut refine: impl FnMut(&GaussianLodNode, Vec3) -> bool) -> Vec<u32> {
+        let mut cut = Vec::new();
+        let mut stack = self.level_cut(usize::MAX);
+        stack.reverse();
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index as usize];
+
+            if !node.is_leaf() && refine(node, self.center(index as usize)) {
+                stack.extend(node.children().rev().map(|child| child as u32));
+            } else {
+                cut.push(index);
+            }
+        }
+
+        cut
+    }
+
+    /// perspective cut keeping the projected node error within `error_budget` pixels,
+    /// `projection_scale` is the viewport height over twice the tangent of half the fov
+    pub fn screen_space_cut(
+        &self,
+        camera_position: Vec3,
+        projection_scale: f32,
+        error_budget: f32,
+    ) -> Vec<u32> {
+        self.cut_by(|node, center| {
+            let distance = (center.distance(camera_position) - node.radius).max(f32::EPSILON);
+            node.error * projection_scale / distance > error_budget
+        })
+    }
+
+    pub fn cut_cloud(&self, cut: &[u32]) -> PlanarGaussian3d {
+        let indices = cut.iter().map(|&node| node as usize).collect::<Vec<_>>();
+        self.gaussians.subset(&indices)
+    }
+}
+
+/// the source cloud, the leaves of level 0
+impl From<&PlanarGaussian3dLod> for PlanarGaussian3d {
+    fn from(lod: &PlanarGaussian3dLod) -> Self {
+        lod.cut_cloud(&lod.level_cut(0))
+    }
+}
+
+impl From<&PlanarGaussian3d> for PlanarGaussian3dLod {
+    fn from(cloud: &PlanarGaussian3d) -> Self {
+        LodCloudBuilder::default().build(cloud)
+    }
+}
+
+/// builds the lod hierarchy offline by merging morton neighbours level by level
+#[derive(Clone, Debug)]
+pub struct LodCloudBuilder {
+    pub branching_factor: usize,
+    pub max_levels: usize,
+}
+
+impl Default for LodCloudBuilder {
+    fn default() -> Self {
+        Self {
+            branching_factor: 8,
+            max_levels: 16,
+        }
+    }
+}
+
+impl LodCloudBuilder {
+    pub fn branching_factor(mut self, branching_factor: usize) -> Self {
+        self.branching_factor = branching_factor.max(2);
+        self
+    }
+
+    pub fn max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = max_levels.max(1);
+        self
+    }
+
+    pub fn build(&self, cloud: &PlanarGaussian3d) -> PlanarGaussian3dLod {
+        let mut gaussians = cloud.iter().collect::<Vec<_>>();
+        if gaussians.is_empty() {
+            return PlanarGaussian3dLod::default();
+        }
+
+        // morton order keeps consecutive groups spatially coherent at every level
+        sort_morton(&mut gaussians);
+
+        let mut nodes = gaussians
+            .iter()
+            .map(|gaussian| GaussianLodNode {
+                radius: LEAF_SIGMA_RADIUS
+                    * gaussian
+                        .scale_opacity
+                        .scale
+                        .iter()
+                        .fold(0.0_f32, |max, scale| max.max(scale.abs())),
+                ..default()
+            })
+            .collect::<Vec<_>>();
+        let mut level_offsets = vec![0, nodes.len() as u32];
+
+        let branching_factor = self.branching_factor.max(2);
+        let mut level = 0;
+        while level + 1 < self.max_levels {
+            let start = level_offsets[level] as usize;
+            let end = level_offsets[level + 1] as usize;
+            if end - start <= 1 {
+                break;
+            }
+
+            level += 1;
+            for group_start in (start..end).step_by(branching_factor) {
+                let group = group_start..(group_start + branching_factor).min(end);
+                let parent_index = nodes.len() as u32;
+
+                let parent = merge_gaussians(&gaussians[group.clone()]);
+                let center = Vec3::from(parent.position_visibility.position);
+                let (radius, error) = group
+                    .clone()
+                    .map(|child| {
+                        let child_center =
+                            Vec3::from(gaussians[child].position_visibility.position);
+                        let offset = child_center.distance(center);
+                        (offset + nodes[child].radius, offset + nodes[child].error)
+                    })
+                    .fold((0.0_f32, 0.0_f32), |(radius, error), child| {
+                        (radius.max(child.0), error.max(child.1))
+                    });
+
+                for child in group.clone() {
+                    nodes[child].parent = parent_index;
+                }
+
+                gaussians.push(parent);
+                nodes.push(GaussianLodNode {
+                    parent: LOD_NO_PARENT,
+                    first_child: group.start as u32,
+                    child_count: group.len() as u32,
+                    level: level as u32,
+                    radius,
+                    error,
+                });
+            }
+
+            level_offsets.push(nodes.len() as u32);
+        }
+
+        PlanarGaussian3dLod {
+            gaussians: gaussians.into(),
+            nodes,
+            level_offsets,
+        }
+    }
+}
+
+fn covariance(gaussian: &Gaussian3d) -> Mat3 {
+    let [w, x, y, z] = gaussian.rotation.rotation;
+    let rotation = Quat::from_xyzw(x, y, z, w);
+    let rotation = if rotation.length_squared() > 0.0 {
+        rotation.normalize()
+    } else {
+        Quat::IDENTITY
+    };
+
+    let basis = Mat3::from_quat(rotation);
+    let variance = Vec3::from(gaussian.scale_opacity.scale).powf(2.0);
+
+    basis * Mat3::from_diagonal(variance) * basis.transpose()
+}
+
+/// projected footprint used to weight children, product of the two largest axes
+fn footprint(scale: Vec3) -> f32 {
+    let scale = scale.abs();
+    scale.x * scale.y * scale.z / scale.min_element().max(f32::EPSILON)
+}
+
+/// moment matched parent, children weighted by opacity times footprint
+pub fn merge_gaussians(children: &[Gaussian3d]) -> Gaussian3d {
+    let mut weights = children
+        .iter()
+        .map(|child| {
+            child.scale_opacity.opacity.max(0.0) * footprint(Vec3::from(child.scale_opacity.scale))
+        })
+        .collect::<Vec<_>>();
+    let mut total_weight = weights.iter().sum::<f32>();
+    if total_weight <= f32::EPSILON {
+        weights.fill(1.0);
+        total_weight = children.len() as f32;
+    }
+
+    let mean = children
+        .iter()
+        .zip(weights.iter())
+        .map(|(child, weight)| Vec3::from(child.position_visibility.position) * *weight)
+        .sum::<Vec3>()
+        / total_weight;
+
+    let mut sigma = Mat3::ZERO;
+    for (child, weight) in children.iter().zip(weights.iter()) {
+        let offset = Vec3::from(child.position_visibility.position) - mean;
+        let spread = Mat3::from_cols(offset * offset.x, offset * offset.y, offset * offset.z);
+        sigma += (covariance(child) + spread) * (*weight / total_weight);
+    }
+
+    let (rotation, scale) = decompose_covariance_3d(sigma);
+
+    let mut parent = Gaussian3d::default();
+    parent.position_visibility.position = mean.to_array();
+    parent.position_visibility.visibility = 1.0;
+    parent.rotation.rotation = rotation.to_array();
+    parent.scale_opacity.scale = scale.to_array();
+
+    // preserve the summed coverage of the children over the parent footprint
+    let coverage = children
+        .iter()
+        .map(|child| {
+            child.scale_opacity.opacity.max(0.0) * footprint(Vec3::from(child.scale_opacity.scale))
+        })
+        .sum::<f32>();
+    let parent_footprint = footprint(scale);
+    parent.scale_opacity.opacity = if parent_footprint > f32::EPSILON {
+        (coverage / parent_footprint).clamp(0.0, 1.0)
+    } else {
+        children
+            .iter()
+            .map(|child| child.scale_opacity.opacity)
+            .fold(0.0, f32::max)
+    };
+
+    for index in 0..SH_COEFF_COUNT {
+        let coefficient = children
+            .iter()
+            .zip(weights.iter())
+            .map(|(child, weight)| child.spherical_harmonic.get(index) * *weight)
+            .sum::<f32>()
+            / total_weight;
+        parent.spherical_harmonic.set(index, coefficient);
+    }
+
+    parent
+}
@@ -1,2 +1,557 @@
-// TODO: gaussian_3d and gaussian_3d_quantized conversions
-// TODO: packed quantized gaussian 3d
+use bevy::{prelude::*, render::sync_component::SyncComponent};
+use bevy_interleave::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gaussian::{
+        f32::{Position, PositionVisibility, Rotation, ScaleOpacity},
+        formats::planar_3d::{Gaussian3d, PlanarGaussian3d},
+        interface::{CommonCloud, PlanarStorageFormat},
+        iter::PositionIter,
+    },
+    material::spherical_harmonics::{
+        SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL, SphericalHarmonicCoefficients,
+    },
+};
+
+/// number of consecutive gaussians sharing one `QuantizedChunk`
+pub const QUANTIZED_CHUNK_SIZE: usize = 256;
+
+pub const QUANTIZED_SH_REST_COUNT: usize = (SH_COEFF_COUNT_PER_CHANNEL - 1) * SH_CHANNELS;
+
+/// two words of 16-bit DC coefficients followed by 8-bit higher order coefficients
+pub const QUANTIZED_SH_WORDS: usize = 2 + QUANTIZED_SH_REST_COUNT.div_ceil(4);
+
+const LOG_SCALE_MIN: f32 = -10.0;
+const LOG_SCALE_STEPS: f32 = 16.0;
+
+#[derive(Clone, Debug, Copy, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize)]
+#[repr(C)]
+pub struct QuantizedPositionVisibility {
+    /// x | y << 16, z | visibility << 16, unorm16 relative to the chunk bounds
+    pub position: [u32; 2],
+}
+
+impl Default for QuantizedPositionVisibility {
+    fn default() -> Self {
+        Self {
+            position: [0, 0xffff << 16],
+        }
+    }
+}
+
+impl QuantizedPositionVisibility {
+    pub fn quantize(position_visibility: &PositionVisibility, chunk: &QuantizedChunk) -> Self {
+        let [x, y, z] = std::array::from_fn(|axis| {
+            let extent = chunk.position_max[axis] - chunk.position_min[axis];
+            if extent > 0.0 {
+                to_unorm16((position_visibility.position[axis] - chunk.position_min[axis]) / extent)
+            } else {
+                0
+            }
+        });
+        let visibility = to_unorm16(position_visibility.visibility);
+
+        Self {
+            position: [x | (y << 16), z | (visibility << 16)],
+        }
+    }
+
+    pub fn position(&self, chunk: &QuantizedChunk) -> Position {
+        let quantized = [
+            self.position[0] & 0xffff,
+            self.position[0] >> 16,
+            self.position[1] & 0xffff,
+        ];
+
+        std::array::from_fn(|axis| {
+            let extent = chunk.position_max[axis] - chunk.position_min[axis];
+            chunk.position_min[axis] + extent * from_unorm16(quantized[axis])
+        })
+    }
+
+    pub fn visibility(&self) -> f32 {
+        from_unorm16(self.position[1] >> 16)
+    }
+
+    pub fn set_visibility(&mut self, visibility: f32) {
+        self.position[1] = (self.position[1] & 0xffff) | (to_unorm16(visibility) << 16);
+    }
+
+    pub fn dequantize(&self, chunk: &QuantizedChunk) -> PositionVisibility {
+        PositionVisibility {
+            position: self.position(chunk),
+            visibility: self.visibility(),
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, Default, Copy, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct QuantizedSphericalHarmonics {
+    /// snorm16 DC coefficients scaled by `sh_dc_scale`, then snorm8 coefficients scaled by `sh_rest_scale`
+    pub coefficients: [u32; QUANTIZED_SH_WORDS],
+}
+
+impl QuantizedSphericalHarmonics {
+    pub fn quantize(
+        spherical_harmonic: &SphericalHarmonicCoefficients,
+        chunk: &QuantizedChunk,
+    ) -> Self {
+        let mut coefficients = [0; QUANTIZED_SH_WORDS];
+
+        let dc: [u32; SH_CHANNELS] = std::array::from_fn(|channel| {
+            to_snorm16(spherical_harmonic.coefficients[channel], chunk.sh_dc_scale)
+        });
+        coefficients[0] = dc[0] | (dc[1] << 16);
+        coefficients[1] = dc[2];
+
+        let rest = spherical_harmonic.coefficients[SH_CHANNELS..]
+            .iter()
+            .take(QUANTIZED_SH_REST_COUNT);
+        for (i, &coefficient) in rest.enumerate() {
+            let value = to_snorm8(coefficient, chunk.sh_rest_scale);
+            coefficients[2 + i / 4] |= value << ((i % 4) * 8);
+        }
+
+        Self { coefficients }
+    }
+
+    pub fn dequantize(&self, chunk: &QuantizedChunk) -> SphericalHarmonicCoefficients {
+        let mut spherical_harmonic = SphericalHarmonicCoefficients::default();
+
+        let dc = [
+            self.coefficients[0] & 0xffff,
+            self.coefficients[0] >> 16,
+            self.coefficients[1] & 0xffff,
+        ];
+        for (channel, value) in dc.into_iter().enumerate() {
+            spherical_harmonic.coefficients[channel] = from_snorm16(value, chunk.sh_dc_scale);
+        }
+
+        let rest = spherical_harmonic.coefficients[SH_CHANNELS..]
+            .iter_mut()
+            .take(QUANTIZED_SH_REST_COUNT);
+        for (i, coefficient) in rest.enumerate() {
+            let value = (self.coefficients[2 + i / 4] >> ((i % 4) * 8)) & 0xff;
+            *coefficient = from_snorm8(value, chunk.sh_rest_scale);
+        }
+
+        spherical_harmonic
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize)]
+#[repr(C)]
+pub struct QuantizedRotation {
+    /// smallest-three quaternion: 2 bits for the largest component index, 3 x unorm10 components
+    pub rotation: u32,
+}
+
+impl Default for QuantizedRotation {
+    fn default() -> Self {
+        Self::quantize(&[1.0, 0.0, 0.0, 0.0].into())
+    }
+}
+
+impl QuantizedRotation {
+    pub fn quantize(rotation: &Rotation) -> Self {
+        let norm = rotation.rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let q = if norm > 0.0 && norm.is_finite() {
+            rotation.rotation.map(|v| v / norm)
+        } else {
+            [1.0, 0.0, 0.0, 0.0]
+        };
+
+        let largest = (0..4)
+            .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+            .unwrap();
+        let sign = if q[largest] < 0.0 { -1.0 } else { 1.0 };
+
+        let mut packed = largest as u32;
+        for (i, &value) in q.iter().enumerate() {
+            if i == largest {
+                continue;
+            }
+
+            let normalized = (value * sign * std::f32::consts::SQRT_2 + 1.0) * 0.5;
+            let quantized = (normalized.clamp(0.0, 1.0) * 1023.0).round() as u32;
+            packed = (packed << 10) | quantized;
+        }
+
+        Self { rotation: packed }
+    }
+
+    pub fn dequantize(&self) -> Rotation {
+        let largest = (self.rotation >> 30) as usize;
+
+        let mut q = [0.0; 4];
+        let mut shift = 20;
+        let mut sum_squares = 0.0;
+        for (i, value) in q.iter_mut().enumerate() {
+            if i == largest {
+                continue;
+            }
+
+            let quantized = (self.rotation >> shift) & 0x3ff;
+            shift -= 10;
+
+            *value = (quantized as f32 / 1023.0 * 2.0 - 1.0) * std::f32::consts::FRAC_1_SQRT_2;
+            sum_squares += *value * *value;
+        }
+        q[largest] = (1.0 - sum_squares).max(0.0).sqrt();
+
+        Rotation { rotation: q }
+    }
+}
+
+#[derive(
+    Clone, Debug, Default, Copy, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct QuantizedScaleOpacity {
+    /// three log-scale bytes ((ln(scale) + 10) * 16) and an opacity byte
+    pub scale_opacity: u32,
+}
+
+impl QuantizedScaleOpacity {
+    pub fn quantize(scale_opacity: &ScaleOpacity) -> Self {
+        let mut packed = 0;
+        for (axis, &scale) in scale_opacity.scale.iter().enumerate() {
+            let log_scale = scale.max(f32::MIN_POSITIVE).ln();
+            let quantized = ((log_scale - LOG_SCALE_MIN) * LOG_SCALE_STEPS)
+                .round()
+                .clamp(0.0, 255.0) as u32;
+            packed |= quantized << (axis * 8);
+        }
+
+        let opacity = (scale_opacity.opacity.clamp(0.0, 1.0) * 255.0).round() as u32;
+
+        Self {
+            scale_opacity: packed | (opacity << 24),
+        }
+    }
+
+    pub fn dequantize(&self) -> ScaleOpacity {
+        let byte = |index: usize| ((self.scale_opacity >> (index * 8)) & 0xff) as f32;
+
+        ScaleOpacity {
+            scale: std::array::from_fn(|axis| (byte(axis) / LOG_SCALE_STEPS + LOG_SCALE_MIN).exp()),
+            opacity: byte(3) / 255.0,
+        }
+    }
+}
+
+/// per-chunk quantization ranges, laid out to match the WGSL `QuantizedChunk` struct
+#[derive(
+    Clone, Debug, Default, Copy, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct QuantizedChunk {
+    pub position_min: [f32; 3],
+    pub sh_dc_scale: f32,
+    pub position_max: [f32; 3],
+    pub sh_rest_scale: f32,
+}
+
+impl QuantizedChunk {
+    pub fn from_gaussians<'a>(gaussians: impl Iterator<Item = &'a Gaussian3d>) -> Self {
+        let mut position_min = [f32::INFINITY; 3];
+        let mut position_max = [f32::NEG_INFINITY; 3];
+        let mut sh_dc_scale = 0.0_f32;
+        let mut sh_rest_scale = 0.0_f32;
+
+        for gaussian in gaussians {
+            for axis in 0..3 {
+                let value = gaussian.position_visibility.position[axis];
+                position_min[axis] = position_min[axis].min(value);
+                position_max[axis] = position_max[axis].max(value);
+            }
+
+            let coefficients = &gaussian.spherical_harmonic.coefficients;
+            for &value in &coefficients[..SH_CHANNELS] {
+                sh_dc_scale = sh_dc_scale.max(value.abs());
+            }
+            for &value in &coefficients[SH_CHANNELS..SH_CHANNELS + QUANTIZED_SH_REST_COUNT] {
+                sh_rest_scale = sh_rest_scale.max(value.abs());
+            }
+        }
+
+        if position_min[0] > position_max[0] {
+            position_min = [0.0; 3];
+            position_max = [0.0; 3];
+        }
+
+        Self {
+            position_min,
+            sh_dc_scale,
+            position_max,
+            sh_rest_scale,
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    ReflectInterleaved,
+    StorageBindings,
+    Reflect,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[serde(default)]
+#[repr(C)]
+pub struct Gaussian3dQuantized {
+    #[serde(default)]
+    pub position_visibility: QuantizedPositionVisibility,
+    #[serde(default)]
+    pub spherical_harmonic: QuantizedSphericalHarmonics,
+    #[serde(default)]
+    pub rotation: QuantizedRotation,
+    #[serde(default)]
+    pub scale_opacity: QuantizedScaleOpacity,
+    /// quantization ranges of the chunk this gaussian belongs to
+    #[serde(default)]
+    pub chunk: QuantizedChunk,
+}
+
+impl Gaussian3dQuantized {
+    pub fn quantize(gaussian: &Gaussian3d, chunk: &QuantizedChunk) -> Self {
+        Self {
+            position_visibility: QuantizedPositionVisibility::quantize(
+                &gaussian.position_visibility,
+                chunk,
+            ),
+            spherical_harmonic: QuantizedSphericalHarmonics::quantize(
+                &gaussian.spherical_harmonic,
+                chunk,
+            ),
+            rotation: QuantizedRotation::quantize(&gaussian.rotation),
+            scale_opacity: QuantizedScaleOpacity::quantize(&gaussian.scale_opacity),
+            chunk: *chunk,
+        }
+    }
+}
+
+impl From<Gaussian3dQuantized> for Gaussian3d {
+    fn from(quantized: Gaussian3dQuantized) -> Self {
+        Self {
+            position_visibility: quantized.position_visibility.dequantize(&quantized.chunk),
+            spherical_harmonic: quantized.spherical_harmonic.dequantize(&quantized.chunk),
+            rotation: quantized.rotation.dequantize(),
+            scale_opacity: quantized.scale_opacity.dequantize(),
+        }
+    }
+}
+
+// the chunk plane holds one entry per QUANTIZED_CHUNK_SIZE gaussians, so the planar type is not derived
+#[derive(Asset, Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct PlanarGaussian3dQuantized {
+    pub position_visibility: Vec<QuantizedPositionVisibility>,
+    pub spherical_harmonic: Vec<QuantizedSphericalHarmonics>,
+    pub rotation: Vec<QuantizedRotation>,
+    pub scale_opacity: Vec<QuantizedScaleOpacity>,
+    pub chunk: Vec<QuantizedChunk>,
+}
+
+impl Planar for PlanarGaussian3dQuantized {
+    type PackedType = Gaussian3dQuantized;
+
+    fn get(&self, index: usize) -> Gaussian3dQuantized {
+        Gaussian3dQuantized {
+            position_visibility: self.position_visibility[index],
+            spherical_harmonic: self.spherical_harmonic[index],
+            rotation: self.rotation[index],
+            scale_opacity: self.scale_opacity[index],
+            chunk: self.chunk[index / QUANTIZED_CHUNK_SIZE],
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position_visibility.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.position_visibility.len()
+    }
+
+    fn set(&mut self, index: usize, value: Gaussian3dQuantized) {
+        let chunk = self.chunk[index / QUANTIZED_CHUNK_SIZE];
+        let value = if value.chunk == chunk {
+            value
+        } else {
+            Gaussian3dQuantized::quantize(&value.into(), &chunk)
+        };
+
+        self.position_visibility[index] = value.position_visibility;
+        self.spherical_harmonic[index] = value.spherical_harmonic;
+        self.rotation[index] = value.rotation;
+        self.scale_opacity[index] = value.scale_opacity;
+    }
+
+    fn to_interleaved(&self) -> Vec<Gaussian3dQuantized> {
+        (0..self.len()).map(|index| self.get(index)).collect()
+    }
+
+    fn from_interleaved(packed: Vec<Gaussian3dQuantized>) -> Self {
+        let mut planar = Self::default();
+
+        for group in packed.chunks(QUANTIZED_CHUNK_SIZE) {
+            let chunk = if group
+                .iter()
+                .all(|gaussian| gaussian.chunk == group[0].chunk)
+            {
+                group[0].chunk
+            } else {
+                let gaussians = group
+                    .iter()
+                    .map(|&gaussian| gaussian.into())
+                    .collect::<Vec<Gaussian3d>>();
+                QuantizedChunk::from_gaussians(gaussians.iter())
+            };
+
+            planar.chunk.push(chunk);
+            for &gaussian in group {
+                let gaussian = if gaussian.chunk == chunk {
+                    gaussian
+                } else {
+                    Gaussian3dQuantized::quantize(&gaussian.into(), &chunk)
+                };
+
+                planar
+                    .position_visibility
+                    .push(gaussian.position_visibility);
+                planar.spherical_harmonic.push(gaussian.spherical_harmonic);
+                planar.rotation.push(gaussian.rotation);
+                planar.scale_opacity.push(gaussian.scale_opacity);
+            }
+        }
+
+        planar
+    }
+
+    fn subset(&self, indices: &[usize]) -> Self {
+        let gaussians = indices
+            .iter()
+            .map(|&index| Gaussian3d::from(self.get(index)))
+            .collect::<PlanarGaussian3d>();
+
+        (&gaussians).into()
+    }
+}
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[require(Transform, Visibility)]
+pub struct PlanarGaussian3dQuantizedHandle(pub Handle<PlanarGaussian3dQuantized>);
+
+impl PlanarHandle<PlanarGaussian3dQuantized> for PlanarGaussian3dQuantizedHandle {
+    fn handle(&self) -> &Handle<PlanarGaussian3dQuantized> {
+        &self.0
+    }
+}
+
+impl SyncComponent for PlanarGaussian3dQuantizedHandle {
+    type Target = Self;
+}
+
+impl CommonCloud for PlanarGaussian3dQuantized {
+    type PackedType = Gaussian3dQuantized;
+
+    fn storage_format() -> PlanarStorageFormat {
+        PlanarStorageFormat::Quantized
+    }
+
+    fn visibility(&self, index: usize) -> f32 {
+        self.position_visibility[index].visibility()
+    }
+
+    fn set_visibility(&mut self, index: usize, visibility: f32) {
+        self.position_visibility[index].set_visibility(visibility);
+    }
+
+    fn position_iter(&self) -> PositionIter<'_> {
+        PositionIter::new_quantized(&self.position_visibility, &self.chunk)
+    }
+
+    #[cfg(feature = "sort_rayon")]
+    fn position_par_iter(&self) -> crate::gaussian::iter::PositionParIter<'_> {
+        crate::gaussian::iter::PositionParIter::new_quantized(
+            &self.position_visibility,
+            &self.chunk,
+        )
+    }
+}
+
+impl From<&PlanarGaussian3d> for PlanarGaussian3dQuantized {
+    fn from(cloud: &PlanarGaussian3d) -> Self {
+        let gaussians = cloud.iter().collect::<Vec<_>>();
+        let mut planar = Self::default();
+
+        for group in gaussians.chunks(QUANTIZED_CHUNK_SIZE) {
+            let chunk = QuantizedChunk::from_gaussians(group.iter());
+            planar.chunk.push(chunk);
+
+            for gaussian in group {
+                let quantized = Gaussian3dQuantized::quantize(gaussian, &chunk);
+
+                planar
+                    .position_visibility
+                    .push(quantized.position_visibility);
+                planar.spherical_harmonic.push(quantized.spherical_harmonic);
+                planar.rotation.push(quantized.rotation);
+                planar.scale_opacity.push(quantized.scale_opacity);
+            }
+        }
+
+        planar
+    }
+}
+
+impl From<&PlanarGaussian3dQuantized> for PlanarGaussian3d {
+    fn from(cloud: &PlanarGaussian3dQuantized) -> Self {
+        (0..cloud.len())
+            .map(|index| Gaussian3d::from(cloud.get(index)))
+            .collect()
+    }
+}
+
+fn to_unorm16(value: f32) -> u32 {
+    (value.clamp(0.0, 1.0) * 65535.0).round() as u32
+}
+
+fn from_unorm16(value: u32) -> f32 {
+    value as f32 / 65535.0
+}
+
+fn to_snorm16(value: f32, scale: f32) -> u32 {
+    if scale <= 0.0 {
+        return 0;
+    }
+
+    ((value / scale).clamp(-1.0, 1.0) * 32767.0).round() as i16 as u16 as u32
+}
+
+fn from_snorm16(value: u32, scale: f32) -> f32 {
+    (value as u16 as i16 as f32 / 32767.0).max(-1.0) * scale
+}
+
+fn to_snorm8(value: f32, scale: f32) -> u32 {
+    if scale <= 0.0 {
+        return 0;
+    }
+
+    ((value / scale).clamp(-1.0, 1.0) * 127.0).round() as i8 as u8 as u32
+}
+
+fn from_snorm8(value: u32, scale: f32) -> f32 {
+    (value as u8 as i8 as f32 / 127.0).max(-1.0) * scale
+}
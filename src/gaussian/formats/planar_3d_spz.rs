@@ -1 +1,453 @@
-// TODO: spz quantized format https://github.com/nianticlabs/spz
+// spz quantized format https://github.com/nianticlabs/spz
+use bevy_interleave::prelude::Planar;
+use half::f16;
+
+use crate::{
+    gaussian::formats::planar_3d::{Gaussian3d, PlanarGaussian3d},
+    io::settings::unpadded_len,
+    material::spherical_harmonics::{
+        SH_CHANNELS, SH_COEFF_COUNT, SH_COEFF_COUNT_PER_CHANNEL, SH_DEGREE,
+    },
+};
+
+pub const SPZ_MAGIC: u32 = 0x5053_474e; // "NGSP"
+pub const SPZ_VERSION: u32 = 3;
+pub const SPZ_HEADER_SIZE: usize = 16;
+pub const SPZ_MAX_SH_DEGREE: u8 = 3;
+pub const SPZ_DEFAULT_FRACTIONAL_BITS: u8 = 12;
+// 24-bit fixed point positions leave no integer bits past 24
+pub const SPZ_MAX_FRACTIONAL_BITS: u8 = 24;
+pub const SPZ_FLAG_ANTIALIASED: u8 = 0x1;
+
+const SPZ_COLOR_SCALE: f32 = 0.15;
+const SPZ_SH1_BITS: u32 = 5;
+const SPZ_SH_REST_BITS: u32 = 4;
+
+// spz stores gaussians in RUB (OpenGL) coordinates, ply clouds are RDF
+const RDF_FROM_RUB: [f32; 3] = [1.0, -1.0, -1.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpzHeader {
+    pub version: u32,
+    pub num_points: u32,
+    pub sh_degree: u8,
+    pub fractional_bits: u8,
+    pub flags: u8,
+}
+
+impl Default for SpzHeader {
+    fn default() -> Self {
+        Self {
+            version: SPZ_VERSION,
+            num_points: 0,
+            sh_degree: 0,
+            fractional_bits: SPZ_DEFAULT_FRACTIONAL_BITS,
+            flags: 0,
+        }
+    }
+}
+
+impl SpzHeader {
+    pub fn to_bytes(&self) -> [u8; SPZ_HEADER_SIZE] {
+        let mut bytes = [0u8; SPZ_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&SPZ_MAGIC.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.version.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.num_points.to_le_bytes());
+        bytes[12] = self.sh_degree;
+        bytes[13] = self.fractional_bits;
+        bytes[14] = self.flags;
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, std::io::Error> {
+        if bytes.len() < SPZ_HEADER_SIZE {
+            return Err(invalid_data("spz header is truncated"));
+        }
+
+        let word = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+
+        if word(0) != SPZ_MAGIC {
+            return Err(invalid_data("invalid spz magic"));
+        }
+
+        let header = Self {
+            version: word(4),
+            num_points: word(8),
+            sh_degree: bytes[12],
+            fractional_bits: bytes[13],
+            flags: bytes[14],
+        };
+
+        if !(1..=SPZ_VERSION).contains(&header.version) {
+            return Err(invalid_data(format!(
+                "unsupported spz version {}",
+                header.version
+            )));
+        }
+
+        if header.sh_degree > SPZ_MAX_SH_DEGREE {
+            return Err(invalid_data(format!(
+                "unsupported spz sh degree {}",
+                header.sh_degree
+            )));
+        }
+
+        if header.fractional_bits > SPZ_MAX_FRACTIONAL_BITS {
+            return Err(invalid_data(format!(
+                "unsupported spz fractional bits {}",
+                header.fractional_bits
+            )));
+        }
+
+        Ok(header)
+    }
+
+    pub fn sh_dim(&self) -> usize {
+        spz_sh_dim(self.sh_degree)
+    }
+
+    fn position_stride(&self) -> usize {
+        if self.version == 1 { 6 } else { 9 }
+    }
+
+    fn rotation_stride(&self) -> usize {
+        if self.version >= 3 { 4 } else { 3 }
+    }
+
+    pub fn payload_size(&self) -> usize {
+        let n = self.num_points as usize;
+        n * (self.position_stride() + 1 + 3 + 3 + self.rotation_stride() + self.sh_dim() * 3)
+    }
+}
+
+/// the quantized attribute planes of an spz cloud, as laid out inside the gzip envelope
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlanarGaussian3dSpz {
+    pub header: SpzHeader,
+    pub positions: Vec<u8>,
+    pub alphas: Vec<u8>,
+    pub colors: Vec<u8>,
+    pub scales: Vec<u8>,
+    pub rotations: Vec<u8>,
+    pub sh: Vec<u8>,
+}
+
+impl PlanarGaussian3dSpz {
+    pub fn len(&self) -> usize {
+        self.header.num_points as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SPZ_HEADER_SIZE + self.header.payload_size());
+        bytes.extend_from_slice(&self.header.to_bytes());
+        bytes.extend_from_slice(&self.positions);
+        bytes.extend_from_slice(&self.alphas);
+        bytes.extend_from_slice(&self.colors);
+        bytes.extend_from_slice(&self.scales);
+        bytes.extend_from_slice(&self.rotations);
+        bytes.extend_from_slice(&self.sh);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, std::io::Error> {
+        let header = SpzHeader::from_bytes(bytes)?;
+        let payload = &bytes[SPZ_HEADER_SIZE..];
+
+        if payload.len() < header.payload_size() {
+            return Err(invalid_data("spz payload is truncated"));
+        }
+
+        let n = header.num_points as usize;
+        let mut offset = 0;
+        let mut take = |len: usize| {
+            let plane = payload[offset..offset + len].to_vec();
+            offset += len;
+            plane
+        };
+
+        Ok(Self {
+            header,
+            positions: take(n * header.position_stride()),
+            alphas: take(n),
+            colors: take(n * 3),
+            scales: take(n * 3),
+            rotations: take(n * header.rotation_stride()),
+            sh: take(n * header.sh_dim() * 3),
+        })
+    }
+
+    pub fn to_planar(&self) -> PlanarGaussian3d {
+        let header = &self.header;
+        let sh_dim = header.sh_dim();
+        let position_scale = 1.0 / (1u32 << header.fractional_bits) as f32;
+
+        let gaussians = (0..self.len())
+            .map(|i| {
+                let mut gaussian = Gaussian3d::default();
+
+                let mut position = [0.0; 3];
+                for (axis, value) in position.iter_mut().enumerate() {
+                    *value = if header.version == 1 {
+                        let offset = (i * 3 + axis) * 2;
+                        f16::from_le_bytes([self.positions[offset], self.positions[offset + 1]])
+                            .to_f32()
+                    } else {
+                        let offset = (i * 3 + axis) * 3;
+                        let fixed = i32::from_le_bytes([
+                            0,
+                            self.positions[offset],
+                            self.positions[offset + 1],
+                            self.positions[offset + 2],
+                        ]) >> 8;
+                        fixed as f32 * position_scale
+                    };
+                }
+                gaussian.position_visibility.position =
+                    std::array::from_fn(|axis| position[axis] * RDF_FROM_RUB[axis]);
+
+                gaussian.scale_opacity.opacity = self.alphas[i] as f32 / 255.0;
+                for axis in 0..3 {
+                    let log_scale = self.scales[i * 3 + axis] as f32 / 16.0 - 10.0;
+                    gaussian.scale_opacity.scale[axis] = log_scale.exp();
+                }
+
+                for channel in 0..SH_CHANNELS {
+                    let color = self.colors[i * 3 + channel] as f32 / 255.0;
+                    gaussian
+                        .spherical_harmonic
+                        .set(channel, (color - 0.5) / SPZ_COLOR_SCALE);
+                }
+
+                for coefficient in 0..sh_dim {
+                    let sign = sh_axis_flip(coefficient);
+                    for channel in 0..SH_CHANNELS {
+                        let quantized = self.sh[(i * sh_dim + coefficient) * 3 + channel];
+                        let interleaved_idx = (coefficient + 1) * SH_CHANNELS + channel;
+
+                        if coefficient + 1 < SH_COEFF_COUNT_PER_CHANNEL
+                            && interleaved_idx < SH_COEFF_COUNT
+                        {
+                            gaussian
+                                .spherical_harmonic
+                                .set(interleaved_idx, sign * unquantize_sh(quantized));
+                        }
+                    }
+                }
+
+                // spz quaternions are xyzw, gaussian rotations are wxyz
+                let xyzw = if header.version >= 3 {
+                    let offset = i * 4;
+                    unpack_smallest_three(u32::from_le_bytes([
+                        self.rotations[offset],
+                        self.rotations[offset + 1],
+                        self.rotations[offset + 2],
+                        self.rotations[offset + 3],
+                    ]))
+                } else {
+                    let offset = i * 3;
+                    let xyz: [f32; 3] = std::array::from_fn(|axis| {
+                        self.rotations[offset + axis] as f32 / 127.5 - 1.0
+                    });
+                    let w = (1.0 - xyz.iter().map(|v| v * v).sum::<f32>())
+                        .max(0.0)
+                        .sqrt();
+                    [xyz[0], xyz[1], xyz[2], w]
+                };
+                gaussian.rotation.rotation = [
+                    xyzw[3],
+                    xyzw[0],
+                    xyzw[1] * RDF_FROM_RUB[1],
+                    xyzw[2] * RDF_FROM_RUB[2],
+                ];
+
+                gaussian
+            })
+            .collect::<Vec<_>>();
+
+        PlanarGaussian3d::from_interleaved(gaussians)
+    }
+}
+
+impl From<&PlanarGaussian3d> for PlanarGaussian3dSpz {
+    fn from(cloud: &PlanarGaussian3d) -> Self {
+        // loaded clouds are padded, the padding is not part of the file
+        let n = unpadded_len(cloud.len(), |index| cloud.get(index));
+        let header = SpzHeader {
+            num_points: n as u32,
+            sh_degree: (SH_DEGREE as u8).min(SPZ_MAX_SH_DEGREE),
+            ..Default::default()
+        };
+        let sh_dim = header.sh_dim();
+        let position_scale = (1u32 << header.fractional_bits) as f32;
+
+        let mut spz = Self {
+            header,
+            positions: Vec::with_capacity(n * 9),
+            alphas: Vec::with_capacity(n),
+            colors: Vec::with_capacity(n * 3),
+            scales: Vec::with_capacity(n * 3),
+            rotations: Vec::with_capacity(n * 4),
+            sh: Vec::with_capacity(n * sh_dim * 3),
+        };
+
+        for gaussian in cloud.iter().take(n) {
+            for (axis, flip) in RDF_FROM_RUB.iter().enumerate() {
+                let value = gaussian.position_visibility.position[axis] * flip;
+                let fixed = (value * position_scale)
+                    .round()
+                    .clamp(-8_388_608.0, 8_388_607.0) as i32;
+                spz.positions.extend_from_slice(&fixed.to_le_bytes()[0..3]);
+            }
+
+            spz.alphas
+                .push(to_u8(gaussian.scale_opacity.opacity * 255.0));
+
+            for channel in 0..SH_CHANNELS {
+                let dc = gaussian.spherical_harmonic.coefficients[channel];
+                spz.colors
+                    .push(to_u8(dc * (SPZ_COLOR_SCALE * 255.0) + 0.5 * 255.0));
+            }
+
+            for axis in 0..3 {
+                let log_scale = gaussian.scale_opacity.scale[axis]
+                    .max(f32::MIN_POSITIVE)
+                    .ln();
+                spz.scales.push(to_u8((log_scale + 10.0) * 16.0));
+            }
+
+            let [w, x, y, z] = gaussian.rotation.rotation;
+            let xyzw = [x, y * RDF_FROM_RUB[1], z * RDF_FROM_RUB[2], w];
+            spz.rotations
+                .extend_from_slice(&pack_smallest_three(xyzw).to_le_bytes());
+
+            for coefficient in 0..sh_dim {
+                let sign = sh_axis_flip(coefficient);
+                let bucket = if coefficient < 3 {
+                    SPZ_SH1_BITS
+                } else {
+                    SPZ_SH_REST_BITS
+                };
+
+                for channel in 0..SH_CHANNELS {
+                    let interleaved_idx = (coefficient + 1) * SH_CHANNELS + channel;
+                    let value = if coefficient + 1 < SH_COEFF_COUNT_PER_CHANNEL
+                        && interleaved_idx < SH_COEFF_COUNT
+                    {
+                        gaussian.spherical_harmonic.coefficients[interleaved_idx]
+                    } else {
+                        0.0
+                    };
+
+                    spz.sh.push(quantize_sh(sign * value, bucket));
+                }
+            }
+        }
+
+        spz
+    }
+}
+
+pub const fn spz_sh_dim(degree: u8) -> usize {
+    match degree {
+        0 => 0,
+        1 => 3,
+        2 => 8,
+        _ => 15,
+    }
+}
+
+// sign of each rest-band sh basis function under the RUB <-> RDF axis flip (y and z negated)
+fn sh_axis_flip(coefficient: usize) -> f32 {
+    const FLIPS: [f32; 15] = [
+        -1.0, -1.0, 1.0, // degree 1: y, z, x
+        -1.0, 1.0, 1.0, -1.0, 1.0, // degree 2: xy, yz, zz, xz, xx-yy
+        -1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0, // degree 3
+    ];
+
+    FLIPS[coefficient]
+}
+
+fn to_u8(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+fn quantize_sh(value: f32, bits: u32) -> u8 {
+    let bucket_size = 1 << (8 - bits);
+    let quantized = (value * 128.0).round() as i32 + 128;
+    let bucketed = (quantized + bucket_size / 2) / bucket_size * bucket_size;
+    bucketed.clamp(0, 255) as u8
+}
+
+fn unquantize_sh(value: u8) -> f32 {
+    (value as f32 - 128.0) / 128.0
+}
+
+// smallest-three quaternion packing: 2 bits for the largest component index, 3 x (1 sign + 9 magnitude) bits
+fn pack_smallest_three(xyzw: [f32; 4]) -> u32 {
+    let norm = xyzw.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let q = if norm > 0.0 && norm.is_finite() {
+        xyzw.map(|v| v / norm)
+    } else {
+        [0.0, 0.0, 0.0, 1.0]
+    };
+
+    let largest = (0..4)
+        .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+        .unwrap();
+    let negate = q[largest] < 0.0;
+
+    const MAGNITUDE_MASK: u32 = (1 << 9) - 1;
+    let mut packed = largest as u32;
+    for (i, &value) in q.iter().enumerate() {
+        if i == largest {
+            continue;
+        }
+
+        let negative = (value < 0.0) != negate;
+        let magnitude = (MAGNITUDE_MASK as f32 * (value.abs() / std::f32::consts::FRAC_1_SQRT_2)
+            + 0.5)
+            .min(MAGNITUDE_MASK as f32) as u32;
+        packed = (packed << 10) | ((negative as u32) << 9) | magnitude;
+    }
+
+    packed
+}
+
+fn unpack_smallest_three(mut packed: u32) -> [f32; 4] {
+    const MAGNITUDE_MASK: u32 = (1 << 9) - 1;
+    let largest = (packed >> 30) as usize;
+
+    let mut q = [0.0; 4];
+    let mut sum_squares = 0.0;
+    for i in (0..4).rev() {
+        if i == largest {
+            continue;
+        }
+
+        let magnitude = packed & MAGNITUDE_MASK;
+        let negative = (packed >> 9) & 0x1 == 1;
+        packed >>= 10;
+
+        let value = std::f32::consts::FRAC_1_SQRT_2 * magnitude as f32 / MAGNITUDE_MASK as f32;
+        q[i] = if negative { -value } else { value };
+        sum_squares += q[i] * q[i];
+    }
+    q[largest] = (1.0 - sum_squares).max(0.0).sqrt();
+
+    q
+}
+
+fn invalid_data(message: impl Into<String>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.into())
+}
@@ -1,17 +1,237 @@
-// TODO: gaussian cloud 4d with temporal hierarchy
-use crate::gaussian::formats::planar_4d::PlanarGaussian4dHandle;
+use bevy::prelude::*;
+use bevy_interleave::prelude::Planar;
+use serde::{Deserialize, Serialize};
 
+use crate::gaussian::formats::planar_4d::{Gaussian4d, PlanarGaussian4d};
+
+/// `|dt|` in standard deviations at which the 4d shader masks a gaussian (marginal below 0.05)
+pub const TEMPORAL_SUPPORT_SIGMA: f32 = 2.447_747;
+
+#[derive(Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
 pub struct TemporalGaussianLevel {
+    pub instance_offset: usize,
     pub instance_count: usize,
-    // TODO: swap buffer slicing
+    /// time range in which any gaussian of the level is visible
+    pub time_start: f32,
+    pub time_stop: f32,
+}
+
+impl TemporalGaussianLevel {
+    /// level of `gaussians` stored from `instance_offset`, its time range covers their support
+    pub fn spanning(instance_offset: usize, gaussians: &[Gaussian4d]) -> Self {
+        let (time_start, time_stop) = gaussians.iter().fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(start, stop), gaussian| {
+                let timestamp = gaussian.timestamp_timescale.timestamp;
+                let support = temporal_support(gaussian);
+                (
+                    start.min(timestamp - support),
+                    stop.max(timestamp + support),
+                )
+            },
+        );
+
+        Self {
+            instance_offset,
+            instance_count: gaussians.len(),
+            time_start,
+            time_stop,
+        }
+    }
+
+    pub fn instances(&self) -> std::ops::Range<usize> {
+        self.instance_offset..self.instance_offset + self.instance_count
+    }
+
+    pub fn overlaps(&self, time_start: f32, time_stop: f32) -> bool {
+        self.time_start <= time_stop && time_start <= self.time_stop
+    }
 }
 
-// TODO: make this an asset
+/// 4d cloud partitioned into levels of consecutive gaussians by temporal span
+#[derive(Asset, Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
 pub struct TemporalGaussianHierarchy {
-    pub flat_cloud: PlanarGaussian4dHandle,
+    pub flat_cloud: PlanarGaussian4d,
     pub levels: Vec<TemporalGaussianLevel>,
-    // TODO: level descriptor validation
 }
 
-// TODO: implement level streaming utilities in src/stream/hierarchy.rs
-// TODO: implement GPU slice utilities in src/stream/slice.rs
+impl TemporalGaussianHierarchy {
+    pub fn time_range(&self) -> Option<(f32, f32)> {
+        self.levels.iter().fold(None, |range, level| match range {
+            None => Some((level.time_start, level.time_stop)),
+            Some((start, stop)) => Some((start.min(level.time_start), stop.max(level.time_stop))),
+        })
+    }
+
+    /// indices of the levels visible somewhere in `[time_start, time_stop]`
+    pub fn levels_overlapping(&self, time_start: f32, time_stop: f32) -> Vec<usize> {
+        self.levels
+            .iter()
+            .enumerate()
+            .filter(|(_, level)| level.overlaps(time_start, time_stop))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// concatenates the gaussians of the given levels into one drawable cloud
+    pub fn resident_cloud(&self, levels: &[usize]) -> PlanarGaussian4d {
+        let indices = levels
+            .iter()
+            .flat_map(|&level| self.levels[level].instances())
+            .collect::<Vec<_>>();
+
+        self.flat_cloud.subset(&indices)
+    }
+
+    /// checks that the levels partition the flat cloud and bound their gaussians in time
+    pub fn validate(&self) -> Result<(), std::io::Error> {
+        let mut expected_offset = 0;
+
+        for (index, level) in self.levels.iter().enumerate() {
+            if level.instance_offset != expected_offset {
+                return Err(invalid_level(
+                    index,
+                    "is not contiguous with the previous level",
+                ));
+            }
+
+            if level.instance_count == 0 {
+                return Err(invalid_level(index, "is empty"));
+            }
+
+            if !level.time_start.is_finite()
+                || !level.time_stop.is_finite()
+                || level.time_start > level.time_stop
+            {
+                return Err(invalid_level(index, "has an invalid time range"));
+            }
+
+            expected_offset += level.instance_count;
+            if expected_offset > self.flat_cloud.len() {
+                return Err(invalid_level(index, "exceeds the flat cloud"));
+            }
+
+            let outside = level.instances().any(|instance| {
+                let timestamp = self.flat_cloud.timestamp_timescale[instance].timestamp;
+                timestamp < level.time_start || timestamp > level.time_stop
+            });
+            if outside {
+                return Err(invalid_level(
+                    index,
+                    "contains gaussians outside its time range",
+                ));
+            }
+        }
+
+        if expected_offset != self.flat_cloud.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "temporal levels do not cover the flat cloud",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid_level(index: usize, reason: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("temporal level {index} {reason}"),
+    )
+}
+
+impl From<&PlanarGaussian4d> for TemporalGaussianHierarchy {
+    fn from(cloud: &PlanarGaussian4d) -> Self {
+        TemporalHierarchyBuilder::default().build(cloud)
+    }
+}
+
+/// partitions a `PlanarGaussian4d` into levels of `level_duration` seconds by timestamp
+#[derive(Clone, Debug)]
+pub struct TemporalHierarchyBuilder {
+    pub level_duration: f32,
+}
+
+impl Default for TemporalHierarchyBuilder {
+    fn default() -> Self {
+        Self {
+            level_duration: 1.0,
+        }
+    }
+}
+
+impl TemporalHierarchyBuilder {
+    pub fn level_duration(mut self, level_duration: f32) -> Self {
+        self.level_duration = level_duration;
+        self
+    }
+
+    pub fn build(&self, cloud: &PlanarGaussian4d) -> TemporalGaussianHierarchy {
+        let mut gaussians = (0..cloud.len())
+            .map(|index| cloud.get(index))
+            .filter(|gaussian| gaussian.timestamp_timescale.timestamp.is_finite())
+            .collect::<Vec<_>>();
+        gaussians.sort_by(|a, b| {
+            a.timestamp_timescale
+                .timestamp
+                .total_cmp(&b.timestamp_timescale.timestamp)
+        });
+
+        let Some(first) = gaussians.first() else {
+            return TemporalGaussianHierarchy::default();
+        };
+
+        let origin = first.timestamp_timescale.timestamp;
+        let level_duration = self.level_duration.max(f32::EPSILON);
+        let bin = |gaussian: &Gaussian4d| {
+            ((gaussian.timestamp_timescale.timestamp - origin) / level_duration).floor() as i64
+        };
+
+        let mut levels = Vec::new();
+        let mut offset = 0;
+        for group in gaussians.chunk_by(|a, b| bin(a) == bin(b)) {
+            levels.push(TemporalGaussianLevel::spanning(offset, group));
+            offset += group.len();
+        }
+
+        TemporalGaussianHierarchy {
+            flat_cloud: gaussians.into(),
+            levels,
+        }
+    }
+}
+
+/// half width of the time window in which the gaussian passes the shader's temporal mask
+pub fn temporal_support(gaussian: &Gaussian4d) -> f32 {
+    let [w, x, y, z] = gaussian.isotropic_rotations.rotation;
+    let [wr, xr, yr, zr] = gaussian.isotropic_rotations.rotation_r;
+    let [sx, sy, sz] = gaussian.scale_opacity.scale;
+
+    // mirrors `conditional_cov3d` in gaussian_4d.wgsl
+    #[rustfmt::skip]
+    let m_l = Mat4::from_cols_array(&[
+        w, -x, -y, -z,
+        x,  w, -z,  y,
+        y,  z,  w, -x,
+        z, -y,  x,  w,
+    ]);
+    #[rustfmt::skip]
+    let m_r = Mat4::from_cols_array(&[
+        wr, -xr, -yr, -zr,
+        xr,  wr,  zr, -yr,
+        yr, -zr,  wr,  xr,
+        zr,  yr, -xr,  wr,
+    ]);
+    let s = Mat4::from_diagonal(Vec4::new(
+        sx,
+        sy,
+        sz,
+        gaussian.timestamp_timescale.timescale,
+    ));
+
+    let m = m_r * m_l * s;
+    let sigma = m.transpose() * m;
+
+    TEMPORAL_SUPPORT_SIGMA * sigma.w_axis.w.max(0.0).sqrt()
+}
@@ -1 +1,145 @@
+use std::marker::Copy;
 
+use bevy::prelude::*;
+use bevy_interleave::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gaussian::{
+        f16::{
+            IsotropicRotations as HalfIsotropicRotations, ScaleOpacityPacked64,
+            TimestampTimescalePacked32,
+        },
+        f32::{IsotropicRotations, PositionVisibility},
+        formats::planar_4d::{Gaussian4d, PlanarGaussian4d, random_gaussians_4d},
+        interface::{CommonCloud, PlanarStorageFormat, TestCloud},
+        iter::PositionIter,
+    },
+    material::spherindrical_harmonics::{
+        HalfSpherindricalHarmonicCoefficients, SpherindricalHarmonicCoefficients,
+    },
+};
+
+/// `Gaussian4d` with f16 harmonics, rotations, scale/opacity and timestamp/timescale, positions stay f32
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    Planar,
+    ReflectInterleaved,
+    StorageBindings,
+    Reflect,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[serde(default)]
+#[repr(C)]
+pub struct Gaussian4dQuantized {
+    #[serde(default)]
+    pub position_visibility: PositionVisibility,
+    #[serde(default)]
+    pub spherindrical_harmonic: HalfSpherindricalHarmonicCoefficients,
+    #[serde(default)]
+    pub isotropic_rotations: HalfIsotropicRotations,
+    #[serde(default)]
+    pub scale_opacity: ScaleOpacityPacked64,
+    #[serde(default)]
+    pub timestamp_timescale: TimestampTimescalePacked32,
+}
+
+impl From<&Gaussian4d> for Gaussian4dQuantized {
+    fn from(gaussian: &Gaussian4d) -> Self {
+        Self {
+            position_visibility: gaussian.position_visibility,
+            spherindrical_harmonic: (&gaussian.spherindrical_harmonic).into(),
+            isotropic_rotations: HalfIsotropicRotations::from_gaussian(gaussian),
+            scale_opacity: (&gaussian.scale_opacity).into(),
+            timestamp_timescale: (&gaussian.timestamp_timescale).into(),
+        }
+    }
+}
+
+impl From<&Gaussian4dQuantized> for Gaussian4d {
+    fn from(gaussian: &Gaussian4dQuantized) -> Self {
+        let [rotation, rotation_r] = gaussian.isotropic_rotations.rotations();
+
+        Self {
+            position_visibility: gaussian.position_visibility,
+            spherindrical_harmonic: SpherindricalHarmonicCoefficients::from(
+                &gaussian.spherindrical_harmonic,
+            ),
+            isotropic_rotations: IsotropicRotations {
+                rotation: rotation.rotation,
+                rotation_r: rotation_r.rotation,
+            },
+            scale_opacity: gaussian.scale_opacity.scale_opacity(),
+            timestamp_timescale: gaussian.timestamp_timescale.timestamp_timescale(),
+        }
+    }
+}
+
+impl From<&PlanarGaussian4d> for PlanarGaussian4dQuantized {
+    fn from(cloud: &PlanarGaussian4d) -> Self {
+        (0..cloud.len())
+            .map(|index| Gaussian4dQuantized::from(&cloud.get(index)))
+            .collect()
+    }
+}
+
+impl From<&PlanarGaussian4dQuantized> for PlanarGaussian4d {
+    fn from(cloud: &PlanarGaussian4dQuantized) -> Self {
+        (0..cloud.len())
+            .map(|index| Gaussian4d::from(&cloud.get(index)))
+            .collect()
+    }
+}
+
+impl CommonCloud for PlanarGaussian4dQuantized {
+    type PackedType = Gaussian4dQuantized;
+
+    fn storage_format() -> PlanarStorageFormat {
+        PlanarStorageFormat::F16
+    }
+
+    fn visibility(&self, index: usize) -> f32 {
+        self.position_visibility[index].visibility
+    }
+
+    fn visibility_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.position_visibility[index].visibility
+    }
+
+    fn position_iter(&self) -> PositionIter<'_> {
+        PositionIter::new(&self.position_visibility)
+    }
+
+    #[cfg(feature = "sort_rayon")]
+    fn position_par_iter(&self) -> crate::gaussian::iter::PositionParIter<'_> {
+        crate::gaussian::iter::PositionParIter::new(&self.position_visibility)
+    }
+}
+
+impl FromIterator<Gaussian4dQuantized> for PlanarGaussian4dQuantized {
+    fn from_iter<I: IntoIterator<Item = Gaussian4dQuantized>>(iter: I) -> Self {
+        iter.into_iter()
+            .collect::<Vec<Gaussian4dQuantized>>()
+            .into()
+    }
+}
+
+impl From<Vec<Gaussian4dQuantized>> for PlanarGaussian4dQuantized {
+    fn from(packed: Vec<Gaussian4dQuantized>) -> Self {
+        Self::from_interleaved(packed)
+    }
+}
+
+impl TestCloud for PlanarGaussian4dQuantized {
+    fn test_model() -> Self {
+        (&random_gaussians_4d(512)).into()
+    }
+}
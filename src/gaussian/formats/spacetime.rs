@@ -1,34 +1,228 @@
 // https://github.com/oppo-us-research/SpacetimeGaussians
 
-// property float x
-// property float y
-// property float z
-// property float trbf_center
-// property float trbf_scale
-// property float nx
-// property float ny
-// property float nz
-// property float motion_0
-
-// property float motion_2
-// property float motion_3
-// property float motion_4
-// property float motion_5
-// property float motion_6
-// property float motion_7
-// property float motion_8
-// property float f_dc_0
-// property float f_dc_1
-// property float f_dc_2
-// property float opacity
-// property float scale_0
-// property float scale_1
-// property float scale_2
-// property float rot_0
-// property float rot_1
-// property float rot_2
-// property float rot_3
-// property float omega_0
-// property float omega_1
-// property float omega_2
-// property float omega_3
+use std::marker::Copy;
+
+use bevy::{math::bounding::Aabb3d, prelude::*};
+use bevy_interleave::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use rand::{
+    Rng,
+    distr::{Distribution, StandardUniform},
+    rng,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gaussian::{
+        f32::{AngularVelocity, PositionVisibility, Rotation, ScaleOpacity, SpacetimeMotion},
+        interface::{CommonCloud, PlanarStorageFormat, TestCloud},
+        iter::PositionIter,
+    },
+    material::spherical_harmonics::{SH_COEFF_COUNT, SphericalHarmonicCoefficients},
+};
+
+/// trajectory samples in units of `trbf_scale` around `trbf_center`, opacity falls to e^-4 at the ends
+const TRAJECTORY_SAMPLES: [f32; 5] = [-2.0, -1.0, 0.0, 1.0, 2.0];
+
+/// 3d gaussian with polynomial motion, angular velocity and temporal rbf opacity
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Copy,
+    PartialEq,
+    Planar,
+    ReflectInterleaved,
+    StorageBindings,
+    Reflect,
+    Pod,
+    Zeroable,
+    Serialize,
+    Deserialize,
+)]
+#[serde(default)]
+#[repr(C)]
+pub struct GaussianSpacetime {
+    #[serde(default)]
+    pub position_visibility: PositionVisibility,
+    #[serde(default)]
+    pub spherical_harmonic: SphericalHarmonicCoefficients,
+    #[serde(default)]
+    pub rotation: Rotation,
+    #[serde(default)]
+    pub scale_opacity: ScaleOpacity,
+    #[serde(default)]
+    pub motion: SpacetimeMotion,
+    #[serde(default)]
+    pub angular_velocity: AngularVelocity,
+}
+
+// mirrors the PLANAR_SPACETIME getters in planar.wgsl
+impl GaussianSpacetime {
+    pub fn position_at(&self, time: f32) -> Vec3 {
+        let dt = time - self.motion.trbf_center;
+        let [m0, m1, m2, m3, m4, m5, m6, m7, m8] = self.motion.motion;
+
+        Vec3::from(self.position_visibility.position)
+            + Vec3::new(m0, m1, m2) * dt
+            + Vec3::new(m3, m4, m5) * dt.powi(2)
+            + Vec3::new(m6, m7, m8) * dt.powi(3)
+    }
+
+    /// normalized [w, x, y, z] rotation
+    pub fn rotation_at(&self, time: f32) -> Vec4 {
+        let dt = time - self.motion.trbf_center;
+        let rotation =
+            Vec4::from(self.rotation.rotation) + Vec4::from(self.angular_velocity.omega) * dt;
+
+        rotation.try_normalize().unwrap_or(Vec4::X)
+    }
+
+    pub fn opacity_at(&self, time: f32) -> f32 {
+        let dt = time - self.motion.trbf_center;
+        let distance = dt / self.motion.trbf_scale.max(f32::EPSILON);
+
+        self.scale_opacity.opacity * (-distance.powi(2)).exp()
+    }
+}
+
+impl CommonCloud for PlanarGaussianSpacetime {
+    type PackedType = GaussianSpacetime;
+
+    // sampled along each trajectory, the base positions alone miss moving gaussians
+    fn compute_aabb(&self) -> Option<Aabb3d> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // TODO: find a more correct aabb bound derived from scalar max gaussian scale
+        let max_scale = Vec3::splat(0.1);
+
+        let (min, max) = (0..self.len())
+            .map(|index| self.get(index))
+            .flat_map(|gaussian| {
+                TRAJECTORY_SAMPLES.map(|sample| {
+                    gaussian.position_at(
+                        gaussian.motion.trbf_center + sample * gaussian.motion.trbf_scale,
+                    )
+                })
+            })
+            .fold(
+                (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+                |(min, max), position| {
+                    (min.min(position - max_scale), max.max(position + max_scale))
+                },
+            );
+
+        Some(Aabb3d {
+            min: min.into(),
+            max: max.into(),
+        })
+    }
+
+    fn storage_format() -> PlanarStorageFormat {
+        PlanarStorageFormat::Spacetime
+    }
+
+    fn visibility(&self, index: usize) -> f32 {
+        self.position_visibility[index].visibility
+    }
+
+    fn visibility_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.position_visibility[index].visibility
+    }
+
+    // TODO: cpu sorts use the base positions, evaluate the trajectory at `CloudSettings::time`
+    fn position_iter(&self) -> PositionIter<'_> {
+        PositionIter::new(&self.position_visibility)
+    }
+
+    #[cfg(feature = "sort_rayon")]
+    fn position_par_iter(&self) -> crate::gaussian::iter::PositionParIter<'_> {
+        crate::gaussian::iter::PositionParIter::new(&self.position_visibility)
+    }
+}
+
+impl FromIterator<GaussianSpacetime> for PlanarGaussianSpacetime {
+    fn from_iter<I: IntoIterator<Item = GaussianSpacetime>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<GaussianSpacetime>>().into()
+    }
+}
+
+impl From<Vec<GaussianSpacetime>> for PlanarGaussianSpacetime {
+    fn from(packed: Vec<GaussianSpacetime>) -> Self {
+        Self::from_interleaved(packed)
+    }
+}
+
+impl Distribution<GaussianSpacetime> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> GaussianSpacetime {
+        let mut motion = [0.0; 9];
+        for coefficient in motion.iter_mut() {
+            *coefficient = rng.random_range(-1.0..1.0);
+        }
+
+        GaussianSpacetime {
+            position_visibility: [
+                rng.random_range(-20.0..20.0),
+                rng.random_range(-20.0..20.0),
+                rng.random_range(-20.0..20.0),
+                1.0,
+            ]
+            .into(),
+            spherical_harmonic: SphericalHarmonicCoefficients {
+                coefficients: {
+                    let mut coefficients = [0.0; SH_COEFF_COUNT];
+                    for coefficient in coefficients.iter_mut() {
+                        *coefficient = rng.random_range(-1.0..1.0);
+                    }
+                    coefficients
+                },
+            },
+            rotation: [
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            ]
+            .into(),
+            scale_opacity: [
+                rng.random_range(0.0..1.0),
+                rng.random_range(0.0..1.0),
+                rng.random_range(0.0..1.0),
+                rng.random_range(0.0..0.8),
+            ]
+            .into(),
+            motion: SpacetimeMotion {
+                motion,
+                trbf_center: rng.random_range(0.0..1.0),
+                trbf_scale: rng.random_range(0.1..1.0),
+                _pad: 0.0,
+            },
+            angular_velocity: [
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            ]
+            .into(),
+        }
+    }
+}
+
+pub fn random_gaussians_spacetime(n: usize) -> PlanarGaussianSpacetime {
+    let mut rng = rng();
+    let mut gaussians: Vec<GaussianSpacetime> = Vec::with_capacity(n);
+
+    for _ in 0..n {
+        gaussians.push(rng.random());
+    }
+
+    PlanarGaussianSpacetime::from_interleaved(gaussians)
+}
+
+impl TestCloud for PlanarGaussianSpacetime {
+    fn test_model() -> Self {
+        random_gaussians_spacetime(512)
+    }
+}
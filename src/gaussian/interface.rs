@@ -6,6 +6,17 @@ use rayon::prelude::*;
 
 use crate::gaussian::iter::PositionIter;
 
+/// gpu storage layout of a cloud's planes, selects the matching shader bindings
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum PlanarStorageFormat {
+    #[default]
+    F32,
+    F16,
+    Quantized,
+    Codebook,
+    Spacetime,
+}
+
 pub trait CommonCloud
 where
     Self: Planar,
@@ -37,7 +48,7 @@ where
                 .fold(
                     || (min, max),
                     |(curr_min, curr_max), position| {
-                        let pos = Vec3::from(*position);
+                        let pos = Vec3::from(position);
                         let offset = Vec3::splat(max_scale);
                         (curr_min.min(pos - offset), curr_max.max(pos + offset))
                     },
@@ -51,8 +62,8 @@ where
         #[cfg(not(feature = "sort_rayon"))]
         {
             for position in self.position_iter() {
-                min = min.min(Vec3::from(*position) - Vec3::splat(max_scale));
-                max = max.max(Vec3::from(*position) + Vec3::splat(max_scale));
+                min = min.min(Vec3::from(position) - Vec3::splat(max_scale));
+                max = max.max(Vec3::from(position) + Vec3::splat(max_scale));
             }
         }
 
@@ -62,8 +73,25 @@ where
         })
     }
 
+    fn storage_format() -> PlanarStorageFormat {
+        PlanarStorageFormat::F32
+    }
+
     fn visibility(&self, index: usize) -> f32;
-    fn visibility_mut(&mut self, index: usize) -> &mut f32;
+
+    #[deprecated(note = "packed formats cannot lend their visibility, use `set_visibility`")]
+    fn visibility_mut(&mut self, _index: usize) -> &mut f32 {
+        unimplemented!(
+            "{} packs its visibility, use `set_visibility`",
+            std::any::type_name::<Self>()
+        )
+    }
+
+    // packed formats override this, the default writes through `visibility_mut`
+    #[allow(deprecated)]
+    fn set_visibility(&mut self, index: usize, visibility: f32) {
+        *self.visibility_mut(index) = visibility;
+    }
 
     // TODO: type erasure for position iterators
     fn position_iter(&self) -> PositionIter<'_>;
@@ -3,75 +3,138 @@ use rayon::iter::plumbing::{Consumer, UnindexedConsumer};
 #[cfg(feature = "sort_rayon")]
 use rayon::prelude::*;
 
-use crate::gaussian::f32::{Position, PositionVisibility};
+use crate::gaussian::{
+    f32::{Position, PositionVisibility},
+    formats::planar_3d_quantized::{
+        QUANTIZED_CHUNK_SIZE, QuantizedChunk, QuantizedPositionVisibility,
+    },
+};
+
+#[derive(Clone, Copy)]
+enum PositionSource<'a> {
+    Planar(&'a [PositionVisibility]),
+    Quantized {
+        positions: &'a [QuantizedPositionVisibility],
+        chunks: &'a [QuantizedChunk],
+    },
+}
+
+impl PositionSource<'_> {
+    #[cfg(feature = "sort_rayon")]
+    fn len(&self) -> usize {
+        match self {
+            Self::Planar(slice) => slice.len(),
+            Self::Quantized { positions, .. } => positions.len(),
+        }
+    }
+
+    fn get(&self, index: usize) -> Position {
+        match self {
+            Self::Planar(slice) => slice[index].position,
+            Self::Quantized { positions, chunks } => {
+                positions[index].position(&chunks[index / QUANTIZED_CHUNK_SIZE])
+            }
+        }
+    }
+}
 
 pub struct PositionIter<'a> {
-    slice_iter: std::slice::Iter<'a, PositionVisibility>,
+    source: PositionSource<'a>,
+    range: std::ops::Range<usize>,
 }
 
 impl<'a> PositionIter<'a> {
     pub fn new(slice: &'a [PositionVisibility]) -> Self {
         Self {
-            slice_iter: slice.iter(),
+            source: PositionSource::Planar(slice),
+            range: 0..slice.len(),
+        }
+    }
+
+    pub fn new_quantized(
+        positions: &'a [QuantizedPositionVisibility],
+        chunks: &'a [QuantizedChunk],
+    ) -> Self {
+        Self {
+            source: PositionSource::Quantized { positions, chunks },
+            range: 0..positions.len(),
         }
     }
 }
 
-impl<'a> Iterator for PositionIter<'a> {
-    type Item = &'a Position;
+impl Iterator for PositionIter<'_> {
+    type Item = Position;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.slice_iter.next().map(|pv| &pv.position)
+        self.range.next().map(|index| self.source.get(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
     }
 }
 
+impl ExactSizeIterator for PositionIter<'_> {}
+
 #[cfg(feature = "sort_rayon")]
 pub struct PositionParIter<'a> {
-    slice_par_iter: rayon::slice::Iter<'a, PositionVisibility>,
+    source: PositionSource<'a>,
 }
 
 #[cfg(feature = "sort_rayon")]
 impl<'a> PositionParIter<'a> {
     pub fn new(slice: &'a [PositionVisibility]) -> Self {
         Self {
-            slice_par_iter: slice.par_iter(),
+            source: PositionSource::Planar(slice),
         }
     }
+
+    pub fn new_quantized(
+        positions: &'a [QuantizedPositionVisibility],
+        chunks: &'a [QuantizedChunk],
+    ) -> Self {
+        Self {
+            source: PositionSource::Quantized { positions, chunks },
+        }
+    }
+
+    fn indexed(self) -> impl IndexedParallelIterator<Item = Position> + 'a {
+        let source = self.source;
+        (0..source.len())
+            .into_par_iter()
+            .map(move |index| source.get(index))
+    }
 }
 
 #[cfg(feature = "sort_rayon")]
-impl<'a> ParallelIterator for PositionParIter<'a> {
-    type Item = &'a Position;
+impl ParallelIterator for PositionParIter<'_> {
+    type Item = Position;
 
     fn drive_unindexed<C>(self, consumer: C) -> C::Result
     where
         C: UnindexedConsumer<Self::Item>,
     {
-        self.slice_par_iter
-            .map(|pv| &pv.position)
-            .drive_unindexed(consumer)
+        self.indexed().drive_unindexed(consumer)
     }
 }
 
 #[cfg(feature = "sort_rayon")]
 impl IndexedParallelIterator for PositionParIter<'_> {
     fn len(&self) -> usize {
-        self.slice_par_iter.len()
+        self.source.len()
     }
 
     fn drive<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
     where
         C: Consumer<Self::Item>,
     {
-        self.slice_par_iter.map(|pv| &pv.position).drive(consumer)
+        self.indexed().drive(consumer)
     }
 
     fn with_producer<CB>(self, callback: CB) -> CB::Output
     where
         CB: rayon::iter::plumbing::ProducerCallback<Self::Item>,
     {
-        self.slice_par_iter
-            .map(|pv| &pv.position)
-            .with_producer(callback)
+        self.indexed().with_producer(callback)
     }
 }
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::GaussianCamera,
+    gaussian::formats::{
+        planar_3d::{Gaussian3d, PlanarGaussian3d, PlanarGaussian3dHandle},
+        planar_3d_lod::PlanarGaussian3dLod,
+    },
+    stream::resident::{ResidentClouds, ResidentStreamPlugin, ResidentStreamQueue},
+};
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+#[require(GaussianLod, Transform, Visibility)]
+pub struct PlanarGaussian3dLodHandle(pub Handle<PlanarGaussian3dLod>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum LodSelection {
+    /// every node of one hierarchy level, 0 is full detail
+    Level(usize),
+    /// refine until the geometric error of each node projects to at most `error_budget` pixels
+    ScreenSpaceError { error_budget: f32 },
+}
+
+impl Default for LodSelection {
+    fn default() -> Self {
+        Self::ScreenSpaceError { error_budget: 1.0 }
+    }
+}
+
+/// selects the cut of a lod hierarchy drawn by this entity, the cut is written to its `PlanarGaussian3dHandle`
+///
+/// the cut cloud stays resident, a new cut only uploads the slots of nodes entering it
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct GaussianLod {
+    pub selection: LodSelection,
+    #[reflect(ignore)]
+    cut: Vec<u32>,
+    #[reflect(ignore)]
+    source: Option<AssetId<PlanarGaussian3dLod>>,
+}
+
+impl GaussianLod {
+    pub fn new(selection: LodSelection) -> Self {
+        Self {
+            selection,
+            ..default()
+        }
+    }
+
+    /// hierarchy nodes currently drawn, indexed by their slot in the cut cloud
+    pub fn cut(&self) -> &[u32] {
+        &self.cut
+    }
+}
+
+#[derive(Default)]
+pub struct LodPlugin;
+
+impl Plugin for LodPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GaussianLod>();
+        app.register_type::<PlanarGaussian3dLodHandle>();
+        app.init_asset::<PlanarGaussian3dLod>();
+
+        app.add_plugins(ResidentStreamPlugin::<Gaussian3d>::default());
+        app.add_systems(Update, select_lod_cut);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn select_lod_cut(
+    mut lod_events: MessageReader<AssetEvent<PlanarGaussian3dLod>>,
+    lod_clouds: Res<Assets<PlanarGaussian3dLod>>,
+    mut clouds: ResMut<Assets<PlanarGaussian3d>>,
+    mut stream_queue: ResMut<ResidentStreamQueue<Gaussian3d>>,
+    mut resident_clouds: ResidentClouds,
+    cameras: Query<(&Camera, &Projection, &GlobalTransform), With<GaussianCamera>>,
+    mut lods: Query<(
+        Entity,
+        &PlanarGaussian3dLodHandle,
+        &mut GaussianLod,
+        &GlobalTransform,
+        Option<&PlanarGaussian3dHandle>,
+    )>,
+) {
+    // node indices of a modified hierarchy no longer match the resident cut
+    let modified = lod_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    // TODO: per-camera cuts, the lowest order camera drives the selection
+    let camera = cameras
+        .iter()
+        .filter(|(camera, ..)| camera.is_active)
+        .min_by_key(|(camera, ..)| camera.order);
+
+    for (entity, lod_handle, mut lod, transform, cloud_handle) in lods.iter_mut() {
+        let Some(lod_cloud) = lod_clouds.get(&lod_handle.0) else {
+            continue;
+        };
+
+        let cut = match (lod.selection, camera) {
+            (LodSelection::Level(level), _) => lod_cloud.level_cut(level),
+            (
+                LodSelection::ScreenSpaceError { error_budget },
+                Some((camera, projection, camera_transform)),
+            ) => {
+                let viewport_height = camera
+                    .logical_viewport_size()
+                    .map(|size| size.y)
+                    .unwrap_or(1.0);
+                let camera_position = transform
+                    .affine()
+                    .inverse()
+                    .transform_point3(camera_transform.translation());
+
+                match projection {
+                    Projection::Perspective(perspective) => {
+                        let projection_scale =
+                            viewport_height / (2.0 * (perspective.fov / 2.0).tan());
+                        lod_cloud.screen_space_cut(camera_position, projection_scale, error_budget)
+                    }
+                    Projection::Orthographic(orthographic) => {
+                        // pixels per local unit, independent of distance
+                        let pixels_per_unit = viewport_height / orthographic.area.height()
+                            * transform.scale().max_element();
+                        lod_cloud.cut_by(|node, _| node.error * pixels_per_unit > error_budget)
+                    }
+                    Projection::Custom(_) => lod_cloud.level_cut(0),
+                }
+            }
+            (LodSelection::ScreenSpaceError { .. }, None) => continue,
+        };
+
+        let source = lod_handle.0.id();
+        // an unchanged cut of an unchanged hierarchy needs no upload and no sort
+        if cut == lod.cut
+            && lod.source == Some(source)
+            && cloud_handle.is_some()
+            && !modified.contains(&source)
+        {
+            continue;
+        }
+
+        let streamed = lod.source == Some(source) && !modified.contains(&source);
+        resident_clouds.restream(
+            &mut clouds,
+            &mut stream_queue,
+            entity,
+            cloud_handle,
+            &mut lod.cut,
+            streamed,
+            cut,
+            &lod_cloud.gaussians,
+        );
+        lod.source = Some(source);
+    }
+}
@@ -46,6 +46,60 @@ pub enum RasterizeMode {
     Velocity,
 }
 
+/// how splats reach the view target
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect, Serialize, Deserialize, ValueEnum,
+)]
+pub enum Rasterizer {
+    /// one hardware blended quad per gaussian
+    #[default]
+    Instanced,
+    /// compute rasterization of screen tiles, blended front-to-back with early termination
+    ///
+    /// needs `sort_radix` without `buffer_texture`, other builds draw `Instanced` instead
+    Tile,
+    /// sort free weighted blended order independent transparency, pairs with `SortMode::None`
+    WeightedBlended,
+}
+
+impl Rasterizer {
+    /// `Instanced` in place of a `Tile` rasterizer this build has no pipeline for
+    pub const fn or_supported(self) -> Self {
+        match self {
+            Self::Tile if !cfg!(all(feature = "sort_radix", not(feature = "buffer_texture"))) => {
+                Self::Instanced
+            }
+            rasterizer => rasterizer,
+        }
+    }
+}
+
+/// how splats interact with the view depth buffer
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect, Serialize, Deserialize, ValueEnum,
+)]
+pub enum DepthMode {
+    /// test against the opaque depth without writing
+    #[default]
+    Test,
+    /// write the quad depth of fragments with alpha above `CloudSettings::depth_alpha_threshold`
+    AlphaThreshold,
+}
+
+/// depth weight of a splat in `Rasterizer::WeightedBlended`, multiplied by its alpha
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect, Serialize, Deserialize, ValueEnum,
+)]
+pub enum OitWeight {
+    /// alpha weighted average, ignores depth
+    Constant,
+    /// `clamp(10 / (1e-5 + (d / 5)^2 + (d / 200)^6), 1e-2, 3e3)` of the view distance `d`
+    #[default]
+    DepthFalloff,
+    /// `clamp(3e3 * z^3, 1e-2, 3e3)` of the reversed ndc depth `z`
+    ProjectedDepth,
+}
+
 #[derive(
     Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect, Serialize, Deserialize, ValueEnum,
 )]
@@ -99,6 +153,10 @@ pub struct CloudSettings {
     pub gaussian_mode: GaussianMode,
     pub playback_mode: PlaybackMode,
     pub rasterize_mode: RasterizeMode,
+    pub rasterizer: Rasterizer,
+    pub oit_weight: OitWeight,
+    pub depth_mode: DepthMode,
+    pub depth_alpha_threshold: f32,
     pub color_space: GaussianColorSpace,
     pub num_classes: usize,
     pub time: f32,
@@ -120,6 +178,10 @@ impl Default for CloudSettings {
             draw_mode: DrawMode::default(),
             gaussian_mode: GaussianMode::default(),
             rasterize_mode: RasterizeMode::default(),
+            rasterizer: Rasterizer::default(),
+            oit_weight: OitWeight::default(),
+            depth_mode: DepthMode::default(),
+            depth_alpha_threshold: 0.5,
             color_space: GaussianColorSpace::default(),
             num_classes: 1,
             playback_mode: PlaybackMode::default(),
@@ -1,17 +1,87 @@
-use std::io::Write;
+use std::{
+    fmt,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
 
-// TODO: support streamed codecs
-pub trait CloudCodec {
-    fn encode(&self) -> Vec<u8>;
-    fn decode(data: &[u8]) -> Self;
+#[derive(Debug)]
+pub enum CloudCodecError {
+    Io(std::io::Error),
+    Encode(String),
+    Decode(String),
+    Header(String),
+    ShDegreeMismatch { found: usize, expected: usize },
+}
+
+impl fmt::Display for CloudCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "cloud io error: {err}"),
+            Self::Encode(message) => write!(f, "failed to encode cloud: {message}"),
+            Self::Decode(message) => write!(f, "failed to decode cloud: {message}"),
+            Self::Header(message) => write!(f, "invalid gcloud header: {message}"),
+            Self::ShDegreeMismatch { found, expected } => write!(
+                f,
+                "cloud was written with sh degree {found} but this build uses sh degree {expected}, enable the `sh{found}` feature",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CloudCodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CloudCodecError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+// asset loaders report `std::io::Error`
+impl From<CloudCodecError> for std::io::Error {
+    fn from(err: CloudCodecError) -> Self {
+        match err {
+            CloudCodecError::Io(err) => err,
+            CloudCodecError::Encode(_) => std::io::Error::other(err),
+            _ => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+pub trait CloudCodec: Sized {
+    fn encode_to<W: Write>(&self, writer: W) -> Result<(), CloudCodecError>;
+
+    /// decodes incrementally where the codec allows it, reads to the end otherwise
+    fn decode_from<R: Read>(reader: R) -> Result<Self, CloudCodecError>;
+
+    fn encode(&self) -> Result<Vec<u8>, CloudCodecError> {
+        let mut output = Vec::new();
+        self.encode_to(&mut output)?;
+        Ok(output)
+    }
 
-    fn write_to_file(&self, path: &str) {
-        let gcloud_file = std::fs::File::create(path).expect("failed to create file");
-        let mut gcloud_writer = std::io::BufWriter::new(gcloud_file);
+    fn decode(data: &[u8]) -> Result<Self, CloudCodecError> {
+        Self::decode_from(data)
+    }
+
+    fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), CloudCodecError> {
+        let gcloud_file = std::fs::File::create(path)?;
+        let mut gcloud_writer = BufWriter::new(gcloud_file);
+
+        self.encode_to(&mut gcloud_writer)?;
+        gcloud_writer.flush()?;
+
+        Ok(())
+    }
 
-        let data = self.encode();
-        gcloud_writer
-            .write_all(data.as_slice())
-            .expect("failed to write to gcloud file");
+    fn read_from_file(path: impl AsRef<Path>) -> Result<Self, CloudCodecError> {
+        let gcloud_file = std::fs::File::open(path)?;
+        Self::decode_from(BufReader::new(gcloud_file))
     }
 }
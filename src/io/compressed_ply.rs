@@ -0,0 +1,222 @@
+use std::io::{BufRead, Write};
+
+use ply_rs::{
+    parser::Parser,
+    ply::{DefaultElement, Header, Property},
+};
+
+use crate::gaussian::formats::planar_3d_chunked::{
+    CHUNK_SIZE, GaussianChunk, PlanarGaussian3dChunked,
+};
+
+const CHUNK_PROPERTIES: [&str; 18] = [
+    "min_x",
+    "min_y",
+    "min_z",
+    "max_x",
+    "max_y",
+    "max_z",
+    "min_scale_x",
+    "min_scale_y",
+    "min_scale_z",
+    "max_scale_x",
+    "max_scale_y",
+    "max_scale_z",
+    "min_r",
+    "min_g",
+    "min_b",
+    "max_r",
+    "max_g",
+    "max_b",
+];
+
+const VERTEX_PROPERTIES: [&str; 4] = [
+    "packed_position",
+    "packed_rotation",
+    "packed_scale",
+    "packed_color",
+];
+
+/// true if the header describes the PlayCanvas compressed ply layout
+pub fn is_compressed_ply(header: &Header) -> bool {
+    header.elements.contains_key("chunk")
+        && header
+            .elements
+            .get("vertex")
+            .is_some_and(|vertex| vertex.properties.contains_key("packed_position"))
+}
+
+pub fn parse_compressed_ply(
+    mut reader: &mut dyn BufRead,
+) -> Result<PlanarGaussian3dChunked, std::io::Error> {
+    let parser = Parser::<DefaultElement>::new();
+    let header = parser.read_header(&mut reader)?;
+
+    read_compressed_payload(reader, &header)
+}
+
+pub(crate) fn read_compressed_payload(
+    mut reader: &mut dyn BufRead,
+    header: &Header,
+) -> Result<PlanarGaussian3dChunked, std::io::Error> {
+    if !is_compressed_ply(header) {
+        return Err(invalid_data("missing chunk or packed vertex elements"));
+    }
+
+    let parser = Parser::<DefaultElement>::new();
+    let mut cloud = PlanarGaussian3dChunked::default();
+
+    // payloads are stored in header order
+    for (_key, element) in &header.elements {
+        let rows = parser.read_payload_for_element(&mut reader, element, header)?;
+
+        match element.name.as_str() {
+            "chunk" => {
+                cloud.chunks = rows
+                    .iter()
+                    .map(parse_chunk)
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+            "vertex" => {
+                for row in &rows {
+                    let mut packed = [0; VERTEX_PROPERTIES.len()];
+                    for (value, key) in packed.iter_mut().zip(VERTEX_PROPERTIES) {
+                        *value = row
+                            .get(key)
+                            .and_then(property_u32)
+                            .ok_or_else(|| invalid_data(&format!("missing vertex {key}")))?;
+                    }
+                    let [position, rotation, scale, color] = packed;
+
+                    cloud.packed_position.push(position);
+                    cloud.packed_rotation.push(rotation);
+                    cloud.packed_scale.push(scale);
+                    cloud.packed_color.push(color);
+                }
+            }
+            "sh" => {
+                cloud.sh_coefficients = element.properties.len();
+
+                for row in &rows {
+                    for index in 0..cloud.sh_coefficients {
+                        let value = row
+                            .get(&format!("f_rest_{index}"))
+                            .and_then(property_u32)
+                            .ok_or_else(|| invalid_data("missing sh coefficient"))?;
+
+                        cloud.spherical_harmonics.push(value.min(255) as u8);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if cloud.chunks.len() < cloud.len().div_ceil(CHUNK_SIZE) {
+        return Err(invalid_data("chunk count does not cover vertex count"));
+    }
+
+    if cloud.spherical_harmonics.len() != cloud.len() * cloud.sh_coefficients {
+        return Err(invalid_data("sh count does not match vertex count"));
+    }
+
+    Ok(cloud)
+}
+
+fn parse_chunk(row: &DefaultElement) -> Result<GaussianChunk, std::io::Error> {
+    let mut values = [0.0; CHUNK_PROPERTIES.len()];
+    for (index, key) in CHUNK_PROPERTIES.iter().enumerate() {
+        values[index] = match row.get(*key).and_then(property_f32) {
+            Some(value) => value,
+            // color ranges are optional and default to [0, 1]
+            None if index >= 15 => 1.0,
+            None if index >= 12 => 0.0,
+            None => return Err(invalid_data("missing chunk property")),
+        };
+    }
+
+    Ok(GaussianChunk {
+        min_position: [values[0], values[1], values[2]],
+        max_position: [values[3], values[4], values[5]],
+        min_scale: [values[6], values[7], values[8]],
+        max_scale: [values[9], values[10], values[11]],
+        min_color: [values[12], values[13], values[14]],
+        max_color: [values[15], values[16], values[17]],
+    })
+}
+
+fn property_f32(property: &Property) -> Option<f32> {
+    match *property {
+        Property::Float(v) => Some(v),
+        Property::Double(v) => Some(v as f32),
+        _ => property_u32(property).map(|v| v as f32),
+    }
+}
+
+fn property_u32(property: &Property) -> Option<u32> {
+    match *property {
+        Property::UChar(v) => Some(v as u32),
+        Property::UShort(v) => Some(v as u32),
+        Property::UInt(v) => Some(v),
+        Property::Char(v) => Some(v.max(0) as u32),
+        Property::Short(v) => Some(v.max(0) as u32),
+        Property::Int(v) => Some(v.max(0) as u32),
+        _ => None,
+    }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// writes a binary little endian PlayCanvas compressed ply
+pub fn write_compressed_ply(
+    cloud: &PlanarGaussian3dChunked,
+    writer: &mut dyn Write,
+) -> Result<(), std::io::Error> {
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format binary_little_endian 1.0")?;
+    writeln!(writer, "element chunk {}", cloud.chunks.len())?;
+    for property in CHUNK_PROPERTIES {
+        writeln!(writer, "property float {property}")?;
+    }
+    writeln!(writer, "element vertex {}", cloud.len())?;
+    for property in VERTEX_PROPERTIES {
+        writeln!(writer, "property uint {property}")?;
+    }
+    if cloud.sh_coefficients > 0 {
+        writeln!(writer, "element sh {}", cloud.len())?;
+        for index in 0..cloud.sh_coefficients {
+            writeln!(writer, "property uchar f_rest_{index}")?;
+        }
+    }
+    writeln!(writer, "end_header")?;
+
+    for chunk in &cloud.chunks {
+        for values in [
+            chunk.min_position,
+            chunk.max_position,
+            chunk.min_scale,
+            chunk.max_scale,
+            chunk.min_color,
+            chunk.max_color,
+        ] {
+            for value in values {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+    }
+
+    for index in 0..cloud.len() {
+        for value in [
+            cloud.packed_position[index],
+            cloud.packed_rotation[index],
+            cloud.packed_scale[index],
+            cloud.packed_color[index],
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+
+    writer.write_all(&cloud.spherical_harmonics)
+}
@@ -1,66 +1,96 @@
-use bincode2::{deserialize_from, serialize_into};
-use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use bincode2::{ErrorKind, deserialize_from};
+use flate2::read::GzDecoder;
 use serde::de::DeserializeOwned;
-use std::io::Cursor;
+use std::io::{BufRead, BufReader, Read};
 
+use crate::io::codec::CloudCodecError;
+
+#[cfg(not(feature = "io_flexbuffers"))]
+use bincode2::serialize_into;
+#[cfg(not(feature = "io_flexbuffers"))]
+use flate2::{Compression, write::GzEncoder};
+#[cfg(not(feature = "io_flexbuffers"))]
+use serde::Serialize;
+#[cfg(not(feature = "io_flexbuffers"))]
+use std::io::Write;
+
+#[cfg(not(feature = "io_flexbuffers"))]
 use crate::{
-    gaussian::formats::{planar_3d::PlanarGaussian3d, planar_4d::PlanarGaussian4d},
+    gaussian::formats::{
+        planar_3d::PlanarGaussian3d, planar_3d_codebook::PlanarGaussian3dCodebook,
+        planar_3d_lod::PlanarGaussian3dLod, planar_3d_quantized::PlanarGaussian3dQuantized,
+        planar_4d::PlanarGaussian4d, planar_4d_hierarchy::TemporalGaussianHierarchy,
+        planar_4d_quantized::PlanarGaussian4dQuantized, spacetime::PlanarGaussianSpacetime,
+    },
     io::codec::CloudCodec,
 };
 
-impl CloudCodec for PlanarGaussian3d {
-    fn encode(&self) -> Vec<u8> {
-        let mut output = Vec::new();
-
-        {
-            let mut gz_encoder = GzEncoder::new(&mut output, Compression::default());
-            serialize_into(&mut gz_encoder, &self).expect("failed to encode cloud");
-        }
-
-        output
-    }
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
-    fn decode(data: &[u8]) -> Self {
-        if let Ok(cloud) = decode_gzip(data) {
-            return cloud;
-        }
+#[cfg(not(feature = "io_flexbuffers"))]
+macro_rules! impl_bincode_codec {
+    ($($cloud:ty),* $(,)?) => {
+        $(
+            impl CloudCodec for $cloud {
+                fn encode_to<W: Write>(&self, writer: W) -> Result<(), CloudCodecError> {
+                    encode_gzip(self, writer)
+                }
 
-        decode_raw(data)
-    }
+                fn decode_from<R: Read>(reader: R) -> Result<Self, CloudCodecError> {
+                    decode_gzip_or_raw(reader)
+                }
+            }
+        )*
+    };
 }
 
-impl CloudCodec for PlanarGaussian4d {
-    fn encode(&self) -> Vec<u8> {
-        let mut output = Vec::new();
-
-        {
-            let mut gz_encoder = GzEncoder::new(&mut output, Compression::default());
-            serialize_into(&mut gz_encoder, &self).expect("failed to encode cloud");
-        }
-
-        output
-    }
+#[cfg(not(feature = "io_flexbuffers"))]
+impl_bincode_codec!(
+    PlanarGaussian3d,
+    PlanarGaussian3dQuantized,
+    PlanarGaussian3dCodebook,
+    PlanarGaussian3dLod,
+    PlanarGaussian4d,
+    PlanarGaussian4dQuantized,
+    PlanarGaussianSpacetime,
+    TemporalGaussianHierarchy,
+);
 
-    fn decode(data: &[u8]) -> Self {
-        if let Ok(cloud) = decode_gzip(data) {
-            return cloud;
-        }
+#[cfg(not(feature = "io_flexbuffers"))]
+fn encode_gzip<T, W>(cloud: &T, writer: W) -> Result<(), CloudCodecError>
+where
+    T: Serialize,
+    W: Write,
+{
+    let mut gz_encoder = GzEncoder::new(writer, Compression::default());
+    serialize_into(&mut gz_encoder, cloud)
+        .map_err(|err| map_error(*err, CloudCodecError::Encode))?;
+    gz_encoder.finish()?;
 
-        decode_raw(data)
-    }
+    Ok(())
 }
 
-fn decode_gzip<T>(data: &[u8]) -> Result<T, bincode2::Error>
+// streams through the gzip decoder, uncompressed payloads are still accepted
+pub(crate) fn decode_gzip_or_raw<T, R>(reader: R) -> Result<T, CloudCodecError>
 where
     T: DeserializeOwned,
+    R: Read,
 {
-    let decompressed = GzDecoder::new(data);
-    deserialize_from(decompressed)
+    let mut reader = BufReader::new(reader);
+    let compressed = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+
+    let decoded = if compressed {
+        deserialize_from(GzDecoder::new(reader))
+    } else {
+        deserialize_from(reader)
+    };
+
+    decoded.map_err(|err| map_error(*err, CloudCodecError::Decode))
 }
 
-fn decode_raw<T>(data: &[u8]) -> T
-where
-    T: DeserializeOwned,
-{
-    deserialize_from(Cursor::new(data)).expect("failed to decode cloud")
+fn map_error(err: ErrorKind, wrap: impl FnOnce(String) -> CloudCodecError) -> CloudCodecError {
+    match err {
+        ErrorKind::Io(err) => CloudCodecError::Io(err),
+        err => wrap(err.to_string()),
+    }
 }
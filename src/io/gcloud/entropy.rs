@@ -0,0 +1,403 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+use bevy_interleave::prelude::*;
+use bytemuck::Pod;
+
+use crate::io::{codec::CloudCodecError, rans};
+
+const TRANSFORM_DIRECT: u8 = 0;
+const TRANSFORM_DELTA: u8 = 1;
+
+const LANE_RAW: u8 = 0;
+const LANE_RANS: u8 = 1;
+
+// bits, transform, min, max and one lane mode and length
+const MIN_COMPONENT_BYTES: usize = 15;
+
+/// quantization and entropy coding of each planar field, see `GcloudContainer::encode_entropy_container`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GcloudEntropySettings {
+    /// 0 stores the quantized planes, 1 adds rANS coding, 2 also tries delta coding per component
+    pub level: u8,
+    /// quantization bits per component in 1..=32, 32 keeps the raw f32 bits
+    pub bits: u8,
+    /// bit depth overrides keyed by planar field name, e.g. `spherical_harmonic`
+    pub plane_bits: BTreeMap<String, u8>,
+}
+
+impl Default for GcloudEntropySettings {
+    fn default() -> Self {
+        Self {
+            level: 2,
+            bits: 16,
+            plane_bits: BTreeMap::new(),
+        }
+    }
+}
+
+impl GcloudEntropySettings {
+    pub fn lossless() -> Self {
+        Self {
+            bits: 32,
+            ..Default::default()
+        }
+    }
+
+    pub fn level(mut self, level: u8) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn bits(mut self, bits: u8) -> Self {
+        self.bits = bits;
+        self
+    }
+
+    pub fn plane_bits(mut self, plane: impl Into<String>, bits: u8) -> Self {
+        self.plane_bits.insert(plane.into(), bits);
+        self
+    }
+
+    fn bits_for(&self, plane: &str) -> Result<u8, CloudCodecError> {
+        let bits = self.plane_bits.get(plane).copied().unwrap_or(self.bits);
+        if !(1..=32).contains(&bits) {
+            return Err(CloudCodecError::Encode(format!(
+                "entropy bit depth of `{plane}` must be in 1..=32, got {bits}"
+            )));
+        }
+
+        Ok(bits)
+    }
+}
+
+struct PlaneLayout {
+    name: &'static str,
+    offset: usize,
+    words: usize,
+}
+
+fn plane_layouts<P: ReflectInterleaved>() -> Vec<PlaneLayout> {
+    let mut offset = 0;
+    P::ordered_field_names()
+        .iter()
+        .zip(P::min_binding_sizes())
+        .map(|(&name, &size)| {
+            let layout = PlaneLayout {
+                name,
+                offset,
+                words: size / 4,
+            };
+            offset += size / 4;
+            layout
+        })
+        .collect()
+}
+
+/// writes every f32 planar field as independently quantized, entropy coded components
+pub fn encode_planes<T, W>(
+    cloud: &T,
+    mut writer: W,
+    settings: &GcloudEntropySettings,
+) -> Result<(), CloudCodecError>
+where
+    T: Planar,
+    T::PackedType: ReflectInterleaved + Pod,
+    W: Write,
+{
+    let packed = cloud.to_interleaved();
+    let words = bytemuck::cast_slice::<_, f32>(&packed);
+    let stride = size_of::<T::PackedType>() / 4;
+    let layouts = plane_layouts::<T::PackedType>();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&(packed.len() as u64).to_le_bytes());
+    data.extend_from_slice(&(layouts.len() as u32).to_le_bytes());
+
+    for layout in &layouts {
+        let bits = settings.bits_for(layout.name)?;
+
+        data.extend_from_slice(&(layout.name.len() as u32).to_le_bytes());
+        data.extend_from_slice(layout.name.as_bytes());
+        data.extend_from_slice(&(layout.words as u32).to_le_bytes());
+
+        for word in 0..layout.words {
+            let values = words
+                .iter()
+                .skip(layout.offset + word)
+                .step_by(stride)
+                .copied()
+                .collect::<Vec<_>>();
+
+            encode_component(&mut data, &values, bits, settings.level);
+        }
+    }
+
+    writer.write_all(&data)?;
+    Ok(())
+}
+
+/// reads planes written by `encode_planes`, the field layout must match `T` and the payload must
+/// hold `expected_count` gaussians
+pub fn decode_planes<T, R>(mut reader: R, expected_count: u64) -> Result<T, CloudCodecError>
+where
+    T: Planar,
+    T::PackedType: ReflectInterleaved + Pod,
+    R: Read,
+{
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let mut data = EntropyReader(data.as_slice());
+
+    let count = u64::from_le_bytes(data.array()?);
+    if count != expected_count {
+        return Err(CloudCodecError::Decode(format!(
+            "entropy payload holds {count} gaussians, expected {expected_count}"
+        )));
+    }
+    let count = usize::try_from(count)
+        .map_err(|_| CloudCodecError::Decode(format!("entropy count {count} is too large")))?;
+    let plane_count = u32::from_le_bytes(data.array()?) as usize;
+
+    let stride = size_of::<T::PackedType>() / 4;
+    let layouts = plane_layouts::<T::PackedType>();
+    if plane_count != layouts.len() {
+        return Err(CloudCodecError::Decode(format!(
+            "entropy payload holds {plane_count} planes, expected {}",
+            layouts.len()
+        )));
+    }
+
+    // every component stores at least its range and one lane header
+    let component_count = layouts.iter().map(|layout| layout.words).sum::<usize>();
+    if data.0.len() < component_count * MIN_COMPONENT_BYTES {
+        return Err(CloudCodecError::Decode(
+            "truncated entropy payload".to_owned(),
+        ));
+    }
+
+    let word_count = count
+        .checked_mul(stride)
+        .ok_or_else(|| CloudCodecError::Decode(format!("entropy count {count} is too large")))?;
+    let mut words = zeroed(word_count)?;
+    for layout in &layouts {
+        let name_len = u32::from_le_bytes(data.array()?) as usize;
+        let name = data.bytes(name_len)?.to_vec();
+        let plane_words = u32::from_le_bytes(data.array()?) as usize;
+        if name != layout.name.as_bytes() || plane_words != layout.words {
+            return Err(CloudCodecError::Decode(format!(
+                "entropy plane `{}` does not match the `{}` field",
+                String::from_utf8_lossy(&name),
+                layout.name,
+            )));
+        }
+
+        for word in 0..layout.words {
+            let values = decode_component(&mut data, count)?;
+            for (index, value) in values.into_iter().enumerate() {
+                words[index * stride + layout.offset + word] = value;
+            }
+        }
+    }
+
+    if !data.0.is_empty() {
+        return Err(CloudCodecError::Decode(format!(
+            "entropy payload has {} unexpected trailing bytes",
+            data.0.len()
+        )));
+    }
+
+    Ok(T::from_interleaved(bytemuck::pod_collect_to_vec(&words)))
+}
+
+// a constant lane rANS codes to a few bytes at any length, so the payload size cannot bound the
+// count, buffers are reserved fallibly instead
+fn zeroed<T: Clone + Default>(len: usize) -> Result<Vec<T>, CloudCodecError> {
+    let mut values = Vec::new();
+    values.try_reserve_exact(len).map_err(|_| {
+        CloudCodecError::Decode(format!(
+            "entropy payload of {len} values does not fit in memory"
+        ))
+    })?;
+    values.resize(len, T::default());
+    Ok(values)
+}
+
+fn mask(bits: u8) -> u32 {
+    u32::MAX >> (32 - bits as u32)
+}
+
+fn encode_component(data: &mut Vec<u8>, values: &[f32], bits: u8, level: u8) {
+    let (min, max) = if bits == 32 {
+        (0.0, 0.0)
+    } else {
+        values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            })
+    };
+    let (min, max) = if min <= max { (min, max) } else { (0.0, 0.0) };
+
+    let steps = mask(bits) as f64;
+    let extent = max as f64 - min as f64;
+    let symbols = values
+        .iter()
+        .map(|&value| {
+            if bits == 32 {
+                value.to_bits()
+            } else if extent > 0.0 && value.is_finite() {
+                ((value as f64 - min as f64) / extent * steps)
+                    .round()
+                    .clamp(0.0, steps) as u32
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let direct = encode_lanes(&symbols, bits, level);
+    let (transform, lanes) = if level >= 2 {
+        let delta = encode_lanes(&delta_encode(&symbols, bits), bits, level);
+        if delta.len() < direct.len() {
+            (TRANSFORM_DELTA, delta)
+        } else {
+            (TRANSFORM_DIRECT, direct)
+        }
+    } else {
+        (TRANSFORM_DIRECT, direct)
+    };
+
+    data.push(bits);
+    data.push(transform);
+    data.extend_from_slice(&min.to_le_bytes());
+    data.extend_from_slice(&max.to_le_bytes());
+    data.extend_from_slice(&lanes);
+}
+
+fn decode_component(data: &mut EntropyReader, count: usize) -> Result<Vec<f32>, CloudCodecError> {
+    let [bits, transform] = data.array()?;
+    if !(1..=32).contains(&bits) || transform > TRANSFORM_DELTA {
+        return Err(CloudCodecError::Decode(format!(
+            "invalid entropy component with {bits} bits and transform {transform}"
+        )));
+    }
+
+    let min = f32::from_le_bytes(data.array()?);
+    let max = f32::from_le_bytes(data.array()?);
+
+    let mut symbols = zeroed(count)?;
+    for lane in 0..(bits as usize).div_ceil(8) {
+        let [mode] = data.array()?;
+        let len = u32::from_le_bytes(data.array()?) as usize;
+        let bytes = data.bytes(len)?;
+
+        let bytes = match mode {
+            LANE_RAW if len == count => bytes.to_vec(),
+            LANE_RANS => rans::decode(bytes, count)?,
+            _ => {
+                return Err(CloudCodecError::Decode(format!(
+                    "invalid entropy lane mode {mode}"
+                )));
+            }
+        };
+
+        for (symbol, byte) in symbols.iter_mut().zip(bytes) {
+            *symbol |= (byte as u32) << (lane * 8);
+        }
+    }
+
+    if transform == TRANSFORM_DELTA {
+        symbols = delta_decode(&symbols, bits);
+    }
+
+    let steps = mask(bits) as f64;
+    let extent = max as f64 - min as f64;
+    Ok(symbols
+        .into_iter()
+        .map(|symbol| {
+            if bits == 32 {
+                f32::from_bits(symbol)
+            } else {
+                (min as f64 + symbol as f64 / steps * extent) as f32
+            }
+        })
+        .collect())
+}
+
+/// splits symbols into byte lanes, each stored raw or rANS coded, whichever is smaller
+fn encode_lanes(symbols: &[u32], bits: u8, level: u8) -> Vec<u8> {
+    let mut data = Vec::new();
+    for lane in 0..(bits as usize).div_ceil(8) {
+        let bytes = symbols
+            .iter()
+            .map(|symbol| (symbol >> (lane * 8)) as u8)
+            .collect::<Vec<_>>();
+
+        let coded = (level >= 1)
+            .then(|| rans::encode(&bytes))
+            .filter(|coded| coded.len() < bytes.len());
+        let (mode, bytes) = match coded {
+            Some(coded) => (LANE_RANS, coded),
+            None => (LANE_RAW, bytes),
+        };
+
+        data.push(mode);
+        data.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        data.extend_from_slice(&bytes);
+    }
+    data
+}
+
+// zigzag of the wrapping difference stays within `bits`
+fn delta_encode(symbols: &[u32], bits: u8) -> Vec<u32> {
+    let shift = 32 - bits as u32;
+    let mut previous = 0u32;
+    symbols
+        .iter()
+        .map(|&symbol| {
+            let delta = symbol.wrapping_sub(previous);
+            previous = symbol;
+
+            let signed = ((delta << shift) as i32) >> shift;
+            ((signed << 1) ^ (signed >> 31)) as u32 & mask(bits)
+        })
+        .collect()
+}
+
+fn delta_decode(symbols: &[u32], bits: u8) -> Vec<u32> {
+    let mut previous = 0u32;
+    symbols
+        .iter()
+        .map(|&zigzag| {
+            let delta = (zigzag >> 1) ^ 0u32.wrapping_sub(zigzag & 1);
+            previous = previous.wrapping_add(delta) & mask(bits);
+            previous
+        })
+        .collect()
+}
+
+struct EntropyReader<'a>(&'a [u8]);
+
+impl EntropyReader<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8], CloudCodecError> {
+        if self.0.len() < len {
+            return Err(CloudCodecError::Decode(
+                "truncated entropy payload".to_owned(),
+            ));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CloudCodecError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+}
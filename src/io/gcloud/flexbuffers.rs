@@ -1,37 +1,70 @@
 use flexbuffers::{FlexbufferSerializer, Reader};
-use serde::{Deserialize, Serialize};
+use serde::{Serialize, de::DeserializeOwned};
+use std::io::{Read, Write};
 
 use crate::{
-    gaussian::formats::{planar_3d::PlanarGaussian3d, planar_4d::PlanarGaussian4d},
-    io::codec::CloudCodec,
+    gaussian::formats::{
+        planar_3d::PlanarGaussian3d, planar_3d_codebook::PlanarGaussian3dCodebook,
+        planar_3d_lod::PlanarGaussian3dLod, planar_3d_quantized::PlanarGaussian3dQuantized,
+        planar_4d::PlanarGaussian4d, planar_4d_hierarchy::TemporalGaussianHierarchy,
+        planar_4d_quantized::PlanarGaussian4dQuantized, spacetime::PlanarGaussianSpacetime,
+    },
+    io::codec::{CloudCodec, CloudCodecError},
 };
 
-impl CloudCodec for PlanarGaussian3d {
-    fn encode(&self) -> Vec<u8> {
-        let mut serializer = FlexbufferSerializer::new();
-        self.serialize(&mut serializer)
-            .expect("failed to serialize cloud");
+macro_rules! impl_flexbuffers_codec {
+    ($($cloud:ty),* $(,)?) => {
+        $(
+            impl CloudCodec for $cloud {
+                fn encode_to<W: Write>(&self, writer: W) -> Result<(), CloudCodecError> {
+                    encode_flexbuffer(self, writer)
+                }
 
-        serializer.view().to_vec()
-    }
+                fn decode_from<R: Read>(reader: R) -> Result<Self, CloudCodecError> {
+                    decode_flexbuffer(reader)
+                }
+            }
+        )*
+    };
+}
+
+impl_flexbuffers_codec!(
+    PlanarGaussian3d,
+    PlanarGaussian3dQuantized,
+    PlanarGaussian3dCodebook,
+    PlanarGaussian3dLod,
+    PlanarGaussian4d,
+    PlanarGaussian4dQuantized,
+    PlanarGaussianSpacetime,
+    TemporalGaussianHierarchy,
+);
+
+fn encode_flexbuffer<T, W>(cloud: &T, mut writer: W) -> Result<(), CloudCodecError>
+where
+    T: Serialize,
+    W: Write,
+{
+    let mut serializer = FlexbufferSerializer::new();
+    cloud
+        .serialize(&mut serializer)
+        .map_err(|err| CloudCodecError::Encode(err.to_string()))?;
+
+    writer.write_all(serializer.view())?;
 
-    fn decode(data: &[u8]) -> Self {
-        let reader = Reader::get_root(data).expect("failed to read flexbuffer");
-        Self::deserialize(reader).expect("deserialization failed")
-    }
+    Ok(())
 }
 
-impl CloudCodec for PlanarGaussian4d {
-    fn encode(&self) -> Vec<u8> {
-        let mut serializer = FlexbufferSerializer::new();
-        self.serialize(&mut serializer)
-            .expect("failed to serialize cloud");
+// flexbuffers roots sit at the end of the buffer, the whole payload is read before decoding
+fn decode_flexbuffer<T, R>(mut reader: R) -> Result<T, CloudCodecError>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
 
-        serializer.view().to_vec()
-    }
+    let root = Reader::get_root(data.as_slice())
+        .map_err(|err| CloudCodecError::Decode(err.to_string()))?;
 
-    fn decode(data: &[u8]) -> Self {
-        let reader = Reader::get_root(data).expect("failed to read flexbuffer");
-        Self::deserialize(reader).expect("deserialization failed")
-    }
+    T::deserialize(root).map_err(|err| CloudCodecError::Decode(err.to_string()))
 }
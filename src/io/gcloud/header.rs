@@ -0,0 +1,607 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use bevy::math::{Vec3, bounding::Aabb3d};
+use bevy_interleave::prelude::Planar;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    gaussian::{
+        formats::{
+            planar_3d::PlanarGaussian3d, planar_3d_codebook::PlanarGaussian3dCodebook,
+            planar_3d_lod::PlanarGaussian3dLod, planar_3d_quantized::PlanarGaussian3dQuantized,
+            planar_4d::PlanarGaussian4d, planar_4d_hierarchy::TemporalGaussianHierarchy,
+            planar_4d_quantized::PlanarGaussian4dQuantized, spacetime::PlanarGaussianSpacetime,
+        },
+        interface::CommonCloud,
+        settings::GaussianMode,
+    },
+    io::{
+        codec::{CloudCodec, CloudCodecError},
+        gcloud::entropy::{self, GcloudEntropySettings},
+    },
+    material::spherical_harmonics::SH_DEGREE,
+};
+
+pub const GCLOUD_MAGIC: [u8; 4] = *b"GCLD";
+pub const GCLOUD_VERSION: u16 = 1;
+
+const FLAG_AABB: u8 = 1;
+const FLAG_QUANTIZED: u8 = 2;
+
+pub type GcloudMetadata = BTreeMap<String, String>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum GcloudCodecId {
+    Flexbuffers = 0,
+    Bincode2Gzip = 1,
+    /// per-plane quantized and rANS coded, see `entropy`
+    Entropy = 2,
+}
+
+impl GcloudCodecId {
+    /// codec compiled into this build, flexbuffers wins when both are enabled
+    pub const fn active() -> Self {
+        if cfg!(feature = "io_flexbuffers") {
+            Self::Flexbuffers
+        } else {
+            Self::Bincode2Gzip
+        }
+    }
+
+    /// payloads this build can decode, whichever codec it encodes with
+    pub const fn is_compiled(self) -> bool {
+        match self {
+            Self::Flexbuffers => cfg!(feature = "io_flexbuffers"),
+            Self::Bincode2Gzip => cfg!(feature = "io_bincode2"),
+            Self::Entropy => true,
+        }
+    }
+
+    /// payloads that carry their own coefficient counts, fixed layouts decode at `SH_DEGREE` only
+    pub const fn is_self_describing(self) -> bool {
+        matches!(self, Self::Flexbuffers)
+    }
+
+    fn from_u8(value: u8) -> Result<Self, CloudCodecError> {
+        match value {
+            0 => Ok(Self::Flexbuffers),
+            1 => Ok(Self::Bincode2Gzip),
+            2 => Ok(Self::Entropy),
+            _ => Err(CloudCodecError::Header(format!("unknown codec id {value}"))),
+        }
+    }
+}
+
+fn gaussian_mode_to_u8(mode: GaussianMode) -> u8 {
+    match mode {
+        GaussianMode::Gaussian2d => 0,
+        GaussianMode::Gaussian3d => 1,
+        GaussianMode::Gaussian4d => 2,
+    }
+}
+
+fn gaussian_mode_from_u8(value: u8) -> Result<GaussianMode, CloudCodecError> {
+    match value {
+        0 => Ok(GaussianMode::Gaussian2d),
+        1 => Ok(GaussianMode::Gaussian3d),
+        2 => Ok(GaussianMode::Gaussian4d),
+        _ => Err(CloudCodecError::Header(format!(
+            "unknown gaussian kind {value}"
+        ))),
+    }
+}
+
+/// container header written ahead of the codec payload
+///
+/// layout: magic, version (u16), header size (u32) then the fields below, all little endian
+#[derive(Clone, Debug, PartialEq)]
+pub struct GcloudHeader {
+    pub version: u16,
+    pub kind: GaussianMode,
+    pub sh_degree: u8,
+    pub codec: GcloudCodecId,
+    pub count: u64,
+    pub aabb: Option<Aabb3d>,
+    /// the payload holds the quantized variant of `kind`, see `GcloudContainer::QUANTIZED`
+    pub quantized: bool,
+    pub metadata: GcloudMetadata,
+}
+
+impl GcloudHeader {
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), CloudCodecError> {
+        let mut fields = Vec::new();
+        fields.push(gaussian_mode_to_u8(self.kind));
+        fields.push(self.sh_degree);
+        fields.push(self.codec as u8);
+        let mut flags = 0;
+        if self.aabb.is_some() {
+            flags |= FLAG_AABB;
+        }
+        if self.quantized {
+            flags |= FLAG_QUANTIZED;
+        }
+        fields.push(flags);
+        fields.extend_from_slice(&self.count.to_le_bytes());
+
+        let (min, max) = self
+            .aabb
+            .map(|aabb| (Vec3::from(aabb.min), Vec3::from(aabb.max)))
+            .unwrap_or_default();
+        for value in min.to_array().into_iter().chain(max.to_array()) {
+            fields.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fields.extend_from_slice(&(self.metadata.len() as u32).to_le_bytes());
+        for (key, value) in self.metadata.iter() {
+            for text in [key, value] {
+                fields.extend_from_slice(&(text.len() as u32).to_le_bytes());
+                fields.extend_from_slice(text.as_bytes());
+            }
+        }
+
+        writer.write_all(&GCLOUD_MAGIC)?;
+        writer.write_all(&self.version.to_le_bytes())?;
+        writer.write_all(&(fields.len() as u32).to_le_bytes())?;
+        writer.write_all(&fields)?;
+
+        Ok(())
+    }
+
+    /// expects the reader to start at the magic bytes
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, CloudCodecError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != GCLOUD_MAGIC {
+            return Err(CloudCodecError::Header("missing gcloud magic".to_owned()));
+        }
+
+        let mut version = [0; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version == 0 || version > GCLOUD_VERSION {
+            return Err(CloudCodecError::Header(format!(
+                "unsupported gcloud version {version}, expected at most {GCLOUD_VERSION}"
+            )));
+        }
+
+        let mut size = [0; 4];
+        reader.read_exact(&mut size)?;
+        let size = u32::from_le_bytes(size) as u64;
+
+        // newer minor revisions may append fields, anything past the known ones is skipped
+        let mut fields = Vec::new();
+        reader.take(size).read_to_end(&mut fields)?;
+        if fields.len() as u64 != size {
+            return Err(CloudCodecError::Header(
+                "truncated gcloud header".to_owned(),
+            ));
+        }
+
+        let mut fields = HeaderFields(fields.as_slice());
+
+        let kind = gaussian_mode_from_u8(fields.u8()?)?;
+        let sh_degree = fields.u8()?;
+        let codec = GcloudCodecId::from_u8(fields.u8()?)?;
+        let flags = fields.u8()?;
+        let count = u64::from_le_bytes(fields.array()?);
+
+        let mut corners = [0.0; 6];
+        for corner in corners.iter_mut() {
+            *corner = f32::from_le_bytes(fields.array()?);
+        }
+        let aabb = (flags & FLAG_AABB != 0).then(|| Aabb3d {
+            min: Vec3::new(corners[0], corners[1], corners[2]).into(),
+            max: Vec3::new(corners[3], corners[4], corners[5]).into(),
+        });
+
+        let entries = u32::from_le_bytes(fields.array()?);
+        let mut metadata = GcloudMetadata::new();
+        for _ in 0..entries {
+            let key = fields.string()?;
+            let value = fields.string()?;
+            metadata.insert(key, value);
+        }
+
+        Ok(Self {
+            version,
+            kind,
+            sh_degree,
+            codec,
+            count,
+            aabb,
+            quantized: flags & FLAG_QUANTIZED != 0,
+            metadata,
+        })
+    }
+
+    pub fn validate<T: GcloudContainer>(&self) -> Result<(), CloudCodecError> {
+        if self.kind != T::KIND {
+            return Err(CloudCodecError::Header(format!(
+                "gcloud holds {:?} gaussians, expected {:?}",
+                self.kind,
+                T::KIND,
+            )));
+        }
+
+        if !self.codec.is_compiled() {
+            return Err(CloudCodecError::Header(format!(
+                "gcloud payload uses the {:?} codec, which is not compiled into this build",
+                self.codec,
+            )));
+        }
+
+        let projects = T::SH_PROJECTION && self.codec.is_self_describing();
+        if self.sh_degree as usize != SH_DEGREE && !projects {
+            return Err(CloudCodecError::ShDegreeMismatch {
+                found: self.sh_degree as usize,
+                expected: SH_DEGREE,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+struct HeaderFields<'a>(&'a [u8]);
+
+impl HeaderFields<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8], CloudCodecError> {
+        if self.0.len() < len {
+            return Err(CloudCodecError::Header(
+                "truncated gcloud header".to_owned(),
+            ));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CloudCodecError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, CloudCodecError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn string(&mut self) -> Result<String, CloudCodecError> {
+        let len = u32::from_le_bytes(self.array()?) as usize;
+        String::from_utf8(self.bytes(len)?.to_vec())
+            .map_err(|err| CloudCodecError::Header(format!("invalid metadata string: {err}")))
+    }
+}
+
+/// a cloud stored as `[header][codec payload]`, headerless legacy payloads are still decoded
+pub trait GcloudContainer: CloudCodec + DeserializeOwned {
+    const KIND: GaussianMode;
+
+    /// harmonics stay valid when truncated or zero extended to another degree
+    const SH_PROJECTION: bool = false;
+
+    /// quantized variants share the container of their full precision kind, the header flag tells
+    /// the payloads apart
+    const QUANTIZED: bool = false;
+
+    fn gaussian_count(&self) -> usize;
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        None
+    }
+
+    fn project_sh_degree(&mut self, _source_degree: usize) {}
+
+    /// clouds made of f32 planes override this with `entropy::encode_planes`
+    fn encode_entropy_to<W: Write>(
+        &self,
+        _writer: W,
+        _settings: &GcloudEntropySettings,
+    ) -> Result<(), CloudCodecError> {
+        Err(CloudCodecError::Encode(format!(
+            "{} does not support the entropy codec",
+            std::any::type_name::<Self>(),
+        )))
+    }
+
+    /// `count` is the gaussian count promised by the header
+    fn decode_entropy_from<R: Read>(_reader: R, _count: u64) -> Result<Self, CloudCodecError> {
+        Err(CloudCodecError::Decode(format!(
+            "{} does not support the entropy codec",
+            std::any::type_name::<Self>(),
+        )))
+    }
+
+    /// decodes the payload of the other precision variant sharing the container
+    fn decode_variant_from<R: Read>(
+        _reader: R,
+        header: &GcloudHeader,
+    ) -> Result<Self, CloudCodecError> {
+        Err(CloudCodecError::Header(format!(
+            "{} cannot read a {} gcloud payload",
+            std::any::type_name::<Self>(),
+            if header.quantized {
+                "quantized"
+            } else {
+                "full precision"
+            },
+        )))
+    }
+
+    fn header(&self) -> GcloudHeader {
+        GcloudHeader {
+            version: GCLOUD_VERSION,
+            kind: Self::KIND,
+            sh_degree: SH_DEGREE as u8,
+            codec: GcloudCodecId::active(),
+            count: self.gaussian_count() as u64,
+            aabb: self.bounds(),
+            quantized: Self::QUANTIZED,
+            metadata: GcloudMetadata::new(),
+        }
+    }
+
+    fn encode_container<W: Write>(
+        &self,
+        mut writer: W,
+        metadata: &GcloudMetadata,
+    ) -> Result<(), CloudCodecError> {
+        let header = GcloudHeader {
+            metadata: metadata.clone(),
+            ..self.header()
+        };
+
+        header.write_to(&mut writer)?;
+        self.encode_to(writer)
+    }
+
+    fn encode_entropy_container<W: Write>(
+        &self,
+        mut writer: W,
+        metadata: &GcloudMetadata,
+        settings: &GcloudEntropySettings,
+    ) -> Result<(), CloudCodecError> {
+        let header = GcloudHeader {
+            codec: GcloudCodecId::Entropy,
+            metadata: metadata.clone(),
+            ..self.header()
+        };
+
+        header.write_to(&mut writer)?;
+        self.encode_entropy_to(writer, settings)
+    }
+
+    /// returns `None` for the header of legacy files
+    fn decode_container<R: Read>(
+        reader: R,
+    ) -> Result<(Option<GcloudHeader>, Self), CloudCodecError> {
+        let mut reader = BufReader::new(reader);
+        if !reader.fill_buf()?.starts_with(&GCLOUD_MAGIC) {
+            return Ok((None, Self::decode_from(reader)?));
+        }
+
+        let header = GcloudHeader::read_from(&mut reader)?;
+        header.validate::<Self>()?;
+
+        let mut cloud = if header.quantized == Self::QUANTIZED {
+            decode_payload(reader, &header)?
+        } else {
+            Self::decode_variant_from(reader, &header)?
+        };
+        if cloud.gaussian_count() as u64 != header.count {
+            return Err(CloudCodecError::Decode(format!(
+                "gcloud header promises {} gaussians, payload holds {}",
+                header.count,
+                cloud.gaussian_count(),
+            )));
+        }
+
+        if header.sh_degree as usize != SH_DEGREE {
+            cloud.project_sh_degree(header.sh_degree as usize);
+        }
+
+        Ok((Some(header), cloud))
+    }
+
+    fn write_container_file(
+        &self,
+        path: impl AsRef<Path>,
+        metadata: &GcloudMetadata,
+    ) -> Result<(), CloudCodecError> {
+        let gcloud_file = std::fs::File::create(path)?;
+        let mut gcloud_writer = BufWriter::new(gcloud_file);
+
+        self.encode_container(&mut gcloud_writer, metadata)?;
+        gcloud_writer.flush()?;
+
+        Ok(())
+    }
+}
+
+fn decode_payload<T: GcloudContainer, R: Read>(
+    reader: R,
+    header: &GcloudHeader,
+) -> Result<T, CloudCodecError> {
+    match header.codec {
+        GcloudCodecId::Entropy => T::decode_entropy_from(reader, header.count),
+        // flexbuffers owns `CloudCodec` when both codecs are compiled
+        #[cfg(all(feature = "io_bincode2", feature = "io_flexbuffers"))]
+        GcloudCodecId::Bincode2Gzip => super::bincode2::decode_gzip_or_raw(reader),
+        _ => T::decode_from(reader),
+    }
+}
+
+impl GcloudContainer for PlanarGaussian3d {
+    const KIND: GaussianMode = GaussianMode::Gaussian3d;
+    const SH_PROJECTION: bool = true;
+
+    fn gaussian_count(&self) -> usize {
+        self.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.compute_aabb()
+    }
+
+    fn project_sh_degree(&mut self, source_degree: usize) {
+        for harmonics in self.spherical_harmonic.iter_mut() {
+            harmonics.project_degree(source_degree);
+        }
+    }
+
+    fn encode_entropy_to<W: Write>(
+        &self,
+        writer: W,
+        settings: &GcloudEntropySettings,
+    ) -> Result<(), CloudCodecError> {
+        entropy::encode_planes(self, writer, settings)
+    }
+
+    fn decode_entropy_from<R: Read>(reader: R, count: u64) -> Result<Self, CloudCodecError> {
+        entropy::decode_planes(reader, count)
+    }
+}
+
+impl GcloudContainer for PlanarGaussian3dQuantized {
+    const KIND: GaussianMode = GaussianMode::Gaussian3d;
+
+    fn gaussian_count(&self) -> usize {
+        self.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.compute_aabb()
+    }
+}
+
+impl GcloudContainer for PlanarGaussian3dCodebook {
+    const KIND: GaussianMode = GaussianMode::Gaussian3d;
+
+    fn gaussian_count(&self) -> usize {
+        self.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.compute_aabb()
+    }
+}
+
+impl GcloudContainer for PlanarGaussian3dLod {
+    const KIND: GaussianMode = GaussianMode::Gaussian3d;
+    const SH_PROJECTION: bool = true;
+
+    fn gaussian_count(&self) -> usize {
+        self.gaussians.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.gaussians.compute_aabb()
+    }
+
+    fn project_sh_degree(&mut self, source_degree: usize) {
+        self.gaussians.project_sh_degree(source_degree);
+    }
+}
+
+// spherindrical harmonics interleave the time basis per degree, there is no cheap projection
+impl GcloudContainer for PlanarGaussian4d {
+    const KIND: GaussianMode = GaussianMode::Gaussian4d;
+
+    fn gaussian_count(&self) -> usize {
+        self.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.compute_aabb()
+    }
+
+    fn encode_entropy_to<W: Write>(
+        &self,
+        writer: W,
+        settings: &GcloudEntropySettings,
+    ) -> Result<(), CloudCodecError> {
+        entropy::encode_planes(self, writer, settings)
+    }
+
+    fn decode_entropy_from<R: Read>(reader: R, count: u64) -> Result<Self, CloudCodecError> {
+        entropy::decode_planes(reader, count)
+    }
+
+    fn decode_variant_from<R: Read>(
+        reader: R,
+        header: &GcloudHeader,
+    ) -> Result<Self, CloudCodecError> {
+        decode_payload::<PlanarGaussian4dQuantized, _>(reader, header)
+            .map(|cloud| Self::from(&cloud))
+    }
+}
+
+// `.gc4d` holds either precision, both loaders convert the other one on load
+impl GcloudContainer for PlanarGaussian4dQuantized {
+    const KIND: GaussianMode = GaussianMode::Gaussian4d;
+    const QUANTIZED: bool = true;
+
+    fn decode_variant_from<R: Read>(
+        reader: R,
+        header: &GcloudHeader,
+    ) -> Result<Self, CloudCodecError> {
+        decode_payload::<PlanarGaussian4d, _>(reader, header).map(|cloud| Self::from(&cloud))
+    }
+
+    fn gaussian_count(&self) -> usize {
+        self.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.compute_aabb()
+    }
+}
+
+impl GcloudContainer for PlanarGaussianSpacetime {
+    const KIND: GaussianMode = GaussianMode::Gaussian4d;
+    const SH_PROJECTION: bool = true;
+
+    fn gaussian_count(&self) -> usize {
+        self.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.compute_aabb()
+    }
+
+    fn project_sh_degree(&mut self, source_degree: usize) {
+        for harmonics in self.spherical_harmonic.iter_mut() {
+            harmonics.project_degree(source_degree);
+        }
+    }
+
+    fn encode_entropy_to<W: Write>(
+        &self,
+        writer: W,
+        settings: &GcloudEntropySettings,
+    ) -> Result<(), CloudCodecError> {
+        entropy::encode_planes(self, writer, settings)
+    }
+
+    fn decode_entropy_from<R: Read>(reader: R, count: u64) -> Result<Self, CloudCodecError> {
+        entropy::decode_planes(reader, count)
+    }
+}
+
+impl GcloudContainer for TemporalGaussianHierarchy {
+    const KIND: GaussianMode = GaussianMode::Gaussian4d;
+
+    fn gaussian_count(&self) -> usize {
+        self.flat_cloud.len()
+    }
+
+    fn bounds(&self) -> Option<Aabb3d> {
+        self.flat_cloud.compute_aabb()
+    }
+}
@@ -1,12 +1,17 @@
 use static_assertions::assert_cfg;
 
-// If both codecs are enabled, prefer flexbuffers.
-#[cfg(all(feature = "io_bincode2", not(feature = "io_flexbuffers")))]
+// If both codecs are enabled, prefer flexbuffers, bincode2 payloads are still decoded.
+#[cfg(feature = "io_bincode2")]
 pub mod bincode2;
 
 #[cfg(feature = "io_flexbuffers")]
 pub mod flexbuffers;
 
+pub mod entropy;
+pub mod header;
+pub mod plas;
+pub mod texture;
+
 assert_cfg!(
     any(feature = "io_bincode2", feature = "io_flexbuffers",),
     "no gcloud io enabled",
@@ -0,0 +1,393 @@
+// https://github.com/fraunhoferhhi/Self-Organizing-Gaussians
+// parallel linear assignment sorting arranges splats on the texture grid so neighbors look alike
+use bevy_interleave::prelude::Planar;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+#[cfg(feature = "sort_rayon")]
+use rayon::prelude::*;
+
+use crate::{gaussian::formats::planar_3d::PlanarGaussian3d, io::gcloud::texture::texture_size};
+
+const FEATURE_COUNT: usize = 14;
+
+type Feature = [f32; FEATURE_COUNT];
+
+/// sorts a 3d cloud into the row-major `texture_size` grid used by `GaussianTextures`
+#[derive(Clone, Debug)]
+pub struct PlasSorter {
+    pub position_weight: f32,
+    pub color_weight: f32,
+    pub scale_weight: f32,
+    pub rotation_weight: f32,
+    pub opacity_weight: f32,
+    /// factor applied to the blur radius after each pass, in (0, 1)
+    pub radius_decay: f32,
+    /// swap iterations per blur radius
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl Default for PlasSorter {
+    fn default() -> Self {
+        Self {
+            position_weight: 1.0,
+            color_weight: 1.0,
+            scale_weight: 1.0,
+            rotation_weight: 0.5,
+            opacity_weight: 0.5,
+            radius_decay: 0.8,
+            iterations: 4,
+            seed: 0,
+        }
+    }
+}
+
+impl PlasSorter {
+    pub fn position_weight(mut self, weight: f32) -> Self {
+        self.position_weight = weight;
+        self
+    }
+
+    pub fn color_weight(mut self, weight: f32) -> Self {
+        self.color_weight = weight;
+        self
+    }
+
+    pub fn scale_weight(mut self, weight: f32) -> Self {
+        self.scale_weight = weight;
+        self
+    }
+
+    pub fn rotation_weight(mut self, weight: f32) -> Self {
+        self.rotation_weight = weight;
+        self
+    }
+
+    pub fn opacity_weight(mut self, weight: f32) -> Self {
+        self.opacity_weight = weight;
+        self
+    }
+
+    pub fn radius_decay(mut self, radius_decay: f32) -> Self {
+        self.radius_decay = radius_decay.clamp(0.05, 0.95);
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn sort(&self, cloud: &PlanarGaussian3d) -> PlanarGaussian3d {
+        cloud.subset(&self.permutation(cloud))
+    }
+
+    /// source index of the splat placed at each grid cell
+    pub fn permutation(&self, cloud: &PlanarGaussian3d) -> Vec<usize> {
+        let count = cloud.len();
+        let mut grid = (0..count).collect::<Vec<_>>();
+        if count < 2 {
+            return grid;
+        }
+
+        let (width, height) = texture_size(count);
+        let (width, height) = (width as usize, height as usize);
+        let features = self.features(cloud);
+        let permutations = [permutations(2), permutations(3), permutations(4)];
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let decay = self.radius_decay.clamp(0.05, 0.95);
+        let mut radius = (width.max(height) / 2) as f32;
+        while radius >= 1.0 {
+            let cell_radius = radius as usize;
+            for _ in 0..self.iterations {
+                let target = blur(&grid, &features, width, height, cell_radius);
+                let groups = groups(width, height, count, cell_radius, &mut rng);
+
+                let solve = |cells: &Vec<usize>| {
+                    let cost =
+                        |from: usize, to: usize| distance(&features[grid[from]], &target[to]);
+                    permutations[cells.len() - 2]
+                        .iter()
+                        .min_by(|a, b| {
+                            let total = |permutation: &Vec<usize>| {
+                                permutation
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(from, &to)| cost(cells[from], cells[to]))
+                                    .sum::<f32>()
+                            };
+                            total(a).total_cmp(&total(b))
+                        })
+                        .cloned()
+                        .unwrap_or_default()
+                };
+
+                #[cfg(feature = "sort_rayon")]
+                let assignments = groups.par_iter().map(solve).collect::<Vec<_>>();
+                #[cfg(not(feature = "sort_rayon"))]
+                let assignments = groups.iter().map(solve).collect::<Vec<_>>();
+
+                for (cells, assignment) in groups.iter().zip(assignments) {
+                    let splats = cells.iter().map(|&cell| grid[cell]).collect::<Vec<_>>();
+                    for (from, to) in assignment.into_iter().enumerate() {
+                        grid[cells[to]] = splats[from];
+                    }
+                }
+            }
+
+            radius *= decay;
+        }
+
+        grid
+    }
+
+    /// mean squared feature distance between horizontally and vertically adjacent splats
+    pub fn neighbor_distance(&self, cloud: &PlanarGaussian3d) -> f32 {
+        let count = cloud.len();
+        let (width, _) = texture_size(count);
+        let width = width as usize;
+        let features = self.features(cloud);
+
+        let mut total = 0.0_f64;
+        let mut pairs = 0usize;
+        for cell in 0..count {
+            let right = (cell % width + 1 < width).then_some(cell + 1);
+            for neighbor in [right, Some(cell + width)].into_iter().flatten() {
+                if neighbor < count {
+                    total += distance(&features[cell], &features[neighbor]) as f64;
+                    pairs += 1;
+                }
+            }
+        }
+
+        if pairs == 0 {
+            0.0
+        } else {
+            (total / pairs as f64) as f32
+        }
+    }
+
+    /// weighted attributes normalized to the unit range of the cloud
+    fn features(&self, cloud: &PlanarGaussian3d) -> Vec<Feature> {
+        let mut features = cloud
+            .iter()
+            .map(|gaussian| {
+                let mut rotation = gaussian.rotation.rotation;
+                let norm = rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+                if norm > 0.0 {
+                    // q and -q are the same rotation
+                    let sign = if rotation[0] < 0.0 { -1.0 } else { 1.0 };
+                    rotation = rotation.map(|v| v * sign / norm);
+                }
+
+                let position = gaussian.position_visibility.position;
+                let scale = gaussian.scale_opacity.scale;
+                let color = &gaussian.spherical_harmonic.coefficients;
+                [
+                    position[0],
+                    position[1],
+                    position[2],
+                    color[0],
+                    color[1],
+                    color[2],
+                    scale[0].max(f32::MIN_POSITIVE).ln(),
+                    scale[1].max(f32::MIN_POSITIVE).ln(),
+                    scale[2].max(f32::MIN_POSITIVE).ln(),
+                    gaussian.scale_opacity.opacity,
+                    rotation[0],
+                    rotation[1],
+                    rotation[2],
+                    rotation[3],
+                ]
+            })
+            .collect::<Vec<Feature>>();
+
+        let weights: Feature = std::array::from_fn(|dimension| match dimension {
+            0..3 => self.position_weight,
+            3..6 => self.color_weight,
+            6..9 => self.scale_weight,
+            9 => self.opacity_weight,
+            _ => self.rotation_weight,
+        });
+
+        for (dimension, weight) in weights.into_iter().enumerate() {
+            let (min, max) = features
+                .iter()
+                .map(|feature| feature[dimension])
+                .filter(|value| value.is_finite())
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                    (min.min(value), max.max(value))
+                });
+            let extent = max - min;
+
+            for feature in &mut features {
+                let value = feature[dimension];
+                feature[dimension] = if extent > 0.0 && value.is_finite() {
+                    (value - min) / extent * weight
+                } else {
+                    0.0
+                };
+            }
+        }
+
+        features
+    }
+}
+
+fn distance(a: &Feature, b: &Feature) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+fn permutations(len: usize) -> Vec<Vec<usize>> {
+    if len == 1 {
+        return vec![vec![0]];
+    }
+
+    let mut result = Vec::new();
+    for permutation in permutations(len - 1) {
+        for slot in 0..len {
+            let mut permutation = permutation.clone();
+            permutation.insert(slot, len - 1);
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+/// box filtered features of the occupied cells within `radius`, the targets splats are pulled towards
+fn blur(
+    grid: &[usize],
+    features: &[Feature],
+    width: usize,
+    height: usize,
+    radius: usize,
+) -> Vec<Feature> {
+    // one weight channel after the features counts the occupied cells
+    const CHANNELS: usize = FEATURE_COUNT + 1;
+
+    let row_sums = |y: usize| {
+        let mut prefix = vec![[0.0_f64; CHANNELS]; width + 1];
+        for x in 0..width {
+            prefix[x + 1] = prefix[x];
+            if let Some(&splat) = grid.get(y * width + x) {
+                for (sum, &value) in prefix[x + 1].iter_mut().zip(&features[splat]) {
+                    *sum += value as f64;
+                }
+                prefix[x + 1][FEATURE_COUNT] += 1.0;
+            }
+        }
+
+        (0..width)
+            .map(|x| {
+                let (start, end) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+                std::array::from_fn::<_, CHANNELS, _>(|channel| {
+                    prefix[end][channel] - prefix[start][channel]
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    #[cfg(feature = "sort_rayon")]
+    let rows = (0..height)
+        .into_par_iter()
+        .map(row_sums)
+        .collect::<Vec<_>>();
+    #[cfg(not(feature = "sort_rayon"))]
+    let rows = (0..height).map(row_sums).collect::<Vec<_>>();
+
+    let mut target = vec![[0.0; FEATURE_COUNT]; grid.len()];
+    let mut window = vec![[0.0_f64; CHANNELS]; width];
+    for row in rows.iter().take(radius.min(height)) {
+        for (sum, values) in window.iter_mut().zip(row) {
+            sum.iter_mut()
+                .zip(values)
+                .for_each(|(sum, value)| *sum += value);
+        }
+    }
+
+    for y in 0..height {
+        if let Some(row) = rows.get(y + radius) {
+            for (sum, values) in window.iter_mut().zip(row) {
+                sum.iter_mut()
+                    .zip(values)
+                    .for_each(|(sum, value)| *sum += value);
+            }
+        }
+        if y > radius {
+            for (sum, values) in window.iter_mut().zip(&rows[y - radius - 1]) {
+                sum.iter_mut()
+                    .zip(values)
+                    .for_each(|(sum, value)| *sum -= value);
+            }
+        }
+
+        for (x, sum) in window.iter().enumerate() {
+            let Some(feature) = target.get_mut(y * width + x) else {
+                break;
+            };
+            let weight = sum[FEATURE_COUNT].max(1.0);
+            for (value, &sum) in feature.iter_mut().zip(sum.iter()) {
+                *value = (sum / weight) as f32;
+            }
+        }
+    }
+
+    target
+}
+
+/// groups of up to four occupied cells, one from each quadrant of randomly offset `2 * radius` tiles
+fn groups(
+    width: usize,
+    height: usize,
+    count: usize,
+    radius: usize,
+    rng: &mut StdRng,
+) -> Vec<Vec<usize>> {
+    let tile = radius * 2;
+    let offset_x = rng.random_range(0..tile);
+    let offset_y = rng.random_range(0..tile);
+
+    let mut groups = Vec::new();
+    for tile_y in (0..height + offset_y).step_by(tile) {
+        for tile_x in (0..width + offset_x).step_by(tile) {
+            let mut quadrants = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(quadrant_x, quadrant_y)| {
+                let mut cells = Vec::with_capacity(radius * radius);
+                for j in 0..radius {
+                    for i in 0..radius {
+                        let x = (tile_x + quadrant_x * radius + i).checked_sub(offset_x);
+                        let y = (tile_y + quadrant_y * radius + j).checked_sub(offset_y);
+                        if let (Some(x), Some(y)) = (x, y)
+                            && x < width
+                            && y < height
+                            && y * width + x < count
+                        {
+                            cells.push(y * width + x);
+                        }
+                    }
+                }
+                cells
+            });
+            for cells in &mut quadrants {
+                cells.shuffle(rng);
+            }
+
+            let len = quadrants.iter().map(Vec::len).max().unwrap_or(0);
+            for index in 0..len {
+                let group = quadrants
+                    .iter()
+                    .filter_map(|cells| cells.get(index).copied())
+                    .collect::<Vec<_>>();
+                if group.len() >= 2 {
+                    groups.push(group);
+                }
+            }
+        }
+    }
+
+    groups
+}
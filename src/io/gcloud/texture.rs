@@ -1 +1,807 @@
-// TODO: convert gcloud to compressed texture format
+// 8-bit attribute textures in the spirit of https://github.com/playcanvas/sogs
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+use bevy::{
+    asset::{Asset, Handle, RenderAssetUsages},
+    image::Image,
+    reflect::TypePath,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_interleave::prelude::Planar;
+
+use crate::{
+    gaussian::{
+        f32::{PositionVisibility, Rotation, ScaleOpacity},
+        formats::{
+            planar_3d::{Gaussian3d, PlanarGaussian3d},
+            planar_3d_codebook::{SH_CODEBOOK_REST_COUNT, ShCodebookBuilder},
+        },
+    },
+    io::codec::CloudCodecError,
+    material::spherical_harmonics::{
+        SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL, SphericalHarmonicCoefficients,
+    },
+};
+
+pub const GAUSSIAN_TEXTURE_MAGIC: [u8; 4] = *b"GTEX";
+pub const GAUSSIAN_TEXTURE_VERSION: u16 = 1;
+
+/// texture sizes are padded to whole 4x4 blocks so BC7, ETC2 and ASTC transcoders accept them
+pub const GAUSSIAN_TEXTURE_BLOCK_SIZE: u32 = 4;
+
+/// asset label of the `GaussianTextureImages` of a loaded `.gtex` cloud
+pub const GAUSSIAN_TEXTURE_IMAGES_LABEL: &str = "textures";
+
+/// codebook entries per row of the `sh_centroids` texture
+pub const SH_CENTROIDS_PER_ROW: usize = 64;
+
+const SH_REST_PER_CHANNEL: usize = SH_COEFF_COUNT_PER_CHANNEL - 1;
+
+/// storage of each plane in the `.gtex` container
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum GaussianTextureEncoding {
+    #[default]
+    Raw = 0,
+    /// lossless png, compresses best once neighboring texels are alike, see `PlasSorter`
+    Png = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GaussianTexturePlane {
+    /// low bytes of the 16-bit log-space position, rgb
+    MeansLow,
+    /// high bytes of the 16-bit log-space position, rgb
+    MeansHigh,
+    /// smallest-three quaternion in rgb, 252 + the largest component index in a
+    Quats,
+    /// log scale, rgb
+    Scales,
+    /// DC color in rgb, opacity in a
+    Sh0,
+    /// u16 codebook index, r | g << 8
+    ShLabels,
+    /// rest-band codebook, one texel per coefficient with the channels in rgb
+    ShCentroids,
+}
+
+impl GaussianTexturePlane {
+    pub const ALL: [Self; 7] = [
+        Self::MeansLow,
+        Self::MeansHigh,
+        Self::Quats,
+        Self::Scales,
+        Self::Sh0,
+        Self::ShLabels,
+        Self::ShCentroids,
+    ];
+
+    /// also the asset label of the plane image
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::MeansLow => "means_low",
+            Self::MeansHigh => "means_high",
+            Self::Quats => "quats",
+            Self::Scales => "scales",
+            Self::Sh0 => "sh0",
+            Self::ShLabels => "sh_labels",
+            Self::ShCentroids => "sh_centroids",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|plane| plane.name() == name)
+    }
+}
+
+/// tightly packed rgba8 texels
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GaussianTexture {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl GaussianTexture {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn texel(&self, index: usize) -> [u8; 4] {
+        self.data[index * 4..index * 4 + 4].try_into().unwrap()
+    }
+
+    pub fn set_texel(&mut self, index: usize, texel: [u8; 4]) {
+        self.data[index * 4..index * 4 + 4].copy_from_slice(&texel);
+    }
+
+    #[cfg(feature = "io_png")]
+    fn encode_png(&self) -> Result<Vec<u8>, CloudCodecError> {
+        let mut bytes = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::High);
+
+        let mut writer = encoder.write_header().map_err(std::io::Error::from)?;
+        writer
+            .write_image_data(&self.data)
+            .map_err(std::io::Error::from)?;
+        writer.finish().map_err(std::io::Error::from)?;
+
+        Ok(bytes)
+    }
+
+    #[cfg(not(feature = "io_png"))]
+    fn encode_png(&self) -> Result<Vec<u8>, CloudCodecError> {
+        Err(CloudCodecError::Encode(
+            "png support not enabled, enable with io_png feature".to_owned(),
+        ))
+    }
+
+    #[cfg(feature = "io_png")]
+    fn decode_png(bytes: &[u8], width: u32, height: u32) -> Result<Self, CloudCodecError> {
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let mut reader = decoder.read_info().map_err(std::io::Error::from)?;
+
+        let info = reader.info();
+        if info.size() != (width, height)
+            || info.color_type != png::ColorType::Rgba
+            || info.bit_depth != png::BitDepth::Eight
+        {
+            return Err(CloudCodecError::Decode(format!(
+                "png texture must be a {width}x{height} rgba8 image"
+            )));
+        }
+
+        let mut texture = Self::new(width, height);
+        reader
+            .next_frame(&mut texture.data)
+            .map_err(std::io::Error::from)?;
+
+        Ok(texture)
+    }
+
+    #[cfg(not(feature = "io_png"))]
+    fn decode_png(_bytes: &[u8], _width: u32, _height: u32) -> Result<Self, CloudCodecError> {
+        Err(CloudCodecError::Decode(
+            "png support not enabled, enable with io_png feature".to_owned(),
+        ))
+    }
+
+    /// linear `Rgba8Unorm` image, the values are quantized codes rather than colors
+    pub fn to_image(&self) -> Image {
+        Image::new(
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.data.clone(),
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+/// dequantization ranges shared by every texel of a plane
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GaussianTextureRanges {
+    /// bounds of `sign(x) * ln(|x| + 1)`
+    pub means_min: [f32; 3],
+    pub means_max: [f32; 3],
+    /// bounds of `ln(scale)`
+    pub scales_min: [f32; 3],
+    pub scales_max: [f32; 3],
+    pub sh0_min: [f32; 3],
+    pub sh0_max: [f32; 3],
+    pub sh_rest_min: f32,
+    pub sh_rest_max: f32,
+}
+
+/// plane images of a loaded `.gtex` cloud, sampled by the `buffer_texture` render path
+#[derive(Asset, TypePath, Clone, Debug, Default)]
+pub struct GaussianTextureImages {
+    pub count: usize,
+    pub ranges: GaussianTextureRanges,
+    pub planes: BTreeMap<GaussianTexturePlane, Handle<Image>>,
+}
+
+/// attribute planes of a 3d cloud laid out as 2d images, splat `i` lives at texel `i` in row-major order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GaussianTextures {
+    pub count: usize,
+    pub ranges: GaussianTextureRanges,
+    pub means_low: GaussianTexture,
+    pub means_high: GaussianTexture,
+    pub quats: GaussianTexture,
+    pub scales: GaussianTexture,
+    pub sh0: GaussianTexture,
+    /// absent when the build has no rest-band coefficients
+    pub sh_labels: Option<GaussianTexture>,
+    pub sh_centroids: Option<GaussianTexture>,
+}
+
+/// width and height of the attribute textures for `count` splats
+pub fn texture_size(count: usize) -> (u32, u32) {
+    let block = GAUSSIAN_TEXTURE_BLOCK_SIZE as usize;
+    let width = ((count as f64).sqrt().ceil() as usize)
+        .max(1)
+        .next_multiple_of(block);
+    let height = count.div_ceil(width).max(1).next_multiple_of(block);
+
+    (width as u32, height as u32)
+}
+
+impl GaussianTextures {
+    pub fn plane(&self, plane: GaussianTexturePlane) -> Option<&GaussianTexture> {
+        match plane {
+            GaussianTexturePlane::MeansLow => Some(&self.means_low),
+            GaussianTexturePlane::MeansHigh => Some(&self.means_high),
+            GaussianTexturePlane::Quats => Some(&self.quats),
+            GaussianTexturePlane::Scales => Some(&self.scales),
+            GaussianTexturePlane::Sh0 => Some(&self.sh0),
+            GaussianTexturePlane::ShLabels => self.sh_labels.as_ref(),
+            GaussianTexturePlane::ShCentroids => self.sh_centroids.as_ref(),
+        }
+    }
+
+    pub fn planes(&self) -> impl Iterator<Item = (GaussianTexturePlane, &GaussianTexture)> {
+        GaussianTexturePlane::ALL
+            .into_iter()
+            .filter_map(|plane| self.plane(plane).map(|texture| (plane, texture)))
+    }
+
+    /// quantizes `cloud`, the rest-band coefficients are clustered with `codebook`
+    pub fn encode(cloud: &PlanarGaussian3d, codebook: &ShCodebookBuilder) -> Self {
+        let count = cloud.len();
+        let (width, height) = texture_size(count);
+
+        let means = cloud
+            .position_visibility
+            .iter()
+            .map(|position_visibility| position_visibility.position.map(log_transform))
+            .collect::<Vec<_>>();
+        let scales = cloud
+            .scale_opacity
+            .iter()
+            .map(|scale_opacity| {
+                scale_opacity
+                    .scale
+                    .map(|scale| scale.max(f32::MIN_POSITIVE).ln())
+            })
+            .collect::<Vec<_>>();
+        let sh0 = cloud
+            .spherical_harmonic
+            .iter()
+            .map(|spherical_harmonic| {
+                std::array::from_fn::<_, SH_CHANNELS, _>(|channel| {
+                    spherical_harmonic.coefficients[channel]
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let (means_min, means_max) = bounds(&means);
+        let (scales_min, scales_max) = bounds(&scales);
+        let (sh0_min, sh0_max) = bounds(&sh0);
+
+        let mut textures = Self {
+            count,
+            ranges: GaussianTextureRanges {
+                means_min,
+                means_max,
+                scales_min,
+                scales_max,
+                sh0_min,
+                sh0_max,
+                ..Default::default()
+            },
+            means_low: GaussianTexture::new(width, height),
+            means_high: GaussianTexture::new(width, height),
+            quats: GaussianTexture::new(width, height),
+            scales: GaussianTexture::new(width, height),
+            sh0: GaussianTexture::new(width, height),
+            sh_labels: None,
+            sh_centroids: None,
+        };
+
+        for index in 0..count {
+            let mean: [u32; 3] = std::array::from_fn(|axis| {
+                quantize(means[index][axis], means_min[axis], means_max[axis], 0xffff)
+            });
+            textures
+                .means_low
+                .set_texel(index, [mean[0] as u8, mean[1] as u8, mean[2] as u8, 0xff]);
+            textures.means_high.set_texel(
+                index,
+                [
+                    (mean[0] >> 8) as u8,
+                    (mean[1] >> 8) as u8,
+                    (mean[2] >> 8) as u8,
+                    0xff,
+                ],
+            );
+
+            textures
+                .quats
+                .set_texel(index, encode_rotation(&cloud.rotation[index]));
+
+            let scale: [u32; 3] = std::array::from_fn(|axis| {
+                quantize(
+                    scales[index][axis],
+                    scales_min[axis],
+                    scales_max[axis],
+                    0xff,
+                )
+            });
+            textures.scales.set_texel(
+                index,
+                [scale[0] as u8, scale[1] as u8, scale[2] as u8, 0xff],
+            );
+
+            let color: [u32; 3] = std::array::from_fn(|channel| {
+                quantize(
+                    sh0[index][channel],
+                    sh0_min[channel],
+                    sh0_max[channel],
+                    0xff,
+                )
+            });
+            let opacity = quantize(cloud.scale_opacity[index].opacity, 0.0, 1.0, 0xff);
+            textures.sh0.set_texel(
+                index,
+                [
+                    color[0] as u8,
+                    color[1] as u8,
+                    color[2] as u8,
+                    opacity as u8,
+                ],
+            );
+        }
+
+        if SH_CODEBOOK_REST_COUNT != 0 {
+            let clustered = codebook.build(cloud);
+
+            let mut labels = GaussianTexture::new(width, height);
+            for (index, spherical_harmonic) in clustered.spherical_harmonic.iter().enumerate() {
+                let label = spherical_harmonic.index();
+                labels.set_texel(index, [label as u8, (label >> 8) as u8, 0, 0xff]);
+            }
+
+            let rest = clustered
+                .codebook
+                .iter()
+                .flat_map(|entry| {
+                    entry.coefficients[SH_CHANNELS..][..SH_CODEBOOK_REST_COUNT].iter()
+                })
+                .copied();
+            let (rest_min, rest_max) = rest.fold((0.0_f32, 0.0_f32), |(min, max), value| {
+                if value.is_finite() {
+                    (min.min(value), max.max(value))
+                } else {
+                    (min, max)
+                }
+            });
+            textures.ranges.sh_rest_min = rest_min;
+            textures.ranges.sh_rest_max = rest_max;
+
+            let (centroid_width, centroid_height) = centroid_size(clustered.codebook.len());
+            let mut centroids = GaussianTexture::new(centroid_width, centroid_height);
+            for (entry_index, entry) in clustered.codebook.iter().enumerate() {
+                let rest = entry.coefficients[SH_CHANNELS..]
+                    .chunks_exact(SH_CHANNELS)
+                    .take(SH_REST_PER_CHANNEL);
+                for (coefficient, values) in rest.enumerate() {
+                    let [r, g, b] = std::array::from_fn(|channel| {
+                        quantize(values[channel], rest_min, rest_max, 0xff) as u8
+                    });
+                    centroids.set_texel(
+                        centroid_texel(entry_index, coefficient, centroid_width),
+                        [r, g, b, 0xff],
+                    );
+                }
+            }
+
+            textures.sh_labels = Some(labels);
+            textures.sh_centroids = Some(centroids);
+        }
+
+        textures
+    }
+
+    /// dequantizes every splat, positions round trip with 16 bits and everything else with 8
+    pub fn decode(&self) -> Result<PlanarGaussian3d, CloudCodecError> {
+        self.validate()?;
+
+        let ranges = &self.ranges;
+        let mut gaussians = Vec::with_capacity(self.count);
+        for index in 0..self.count {
+            let low = self.means_low.texel(index);
+            let high = self.means_high.texel(index);
+            let position = std::array::from_fn(|axis| {
+                let mean = low[axis] as u32 | ((high[axis] as u32) << 8);
+                inverse_log_transform(dequantize(
+                    mean,
+                    ranges.means_min[axis],
+                    ranges.means_max[axis],
+                    0xffff,
+                ))
+            });
+
+            let scale_texel = self.scales.texel(index);
+            let scale = std::array::from_fn(|axis| {
+                dequantize(
+                    scale_texel[axis] as u32,
+                    ranges.scales_min[axis],
+                    ranges.scales_max[axis],
+                    0xff,
+                )
+                .exp()
+            });
+
+            let sh0 = self.sh0.texel(index);
+            let mut spherical_harmonic = SphericalHarmonicCoefficients::default();
+            for (channel, value) in spherical_harmonic.coefficients[..SH_CHANNELS]
+                .iter_mut()
+                .enumerate()
+            {
+                *value = dequantize(
+                    sh0[channel] as u32,
+                    ranges.sh0_min[channel],
+                    ranges.sh0_max[channel],
+                    0xff,
+                );
+            }
+
+            if let (Some(labels), Some(centroids)) = (&self.sh_labels, &self.sh_centroids) {
+                let [low, high, ..] = labels.texel(index);
+                let label = low as usize | ((high as usize) << 8);
+                let rest = spherical_harmonic.coefficients[SH_CHANNELS..]
+                    .chunks_exact_mut(SH_CHANNELS)
+                    .take(SH_REST_PER_CHANNEL);
+                for (coefficient, values) in rest.enumerate() {
+                    let texel =
+                        centroids.texel(centroid_texel(label, coefficient, centroids.width));
+                    for (value, &code) in values.iter_mut().zip(&texel) {
+                        *value =
+                            dequantize(code as u32, ranges.sh_rest_min, ranges.sh_rest_max, 0xff);
+                    }
+                }
+            }
+
+            gaussians.push(Gaussian3d {
+                position_visibility: PositionVisibility {
+                    position,
+                    visibility: 1.0,
+                },
+                spherical_harmonic,
+                rotation: decode_rotation(self.quats.texel(index)),
+                scale_opacity: ScaleOpacity {
+                    scale,
+                    opacity: sh0[3] as f32 / 255.0,
+                },
+            });
+        }
+
+        Ok(PlanarGaussian3d::from_interleaved(gaussians))
+    }
+
+    fn validate(&self) -> Result<(), CloudCodecError> {
+        let (width, height) = texture_size(self.count);
+        for (plane, texture) in self.planes() {
+            let texels = texture.width as usize * texture.height as usize;
+            let attribute = plane != GaussianTexturePlane::ShCentroids;
+            if texture.data.len() != texels * 4
+                || (attribute && (texture.width, texture.height) != (width, height))
+            {
+                return Err(CloudCodecError::Decode(format!(
+                    "texture `{}` is {}x{} with {} bytes, expected {width}x{height} rgba8",
+                    plane.name(),
+                    texture.width,
+                    texture.height,
+                    texture.data.len(),
+                )));
+            }
+        }
+
+        if self.sh_labels.is_some() != self.sh_centroids.is_some() {
+            return Err(CloudCodecError::Decode(
+                "`sh_labels` and `sh_centroids` must be stored together".to_owned(),
+            ));
+        }
+
+        if let (Some(labels), Some(centroids)) = (&self.sh_labels, &self.sh_centroids) {
+            let entries = (centroids.width as usize)
+                .checked_div(SH_REST_PER_CHANNEL)
+                .map_or(0, |columns| columns * centroids.height as usize);
+            let out_of_range = (0..self.count).any(|index| {
+                let [low, high, ..] = labels.texel(index);
+                (low as usize | ((high as usize) << 8)) >= entries
+            });
+            if SH_REST_PER_CHANNEL == 0 || out_of_range {
+                return Err(CloudCodecError::Decode(
+                    "`sh_labels` references a missing codebook entry".to_owned(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), CloudCodecError> {
+        self.write_encoded_to(writer, GaussianTextureEncoding::Raw)
+    }
+
+    pub fn write_encoded_to<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: GaussianTextureEncoding,
+    ) -> Result<(), CloudCodecError> {
+        let ranges = &self.ranges;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&GAUSSIAN_TEXTURE_MAGIC);
+        data.extend_from_slice(&GAUSSIAN_TEXTURE_VERSION.to_le_bytes());
+        data.extend_from_slice(&(self.count as u64).to_le_bytes());
+        for value in [
+            ranges.means_min,
+            ranges.means_max,
+            ranges.scales_min,
+            ranges.scales_max,
+            ranges.sh0_min,
+            ranges.sh0_max,
+        ]
+        .into_iter()
+        .flatten()
+        .chain([ranges.sh_rest_min, ranges.sh_rest_max])
+        {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        data.extend_from_slice(&(self.planes().count() as u32).to_le_bytes());
+        for (plane, texture) in self.planes() {
+            data.extend_from_slice(&(plane.name().len() as u32).to_le_bytes());
+            data.extend_from_slice(plane.name().as_bytes());
+            data.extend_from_slice(&texture.width.to_le_bytes());
+            data.extend_from_slice(&texture.height.to_le_bytes());
+            data.push(encoding as u8);
+
+            let bytes = match encoding {
+                GaussianTextureEncoding::Raw => texture.data.clone(),
+                GaussianTextureEncoding::Png => texture.encode_png()?,
+            };
+            data.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            data.extend_from_slice(&bytes);
+        }
+
+        writer.write_all(&data)?;
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, CloudCodecError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let mut data = TextureReader(data.as_slice());
+
+        if data.array()? != GAUSSIAN_TEXTURE_MAGIC {
+            return Err(CloudCodecError::Header(
+                "missing gaussian texture magic".to_owned(),
+            ));
+        }
+        let version = u16::from_le_bytes(data.array()?);
+        if version != GAUSSIAN_TEXTURE_VERSION {
+            return Err(CloudCodecError::Header(format!(
+                "unsupported gaussian texture version {version}"
+            )));
+        }
+
+        let count = u64::from_le_bytes(data.array()?) as usize;
+        let mut floats = [0.0_f32; 20];
+        for value in &mut floats {
+            *value = f32::from_le_bytes(data.array()?);
+        }
+        let vector = |offset: usize| -> [f32; 3] { floats[offset..offset + 3].try_into().unwrap() };
+        let ranges = GaussianTextureRanges {
+            means_min: vector(0),
+            means_max: vector(3),
+            scales_min: vector(6),
+            scales_max: vector(9),
+            sh0_min: vector(12),
+            sh0_max: vector(15),
+            sh_rest_min: floats[18],
+            sh_rest_max: floats[19],
+        };
+
+        let mut textures = Self {
+            count,
+            ranges,
+            ..Default::default()
+        };
+        let mut found = Vec::new();
+
+        let plane_count = u32::from_le_bytes(data.array()?);
+        for _ in 0..plane_count {
+            let name_len = u32::from_le_bytes(data.array()?) as usize;
+            let name = String::from_utf8_lossy(data.bytes(name_len)?).into_owned();
+            let plane = GaussianTexturePlane::from_name(&name).ok_or_else(|| {
+                CloudCodecError::Decode(format!("unknown gaussian texture `{name}`"))
+            })?;
+            if found.contains(&plane) {
+                return Err(CloudCodecError::Decode(format!(
+                    "gaussian texture `{name}` is stored twice"
+                )));
+            }
+            found.push(plane);
+
+            let width = u32::from_le_bytes(data.array()?);
+            let height = u32::from_le_bytes(data.array()?);
+            let [encoding] = data.array()?;
+            let len = u64::from_le_bytes(data.array()?) as usize;
+            let bytes = data.bytes(len)?;
+
+            let texture = match encoding {
+                0 => GaussianTexture {
+                    width,
+                    height,
+                    data: bytes.to_vec(),
+                },
+                1 => GaussianTexture::decode_png(bytes, width, height)?,
+                _ => {
+                    return Err(CloudCodecError::Decode(format!(
+                        "unknown encoding {encoding} of gaussian texture `{name}`"
+                    )));
+                }
+            };
+
+            match plane {
+                GaussianTexturePlane::MeansLow => textures.means_low = texture,
+                GaussianTexturePlane::MeansHigh => textures.means_high = texture,
+                GaussianTexturePlane::Quats => textures.quats = texture,
+                GaussianTexturePlane::Scales => textures.scales = texture,
+                GaussianTexturePlane::Sh0 => textures.sh0 = texture,
+                GaussianTexturePlane::ShLabels => textures.sh_labels = Some(texture),
+                GaussianTexturePlane::ShCentroids => textures.sh_centroids = Some(texture),
+            }
+        }
+
+        if !data.0.is_empty() {
+            return Err(CloudCodecError::Decode(format!(
+                "gaussian textures have {} unexpected trailing bytes",
+                data.0.len()
+            )));
+        }
+
+        textures.validate()?;
+        Ok(textures)
+    }
+}
+
+fn log_transform(value: f32) -> f32 {
+    value.signum() * value.abs().ln_1p()
+}
+
+fn inverse_log_transform(value: f32) -> f32 {
+    value.signum() * value.abs().exp_m1()
+}
+
+fn bounds(values: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for value in values {
+        for axis in 0..3 {
+            if value[axis].is_finite() {
+                min[axis] = min[axis].min(value[axis]);
+                max[axis] = max[axis].max(value[axis]);
+            }
+        }
+    }
+
+    for axis in 0..3 {
+        if min[axis] > max[axis] {
+            (min[axis], max[axis]) = (0.0, 0.0);
+        }
+    }
+
+    (min, max)
+}
+
+fn quantize(value: f32, min: f32, max: f32, steps: u32) -> u32 {
+    let extent = max - min;
+    if extent > 0.0 && value.is_finite() {
+        ((value - min) / extent * steps as f32)
+            .round()
+            .clamp(0.0, steps as f32) as u32
+    } else {
+        0
+    }
+}
+
+fn dequantize(value: u32, min: f32, max: f32, steps: u32) -> f32 {
+    min + (max - min) * value as f32 / steps as f32
+}
+
+fn centroid_size(entries: usize) -> (u32, u32) {
+    let block = GAUSSIAN_TEXTURE_BLOCK_SIZE as usize;
+    let width = SH_CENTROIDS_PER_ROW * SH_REST_PER_CHANNEL;
+    let height = entries
+        .div_ceil(SH_CENTROIDS_PER_ROW)
+        .max(1)
+        .next_multiple_of(block);
+
+    (width as u32, height as u32)
+}
+
+fn centroid_texel(entry: usize, coefficient: usize, width: u32) -> usize {
+    let per_row = width as usize / SH_REST_PER_CHANNEL;
+    let (row, column) = (entry / per_row, entry % per_row);
+
+    row * width as usize + column * SH_REST_PER_CHANNEL + coefficient
+}
+
+fn encode_rotation(rotation: &Rotation) -> [u8; 4] {
+    let norm = rotation.rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let q = if norm > 0.0 && norm.is_finite() {
+        rotation.rotation.map(|v| v / norm)
+    } else {
+        [1.0, 0.0, 0.0, 0.0]
+    };
+
+    let largest = (0..4)
+        .max_by(|&a, &b| q[a].abs().total_cmp(&q[b].abs()))
+        .unwrap();
+    let sign = if q[largest] < 0.0 { -1.0 } else { 1.0 };
+
+    let mut texel = [0, 0, 0, 252 + largest as u8];
+    for (slot, component) in (0..4).filter(|&i| i != largest).enumerate() {
+        let normalized = (q[component] * sign * std::f32::consts::SQRT_2 + 1.0) * 0.5;
+        texel[slot] = quantize(normalized, 0.0, 1.0, 0xff) as u8;
+    }
+
+    texel
+}
+
+fn decode_rotation(texel: [u8; 4]) -> Rotation {
+    let largest = texel[3].saturating_sub(252).min(3) as usize;
+
+    let mut q = [0.0; 4];
+    let mut sum_squares = 0.0;
+    for (slot, component) in (0..4).filter(|&i| i != largest).enumerate() {
+        let value = (texel[slot] as f32 / 255.0 * 2.0 - 1.0) * std::f32::consts::FRAC_1_SQRT_2;
+        q[component] = value;
+        sum_squares += value * value;
+    }
+    q[largest] = (1.0 - sum_squares).max(0.0).sqrt();
+
+    Rotation { rotation: q }
+}
+
+struct TextureReader<'a>(&'a [u8]);
+
+impl TextureReader<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8], CloudCodecError> {
+        if self.0.len() < len {
+            return Err(CloudCodecError::Decode(
+                "truncated gaussian textures".to_owned(),
+            ));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CloudCodecError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+}
@@ -0,0 +1,407 @@
+// https://github.com/mkkellogg/GaussianSplats3D
+use std::io::Read;
+
+use bevy_interleave::prelude::Planar;
+use half::f16;
+
+use crate::{
+    gaussian::formats::planar_3d::{Gaussian3d, PlanarGaussian3d},
+    io::splat::{color_to_sh_dc, normalize_rotation},
+    material::spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT, SH_COEFF_COUNT_PER_CHANNEL},
+};
+
+pub const KSPLAT_HEADER_SIZE: usize = 4096;
+pub const KSPLAT_SECTION_HEADER_SIZE: usize = 1024;
+pub const KSPLAT_MAX_SH_DEGREE: usize = 2;
+
+const KSPLAT_DEFAULT_SH_HALF_RANGE: f32 = 1.5;
+
+/// per splat layout of a compression level
+#[derive(Clone, Copy, Debug)]
+struct KsplatLayout {
+    bytes_per_center: usize,
+    scale_offset: usize,
+    rotation_offset: usize,
+    color_offset: usize,
+    sh_offset: usize,
+    bytes_per_sh: usize,
+    scale_range: u32,
+}
+
+impl KsplatLayout {
+    fn for_level(compression_level: u16) -> Result<Self, std::io::Error> {
+        match compression_level {
+            0 => Ok(Self {
+                bytes_per_center: 12,
+                scale_offset: 12,
+                rotation_offset: 24,
+                color_offset: 40,
+                sh_offset: 44,
+                bytes_per_sh: 4,
+                scale_range: 1,
+            }),
+            1 | 2 => Ok(Self {
+                bytes_per_center: 6,
+                scale_offset: 6,
+                rotation_offset: 12,
+                color_offset: 20,
+                sh_offset: 24,
+                bytes_per_sh: if compression_level == 1 { 2 } else { 1 },
+                scale_range: 32767,
+            }),
+            _ => Err(invalid_data(format!(
+                "unsupported ksplat compression level {compression_level}"
+            ))),
+        }
+    }
+
+    fn bytes_per_splat(&self, sh_degree: usize) -> usize {
+        self.sh_offset + ksplat_sh_dim(sh_degree) * SH_CHANNELS * self.bytes_per_sh
+    }
+}
+
+/// sh coefficients per channel above the dc term
+pub const fn ksplat_sh_dim(degree: usize) -> usize {
+    match degree {
+        0 => 0,
+        1 => 3,
+        _ => 8,
+    }
+}
+
+#[derive(Clone, Debug)]
+struct KsplatHeader {
+    max_section_count: usize,
+    section_count: usize,
+    compression_level: u16,
+    min_sh: f32,
+    max_sh: f32,
+}
+
+#[derive(Clone, Debug)]
+struct KsplatSection {
+    splat_count: usize,
+    max_splat_count: usize,
+    bucket_size: usize,
+    bucket_count: usize,
+    half_bucket_block_size: f32,
+    bucket_storage_size: usize,
+    scale_range: u32,
+    full_bucket_count: usize,
+    partially_filled_bucket_count: usize,
+    sh_degree: usize,
+}
+
+pub fn parse_ksplat_3d(reader: &mut dyn Read) -> Result<PlanarGaussian3d, std::io::Error> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    decode_ksplat_3d(&data)
+}
+
+pub fn decode_ksplat_3d(data: &[u8]) -> Result<PlanarGaussian3d, std::io::Error> {
+    let bytes = KsplatBytes(data);
+
+    let header = read_header(&bytes)?;
+    let layout = KsplatLayout::for_level(header.compression_level)?;
+
+    let mut gaussians = Vec::new();
+    let mut section_base = checked_add(
+        KSPLAT_HEADER_SIZE,
+        checked_mul(header.max_section_count, KSPLAT_SECTION_HEADER_SIZE)?,
+    )?;
+
+    for index in 0..header.max_section_count {
+        // section headers precede `section_base`, which is checked above
+        let section = read_section(
+            &bytes,
+            KSPLAT_HEADER_SIZE + index * KSPLAT_SECTION_HEADER_SIZE,
+        )?;
+        let bytes_per_splat = layout.bytes_per_splat(section.sh_degree);
+
+        // offsets come from untrusted counts, every sum and product is checked so a crafted
+        // header fails to load instead of wrapping around
+        let partial_lengths_size = checked_mul(section.partially_filled_bucket_count, 4)?;
+        let buckets_base = checked_add(section_base, partial_lengths_size)?;
+        let data_base = checked_add(
+            buckets_base,
+            checked_mul(section.bucket_storage_size, section.bucket_count)?,
+        )?;
+        let section_end = checked_add(
+            data_base,
+            checked_mul(bytes_per_splat, section.max_splat_count)?,
+        )?;
+
+        if index < header.section_count {
+            let partial_lengths = (0..section.partially_filled_bucket_count)
+                .map(|bucket| bytes.u32(section_base + bucket * 4).map(|len| len as usize))
+                .collect::<Result<Vec<_>, _>>()?;
+            let bucket_index = BucketIndex::new(&section, partial_lengths)?;
+
+            // splat_count <= max_splat_count, so these offsets stay below `section_end`
+            for local in 0..section.splat_count {
+                let record = bytes.slice(data_base + local * bytes_per_splat, bytes_per_splat)?;
+                let bucket_center = if header.compression_level == 0 {
+                    [0.0; 3]
+                } else {
+                    let bucket = bucket_index.bucket_of(local);
+                    if bucket >= section.bucket_count {
+                        return Err(invalid_data("ksplat splat outside of its buckets"));
+                    }
+
+                    let offset = buckets_base + bucket * section.bucket_storage_size;
+                    [
+                        bytes.f32(offset)?,
+                        bytes.f32(checked_add(offset, 4)?)?,
+                        bytes.f32(checked_add(offset, 8)?)?,
+                    ]
+                };
+
+                gaussians.push(decode_splat(
+                    record,
+                    &header,
+                    &layout,
+                    &section,
+                    bucket_center,
+                ));
+            }
+        }
+
+        section_base = section_end;
+    }
+
+    Ok(PlanarGaussian3d::from_interleaved(gaussians))
+}
+
+fn read_header(bytes: &KsplatBytes) -> Result<KsplatHeader, std::io::Error> {
+    let major = bytes.u8(0)?;
+    let minor = bytes.u8(1)?;
+    if major != 0 || minor < 1 {
+        return Err(invalid_data(format!(
+            "unsupported ksplat version {major}.{minor}"
+        )));
+    }
+
+    let max_section_count = bytes.u32(4)? as usize;
+    let section_count = bytes.u32(8)? as usize;
+    if section_count > max_section_count {
+        return Err(invalid_data("ksplat section count exceeds its maximum"));
+    }
+
+    // zeroed ranges fall back to the GaussianSplats3D defaults
+    let min_sh = bytes.f32(36)?;
+    let max_sh = bytes.f32(40)?;
+
+    Ok(KsplatHeader {
+        max_section_count,
+        section_count,
+        compression_level: bytes.u16(20)?,
+        min_sh: if min_sh != 0.0 {
+            min_sh
+        } else {
+            -KSPLAT_DEFAULT_SH_HALF_RANGE
+        },
+        max_sh: if max_sh != 0.0 {
+            max_sh
+        } else {
+            KSPLAT_DEFAULT_SH_HALF_RANGE
+        },
+    })
+}
+
+fn read_section(bytes: &KsplatBytes, base: usize) -> Result<KsplatSection, std::io::Error> {
+    let splat_count = bytes.u32(base)? as usize;
+    let max_splat_count = bytes.u32(base + 4)? as usize;
+    let sh_degree = bytes.u16(base + 40)? as usize;
+
+    if splat_count > max_splat_count {
+        return Err(invalid_data(
+            "ksplat section splat count exceeds its maximum",
+        ));
+    }
+    if sh_degree > KSPLAT_MAX_SH_DEGREE {
+        return Err(invalid_data(format!(
+            "unsupported ksplat sh degree {sh_degree}"
+        )));
+    }
+
+    Ok(KsplatSection {
+        splat_count,
+        max_splat_count,
+        bucket_size: bytes.u32(base + 8)? as usize,
+        bucket_count: bytes.u32(base + 12)? as usize,
+        half_bucket_block_size: bytes.f32(base + 16)? / 2.0,
+        bucket_storage_size: bytes.u16(base + 20)? as usize,
+        scale_range: bytes.u32(base + 24)?,
+        full_bucket_count: bytes.u32(base + 32)? as usize,
+        partially_filled_bucket_count: bytes.u32(base + 36)? as usize,
+        sh_degree,
+    })
+}
+
+fn decode_splat(
+    record: &[u8],
+    header: &KsplatHeader,
+    layout: &KsplatLayout,
+    section: &KsplatSection,
+    bucket_center: [f32; 3],
+) -> Gaussian3d {
+    let record = KsplatBytes(record);
+    let compressed = header.compression_level > 0;
+
+    // record bounds are checked by the caller
+    let f32_at = |offset: usize| record.f32(offset).unwrap_or_default();
+    let f16_at = |offset: usize| f16::from_bits(record.u16(offset).unwrap_or_default()).to_f32();
+
+    let mut gaussian = Gaussian3d::default();
+
+    let scale_range = if section.scale_range != 0 {
+        section.scale_range
+    } else {
+        layout.scale_range
+    };
+    let compression_scale = section.half_bucket_block_size / scale_range as f32;
+
+    gaussian.position_visibility.position = std::array::from_fn(|axis| {
+        if compressed {
+            let quantized = record.u16(axis * 2).unwrap_or_default() as f32;
+            (quantized - scale_range as f32) * compression_scale + bucket_center[axis]
+        } else {
+            f32_at(axis * 4)
+        }
+    });
+
+    let component_size = layout.bytes_per_center / 3;
+    let component = |offset: usize| {
+        if compressed {
+            f16_at(offset)
+        } else {
+            f32_at(offset)
+        }
+    };
+
+    gaussian.scale_opacity.scale =
+        std::array::from_fn(|axis| component(layout.scale_offset + axis * component_size));
+
+    // stored as wxyz
+    let rotation: [f32; 4] =
+        std::array::from_fn(|i| component(layout.rotation_offset + i * component_size));
+    gaussian.rotation.rotation = normalize_rotation(rotation);
+
+    for channel in 0..SH_CHANNELS {
+        gaussian.spherical_harmonic.set(
+            channel,
+            color_to_sh_dc(record.0[layout.color_offset + channel]),
+        );
+    }
+    gaussian.scale_opacity.opacity = record.0[layout.color_offset + 3] as f32 / 255.0;
+
+    // coefficient major, rgb per coefficient
+    let sh_dim = ksplat_sh_dim(section.sh_degree);
+    for coefficient in 0..sh_dim {
+        for channel in 0..SH_CHANNELS {
+            let offset =
+                layout.sh_offset + (coefficient * SH_CHANNELS + channel) * layout.bytes_per_sh;
+            let value = match layout.bytes_per_sh {
+                4 => f32_at(offset),
+                2 => f16_at(offset),
+                _ => {
+                    let normalized = record.0[offset] as f32 / 255.0;
+                    header.min_sh + normalized * (header.max_sh - header.min_sh)
+                }
+            };
+
+            let interleaved_idx = (coefficient + 1) * SH_CHANNELS + channel;
+            if coefficient + 1 < SH_COEFF_COUNT_PER_CHANNEL && interleaved_idx < SH_COEFF_COUNT {
+                gaussian.spherical_harmonic.set(interleaved_idx, value);
+            }
+        }
+    }
+
+    gaussian
+}
+
+/// maps section local splat indices to buckets, trailing buckets may be partially filled
+struct BucketIndex {
+    bucket_size: usize,
+    full_bucket_count: usize,
+    full_splats: usize,
+    partial_ends: Vec<usize>,
+}
+
+impl BucketIndex {
+    fn new(section: &KsplatSection, partial_lengths: Vec<usize>) -> Result<Self, std::io::Error> {
+        let full_splats = checked_mul(section.full_bucket_count, section.bucket_size)?;
+
+        let mut end = full_splats;
+        let partial_ends = partial_lengths
+            .iter()
+            .map(|&length| {
+                end = checked_add(end, length)?;
+                Ok(end)
+            })
+            .collect::<Result<_, std::io::Error>>()?;
+
+        Ok(Self {
+            bucket_size: section.bucket_size.max(1),
+            full_bucket_count: section.full_bucket_count,
+            full_splats,
+            partial_ends,
+        })
+    }
+
+    fn bucket_of(&self, local: usize) -> usize {
+        if local < self.full_splats {
+            return local / self.bucket_size;
+        }
+
+        self.full_bucket_count + self.partial_ends.partition_point(|&end| end <= local)
+    }
+}
+
+struct KsplatBytes<'a>(&'a [u8]);
+
+impl KsplatBytes<'_> {
+    fn slice(&self, offset: usize, len: usize) -> Result<&[u8], std::io::Error> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.0.get(offset..end))
+            .ok_or_else(|| invalid_data("truncated ksplat data"))
+    }
+
+    fn array<const N: usize>(&self, offset: usize) -> Result<[u8; N], std::io::Error> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.slice(offset, N)?);
+        Ok(array)
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, std::io::Error> {
+        Ok(self.array::<1>(offset)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, std::io::Error> {
+        Ok(u16::from_le_bytes(self.array(offset)?))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, std::io::Error> {
+        Ok(u32::from_le_bytes(self.array(offset)?))
+    }
+
+    fn f32(&self, offset: usize) -> Result<f32, std::io::Error> {
+        Ok(f32::from_le_bytes(self.array(offset)?))
+    }
+}
+
+fn checked_add(a: usize, b: usize) -> Result<usize, std::io::Error> {
+    a.checked_add(b)
+        .ok_or_else(|| invalid_data("ksplat offset overflow"))
+}
+
+fn checked_mul(a: usize, b: usize) -> Result<usize, std::io::Error> {
+    a.checked_mul(b)
+        .ok_or_else(|| invalid_data("ksplat size overflow"))
+}
+
+fn invalid_data(message: impl Into<String>) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.into())
+}
@@ -7,8 +7,22 @@ use bevy::{
 };
 
 use crate::{
-    gaussian::formats::planar_3d::PlanarGaussian3d, gaussian::formats::planar_4d::PlanarGaussian4d,
-    io::codec::CloudCodec,
+    gaussian::formats::planar_3d::PlanarGaussian3d,
+    gaussian::formats::planar_3d_chunked::{ChunkedCloudBuilder, PlanarGaussian3dChunked},
+    gaussian::formats::planar_3d_codebook::{PlanarGaussian3dCodebook, ShCodebookBuilder},
+    gaussian::formats::planar_3d_lod::{LodCloudBuilder, PlanarGaussian3dLod},
+    gaussian::formats::planar_3d_quantized::PlanarGaussian3dQuantized,
+    gaussian::formats::planar_4d::PlanarGaussian4d,
+    gaussian::formats::planar_4d_hierarchy::TemporalGaussianHierarchy,
+    gaussian::formats::planar_4d_quantized::PlanarGaussian4dQuantized,
+    gaussian::formats::spacetime::PlanarGaussianSpacetime,
+    io::{
+        gcloud::{
+            header::GcloudContainer,
+            texture::{GAUSSIAN_TEXTURE_IMAGES_LABEL, GaussianTextureImages, GaussianTextures},
+        },
+        settings::GaussianLoaderSettings,
+    },
 };
 
 #[derive(Default, TypePath)]
@@ -16,13 +30,13 @@ pub struct Gaussian3dLoader;
 
 impl AssetLoader for Gaussian3dLoader {
     type Asset = PlanarGaussian3d;
-    type Settings = ();
+    type Settings = GaussianLoaderSettings;
     type Error = std::io::Error;
 
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
@@ -41,7 +55,15 @@ impl AssetLoader for Gaussian3dLoader {
                     let cursor = Cursor::new(bytes);
                     let mut f = BufReader::new(cursor);
 
-                    Ok(crate::io::ply::parse_ply_3d(&mut f)?)
+                    let (cloud, material) =
+                        crate::io::ply::parse_ply_3d_relightable(&mut f, settings)?;
+
+                    // relightable attributes stay addressable as `cloud.ply#pbr`
+                    if let Some(material) = material {
+                        load_context.add_labeled_asset("pbr".to_owned(), material);
+                    }
+
+                    Ok(cloud)
                 }
 
                 #[cfg(not(feature = "io_ply"))]
@@ -51,17 +73,258 @@ impl AssetLoader for Gaussian3dLoader {
                     ))
                 }
             }
+            Some("spz") => {
+                #[cfg(feature = "io_spz")]
+                {
+                    Ok(settings.apply_3d(crate::io::spz::decode_spz_3d(bytes.as_slice())?))
+                }
+
+                #[cfg(not(feature = "io_spz"))]
+                {
+                    Err(std::io::Error::other(
+                        "spz support not enabled, enable with io_spz feature",
+                    ))
+                }
+            }
+            Some("splat") => {
+                #[cfg(feature = "io_splat")]
+                {
+                    Ok(settings.apply_3d(crate::io::splat::decode_splat_3d(bytes.as_slice())?))
+                }
+
+                #[cfg(not(feature = "io_splat"))]
+                {
+                    Err(std::io::Error::other(
+                        "splat support not enabled, enable with io_splat feature",
+                    ))
+                }
+            }
+            Some("ksplat") => {
+                #[cfg(feature = "io_splat")]
+                {
+                    Ok(settings.apply_3d(crate::io::ksplat::decode_ksplat_3d(bytes.as_slice())?))
+                }
+
+                #[cfg(not(feature = "io_splat"))]
+                {
+                    Err(std::io::Error::other(
+                        "ksplat support not enabled, enable with io_splat feature",
+                    ))
+                }
+            }
             Some("gcloud") => {
-                let cloud = PlanarGaussian3d::decode(bytes.as_slice());
+                let cloud = PlanarGaussian3d::decode_container(bytes.as_slice())?.1;
+
+                Ok(settings.apply_3d(cloud))
+            }
+            Some("gtex") => {
+                let textures = GaussianTextures::read_from(bytes.as_slice())?;
+
+                // the attribute images stay addressable as `cloud.gtex#<plane>`
+                let planes = textures
+                    .planes()
+                    .map(|(plane, texture)| {
+                        let image = load_context
+                            .add_labeled_asset(plane.name().to_owned(), texture.to_image());
+                        (plane, image)
+                    })
+                    .collect();
+                load_context.add_labeled_asset(
+                    GAUSSIAN_TEXTURE_IMAGES_LABEL.to_owned(),
+                    GaussianTextureImages {
+                        count: textures.count,
+                        ranges: textures.ranges.clone(),
+                        planes,
+                    },
+                );
+
+                // `buffer_texture` samples the planes as stored, the settings reach the decoded cloud
+                Ok(settings.apply_3d(textures.decode()?))
+            }
+            _ => Err(std::io::Error::other(
+                "only .ply, .spz, .splat, .ksplat, .gcloud, and .gtex supported",
+            )),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ply", "spz", "splat", "ksplat", "gcloud", "gtex"]
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct Gaussian3dQuantizedLoader;
+
+impl AssetLoader for Gaussian3dQuantizedLoader {
+    type Asset = PlanarGaussian3dQuantized;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let extension = load_context
+            .path()
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str());
+
+        match extension {
+            Some("gcq") => {
+                let cloud = PlanarGaussian3dQuantized::decode_container(bytes.as_slice())?.1;
+
+                Ok(settings.apply_3d_stored(cloud, |cloud, _| cloud.into()))
+            }
+            _ => Err(std::io::Error::other("only .gcq supported")),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gcq"]
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct Gaussian3dCodebookLoader;
+
+impl AssetLoader for Gaussian3dCodebookLoader {
+    type Asset = PlanarGaussian3dCodebook;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
 
-                Ok(cloud)
+        let extension = load_context
+            .path()
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str());
+
+        match extension {
+            Some("gcvq") => {
+                let cloud = PlanarGaussian3dCodebook::decode_container(bytes.as_slice())?.1;
+
+                // the codebook is retrained on the processed harmonics
+                Ok(settings.apply_3d_stored(cloud, |cloud, decoded| {
+                    ShCodebookBuilder::default()
+                        .codebook_size(decoded.codebook.len())
+                        .build(cloud)
+                }))
             }
-            _ => Err(std::io::Error::other("only .ply and .gcloud supported")),
+            _ => Err(std::io::Error::other("only .gcvq supported")),
         }
     }
 
     fn extensions(&self) -> &[&str] {
-        &["ply", "gcloud"]
+        &["gcvq"]
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct Gaussian3dLodLoader;
+
+impl AssetLoader for Gaussian3dLodLoader {
+    type Asset = PlanarGaussian3dLod;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let extension = load_context
+            .path()
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str());
+
+        match extension {
+            Some("glod") => {
+                let lod = PlanarGaussian3dLod::decode_container(bytes.as_slice())?.1;
+
+                // pruning changes the leaves, the hierarchy is rebuilt with the decoded shape
+                Ok(settings.apply_3d_stored(lod, |cloud, decoded| {
+                    let branching_factor = decoded
+                        .nodes
+                        .iter()
+                        .map(|node| node.child_count as usize)
+                        .max()
+                        .unwrap_or_default();
+
+                    LodCloudBuilder::default()
+                        .branching_factor(branching_factor)
+                        .max_levels(decoded.level_count())
+                        .build(cloud)
+                }))
+            }
+            _ => Err(std::io::Error::other("only .glod supported")),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["glod"]
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct Gaussian3dChunkedLoader;
+
+impl AssetLoader for Gaussian3dChunkedLoader {
+    type Asset = PlanarGaussian3dChunked;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        #[cfg(feature = "io_ply")]
+        {
+            let cursor = Cursor::new(bytes);
+            let mut f = BufReader::new(cursor);
+
+            let cloud = crate::io::compressed_ply::parse_compressed_ply(&mut f)?;
+
+            Ok(settings.apply_3d_stored(cloud, |cloud, decoded| {
+                ChunkedCloudBuilder::default()
+                    .sh_degree(decoded.sh_degree())
+                    .build(cloud)
+            }))
+        }
+
+        #[cfg(not(feature = "io_ply"))]
+        {
+            Err(std::io::Error::other(
+                "compressed ply support not enabled, enable with io_ply feature",
+            ))
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["compressed.ply"]
     }
 }
 
@@ -70,13 +333,13 @@ pub struct Gaussian4dLoader;
 
 impl AssetLoader for Gaussian4dLoader {
     type Asset = PlanarGaussian4d;
-    type Settings = ();
+    type Settings = GaussianLoaderSettings;
     type Error = std::io::Error;
 
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
@@ -95,7 +358,9 @@ impl AssetLoader for Gaussian4dLoader {
                     let cursor = Cursor::new(bytes);
                     let mut f = BufReader::new(cursor);
 
-                    Ok(crate::io::ply::parse_ply_4d(&mut f)?)
+                    Ok(crate::io::ply::parse_ply_4d_with_settings(
+                        &mut f, settings,
+                    )?)
                 }
 
                 #[cfg(not(feature = "io_ply"))]
@@ -105,7 +370,11 @@ impl AssetLoader for Gaussian4dLoader {
                     ))
                 }
             }
-            Some("gc4d") => Ok(PlanarGaussian4d::decode(bytes.as_slice())),
+            Some("gc4d") => {
+                let cloud = PlanarGaussian4d::decode_container(bytes.as_slice())?.1;
+
+                Ok(settings.apply_4d(cloud))
+            }
             _ => Err(std::io::Error::other("only .ply4d and .gc4d supported")),
         }
     }
@@ -114,3 +383,139 @@ impl AssetLoader for Gaussian4dLoader {
         &["ply4d", "gc4d"]
     }
 }
+
+#[derive(Default, TypePath)]
+pub struct Gaussian4dQuantizedLoader;
+
+impl AssetLoader for Gaussian4dQuantizedLoader {
+    type Asset = PlanarGaussian4dQuantized;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let extension = load_context
+            .path()
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str());
+
+        match extension {
+            Some("gc4d") => {
+                let cloud = PlanarGaussian4dQuantized::decode_container(bytes.as_slice())?.1;
+
+                Ok(settings.apply_4d_stored(cloud, |cloud, _| cloud.into()))
+            }
+            _ => Err(std::io::Error::other("only .gc4d supported")),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gc4d"]
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct GaussianSpacetimeLoader;
+
+impl AssetLoader for GaussianSpacetimeLoader {
+    type Asset = PlanarGaussianSpacetime;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let extension = load_context
+            .path()
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str());
+
+        match extension {
+            // the layout is checked against the header, `.plyst` is kept for existing assets
+            Some("ply") | Some("plyst") => {
+                #[cfg(feature = "io_ply")]
+                {
+                    let cursor = Cursor::new(bytes);
+                    let mut f = BufReader::new(cursor);
+
+                    Ok(crate::io::ply::parse_ply_spacetime_with_settings(
+                        &mut f, settings,
+                    )?)
+                }
+
+                #[cfg(not(feature = "io_ply"))]
+                {
+                    Err(std::io::Error::other(
+                        "spacetime ply support not enabled, enable with io_ply feature",
+                    ))
+                }
+            }
+            Some("gcst") => {
+                let cloud = PlanarGaussianSpacetime::decode_container(bytes.as_slice())?.1;
+
+                Ok(settings.apply_spacetime(cloud))
+            }
+            _ => Err(std::io::Error::other(
+                "only .ply, .plyst and .gcst supported",
+            )),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ply", "plyst", "gcst"]
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct TemporalGaussianHierarchyLoader;
+
+impl AssetLoader for TemporalGaussianHierarchyLoader {
+    type Asset = TemporalGaussianHierarchy;
+    type Settings = GaussianLoaderSettings;
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let extension = load_context
+            .path()
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str());
+
+        match extension {
+            Some("gth") => {
+                let hierarchy = TemporalGaussianHierarchy::decode_container(bytes.as_slice())?.1;
+                hierarchy.validate()?;
+
+                Ok(settings.apply_temporal_hierarchy(hierarchy))
+            }
+            _ => Err(std::io::Error::other("only .gth supported")),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gth"]
+    }
+}
@@ -0,0 +1,398 @@
+// https://github.com/zeux/meshoptimizer/blob/master/src/vertexcodec.cpp
+// vertex codec v0 as used by the `EXT_meshopt_compression` ATTRIBUTES mode
+use std::io::ErrorKind;
+
+const VERTEX_HEADER: u8 = 0xa0;
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+const BYTE_GROUP_SIZE: usize = 16;
+const TAIL_MIN_SIZE: usize = 32;
+
+/// largest vertex stride the codec supports
+pub const MESHOPT_MAX_VERTEX_SIZE: usize = 256;
+
+fn vertex_block_size(vertex_size: usize) -> usize {
+    let size = (VERTEX_BLOCK_SIZE_BYTES / vertex_size) & !(BYTE_GROUP_SIZE - 1);
+    size.min(VERTEX_BLOCK_MAX_SIZE)
+}
+
+fn tail_size(vertex_size: usize) -> usize {
+    vertex_size.max(TAIL_MIN_SIZE)
+}
+
+fn zigzag8(value: u8) -> u8 {
+    ((value as i8 >> 7) as u8) ^ (value << 1)
+}
+
+fn unzigzag8(value: u8) -> u8 {
+    (0u8.wrapping_sub(value & 1)) ^ (value >> 1)
+}
+
+fn validate_vertex_size(vertex_size: usize) -> Result<(), std::io::Error> {
+    if vertex_size == 0 || vertex_size > MESHOPT_MAX_VERTEX_SIZE || !vertex_size.is_multiple_of(4) {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "meshopt vertex size must be a multiple of 4 in 4..={MESHOPT_MAX_VERTEX_SIZE}, got {vertex_size}"
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// encodes `vertices` laid out with a stride of `vertex_size` bytes
+pub fn encode_vertex_buffer(
+    vertices: &[u8],
+    vertex_size: usize,
+) -> Result<Vec<u8>, std::io::Error> {
+    validate_vertex_size(vertex_size)?;
+    if !vertices.len().is_multiple_of(vertex_size) {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "meshopt vertex data of {} bytes is not a multiple of the {vertex_size} byte stride",
+                vertices.len()
+            ),
+        ));
+    }
+
+    let vertex_count = vertices.len() / vertex_size;
+    let mut data = vec![VERTEX_HEADER];
+
+    let mut first_vertex = vec![0u8; vertex_size];
+    if vertex_count > 0 {
+        first_vertex.copy_from_slice(&vertices[..vertex_size]);
+    }
+    let mut last_vertex = first_vertex.clone();
+
+    let block_size = vertex_block_size(vertex_size);
+    for block in vertices.chunks(block_size * vertex_size) {
+        encode_vertex_block(&mut data, block, vertex_size, &mut last_vertex);
+    }
+
+    data.resize(data.len() + tail_size(vertex_size) - vertex_size, 0);
+    data.extend_from_slice(&first_vertex);
+
+    Ok(data)
+}
+
+fn encode_vertex_block(
+    data: &mut Vec<u8>,
+    block: &[u8],
+    vertex_size: usize,
+    last_vertex: &mut [u8],
+) {
+    let vertex_count = block.len() / vertex_size;
+    let aligned_count = vertex_count.next_multiple_of(BYTE_GROUP_SIZE);
+    let mut buffer = vec![0u8; aligned_count];
+
+    for (byte, previous) in last_vertex.iter().enumerate() {
+        let mut previous = *previous;
+        for (index, delta) in buffer.iter_mut().take(vertex_count).enumerate() {
+            let value = block[index * vertex_size + byte];
+            *delta = zigzag8(value.wrapping_sub(previous));
+            previous = value;
+        }
+
+        encode_bytes(data, &buffer);
+    }
+
+    last_vertex.copy_from_slice(&block[(vertex_count - 1) * vertex_size..]);
+}
+
+fn encode_bytes(data: &mut Vec<u8>, buffer: &[u8]) {
+    let group_count = buffer.len() / BYTE_GROUP_SIZE;
+    let header_offset = data.len();
+    data.resize(header_offset + group_count.div_ceil(4), 0);
+
+    for (group_index, group) in buffer.chunks_exact(BYTE_GROUP_SIZE).enumerate() {
+        // 0 is an all zero group, 1 and 2 pack 2 and 4 bit values, 3 stores raw bytes
+        let bitslog2 = if group.iter().all(|&value| value == 0) {
+            0
+        } else {
+            (1..=3)
+                .min_by_key(|&bitslog2| encoded_group_size(group, 1 << bitslog2))
+                .unwrap_or(3)
+        };
+
+        data[header_offset + group_index / 4] |= (bitslog2 as u8) << ((group_index % 4) * 2);
+        if bitslog2 > 0 {
+            encode_bytes_group(data, group, 1 << bitslog2);
+        }
+    }
+}
+
+fn encoded_group_size(group: &[u8], bits: usize) -> usize {
+    if bits == 8 {
+        return BYTE_GROUP_SIZE;
+    }
+
+    let sentinel = (1usize << bits) - 1;
+    let overflow = group
+        .iter()
+        .filter(|&&value| value as usize >= sentinel)
+        .count();
+    BYTE_GROUP_SIZE * bits / 8 + overflow
+}
+
+fn encode_bytes_group(data: &mut Vec<u8>, group: &[u8], bits: usize) {
+    if bits == 8 {
+        data.extend_from_slice(group);
+        return;
+    }
+
+    let sentinel = (1u8 << bits) - 1;
+    let per_byte = 8 / bits;
+    let mut overflow = Vec::new();
+
+    for values in group.chunks_exact(per_byte) {
+        let mut byte = 0u8;
+        for &value in values {
+            let encoded = value.min(sentinel);
+            byte = (byte << bits) | encoded;
+            if encoded == sentinel {
+                overflow.push(value);
+            }
+        }
+        data.push(byte);
+    }
+
+    data.extend_from_slice(&overflow);
+}
+
+/// decodes `vertex_count` vertices of `vertex_size` bytes
+pub fn decode_vertex_buffer(
+    data: &[u8],
+    vertex_count: usize,
+    vertex_size: usize,
+) -> Result<Vec<u8>, std::io::Error> {
+    validate_vertex_size(vertex_size)?;
+
+    let truncated =
+        || std::io::Error::new(ErrorKind::InvalidData, "meshopt vertex buffer is truncated");
+
+    if data.len() < 1 + tail_size(vertex_size) {
+        return Err(truncated());
+    }
+
+    let header = data[0];
+    if header & 0xf0 != VERTEX_HEADER || header & 0x0f != 0 {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!("unsupported meshopt vertex buffer header 0x{header:02x}"),
+        ));
+    }
+
+    let mut last_vertex = data[data.len() - vertex_size..].to_vec();
+    let body = &data[..data.len() - tail_size(vertex_size)];
+    let mut cursor = 1usize;
+
+    let length = vertex_count.checked_mul(vertex_size).ok_or_else(|| {
+        std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "meshopt vertex buffer of {vertex_count} vertices of {vertex_size} bytes overflows"
+            ),
+        )
+    })?;
+    let mut vertices = vec![0u8; length];
+    let block_size = vertex_block_size(vertex_size);
+    for block in vertices.chunks_mut(block_size * vertex_size) {
+        cursor = decode_vertex_block(body, cursor, block, vertex_size, &mut last_vertex)
+            .ok_or_else(truncated)?;
+    }
+
+    if cursor != body.len() {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "meshopt vertex buffer has {} unexpected trailing bytes",
+                body.len() - cursor
+            ),
+        ));
+    }
+
+    Ok(vertices)
+}
+
+fn decode_vertex_block(
+    data: &[u8],
+    mut cursor: usize,
+    block: &mut [u8],
+    vertex_size: usize,
+    last_vertex: &mut [u8],
+) -> Option<usize> {
+    let vertex_count = block.len() / vertex_size;
+    let mut buffer = vec![0u8; vertex_count.next_multiple_of(BYTE_GROUP_SIZE)];
+
+    for (byte, previous) in last_vertex.iter().enumerate() {
+        cursor = decode_bytes(data, cursor, &mut buffer)?;
+
+        let mut previous = *previous;
+        for (index, delta) in buffer.iter().take(vertex_count).enumerate() {
+            let value = unzigzag8(*delta).wrapping_add(previous);
+            block[index * vertex_size + byte] = value;
+            previous = value;
+        }
+    }
+
+    last_vertex.copy_from_slice(&block[(vertex_count - 1) * vertex_size..]);
+    Some(cursor)
+}
+
+fn decode_bytes(data: &[u8], cursor: usize, buffer: &mut [u8]) -> Option<usize> {
+    let group_count = buffer.len() / BYTE_GROUP_SIZE;
+    let header = data.get(cursor..cursor + group_count.div_ceil(4))?;
+    let mut cursor = cursor + header.len();
+
+    for (group_index, group) in buffer.chunks_exact_mut(BYTE_GROUP_SIZE).enumerate() {
+        let bitslog2 = (header[group_index / 4] >> ((group_index % 4) * 2)) & 3;
+        cursor = decode_bytes_group(data, cursor, group, bitslog2)?;
+    }
+
+    Some(cursor)
+}
+
+fn decode_bytes_group(data: &[u8], cursor: usize, group: &mut [u8], bitslog2: u8) -> Option<usize> {
+    match bitslog2 {
+        0 => {
+            group.fill(0);
+            Some(cursor)
+        }
+        3 => {
+            group.copy_from_slice(data.get(cursor..cursor + BYTE_GROUP_SIZE)?);
+            Some(cursor + BYTE_GROUP_SIZE)
+        }
+        _ => {
+            let bits = 1usize << bitslog2;
+            let sentinel = (1u8 << bits) - 1;
+            let per_byte = 8 / bits;
+            let packed = data.get(cursor..cursor + BYTE_GROUP_SIZE / per_byte)?;
+            let mut overflow = cursor + packed.len();
+
+            for (byte, values) in packed.iter().zip(group.chunks_exact_mut(per_byte)) {
+                for (slot, value) in values.iter_mut().enumerate() {
+                    let encoded = (byte >> (8 - bits * (slot + 1))) & sentinel;
+                    *value = if encoded == sentinel {
+                        overflow += 1;
+                        *data.get(overflow - 1)?
+                    } else {
+                        encoded
+                    };
+                }
+            }
+
+            Some(overflow)
+        }
+    }
+}
+
+/// `EXT_meshopt_compression` filters, applied after decoding ATTRIBUTES data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeshoptFilter {
+    None,
+    Octahedral,
+    Quaternion,
+    Exponential,
+}
+
+impl MeshoptFilter {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "NONE" => Some(Self::None),
+            "OCTAHEDRAL" => Some(Self::Octahedral),
+            "QUATERNION" => Some(Self::Quaternion),
+            "EXPONENTIAL" => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+
+    /// reverses the filter in place on vertices of `vertex_size` bytes
+    pub fn decode(self, vertices: &mut [u8], vertex_size: usize) -> Result<(), std::io::Error> {
+        validate_vertex_size(vertex_size)?;
+
+        match (self, vertex_size) {
+            (Self::None, _) => {}
+            (Self::Octahedral, 4) => vertices.chunks_exact_mut(4).for_each(decode_octahedral_i8),
+            (Self::Octahedral, 8) => vertices.chunks_exact_mut(8).for_each(decode_octahedral_i16),
+            (Self::Quaternion, 8) => vertices.chunks_exact_mut(8).for_each(decode_quaternion),
+            (Self::Exponential, _) => vertices.chunks_exact_mut(4).for_each(decode_exponential),
+            _ => {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("meshopt {self:?} filter does not support a {vertex_size} byte stride"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// x and y on the octahedron with z holding the encoded one, returns the rounded normal at `max`
+fn octahedral_normal([x, y, one]: [f32; 3], max: f32) -> [f32; 3] {
+    let z = one - x.abs() - y.abs();
+
+    // fold the lower hemisphere back
+    let fold = z.min(0.0);
+    let x = x + if x >= 0.0 { fold } else { -fold };
+    let y = y + if y >= 0.0 { fold } else { -fold };
+
+    let scale = max / (x * x + y * y + z * z).sqrt();
+    [x, y, z].map(|component| (component * scale).round())
+}
+
+fn decode_octahedral_i8(vertex: &mut [u8]) {
+    let encoded = [0, 1, 2].map(|index| vertex[index] as i8 as f32);
+    let normal = octahedral_normal(encoded, i8::MAX as f32);
+
+    for (byte, component) in vertex.iter_mut().zip(normal) {
+        *byte = component as i8 as u8;
+    }
+}
+
+fn decode_octahedral_i16(vertex: &mut [u8]) {
+    let encoded = [0, 1, 2].map(|index| read_i16(vertex, index) as f32);
+    let normal = octahedral_normal(encoded, i16::MAX as f32);
+
+    for (index, component) in normal.into_iter().enumerate() {
+        write_i16(vertex, index, component as i16);
+    }
+}
+
+/// three components scaled by sqrt(2), the low bits of w select the dropped largest component
+fn decode_quaternion(vertex: &mut [u8]) {
+    let [a, b, c, w] = [0, 1, 2, 3].map(|index| read_i16(vertex, index));
+
+    let scale = std::f32::consts::FRAC_1_SQRT_2 / (w | 3) as f32;
+    let [a, b, c] = [a, b, c].map(|component| component as f32 * scale);
+    let largest = (1.0 - a * a - b * b - c * c).max(0.0).sqrt();
+
+    let dropped = (w & 3) as usize;
+    for (offset, component) in [largest, a, b, c].into_iter().enumerate() {
+        write_i16(
+            vertex,
+            (dropped + offset) & 3,
+            (component * i16::MAX as f32).round() as i16,
+        );
+    }
+}
+
+/// 24 bit signed mantissa with an 8 bit signed exponent
+fn decode_exponential(word: &mut [u8]) {
+    let bits = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+    let mantissa = ((bits << 8) as i32) >> 8;
+    let exponent = (bits as i32) >> 24;
+
+    let value = f32::from_bits(((exponent + 127) as u32) << 23) * mantissa as f32;
+    word.copy_from_slice(&value.to_le_bytes());
+}
+
+fn read_i16(vertex: &[u8], index: usize) -> i16 {
+    i16::from_le_bytes([vertex[index * 2], vertex[index * 2 + 1]])
+}
+
+fn write_i16(vertex: &mut [u8], index: usize, value: i16) {
+    vertex[index * 2..index * 2 + 2].copy_from_slice(&value.to_le_bytes());
+}
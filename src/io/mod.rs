@@ -1,19 +1,52 @@
 use bevy::prelude::*;
 
+use crate::gaussian::formats::planar_3d_chunked::PlanarGaussian3dChunked;
+
 pub mod codec;
 pub mod gcloud;
 pub mod loader;
+pub mod meshopt;
+pub mod rans;
 pub mod scene;
+pub mod settings;
+
+#[cfg(feature = "io_ply")]
+pub mod compressed_ply;
 
 #[cfg(feature = "io_ply")]
 pub mod ply;
 
+#[cfg(feature = "io_splat")]
+pub mod ksplat;
+
+#[cfg(feature = "io_splat")]
+pub mod splat;
+
+#[cfg(feature = "io_spz")]
+pub mod spz;
+
 #[derive(Default)]
 pub struct IoPlugin;
 impl Plugin for IoPlugin {
     fn build(&self, app: &mut App) {
+        // `.ply` is shared, untyped loads resolve to the last registered loader, the 3d one
+        app.init_asset_loader::<loader::GaussianSpacetimeLoader>();
         app.init_asset_loader::<loader::Gaussian3dLoader>();
+        app.init_asset_loader::<loader::Gaussian3dQuantizedLoader>();
+        app.init_asset_loader::<loader::Gaussian3dCodebookLoader>();
+
+        app.init_asset::<gcloud::texture::GaussianTextureImages>();
+
+        app.init_asset::<PlanarGaussian3dChunked>();
+        app.init_asset_loader::<loader::Gaussian3dChunkedLoader>();
+        app.init_asset_loader::<loader::Gaussian3dLodLoader>();
+        // `.gc4d` is shared as well, untyped loads resolve to the full precision loader
+        app.init_asset_loader::<loader::Gaussian4dQuantizedLoader>();
         app.init_asset_loader::<loader::Gaussian4dLoader>();
+        app.init_asset_loader::<loader::TemporalGaussianHierarchyLoader>();
+
+        #[cfg(feature = "io_ply")]
+        app.add_systems(Update, ply::apply_gaussian_2d_mode);
 
         app.add_plugins(scene::GaussianScenePlugin);
     }
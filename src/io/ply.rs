@@ -1,134 +1,446 @@
 use core::panic;
-use std::io::BufRead;
+use std::io::{BufRead, Cursor, Read, Write};
 
+use bevy::prelude::*;
 use bevy_interleave::prelude::Planar;
+use half::f16;
 use ply_rs::{
     parser::Parser,
-    ply::{Property, PropertyAccess},
+    ply::{ElementDef, Header, Property, PropertyAccess},
 };
 
 use crate::{
-    gaussian::formats::{
-        planar_3d::{Gaussian3d, PlanarGaussian3d},
-        planar_4d::{Gaussian4d, PlanarGaussian4d},
+    gaussian::{
+        formats::{
+            planar_3d::{Gaussian3d, PlanarGaussian3d, PlanarGaussian3dHandle},
+            planar_4d::{Gaussian4d, PlanarGaussian4d},
+            spacetime::{GaussianSpacetime, PlanarGaussianSpacetime},
+        },
+        settings::{CloudSettings, GaussianMode},
+    },
+    io::{
+        compressed_ply::{is_compressed_ply, read_compressed_payload},
+        settings::{GaussianLoaderSettings, unpadded_len},
     },
     material::{
+        pbr::{PbrAttributes, PbrMaterial},
         spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT, SH_COEFF_COUNT_PER_CHANNEL},
-        spherindrical_harmonics::SH_4D_COEFF_COUNT,
+        spherindrical_harmonics::{SH_4D_COEFF_COUNT, SH_4D_COEFF_COUNT_PER_CHANNEL},
     },
 };
 
-pub const MAX_SIZE_VARIANCE: f32 = 4.0;
+pub use crate::io::settings::MAX_SIZE_VARIANCE;
+
+/// f_rest slots buffered per vertex, enough for degree 4 files
+const MAX_PLY_REST_COEFFICIENTS: usize = 72;
+
+const SH_C0: f32 = 0.282_094_8;
+
+// ply-rs has no `half` scalar, binary half properties are re-declared as prefixed ushort bits
+const HALF_PROPERTY_PREFIX: &str = "__half_";
+
+/// rewrites `half`/`float16` property declarations so ply-rs can read the header
+fn with_half_properties<'a>(
+    reader: &'a mut dyn BufRead,
+) -> Result<impl BufRead + 'a, std::io::Error> {
+    let mut header = String::new();
+    let mut ascii = false;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "ply header is missing end_header",
+            ));
+        }
+
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens.as_slice() {
+            ["format", "ascii", ..] => {
+                ascii = true;
+                header.push_str(&line);
+            }
+            ["property", "half" | "float16", name] if ascii => {
+                header.push_str(&format!("property float {name}\n"));
+            }
+            ["property", "half" | "float16", name] => {
+                header.push_str(&format!("property ushort {HALF_PROPERTY_PREFIX}{name}\n"));
+            }
+            ["end_header"] => {
+                header.push_str(&line);
+                break;
+            }
+            _ => header.push_str(&line),
+        }
+    }
+
+    Ok(Cursor::new(header.into_bytes()).chain(reader))
+}
+
+fn decode_half_property(key: String, property: Property) -> (String, Property) {
+    if let Property::UShort(bits) = property
+        && let Some(name) = key.strip_prefix(HALF_PROPERTY_PREFIX)
+    {
+        return (
+            name.to_owned(),
+            Property::Float(f16::from_bits(bits).to_f32()),
+        );
+    }
+
+    (key, property)
+}
+
+/// any scalar property as f32, lists are ignored
+fn property_value(property: &Property) -> Option<f32> {
+    match *property {
+        Property::Char(v) => Some(v as f32),
+        Property::UChar(v) => Some(v as f32),
+        Property::Short(v) => Some(v as f32),
+        Property::UShort(v) => Some(v as f32),
+        Property::Int(v) => Some(v as f32),
+        Property::UInt(v) => Some(v as f32),
+        Property::Float(v) => Some(v),
+        Property::Double(v) => Some(v as f32),
+        _ => None,
+    }
+}
 
-impl PropertyAccess for Gaussian3d {
+/// integer colors are normalized by their type range, float colors are taken as is
+fn color_value(property: &Property) -> Option<f32> {
+    match *property {
+        Property::UChar(v) => Some(v as f32 / u8::MAX as f32),
+        Property::UShort(v) => Some(v as f32 / u16::MAX as f32),
+        _ => property_value(property),
+    }
+}
+
+/// vertex values buffered until the header tells how to interpret them
+#[derive(Clone, Copy)]
+struct PlyVertex3d {
+    gaussian: Gaussian3d,
+    rest: [f32; MAX_PLY_REST_COEFFICIENTS],
+    color: [f32; 3],
+    alpha: Option<f32>,
+    pbr: PbrAttributes,
+}
+
+impl PropertyAccess for PlyVertex3d {
     fn new() -> Self {
-        Gaussian3d::default()
+        Self {
+            gaussian: Gaussian3d::default(),
+            rest: [0.0; MAX_PLY_REST_COEFFICIENTS],
+            color: [0.0; 3],
+            alpha: None,
+            pbr: PbrAttributes::default(),
+        }
     }
 
     fn set_property(&mut self, key: String, property: Property) {
-        match (key.as_ref(), property) {
-            ("x", Property::Float(v)) => self.position_visibility.position[0] = v,
-            ("y", Property::Float(v)) => self.position_visibility.position[1] = v,
-            ("z", Property::Float(v)) => self.position_visibility.position[2] = v,
-            ("visibility", Property::Float(v)) => self.position_visibility.visibility = v,
-            ("f_dc_0", Property::Float(v)) => self.spherical_harmonic.set(0, v),
-            ("f_dc_1", Property::Float(v)) => self.spherical_harmonic.set(1, v),
-            ("f_dc_2", Property::Float(v)) => self.spherical_harmonic.set(2, v),
-            ("scale_0", Property::Float(v)) => self.scale_opacity.scale[0] = v,
-            ("scale_1", Property::Float(v)) => self.scale_opacity.scale[1] = v,
-            ("scale_2", Property::Float(v)) => self.scale_opacity.scale[2] = v,
-            ("opacity", Property::Float(v)) => {
-                self.scale_opacity.opacity = 1.0 / (1.0 + (-v).exp())
-            }
-            ("rot_0", Property::Float(v)) => self.rotation.rotation[0] = v,
-            ("rot_1", Property::Float(v)) => self.rotation.rotation[1] = v,
-            ("rot_2", Property::Float(v)) => self.rotation.rotation[2] = v,
-            ("rot_3", Property::Float(v)) => self.rotation.rotation[3] = v,
-            (_, Property::Float(v)) if key.starts_with("f_rest_") => {
-                let i = key[7..].parse::<usize>().unwrap();
+        let (key, property) = decode_half_property(key, property);
+
+        match key.as_str() {
+            "red" => self.color[0] = color_value(&property).unwrap_or_default(),
+            "green" => self.color[1] = color_value(&property).unwrap_or_default(),
+            "blue" => self.color[2] = color_value(&property).unwrap_or_default(),
+            "alpha" => self.alpha = color_value(&property),
+            _ => {
+                let Some(v) = property_value(&property) else {
+                    return;
+                };
 
-                // interleaved
-                // if (i + 3) < SH_COEFF_COUNT {
-                //     self.spherical_harmonic.coefficients[i + 3] = v;
-                // }
+                let gaussian = &mut self.gaussian;
+                match key.as_str() {
+                    "x" => gaussian.position_visibility.position[0] = v,
+                    "y" => gaussian.position_visibility.position[1] = v,
+                    "z" => gaussian.position_visibility.position[2] = v,
+                    "visibility" => gaussian.position_visibility.visibility = v,
+                    "f_dc_0" => gaussian.spherical_harmonic.set(0, v),
+                    "f_dc_1" => gaussian.spherical_harmonic.set(1, v),
+                    "f_dc_2" => gaussian.spherical_harmonic.set(2, v),
+                    "scale_0" => gaussian.scale_opacity.scale[0] = v,
+                    "scale_1" => gaussian.scale_opacity.scale[1] = v,
+                    "scale_2" => gaussian.scale_opacity.scale[2] = v,
+                    "opacity" => gaussian.scale_opacity.opacity = v,
+                    "rot_0" => gaussian.rotation.rotation[0] = v,
+                    "rot_1" => gaussian.rotation.rotation[1] = v,
+                    "rot_2" => gaussian.rotation.rotation[2] = v,
+                    "rot_3" => gaussian.rotation.rotation[3] = v,
+                    "albedo_0" | "base_color_0" => self.pbr.albedo[0] = v.clamp(0.0, 1.0),
+                    "albedo_1" | "base_color_1" => self.pbr.albedo[1] = v.clamp(0.0, 1.0),
+                    "albedo_2" | "base_color_2" => self.pbr.albedo[2] = v.clamp(0.0, 1.0),
+                    "roughness" => self.pbr.roughness = v.clamp(0.0, 1.0),
+                    "metallic" => self.pbr.metallic = v.clamp(0.0, 1.0),
+                    _ => {
+                        if let Some(slot) = key
+                            .strip_prefix("f_rest_")
+                            .and_then(|i| i.parse::<usize>().ok())
+                            .and_then(|i| self.rest.get_mut(i))
+                        {
+                            *slot = v;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
 
-                // planar
-                let channel = i / SH_COEFF_COUNT_PER_CHANNEL;
-                let coefficient = if SH_COEFF_COUNT_PER_CHANNEL == 1 {
-                    1
-                } else {
-                    (i % (SH_COEFF_COUNT_PER_CHANNEL - 1)) + 1
-                };
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PlyLayout3d {
+    Gaussian {
+        surfel: bool,
+        rest_per_channel: usize,
+    },
+    PointCloud,
+}
 
-                let interleaved_idx = coefficient * SH_CHANNELS + channel;
+impl PlyLayout3d {
+    fn detect(element: &ElementDef) -> Result<Self, std::io::Error> {
+        let has = |key: &str| {
+            element.properties.contains_key(key)
+                || element
+                    .properties
+                    .contains_key(&format!("{HALF_PROPERTY_PREFIX}{key}"))
+        };
+        let has_all = |keys: &[&str]| keys.iter().all(|key| has(key));
+
+        if !has_all(&["x", "y", "z"]) {
+            return Err(missing_properties());
+        }
 
-                if interleaved_idx < SH_COEFF_COUNT {
-                    self.spherical_harmonic.set(interleaved_idx, v);
-                } else {
-                    // TODO: convert higher degree SH to lower degree SH
+        if has_all(&["f_dc_0", "f_dc_1", "f_dc_2"]) {
+            if !has_all(&[
+                "scale_0", "scale_1", "opacity", "rot_0", "rot_1", "rot_2", "rot_3",
+            ]) {
+                return Err(missing_properties());
+            }
+
+            let rest_count = element
+                .properties
+                .keys()
+                .filter(|key| {
+                    key.trim_start_matches(HALF_PROPERTY_PREFIX)
+                        .starts_with("f_rest_")
+                })
+                .count();
+
+            return Ok(Self::Gaussian {
+                surfel: !has("scale_2"),
+                rest_per_channel: rest_count.min(MAX_PLY_REST_COEFFICIENTS) / SH_CHANNELS,
+            });
+        }
+
+        if has_all(&["red", "green", "blue"]) {
+            return Ok(Self::PointCloud);
+        }
+
+        Err(missing_properties())
+    }
+}
+
+/// relightable trainers store linear `albedo_*` (or `base_color_*`), `roughness` and `metallic`
+const PBR_PROPERTIES: [&str; 8] = [
+    "albedo_0",
+    "base_color_0",
+    "albedo_1",
+    "base_color_1",
+    "albedo_2",
+    "base_color_2",
+    "roughness",
+    "metallic",
+];
+
+fn has_pbr_properties(element: &ElementDef) -> bool {
+    element
+        .properties
+        .keys()
+        .any(|key| PBR_PROPERTIES.contains(&key.trim_start_matches(HALF_PROPERTY_PREFIX)))
+}
+
+fn missing_properties() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "missing required properties",
+    )
+}
+
+impl PlyVertex3d {
+    fn into_gaussian(
+        self,
+        layout: PlyLayout3d,
+        point_scale: f32,
+        settings: &GaussianLoaderSettings,
+    ) -> Gaussian3d {
+        let mut gaussian = self.gaussian;
+
+        match layout {
+            PlyLayout3d::Gaussian {
+                surfel,
+                rest_per_channel,
+            } => {
+                // bands above the compiled degree are dropped, the lower bands are unchanged
+                for (i, &value) in self.rest[..rest_per_channel * SH_CHANNELS]
+                    .iter()
+                    .enumerate()
+                {
+                    let channel = i / rest_per_channel;
+                    let coefficient = i % rest_per_channel + 1;
+                    let interleaved_idx = coefficient * SH_CHANNELS + channel;
+
+                    if coefficient < SH_COEFF_COUNT_PER_CHANNEL && interleaved_idx < SH_COEFF_COUNT
+                    {
+                        gaussian.spherical_harmonic.set(interleaved_idx, value);
+                    }
+                }
+
+                // plys store logits and log scales
+                settings.activate_3d(&mut gaussian, false, if surfel { 2 } else { 3 });
+            }
+            PlyLayout3d::PointCloud => {
+                for (channel, color) in self.color.iter().enumerate() {
+                    gaussian
+                        .spherical_harmonic
+                        .set(channel, (color - 0.5) / SH_C0);
                 }
+
+                gaussian.scale_opacity.scale = [point_scale; 3];
+                gaussian.scale_opacity.opacity = self.alpha.unwrap_or(1.0);
             }
-            (_, _) => {}
         }
+
+        gaussian.rotation.rotation =
+            normalized_or_identity(gaussian.rotation.rotation, [1.0, 0.0, 0.0, 0.0]);
+
+        gaussian
+    }
+}
+
+/// isotropic size for point cloud plys, about half the mean point spacing
+fn point_cloud_scale(vertices: &[PlyVertex3d]) -> f32 {
+    let (min, max) = vertices.iter().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), vertex| {
+            let position = Vec3::from(vertex.gaussian.position_visibility.position);
+            (min.min(position), max.max(position))
+        },
+    );
+
+    let extent = (max - min).max_element();
+    if vertices.is_empty() || !extent.is_finite() || extent <= 0.0 {
+        return 0.01;
     }
+
+    0.5 * extent / (vertices.len() as f32).cbrt()
 }
 
-pub fn parse_ply_3d(mut reader: &mut dyn BufRead) -> Result<PlanarGaussian3d, std::io::Error> {
-    let gaussian_parser = Parser::<Gaussian3d>::new();
+/// 2dgs files omit `scale_2`, see `PlanarGaussian3d::is_gaussian_2d`
+pub fn parse_ply_3d(reader: &mut dyn BufRead) -> Result<PlanarGaussian3d, std::io::Error> {
+    parse_ply_3d_with_settings(reader, &GaussianLoaderSettings::default())
+}
+
+pub fn parse_ply_3d_with_settings(
+    reader: &mut dyn BufRead,
+    settings: &GaussianLoaderSettings,
+) -> Result<PlanarGaussian3d, std::io::Error> {
+    Ok(parse_ply_3d_relightable(reader, settings)?.0)
+}
+
+/// also returns the relightable attributes when the vertices carry any, indexed like the cloud
+pub fn parse_ply_3d_relightable(
+    reader: &mut dyn BufRead,
+    settings: &GaussianLoaderSettings,
+) -> Result<(PlanarGaussian3d, Option<PbrMaterial>), std::io::Error> {
+    let mut reader = with_half_properties(reader)?;
+
+    let gaussian_parser = Parser::<PlyVertex3d>::new();
     let header = gaussian_parser.read_header(&mut reader)?;
 
-    let mut cloud = Vec::new();
+    if is_spacetime_ply(&header) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "spacetime gaussian ply, load it as a PlanarGaussianSpacetime",
+        ));
+    }
 
-    let required_properties = vec![
-        "x", "y", "z", "f_dc_0", "f_dc_1", "f_dc_2", "scale_0", "scale_1", "opacity", "rot_0",
-        "rot_1", "rot_2", "rot_3",
-    ];
-    let mut required_property_count = required_properties.len();
+    if is_compressed_ply(&header) {
+        let chunked = read_compressed_payload(&mut reader, &header)?;
+        let cloud = PlanarGaussian3d::from_interleaved(chunked.iter().collect());
+
+        return Ok((settings.apply_3d(cloud), None));
+    }
+
+    let mut cloud = Vec::new();
+    let mut material = None;
 
     for (_key, element) in &header.elements {
         if element.name == "vertex" {
-            for (key, _prop) in &element.properties {
-                required_property_count -= required_properties.contains(&key.as_str()) as usize;
+            let layout = PlyLayout3d::detect(element)?;
+            let vertices =
+                gaussian_parser.read_payload_for_element(&mut reader, element, &header)?;
+
+            let point_scale = match layout {
+                PlyLayout3d::PointCloud => point_cloud_scale(&vertices),
+                PlyLayout3d::Gaussian { .. } => 0.0,
+            };
+
+            if has_pbr_properties(element) {
+                material = Some(PbrMaterial {
+                    attributes: vertices.iter().map(|vertex| vertex.pbr).collect(),
+                });
             }
 
-            if required_property_count > 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "missing required properties",
-                ));
-            }
-
-            cloud = gaussian_parser.read_payload_for_element(&mut reader, element, &header)?;
+            cloud = vertices
+                .into_iter()
+                .map(|vertex| vertex.into_gaussian(layout, point_scale, settings))
+                .collect();
         }
     }
 
-    for gaussian in &mut cloud {
-        // TODO: add automatic scaling normalization detection (e.g. don't normalize twice)
-        let mean_scale = (gaussian.scale_opacity.scale[0]
-            + gaussian.scale_opacity.scale[1]
-            + gaussian.scale_opacity.scale[2])
-            / 3.0;
-        for i in 0..3 {
-            gaussian.scale_opacity.scale[i] = gaussian.scale_opacity.scale[i]
-                .max(mean_scale - MAX_SIZE_VARIANCE)
-                .min(mean_scale + MAX_SIZE_VARIANCE)
-                .exp();
-        }
+    // attributes follow the gaussians through pruning and padding
+    if let Some(material) = &mut material {
+        let mut retained = cloud.iter().map(|gaussian| settings.retains_3d(gaussian));
+        material
+            .attributes
+            .retain(|_| retained.next().unwrap_or(false));
+    }
 
-        let norm = (0..4)
-            .map(|i| gaussian.rotation.rotation[i].powf(2.0))
-            .sum::<f32>()
-            .sqrt();
-        for i in 0..4 {
-            gaussian.rotation.rotation[i] /= norm;
-        }
+    settings.process_3d(&mut cloud);
+
+    // pad with empty gaussians to a multiple of the sort workgroup size
+    settings.pad(&mut cloud);
+
+    if let Some(material) = &mut material {
+        settings.pad(&mut material.attributes);
     }
 
-    // pad with empty gaussians to multiple of 32
-    let pad = 32 - (cloud.len() % 32);
-    cloud.extend(std::iter::repeat_n(Gaussian3d::default(), pad));
+    Ok((PlanarGaussian3d::from_interleaved(cloud), material))
+}
+
+/// switches clouds loaded from 2dgs plys to `GaussianMode::Gaussian2d`
+pub fn apply_gaussian_2d_mode(
+    mut asset_events: MessageReader<AssetEvent<PlanarGaussian3d>>,
+    clouds: Res<Assets<PlanarGaussian3d>>,
+    mut settings: Query<(&PlanarGaussian3dHandle, &mut CloudSettings)>,
+) {
+    for event in asset_events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+
+        let Some(cloud) = clouds.get(*id) else {
+            continue;
+        };
+        if !cloud.is_gaussian_2d() {
+            continue;
+        }
 
-    Ok(PlanarGaussian3d::from_interleaved(cloud))
+        for (handle, mut settings) in settings.iter_mut() {
+            if handle.0.id() == *id && settings.gaussian_mode == GaussianMode::Gaussian3d {
+                settings.gaussian_mode = GaussianMode::Gaussian2d;
+            }
+        }
+    }
 }
 
 impl PropertyAccess for Gaussian4d {
@@ -137,16 +449,21 @@ impl PropertyAccess for Gaussian4d {
     }
 
     fn set_property(&mut self, key: String, property: Property) {
-        match (key.as_ref(), property) {
-            ("x", Property::Float(v)) => self.position_visibility.position[0] = v,
-            ("y", Property::Float(v)) => self.position_visibility.position[1] = v,
-            ("z", Property::Float(v)) => self.position_visibility.position[2] = v,
-            ("visibility", Property::Float(v)) => self.position_visibility.visibility = v,
+        let (key, property) = decode_half_property(key, property);
+        let Some(v) = property_value(&property) else {
+            return;
+        };
+
+        match key.as_str() {
+            "x" => self.position_visibility.position[0] = v,
+            "y" => self.position_visibility.position[1] = v,
+            "z" => self.position_visibility.position[2] = v,
+            "visibility" => self.position_visibility.visibility = v,
 
-            ("t", Property::Float(v)) => self.timestamp_timescale.timestamp = v,
-            ("st", Property::Float(v)) => self.timestamp_timescale.timescale = v,
+            "t" => self.timestamp_timescale.timestamp = v,
+            "st" => self.timestamp_timescale.timescale = v,
 
-            (_, Property::Float(v)) if key.starts_with("feat_") => {
+            _ if key.starts_with("feat_") => {
                 let channel = match key.chars().nth(5).unwrap() {
                     'r' => 0,
                     'g' => 1,
@@ -163,26 +480,35 @@ impl PropertyAccess for Gaussian4d {
                 }
             }
 
-            ("sx", Property::Float(v)) => self.scale_opacity.scale[0] = v,
-            ("sy", Property::Float(v)) => self.scale_opacity.scale[1] = v,
-            ("sz", Property::Float(v)) => self.scale_opacity.scale[2] = v,
-            ("opacity", Property::Float(v)) => self.scale_opacity.opacity = v,
+            "sx" => self.scale_opacity.scale[0] = v,
+            "sy" => self.scale_opacity.scale[1] = v,
+            "sz" => self.scale_opacity.scale[2] = v,
+            "opacity" => self.scale_opacity.opacity = v,
 
-            ("rot_x", Property::Float(v)) => self.isotropic_rotations.rotation[0] = v,
-            ("rot_y", Property::Float(v)) => self.isotropic_rotations.rotation[1] = v,
-            ("rot_z", Property::Float(v)) => self.isotropic_rotations.rotation[2] = v,
-            ("rot_w", Property::Float(v)) => self.isotropic_rotations.rotation[3] = v,
+            "rot_x" => self.isotropic_rotations.rotation[0] = v,
+            "rot_y" => self.isotropic_rotations.rotation[1] = v,
+            "rot_z" => self.isotropic_rotations.rotation[2] = v,
+            "rot_w" => self.isotropic_rotations.rotation[3] = v,
 
-            ("rot_r_x", Property::Float(v)) => self.isotropic_rotations.rotation_r[0] = v,
-            ("rot_r_y", Property::Float(v)) => self.isotropic_rotations.rotation_r[1] = v,
-            ("rot_r_z", Property::Float(v)) => self.isotropic_rotations.rotation_r[2] = v,
-            ("rot_r_w", Property::Float(v)) => self.isotropic_rotations.rotation_r[3] = v,
+            "rot_r_x" => self.isotropic_rotations.rotation_r[0] = v,
+            "rot_r_y" => self.isotropic_rotations.rotation_r[1] = v,
+            "rot_r_z" => self.isotropic_rotations.rotation_r[2] = v,
+            "rot_r_w" => self.isotropic_rotations.rotation_r[3] = v,
             _ => {}
         }
     }
 }
 
-pub fn parse_ply_4d(mut reader: &mut dyn BufRead) -> Result<PlanarGaussian4d, std::io::Error> {
+pub fn parse_ply_4d(reader: &mut dyn BufRead) -> Result<PlanarGaussian4d, std::io::Error> {
+    parse_ply_4d_with_settings(reader, &GaussianLoaderSettings::default())
+}
+
+pub fn parse_ply_4d_with_settings(
+    reader: &mut dyn BufRead,
+    settings: &GaussianLoaderSettings,
+) -> Result<PlanarGaussian4d, std::io::Error> {
+    let mut reader = with_half_properties(reader)?;
+
     let parser = Parser::<Gaussian4d>::new();
     let header = parser.read_header(&mut reader)?;
 
@@ -197,7 +523,8 @@ pub fn parse_ply_4d(mut reader: &mut dyn BufRead) -> Result<PlanarGaussian4d, st
     for (_key, element) in &header.elements {
         if element.name == "vertex" {
             for (key, _prop) in &element.properties {
-                required_property_count -= required_properties.contains(&key.as_str()) as usize;
+                let key = key.trim_start_matches(HALF_PROPERTY_PREFIX);
+                required_property_count -= required_properties.contains(&key) as usize;
             }
 
             if required_property_count > 0 {
@@ -236,12 +563,358 @@ pub fn parse_ply_4d(mut reader: &mut dyn BufRead) -> Result<PlanarGaussian4d, st
             *v /= norm;
         }
 
+        settings.activate_4d(g);
+
         // TODO: normalize timescale between 0 and 1
     }
 
-    // pad to multiple of 32
-    let pad = 32 - (cloud.len() % 32);
-    cloud.extend(std::iter::repeat_n(Gaussian4d::default(), pad));
+    settings.process_4d(&mut cloud);
+
+    // pad to multiple of the sort workgroup size
+    settings.pad(&mut cloud);
 
     Ok(PlanarGaussian4d::from_interleaved(cloud))
 }
+
+impl PropertyAccess for GaussianSpacetime {
+    fn new() -> Self {
+        GaussianSpacetime::default()
+    }
+
+    fn set_property(&mut self, key: String, property: Property) {
+        let (key, property) = decode_half_property(key, property);
+        let Some(v) = property_value(&property) else {
+            return;
+        };
+
+        match key.as_str() {
+            "x" => self.position_visibility.position[0] = v,
+            "y" => self.position_visibility.position[1] = v,
+            "z" => self.position_visibility.position[2] = v,
+            "visibility" => self.position_visibility.visibility = v,
+
+            "trbf_center" => self.motion.trbf_center = v,
+            "trbf_scale" => self.motion.trbf_scale = v,
+            _ if key.starts_with("motion_") => {
+                if let Some(coefficient) = key[7..]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| self.motion.motion.get_mut(i))
+                {
+                    *coefficient = v;
+                }
+            }
+
+            // features are read as the sh dc band, the color mlp of the full model is not supported
+            "f_dc_0" => self.spherical_harmonic.set(0, v),
+            "f_dc_1" => self.spherical_harmonic.set(1, v),
+            "f_dc_2" => self.spherical_harmonic.set(2, v),
+
+            "scale_0" => self.scale_opacity.scale[0] = v,
+            "scale_1" => self.scale_opacity.scale[1] = v,
+            "scale_2" => self.scale_opacity.scale[2] = v,
+            "opacity" => self.scale_opacity.opacity = v,
+
+            // left unnormalized, the shader normalizes after applying the angular velocity
+            "rot_0" => self.rotation.rotation[0] = v,
+            "rot_1" => self.rotation.rotation[1] = v,
+            "rot_2" => self.rotation.rotation[2] = v,
+            "rot_3" => self.rotation.rotation[3] = v,
+
+            "omega_0" => self.angular_velocity.omega[0] = v,
+            "omega_1" => self.angular_velocity.omega[1] = v,
+            "omega_2" => self.angular_velocity.omega[2] = v,
+            "omega_3" => self.angular_velocity.omega[3] = v,
+            _ => {}
+        }
+    }
+}
+
+pub fn parse_ply_spacetime(
+    reader: &mut dyn BufRead,
+) -> Result<PlanarGaussianSpacetime, std::io::Error> {
+    parse_ply_spacetime_with_settings(reader, &GaussianLoaderSettings::default())
+}
+
+/// true if the header describes a SpacetimeGaussians ply, its vertices carry a temporal rbf
+pub fn is_spacetime_ply(header: &Header) -> bool {
+    header.elements.get("vertex").is_some_and(|vertex| {
+        vertex
+            .properties
+            .keys()
+            .any(|key| key.trim_start_matches(HALF_PROPERTY_PREFIX) == "trbf_center")
+    })
+}
+
+/// spacetime plys store logits and log scales like 3d plys
+pub fn parse_ply_spacetime_with_settings(
+    reader: &mut dyn BufRead,
+    settings: &GaussianLoaderSettings,
+) -> Result<PlanarGaussianSpacetime, std::io::Error> {
+    let mut reader = with_half_properties(reader)?;
+
+    let parser = Parser::<GaussianSpacetime>::new();
+    let header = parser.read_header(&mut reader)?;
+
+    if !is_spacetime_ply(&header) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a spacetime gaussian ply, the vertices carry no `trbf_center`",
+        ));
+    }
+
+    let mut cloud = Vec::new();
+
+    let required_properties = vec![
+        "x",
+        "y",
+        "z",
+        "trbf_center",
+        "trbf_scale",
+        "motion_0",
+        "motion_1",
+        "motion_2",
+        "motion_3",
+        "motion_4",
+        "motion_5",
+        "motion_6",
+        "motion_7",
+        "motion_8",
+        "f_dc_0",
+        "f_dc_1",
+        "f_dc_2",
+        "opacity",
+        "scale_0",
+        "scale_1",
+        "scale_2",
+        "rot_0",
+        "rot_1",
+        "rot_2",
+        "rot_3",
+        "omega_0",
+        "omega_1",
+        "omega_2",
+        "omega_3",
+    ];
+    let mut required_property_count = required_properties.len();
+
+    for (_key, element) in &header.elements {
+        if element.name == "vertex" {
+            for (key, _prop) in &element.properties {
+                let key = key.trim_start_matches(HALF_PROPERTY_PREFIX);
+                required_property_count -= required_properties.contains(&key) as usize;
+            }
+
+            if required_property_count > 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "missing required properties",
+                ));
+            }
+
+            cloud = parser.read_payload_for_element(&mut reader, element, &header)?;
+        }
+    }
+
+    for gaussian in cloud.iter_mut() {
+        settings.activate_spacetime(gaussian, false);
+    }
+
+    settings.process_spacetime(&mut cloud);
+
+    // pad to multiple of the sort workgroup size
+    settings.pad(&mut cloud);
+
+    Ok(PlanarGaussianSpacetime::from_interleaved(cloud))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlyEncoding {
+    Ascii,
+    #[default]
+    BinaryLittleEndian,
+}
+
+impl PlyEncoding {
+    fn format(&self) -> &'static str {
+        match self {
+            PlyEncoding::Ascii => "ascii",
+            PlyEncoding::BinaryLittleEndian => "binary_little_endian",
+        }
+    }
+}
+
+fn write_ply_header(
+    writer: &mut dyn Write,
+    encoding: PlyEncoding,
+    count: usize,
+    properties: &[String],
+) -> Result<(), std::io::Error> {
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", encoding.format())?;
+    writeln!(writer, "element vertex {count}")?;
+    for property in properties {
+        writeln!(writer, "property float {property}")?;
+    }
+    writeln!(writer, "end_header")
+}
+
+fn write_ply_row(
+    writer: &mut dyn Write,
+    encoding: PlyEncoding,
+    row: &[f32],
+) -> Result<(), std::io::Error> {
+    match encoding {
+        PlyEncoding::Ascii => {
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    write!(writer, " ")?;
+                }
+                write!(writer, "{value}")?;
+            }
+            writeln!(writer)
+        }
+        PlyEncoding::BinaryLittleEndian => {
+            for value in row {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn normalized_or_identity(rotation: [f32; 4], identity: [f32; 4]) -> [f32; 4] {
+    let norm = rotation.iter().map(|v| v.powi(2)).sum::<f32>().sqrt();
+    if norm > 0.0 && norm.is_finite() {
+        rotation.map(|v| v / norm)
+    } else {
+        identity
+    }
+}
+
+/// writes logit opacity and log scales like the trainers, `parse_ply_3d` applies sigmoid and exp
+/// on load so written clouds read back unchanged
+pub fn write_ply_3d(
+    cloud: &PlanarGaussian3d,
+    writer: &mut dyn Write,
+    encoding: PlyEncoding,
+) -> Result<(), std::io::Error> {
+    let rest_per_channel = SH_COEFF_COUNT_PER_CHANNEL - 1;
+
+    let mut properties = [
+        "x", "y", "z", "nx", "ny", "nz", "f_dc_0", "f_dc_1", "f_dc_2",
+    ]
+    .map(String::from)
+    .to_vec();
+    properties.extend((0..rest_per_channel * SH_CHANNELS).map(|i| format!("f_rest_{i}")));
+    // 2dgs files are told apart by the missing scale_2
+    let surfel = cloud.is_gaussian_2d();
+    properties.extend(
+        [
+            "opacity", "scale_0", "scale_1", "scale_2", "rot_0", "rot_1", "rot_2", "rot_3",
+        ]
+        .into_iter()
+        .filter(|property| !(surfel && *property == "scale_2"))
+        .map(String::from),
+    );
+
+    // loaded clouds are padded, the padding is not part of the file
+    let count = unpadded_len(cloud.len(), |index| cloud.get(index));
+
+    let mut writer = std::io::BufWriter::new(writer);
+    write_ply_header(&mut writer, encoding, count, &properties)?;
+
+    let mut row = Vec::with_capacity(properties.len());
+    for gaussian in (0..count).map(|index| cloud.get(index)) {
+        row.clear();
+
+        row.extend_from_slice(&gaussian.position_visibility.position);
+        row.extend_from_slice(&[0.0; 3]);
+
+        // interleaved to planar, dc first
+        row.extend((0..SH_CHANNELS).map(|channel| gaussian.spherical_harmonic.get(channel)));
+        for channel in 0..SH_CHANNELS {
+            row.extend((1..=rest_per_channel).map(|coefficient| {
+                gaussian
+                    .spherical_harmonic
+                    .get(coefficient * SH_CHANNELS + channel)
+            }));
+        }
+
+        let opacity = gaussian
+            .scale_opacity
+            .opacity
+            .clamp(f32::EPSILON, 1.0 - f32::EPSILON);
+        row.push((opacity / (1.0 - opacity)).ln());
+
+        let axes = if surfel { 2 } else { 3 };
+        row.extend(
+            gaussian.scale_opacity.scale[..axes]
+                .iter()
+                .map(|scale| scale.max(f32::MIN_POSITIVE).ln()),
+        );
+
+        row.extend(normalized_or_identity(
+            gaussian.rotation.rotation,
+            [1.0, 0.0, 0.0, 0.0],
+        ));
+
+        write_ply_row(&mut writer, encoding, &row)?;
+    }
+
+    writer.flush()
+}
+
+pub fn write_ply_4d(
+    cloud: &PlanarGaussian4d,
+    writer: &mut dyn Write,
+    encoding: PlyEncoding,
+) -> Result<(), std::io::Error> {
+    let mut properties = ["x", "y", "z", "t", "st"].map(String::from).to_vec();
+    for i in 0..SH_4D_COEFF_COUNT_PER_CHANNEL {
+        properties.extend(["r", "g", "b"].map(|channel| format!("feat_{channel}_{i}")));
+    }
+    properties.extend(
+        [
+            "sx", "sy", "sz", "opacity", "rot_x", "rot_y", "rot_z", "rot_w", "rot_r_x", "rot_r_y",
+            "rot_r_z", "rot_r_w",
+        ]
+        .map(String::from),
+    );
+
+    // loaded clouds are padded, the padding is not part of the file
+    let count = unpadded_len(cloud.len(), |index| cloud.get(index));
+
+    let mut writer = std::io::BufWriter::new(writer);
+    write_ply_header(&mut writer, encoding, count, &properties)?;
+
+    let mut row = Vec::with_capacity(properties.len());
+    for gaussian in (0..count).map(|index| cloud.get(index)) {
+        row.clear();
+
+        row.extend_from_slice(&gaussian.position_visibility.position);
+        row.push(gaussian.timestamp_timescale.timestamp);
+        row.push(gaussian.timestamp_timescale.timescale);
+
+        row.extend(
+            (0..SH_4D_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS)
+                .map(|i| gaussian.spherindrical_harmonic.get(i)),
+        );
+
+        row.extend_from_slice(&gaussian.scale_opacity.scale);
+        row.push(gaussian.scale_opacity.opacity);
+
+        row.extend(normalized_or_identity(
+            gaussian.isotropic_rotations.rotation,
+            [0.0, 0.0, 0.0, 1.0],
+        ));
+        row.extend(normalized_or_identity(
+            gaussian.isotropic_rotations.rotation_r,
+            [0.0, 0.0, 0.0, 1.0],
+        ));
+
+        write_ply_row(&mut writer, encoding, &row)?;
+    }
+
+    writer.flush()
+}
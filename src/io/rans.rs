@@ -0,0 +1,153 @@
+// https://github.com/rygorous/ryg_rans
+// order-0 byte-wise rANS with the normalized frequency table stored ahead of the stream
+use std::io::ErrorKind;
+
+const PROB_BITS: u32 = 12;
+const PROB_SCALE: u32 = 1 << PROB_BITS;
+const RANS_L: u32 = 1 << 23;
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, format!("rans stream {message}"))
+}
+
+/// scales symbol counts to `PROB_SCALE`, keeping every present symbol representable
+fn normalize_frequencies(counts: &[u32; 256]) -> [u32; 256] {
+    let total = counts.iter().map(|&count| count as u64).sum::<u64>();
+    let mut frequencies = [0u32; 256];
+    if total == 0 {
+        return frequencies;
+    }
+
+    for (frequency, &count) in frequencies.iter_mut().zip(counts) {
+        if count > 0 {
+            *frequency = ((count as u64 * PROB_SCALE as u64 / total) as u32).max(1);
+        }
+    }
+
+    let mut sum = frequencies.iter().sum::<u32>();
+    while sum != PROB_SCALE {
+        let (largest, _) = frequencies
+            .iter()
+            .enumerate()
+            .filter(|&(_, &frequency)| sum < PROB_SCALE || frequency > 1)
+            .max_by_key(|&(_, &frequency)| frequency)
+            .expect("at least one symbol is present");
+
+        if sum < PROB_SCALE {
+            frequencies[largest] += 1;
+            sum += 1;
+        } else {
+            frequencies[largest] -= 1;
+            sum -= 1;
+        }
+    }
+
+    frequencies
+}
+
+fn cumulative(frequencies: &[u32; 256]) -> [u32; 256] {
+    let mut starts = [0u32; 256];
+    let mut start = 0;
+    for (symbol, &frequency) in frequencies.iter().enumerate() {
+        starts[symbol] = start;
+        start += frequency;
+    }
+    starts
+}
+
+/// encodes `symbols`, the decoder needs the symbol count
+pub fn encode(symbols: &[u8]) -> Vec<u8> {
+    let mut counts = [0u32; 256];
+    for &symbol in symbols {
+        counts[symbol as usize] += 1;
+    }
+    let frequencies = normalize_frequencies(&counts);
+    let starts = cumulative(&frequencies);
+
+    let mut data = Vec::new();
+    let present = frequencies
+        .iter()
+        .filter(|&&frequency| frequency > 0)
+        .count();
+    data.extend_from_slice(&(present as u16).to_le_bytes());
+    for (symbol, &frequency) in frequencies.iter().enumerate() {
+        if frequency > 0 {
+            data.push(symbol as u8);
+            data.extend_from_slice(&(frequency as u16).to_le_bytes());
+        }
+    }
+
+    // rans is last in first out, the stream is written backwards then flipped
+    let mut reversed = Vec::new();
+    let mut state = RANS_L;
+    for &symbol in symbols.iter().rev() {
+        let frequency = frequencies[symbol as usize];
+        let state_max = ((RANS_L >> PROB_BITS) << 8) * frequency;
+        while state >= state_max {
+            reversed.push(state as u8);
+            state >>= 8;
+        }
+
+        state = ((state / frequency) << PROB_BITS) + state % frequency + starts[symbol as usize];
+    }
+    reversed.extend_from_slice(&state.to_be_bytes());
+
+    data.extend(reversed.into_iter().rev());
+    data
+}
+
+/// decodes `len` symbols written by `encode`
+pub fn decode(data: &[u8], len: usize) -> Result<Vec<u8>, std::io::Error> {
+    let mut cursor = 0usize;
+    let mut take = |count: usize| {
+        let bytes = data
+            .get(cursor..cursor + count)
+            .ok_or_else(|| invalid("is truncated"))?;
+        cursor += count;
+        Ok::<_, std::io::Error>(bytes)
+    };
+
+    let present = u16::from_le_bytes(take(2)?.try_into().unwrap()) as usize;
+    let mut frequencies = [0u32; 256];
+    for _ in 0..present {
+        let entry = take(3)?;
+        frequencies[entry[0] as usize] = u16::from_le_bytes([entry[1], entry[2]]) as u32;
+    }
+
+    let symbols_present = frequencies.iter().any(|&frequency| frequency > 0);
+    if frequencies.iter().sum::<u32>() != PROB_SCALE && (len > 0 || symbols_present) {
+        return Err(invalid("has an invalid frequency table"));
+    }
+
+    let starts = cumulative(&frequencies);
+    let mut slots = vec![0u8; PROB_SCALE as usize];
+    for (symbol, (&start, &frequency)) in starts.iter().zip(&frequencies).enumerate() {
+        slots[start as usize..(start + frequency) as usize].fill(symbol as u8);
+    }
+
+    let mut state = u32::from_le_bytes(take(4)?.try_into().unwrap());
+    // `len` comes from the caller's file, a constant stream codes any length in a few bytes so
+    // the reservation is fallible rather than trusted
+    let mut symbols = Vec::new();
+    symbols
+        .try_reserve_exact(len)
+        .map_err(|_| invalid("length does not fit in memory"))?;
+    for _ in 0..len {
+        let slot = state & (PROB_SCALE - 1);
+        let symbol = slots[slot as usize];
+        state =
+            frequencies[symbol as usize] * (state >> PROB_BITS) + slot - starts[symbol as usize];
+
+        while state < RANS_L {
+            state = (state << 8) | take(1)?[0] as u32;
+        }
+
+        symbols.push(symbol);
+    }
+
+    if cursor != data.len() {
+        return Err(invalid("has trailing bytes"));
+    }
+
+    Ok(symbols)
+}
@@ -7,8 +7,10 @@ use base64::Engine as _;
 use bevy::reflect::TypePath;
 use bevy::{
     asset::{AssetLoader, AssetPath, LoadContext, io::Reader},
+    camera::ScalingMode,
     prelude::*,
 };
+use bevy_interleave::prelude::Planar;
 use gltf::{
     Accessor,
     accessor::{DataType, Dimensions, Item, Iter},
@@ -17,15 +19,24 @@ use gltf::{
 use serde::Deserialize;
 use serde_json::{Value, json};
 
+use crate::camera::GaussianCamera;
 use crate::gaussian::{
-    formats::planar_3d::{Gaussian3d, PlanarGaussian3d, PlanarGaussian3dHandle},
+    f32::IsotropicRotations,
+    formats::{
+        planar_3d::{Gaussian3d, PlanarGaussian3d, PlanarGaussian3dHandle},
+        planar_4d::{Gaussian4d, PlanarGaussian4d, PlanarGaussian4dHandle},
+    },
     settings::{CloudSettings, GaussianColorSpace, GaussianMode},
 };
-use crate::material::spherical_harmonics::{
-    SH_CHANNELS, SH_COEFF_COUNT, SH_COEFF_COUNT_PER_CHANNEL,
+use crate::io::meshopt::{self, MeshoptFilter};
+use crate::material::{
+    spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT, SH_COEFF_COUNT_PER_CHANNEL},
+    spherindrical_harmonics::{SH_4D_DEGREE_TIME, SpherindricalHarmonicCoefficients},
 };
 
 const KHR_GAUSSIAN_SPLATTING_EXTENSION: &str = "KHR_gaussian_splatting";
+const KHR_MESH_QUANTIZATION_EXTENSION: &str = "KHR_mesh_quantization";
+const EXT_MESHOPT_COMPRESSION_EXTENSION: &str = "EXT_meshopt_compression";
 
 const ATTR_POSITION: &str = "POSITION";
 const ATTR_COLOR_0: &str = "COLOR_0";
@@ -35,6 +46,15 @@ const ATTR_OPACITY: &str = "KHR_gaussian_splatting:OPACITY";
 const ATTR_SH_PREFIX: &str = "KHR_gaussian_splatting:SH_DEGREE_";
 const SH_DEGREE_ZERO_BASIS: f32 = 0.282_095;
 
+// 4d primitives nest this extension in the KHR_gaussian_splatting object, the KHR
+// attributes hold the spatial part so viewers without it still show a static cloud
+const EXT_GAUSSIAN_SPLATTING_4D: &str = "EXT_gaussian_splatting_4d";
+
+const ATTR_TIMESTAMP: &str = "EXT_gaussian_splatting_4d:TIMESTAMP";
+const ATTR_TIMESCALE: &str = "EXT_gaussian_splatting_4d:TIMESCALE";
+const ATTR_ROTATION_R: &str = "EXT_gaussian_splatting_4d:ROTATION_R";
+const ATTR_SH_TIME_PREFIX: &str = "EXT_gaussian_splatting_4d:SH_TIME_";
+
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect)]
 pub enum GaussianKernel {
     #[default]
@@ -83,19 +103,136 @@ pub struct GaussianPrimitiveMetadata {
     pub spec: GaussianPrimitiveSpec,
 }
 
+#[derive(Clone, Debug, Reflect)]
+pub enum SceneCloudHandle {
+    Gaussian3d(Handle<PlanarGaussian3d>),
+    Gaussian4d(Handle<PlanarGaussian4d>),
+}
+
+impl Default for SceneCloudHandle {
+    fn default() -> Self {
+        Self::Gaussian3d(Handle::default())
+    }
+}
+
+impl SceneCloudHandle {
+    pub fn as_3d(&self) -> Option<&Handle<PlanarGaussian3d>> {
+        match self {
+            Self::Gaussian3d(handle) => Some(handle),
+            Self::Gaussian4d(_) => None,
+        }
+    }
+
+    pub fn as_4d(&self) -> Option<&Handle<PlanarGaussian4d>> {
+        match self {
+            Self::Gaussian3d(_) => None,
+            Self::Gaussian4d(handle) => Some(handle),
+        }
+    }
+}
+
+/// 4d bundles carry their playback range in `settings.time_start` and `settings.time_stop`
 #[derive(Clone, Debug, Default, Reflect)]
 pub struct CloudBundle {
-    pub cloud: Handle<PlanarGaussian3d>,
+    pub cloud: SceneCloudHandle,
     pub name: String,
     pub settings: CloudSettings,
     pub transform: Transform,
     pub metadata: GaussianPrimitiveMetadata,
 }
 
+/// glTF camera parameters, `None` fields were absent in the source document
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub enum SceneCameraProjection {
+    Perspective {
+        yfov: f32,
+        aspect_ratio: Option<f32>,
+        znear: f32,
+        /// infinite projection when `None`
+        zfar: Option<f32>,
+    },
+    Orthographic {
+        /// half extents of the view volume
+        xmag: f32,
+        ymag: f32,
+        znear: f32,
+        zfar: f32,
+    },
+}
+
+impl Default for SceneCameraProjection {
+    fn default() -> Self {
+        Self::Perspective {
+            yfov: std::f32::consts::FRAC_PI_4,
+            aspect_ratio: None,
+            znear: 0.01,
+            zfar: Some(1000.0),
+        }
+    }
+}
+
+impl SceneCameraProjection {
+    /// infinite perspective cameras fall back to bevy's default far plane
+    pub fn to_projection(&self) -> Projection {
+        match *self {
+            Self::Perspective {
+                yfov,
+                aspect_ratio,
+                znear,
+                zfar,
+            } => {
+                let default = PerspectiveProjection::default();
+                Projection::Perspective(PerspectiveProjection {
+                    fov: yfov,
+                    aspect_ratio: aspect_ratio.unwrap_or(default.aspect_ratio),
+                    near: znear,
+                    far: zfar.unwrap_or(default.far),
+                    near_clip_plane: Vec4::new(0.0, 0.0, -1.0, -znear),
+                })
+            }
+            Self::Orthographic {
+                xmag,
+                ymag,
+                znear,
+                zfar,
+            } => Projection::Orthographic(OrthographicProjection {
+                near: znear,
+                far: zfar,
+                scaling_mode: ScalingMode::Fixed {
+                    width: xmag * 2.0,
+                    height: ymag * 2.0,
+                },
+                area: Rect::new(-xmag, -ymag, xmag, ymag),
+                ..OrthographicProjection::default_3d()
+            }),
+        }
+    }
+
+    /// custom projections have no glTF equivalent
+    pub fn from_projection(projection: &Projection) -> Option<Self> {
+        match projection {
+            Projection::Perspective(perspective) => Some(Self::Perspective {
+                yfov: perspective.fov,
+                aspect_ratio: Some(perspective.aspect_ratio),
+                znear: perspective.near,
+                zfar: Some(perspective.far),
+            }),
+            Projection::Orthographic(orthographic) => Some(Self::Orthographic {
+                xmag: orthographic.area.width() / 2.0,
+                ymag: orthographic.area.height() / 2.0,
+                znear: orthographic.near,
+                zfar: orthographic.far,
+            }),
+            Projection::Custom(_) => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Reflect)]
 pub struct SceneCamera {
     pub name: String,
     pub transform: Transform,
+    pub projection: SceneCameraProjection,
 }
 
 #[derive(Asset, Clone, Debug, Default, Reflect)]
@@ -104,22 +241,50 @@ pub struct GaussianScene {
     pub cameras: Vec<SceneCamera>,
 }
 
+#[derive(Clone, Debug)]
+pub enum SceneExportGaussians {
+    Gaussian3d(PlanarGaussian3d),
+    Gaussian4d(PlanarGaussian4d),
+}
+
+impl From<PlanarGaussian3d> for SceneExportGaussians {
+    fn from(cloud: PlanarGaussian3d) -> Self {
+        Self::Gaussian3d(cloud)
+    }
+}
+
+impl From<PlanarGaussian4d> for SceneExportGaussians {
+    fn from(cloud: PlanarGaussian4d) -> Self {
+        Self::Gaussian4d(cloud)
+    }
+}
+
+/// 4d clouds export `settings.time_start..settings.time_stop` as their playback range
 #[derive(Clone, Debug)]
 pub struct SceneExportCloud {
-    pub cloud: PlanarGaussian3d,
+    pub cloud: SceneExportGaussians,
     pub name: String,
     pub settings: CloudSettings,
     pub transform: Transform,
     pub metadata: GaussianPrimitiveMetadata,
 }
 
+/// size options for KHR scene export, the default writes plain f32 accessors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SceneExportOptions {
+    /// `KHR_mesh_quantization`, normalized i16 rotations, u8 opacity, u8 sh dc color and i16
+    /// higher sh bands, sh accessors are fitted to their range with an `extras` offset and scale,
+    /// positions and scales stay f32 as they are unbounded
+    pub quantize: bool,
+    /// `EXT_meshopt_compression` buffer views in ATTRIBUTES mode
+    pub meshopt: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct SceneExportCamera {
     pub name: String,
     pub transform: Transform,
-    pub yfov_radians: f32,
-    pub znear: f32,
-    pub zfar: Option<f32>,
+    pub projection: SceneCameraProjection,
 }
 
 impl Default for SceneExportCamera {
@@ -127,9 +292,17 @@ impl Default for SceneExportCamera {
         Self {
             name: "camera".to_owned(),
             transform: Transform::default(),
-            yfov_radians: std::f32::consts::FRAC_PI_4,
-            znear: 0.01,
-            zfar: Some(1000.0),
+            projection: SceneCameraProjection::default(),
+        }
+    }
+}
+
+impl From<&SceneCamera> for SceneExportCamera {
+    fn from(camera: &SceneCamera) -> Self {
+        Self {
+            name: camera.name.clone(),
+            transform: camera.transform,
+            projection: camera.projection.clone(),
         }
     }
 }
@@ -141,6 +314,10 @@ pub struct GaussianSceneHandle(pub Handle<GaussianScene>);
 #[derive(Component, Clone, Debug, Default, Reflect)]
 pub struct GaussianSceneLoaded;
 
+/// spawns the scene cameras as `Camera3d` children of the scene entity, only the first is active
+#[derive(Component, Clone, Debug, Default, Reflect)]
+pub struct GaussianSceneCameras;
+
 #[derive(Default)]
 pub struct GaussianScenePlugin;
 
@@ -151,11 +328,14 @@ impl Plugin for GaussianScenePlugin {
         app.register_type::<GaussianSortingMethod>();
         app.register_type::<GaussianPrimitiveSpec>();
         app.register_type::<GaussianPrimitiveMetadata>();
+        app.register_type::<SceneCloudHandle>();
         app.register_type::<CloudBundle>();
+        app.register_type::<SceneCameraProjection>();
         app.register_type::<SceneCamera>();
         app.register_type::<GaussianScene>();
         app.register_type::<GaussianSceneHandle>();
         app.register_type::<GaussianSceneLoaded>();
+        app.register_type::<GaussianSceneCameras>();
 
         app.init_asset::<GaussianScene>();
         app.init_asset_loader::<GaussianSceneLoader>();
@@ -166,11 +346,14 @@ impl Plugin for GaussianScenePlugin {
 
 fn spawn_scene(
     mut commands: Commands,
-    scene_handles: Query<(Entity, &GaussianSceneHandle), Without<GaussianSceneLoaded>>,
+    scene_handles: Query<
+        (Entity, &GaussianSceneHandle, Has<GaussianSceneCameras>),
+        Without<GaussianSceneLoaded>,
+    >,
     asset_server: Res<AssetServer>,
     scenes: Res<Assets<GaussianScene>>,
 ) {
-    for (entity, scene_handle) in scene_handles.iter() {
+    for (entity, scene_handle, spawn_cameras) in scene_handles.iter() {
         if let Some(load_state) = asset_server.get_load_state(&scene_handle.0)
             && !load_state.is_loaded()
         {
@@ -182,17 +365,44 @@ fn spawn_scene(
         };
 
         let bundles = scene.bundles.clone();
+        let cameras = if spawn_cameras {
+            scene.cameras.clone()
+        } else {
+            Vec::new()
+        };
 
         commands
             .entity(entity)
             .with_children(move |builder| {
                 for bundle in bundles {
-                    builder.spawn((
-                        PlanarGaussian3dHandle(bundle.cloud.clone()),
-                        Name::new(bundle.name.clone()),
-                        bundle.settings.clone(),
+                    let components = (
+                        Name::new(bundle.name),
+                        bundle.settings,
                         bundle.transform,
-                        bundle.metadata.clone(),
+                        bundle.metadata,
+                    );
+
+                    match bundle.cloud {
+                        SceneCloudHandle::Gaussian3d(cloud) => {
+                            builder.spawn((PlanarGaussian3dHandle(cloud), components));
+                        }
+                        SceneCloudHandle::Gaussian4d(cloud) => {
+                            builder.spawn((PlanarGaussian4dHandle(cloud), components));
+                        }
+                    }
+                }
+
+                for (index, camera) in cameras.into_iter().enumerate() {
+                    builder.spawn((
+                        Name::new(camera.name),
+                        Camera3d::default(),
+                        Camera {
+                            is_active: index == 0,
+                            ..default()
+                        },
+                        camera.projection.to_projection(),
+                        camera.transform,
+                        GaussianCamera::default(),
                     ));
                 }
             })
@@ -230,6 +440,7 @@ struct GaussianPrimitiveSource {
     attributes: HashMap<String, usize>,
     metadata: GaussianPrimitiveMetadata,
     color_space: GaussianColorSpace,
+    temporal: Option<RawTemporalExtension>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -241,6 +452,83 @@ struct RawRoot {
     meshes: Vec<RawMesh>,
     #[serde(default)]
     nodes: Vec<RawNode>,
+    #[serde(default)]
+    buffers: Vec<RawBuffer>,
+    #[serde(default)]
+    buffer_views: Vec<RawBufferView>,
+    #[serde(default)]
+    accessors: Vec<RawAccessor>,
+}
+
+impl RawRoot {
+    /// `offset + scale * value` written by the quantized exporter, identity otherwise
+    fn accessor_range(&self, index: usize) -> Result<(f32, f32), std::io::Error> {
+        let Some(extras) = self.accessors.get(index).map(|accessor| &accessor.extras) else {
+            return Ok((0.0, 1.0));
+        };
+
+        let component = |key: &str, default: f32| match extras.get(key) {
+            None => Ok(default),
+            Some(value) => value
+                .as_f64()
+                .map(|value| value as f32)
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("accessor {index} has an invalid extras.{key} of {value}"),
+                    )
+                }),
+        };
+
+        Ok((component("offset", 0.0)?, component("scale", 1.0)?))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawAccessor {
+    #[serde(default)]
+    extras: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawBuffer {
+    #[serde(default)]
+    extensions: HashMap<String, Value>,
+}
+
+impl RawBuffer {
+    fn is_meshopt_fallback(&self) -> bool {
+        self.extensions
+            .get(EXT_MESHOPT_COMPRESSION_EXTENSION)
+            .and_then(|extension| extension.get("fallback"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    #[serde(default)]
+    extensions: HashMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMeshoptBufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: usize,
+    count: usize,
+    mode: String,
+    #[serde(default)]
+    filter: Option<String>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -275,6 +563,15 @@ struct RawGaussianExtension {
     projection: String,
     #[serde(default = "default_sorting_method")]
     sorting_method: String,
+    #[serde(default)]
+    extensions: HashMap<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTemporalExtension {
+    #[serde(default)]
+    playback_range: Option<[f32; 2]>,
 }
 
 fn default_projection() -> String {
@@ -307,7 +604,8 @@ async fn load_gltf_scene(
     }
     ensure_gaussian_extension_used(&raw_root.extensions_used)?;
 
-    let buffers = load_buffers(&gltf, load_context).await?;
+    let mut buffers = load_buffers(&gltf, &raw_root, load_context).await?;
+    decode_meshopt_buffer_views(&raw_root, &mut buffers)?;
     let scene = gltf.default_scene().or_else(|| gltf.scenes().next());
     let Some(scene) = scene else {
         return Err(std::io::Error::new(
@@ -345,6 +643,25 @@ async fn load_gltf_scene(
     Ok(GaussianScene { bundles, cameras })
 }
 
+fn scene_camera_projection(camera: &gltf::Camera<'_>) -> SceneCameraProjection {
+    match camera.projection() {
+        gltf::camera::Projection::Perspective(perspective) => SceneCameraProjection::Perspective {
+            yfov: perspective.yfov(),
+            aspect_ratio: perspective.aspect_ratio(),
+            znear: perspective.znear(),
+            zfar: perspective.zfar(),
+        },
+        gltf::camera::Projection::Orthographic(orthographic) => {
+            SceneCameraProjection::Orthographic {
+                xmag: orthographic.xmag(),
+                ymag: orthographic.ymag(),
+                znear: orthographic.znear(),
+                zfar: orthographic.zfar(),
+            }
+        }
+    }
+}
+
 fn ensure_gaussian_extension_used(extensions_used: &[String]) -> Result<(), std::io::Error> {
     if extensions_used
         .iter()
@@ -422,6 +739,20 @@ fn collect_gaussian_primitives(
             let projection = parse_projection(&extension.projection, mesh_index, primitive_index)?;
             let sorting_method =
                 parse_sorting_method(&extension.sorting_method, mesh_index, primitive_index)?;
+            let temporal = extension
+                .extensions
+                .get(EXT_GAUSSIAN_SPLATTING_4D)
+                .map(|value| {
+                    serde_json::from_value::<RawTemporalExtension>(value.clone()).map_err(|err| {
+                        std::io::Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "mesh {mesh_index} primitive {primitive_index} has invalid {EXT_GAUSSIAN_SPLATTING_4D} extension payload: {err}"
+                            ),
+                        )
+                    })
+                })
+                .transpose()?;
 
             sources.insert(
                 (mesh_index, primitive_index),
@@ -440,6 +771,7 @@ fn collect_gaussian_primitives(
                         },
                     },
                     color_space,
+                    temporal,
                 },
             );
         }
@@ -555,13 +887,21 @@ fn parse_sorting_method(
 
 async fn load_buffers(
     gltf: &gltf::Gltf,
+    raw_root: &RawRoot,
     load_context: &mut LoadContext<'_>,
 ) -> Result<Vec<Vec<u8>>, std::io::Error> {
     let mut buffers = Vec::new();
     let mut blob = gltf.blob.clone();
 
     for buffer in gltf.buffers() {
+        let meshopt_fallback = raw_root
+            .buffers
+            .get(buffer.index())
+            .is_some_and(RawBuffer::is_meshopt_fallback);
+
         let mut data = match buffer.source() {
+            // filled by `decode_meshopt_buffer_views`
+            Source::Bin if meshopt_fallback => vec![0; buffer.length()],
             Source::Bin => blob.take().ok_or_else(|| {
                 std::io::Error::new(
                     ErrorKind::InvalidData,
@@ -612,6 +952,80 @@ async fn load_buffers(
     Ok(buffers)
 }
 
+/// decodes `EXT_meshopt_compression` views into the buffer range they describe,
+/// the ATTRIBUTES mode is supported with every filter, the exporter writes it unfiltered
+fn decode_meshopt_buffer_views(
+    raw_root: &RawRoot,
+    buffers: &mut [Vec<u8>],
+) -> Result<(), std::io::Error> {
+    for (view_index, buffer_view) in raw_root.buffer_views.iter().enumerate() {
+        let Some(extension) = buffer_view
+            .extensions
+            .get(EXT_MESHOPT_COMPRESSION_EXTENSION)
+        else {
+            continue;
+        };
+
+        let extension: RawMeshoptBufferView = serde_json::from_value(extension.clone())
+            .map_err(|err| {
+                std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid {EXT_MESHOPT_COMPRESSION_EXTENSION} on bufferView {view_index}: {err}"),
+                )
+            })?;
+
+        let filter_name = extension.filter.as_deref().unwrap_or("NONE");
+        let filter = MeshoptFilter::from_name(filter_name)
+            .filter(|_| extension.mode == "ATTRIBUTES")
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "bufferView {view_index} uses unsupported meshopt mode '{}' with filter '{filter_name}'",
+                        extension.mode
+                    ),
+                )
+            })?;
+
+        let out_of_range = || {
+            std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("meshopt bufferView {view_index} references data outside its buffer"),
+            )
+        };
+
+        let compressed_end = extension
+            .byte_offset
+            .checked_add(extension.byte_length)
+            .ok_or_else(out_of_range)?;
+        let target_end = extension
+            .count
+            .checked_mul(extension.byte_stride)
+            .and_then(|length| buffer_view.byte_offset.checked_add(length))
+            .ok_or_else(out_of_range)?;
+
+        // bound the target before decoding so a bogus count cannot drive the allocation
+        if buffers
+            .get(buffer_view.buffer)
+            .is_none_or(|buffer| buffer.len() < target_end)
+        {
+            return Err(out_of_range());
+        }
+
+        let compressed = buffers
+            .get(extension.buffer)
+            .and_then(|buffer| buffer.get(extension.byte_offset..compressed_end))
+            .ok_or_else(out_of_range)?;
+        let mut decoded =
+            meshopt::decode_vertex_buffer(compressed, extension.count, extension.byte_stride)?;
+        filter.decode(&mut decoded, extension.byte_stride)?;
+
+        buffers[buffer_view.buffer][buffer_view.byte_offset..target_end].copy_from_slice(&decoded);
+    }
+
+    Ok(())
+}
+
 fn decode_data_uri(uri: &str) -> Option<Result<Vec<u8>, std::io::Error>> {
     let rest = uri.strip_prefix("data:")?;
 
@@ -706,10 +1120,11 @@ fn collect_node_bundles(
         .and_then(|raw_node| raw_node.name.as_deref())
         .unwrap_or("gaussian_node");
 
-    if node.camera().is_some() {
+    if let Some(camera) = node.camera() {
         cameras.push(SceneCamera {
             name: node_name.to_owned(),
             transform: Transform::from_matrix(world_transform),
+            projection: scene_camera_projection(&camera),
         });
     }
 
@@ -720,18 +1135,33 @@ fn collect_node_bundles(
                 continue;
             };
 
-            let cloud = decode_gaussian_primitive(document, buffers, source)?;
-            let cloud_handle =
-                load_context.add_labeled_asset(format!("gltf_gaussian_{}", *bundle_index), cloud);
-
-            let settings = CloudSettings {
-                gaussian_mode: GaussianMode::Gaussian3d,
+            let label = format!("gltf_gaussian_{}", *bundle_index);
+            let mut settings = CloudSettings {
                 color_space: source.color_space,
                 ..default()
             };
 
+            let cloud = match &source.temporal {
+                None => {
+                    let cloud = decode_gaussian_primitive(document, raw_root, buffers, source)?;
+                    settings.gaussian_mode = GaussianMode::Gaussian3d;
+
+                    SceneCloudHandle::Gaussian3d(load_context.add_labeled_asset(label, cloud))
+                }
+                Some(temporal) => {
+                    let cloud = decode_gaussian_4d_primitive(document, raw_root, buffers, source)?;
+                    settings.gaussian_mode = GaussianMode::Gaussian4d;
+                    if let Some([time_start, time_stop]) = temporal.playback_range {
+                        settings.time_start = time_start;
+                        settings.time_stop = time_stop;
+                    }
+
+                    SceneCloudHandle::Gaussian4d(load_context.add_labeled_asset(label, cloud))
+                }
+            };
+
             bundles.push(CloudBundle {
-                cloud: cloud_handle,
+                cloud,
                 name: format!(
                     "{node_name}_mesh{}_primitive{}",
                     mesh.index(),
@@ -766,6 +1196,14 @@ fn collect_node_bundles(
 pub fn encode_khr_gaussian_scene_gltf_bytes(
     clouds: &[SceneExportCloud],
     camera: Option<&SceneExportCamera>,
+) -> Result<Vec<u8>, std::io::Error> {
+    encode_khr_gaussian_scene_gltf_bytes_with_options(clouds, camera, SceneExportOptions::default())
+}
+
+pub fn encode_khr_gaussian_scene_gltf_bytes_with_options(
+    clouds: &[SceneExportCloud],
+    camera: Option<&SceneExportCamera>,
+    options: SceneExportOptions,
 ) -> Result<Vec<u8>, std::io::Error> {
     if clouds.is_empty() {
         return Err(std::io::Error::new(
@@ -774,9 +1212,7 @@ pub fn encode_khr_gaussian_scene_gltf_bytes(
         ));
     }
 
-    let mut binary = Vec::<u8>::new();
-    let mut buffer_views = Vec::<Value>::new();
-    let mut accessors = Vec::<Value>::new();
+    let mut buffers = ExportBuffers::new(options.meshopt);
     let mut meshes = Vec::<Value>::new();
     let mut nodes = Vec::<Value>::new();
     let mut scene_nodes = Vec::<usize>::new();
@@ -785,69 +1221,70 @@ pub fn encode_khr_gaussian_scene_gltf_bytes(
     let export_sh_degree = max_export_sh_degree().min(3);
     let export_coeff_count = (export_sh_degree + 1) * (export_sh_degree + 1);
 
-    for cloud in clouds {
-        let source_gaussian_count = cloud.cloud.position_visibility.len();
-        if source_gaussian_count == 0 {
-            continue;
-        }
-
-        let mut positions = Vec::<f32>::with_capacity(source_gaussian_count * 3);
-        let mut rotations = Vec::<f32>::with_capacity(source_gaussian_count * 4);
-        let mut scales = Vec::<f32>::with_capacity(source_gaussian_count * 3);
-        let mut opacities = Vec::<f32>::with_capacity(source_gaussian_count);
-        let mut sh_channels = (0..export_coeff_count)
-            .map(|_| Vec::<f32>::with_capacity(source_gaussian_count * 3))
-            .collect::<Vec<_>>();
-
-        let mut position_min = [f32::INFINITY; 3];
-        let mut position_max = [f32::NEG_INFINITY; 3];
-        let mut dropped_gaussians = 0usize;
+    let mut uses_temporal_extension = false;
 
-        for gaussian in cloud.cloud.iter() {
-            let rotation = gaussian.rotation.rotation;
-            let rotation_length_sq = rotation
-                .iter()
-                .map(|component| component * component)
-                .sum::<f32>();
-            if rotation_length_sq <= f32::EPSILON || !rotation_length_sq.is_finite() {
-                dropped_gaussians += 1;
-                continue;
-            }
-            let inv_rotation_length = rotation_length_sq.sqrt().recip();
-            let normalized_rotation = [
-                rotation[0] * inv_rotation_length,
-                rotation[1] * inv_rotation_length,
-                rotation[2] * inv_rotation_length,
-                rotation[3] * inv_rotation_length,
-            ];
-
-            let position = gaussian.position_visibility.position;
-            positions.extend_from_slice(&position);
-            for axis in 0..3 {
-                position_min[axis] = position_min[axis].min(position[axis]);
-                position_max[axis] = position_max[axis].max(position[axis]);
+    for cloud in clouds {
+        let attributes = match &cloud.cloud {
+            SceneExportGaussians::Gaussian3d(planar) => {
+                let mut attributes = ExportAttributes::new(export_coeff_count);
+                for gaussian in planar.iter() {
+                    let Some(rotation) = normalized_export_rotation(gaussian.rotation.rotation)
+                    else {
+                        attributes.dropped_gaussians += 1;
+                        continue;
+                    };
+
+                    let sh = &gaussian.spherical_harmonic;
+                    attributes.push(
+                        gaussian.position_visibility.position,
+                        rotation,
+                        gaussian.scale_opacity.scale,
+                        gaussian.scale_opacity.opacity,
+                        |index| {
+                            std::array::from_fn(|channel| sh.get(index * SH_CHANNELS + channel))
+                        },
+                    );
+                }
+                attributes
             }
-
-            rotations.extend_from_slice(&normalized_rotation);
-
-            scales.extend_from_slice(&[
-                gaussian.scale_opacity.scale[0].max(1e-6).ln(),
-                gaussian.scale_opacity.scale[1].max(1e-6).ln(),
-                gaussian.scale_opacity.scale[2].max(1e-6).ln(),
-            ]);
-            opacities.push(gaussian.scale_opacity.opacity.clamp(0.0, 1.0));
-
-            for (coefficient_index, channel) in sh_channels.iter_mut().enumerate() {
-                let base = coefficient_index * SH_CHANNELS;
-                channel.extend_from_slice(&[
-                    gaussian.spherical_harmonic.coefficients[base],
-                    gaussian.spherical_harmonic.coefficients[base + 1],
-                    gaussian.spherical_harmonic.coefficients[base + 2],
-                ]);
+            SceneExportGaussians::Gaussian4d(planar) => {
+                let mut attributes = ExportAttributes::new(export_coeff_count);
+                let mut temporal = TemporalExportAttributes::new(export_coeff_count);
+                for index in 0..planar.len() {
+                    let gaussian = planar.get(index);
+                    let rotations = &gaussian.isotropic_rotations;
+                    let (Some(rotation), Some(rotation_r)) = (
+                        normalized_export_rotation(rotations.rotation),
+                        normalized_export_rotation(rotations.rotation_r),
+                    ) else {
+                        attributes.dropped_gaussians += 1;
+                        continue;
+                    };
+
+                    let sh = &gaussian.spherindrical_harmonic;
+                    let rgb = |index: usize| -> [f32; 3] {
+                        std::array::from_fn(|channel| sh.get(index * SH_CHANNELS + channel))
+                    };
+                    attributes.push(
+                        gaussian.position_visibility.position,
+                        rotation,
+                        gaussian.scale_opacity.scale,
+                        gaussian.scale_opacity.opacity,
+                        rgb,
+                    );
+                    temporal.push(
+                        gaussian.timestamp_timescale.timestamp,
+                        gaussian.timestamp_timescale.timescale,
+                        rotation_r,
+                        rgb,
+                    );
+                }
+                attributes.temporal = Some(temporal);
+                attributes
             }
-        }
+        };
 
-        let gaussian_count = positions.len() / 3;
+        let gaussian_count = attributes.positions.len() / 3;
         if gaussian_count == 0 {
             warn!(
                 "skipping cloud '{}' during KHR export because all gaussians had invalid rotations",
@@ -855,94 +1292,121 @@ pub fn encode_khr_gaussian_scene_gltf_bytes(
             );
             continue;
         }
-        if dropped_gaussians > 0 {
+        if attributes.dropped_gaussians > 0 {
             warn!(
                 "dropped {} gaussians with invalid rotations while exporting cloud '{}'",
-                dropped_gaussians, cloud.name
+                attributes.dropped_gaussians, cloud.name
             );
         }
 
-        let position_accessor = push_f32_accessor(
-            &mut binary,
-            &mut buffer_views,
-            &mut accessors,
-            AccessorSpec {
-                values: &positions,
-                count: gaussian_count,
-                accessor_type: "VEC3",
-                min: Some(position_min.to_vec()),
-                max: Some(position_max.to_vec()),
-            },
-        );
-        let rotation_accessor = push_f32_accessor(
-            &mut binary,
-            &mut buffer_views,
-            &mut accessors,
-            AccessorSpec {
-                values: &rotations,
-                count: gaussian_count,
-                accessor_type: "VEC4",
-                min: None,
-                max: None,
-            },
-        );
-        let scale_accessor = push_f32_accessor(
-            &mut binary,
-            &mut buffer_views,
-            &mut accessors,
-            AccessorSpec {
-                values: &scales,
-                count: gaussian_count,
-                accessor_type: "VEC3",
-                min: None,
-                max: None,
-            },
-        );
-        let opacity_accessor = push_f32_accessor(
-            &mut binary,
-            &mut buffer_views,
-            &mut accessors,
-            AccessorSpec {
-                values: &opacities,
-                count: gaussian_count,
-                accessor_type: "SCALAR",
-                min: None,
-                max: None,
-            },
-        );
+        let position_accessor = buffers.push_accessor(AccessorSpec {
+            values: &attributes.positions,
+            count: gaussian_count,
+            accessor_type: "VEC3",
+            component: ExportComponent::F32,
+            fitted: false,
+            min: Some(attributes.position_min.to_vec()),
+            max: Some(attributes.position_max.to_vec()),
+        })?;
+
+        let (rotation_component, opacity_component, sh_dc_component, sh_rest_component) =
+            if options.quantize {
+                (
+                    ExportComponent::I16Normalized,
+                    ExportComponent::U8Normalized,
+                    ExportComponent::U8Normalized,
+                    ExportComponent::I16Normalized,
+                )
+            } else {
+                (
+                    ExportComponent::F32,
+                    ExportComponent::F32,
+                    ExportComponent::F32,
+                    ExportComponent::F32,
+                )
+            };
 
-        let mut attributes = serde_json::Map::new();
-        attributes.insert(ATTR_POSITION.to_owned(), json!(position_accessor));
-        attributes.insert(ATTR_ROTATION.to_owned(), json!(rotation_accessor));
-        attributes.insert(ATTR_SCALE.to_owned(), json!(scale_accessor));
-        attributes.insert(ATTR_OPACITY.to_owned(), json!(opacity_accessor));
-
-        for (coefficient_index, values) in sh_channels.iter().enumerate() {
-            let sh_accessor = push_f32_accessor(
-                &mut binary,
-                &mut buffer_views,
-                &mut accessors,
-                AccessorSpec {
+        let mut push_accessor =
+            |values: &[f32], accessor_type: &str, component: ExportComponent, fitted: bool| {
+                buffers.push_accessor(AccessorSpec {
                     values,
                     count: gaussian_count,
-                    accessor_type: "VEC3",
+                    accessor_type,
+                    component,
+                    fitted,
                     min: None,
                     max: None,
-                },
-            );
+                })
+            };
+        let sh_component = |coefficient_index: usize| {
+            if coefficient_index == 0 {
+                sh_dc_component
+            } else {
+                sh_rest_component
+            }
+        };
+
+        let rotation_accessor =
+            push_accessor(&attributes.rotations, "VEC4", rotation_component, false)?;
+        let scale_accessor =
+            push_accessor(&attributes.scales, "VEC3", ExportComponent::F32, false)?;
+        let opacity_accessor =
+            push_accessor(&attributes.opacities, "SCALAR", opacity_component, false)?;
+
+        let mut primitive_attributes = serde_json::Map::new();
+        primitive_attributes.insert(ATTR_POSITION.to_owned(), json!(position_accessor));
+        primitive_attributes.insert(ATTR_ROTATION.to_owned(), json!(rotation_accessor));
+        primitive_attributes.insert(ATTR_SCALE.to_owned(), json!(scale_accessor));
+        primitive_attributes.insert(ATTR_OPACITY.to_owned(), json!(opacity_accessor));
+
+        for (coefficient_index, values) in attributes.sh_channels.iter().enumerate() {
+            let sh_accessor = push_accessor(values, "VEC3", sh_component(coefficient_index), true)?;
             let (degree, coefficient) = sh_index_to_degree_coefficient(coefficient_index);
-            attributes.insert(
+            primitive_attributes.insert(
                 format!("{ATTR_SH_PREFIX}{degree}_COEF_{coefficient}"),
                 json!(sh_accessor),
             );
         }
 
-        let primitive_extension =
+        if let Some(temporal) = &attributes.temporal {
+            let timestamp_accessor =
+                push_accessor(&temporal.timestamps, "SCALAR", ExportComponent::F32, false)?;
+            let timescale_accessor =
+                push_accessor(&temporal.timescales, "SCALAR", ExportComponent::F32, false)?;
+            let rotation_r_accessor =
+                push_accessor(&temporal.rotations_r, "VEC4", rotation_component, false)?;
+            primitive_attributes.insert(ATTR_TIMESTAMP.to_owned(), json!(timestamp_accessor));
+            primitive_attributes.insert(ATTR_TIMESCALE.to_owned(), json!(timescale_accessor));
+            primitive_attributes.insert(ATTR_ROTATION_R.to_owned(), json!(rotation_r_accessor));
+
+            for (index, values) in temporal.sh_channels.iter().enumerate() {
+                let sh_accessor = push_accessor(values, "VEC3", sh_rest_component, true)?;
+                let time_degree = index / export_coeff_count + 1;
+                let (degree, coefficient) =
+                    sh_index_to_degree_coefficient(index % export_coeff_count);
+                primitive_attributes.insert(
+                    format!(
+                        "{ATTR_SH_TIME_PREFIX}{time_degree}_DEGREE_{degree}_COEF_{coefficient}"
+                    ),
+                    json!(sh_accessor),
+                );
+            }
+        }
+
+        let mut primitive_extension =
             gaussian_extension_object(&cloud.metadata, cloud.settings.color_space);
+        let temporal_extension = attributes.temporal.as_ref().map(|_| {
+            json!({
+                "playbackRange": [cloud.settings.time_start, cloud.settings.time_stop],
+            })
+        });
+        uses_temporal_extension |= temporal_extension.is_some();
+        set_temporal_extension(&mut primitive_extension, temporal_extension);
+
         meshes.push(json!({
             "name": cloud.name,
             "primitives": [{
-                "attributes": Value::Object(attributes),
+                "attributes": Value::Object(primitive_attributes),
                 "mode": 0,
                 "extensions": {
                     KHR_GAUSSIAN_SPLATTING_EXTENSION: primitive_extension
@@ -967,18 +1431,7 @@ pub fn encode_khr_gaussian_scene_gltf_bytes(
     }
 
     if let Some(camera) = camera {
-        let mut perspective = serde_json::Map::new();
-        perspective.insert("yfov".to_owned(), json!(camera.yfov_radians));
-        perspective.insert("znear".to_owned(), json!(camera.znear));
-        if let Some(zfar) = camera.zfar {
-            perspective.insert("zfar".to_owned(), json!(zfar));
-        }
-
-        cameras_json.push(json!({
-            "name": camera.name,
-            "type": "perspective",
-            "perspective": Value::Object(perspective),
-        }));
+        cameras_json.push(camera_json(camera));
 
         let camera_node_index = nodes.len();
         scene_nodes.push(camera_node_index);
@@ -989,34 +1442,38 @@ pub fn encode_khr_gaussian_scene_gltf_bytes(
         }));
     }
 
-    align_to_four_bytes(&mut binary);
+    if buffers.clamped_values > 0 {
+        warn!(
+            "clamped {} values outside the normalized range while quantizing the KHR export",
+            buffers.clamped_values
+        );
+    }
 
     let mut root = serde_json::Map::new();
     root.insert("asset".to_owned(), json!({ "version": "2.0" }));
-    root.insert(
-        "extensionsUsed".to_owned(),
-        json!([KHR_GAUSSIAN_SPLATTING_EXTENSION]),
-    );
-    root.insert(
-        "extensionsRequired".to_owned(),
-        json!([KHR_GAUSSIAN_SPLATTING_EXTENSION]),
-    );
+    let mut extensions_used = vec![KHR_GAUSSIAN_SPLATTING_EXTENSION];
+    let mut extensions_required = vec![KHR_GAUSSIAN_SPLATTING_EXTENSION];
+    if uses_temporal_extension {
+        extensions_used.push(EXT_GAUSSIAN_SPLATTING_4D);
+    }
+    if options.quantize {
+        extensions_used.push(KHR_MESH_QUANTIZATION_EXTENSION);
+        extensions_required.push(KHR_MESH_QUANTIZATION_EXTENSION);
+    }
+    if options.meshopt {
+        // the fallback buffer holds no data, so readers must decode
+        extensions_used.push(EXT_MESHOPT_COMPRESSION_EXTENSION);
+        extensions_required.push(EXT_MESHOPT_COMPRESSION_EXTENSION);
+    }
+    root.insert("extensionsUsed".to_owned(), json!(extensions_used));
+    root.insert("extensionsRequired".to_owned(), json!(extensions_required));
     root.insert("scene".to_owned(), json!(0));
     root.insert("scenes".to_owned(), json!([{ "nodes": scene_nodes }]));
     root.insert("nodes".to_owned(), Value::Array(nodes));
     root.insert("meshes".to_owned(), Value::Array(meshes));
-    root.insert(
-        "buffers".to_owned(),
-        json!([{
-            "byteLength": binary.len(),
-            "uri": format!(
-                "data:application/octet-stream;base64,{}",
-                base64::engine::general_purpose::STANDARD.encode(&binary)
-            ),
-        }]),
-    );
-    root.insert("bufferViews".to_owned(), Value::Array(buffer_views));
-    root.insert("accessors".to_owned(), Value::Array(accessors));
+    root.insert("buffers".to_owned(), buffers.buffers_json());
+    root.insert("bufferViews".to_owned(), Value::Array(buffers.buffer_views));
+    root.insert("accessors".to_owned(), Value::Array(buffers.accessors));
     if !cameras_json.is_empty() {
         root.insert("cameras".to_owned(), Value::Array(cameras_json));
     }
@@ -1034,7 +1491,16 @@ pub fn write_khr_gaussian_scene_gltf(
     clouds: &[SceneExportCloud],
     camera: Option<&SceneExportCamera>,
 ) -> Result<(), std::io::Error> {
-    let bytes = encode_khr_gaussian_scene_gltf_bytes(clouds, camera)?;
+    write_khr_gaussian_scene_gltf_with_options(path, clouds, camera, SceneExportOptions::default())
+}
+
+pub fn write_khr_gaussian_scene_gltf_with_options(
+    path: impl AsRef<Path>,
+    clouds: &[SceneExportCloud],
+    camera: Option<&SceneExportCamera>,
+    options: SceneExportOptions,
+) -> Result<(), std::io::Error> {
+    let bytes = encode_khr_gaussian_scene_gltf_bytes_with_options(clouds, camera, options)?;
     std::fs::write(path, bytes)
 }
 
@@ -1042,7 +1508,15 @@ pub fn encode_khr_gaussian_scene_glb_bytes(
     clouds: &[SceneExportCloud],
     camera: Option<&SceneExportCamera>,
 ) -> Result<Vec<u8>, std::io::Error> {
-    let gltf_bytes = encode_khr_gaussian_scene_gltf_bytes(clouds, camera)?;
+    encode_khr_gaussian_scene_glb_bytes_with_options(clouds, camera, SceneExportOptions::default())
+}
+
+pub fn encode_khr_gaussian_scene_glb_bytes_with_options(
+    clouds: &[SceneExportCloud],
+    camera: Option<&SceneExportCamera>,
+    options: SceneExportOptions,
+) -> Result<Vec<u8>, std::io::Error> {
+    let gltf_bytes = encode_khr_gaussian_scene_gltf_bytes_with_options(clouds, camera, options)?;
     let mut root: Value = serde_json::from_slice(&gltf_bytes).map_err(|err| {
         std::io::Error::new(
             ErrorKind::InvalidData,
@@ -1082,7 +1556,16 @@ pub fn write_khr_gaussian_scene_glb(
     clouds: &[SceneExportCloud],
     camera: Option<&SceneExportCamera>,
 ) -> Result<(), std::io::Error> {
-    let bytes = encode_khr_gaussian_scene_glb_bytes(clouds, camera)?;
+    write_khr_gaussian_scene_glb_with_options(path, clouds, camera, SceneExportOptions::default())
+}
+
+pub fn write_khr_gaussian_scene_glb_with_options(
+    path: impl AsRef<Path>,
+    clouds: &[SceneExportCloud],
+    camera: Option<&SceneExportCamera>,
+    options: SceneExportOptions,
+) -> Result<(), std::io::Error> {
+    let bytes = encode_khr_gaussian_scene_glb_bytes_with_options(clouds, camera, options)?;
     std::fs::write(path, bytes)
 }
 
@@ -1092,11 +1575,16 @@ fn extract_embedded_binary_buffer(root: &mut Value) -> Result<Vec<u8>, std::io::
         .and_then(Value::as_array_mut)
         .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidData, "missing glTF buffers array"))?;
 
-    if buffers.len() != 1 {
+    // later buffers are uri-less meshopt fallbacks
+    if buffers.is_empty()
+        || buffers[1..]
+            .iter()
+            .any(|buffer| buffer.get("uri").is_some())
+    {
         return Err(std::io::Error::new(
             ErrorKind::InvalidData,
             format!(
-                "KHR_gaussian_splatting export expects exactly one buffer, found {}",
+                "KHR_gaussian_splatting export expects exactly one embedded buffer, found {}",
                 buffers.len()
             ),
         ));
@@ -1122,14 +1610,213 @@ fn extract_embedded_binary_buffer(root: &mut Value) -> Result<Vec<u8>, std::io::
         )
     })??;
 
-    buffer.insert("byteLength".to_owned(), json!(binary.len()));
+    buffer.insert("byteLength".to_owned(), json!(binary.len()));
+
+    Ok(binary)
+}
+
+fn align_to_four_bytes(bytes: &mut Vec<u8>) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(0);
+    }
+}
+
+struct ExportAttributes {
+    positions: Vec<f32>,
+    rotations: Vec<f32>,
+    scales: Vec<f32>,
+    opacities: Vec<f32>,
+    sh_channels: Vec<Vec<f32>>,
+    position_min: [f32; 3],
+    position_max: [f32; 3],
+    dropped_gaussians: usize,
+    temporal: Option<TemporalExportAttributes>,
+}
+
+impl ExportAttributes {
+    fn new(coeff_count: usize) -> Self {
+        Self {
+            positions: Vec::new(),
+            rotations: Vec::new(),
+            scales: Vec::new(),
+            opacities: Vec::new(),
+            sh_channels: vec![Vec::new(); coeff_count],
+            position_min: [f32::INFINITY; 3],
+            position_max: [f32::NEG_INFINITY; 3],
+            dropped_gaussians: 0,
+            temporal: None,
+        }
+    }
+
+    fn push(
+        &mut self,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        scale: [f32; 3],
+        opacity: f32,
+        sh: impl Fn(usize) -> [f32; 3],
+    ) {
+        self.positions.extend_from_slice(&position);
+        for (axis, &value) in position.iter().enumerate() {
+            self.position_min[axis] = self.position_min[axis].min(value);
+            self.position_max[axis] = self.position_max[axis].max(value);
+        }
+
+        self.rotations.extend_from_slice(&rotation);
+        self.scales
+            .extend(scale.iter().map(|scale| scale.max(1e-6).ln()));
+        self.opacities.push(opacity.clamp(0.0, 1.0));
+
+        for (coefficient_index, channel) in self.sh_channels.iter_mut().enumerate() {
+            channel.extend_from_slice(&sh(coefficient_index));
+        }
+    }
+}
+
+struct TemporalExportAttributes {
+    timestamps: Vec<f32>,
+    timescales: Vec<f32>,
+    rotations_r: Vec<f32>,
+    /// `(time_degree - 1) * coeff_count + coefficient`
+    sh_channels: Vec<Vec<f32>>,
+    coeff_count: usize,
+}
+
+impl TemporalExportAttributes {
+    fn new(coeff_count: usize) -> Self {
+        Self {
+            timestamps: Vec::new(),
+            timescales: Vec::new(),
+            rotations_r: Vec::new(),
+            sh_channels: vec![Vec::new(); coeff_count * SH_4D_DEGREE_TIME],
+            coeff_count,
+        }
+    }
+
+    fn push(
+        &mut self,
+        timestamp: f32,
+        timescale: f32,
+        rotation_r: [f32; 4],
+        sh: impl Fn(usize) -> [f32; 3],
+    ) {
+        self.timestamps.push(timestamp);
+        self.timescales.push(timescale);
+        self.rotations_r.extend_from_slice(&rotation_r);
+
+        for (index, channel) in self.sh_channels.iter_mut().enumerate() {
+            let time_degree = index / self.coeff_count + 1;
+            let coefficient = index % self.coeff_count;
+            channel.extend_from_slice(&sh(time_degree * SH_COEFF_COUNT_PER_CHANNEL + coefficient));
+        }
+    }
+}
+
+fn normalized_export_rotation(rotation: [f32; 4]) -> Option<[f32; 4]> {
+    let rotation_length_sq = rotation
+        .iter()
+        .map(|component| component * component)
+        .sum::<f32>();
+    if rotation_length_sq <= f32::EPSILON || !rotation_length_sq.is_finite() {
+        return None;
+    }
+
+    let inv_rotation_length = rotation_length_sq.sqrt().recip();
+    Some(rotation.map(|component| component * inv_rotation_length))
+}
+
+/// sets or clears the nested 4d extension, stale payloads from loaded 4d scenes are dropped for 3d clouds
+fn set_temporal_extension(primitive_extension: &mut Value, temporal_extension: Option<Value>) {
+    let Some(extension_object) = primitive_extension.as_object_mut() else {
+        return;
+    };
 
-    Ok(binary)
+    match temporal_extension {
+        Some(temporal_extension) => {
+            let extensions = extension_object
+                .entry("extensions")
+                .or_insert_with(|| json!({}));
+            if let Some(extensions) = extensions.as_object_mut() {
+                extensions.insert(EXT_GAUSSIAN_SPLATTING_4D.to_owned(), temporal_extension);
+            }
+        }
+        None => {
+            let emptied = extension_object
+                .get_mut("extensions")
+                .and_then(Value::as_object_mut)
+                .is_some_and(|extensions| {
+                    extensions.remove(EXT_GAUSSIAN_SPLATTING_4D).is_some() && extensions.is_empty()
+                });
+            if emptied {
+                extension_object.remove("extensions");
+            }
+        }
+    }
 }
 
-fn align_to_four_bytes(bytes: &mut Vec<u8>) {
-    while !bytes.len().is_multiple_of(4) {
-        bytes.push(0);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportComponent {
+    F32,
+    I16Normalized,
+    U8Normalized,
+}
+
+impl ExportComponent {
+    fn component_type(self) -> u32 {
+        match self {
+            Self::F32 => 5126,
+            Self::I16Normalized => 5122,
+            Self::U8Normalized => 5121,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::F32 => 4,
+            Self::I16Normalized => 2,
+            Self::U8Normalized => 1,
+        }
+    }
+
+    /// offset and scale that map `values` onto the normalized range, identity for floats
+    fn fitted_range(self, values: &[f32]) -> (f32, f32) {
+        let (min, max) = values
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+
+        let (offset, extent) = match self {
+            Self::F32 => return (0.0, 1.0),
+            Self::I16Normalized => (0.0, min.abs().max(max.abs())),
+            Self::U8Normalized => (min, max - min),
+        };
+
+        match (offset.is_finite(), extent.is_finite() && extent > 0.0) {
+            (true, true) => (offset, extent),
+            (true, false) => (offset, 1.0),
+            (false, _) => (0.0, 1.0),
+        }
+    }
+
+    /// returns true when the value was clamped into the normalized range
+    fn write(self, bytes: &mut Vec<u8>, value: f32) -> bool {
+        match self {
+            Self::F32 => {
+                bytes.extend_from_slice(&value.to_le_bytes());
+                false
+            }
+            Self::I16Normalized => {
+                let clamped = value.clamp(-1.0, 1.0);
+                bytes.extend_from_slice(&((clamped * 32767.0).round() as i16).to_le_bytes());
+                clamped != value
+            }
+            Self::U8Normalized => {
+                let clamped = value.clamp(0.0, 1.0);
+                bytes.push((clamped * 255.0).round() as u8);
+                clamped != value
+            }
+        }
     }
 }
 
@@ -1137,45 +1824,193 @@ struct AccessorSpec<'a> {
     values: &'a [f32],
     count: usize,
     accessor_type: &'a str,
+    component: ExportComponent,
+    /// stores `(value - offset) / scale` and records the range in the accessor extras
+    fitted: bool,
     min: Option<Vec<f32>>,
     max: Option<Vec<f32>>,
 }
 
-fn push_f32_accessor(
-    binary: &mut Vec<u8>,
-    buffer_views: &mut Vec<Value>,
-    accessors: &mut Vec<Value>,
-    spec: AccessorSpec<'_>,
-) -> usize {
-    align_to_four_bytes(binary);
-    let byte_offset = binary.len();
-    for value in spec.values {
-        binary.extend_from_slice(&value.to_le_bytes());
+/// buffer 0 holds the data, meshopt views decode into the uri-less fallback buffer 1
+struct ExportBuffers {
+    binary: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshopt: bool,
+    fallback_length: usize,
+    clamped_values: usize,
+}
+
+impl ExportBuffers {
+    fn new(meshopt: bool) -> Self {
+        Self {
+            binary: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            meshopt,
+            fallback_length: 0,
+            clamped_values: 0,
+        }
     }
-    let byte_length = std::mem::size_of_val(spec.values);
 
-    let buffer_view_index = buffer_views.len();
-    buffer_views.push(json!({
-        "buffer": 0,
-        "byteOffset": byte_offset,
-        "byteLength": byte_length,
-    }));
+    fn push_accessor(&mut self, spec: AccessorSpec<'_>) -> Result<usize, std::io::Error> {
+        let components = spec.values.len() / spec.count.max(1);
+        let element_size = components * spec.component.size();
+        // vertex attribute elements are 4 byte aligned
+        let stride = element_size.next_multiple_of(4);
+
+        let (offset, scale) = if spec.fitted {
+            spec.component.fitted_range(spec.values)
+        } else {
+            (0.0, 1.0)
+        };
+
+        let mut elements = Vec::with_capacity(spec.count * stride);
+        for element in spec.values.chunks(components.max(1)) {
+            for &value in element {
+                if spec
+                    .component
+                    .write(&mut elements, (value - offset) / scale)
+                {
+                    self.clamped_values += 1;
+                }
+            }
+            elements.resize(elements.len().next_multiple_of(stride), 0);
+        }
+
+        align_to_four_bytes(&mut self.binary);
+        let byte_offset = self.binary.len();
+
+        let mut buffer_view = if self.meshopt {
+            let encoded = meshopt::encode_vertex_buffer(&elements, stride)?;
+            self.binary.extend_from_slice(&encoded);
+
+            let fallback_offset = self.fallback_length;
+            self.fallback_length += elements.len();
+
+            json!({
+                "buffer": 1,
+                "byteOffset": fallback_offset,
+                "byteLength": elements.len(),
+                "extensions": {
+                    EXT_MESHOPT_COMPRESSION_EXTENSION: {
+                        "buffer": 0,
+                        "byteOffset": byte_offset,
+                        "byteLength": encoded.len(),
+                        "byteStride": stride,
+                        "count": spec.count,
+                        "mode": "ATTRIBUTES",
+                    }
+                },
+            })
+        } else {
+            self.binary.extend_from_slice(&elements);
+
+            json!({
+                "buffer": 0,
+                "byteOffset": byte_offset,
+                "byteLength": elements.len(),
+            })
+        };
+        if self.meshopt || stride != element_size {
+            buffer_view["byteStride"] = json!(stride);
+        }
+
+        let buffer_view_index = self.buffer_views.len();
+        self.buffer_views.push(buffer_view);
+
+        let mut accessor = serde_json::Map::new();
+        accessor.insert("bufferView".to_owned(), json!(buffer_view_index));
+        accessor.insert(
+            "componentType".to_owned(),
+            json!(spec.component.component_type()),
+        );
+        if spec.component != ExportComponent::F32 {
+            accessor.insert("normalized".to_owned(), json!(true));
+        }
+        accessor.insert("count".to_owned(), json!(spec.count));
+        accessor.insert("type".to_owned(), json!(spec.accessor_type));
+        if let Some(min) = spec.min {
+            accessor.insert("min".to_owned(), json!(min));
+        }
+        if let Some(max) = spec.max {
+            accessor.insert("max".to_owned(), json!(max));
+        }
+        if spec.fitted && (offset, scale) != (0.0, 1.0) {
+            accessor.insert(
+                "extras".to_owned(),
+                json!({ "offset": offset, "scale": scale }),
+            );
+        }
 
-    let mut accessor = serde_json::Map::new();
-    accessor.insert("bufferView".to_owned(), json!(buffer_view_index));
-    accessor.insert("componentType".to_owned(), json!(5126u32));
-    accessor.insert("count".to_owned(), json!(spec.count));
-    accessor.insert("type".to_owned(), json!(spec.accessor_type));
-    if let Some(min) = spec.min {
-        accessor.insert("min".to_owned(), json!(min));
+        let accessor_index = self.accessors.len();
+        self.accessors.push(Value::Object(accessor));
+        Ok(accessor_index)
     }
-    if let Some(max) = spec.max {
-        accessor.insert("max".to_owned(), json!(max));
+
+    fn buffers_json(&mut self) -> Value {
+        align_to_four_bytes(&mut self.binary);
+
+        let mut buffers = vec![json!({
+            "byteLength": self.binary.len(),
+            "uri": format!(
+                "data:application/octet-stream;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(&self.binary)
+            ),
+        })];
+        if self.meshopt {
+            buffers.push(json!({
+                "byteLength": self.fallback_length,
+                "extensions": {
+                    EXT_MESHOPT_COMPRESSION_EXTENSION: { "fallback": true }
+                },
+            }));
+        }
+
+        Value::Array(buffers)
     }
+}
+
+fn camera_json(camera: &SceneExportCamera) -> Value {
+    match camera.projection {
+        SceneCameraProjection::Perspective {
+            yfov,
+            aspect_ratio,
+            znear,
+            zfar,
+        } => {
+            let mut perspective = serde_json::Map::new();
+            perspective.insert("yfov".to_owned(), json!(yfov));
+            perspective.insert("znear".to_owned(), json!(znear));
+            if let Some(aspect_ratio) = aspect_ratio {
+                perspective.insert("aspectRatio".to_owned(), json!(aspect_ratio));
+            }
+            if let Some(zfar) = zfar {
+                perspective.insert("zfar".to_owned(), json!(zfar));
+            }
 
-    let accessor_index = accessors.len();
-    accessors.push(Value::Object(accessor));
-    accessor_index
+            json!({
+                "name": camera.name,
+                "type": "perspective",
+                "perspective": Value::Object(perspective),
+            })
+        }
+        SceneCameraProjection::Orthographic {
+            xmag,
+            ymag,
+            znear,
+            zfar,
+        } => json!({
+            "name": camera.name,
+            "type": "orthographic",
+            "orthographic": {
+                "xmag": xmag,
+                "ymag": ymag,
+                "znear": znear,
+                "zfar": zfar,
+            },
+        }),
+    }
 }
 
 fn transform_matrix_values(transform: Transform) -> [f32; 16] {
@@ -1302,11 +2137,43 @@ fn max_export_sh_degree() -> usize {
     0
 }
 
-fn decode_gaussian_primitive(
+struct PrimitiveAttributes {
+    positions: Vec<[f32; 3]>,
+    rotations: Vec<[f32; 4]>,
+    scales: Vec<[f32; 3]>,
+    opacities: Vec<f32>,
+    color_fallback: Option<Vec<[f32; 3]>>,
+    sh_channels: Vec<(usize, Vec<[f32; 3]>)>,
+}
+
+impl PrimitiveAttributes {
+    fn count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// visits (coefficient index, rgb) pairs of gaussian `index`, falling back to COLOR_0 without sh
+    fn for_each_sh(&self, index: usize, mut visit: impl FnMut(usize, [f32; 3])) {
+        if self.sh_channels.is_empty()
+            && let Some(color_values) = &self.color_fallback
+        {
+            visit(
+                0,
+                color_values[index].map(|value| value / SH_DEGREE_ZERO_BASIS),
+            );
+        }
+
+        for (coefficient_index, values) in &self.sh_channels {
+            visit(*coefficient_index, values[index]);
+        }
+    }
+}
+
+fn read_primitive_attributes(
     document: &gltf::Document,
+    raw_root: &RawRoot,
     buffers: &[Vec<u8>],
     source: &GaussianPrimitiveSource,
-) -> Result<PlanarGaussian3d, std::io::Error> {
+) -> Result<PrimitiveAttributes, std::io::Error> {
     let position_accessor = required_accessor(document, &source.attributes, ATTR_POSITION)?;
     let rotation_accessor = required_accessor(document, &source.attributes, ATTR_ROTATION)?;
     let scale_accessor = required_accessor(document, &source.attributes, ATTR_SCALE)?;
@@ -1319,7 +2186,7 @@ fn decode_gaussian_primitive(
     ensure_count(&opacity_accessor, count, ATTR_OPACITY)?;
 
     let positions = read_position_attribute(&position_accessor, buffers)?;
-    let rotations = read_rotation_attribute(&rotation_accessor, buffers)?;
+    let rotations = read_rotation_attribute(&rotation_accessor, buffers, ATTR_ROTATION)?;
     let scales = read_scale_attribute(&scale_accessor, buffers)?;
     let opacities = read_opacity_attribute(&opacity_accessor, buffers)?;
     let color_fallback = if sh_accessors.is_empty() {
@@ -1343,51 +2210,131 @@ fn decode_gaussian_primitive(
         )?;
         sh_channels.push((
             coefficient_index,
-            read_sh_coefficient_attribute(&accessor, buffers)?,
+            read_sh_coefficient_attribute(
+                &accessor,
+                buffers,
+                raw_root.accessor_range(accessor.index())?,
+            )?,
         ));
     }
 
-    let mut gaussians = Vec::with_capacity(count);
-    for index in 0..count {
+    Ok(PrimitiveAttributes {
+        positions,
+        rotations,
+        scales,
+        opacities,
+        color_fallback,
+        sh_channels,
+    })
+}
+
+fn decode_gaussian_primitive(
+    document: &gltf::Document,
+    raw_root: &RawRoot,
+    buffers: &[Vec<u8>],
+    source: &GaussianPrimitiveSource,
+) -> Result<PlanarGaussian3d, std::io::Error> {
+    let attributes = read_primitive_attributes(document, raw_root, buffers, source)?;
+
+    let mut gaussians = Vec::with_capacity(attributes.count());
+    for index in 0..attributes.count() {
         let mut spherical_harmonic =
             crate::material::spherical_harmonics::SphericalHarmonicCoefficients::default();
 
-        if sh_channels.is_empty()
-            && let Some(color_values) = &color_fallback
-        {
-            let color = color_values[index];
-            spherical_harmonic.set(0, color[0] / SH_DEGREE_ZERO_BASIS);
-            spherical_harmonic.set(1, color[1] / SH_DEGREE_ZERO_BASIS);
-            spherical_harmonic.set(2, color[2] / SH_DEGREE_ZERO_BASIS);
-        }
-
-        for (coefficient_index, values) in &sh_channels {
-            let rgb = values[index];
+        attributes.for_each_sh(index, |coefficient_index, rgb| {
             let base = coefficient_index * SH_CHANNELS;
             if (base + 2) < SH_COEFF_COUNT {
                 spherical_harmonic.set(base, rgb[0]);
                 spherical_harmonic.set(base + 1, rgb[1]);
                 spherical_harmonic.set(base + 2, rgb[2]);
             }
-        }
+        });
 
+        let position = attributes.positions[index];
+        let scale = attributes.scales[index];
         gaussians.push(Gaussian3d {
-            position_visibility: [
-                positions[index][0],
-                positions[index][1],
-                positions[index][2],
-                1.0,
-            ]
-            .into(),
+            position_visibility: [position[0], position[1], position[2], 1.0].into(),
             spherical_harmonic,
-            rotation: rotations[index].into(),
-            scale_opacity: [
-                scales[index][0],
-                scales[index][1],
-                scales[index][2],
-                opacities[index],
-            ]
-            .into(),
+            rotation: attributes.rotations[index].into(),
+            scale_opacity: [scale[0], scale[1], scale[2], attributes.opacities[index]].into(),
+        });
+    }
+
+    Ok(gaussians.into())
+}
+
+fn decode_gaussian_4d_primitive(
+    document: &gltf::Document,
+    raw_root: &RawRoot,
+    buffers: &[Vec<u8>],
+    source: &GaussianPrimitiveSource,
+) -> Result<PlanarGaussian4d, std::io::Error> {
+    let attributes = read_primitive_attributes(document, raw_root, buffers, source)?;
+    let count = attributes.count();
+
+    let timestamp_accessor = required_accessor(document, &source.attributes, ATTR_TIMESTAMP)?;
+    let timescale_accessor = required_accessor(document, &source.attributes, ATTR_TIMESCALE)?;
+    let rotation_r_accessor = required_accessor(document, &source.attributes, ATTR_ROTATION_R)?;
+    ensure_count(&timestamp_accessor, count, ATTR_TIMESTAMP)?;
+    ensure_count(&timescale_accessor, count, ATTR_TIMESCALE)?;
+    ensure_count(&rotation_r_accessor, count, ATTR_ROTATION_R)?;
+
+    let timestamps = read_f32_scalar_attribute(&timestamp_accessor, buffers, ATTR_TIMESTAMP)?;
+    let timescales = read_f32_scalar_attribute(&timescale_accessor, buffers, ATTR_TIMESCALE)?;
+    let rotations_r = read_rotation_attribute(&rotation_r_accessor, buffers, ATTR_ROTATION_R)?;
+
+    let mut time_sh_channels = Vec::new();
+    for (coefficient_index, accessor_index) in collect_time_sh_coefficient_map(&source.attributes) {
+        let accessor = document.accessors().nth(accessor_index).ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("time SH attribute references missing accessor index {accessor_index}"),
+            )
+        })?;
+        ensure_count(
+            &accessor,
+            count,
+            &format!("{ATTR_SH_TIME_PREFIX}{coefficient_index}"),
+        )?;
+        time_sh_channels.push((
+            coefficient_index,
+            read_sh_coefficient_attribute(
+                &accessor,
+                buffers,
+                raw_root.accessor_range(accessor.index())?,
+            )?,
+        ));
+    }
+
+    let mut gaussians = Vec::with_capacity(count);
+    for index in 0..count {
+        let mut spherindrical_harmonic = SpherindricalHarmonicCoefficients::default();
+        let mut set_coefficient = |coefficient_index: usize, rgb: [f32; 3]| {
+            for (channel, value) in rgb.into_iter().enumerate() {
+                spherindrical_harmonic.set(coefficient_index * SH_CHANNELS + channel, value);
+            }
+        };
+
+        attributes.for_each_sh(index, |coefficient_index, rgb| {
+            if coefficient_index < SH_COEFF_COUNT_PER_CHANNEL {
+                set_coefficient(coefficient_index, rgb);
+            }
+        });
+        for (coefficient_index, values) in &time_sh_channels {
+            set_coefficient(*coefficient_index, values[index]);
+        }
+
+        let position = attributes.positions[index];
+        let scale = attributes.scales[index];
+        gaussians.push(Gaussian4d {
+            position_visibility: [position[0], position[1], position[2], 1.0].into(),
+            spherindrical_harmonic,
+            isotropic_rotations: IsotropicRotations {
+                rotation: attributes.rotations[index],
+                rotation_r: rotations_r[index],
+            },
+            scale_opacity: [scale[0], scale[1], scale[2], attributes.opacities[index]].into(),
+            timestamp_timescale: [timestamps[index], timescales[index], 0.0, 0.0].into(),
         });
     }
 
@@ -1549,6 +2496,53 @@ fn collect_sh_coefficient_map(
     Ok(coefficient_map)
 }
 
+/// maps time sh attributes to spherindrical coefficient indices, missing coefficients stay zero
+fn collect_time_sh_coefficient_map(attributes: &HashMap<String, usize>) -> Vec<(usize, usize)> {
+    let supported_degree = max_supported_sh_degree();
+    let mut coefficient_map = Vec::new();
+    let mut discarded = 0usize;
+
+    for (semantic, accessor_index) in attributes {
+        let Some((time_degree, degree, coefficient)) = parse_time_sh_semantic(semantic) else {
+            continue;
+        };
+
+        if time_degree == 0
+            || time_degree > SH_4D_DEGREE_TIME
+            || degree > supported_degree
+            || coefficient > 2 * degree
+        {
+            discarded += 1;
+            continue;
+        }
+
+        let coefficient_index =
+            time_degree * SH_COEFF_COUNT_PER_CHANNEL + sh_coefficient_index(degree, coefficient);
+        coefficient_map.push((coefficient_index, *accessor_index));
+    }
+
+    if discarded > 0 {
+        warn!(
+            "discarded {discarded} time spherical harmonics attributes outside of the supported time degree {SH_4D_DEGREE_TIME} and spatial degree {supported_degree}"
+        );
+    }
+
+    coefficient_map.sort_unstable();
+    coefficient_map
+}
+
+fn parse_time_sh_semantic(semantic: &str) -> Option<(usize, usize, usize)> {
+    let rest = semantic.strip_prefix(ATTR_SH_TIME_PREFIX)?;
+    let (time_degree, rest) = rest.split_once("_DEGREE_")?;
+    let (degree, coefficient) = rest.split_once("_COEF_")?;
+
+    Some((
+        time_degree.parse().ok()?,
+        degree.parse().ok()?,
+        coefficient.parse().ok()?,
+    ))
+}
+
 fn parse_sh_semantic(semantic: &str) -> Option<(usize, usize)> {
     let rest = semantic.strip_prefix(ATTR_SH_PREFIX)?;
     let (degree, coefficient) = rest.split_once("_COEF_")?;
@@ -1631,12 +2625,13 @@ fn read_position_attribute(
 fn read_rotation_attribute(
     accessor: &Accessor<'_>,
     buffers: &[Vec<u8>],
+    semantic: &str,
 ) -> Result<Vec<[f32; 4]>, std::io::Error> {
     if accessor.dimensions() != Dimensions::Vec4 {
         return Err(std::io::Error::new(
             ErrorKind::InvalidData,
             format!(
-                "attribute semantic '{ATTR_ROTATION}' must use accessor type VEC4, got {:?}",
+                "attribute semantic '{semantic}' must use accessor type VEC4, got {:?}",
                 accessor.dimensions()
             ),
         ));
@@ -1644,8 +2639,8 @@ fn read_rotation_attribute(
 
     let normalized = accessor.normalized();
     let mut values = match accessor.data_type() {
-        DataType::F32 => read_items::<[f32; 4]>(accessor, buffers, ATTR_ROTATION)?,
-        DataType::I8 if normalized => read_items::<[i8; 4]>(accessor, buffers, ATTR_ROTATION)?
+        DataType::F32 => read_items::<[f32; 4]>(accessor, buffers, semantic)?,
+        DataType::I8 if normalized => read_items::<[i8; 4]>(accessor, buffers, semantic)?
             .into_iter()
             .map(|v| {
                 [
@@ -1656,7 +2651,7 @@ fn read_rotation_attribute(
                 ]
             })
             .collect(),
-        DataType::I16 if normalized => read_items::<[i16; 4]>(accessor, buffers, ATTR_ROTATION)?
+        DataType::I16 if normalized => read_items::<[i16; 4]>(accessor, buffers, semantic)?
             .into_iter()
             .map(|v| {
                 [
@@ -1671,7 +2666,7 @@ fn read_rotation_attribute(
             return Err(std::io::Error::new(
                 ErrorKind::InvalidData,
                 format!(
-                    "attribute semantic '{ATTR_ROTATION}' must use float or normalized signed integer components"
+                    "attribute semantic '{semantic}' must use float or normalized signed integer components"
                 ),
             ));
         }
@@ -1686,7 +2681,7 @@ fn read_rotation_attribute(
     if replaced_zero_length_quaternions > 0 {
         warn!(
             "attribute semantic '{}' contained {} zero-length quaternions; replacing them with identity rotations",
-            ATTR_ROTATION, replaced_zero_length_quaternions
+            semantic, replaced_zero_length_quaternions
         );
     }
 
@@ -1698,7 +2693,7 @@ fn read_rotation_attribute(
         return Err(std::io::Error::new(
             ErrorKind::InvalidData,
             format!(
-                "attribute semantic '{ATTR_ROTATION}' contains non-finite values, which are invalid"
+                "attribute semantic '{semantic}' contains non-finite values, which are invalid"
             ),
         ));
     }
@@ -1825,6 +2820,33 @@ fn read_opacity_attribute(
     Ok(values)
 }
 
+fn read_f32_scalar_attribute(
+    accessor: &Accessor<'_>,
+    buffers: &[Vec<u8>],
+    semantic: &str,
+) -> Result<Vec<f32>, std::io::Error> {
+    if accessor.dimensions() != Dimensions::Scalar || accessor.data_type() != DataType::F32 {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "attribute semantic '{semantic}' must use accessor type SCALAR with float components"
+            ),
+        ));
+    }
+
+    let values = read_items::<f32>(accessor, buffers, semantic)?;
+    if values.iter().any(|value| !value.is_finite()) {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "attribute semantic '{semantic}' contains non-finite values, which are invalid"
+            ),
+        ));
+    }
+
+    Ok(values)
+}
+
 fn read_color_attribute(
     accessor: &Accessor<'_>,
     buffers: &[Vec<u8>],
@@ -1926,6 +2948,7 @@ fn read_color_attribute(
 fn read_sh_coefficient_attribute(
     accessor: &Accessor<'_>,
     buffers: &[Vec<u8>],
+    (offset, scale): (f32, f32),
 ) -> Result<Vec<[f32; 3]>, std::io::Error> {
     if accessor.dimensions() != Dimensions::Vec3 {
         return Err(std::io::Error::new(
@@ -1934,14 +2957,33 @@ fn read_sh_coefficient_attribute(
         ));
     }
 
-    if accessor.data_type() != DataType::F32 {
-        return Err(std::io::Error::new(
-            ErrorKind::InvalidData,
-            "spherical harmonics attributes must use float components",
-        ));
-    }
-
-    let values = read_items::<[f32; 3]>(accessor, buffers, "KHR_gaussian_splatting:SH")?;
+    let semantic = "KHR_gaussian_splatting:SH";
+    let normalized = accessor.normalized();
+    let values = match accessor.data_type() {
+        DataType::F32 => read_items::<[f32; 3]>(accessor, buffers, semantic)?,
+        DataType::I8 if normalized => read_items::<[i8; 3]>(accessor, buffers, semantic)?
+            .into_iter()
+            .map(|v| v.map(normalize_i8))
+            .collect(),
+        DataType::I16 if normalized => read_items::<[i16; 3]>(accessor, buffers, semantic)?
+            .into_iter()
+            .map(|v| v.map(normalize_i16))
+            .collect(),
+        DataType::U8 if normalized => read_items::<[u8; 3]>(accessor, buffers, semantic)?
+            .into_iter()
+            .map(|v| v.map(normalize_u8))
+            .collect::<Vec<_>>(),
+        _ => {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                "spherical harmonics attributes must use float or normalized integer components",
+            ));
+        }
+    };
+    let values = values
+        .into_iter()
+        .map(|v| v.map(|component| offset + scale * component))
+        .collect::<Vec<_>>();
     if values
         .iter()
         .flatten()
@@ -2180,7 +3222,7 @@ mod tests {
         .into();
 
         let export_cloud = SceneExportCloud {
-            cloud,
+            cloud: cloud.into(),
             name: "cloud".to_owned(),
             settings: CloudSettings::default(),
             transform: Transform::default(),
@@ -2222,7 +3264,7 @@ mod tests {
         .into();
 
         let export_cloud = SceneExportCloud {
-            cloud,
+            cloud: cloud.into(),
             name: "cloud".to_owned(),
             settings: CloudSettings::default(),
             transform: Transform::default(),
@@ -2250,6 +3292,70 @@ mod tests {
         assert!(buffer.get("byteLength").and_then(Value::as_u64).unwrap() > 0);
     }
 
+    #[test]
+    fn round_trips_camera_projection_through_glb() {
+        let projections = [
+            SceneCameraProjection::Perspective {
+                yfov: 0.6,
+                aspect_ratio: Some(1.5),
+                znear: 0.1,
+                zfar: None,
+            },
+            SceneCameraProjection::Orthographic {
+                xmag: 2.0,
+                ymag: 1.5,
+                znear: 0.5,
+                zfar: 50.0,
+            },
+        ];
+
+        for projection in projections {
+            let cloud: PlanarGaussian3d = vec![Gaussian3d {
+                position_visibility: [1.0, 2.0, 3.0, 1.0].into(),
+                spherical_harmonic:
+                    crate::material::spherical_harmonics::SphericalHarmonicCoefficients::default(),
+                rotation: [1.0, 0.0, 0.0, 0.0].into(),
+                scale_opacity: [1.0, 1.0, 1.0, 0.5].into(),
+            }]
+            .into();
+
+            let export_cloud = SceneExportCloud {
+                cloud: cloud.into(),
+                name: "cloud".to_owned(),
+                settings: CloudSettings::default(),
+                transform: Transform::default(),
+                metadata: GaussianPrimitiveMetadata::default(),
+            };
+            let export_camera = SceneExportCamera {
+                projection: projection.clone(),
+                ..default()
+            };
+
+            let glb_bytes =
+                encode_khr_gaussian_scene_glb_bytes(&[export_cloud], Some(&export_camera)).unwrap();
+            let gltf = gltf::Gltf::from_slice_without_validation(&glb_bytes).unwrap();
+            let camera = gltf.cameras().next().unwrap();
+
+            assert_eq!(scene_camera_projection(&camera), projection);
+        }
+    }
+
+    #[test]
+    fn converts_orthographic_projection_both_ways() {
+        let projection = SceneCameraProjection::Orthographic {
+            xmag: 3.0,
+            ymag: 2.0,
+            znear: 0.0,
+            zfar: 100.0,
+        };
+
+        let bevy_projection = projection.to_projection();
+        assert_eq!(
+            SceneCameraProjection::from_projection(&bevy_projection),
+            Some(projection)
+        );
+    }
+
     #[test]
     fn normalizes_zero_length_quaternion_to_identity() {
         let mut quaternion = [0.0, 0.0, 0.0, 0.0];
@@ -2343,7 +3449,7 @@ mod tests {
         let accessors: Vec<_> = gltf.document.accessors().collect();
         let buffers = vec![buffer];
 
-        let rotations = read_rotation_attribute(&accessors[0], &buffers).unwrap();
+        let rotations = read_rotation_attribute(&accessors[0], &buffers, ATTR_ROTATION).unwrap();
         assert!((rotations[0][0] - 1.0).abs() < 1e-6);
         assert!(rotations[0][1].abs() < 1e-6);
         assert!(rotations[0][2].abs() < 1e-6);
@@ -2404,7 +3510,7 @@ mod tests {
         let cloud: PlanarGaussian3d = vec![invalid, valid].into();
 
         let export_cloud = SceneExportCloud {
-            cloud,
+            cloud: cloud.into(),
             name: "cloud".to_owned(),
             settings: CloudSettings::default(),
             transform: Transform::default(),
@@ -2435,4 +3541,60 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn encodes_4d_cloud_with_temporal_extension() {
+        let mut gaussian = Gaussian4d {
+            position_visibility: [1.0, 2.0, 3.0, 1.0].into(),
+            isotropic_rotations: IsotropicRotations {
+                rotation: [1.0, 0.0, 0.0, 0.0],
+                rotation_r: [0.0, 1.0, 0.0, 0.0],
+            },
+            scale_opacity: [1.0, 1.0, 1.0, 0.5].into(),
+            ..default()
+        };
+        gaussian.timestamp_timescale.timestamp = 0.25;
+        gaussian.timestamp_timescale.timescale = 0.5;
+        let cloud: PlanarGaussian4d = vec![gaussian].into();
+
+        let export_cloud = SceneExportCloud {
+            cloud: cloud.into(),
+            name: "actor".to_owned(),
+            settings: CloudSettings {
+                time_start: 0.0,
+                time_stop: 2.0,
+                ..default()
+            },
+            transform: Transform::default(),
+            metadata: GaussianPrimitiveMetadata::default(),
+        };
+
+        let bytes = encode_khr_gaussian_scene_gltf_bytes(&[export_cloud], None).unwrap();
+        let root: Value = serde_json::from_slice(&bytes).unwrap();
+
+        let extensions_used = root["extensionsUsed"].as_array().unwrap();
+        assert!(
+            extensions_used
+                .iter()
+                .any(|value| value == KHR_GAUSSIAN_SPLATTING_EXTENSION)
+        );
+        assert!(
+            extensions_used
+                .iter()
+                .any(|value| value == EXT_GAUSSIAN_SPLATTING_4D)
+        );
+
+        let primitive = &root["meshes"][0]["primitives"][0];
+        for semantic in [ATTR_TIMESTAMP, ATTR_TIMESCALE, ATTR_ROTATION_R] {
+            assert!(
+                primitive["attributes"][semantic].is_u64(),
+                "missing {semantic}"
+            );
+        }
+        assert_eq!(
+            primitive["extensions"][KHR_GAUSSIAN_SPLATTING_EXTENSION]["extensions"]
+                [EXT_GAUSSIAN_SPLATTING_4D]["playbackRange"],
+            serde_json::json!([0.0, 2.0])
+        );
+    }
 }
@@ -0,0 +1,545 @@
+use bevy::{math::Affine3A, prelude::*};
+use bevy_interleave::prelude::Planar;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gaussian::formats::{
+        planar_3d::{Gaussian3d, PlanarGaussian3d},
+        planar_4d::{Gaussian4d, PlanarGaussian4d},
+        planar_4d_hierarchy::{TemporalGaussianHierarchy, TemporalGaussianLevel},
+        spacetime::{GaussianSpacetime, PlanarGaussianSpacetime},
+    },
+    material::spherical_harmonics::ShRotation,
+};
+
+/// log scale spread allowed around the per-gaussian mean when decoding raw 3d plys
+pub const MAX_SIZE_VARIANCE: f32 = 4.0;
+
+/// gaussian counts are padded to a multiple of the sort workgroup size
+pub const DEFAULT_PAD_TO: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum CameraConvention {
+    /// y up, -z forward
+    #[default]
+    OpenGl,
+    /// y down, +z forward, as written by colmap based trainers
+    OpenCv,
+}
+
+/// `.meta` settings shared by every gaussian loader, clouds are processed by `apply_3d`,
+/// `apply_4d` or `apply_spacetime`, stored formats are converted through them and rebuilt
+///
+/// the default pads clouds to `DEFAULT_PAD_TO` and clamps the log scale spread of raw 3d plys to
+/// `MAX_SIZE_VARIANCE`, formats storing activated values are left as decoded
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GaussianLoaderSettings {
+    pub up_axis: UpAxis,
+    pub camera_convention: CameraConvention,
+    /// baked after the axis conversion, non-uniform scales are averaged
+    pub transform: Transform,
+    /// `None` keeps the format convention, 3d and spacetime plys store logits and log scales, every
+    /// other format stores activated values, default loads activate exactly as before the settings
+    pub activations_applied: Option<bool>,
+    /// 3d clouds only, `None` clamps stored log scales to `MAX_SIZE_VARIANCE` and leaves activated
+    /// scales alone, `Some(f32::INFINITY)` disables the clamp
+    pub max_scale_variance: Option<f32>,
+    pub opacity_clamp: Option<(f32, f32)>,
+    /// drops gaussians with an opacity below the threshold
+    pub prune_opacity: Option<f32>,
+    /// clears sh bands above the degree, the compiled degree is the upper bound
+    pub sh_degree: Option<usize>,
+    /// clouds are padded with empty gaussians to a multiple of `pad_to`, 0 disables padding
+    pub pad_to: usize,
+}
+
+impl Default for GaussianLoaderSettings {
+    fn default() -> Self {
+        Self {
+            up_axis: UpAxis::Y,
+            camera_convention: CameraConvention::OpenGl,
+            transform: Transform::IDENTITY,
+            activations_applied: None,
+            max_scale_variance: None,
+            opacity_clamp: None,
+            prune_opacity: None,
+            sh_degree: None,
+            pad_to: DEFAULT_PAD_TO,
+        }
+    }
+}
+
+impl GaussianLoaderSettings {
+    /// rotation from the file convention to bevy's y up frame
+    pub fn axis_rotation(&self) -> Quat {
+        let camera = match self.camera_convention {
+            CameraConvention::OpenGl => Quat::IDENTITY,
+            CameraConvention::OpenCv => Quat::from_rotation_x(std::f32::consts::PI),
+        };
+
+        // (x, y, z) -> (x, z, -y)
+        let up = match self.up_axis {
+            UpAxis::Y => Quat::IDENTITY,
+            UpAxis::Z => Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+        };
+
+        up * camera
+    }
+
+    fn baked_affine(&self) -> Affine3A {
+        self.transform.compute_affine() * Affine3A::from_quat(self.axis_rotation())
+    }
+
+    fn baked_rotation(&self) -> Quat {
+        self.transform.rotation * self.axis_rotation()
+    }
+
+    fn sh_rotation(&self) -> Option<ShRotation> {
+        let rotation = self.baked_rotation();
+
+        (rotation != Quat::IDENTITY).then(|| ShRotation::new(rotation))
+    }
+
+    fn baked_scale(&self) -> f32 {
+        self.transform.scale.abs().element_sum() / 3.0
+    }
+
+    fn bakes_transform(&self) -> bool {
+        self.up_axis != UpAxis::Y
+            || self.camera_convention != CameraConvention::OpenGl
+            || self.transform != Transform::IDENTITY
+    }
+
+    /// true when `process_3d`, `process_4d` and `process_spacetime` leave the gaussians unchanged
+    pub fn is_passthrough(&self) -> bool {
+        !self.bakes_transform()
+            && self.opacity_clamp.is_none()
+            && self.prune_opacity.is_none()
+            && self.sh_degree.is_none()
+    }
+
+    /// true when decoded 3d clouds need neither activations nor a scale clamp
+    fn keeps_activations_3d(&self) -> bool {
+        self.activations_applied != Some(false) && self.max_scale_variance.is_none()
+    }
+
+    /// true when `pad` leaves a cloud of `len` gaussians unchanged
+    /// true when `apply_3d` changes more than the padding
+    fn rebuilds_3d(&self) -> bool {
+        !self.is_passthrough() || !self.keeps_activations_3d()
+    }
+
+    /// true when `apply_4d` changes more than the padding
+    fn rebuilds_4d(&self) -> bool {
+        !self.is_passthrough() || self.activations_applied == Some(false)
+    }
+
+    /// stored formats index their gaussians and are never padded
+    fn unpadded(&self) -> Self {
+        Self {
+            pad_to: 0,
+            ..self.clone()
+        }
+    }
+
+    fn is_padded(&self, len: usize) -> bool {
+        self.pad_to != 0 && len.is_multiple_of(self.pad_to)
+    }
+
+    fn clamp_opacity(&self, opacity: f32) -> f32 {
+        match self.opacity_clamp {
+            Some((min, max)) => opacity.clamp(min, max),
+            None => opacity,
+        }
+    }
+
+    fn keeps(&self, opacity: f32) -> bool {
+        self.prune_opacity
+            .is_none_or(|threshold| opacity >= threshold)
+    }
+
+    /// true when `process_3d` keeps the gaussian
+    pub fn retains_3d(&self, gaussian: &Gaussian3d) -> bool {
+        self.keeps(self.clamp_opacity(gaussian.scale_opacity.opacity))
+    }
+
+    /// sigmoid on opacity and exp on log scales unless the activations are applied, `activated` is
+    /// the format convention, then clamps the spread of the first `axes` log scales, surfels keep
+    /// a zero third scale
+    ///
+    /// activated scales are only clamped when `max_scale_variance` is set
+    pub fn activate_3d(&self, gaussian: &mut Gaussian3d, activated: bool, axes: usize) {
+        let activated = self.activations_applied.unwrap_or(activated);
+        if !activated {
+            let opacity = &mut gaussian.scale_opacity.opacity;
+            *opacity = 1.0 / (1.0 + (-*opacity).exp());
+        }
+
+        let max_scale_variance = self
+            .max_scale_variance
+            .or((!activated).then_some(MAX_SIZE_VARIANCE));
+
+        let scale = &mut gaussian.scale_opacity.scale;
+        scale[axes..].fill(0.0);
+        if activated && max_scale_variance.is_none() {
+            return;
+        }
+
+        // clamping happens on log scales, activated scales are taken back to log space
+        let scale = &mut scale[..axes];
+        if activated {
+            for value in scale.iter_mut() {
+                *value = value.max(f32::MIN_POSITIVE).ln();
+            }
+        }
+
+        let mean_scale = scale.iter().sum::<f32>() / axes as f32;
+        for value in scale.iter_mut() {
+            if let Some(variance) = max_scale_variance {
+                *value = value.clamp(mean_scale - variance, mean_scale + variance);
+            }
+            *value = value.exp();
+        }
+    }
+
+    /// exp on scales and sigmoid on opacity when the activations are not applied, 4d formats store
+    /// activated values
+    pub fn activate_4d(&self, gaussian: &mut Gaussian4d) {
+        if self.activations_applied == Some(false) {
+            for value in &mut gaussian.scale_opacity.scale {
+                *value = value.exp();
+            }
+
+            let opacity = &mut gaussian.scale_opacity.opacity;
+            *opacity = 1.0 / (1.0 + (-*opacity).exp());
+        }
+    }
+
+    /// exp on scales and the temporal rbf scale and sigmoid on opacity unless the activations are
+    /// applied, `activated` is the format convention
+    pub fn activate_spacetime(&self, gaussian: &mut GaussianSpacetime, activated: bool) {
+        if !self.activations_applied.unwrap_or(activated) {
+            for value in &mut gaussian.scale_opacity.scale {
+                *value = value.exp();
+            }
+            gaussian.motion.trbf_scale = gaussian.motion.trbf_scale.exp();
+
+            let opacity = &mut gaussian.scale_opacity.opacity;
+            *opacity = 1.0 / (1.0 + (-*opacity).exp());
+        }
+    }
+
+    /// bakes the transform, clamps opacity, prunes and limits the sh degree
+    ///
+    /// sh bands are rotated with the cloud so view dependent color follows the baked rotation
+    pub fn process_3d(&self, gaussians: &mut Vec<Gaussian3d>) {
+        let bake = self.bakes_transform();
+        let affine = self.baked_affine();
+        let rotation = self.baked_rotation();
+        let sh_rotation = self.sh_rotation();
+        let scale = self.baked_scale();
+
+        for gaussian in gaussians.iter_mut() {
+            if bake {
+                let position = Vec3::from(gaussian.position_visibility.position);
+                gaussian.position_visibility.position =
+                    affine.transform_point3(position).to_array();
+
+                let [w, x, y, z] = gaussian.rotation.rotation;
+                let rotated = rotation * Quat::from_xyzw(x, y, z, w);
+                gaussian.rotation.rotation = [rotated.w, rotated.x, rotated.y, rotated.z];
+
+                if let Some(sh_rotation) = &sh_rotation {
+                    gaussian.spherical_harmonic.rotate(sh_rotation);
+                }
+
+                for value in gaussian.scale_opacity.scale.iter_mut() {
+                    *value *= scale;
+                }
+            }
+
+            gaussian.scale_opacity.opacity = self.clamp_opacity(gaussian.scale_opacity.opacity);
+
+            if let Some(degree) = self.sh_degree {
+                gaussian.spherical_harmonic.project_degree(degree);
+            }
+        }
+
+        gaussians.retain(|gaussian| self.keeps(gaussian.scale_opacity.opacity));
+    }
+
+    /// same as `process_3d`, the spatial rotation is folded into the isoclinic pair
+    pub fn process_4d(&self, gaussians: &mut Vec<Gaussian4d>) {
+        let bake = self.bakes_transform();
+        let affine = self.baked_affine();
+        let (left, right) = isoclinic_spatial_rotation(self.baked_rotation());
+        let sh_rotation = self.sh_rotation();
+        let scale = self.baked_scale();
+
+        for gaussian in gaussians.iter_mut() {
+            if bake {
+                let position = Vec3::from(gaussian.position_visibility.position);
+                gaussian.position_visibility.position =
+                    affine.transform_point3(position).to_array();
+
+                let rotations = &mut gaussian.isotropic_rotations;
+                rotations.rotation = quat_mul_wxyz(left, rotations.rotation);
+                rotations.rotation_r = quat_mul_wxyz(right, rotations.rotation_r);
+
+                if let Some(sh_rotation) = &sh_rotation {
+                    gaussian.spherindrical_harmonic.rotate(sh_rotation);
+                }
+
+                for value in gaussian.scale_opacity.scale.iter_mut() {
+                    *value *= scale;
+                }
+            }
+
+            gaussian.scale_opacity.opacity = self.clamp_opacity(gaussian.scale_opacity.opacity);
+
+            if let Some(degree) = self.sh_degree {
+                gaussian.spherindrical_harmonic.project_degree(degree);
+            }
+        }
+
+        gaussians.retain(|gaussian| self.keeps(gaussian.scale_opacity.opacity));
+    }
+
+    /// same as `process_3d`, motion coefficients and angular velocity follow the baked transform
+    pub fn process_spacetime(&self, gaussians: &mut Vec<GaussianSpacetime>) {
+        let bake = self.bakes_transform();
+        let affine = self.baked_affine();
+        let rotation = self.baked_rotation();
+        let sh_rotation = self.sh_rotation();
+        let scale = self.baked_scale();
+
+        for gaussian in gaussians.iter_mut() {
+            if bake {
+                let position = Vec3::from(gaussian.position_visibility.position);
+                gaussian.position_visibility.position =
+                    affine.transform_point3(position).to_array();
+
+                // the trajectory is a polynomial in time, each coefficient is a displacement
+                for coefficient in gaussian.motion.motion.chunks_exact_mut(3) {
+                    let displaced = affine.transform_vector3(Vec3::from_slice(coefficient));
+                    coefficient.copy_from_slice(&displaced.to_array());
+                }
+
+                // the shader normalizes `rotation + omega * dt`, both rotate linearly
+                let [w, x, y, z] = gaussian.rotation.rotation;
+                let rotated = rotation * Quat::from_xyzw(x, y, z, w);
+                gaussian.rotation.rotation = [rotated.w, rotated.x, rotated.y, rotated.z];
+
+                let [w, x, y, z] = gaussian.angular_velocity.omega;
+                let rotated = rotation * Quat::from_xyzw(x, y, z, w);
+                gaussian.angular_velocity.omega = [rotated.w, rotated.x, rotated.y, rotated.z];
+
+                if let Some(sh_rotation) = &sh_rotation {
+                    gaussian.spherical_harmonic.rotate(sh_rotation);
+                }
+
+                for value in gaussian.scale_opacity.scale.iter_mut() {
+                    *value *= scale;
+                }
+            }
+
+            gaussian.scale_opacity.opacity = self.clamp_opacity(gaussian.scale_opacity.opacity);
+
+            if let Some(degree) = self.sh_degree {
+                gaussian.spherical_harmonic.project_degree(degree);
+            }
+        }
+
+        gaussians.retain(|gaussian| self.keeps(gaussian.scale_opacity.opacity));
+    }
+
+    /// pads with empty gaussians to a multiple of `pad_to`, aligned clouds are left as they are
+    pub fn pad<T: Default + Clone>(&self, gaussians: &mut Vec<T>) {
+        if self.pad_to == 0 {
+            return;
+        }
+
+        gaussians.resize(gaussians.len().next_multiple_of(self.pad_to), T::default());
+    }
+
+    /// activates, processes and pads a decoded cloud, existing padding is dropped and redone
+    ///
+    /// padded clouds the settings leave unchanged are returned as decoded
+    pub fn apply_3d(&self, cloud: PlanarGaussian3d) -> PlanarGaussian3d {
+        if self.is_passthrough() && self.keeps_activations_3d() && self.is_padded(cloud.len()) {
+            return cloud;
+        }
+
+        let len = unpadded_len(cloud.len(), |index| cloud.get(index));
+        let mut gaussians = cloud.iter().take(len).collect::<Vec<_>>();
+
+        for gaussian in gaussians.iter_mut() {
+            // flat surfels are stored with a zero third scale
+            let axes = if gaussian.scale_opacity.scale[2] == 0.0 {
+                2
+            } else {
+                3
+            };
+            self.activate_3d(gaussian, true, axes);
+        }
+
+        self.process_3d(&mut gaussians);
+        self.pad(&mut gaussians);
+
+        PlanarGaussian3d::from_interleaved(gaussians)
+    }
+
+    pub fn apply_4d(&self, cloud: PlanarGaussian4d) -> PlanarGaussian4d {
+        if self.is_passthrough()
+            && self.activations_applied != Some(false)
+            && self.is_padded(cloud.len())
+        {
+            return cloud;
+        }
+
+        let len = unpadded_len(cloud.len(), |index| cloud.get(index));
+        let mut gaussians = (0..len).map(|index| cloud.get(index)).collect::<Vec<_>>();
+
+        for gaussian in gaussians.iter_mut() {
+            self.activate_4d(gaussian);
+        }
+
+        self.process_4d(&mut gaussians);
+        self.pad(&mut gaussians);
+
+        PlanarGaussian4d::from_interleaved(gaussians)
+    }
+
+    /// runs a stored 3d format through `apply_3d`, `rebuild` encodes the processed cloud like the
+    /// decoded one, clouds the settings leave unchanged are returned as decoded
+    pub fn apply_3d_stored<T>(
+        &self,
+        cloud: T,
+        rebuild: impl FnOnce(&PlanarGaussian3d, &T) -> T,
+    ) -> T
+    where
+        PlanarGaussian3d: for<'a> From<&'a T>,
+    {
+        if !self.rebuilds_3d() {
+            return cloud;
+        }
+
+        let processed = self.unpadded().apply_3d(PlanarGaussian3d::from(&cloud));
+        rebuild(&processed, &cloud)
+    }
+
+    /// same as `apply_3d_stored` through `apply_4d`
+    pub fn apply_4d_stored<T>(
+        &self,
+        cloud: T,
+        rebuild: impl FnOnce(&PlanarGaussian4d, &T) -> T,
+    ) -> T
+    where
+        PlanarGaussian4d: for<'a> From<&'a T>,
+    {
+        if !self.rebuilds_4d() {
+            return cloud;
+        }
+
+        let processed = self.unpadded().apply_4d(PlanarGaussian4d::from(&cloud));
+        rebuild(&processed, &cloud)
+    }
+
+    /// processes every level on its own so pruning keeps the partition, emptied levels are dropped
+    /// and the time ranges follow the baked scale
+    pub fn apply_temporal_hierarchy(
+        &self,
+        hierarchy: TemporalGaussianHierarchy,
+    ) -> TemporalGaussianHierarchy {
+        if !self.rebuilds_4d() {
+            return hierarchy;
+        }
+
+        let settings = self.unpadded();
+        let mut gaussians = Vec::with_capacity(hierarchy.flat_cloud.len());
+        let mut levels = Vec::with_capacity(hierarchy.levels.len());
+        for level in &hierarchy.levels {
+            let indices = level.instances().collect::<Vec<_>>();
+            let cloud = settings.apply_4d(hierarchy.flat_cloud.subset(&indices));
+            if cloud.is_empty() {
+                continue;
+            }
+
+            let level = (0..cloud.len())
+                .map(|index| cloud.get(index))
+                .collect::<Vec<_>>();
+            levels.push(TemporalGaussianLevel::spanning(gaussians.len(), &level));
+            gaussians.extend(level);
+        }
+
+        TemporalGaussianHierarchy {
+            flat_cloud: PlanarGaussian4d::from_interleaved(gaussians),
+            levels,
+        }
+    }
+
+    pub fn apply_spacetime(&self, cloud: PlanarGaussianSpacetime) -> PlanarGaussianSpacetime {
+        if self.is_passthrough()
+            && self.activations_applied != Some(false)
+            && self.is_padded(cloud.len())
+        {
+            return cloud;
+        }
+
+        let len = unpadded_len(cloud.len(), |index| cloud.get(index));
+        let mut gaussians = (0..len).map(|index| cloud.get(index)).collect::<Vec<_>>();
+
+        for gaussian in gaussians.iter_mut() {
+            self.activate_spacetime(gaussian, true);
+        }
+
+        self.process_spacetime(&mut gaussians);
+        self.pad(&mut gaussians);
+
+        PlanarGaussianSpacetime::from_interleaved(gaussians)
+    }
+}
+
+/// length of a cloud without the trailing empty gaussians `GaussianLoaderSettings::pad` appends,
+/// writers use it so a load and write round trip keeps the cloud size
+pub fn unpadded_len<T: Default + PartialEq>(len: usize, gaussian: impl Fn(usize) -> T) -> usize {
+    let empty = T::default();
+
+    (0..len)
+        .rev()
+        .find(|&index| gaussian(index) != empty)
+        .map_or(0, |index| index + 1)
+}
+
+/// 4d rotations are `x -> l * x * conj(r)` over quaternions with t on the k axis,
+/// returns the wxyz factors that left-multiply `l` and `r` to rotate space around t
+pub fn isoclinic_spatial_rotation(rotation: Quat) -> ([f32; 4], [f32; 4]) {
+    let [x, y, z, w] = rotation.to_array();
+
+    // x, y, z live on the 1, i, j axes, the mapping is a reflection so the angle flips
+    let left = [w, z, -y, x];
+    // conj(k) * left * k
+    let right = [w, -z, y, x];
+
+    (left, right)
+}
+
+fn quat_mul_wxyz(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let [aw, ax, ay, az] = a;
+    let [bw, bx, by, bz] = b;
+
+    [
+        aw * bw - ax * bx - ay * by - az * bz,
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+    ]
+}
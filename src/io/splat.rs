@@ -0,0 +1,134 @@
+// https://github.com/antimatter15/splat
+use std::io::{Read, Write};
+
+use bevy_interleave::prelude::Planar;
+
+use crate::{
+    gaussian::formats::planar_3d::{Gaussian3d, PlanarGaussian3d},
+    io::settings::unpadded_len,
+    material::spherical_harmonics::SH_CHANNELS,
+};
+
+/// position (3 x f32), scale (3 x f32), rgba (4 x u8), rotation (4 x u8)
+pub const SPLAT_BYTES_PER_GAUSSIAN: usize = 32;
+
+pub(crate) const SH_C0: f32 = 0.282_094_8;
+
+pub fn parse_splat_3d(reader: &mut dyn Read) -> Result<PlanarGaussian3d, std::io::Error> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    if data.len() % SPLAT_BYTES_PER_GAUSSIAN != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "splat size {} is not a multiple of {SPLAT_BYTES_PER_GAUSSIAN} bytes",
+                data.len()
+            ),
+        ));
+    }
+
+    let gaussians = data
+        .chunks_exact(SPLAT_BYTES_PER_GAUSSIAN)
+        .map(|record| {
+            let f32_at = |offset: usize| {
+                f32::from_le_bytes([
+                    record[offset],
+                    record[offset + 1],
+                    record[offset + 2],
+                    record[offset + 3],
+                ])
+            };
+
+            let mut gaussian = Gaussian3d::default();
+            gaussian.position_visibility.position = std::array::from_fn(|axis| f32_at(axis * 4));
+            gaussian.scale_opacity.scale = std::array::from_fn(|axis| f32_at(12 + axis * 4));
+
+            for channel in 0..SH_CHANNELS {
+                gaussian
+                    .spherical_harmonic
+                    .set(channel, color_to_sh_dc(record[24 + channel]));
+            }
+            gaussian.scale_opacity.opacity = record[27] as f32 / 255.0;
+
+            // wxyz, each component mapped from [-1, 1] to [0, 255]
+            let rotation: [f32; 4] =
+                std::array::from_fn(|i| (record[28 + i] as f32 - 128.0) / 128.0);
+            gaussian.rotation.rotation = normalize_rotation(rotation);
+
+            gaussian
+        })
+        .collect::<Vec<_>>();
+
+    Ok(PlanarGaussian3d::from_interleaved(gaussians))
+}
+
+/// only the sh dc term survives, higher bands are dropped
+pub fn write_splat_3d(
+    cloud: &PlanarGaussian3d,
+    writer: &mut dyn Write,
+) -> Result<(), std::io::Error> {
+    let mut record = [0u8; SPLAT_BYTES_PER_GAUSSIAN];
+
+    // loaded clouds are padded, the padding is not part of the file
+    let count = unpadded_len(cloud.len(), |index| cloud.get(index));
+
+    for gaussian in (0..count).map(|index| cloud.get(index)) {
+        for axis in 0..3 {
+            record[axis * 4..axis * 4 + 4]
+                .copy_from_slice(&gaussian.position_visibility.position[axis].to_le_bytes());
+            record[12 + axis * 4..16 + axis * 4]
+                .copy_from_slice(&gaussian.scale_opacity.scale[axis].to_le_bytes());
+        }
+
+        for channel in 0..SH_CHANNELS {
+            record[24 + channel] = sh_dc_to_color(gaussian.spherical_harmonic.get(channel));
+        }
+        record[27] = to_u8(gaussian.scale_opacity.opacity * 255.0);
+
+        let rotation = normalize_rotation(gaussian.rotation.rotation);
+        for (i, value) in rotation.iter().enumerate() {
+            record[28 + i] = to_u8(value * 128.0 + 128.0);
+        }
+
+        writer.write_all(&record)?;
+    }
+
+    Ok(())
+}
+
+pub fn decode_splat_3d(data: &[u8]) -> Result<PlanarGaussian3d, std::io::Error> {
+    parse_splat_3d(&mut std::io::Cursor::new(data))
+}
+
+pub fn encode_splat_3d(cloud: &PlanarGaussian3d) -> Result<Vec<u8>, std::io::Error> {
+    let mut output = Vec::with_capacity(cloud.len() * SPLAT_BYTES_PER_GAUSSIAN);
+    write_splat_3d(cloud, &mut output)?;
+
+    Ok(output)
+}
+
+pub(crate) fn color_to_sh_dc(color: u8) -> f32 {
+    (color as f32 / 255.0 - 0.5) / SH_C0
+}
+
+pub(crate) fn sh_dc_to_color(dc: f32) -> u8 {
+    to_u8((0.5 + SH_C0 * dc) * 255.0)
+}
+
+pub(crate) fn normalize_rotation(rotation: [f32; 4]) -> [f32; 4] {
+    let norm = rotation
+        .iter()
+        .map(|value| value * value)
+        .sum::<f32>()
+        .sqrt();
+    if norm <= f32::EPSILON {
+        return [1.0, 0.0, 0.0, 0.0];
+    }
+
+    rotation.map(|value| value / norm)
+}
+
+fn to_u8(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
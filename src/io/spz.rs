@@ -0,0 +1,38 @@
+use std::io::{Read, Write};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use crate::gaussian::formats::{planar_3d::PlanarGaussian3d, planar_3d_spz::PlanarGaussian3dSpz};
+
+pub fn parse_spz_3d(reader: &mut dyn Read) -> Result<PlanarGaussian3d, std::io::Error> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(reader).read_to_end(&mut decompressed)?;
+
+    let spz = PlanarGaussian3dSpz::from_bytes(&decompressed)?;
+
+    Ok(spz.to_planar())
+}
+
+pub fn write_spz_3d(
+    cloud: &PlanarGaussian3d,
+    writer: &mut dyn Write,
+) -> Result<(), std::io::Error> {
+    let spz = PlanarGaussian3dSpz::from(cloud);
+
+    let mut gz_encoder = GzEncoder::new(writer, Compression::default());
+    gz_encoder.write_all(&spz.to_bytes())?;
+    gz_encoder.finish()?;
+
+    Ok(())
+}
+
+pub fn decode_spz_3d(data: &[u8]) -> Result<PlanarGaussian3d, std::io::Error> {
+    parse_spz_3d(&mut std::io::Cursor::new(data))
+}
+
+pub fn encode_spz_3d(cloud: &PlanarGaussian3d) -> Result<Vec<u8>, std::io::Error> {
+    let mut output = Vec::new();
+    write_spz_3d(cloud, &mut output)?;
+
+    Ok(output)
+}
@@ -12,27 +12,61 @@ pub use gaussian::{
             Gaussian3d, PlanarGaussian3d, PlanarGaussian3dHandle, random_gaussians_3d,
             random_gaussians_3d_seeded,
         },
+        planar_3d_chunked::{ChunkedCloudBuilder, PlanarGaussian3dChunked},
+        planar_3d_codebook::{
+            Gaussian3dCodebook, PlanarGaussian3dCodebook, PlanarGaussian3dCodebookHandle,
+            ShCodebookBuilder,
+        },
+        planar_3d_lod::{LodCloudBuilder, PlanarGaussian3dLod},
+        planar_3d_quantized::{
+            Gaussian3dQuantized, PlanarGaussian3dQuantized, PlanarGaussian3dQuantizedHandle,
+        },
         planar_4d::{
             Gaussian4d, PlanarGaussian4d, PlanarGaussian4dHandle, random_gaussians_4d,
             random_gaussians_4d_seeded,
         },
+        planar_4d_hierarchy::{TemporalGaussianHierarchy, TemporalHierarchyBuilder},
+        planar_4d_quantized::{
+            Gaussian4dQuantized, PlanarGaussian4dQuantized, PlanarGaussian4dQuantizedHandle,
+        },
+        spacetime::{
+            GaussianSpacetime, PlanarGaussianSpacetime, PlanarGaussianSpacetimeHandle,
+            random_gaussians_spacetime,
+        },
+    },
+    lod::{GaussianLod, LodSelection, PlanarGaussian3dLodHandle},
+    settings::{
+        CloudSettings, DepthMode, GaussianMode, OitWeight, RadixSortDepthBits, RasterizeMode,
+        Rasterizer,
     },
-    settings::{CloudSettings, GaussianMode, RadixSortDepthBits, RasterizeMode},
 };
 
 pub use io::scene::{
     GaussianKernel, GaussianPrimitiveMetadata, GaussianPrimitiveSpec, GaussianProjection,
-    GaussianScene, GaussianSceneHandle, GaussianSortingMethod, SceneCamera, SceneExportCamera,
-    SceneExportCloud, write_khr_gaussian_scene_glb, write_khr_gaussian_scene_gltf,
+    GaussianScene, GaussianSceneCameras, GaussianSceneHandle, GaussianSortingMethod, SceneCamera,
+    SceneCameraProjection, SceneCloudHandle, SceneExportCamera, SceneExportCloud,
+    SceneExportGaussians, SceneExportOptions, write_khr_gaussian_scene_glb,
+    write_khr_gaussian_scene_glb_with_options, write_khr_gaussian_scene_gltf,
+    write_khr_gaussian_scene_gltf_with_options,
 };
 
-pub use material::spherical_harmonics::SphericalHarmonicCoefficients;
+pub use io::settings::{CameraConvention, GaussianLoaderSettings, UpAxis};
+
+pub use lighting::GaussianLighting;
+
+pub use material::{
+    pbr::{PbrAttributes, PbrMaterial, PbrMaterialHandle},
+    spherical_harmonics::SphericalHarmonicCoefficients,
+};
+
+pub use stream::hierarchy::{TemporalGaussianHierarchyHandle, TemporalStreaming};
 
 use io::IoPlugin;
 
 pub mod camera;
 pub mod gaussian;
 pub mod io;
+pub mod lighting;
 pub mod material;
 pub mod math;
 pub mod morph;
@@ -57,22 +91,40 @@ impl Plugin for GaussianSplattingPlugin {
         app.add_plugins((
             camera::GaussianCameraPlugin,
             gaussian::settings::SettingsPlugin,
+            gaussian::lod::LodPlugin,
+            stream::hierarchy::TemporalHierarchyPlugin,
             gaussian::cloud::CloudPlugin::<Gaussian3d>::default(),
+            gaussian::cloud::CloudPlugin::<Gaussian3dQuantized>::default(),
+            gaussian::cloud::CloudPlugin::<Gaussian3dCodebook>::default(),
             gaussian::cloud::CloudPlugin::<Gaussian4d>::default(),
+            gaussian::cloud::CloudPlugin::<Gaussian4dQuantized>::default(),
+            gaussian::cloud::CloudPlugin::<GaussianSpacetime>::default(),
         ));
 
         // TODO: add half types
         app.add_plugins((
             PlanarStoragePlugin::<Gaussian3d>::default(),
+            PlanarStoragePlugin::<Gaussian3dQuantized>::default(),
+            PlanarStoragePlugin::<Gaussian3dCodebook>::default(),
             PlanarStoragePlugin::<Gaussian4d>::default(),
+            PlanarStoragePlugin::<Gaussian4dQuantized>::default(),
+            PlanarStoragePlugin::<GaussianSpacetime>::default(),
         ));
 
         app.add_plugins((
             render::RenderPipelinePlugin::<Gaussian3d>::default(),
+            render::RenderPipelinePlugin::<Gaussian3dQuantized>::default(),
+            render::RenderPipelinePlugin::<Gaussian3dCodebook>::default(),
             render::RenderPipelinePlugin::<Gaussian4d>::default(),
+            render::RenderPipelinePlugin::<Gaussian4dQuantized>::default(),
+            render::RenderPipelinePlugin::<GaussianSpacetime>::default(),
         ));
 
-        app.add_plugins((material::MaterialPlugin, query::QueryPlugin));
+        app.add_plugins((
+            lighting::LightingPlugin,
+            material::MaterialPlugin,
+            query::QueryPlugin,
+        ));
 
         #[cfg(feature = "noise")]
         app.add_plugins(noise::NoisePlugin);
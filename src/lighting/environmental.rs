@@ -0,0 +1,56 @@
+use bevy::{
+    light::EnvironmentMapLight,
+    prelude::*,
+    render::{
+        Extract, render_asset::RenderAssets, render_resource::TextureViewDimension,
+        texture::GpuImage,
+    },
+};
+
+use crate::camera::GaussianCamera;
+
+/// environment map of the first gaussian camera carrying an `EnvironmentMapLight`
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GaussianEnvironment {
+    pub diffuse_map: Option<AssetId<Image>>,
+    pub specular_map: Option<AssetId<Image>>,
+    pub intensity: f32,
+    pub rotation: Quat,
+}
+
+impl GaussianEnvironment {
+    /// diffuse and specular cubemaps, once both are on the gpu
+    pub fn maps<'a>(
+        &self,
+        gpu_images: &'a RenderAssets<GpuImage>,
+    ) -> Option<(&'a GpuImage, &'a GpuImage)> {
+        let diffuse = gpu_images.get(self.diffuse_map?)?;
+        let specular = gpu_images.get(self.specular_map?)?;
+
+        (is_cubemap(diffuse) && is_cubemap(specular)).then_some((diffuse, specular))
+    }
+}
+
+fn is_cubemap(image: &GpuImage) -> bool {
+    image
+        .texture_view_descriptor
+        .as_ref()
+        .and_then(|descriptor| descriptor.dimension)
+        == Some(TextureViewDimension::Cube)
+}
+
+pub fn extract_gaussian_environment(
+    mut environment: ResMut<GaussianEnvironment>,
+    cameras: Extract<Query<&EnvironmentMapLight, With<GaussianCamera>>>,
+) {
+    *environment = cameras
+        .iter()
+        .next()
+        .map(|light| GaussianEnvironment {
+            diffuse_map: Some(light.diffuse_map.id()),
+            specular_map: Some(light.specular_map.id()),
+            intensity: light.intensity,
+            rotation: light.rotation,
+        })
+        .unwrap_or_default();
+}
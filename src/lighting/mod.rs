@@ -0,0 +1,361 @@
+#![allow(dead_code)] // ShaderType derives emit unused check helpers
+use std::f32::consts::PI;
+
+use bevy::{
+    prelude::*,
+    render::{
+        Extract, ExtractSchedule, Render, RenderApp, RenderSystems,
+        render_asset::RenderAssets,
+        render_resource::*,
+        renderer::{RenderDevice, RenderQueue},
+        texture::GpuImage,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "buffer_storage")]
+use bevy::render::texture::FallbackImage;
+#[cfg(feature = "buffer_storage")]
+use bevy_interleave::prelude::*;
+
+#[cfg(feature = "buffer_storage")]
+use crate::{
+    material::pbr::{FallbackPbrMaterial, GpuPbrMaterial, PbrMaterialHandle},
+    render::CloudPipeline,
+    sort::{GpuSortedEntry, SortEntry, SortedEntriesHandle},
+};
+
+pub mod environmental;
+
+use environmental::GaussianEnvironment;
+
+/// lights beyond these counts are ignored by relit clouds
+pub const MAX_GAUSSIAN_DIRECTIONAL_LIGHTS: usize = 4;
+pub const MAX_GAUSSIAN_POINT_LIGHTS: usize = 16;
+
+/// selects between the trained color of a cloud and shading its `PbrMaterial` with the scene lights
+///
+/// relighting requires `buffer_storage` and per-gaussian rotations, tile rasterized and 4d clouds keep their baked color
+#[derive(
+    Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize,
+)]
+#[reflect(Component, Default)]
+pub enum GaussianLighting {
+    /// view dependent color from the spherical harmonics
+    #[default]
+    Baked,
+    /// `DirectionalLight`, `PointLight` and the camera's `EnvironmentMapLight`
+    Relit,
+}
+
+impl GaussianLighting {
+    /// false wherever the build cannot relight, see `GaussianLighting`
+    pub fn is_relit(&self) -> bool {
+        cfg!(all(
+            feature = "buffer_storage",
+            not(feature = "precompute_covariance_3d")
+        )) && *self == Self::Relit
+    }
+}
+
+#[derive(Default)]
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GaussianLighting>();
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<GaussianLights>()
+                .init_resource::<GaussianEnvironment>()
+                .add_systems(
+                    ExtractSchedule,
+                    (
+                        extract_gaussian_lights,
+                        environmental::extract_gaussian_environment,
+                    ),
+                )
+                .add_systems(
+                    Render,
+                    prepare_gaussian_lights.in_set(RenderSystems::PrepareResources),
+                );
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<GaussianLightsUniform>();
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ShaderType)]
+pub struct GpuDirectionalLight {
+    /// linear color scaled by the illuminance
+    pub color: Vec4,
+    pub direction_to_light: Vec4,
+}
+
+#[derive(Clone, Copy, Debug, Default, ShaderType)]
+pub struct GpuPointLight {
+    /// linear color scaled by the luminous intensity, w holds the inverse squared range
+    pub color_inverse_square_range: Vec4,
+    /// w holds the radius
+    pub position: Vec4,
+}
+
+/// lights shared by every relit cloud, matches `GaussianLights` in `pbr.wgsl`
+#[derive(Resource, Clone, Copy, Debug, Default, ShaderType)]
+pub struct GaussianLights {
+    pub directional_lights: [GpuDirectionalLight; MAX_GAUSSIAN_DIRECTIONAL_LIGHTS],
+    pub point_lights: [GpuPointLight; MAX_GAUSSIAN_POINT_LIGHTS],
+    /// inverse of the environment map rotation
+    pub environment_rotation: Vec4,
+    pub directional_count: u32,
+    pub point_count: u32,
+    /// zero until both environment cubemaps are loaded
+    pub environment_intensity: f32,
+}
+
+#[derive(Resource)]
+pub struct GaussianLightsUniform {
+    pub buffer: UniformBuffer<GaussianLights>,
+    pub environment_sampler: Sampler,
+}
+
+impl FromWorld for GaussianLightsUniform {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+
+        let environment_sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("gaussian_environment_sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: MipmapFilterMode::Linear,
+            ..default()
+        });
+
+        Self {
+            buffer: UniformBuffer::default(),
+            environment_sampler,
+        }
+    }
+}
+
+fn extract_gaussian_lights(
+    mut lights: ResMut<GaussianLights>,
+    directional_lights: Extract<Query<(&DirectionalLight, &GlobalTransform, &InheritedVisibility)>>,
+    point_lights: Extract<Query<(&PointLight, &GlobalTransform, &InheritedVisibility)>>,
+) {
+    let mut extracted = GaussianLights::default();
+
+    let visible_directional_lights = directional_lights
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .take(MAX_GAUSSIAN_DIRECTIONAL_LIGHTS);
+
+    for (slot, (light, transform, _)) in extracted
+        .directional_lights
+        .iter_mut()
+        .zip(visible_directional_lights)
+    {
+        // directional lights shine along their forward axis
+        *slot = GpuDirectionalLight {
+            color: (light.color.to_linear().to_vec3() * light.illuminance).extend(1.0),
+            direction_to_light: transform.back().as_vec3().extend(0.0),
+        };
+        extracted.directional_count += 1;
+    }
+
+    let visible_point_lights = point_lights
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .take(MAX_GAUSSIAN_POINT_LIGHTS);
+
+    for (slot, (light, transform, _)) in extracted.point_lights.iter_mut().zip(visible_point_lights)
+    {
+        // lumens to candela, as bevy's pbr lights
+        let intensity = light.intensity / (4.0 * PI);
+
+        *slot = GpuPointLight {
+            color_inverse_square_range: (light.color.to_linear().to_vec3() * intensity)
+                .extend(1.0 / (light.range * light.range).max(f32::EPSILON)),
+            position: transform.translation().extend(light.radius),
+        };
+        extracted.point_count += 1;
+    }
+
+    *lights = extracted;
+}
+
+fn prepare_gaussian_lights(
+    lights: Res<GaussianLights>,
+    environment: Res<GaussianEnvironment>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    mut uniform: ResMut<GaussianLightsUniform>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let mut lights = *lights;
+
+    if environment.maps(&gpu_images).is_some() {
+        lights.environment_intensity = environment.intensity;
+        lights.environment_rotation = Vec4::from(environment.rotation.inverse());
+    } else {
+        lights.environment_intensity = 0.0;
+        lights.environment_rotation = Vec4::from(Quat::IDENTITY);
+    }
+
+    uniform.buffer.set(lights);
+    uniform.buffer.write_buffer(&render_device, &render_queue);
+}
+
+/// sorted entries followed by the material attributes, lights and environment of relit clouds
+pub fn relit_layout_entries(sorted_entry: BindGroupLayoutEntry) -> Vec<BindGroupLayoutEntry> {
+    let cubemap = |binding| BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::VERTEX_FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::Cube,
+            multisampled: false,
+        },
+        count: None,
+    };
+
+    vec![
+        sorted_entry,
+        BindGroupLayoutEntry {
+            binding: 1,
+            visibility: ShaderStages::VERTEX_FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: 2,
+            visibility: ShaderStages::VERTEX_FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: Some(GaussianLights::min_size()),
+            },
+            count: None,
+        },
+        cubemap(3),
+        cubemap(4),
+        BindGroupLayoutEntry {
+            binding: 5,
+            visibility: ShaderStages::VERTEX_FRAGMENT,
+            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+            count: None,
+        },
+    ]
+}
+
+/// replaces the sorted bind group of relit clouds, drawn at the same dynamic offsets
+#[derive(Component)]
+pub struct RelitBindGroup {
+    pub bind_group: BindGroup,
+}
+
+// rebuilt every frame, the material and environment may finish loading at any time
+#[cfg(feature = "buffer_storage")]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn queue_relit_bind_groups<R: PlanarSync>(
+    mut commands: Commands,
+    gaussian_cloud_pipeline: Res<CloudPipeline<R>>,
+    render_device: Res<RenderDevice>,
+    lights: Res<GaussianLightsUniform>,
+    environment: Res<GaussianEnvironment>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    fallback_image: Res<FallbackImage>,
+    fallback_material: Res<FallbackPbrMaterial>,
+    pbr_materials: Res<RenderAssets<GpuPbrMaterial>>,
+    gaussian_cloud_res: Res<RenderAssets<R::GpuPlanarType>>,
+    sorted_entries_res: Res<RenderAssets<GpuSortedEntry>>,
+    gaussian_clouds: Query<(
+        Entity,
+        &R::PlanarTypeHandle,
+        &SortedEntriesHandle,
+        &GaussianLighting,
+        &PbrMaterialHandle,
+    )>,
+) {
+    let Some(lights_binding) = lights.buffer.binding() else {
+        return;
+    };
+
+    let (diffuse_map, specular_map) = environment
+        .maps(&gpu_images)
+        .map(|(diffuse, specular)| (&diffuse.texture_view, &specular.texture_view))
+        .unwrap_or((
+            &fallback_image.cube.texture_view,
+            &fallback_image.cube.texture_view,
+        ));
+
+    for (entity, cloud_handle, sorted_entries_handle, lighting, material) in &gaussian_clouds {
+        if !lighting.is_relit() {
+            continue;
+        }
+
+        let Some(cloud) = gaussian_cloud_res.get(cloud_handle.handle()) else {
+            continue;
+        };
+
+        let Some(sorted_entries) = sorted_entries_res.get(&sorted_entries_handle.0) else {
+            continue;
+        };
+
+        let attribute_buffer = pbr_materials
+            .get(material)
+            .map_or(&fallback_material.attribute_buffer, |material| {
+                &material.attribute_buffer
+            });
+
+        let bind_group = render_device.create_bind_group(
+            "relit_sorted_bind_group",
+            &gaussian_cloud_pipeline.relit_layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: &sorted_entries.sorted_entry_buffer,
+                        offset: 0,
+                        size: BufferSize::new(
+                            (cloud.len() * std::mem::size_of::<SortEntry>()) as u64,
+                        ),
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: attribute_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: lights_binding.clone(),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::TextureView(diffuse_map),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::TextureView(specular_map),
+                },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: BindingResource::Sampler(&lights.environment_sampler),
+                },
+            ],
+        );
+
+        commands
+            .entity(entity)
+            .insert(RelitBindGroup { bind_group });
+    }
+}
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 pub mod classification;
 pub mod depth;
 pub mod optical_flow;
+pub mod pbr;
 pub mod position;
 pub mod spherical_harmonics;
 pub mod spherindrical_harmonics;
@@ -23,6 +24,7 @@ impl Plugin for MaterialPlugin {
             classification::ClassificationMaterialPlugin,
             depth::DepthMaterialPlugin,
             optical_flow::OpticalFlowMaterialPlugin,
+            pbr::PbrMaterialPlugin,
             position::PositionMaterialPlugin,
             spherical_harmonics::SphericalHarmonicCoefficientsPlugin,
             spherindrical_harmonics::SpherindricalHarmonicCoefficientsPlugin,
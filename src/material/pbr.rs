@@ -0,0 +1,151 @@
+use bevy::{
+    asset::{RenderAssetUsages, load_internal_asset, uuid_handle},
+    ecs::system::{SystemParamItem, lifetimeless::SRes},
+    prelude::*,
+    render::{
+        RenderApp,
+        render_asset::{PrepareAssetError, RenderAsset, RenderAssetPlugin},
+        render_resource::*,
+        renderer::RenderDevice,
+    },
+};
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+const PBR_SHADER_HANDLE: Handle<Shader> = uuid_handle!("3c9d2a41-7f0e-4b6a-9d85-1e4f6c2b8a70");
+
+pub struct PbrMaterialPlugin;
+
+impl Plugin for PbrMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, PBR_SHADER_HANDLE, "pbr.wgsl", Shader::from_wgsl);
+
+        app.register_type::<PbrMaterialHandle>();
+        app.init_asset::<PbrMaterial>();
+        app.register_asset_reflect::<PbrMaterial>();
+
+        app.add_plugins(RenderAssetPlugin::<GpuPbrMaterial>::default());
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<FallbackPbrMaterial>();
+        }
+    }
+}
+
+/// surface attributes of a single gaussian, matches `PbrAttributes` in `pbr.wgsl`
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Pod, Zeroable, Serialize, Deserialize)]
+#[repr(C)]
+pub struct PbrAttributes {
+    /// linear rgb
+    pub albedo: [f32; 3],
+    /// perceptual roughness
+    pub roughness: f32,
+    pub metallic: f32,
+}
+
+// same defaults as `StandardMaterial`
+impl Default for PbrAttributes {
+    fn default() -> Self {
+        Self {
+            albedo: [1.0; 3],
+            roughness: 0.5,
+            metallic: 0.0,
+        }
+    }
+}
+
+/// relightable attributes, indexed like the gaussians of the cloud sharing the entity
+///
+/// clouds relit without a material shade their baked color as a dielectric albedo
+#[derive(Asset, Clone, Debug, Default, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct PbrMaterial {
+    pub attributes: Vec<PbrAttributes>,
+}
+
+impl PbrMaterial {
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct PbrMaterialHandle(pub Handle<PbrMaterial>);
+
+impl From<Handle<PbrMaterial>> for PbrMaterialHandle {
+    fn from(handle: Handle<PbrMaterial>) -> Self {
+        Self(handle)
+    }
+}
+
+impl From<&PbrMaterialHandle> for AssetId<PbrMaterial> {
+    fn from(handle: &PbrMaterialHandle) -> Self {
+        handle.0.id()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuPbrMaterial {
+    pub attribute_buffer: Buffer,
+    pub count: usize,
+}
+
+impl RenderAsset for GpuPbrMaterial {
+    type SourceAsset = PbrMaterial;
+    type Param = SRes<RenderDevice>;
+
+    fn prepare_asset(
+        source: Self::SourceAsset,
+        _: AssetId<Self::SourceAsset>,
+        render_device: &mut SystemParamItem<Self::Param>,
+        _: Option<&Self>,
+    ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
+        // empty storage bindings are invalid, empty materials bind a single default entry
+        let attributes = if source.is_empty() {
+            vec![PbrAttributes::default()]
+        } else {
+            source.attributes
+        };
+
+        let attribute_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("pbr_attribute_buffer"),
+            contents: bytemuck::cast_slice(attributes.as_slice()),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Ok(GpuPbrMaterial {
+            attribute_buffer,
+            count: attributes.len(),
+        })
+    }
+
+    fn asset_usage(_: &Self::SourceAsset) -> RenderAssetUsages {
+        RenderAssetUsages::default()
+    }
+}
+
+/// default attributes bound for relit clouds without a loaded material
+#[derive(Resource)]
+pub struct FallbackPbrMaterial {
+    pub attribute_buffer: Buffer,
+}
+
+impl FromWorld for FallbackPbrMaterial {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+
+        let attribute_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("fallback_pbr_attribute_buffer"),
+            contents: bytemuck::bytes_of(&PbrAttributes::default()),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Self { attribute_buffer }
+    }
+}
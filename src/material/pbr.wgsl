@@ -1 +1,190 @@
+#define_import_path bevy_gaussian_splatting::pbr
 
+#import bevy_gaussian_splatting::bindings::view
+
+const PI: f32 = 3.141592653589793;
+
+struct PbrAttributes {
+    albedo: array<f32, 3>,
+    roughness: f32,
+    metallic: f32,
+};
+
+struct DirectionalLight {
+    color: vec4<f32>,
+    direction_to_light: vec4<f32>,
+};
+
+struct PointLight {
+    color_inverse_square_range: vec4<f32>,
+    position: vec4<f32>,
+};
+
+struct GaussianLights {
+    directional_lights: array<DirectionalLight, #{MAX_GAUSSIAN_DIRECTIONAL_LIGHTS}>,
+    point_lights: array<PointLight, #{MAX_GAUSSIAN_POINT_LIGHTS}>,
+    environment_rotation: vec4<f32>,
+    directional_count: u32,
+    point_count: u32,
+    environment_intensity: f32,
+};
+
+// group 3 binding 0 holds the sorted entries
+@group(3) @binding(1) var<storage, read> pbr_attributes: array<PbrAttributes>;
+@group(3) @binding(2) var<uniform> lights: GaussianLights;
+@group(3) @binding(3) var diffuse_environment_map: texture_cube<f32>;
+@group(3) @binding(4) var specular_environment_map: texture_cube<f32>;
+@group(3) @binding(5) var environment_map_sampler: sampler;
+
+fn get_pbr_attributes(index: u32) -> PbrAttributes {
+    return pbr_attributes[index];
+}
+
+fn get_albedo(attributes: PbrAttributes) -> vec3<f32> {
+    return vec3<f32>(
+        attributes.albedo[0],
+        attributes.albedo[1],
+        attributes.albedo[2],
+    );
+}
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a2 = roughness * roughness;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+fn visibility_smith_ggx(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let a2 = roughness * roughness;
+    let lambda_v = n_dot_l * sqrt((n_dot_v - a2 * n_dot_v) * n_dot_v + a2);
+    let lambda_l = n_dot_v * sqrt((n_dot_l - a2 * n_dot_l) * n_dot_l + a2);
+    return 0.5 / (lambda_v + lambda_l);
+}
+
+fn fresnel_schlick(f0: vec3<f32>, l_dot_h: f32) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(1.0 - l_dot_h, 5.0);
+}
+
+// analytic fit of the split sum brdf, see: https://www.unrealengine.com/en-US/blog/physically-based-shading-on-mobile
+fn environment_brdf(f0: vec3<f32>, perceptual_roughness: f32, n_dot_v: f32) -> vec3<f32> {
+    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r = perceptual_roughness * c0 + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let ab = vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+    return f0 * ab.x + ab.y;
+}
+
+// lambert diffuse and ggx specular, scaled by n dot l
+fn brdf(
+    diffuse_color: vec3<f32>,
+    f0: vec3<f32>,
+    roughness: f32,
+    normal: vec3<f32>,
+    view_direction: vec3<f32>,
+    light_direction: vec3<f32>,
+) -> vec3<f32> {
+    let n_dot_l = saturate(dot(normal, light_direction));
+    if n_dot_l <= 0.0 {
+        return vec3<f32>(0.0);
+    }
+
+    let half_vector = normalize(light_direction + view_direction);
+    let n_dot_v = max(dot(normal, view_direction), 1e-4);
+    let n_dot_h = saturate(dot(normal, half_vector));
+    let l_dot_h = saturate(dot(light_direction, half_vector));
+
+    let specular = distribution_ggx(n_dot_h, roughness)
+        * visibility_smith_ggx(n_dot_v, n_dot_l, roughness)
+        * fresnel_schlick(f0, l_dot_h);
+
+    return (diffuse_color / PI + specular) * n_dot_l;
+}
+
+fn quat_rotate(q: vec4<f32>, direction: vec3<f32>) -> vec3<f32> {
+    let t = 2.0 * cross(q.xyz, direction);
+    return direction + q.w * t + cross(q.xyz, t);
+}
+
+fn environment_sample_direction(direction: vec3<f32>) -> vec3<f32> {
+    var sample_direction = quat_rotate(lights.environment_rotation, direction);
+
+    // cubemaps are left-handed
+    sample_direction.z = -sample_direction.z;
+    return sample_direction;
+}
+
+// linear radiance towards the viewer, exposed like bevy's pbr meshes
+fn relight(
+    albedo: vec3<f32>,
+    perceptual_roughness: f32,
+    metallic: f32,
+    world_position: vec3<f32>,
+    normal: vec3<f32>,
+    view_direction: vec3<f32>,
+) -> vec3<f32> {
+    let clamped_roughness = clamp(perceptual_roughness, 0.089, 1.0);
+    let roughness = clamped_roughness * clamped_roughness;
+    let diffuse_color = albedo * (1.0 - metallic);
+    let f0 = mix(vec3<f32>(0.04), albedo, metallic);
+
+    var color = vec3<f32>(0.0);
+
+    for (var i = 0u; i < lights.directional_count; i += 1u) {
+        let light = lights.directional_lights[i];
+        color += brdf(
+            diffuse_color,
+            f0,
+            roughness,
+            normal,
+            view_direction,
+            light.direction_to_light.xyz,
+        ) * light.color.rgb;
+    }
+
+    for (var i = 0u; i < lights.point_count; i += 1u) {
+        let light = lights.point_lights[i];
+        let to_light = light.position.xyz - world_position;
+        let distance_squared = max(dot(to_light, to_light), 1e-4);
+
+        // inverse square falloff windowed to the light range
+        let factor = distance_squared * light.color_inverse_square_range.w;
+        let window = saturate(1.0 - factor * factor);
+        let attenuation = window * window / distance_squared;
+
+        color += brdf(
+            diffuse_color,
+            f0,
+            roughness,
+            normal,
+            view_direction,
+            to_light * inverseSqrt(distance_squared),
+        ) * light.color_inverse_square_range.rgb * attenuation;
+    }
+
+    if lights.environment_intensity > 0.0 {
+        let n_dot_v = max(dot(normal, view_direction), 1e-4);
+
+        let irradiance = textureSampleLevel(
+            diffuse_environment_map,
+            environment_map_sampler,
+            environment_sample_direction(normal),
+            0.0,
+        ).rgb;
+
+        let radiance_level = clamped_roughness * f32(textureNumLevels(specular_environment_map) - 1u);
+        let radiance = textureSampleLevel(
+            specular_environment_map,
+            environment_map_sampler,
+            environment_sample_direction(reflect(-view_direction, normal)),
+            radiance_level,
+        ).rgb;
+
+        color += (
+            diffuse_color * irradiance
+            + environment_brdf(f0, clamped_roughness, n_dot_v) * radiance
+        ) * lights.environment_intensity;
+    }
+
+    return color * view.exposure;
+}
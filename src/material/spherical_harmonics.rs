@@ -30,7 +30,7 @@ impl Plugin for SphericalHarmonicCoefficientsPlugin {
     }
 }
 
-const fn num_sh_coefficients(degree: usize) -> usize {
+pub const fn num_sh_coefficients(degree: usize) -> usize {
     if degree == 0 {
         1
     } else {
@@ -76,6 +76,16 @@ pub const SH_DEGREE: usize = 0;
 pub const SH_DEGREE: usize = 0;
 
 pub const SH_CHANNELS: usize = 3;
+
+/// `degree` limited to the compiled `SH_DEGREE`
+pub const fn clamp_sh_degree(degree: usize) -> usize {
+    // compared from the `SH_DEGREE` side so sh0 builds do not see an absurd comparison
+    if SH_DEGREE < degree {
+        SH_DEGREE
+    } else {
+        degree
+    }
+}
 pub const SH_COEFF_COUNT_PER_CHANNEL: usize = num_sh_coefficients(SH_DEGREE);
 pub const SH_COEFF_COUNT: usize = pad_4(SH_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS);
 
@@ -150,6 +160,162 @@ impl SphericalHarmonicCoefficients {
     pub fn set(&mut self, index: usize, value: f32) {
         self.coefficients[index] = value;
     }
+
+    pub fn get(&self, index: usize) -> f32 {
+        self.coefficients[index]
+    }
+
+    /// clears coefficients that a cloud written at `source_degree` could not provide to this build
+    pub fn project_degree(&mut self, source_degree: usize) {
+        let kept = num_sh_coefficients(clamp_sh_degree(source_degree)) * SH_CHANNELS;
+
+        for coefficient in self.coefficients.iter_mut().skip(kept) {
+            *coefficient = 0.0;
+        }
+    }
+
+    pub fn rotate(&mut self, rotation: &ShRotation) {
+        rotation.rotate_block(&mut self.coefficients[..SH_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS]);
+    }
+}
+
+/// real sh basis with the shader's signs and ordering, band 4 uses the usual 3dgs constants
+pub fn sh_basis(direction: Vec3) -> [f32; 25] {
+    let Vec3 { x, y, z } = direction;
+    let (xx, yy, zz) = (x * x, y * y, z * z);
+
+    [
+        0.282_094_8,
+        -0.488_602_5 * y,
+        0.488_602_5 * z,
+        -0.488_602_5 * x,
+        1.092_548_5 * x * y,
+        -1.092_548_5 * y * z,
+        0.315_391_57 * (2.0 * zz - xx - yy),
+        -1.092_548_5 * x * z,
+        0.546_274_2 * (xx - yy),
+        -0.590_043_6 * y * (3.0 * xx - yy),
+        2.890_611_4 * x * y * z,
+        -0.457_045_8 * y * (4.0 * zz - xx - yy),
+        0.373_176_34 * z * (2.0 * zz - 3.0 * xx - 3.0 * yy),
+        -0.457_045_8 * x * (4.0 * zz - xx - yy),
+        1.445_305_7 * z * (xx - yy),
+        -0.590_043_6 * x * (xx - 3.0 * yy),
+        2.503_342_9 * x * y * (xx - yy),
+        -1.770_130_8 * y * z * (3.0 * xx - yy),
+        0.946_174_7 * x * y * (7.0 * zz - 1.0),
+        -0.669_046_5 * y * z * (7.0 * zz - 3.0),
+        0.105_785_55 * (zz * (35.0 * zz - 30.0) + 3.0),
+        -0.669_046_5 * x * z * (7.0 * zz - 3.0),
+        0.473_087_35 * (xx - yy) * (7.0 * zz - 1.0),
+        -1.770_130_8 * x * z * (xx - 3.0 * yy),
+        0.625_835_7 * (xx * (xx - 3.0 * yy) - yy * (3.0 * xx - yy)),
+    ]
+}
+
+/// wigner-d matrices of every compiled band, band `l` is a row major `(2l + 1)^2` block
+///
+/// rotations keep each band closed, so a least squares fit over sample directions is exact
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShRotation {
+    bands: Vec<Vec<f32>>,
+}
+
+impl ShRotation {
+    const SAMPLES: usize = 64;
+
+    pub fn new(rotation: Quat) -> Self {
+        let inverse = rotation.inverse();
+
+        // fibonacci sphere, well conditioned for every band up to 4
+        let directions = (0..Self::SAMPLES).map(|i| {
+            let z = 1.0 - (2.0 * i as f32 + 1.0) / Self::SAMPLES as f32;
+            let radius = (1.0 - z * z).sqrt();
+            let angle = i as f32 * std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+
+            Vec3::new(radius * angle.cos(), radius * angle.sin(), z)
+        });
+        let samples = directions
+            .map(|direction| (sh_basis(direction), sh_basis(inverse * direction)))
+            .collect::<Vec<_>>();
+
+        let bands = (1..)
+            .take(SH_DEGREE)
+            .map(|band| fit_band(band, &samples))
+            .collect();
+
+        Self { bands }
+    }
+
+    /// rotates `(SH_DEGREE + 1)^2` coefficient major coefficients with interleaved channels
+    pub fn rotate_block(&self, coefficients: &mut [f32]) {
+        for (band, matrix) in (1..).zip(&self.bands) {
+            let first = band * band;
+            let width = 2 * band + 1;
+
+            for channel in 0..SH_CHANNELS {
+                let index = |m: usize| (first + m) * SH_CHANNELS + channel;
+                let source = (0..width)
+                    .map(|m| coefficients[index(m)])
+                    .collect::<Vec<_>>();
+
+                for (row, weights) in matrix.chunks_exact(width).enumerate() {
+                    coefficients[index(row)] =
+                        weights.iter().zip(&source).map(|(w, c)| w * c).sum();
+                }
+            }
+        }
+    }
+}
+
+/// solves `basis * d = rotated_basis` over the band's samples through the normal equations
+fn fit_band(band: usize, samples: &[([f32; 25], [f32; 25])]) -> Vec<f32> {
+    let first = band * band;
+    let width = 2 * band + 1;
+
+    // augmented [basis^T basis | basis^T rotated_basis]
+    let columns = 2 * width;
+    let mut system = vec![0.0_f64; width * columns];
+    for (basis, rotated) in samples {
+        for row in 0..width {
+            let y = basis[first + row] as f64;
+            for col in 0..width {
+                system[row * columns + col] += y * basis[first + col] as f64;
+                system[row * columns + width + col] += y * rotated[first + col] as f64;
+            }
+        }
+    }
+
+    for pivot in 0..width {
+        let best = (pivot..width)
+            .max_by(|&a, &b| {
+                system[a * columns + pivot]
+                    .abs()
+                    .total_cmp(&system[b * columns + pivot].abs())
+            })
+            .unwrap_or(pivot);
+        for col in 0..columns {
+            system.swap(pivot * columns + col, best * columns + col);
+        }
+
+        let scale = system[pivot * columns + pivot];
+        for col in 0..columns {
+            system[pivot * columns + col] /= scale;
+        }
+
+        for row in (0..width).filter(|&row| row != pivot) {
+            let factor = system[row * columns + pivot];
+            for col in 0..columns {
+                system[row * columns + col] -= factor * system[pivot * columns + col];
+            }
+        }
+    }
+
+    // rotated_basis = basis * d, so d maps source coefficients to rotated ones
+    system
+        .chunks_exact(columns)
+        .flat_map(|row| row[width..].iter().map(|&value| value as f32))
+        .collect()
 }
 
 // #[cfg(feature = "f16")]
@@ -239,5 +405,5 @@ where
         }
     }
 
-    d.deserialize_seq(CoefficientsVisitor)
+    d.deserialize_tuple(SH_COEFF_COUNT, CoefficientsVisitor)
 }
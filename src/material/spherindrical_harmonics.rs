@@ -9,11 +9,12 @@ use bevy::{
 use bytemuck::{Pod, Zeroable};
 use serde::{Deserialize, Serialize, Serializer, ser::SerializeTuple};
 
-// #[cfg(feature = "f16")]
-// use half::f16;
+use half::f16;
 
 use crate::{
-    material::spherical_harmonics::{SH_CHANNELS, SH_DEGREE},
+    material::spherical_harmonics::{
+        SH_CHANNELS, SH_DEGREE, ShRotation, clamp_sh_degree, num_sh_coefficients,
+    },
     math::{gcd, pad_4},
 };
 
@@ -32,6 +33,9 @@ pub const POD_PLANE_COUNT: usize = SH_4D_COEFF_COUNT / POD_ARRAY_SIZE;
 pub const WASTE: usize = POD_PLANE_COUNT * POD_ARRAY_SIZE - SH_4D_COEFF_COUNT;
 static_assertions::const_assert_eq!(WASTE, 0);
 
+pub const HALF_POD_ARRAY_SIZE: usize = gcd(HALF_SH_4D_COEFF_COUNT, MAX_POD_U32_ARRAY_SIZE);
+pub const HALF_POD_PLANE_COUNT: usize = HALF_SH_4D_COEFF_COUNT / HALF_POD_ARRAY_SIZE;
+
 // #[cfg(feature = "f16")]
 // pub const SH_4D_VEC4_PLANES: usize = HALF_SH_4D_COEFF_COUNT / 4;
 pub const SH_4D_VEC4_PLANES: usize = SH_4D_COEFF_COUNT / 4;
@@ -134,6 +138,109 @@ impl SpherindricalHarmonicCoefficients {
 
         self.coefficients[pod_index][pod_offset] = value;
     }
+
+    pub fn get(&self, index: usize) -> f32 {
+        let pod_index = index / POD_ARRAY_SIZE;
+        let pod_offset = index % POD_ARRAY_SIZE;
+
+        self.coefficients[pod_index][pod_offset]
+    }
+
+    /// clears spatial bands above `degree` in every time band
+    pub fn project_degree(&mut self, degree: usize) {
+        let spatial_count = num_sh_coefficients(SH_DEGREE);
+        let kept = num_sh_coefficients(clamp_sh_degree(degree));
+
+        for index in 0..SH_4D_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS {
+            if (index / SH_CHANNELS) % spatial_count >= kept {
+                self.set(index, 0.0);
+            }
+        }
+    }
+
+    /// rotates the spatial bands of every time band
+    pub fn rotate(&mut self, rotation: &ShRotation) {
+        let spatial = num_sh_coefficients(SH_DEGREE) * SH_CHANNELS;
+
+        self.coefficients.as_flattened_mut()[..SH_4D_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS]
+            .chunks_exact_mut(spatial)
+            .for_each(|block| rotation.rotate_block(block));
+    }
+}
+
+/// two f16 coefficients per u32, even indices in the low half
+#[allow(dead_code)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Reflect, ShaderType, Pod, Zeroable, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct HalfSpherindricalHarmonicCoefficients {
+    #[serde(
+        serialize_with = "half_coefficients_serializer",
+        deserialize_with = "half_coefficients_deserializer"
+    )]
+    pub coefficients: [[u32; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT],
+}
+
+impl Default for HalfSpherindricalHarmonicCoefficients {
+    fn default() -> Self {
+        Self {
+            coefficients: [[0; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT],
+        }
+    }
+}
+
+impl HalfSpherindricalHarmonicCoefficients {
+    pub fn set(&mut self, index: usize, value: f32) {
+        let quantized = f16::from_f32(value).to_bits() as u32;
+        let pair_index = index / 2;
+        let pod_index = pair_index / HALF_POD_ARRAY_SIZE;
+        let pod_offset = pair_index % HALF_POD_ARRAY_SIZE;
+
+        let word = &mut self.coefficients[pod_index][pod_offset];
+        *word = match index % 2 {
+            0 => (*word & 0xffff0000) | quantized,
+            _ => (*word & 0x0000ffff) | (quantized << 16),
+        };
+    }
+
+    pub fn get(&self, index: usize) -> f32 {
+        let pair_index = index / 2;
+        let pod_index = pair_index / HALF_POD_ARRAY_SIZE;
+        let pod_offset = pair_index % HALF_POD_ARRAY_SIZE;
+
+        let word = self.coefficients[pod_index][pod_offset];
+        let bits = match index % 2 {
+            0 => word & 0xffff,
+            _ => word >> 16,
+        };
+
+        f16::from_bits(bits as u16).to_f32()
+    }
+}
+
+impl From<&SpherindricalHarmonicCoefficients> for HalfSpherindricalHarmonicCoefficients {
+    fn from(coefficients: &SpherindricalHarmonicCoefficients) -> Self {
+        let mut half = Self::default();
+
+        for index in 0..SH_4D_COEFF_COUNT {
+            half.set(index, coefficients.get(index));
+        }
+
+        half
+    }
+}
+
+impl From<&HalfSpherindricalHarmonicCoefficients> for SpherindricalHarmonicCoefficients {
+    fn from(half: &HalfSpherindricalHarmonicCoefficients) -> Self {
+        let mut coefficients = Self::default();
+
+        for index in 0..SH_4D_COEFF_COUNT {
+            coefficients.set(index, half.get(index));
+        }
+
+        coefficients
+    }
 }
 
 // #[cfg(feature = "f16")]
@@ -231,3 +338,54 @@ where
 
     d.deserialize_tuple(SH_4D_COEFF_COUNT, CoefficientsVisitor)
 }
+
+fn half_coefficients_serializer<S>(
+    n: &[[u32; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT],
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut tup = s.serialize_tuple(HALF_POD_PLANE_COUNT)?;
+    for &x in n.iter() {
+        tup.serialize_element(&x)?;
+    }
+
+    tup.end()
+}
+
+fn half_coefficients_deserializer<'de, D>(
+    d: D,
+) -> Result<[[u32; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct CoefficientsVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for CoefficientsVisitor {
+        type Value = [[u32; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT];
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("an array of packed halfs")
+        }
+
+        fn visit_seq<A>(
+            self,
+            mut seq: A,
+        ) -> Result<[[u32; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT], A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut coefficients = [[0; HALF_POD_ARRAY_SIZE]; HALF_POD_PLANE_COUNT];
+
+            for (i, coefficient) in coefficients.iter_mut().enumerate() {
+                *coefficient = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+            }
+            Ok(coefficients)
+        }
+    }
+
+    d.deserialize_tuple(HALF_POD_PLANE_COUNT, CoefficientsVisitor)
+}
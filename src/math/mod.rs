@@ -5,3 +5,17 @@ pub const fn gcd(a: usize, b: usize) -> usize {
 pub const fn pad_4(x: usize) -> usize {
     (x + 3) & !3
 }
+
+/// interleaves the low 10 bits of each axis into a 30 bit morton code
+pub const fn morton_3d(x: u32, y: u32, z: u32) -> u32 {
+    const fn part_1_by_2(mut v: u32) -> u32 {
+        v &= 0x0000_03ff;
+        v = (v ^ (v << 16)) & 0xff00_00ff;
+        v = (v ^ (v << 8)) & 0x0300_f00f;
+        v = (v ^ (v << 4)) & 0x030c_30c3;
+        v = (v ^ (v << 2)) & 0x0924_9249;
+        v
+    }
+
+    (part_1_by_2(z) << 2) | (part_1_by_2(y) << 1) | part_1_by_2(x)
+}
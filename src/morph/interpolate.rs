@@ -86,6 +86,10 @@ where
     }
 
     fn finish(&self, app: &mut App) {
+        if TypeId::of::<R::PlanarType>() != TypeId::of::<PlanarGaussian3d>() {
+            return;
+        }
+
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app.init_resource::<GaussianInterpolatePipeline<R>>();
         }
@@ -6,7 +6,7 @@ use crate::{
         formats::{planar_3d::Gaussian3d, planar_4d::Gaussian4d},
         interface::CommonCloud,
     },
-    io::codec::CloudCodec,
+    io::gcloud::header::{GcloudContainer, GcloudMetadata},
 };
 
 #[derive(Component, Debug, Default, Reflect)]
@@ -63,7 +63,7 @@ where
 
 impl<R: PlanarSync> Plugin for CommonCloudSelectPlugin<R>
 where
-    R::PlanarType: CloudCodec,
+    R::PlanarType: GcloudContainer,
     R::PlanarType: CommonCloud,
 {
     fn build(&self, app: &mut App) {
@@ -99,11 +99,11 @@ fn apply_selection<R: PlanarSync>(
         let mut cloud = gaussian_clouds_res.get_mut(cloud_handle.handle()).unwrap();
 
         (0..cloud.len()).for_each(|index| {
-            *cloud.visibility_mut(index) = 0.0;
+            cloud.set_visibility(index, 0.0);
         });
 
         select.indicies.iter().for_each(|index| {
-            *cloud.visibility_mut(*index) = 1.0;
+            cloud.set_visibility(*index, 1.0);
         });
 
         select.completed = true;
@@ -139,11 +139,11 @@ fn invert_selection<R: PlanarSync>(
                 new_indicies.push(index);
             }
 
-            *cloud.visibility_mut(index) = 1.0;
+            cloud.set_visibility(index, 1.0);
         });
 
         select.indicies.iter().for_each(|index| {
-            *cloud.visibility_mut(*index) = 0.0;
+            cloud.set_visibility(*index, 0.0);
         });
 
         select.indicies = new_indicies;
@@ -158,7 +158,7 @@ pub fn save_selection<R: PlanarSync>(
     mut gaussian_clouds_res: ResMut<Assets<R::PlanarType>>,
     mut selections: Query<(Entity, &R::PlanarTypeHandle, &Select)>,
 ) where
-    R::PlanarType: CloudCodec,
+    R::PlanarType: GcloudContainer,
     R::PlanarType: CommonCloud,
 {
     if events.is_empty() {
@@ -171,6 +171,10 @@ pub fn save_selection<R: PlanarSync>(
 
         let selected = cloud.subset(select.indicies.as_slice());
 
-        selected.write_to_file("live_output.gcloud");
+        if let Err(err) =
+            selected.write_container_file("live_output.gcloud", &GcloudMetadata::new())
+        {
+            error!("failed to save selection: {err}");
+        }
     }
 }
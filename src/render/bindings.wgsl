@@ -21,8 +21,11 @@ struct GaussianUniforms {
     time_stop: f32,
     num_classes: u32,
     color_space: u32,
+    depth_alpha_threshold: f32,
     min: vec4<f32>,
     max: vec4<f32>,
+    // world space bounding sphere, `[center, radius]`
+    bounds: vec4<f32>,
 };
 @group(1) @binding(0) var<uniform> gaussian_uniforms: GaussianUniforms;
 
@@ -140,6 +143,45 @@ struct GaussianUniforms {
         #endif
     #endif
 
+    #ifdef PLANAR_QUANTIZED
+        struct QuantizedChunk {
+            position_min: vec3<f32>,
+            sh_dc_scale: f32,
+            position_max: vec3<f32>,
+            sh_rest_scale: f32,
+        };
+
+        @group(2) @binding(0) var<storage, read> position_visibility: array<vec2<u32>>;
+        @group(2) @binding(1) var<storage, read> spherical_harmonics: array<array<u32, #{QUANTIZED_SH_WORDS}>>;
+        @group(2) @binding(2) var<storage, read> rotation: array<u32>;
+        @group(2) @binding(3) var<storage, read> scale_opacity: array<u32>;
+        @group(2) @binding(4) var<storage, read> chunks: array<QuantizedChunk>;
+    #endif
+
+    #ifdef PLANAR_CODEBOOK
+        @group(2) @binding(0) var<storage, read> position_visibility: array<vec4<f32>>;
+        @group(2) @binding(1) var<storage, read> spherical_harmonics: array<vec2<u32>>;
+        @group(2) @binding(2) var<storage, read> rotation: array<vec4<f32>>;
+        @group(2) @binding(3) var<storage, read> scale_opacity: array<vec4<f32>>;
+        @group(2) @binding(4) var<storage, read> sh_codebook: array<array<f32, #{SH_COEFF_COUNT}>>;
+    #endif
+
+    #ifdef PLANAR_SPACETIME
+        struct SpacetimeMotion {
+            motion: array<f32, 9>,
+            trbf_center: f32,
+            trbf_scale: f32,
+            _pad: f32,
+        };
+
+        @group(2) @binding(0) var<storage, read> position_visibility: array<vec4<f32>>;
+        @group(2) @binding(1) var<storage, read> spherical_harmonics: array<array<f32, #{SH_COEFF_COUNT}>>;
+        @group(2) @binding(2) var<storage, read> rotation: array<vec4<f32>>;
+        @group(2) @binding(3) var<storage, read> scale_opacity: array<vec4<f32>>;
+        @group(2) @binding(4) var<storage, read> motion: array<SpacetimeMotion>;
+        @group(2) @binding(5) var<storage, read> angular_velocity: array<vec4<f32>>;
+    #endif
+
     #ifdef PLANAR_TEXTURE_F16
         @group(2) @binding(0) var position_visibility: texture_2d<f32>;
 
@@ -172,30 +214,45 @@ struct GaussianUniforms {
         #endif
     #endif
 
+    // clouds without `.gtex` planes keep their planar storage bind group on the texture path
     #ifdef PLANAR_TEXTURE_F32
-        @group(2) @binding(0) var position_visibility: texture_2d<f32>;
-
-        #if SH_VEC4_PLANES == 1
-            @group(2) @binding(1) var spherical_harmonics: texture_2d<f32>;
-        #else
-            @group(2) @binding(1) var spherical_harmonics: texture_2d_array<f32>;
-        #endif
+        @group(2) @binding(0) var<storage, read> position_visibility: array<vec4<f32>>;
+        @group(2) @binding(1) var<storage, read> spherical_harmonics: array<array<f32, #{SH_COEFF_COUNT}>>;
 
         // TODO: support f32_cov3d_opacity texture
 
-        @group(2) @binding(2) var rotation_scale_opacity: texture_2d<f32>;
+        @group(2) @binding(2) var<storage, read> rotation: array<vec4<f32>>;
+        @group(2) @binding(3) var<storage, read> scale_opacity: array<vec4<f32>>;
 
         #ifdef BINARY_GAUSSIAN_OP
-            @group(3) @binding(0) var rhs_position_visibility: texture_2d<f32>;
+            @group(3) @binding(0) var<storage, read> rhs_position_visibility: array<vec4<f32>>;
+            @group(3) @binding(1) var<storage, read> rhs_spherical_harmonics: array<array<f32, #{SH_COEFF_COUNT}>>;
+            @group(3) @binding(2) var<storage, read> rhs_rotation: array<vec4<f32>>;
+            @group(3) @binding(3) var<storage, read> rhs_scale_opacity: array<vec4<f32>>;
+        #endif
+    #endif
 
-            #if SH_VEC4_PLANES == 1
-                @group(3) @binding(1) var rhs_spherical_harmonics: texture_2d<f32>;
-            #else
-                @group(3) @binding(1) var rhs_spherical_harmonics: texture_2d_array<f32>;
-            #endif
+    #ifdef PLANAR_TEXTURE_GTEX
+        struct GaussianTextureRanges {
+            means_min: vec3<f32>,
+            means_max: vec3<f32>,
+            scales_min: vec3<f32>,
+            scales_max: vec3<f32>,
+            sh0_min: vec3<f32>,
+            sh0_max: vec3<f32>,
+            sh_rest_min: f32,
+            sh_rest_max: f32,
+        };
 
-            @group(3) @binding(2) var rhs_rotation_scale_opacity: texture_2d<f32>;
-        #endif
+        // `GaussianTexturePlane::ALL` order, every plane is a linear rgba8unorm image
+        @group(2) @binding(0) var gtex_means_low: texture_2d<f32>;
+        @group(2) @binding(1) var gtex_means_high: texture_2d<f32>;
+        @group(2) @binding(2) var gtex_quats: texture_2d<f32>;
+        @group(2) @binding(3) var gtex_scales: texture_2d<f32>;
+        @group(2) @binding(4) var gtex_sh0: texture_2d<f32>;
+        @group(2) @binding(5) var gtex_sh_labels: texture_2d<f32>;
+        @group(2) @binding(6) var gtex_sh_centroids: texture_2d<f32>;
+        @group(2) @binding(7) var<uniform> gtex_ranges: GaussianTextureRanges;
     #endif
 #else ifdef GAUSSIAN_4D
     #ifdef PLANAR_F32
@@ -219,6 +276,20 @@ struct GaussianUniforms {
             @group(3) @binding(4) var<storage, read> rhs_timestamp_timescale: array<vec4<f32>>;
         #endif
     #endif
+
+    #ifdef PLANAR_F16
+        #ifdef READ_WRITE_POINTS
+            @group(2) @binding(0) var<storage, read_write> position_visibility: array<vec4<f32>>;
+        #else
+            @group(2) @binding(0) var<storage, read> position_visibility: array<vec4<f32>>;
+        #endif
+
+        @group(2) @binding(1) var<storage, read> spherindrical_harmonics: array<array<u32, #{HALF_SH_4D_COEFF_COUNT}>>;
+
+        @group(2) @binding(2) var<storage, read> isotropic_rotations: array<vec4<u32>>;
+        @group(2) @binding(3) var<storage, read> scale_opacity: array<vec2<u32>>;
+        @group(2) @binding(4) var<storage, read> timestamp_timescale: array<u32>;
+    #endif
 #endif
 
 struct DrawIndirect {
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use bevy::{
+    camera::{MainPassResolutionOverride, Viewport},
+    core_pipeline::{
+        Core3d, Core3dSystems,
+        core_3d::{Transparent3d, main_opaque_pass_3d, main_transparent_pass_3d},
+        prepass::{DeferredPrepass, DepthPrepass, ViewPrepassTextures, node::late_prepass},
+    },
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSystems,
+        camera::ExtractedCamera,
+        render_phase::SortedRenderPhase,
+        render_resource::{RenderPassDescriptor, StoreOp},
+        renderer::{RenderContext, ViewQuery},
+        view::{ExtractedView, RetainedViewEntity, ViewDepthTexture},
+    },
+};
+use bevy_interleave::prelude::*;
+
+use crate::render::oit::run_oit_accumulation;
+
+pub struct SplatDepthPlugin<R: PlanarSync> {
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> Default for SplatDepthPlugin<R> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: PlanarSync> Plugin for SplatDepthPlugin<R> {
+    fn build(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<SplatDepthPhases<R>>()
+                .add_systems(
+                    Render,
+                    clear_splat_depth_phases::<R>.in_set(RenderSystems::PrepareViews),
+                )
+                .add_systems(
+                    Core3d,
+                    (
+                        // visible to ssao and the opaque pass, like alpha masked meshes
+                        splat_depth_prepass::<R>
+                            .after(late_prepass)
+                            .in_set(Core3dSystems::Prepass),
+                        splat_depth_pass::<R>
+                            .after(main_opaque_pass_3d)
+                            .after(run_oit_accumulation::<R>)
+                            .before(main_transparent_pass_3d)
+                            .in_set(Core3dSystems::MainPass),
+                    ),
+                );
+        }
+    }
+}
+
+/// clouds of each view writing alpha thresholded depth
+#[derive(Resource, Deref, DerefMut)]
+pub struct SplatDepthPhases<R: PlanarSync> {
+    #[deref]
+    pub phases: HashMap<RetainedViewEntity, SortedRenderPhase<Transparent3d>>,
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> Default for SplatDepthPhases<R> {
+    fn default() -> Self {
+        Self {
+            phases: HashMap::new(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+fn clear_splat_depth_phases<R: PlanarSync>(mut phases: ResMut<SplatDepthPhases<R>>) {
+    phases.clear();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_splat_depth(
+    world: &World,
+    ctx: &mut RenderContext,
+    view_entity: Entity,
+    camera: &ExtractedCamera,
+    phase: &SortedRenderPhase<Transparent3d>,
+    depth: &ViewDepthTexture,
+    resolution_override: Option<&MainPassResolutionOverride>,
+    label: &'static str,
+) {
+    let mut render_pass = ctx.begin_tracked_render_pass(RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[],
+        depth_stencil_attachment: Some(depth.get_attachment(StoreOp::Store)),
+        timestamp_writes: None,
+        occlusion_query_set: None,
+        multiview_mask: None,
+    });
+
+    if let Some(viewport) =
+        Viewport::from_viewport_and_override(camera.viewport.as_ref(), resolution_override)
+    {
+        render_pass.set_camera_viewport(&viewport);
+    }
+
+    if let Err(err) = phase.render(&mut render_pass, world, view_entity) {
+        error!("error encountered while writing gaussian cloud depth {err:?}");
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn splat_depth_prepass<R: PlanarSync>(
+    world: &World,
+    view: ViewQuery<
+        (
+            &ExtractedCamera,
+            &ExtractedView,
+            &ViewDepthTexture,
+            &ViewPrepassTextures,
+            Has<DeferredPrepass>,
+            Option<&MainPassResolutionOverride>,
+        ),
+        With<DepthPrepass>,
+    >,
+    depth_phases: Res<SplatDepthPhases<R>>,
+    mut ctx: RenderContext,
+) {
+    let view_entity = view.entity();
+    let (camera, extracted_view, depth, prepass_textures, deferred_prepass, resolution_override) =
+        view.into_inner();
+
+    let Some(phase) = depth_phases.get(&extracted_view.retained_view_entity) else {
+        return;
+    };
+
+    render_splat_depth(
+        world,
+        &mut ctx,
+        view_entity,
+        camera,
+        phase,
+        depth,
+        resolution_override,
+        "gaussian_depth_prepass",
+    );
+
+    // the prepass already copied its depth, refresh the copy with the splats
+    if !deferred_prepass && let Some(prepass_depth) = &prepass_textures.depth {
+        ctx.command_encoder().copy_texture_to_texture(
+            depth.texture.as_image_copy(),
+            prepass_depth.texture.texture.as_image_copy(),
+            prepass_textures.size,
+        );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn splat_depth_pass<R: PlanarSync>(
+    world: &World,
+    view: ViewQuery<
+        (
+            &ExtractedCamera,
+            &ExtractedView,
+            &ViewDepthTexture,
+            Option<&MainPassResolutionOverride>,
+        ),
+        Without<DepthPrepass>,
+    >,
+    depth_phases: Res<SplatDepthPhases<R>>,
+    mut ctx: RenderContext,
+) {
+    let view_entity = view.entity();
+    let (camera, extracted_view, depth, resolution_override) = view.into_inner();
+
+    let Some(phase) = depth_phases.get(&extracted_view.retained_view_entity) else {
+        return;
+    };
+
+    render_splat_depth(
+        world,
+        &mut ctx,
+        view_entity,
+        camera,
+        phase,
+        depth,
+        resolution_override,
+        "gaussian_depth_pass",
+    );
+}
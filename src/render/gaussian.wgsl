@@ -18,6 +18,14 @@
     in_frustum,
 }
 
+#ifdef RELIGHT
+    #import bevy_gaussian_splatting::pbr::{
+        get_albedo,
+        get_pbr_attributes,
+        relight,
+    }
+#endif
+
 #ifdef GAUSSIAN_2D
     #import bevy_gaussian_splatting::gaussian_2d::{
         compute_cov2d_surfel,
@@ -181,6 +189,69 @@ fn world_to_local_direction(ray_direction_world: vec3<f32>, transform: mat4x4<f3
 
     return normalize(local);
 }
+
+#ifdef RELIGHT
+#ifdef GAUSSIAN_3D_STRUCTURE
+// world normal along the shortest axis, as in RASTERIZE_NORMAL, flipped towards the viewer
+fn splat_normal(splat_index: u32, view_direction: vec3<f32>) -> vec3<f32> {
+    let scale = get_scale(splat_index);
+
+    // columns of the transposed rotation matrix are the gaussian axes
+    var axes = transpose(get_rotation_matrix(get_rotation(splat_index)));
+
+    var shortest = 2u;
+    if scale.x <= scale.y && scale.x <= scale.z {
+        shortest = 0u;
+    } else if scale.y <= scale.z {
+        shortest = 1u;
+    }
+
+    let T = mat3x3<f32>(
+        gaussian_uniforms.transform[0].xyz,
+        gaussian_uniforms.transform[1].xyz,
+        gaussian_uniforms.transform[2].xyz,
+    );
+
+    // crossing the transformed tangents keeps the normal correct under non-uniform cloud scales
+    let tangent = T * axes[(shortest + 1u) % 3u];
+    let bitangent = T * axes[(shortest + 2u) % 3u];
+    let normal = normalize(cross(tangent, bitangent));
+
+    return select(normal, -normal, dot(normal, view_direction) < 0.0);
+}
+
+fn relight_splat(
+    splat_index: u32,
+    baked_color: vec3<f32>,
+    world_position: vec3<f32>,
+    view_direction: vec3<f32>,
+) -> vec3<f32> {
+    let normal = splat_normal(splat_index, view_direction);
+
+#ifdef PBR_ATTRIBUTES
+    let attributes = get_pbr_attributes(splat_index);
+    let albedo = get_albedo(attributes);
+    let roughness = attributes.roughness;
+    let metallic = attributes.metallic;
+#else
+    // clouds without a material shade their baked color as a dielectric
+    let albedo = saturate(baked_color);
+    let roughness = 0.5;
+    let metallic = 0.0;
+#endif
+
+    return relight(
+        albedo,
+        roughness,
+        metallic,
+        world_position,
+        normal,
+        view_direction,
+    );
+}
+#endif
+#endif
+
 @vertex
 fn vs_points(
     @builtin(instance_index) instance_index: u32,
@@ -410,6 +481,15 @@ fn vs_points(
 
     #ifdef GAUSSIAN_3D_STRUCTURE
         rgb = get_color(splat_index, ray_direction_local);
+
+        #ifdef RELIGHT
+            rgb = relight_splat(
+                splat_index,
+                rgb,
+                transformed_position,
+                -ray_direction_world,
+            );
+        #endif
     #else ifdef GAUSSIAN_4D
         rgb = get_color(splat_index, gaussian_4d.dir_t, ray_direction_local);
     #endif
@@ -435,8 +515,8 @@ fn vs_points(
     return output;
 }
 
-@fragment
-fn fs_main(input: GaussianVertexOutput) -> @location(0) vec4<f32> {
+// premultiplied splat color at the fragment
+fn splat_color(input: GaussianVertexOutput) -> vec4<f32> {
 #ifdef USE_AABB
 #ifdef GAUSSIAN_2D
     let radius = input.radius;
@@ -496,10 +576,61 @@ fn fs_main(input: GaussianVertexOutput) -> @location(0) vec4<f32> {
 
     let alpha = min(exp(power) * input.color.a, 0.999);
 
-    // TODO: round alpha to terminate depth test?
-
     return vec4<f32>(
         input.color.rgb * alpha,
         alpha,
     );
 }
+
+@fragment
+fn fs_main(input: GaussianVertexOutput) -> @location(0) vec4<f32> {
+    return splat_color(input);
+}
+
+#ifdef WRITE_DEPTH
+// splat quads are flat at the projected center, only sufficiently opaque fragments occlude
+@fragment
+fn fs_depth(input: GaussianVertexOutput) {
+    if (splat_color(input).a < gaussian_uniforms.depth_alpha_threshold) {
+        discard;
+    }
+}
+#endif
+
+#ifdef WEIGHTED_BLENDED_OIT
+struct OitFragmentOutput {
+    @location(0) accum: vec4<f32>,
+    @location(1) revealage: f32,
+};
+
+// McGuire and Bavoil 2013, weighted blended order-independent transparency
+fn oit_weight(ndc_depth: f32) -> f32 {
+#ifdef OIT_WEIGHT_CONSTANT
+    return 1.0;
+#else ifdef OIT_WEIGHT_DEPTH_FALLOFF
+    let view_position = view.view_from_clip * vec4<f32>(0.0, 0.0, ndc_depth, 1.0);
+    let distance = abs(view_position.z / view_position.w);
+    return clamp(
+        10.0 / (1e-5 + pow(distance / 5.0, 2.0) + pow(distance / 200.0, 6.0)),
+        1e-2,
+        3e3,
+    );
+#else ifdef OIT_WEIGHT_PROJECTED_DEPTH
+    // reversed z, near splats approach 1.0
+    return clamp(3e3 * pow(ndc_depth, 3.0), 1e-2, 3e3);
+#endif
+}
+
+@fragment
+fn fs_oit(input: GaussianVertexOutput) -> OitFragmentOutput {
+    var output: OitFragmentOutput;
+
+    let color = splat_color(input);
+    let weight = oit_weight(input.position.z);
+
+    output.accum = color * weight;
+    output.revealage = color.a;
+
+    return output;
+}
+#endif
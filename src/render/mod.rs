@@ -44,15 +44,21 @@ use crate::{
     camera::GaussianCamera,
     gaussian::{
         cloud::CloudVisibilityClass,
-        interface::CommonCloud,
+        formats::planar_3d_quantized::{QUANTIZED_CHUNK_SIZE, QUANTIZED_SH_WORDS},
+        interface::{CommonCloud, PlanarStorageFormat},
         settings::{
-            CloudSettings, DrawMode, GaussianColorSpace, GaussianMode, RadixSortDepthBits,
-            RasterizeMode,
+            CloudSettings, DepthMode, DrawMode, GaussianColorSpace, GaussianMode, OitWeight,
+            RadixSortDepthBits, RasterizeMode, Rasterizer,
         },
     },
+    lighting::{
+        GaussianLighting, MAX_GAUSSIAN_DIRECTIONAL_LIGHTS, MAX_GAUSSIAN_POINT_LIGHTS,
+        RelitBindGroup,
+    },
     material::{
+        pbr::{GpuPbrMaterial, PbrMaterialHandle},
         spherical_harmonics::{HALF_SH_COEFF_COUNT, SH_COEFF_COUNT, SH_DEGREE, SH_VEC4_PLANES},
-        spherindrical_harmonics::{SH_4D_COEFF_COUNT, SH_4D_DEGREE_TIME},
+        spherindrical_harmonics::{HALF_SH_4D_COEFF_COUNT, SH_4D_COEFF_COUNT, SH_4D_DEGREE_TIME},
     },
     morph::MorphPlugin,
     sort::{GpuSortedEntry, SortPlugin, SortTrigger, SortedEntriesHandle},
@@ -64,9 +70,18 @@ mod packed;
 #[cfg(feature = "buffer_storage")]
 mod planar;
 
+pub mod depth;
+
+pub mod oit;
+
+pub mod reference;
+
 #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
 mod texture;
 
+#[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+pub mod tile;
+
 const BINDINGS_SHADER_HANDLE: Handle<Shader> = uuid_handle!("cfd9a3d9-a0cb-40c8-ab0b-073110a02474");
 const GAUSSIAN_SHADER_HANDLE: Handle<Shader> = uuid_handle!("9a18d83b-137d-4f44-9628-e2defc4b62b0");
 const GAUSSIAN_2D_SHADER_HANDLE: Handle<Shader> =
@@ -107,6 +122,12 @@ where
 
         app.add_plugins(MorphPlugin::<R>::default());
         app.add_plugins(SortPlugin::<R>::default());
+        app.add_plugins(oit::WeightedBlendedPlugin::<R>::default());
+        app.add_plugins(depth::SplatDepthPlugin::<R>::default());
+
+        #[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+        app.add_plugins(tile::TileRasterizerPlugin::<R>::default());
+
         app.init_resource::<PlanarStorageRebindQueue<R>>();
         app.add_systems(PostUpdate, queue_planar_storage_rebinds::<R>);
 
@@ -135,6 +156,20 @@ where
                         queue_gaussians::<R>.in_set(RenderSystems::Queue),
                     ),
                 );
+
+            #[cfg(feature = "buffer_storage")]
+            render_app.add_systems(
+                Render,
+                crate::lighting::queue_relit_bind_groups::<R>
+                    .in_set(RenderSystems::PrepareBindGroups),
+            );
+
+            #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+            render_app.add_systems(
+                Render,
+                texture::queue_gaussian_texture_bind_groups::<R>
+                    .in_set(RenderSystems::PrepareBindGroups),
+            );
         }
 
         // TODO: refactor common resources into a common plugin
@@ -203,6 +238,11 @@ where
             Shader::from_wgsl
         );
 
+        oit::load_oit_shaders(app);
+
+        #[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+        tile::load_tile_shaders(app);
+
         app.add_plugins(UniformComponentPlugin::<CloudUniform>::default());
 
         #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
@@ -267,7 +307,7 @@ fn queue_planar_storage_rebinds<R: PlanarSync>(
     }
 }
 
-fn extract_planar_storage_rebind_queue<R: PlanarSync>(
+pub(crate) fn extract_planar_storage_rebind_queue<R: PlanarSync>(
     mut commands: Commands,
     mut main_world: ResMut<bevy::render::MainWorld>,
 ) {
@@ -320,6 +360,8 @@ pub struct GpuCloudBundle<R: PlanarSync> {
     pub sorted_entries: SortedEntriesHandle,
     pub cloud_handle: R::PlanarTypeHandle,
     pub transform: GlobalTransform,
+    pub lighting: GaussianLighting,
+    pub pbr_material: PbrMaterialHandle,
 }
 
 #[allow(type_alias_bounds)]
@@ -330,6 +372,8 @@ type GpuCloudBundleQuery<R: bevy_interleave::prelude::PlanarSync> = (
     &'static SortedEntriesHandle,
     &'static CloudSettings,
     &'static GlobalTransform,
+    &'static GaussianLighting,
+    &'static PbrMaterialHandle,
 );
 
 #[allow(type_alias_bounds)]
@@ -349,7 +393,10 @@ fn queue_gaussians<R: PlanarSync>(
     pipeline_cache: Res<PipelineCache>,
     gaussian_clouds: Res<RenderAssets<R::GpuPlanarType>>,
     sorted_entries: Res<RenderAssets<GpuSortedEntry>>,
+    pbr_materials: Res<RenderAssets<GpuPbrMaterial>>,
     mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    mut oit_accumulation_phases: ResMut<oit::OitAccumulationPhases<R>>,
+    mut depth_phases: ResMut<depth::SplatDepthPhases<R>>,
     mut views: Query<(
         &ExtractedView,
         &GaussianCamera,
@@ -357,6 +404,10 @@ fn queue_gaussians<R: PlanarSync>(
         Option<&Msaa>,
     )>,
     gaussian_splatting_bundles: Query<GpuCloudBundleQuery<R>>,
+    #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))] gtex_clouds: Query<
+        (),
+        With<texture::ExtractedGaussianTextures>,
+    >,
 ) {
     debug!("queue_gaussians");
 
@@ -395,8 +446,16 @@ fn queue_gaussians<R: PlanarSync>(
                 continue;
             }
 
-            let (_entity, cloud_handle, aabb, sorted_entries_handle, settings, transform) =
-                gaussian_splatting_bundles.get(*render_entity).unwrap();
+            let (
+                _entity,
+                cloud_handle,
+                aabb,
+                sorted_entries_handle,
+                settings,
+                transform,
+                lighting,
+                pbr_material,
+            ) = gaussian_splatting_bundles.get(*render_entity).unwrap();
 
             debug!("queue gaussians clouds");
             if gaussian_clouds.get(cloud_handle.handle()).is_none() {
@@ -409,8 +468,23 @@ fn queue_gaussians<R: PlanarSync>(
                 return;
             }
 
+            #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+            let gtex = gtex_clouds.contains(*render_entity);
+            // `PLANAR_TEXTURE_F32` only reads f32 planar clouds
+            #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+            if !gtex && custom_pipeline.storage_format != PlanarStorageFormat::F32 {
+                debug!(
+                    "texture path does not draw {:?} clouds",
+                    custom_pipeline.storage_format
+                );
+                continue;
+            }
+
             let msaa = msaa.cloned().unwrap_or_default();
 
+            // matches the group 3 bind group picked by `DrawGaussianInstanced`
+            let relight = lighting.is_relit();
+
             let key = CloudPipelineKey {
                 aabb: settings.aabb,
                 binary_gaussian_op: false,
@@ -419,12 +493,18 @@ fn queue_gaussians<R: PlanarSync>(
                 draw_mode: settings.draw_mode,
                 gaussian_mode: settings.gaussian_mode,
                 rasterize_mode: settings.rasterize_mode,
+                rasterizer: settings.rasterizer,
+                oit_weight: settings.oit_weight,
+                write_depth: false,
+                relight,
+                pbr_attributes: relight && pbr_materials.get(pbr_material).is_some(),
+                storage_format: custom_pipeline.storage_format,
+                #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+                gtex,
                 sample_count: msaa.samples(),
                 hdr: view.target_format == TextureFormat::Rgba16Float,
             };
 
-            let pipeline = pipelines.specialize(&pipeline_cache, &custom_pipeline, key);
-
             let rangefinder = view.rangefinder3d();
             let aabb_center = (aabb.min() + aabb.max()) / 2.0;
             let aabb_size = aabb.max() - aabb.min();
@@ -434,7 +514,7 @@ fn queue_gaussians<R: PlanarSync>(
                 );
             let distance = rangefinder.distance(&center.translation());
 
-            transparent_phase.add_transient(Transparent3d {
+            let item = |pipeline| Transparent3d {
                 sorting_info: TransparentSortingInfo3d::Sorted {
                     mesh_center: center.translation(),
                     depth_bias: 0.0,
@@ -446,7 +526,42 @@ fn queue_gaussians<R: PlanarSync>(
                 batch_range: 0..1,
                 extra_index: PhaseItemExtraIndex::None,
                 indexed: false,
-            });
+            };
+
+            // quad depth for the hardware rasterized clouds, tiles write their composited depth
+            if settings.depth_mode == DepthMode::AlphaThreshold
+                && matches!(
+                    settings.rasterizer,
+                    Rasterizer::Instanced | Rasterizer::WeightedBlended
+                )
+            {
+                let depth_key = CloudPipelineKey {
+                    rasterizer: Rasterizer::Instanced,
+                    oit_weight: OitWeight::default(),
+                    write_depth: true,
+                    hdr: false,
+                    ..key
+                };
+                let pipeline = pipelines.specialize(&pipeline_cache, &custom_pipeline, depth_key);
+
+                depth_phases
+                    .entry(view.retained_view_entity)
+                    .or_default()
+                    .add_transient(item(pipeline));
+            }
+
+            // weighted blended clouds accumulate before the transparent pass and resolve once per view,
+            // other rasterizers composite their own output
+            if settings.rasterizer == Rasterizer::Instanced {
+                let pipeline = pipelines.specialize(&pipeline_cache, &custom_pipeline, key);
+                transparent_phase.add_transient(item(pipeline));
+            } else if settings.rasterizer == Rasterizer::WeightedBlended {
+                let pipeline = pipelines.specialize(&pipeline_cache, &custom_pipeline, key);
+                oit_accumulation_phases
+                    .entry(view.retained_view_entity)
+                    .or_default()
+                    .add_transient(item(pipeline));
+            }
         }
     }
 }
@@ -466,6 +581,14 @@ pub struct CloudPipeline<R: PlanarSync> {
     pub compute_view_layout_desc: BindGroupLayoutDescriptor,
     pub sorted_layout: BindGroupLayout,
     pub sorted_layout_desc: BindGroupLayoutDescriptor,
+    #[cfg(feature = "buffer_storage")]
+    pub relit_layout: BindGroupLayout,
+    #[cfg(feature = "buffer_storage")]
+    pub relit_layout_desc: BindGroupLayoutDescriptor,
+    /// group 2 of clouds drawn from `.gtex` planes, see `texture::BufferTexturePlugin`
+    #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+    pub gaussian_texture_layout_desc: BindGroupLayoutDescriptor,
+    pub storage_format: PlanarStorageFormat,
     phantom: std::marker::PhantomData<R>,
 }
 
@@ -512,6 +635,7 @@ pub(crate) fn storage_layout_descriptor<P: ReflectInterleaved>(
 
 impl<R: PlanarSync> FromWorld for CloudPipeline<R>
 where
+    R::PlanarType: CommonCloud,
     R::GpuPlanarType: GpuPlanarStorage,
     <R::GpuPlanarType as GpuPlanar>::PackedType: ReflectInterleaved,
 {
@@ -656,6 +780,15 @@ where
         #[cfg(feature = "buffer_storage")]
         let sorted_layout =
             render_device.create_bind_group_layout(Some("sorted_layout"), &sorted_layout_entries);
+
+        #[cfg(feature = "buffer_storage")]
+        let relit_layout_entries = crate::lighting::relit_layout_entries(sorted_layout_entries[0]);
+        #[cfg(feature = "buffer_storage")]
+        let relit_layout_desc =
+            BindGroupLayoutDescriptor::new("relit_sorted_layout", &relit_layout_entries);
+        #[cfg(feature = "buffer_storage")]
+        let relit_layout = render_device
+            .create_bind_group_layout(Some("relit_sorted_layout"), &relit_layout_entries);
         #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
         let sorted_layout = texture::get_sorted_bind_group_layout(render_device);
         #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
@@ -687,6 +820,13 @@ where
             shader: GAUSSIAN_SHADER_HANDLE,
             sorted_layout,
             sorted_layout_desc,
+            #[cfg(feature = "buffer_storage")]
+            relit_layout,
+            #[cfg(feature = "buffer_storage")]
+            relit_layout_desc,
+            #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+            gaussian_texture_layout_desc: texture::gaussian_texture_layout_descriptor(),
+            storage_format: R::PlanarType::storage_format(),
             phantom: std::marker::PhantomData,
         }
     }
@@ -774,10 +914,16 @@ pub fn shader_defs_with_defines(
     let mut shader_defs = vec![
         ShaderDefVal::UInt("SH_COEFF_COUNT".into(), SH_COEFF_COUNT as u32),
         ShaderDefVal::UInt("SH_4D_COEFF_COUNT".into(), SH_4D_COEFF_COUNT as u32),
+        ShaderDefVal::UInt(
+            "HALF_SH_4D_COEFF_COUNT".into(),
+            HALF_SH_4D_COEFF_COUNT as u32,
+        ),
         ShaderDefVal::UInt("SH_DEGREE".into(), SH_DEGREE as u32),
         ShaderDefVal::UInt("SH_DEGREE_TIME".into(), SH_4D_DEGREE_TIME as u32),
         ShaderDefVal::UInt("HALF_SH_COEFF_COUNT".into(), HALF_SH_COEFF_COUNT as u32),
         ShaderDefVal::UInt("SH_VEC4_PLANES".into(), SH_VEC4_PLANES as u32),
+        ShaderDefVal::UInt("QUANTIZED_CHUNK_SIZE".into(), QUANTIZED_CHUNK_SIZE as u32),
+        ShaderDefVal::UInt("QUANTIZED_SH_WORDS".into(), QUANTIZED_SH_WORDS as u32),
         ShaderDefVal::UInt("RADIX_BASE".into(), defines.radix_base),
         ShaderDefVal::UInt("RADIX_BITS_PER_DIGIT".into(), defines.radix_bits_per_digit),
         ShaderDefVal::UInt("RADIX_DIGIT_PLACES".into(), defines.radix_digit_places),
@@ -849,13 +995,23 @@ pub fn shader_defs_with_defines(
     // shader_defs.push("PLANAR_F16".into());
 
     #[cfg(feature = "buffer_storage")]
-    shader_defs.push("PLANAR_F32".into());
+    match key.storage_format {
+        PlanarStorageFormat::F32 => shader_defs.push("PLANAR_F32".into()),
+        PlanarStorageFormat::F16 => shader_defs.push("PLANAR_F16".into()),
+        PlanarStorageFormat::Quantized => shader_defs.push("PLANAR_QUANTIZED".into()),
+        PlanarStorageFormat::Codebook => shader_defs.push("PLANAR_CODEBOOK".into()),
+        PlanarStorageFormat::Spacetime => shader_defs.push("PLANAR_SPACETIME".into()),
+    }
 
     // #[cfg(all(feature = "f16", feature = "buffer_texture"))]
     // shader_defs.push("PLANAR_TEXTURE_F16".into());
 
     #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
-    shader_defs.push("PLANAR_TEXTURE_F32".into());
+    if key.gtex {
+        shader_defs.push("PLANAR_TEXTURE_GTEX".into());
+    } else {
+        shader_defs.push("PLANAR_TEXTURE_F32".into());
+    }
 
     #[cfg(feature = "precompute_covariance_3d")]
     shader_defs.push("PRECOMPUTE_COVARIANCE_3D".into());
@@ -863,13 +1019,19 @@ pub fn shader_defs_with_defines(
     #[cfg(feature = "webgl2")]
     shader_defs.push("WEBGL2".into());
 
-    match key.gaussian_mode {
+    // spacetime gaussians are 3d gaussians evaluated at `time`, they share the 3d pipeline
+    let gaussian_mode = match (key.storage_format, key.gaussian_mode) {
+        (PlanarStorageFormat::Spacetime, GaussianMode::Gaussian4d) => GaussianMode::Gaussian3d,
+        (_, gaussian_mode) => gaussian_mode,
+    };
+
+    match gaussian_mode {
         GaussianMode::Gaussian2d => shader_defs.push("GAUSSIAN_2D".into()),
         GaussianMode::Gaussian3d => shader_defs.push("GAUSSIAN_3D".into()),
         GaussianMode::Gaussian4d => shader_defs.push("GAUSSIAN_4D".into()),
     }
 
-    match key.gaussian_mode {
+    match gaussian_mode {
         GaussianMode::Gaussian2d | GaussianMode::Gaussian3d => {
             shader_defs.push("GAUSSIAN_3D_STRUCTURE".into());
         }
@@ -892,6 +1054,36 @@ pub fn shader_defs_with_defines(
         DrawMode::HighlightSelected => shader_defs.push("HIGHLIGHT_SELECTED".into()),
     }
 
+    if key.write_depth {
+        shader_defs.push("WRITE_DEPTH".into());
+    }
+
+    if key.relight {
+        shader_defs.push("RELIGHT".into());
+        shader_defs.push(ShaderDefVal::UInt(
+            "MAX_GAUSSIAN_DIRECTIONAL_LIGHTS".into(),
+            MAX_GAUSSIAN_DIRECTIONAL_LIGHTS as u32,
+        ));
+        shader_defs.push(ShaderDefVal::UInt(
+            "MAX_GAUSSIAN_POINT_LIGHTS".into(),
+            MAX_GAUSSIAN_POINT_LIGHTS as u32,
+        ));
+
+        if key.pbr_attributes {
+            shader_defs.push("PBR_ATTRIBUTES".into());
+        }
+    }
+
+    if key.rasterizer == Rasterizer::WeightedBlended {
+        shader_defs.push("WEIGHTED_BLENDED_OIT".into());
+
+        match key.oit_weight {
+            OitWeight::Constant => shader_defs.push("OIT_WEIGHT_CONSTANT".into()),
+            OitWeight::DepthFalloff => shader_defs.push("OIT_WEIGHT_DEPTH_FALLOFF".into()),
+            OitWeight::ProjectedDepth => shader_defs.push("OIT_WEIGHT_PROJECTED_DEPTH".into()),
+        }
+    }
+
     shader_defs
 }
 
@@ -904,6 +1096,15 @@ pub struct CloudPipelineKey {
     pub draw_mode: DrawMode,
     pub gaussian_mode: GaussianMode,
     pub rasterize_mode: RasterizeMode,
+    pub rasterizer: Rasterizer,
+    pub oit_weight: OitWeight,
+    pub write_depth: bool,
+    pub relight: bool,
+    pub pbr_attributes: bool,
+    pub storage_format: PlanarStorageFormat,
+    /// draws from `.gtex` planes instead of the planar storage
+    #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+    pub gtex: bool,
     pub sample_count: u32,
     pub hdr: bool,
 }
@@ -922,13 +1123,46 @@ impl<R: PlanarSync> SpecializedRenderPipeline for CloudPipeline<R> {
 
         debug!("specializing cloud pipeline");
 
+        let (entry_point, targets) = if key.write_depth {
+            ("fs_depth", vec![])
+        } else if key.rasterizer == Rasterizer::WeightedBlended {
+            ("fs_oit", oit::oit_color_targets().to_vec())
+        } else {
+            (
+                "fs_main",
+                vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            )
+        };
+
+        #[cfg(feature = "buffer_storage")]
+        let sorted_layout_desc = if key.relight {
+            &self.relit_layout_desc
+        } else {
+            &self.sorted_layout_desc
+        };
+        #[cfg(not(feature = "buffer_storage"))]
+        let sorted_layout_desc = &self.sorted_layout_desc;
+
+        #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+        let gaussian_cloud_layout_desc = if key.gtex {
+            &self.gaussian_texture_layout_desc
+        } else {
+            &self.gaussian_cloud_layout_desc
+        };
+        #[cfg(not(all(feature = "buffer_texture", not(feature = "buffer_storage"))))]
+        let gaussian_cloud_layout_desc = &self.gaussian_cloud_layout_desc;
+
         RenderPipelineDescriptor {
             label: Some("gaussian cloud render pipeline".into()),
             layout: vec![
                 self.view_layout_desc.clone(),
                 self.gaussian_uniform_layout_desc.clone(),
-                self.gaussian_cloud_layout_desc.clone(),
-                self.sorted_layout_desc.clone(),
+                gaussian_cloud_layout_desc.clone(),
+                sorted_layout_desc.clone(),
             ],
             immediate_size: 0,
             vertex: VertexState {
@@ -940,12 +1174,8 @@ impl<R: PlanarSync> SpecializedRenderPipeline for CloudPipeline<R> {
             fragment: Some(FragmentState {
                 shader: self.shader.clone(),
                 shader_defs,
-                entry_point: Some("fs_main".into()),
-                targets: vec![Some(ColorTargetState {
-                    format,
-                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-                    write_mask: ColorWrites::ALL,
-                })],
+                entry_point: Some(entry_point.into()),
+                targets,
             }),
             primitive: PrimitiveState {
                 topology: PrimitiveTopology::TriangleStrip,
@@ -958,7 +1188,7 @@ impl<R: PlanarSync> SpecializedRenderPipeline for CloudPipeline<R> {
             },
             depth_stencil: Some(DepthStencilState {
                 format: TextureFormat::Depth32Float,
-                depth_write_enabled: Some(false),
+                depth_write_enabled: Some(key.write_depth),
                 depth_compare: Some(CompareFunction::GreaterEqual),
                 stencil: StencilState {
                     front: StencilFaceState::IGNORE,
@@ -1004,8 +1234,29 @@ pub struct CloudUniform {
     pub time_stop: f32,
     pub num_classes: u32,
     pub color_space: u32,
+    pub depth_alpha_threshold: f32,
     pub min: Vec4,
     pub max: Vec4,
+    /// world space bounding sphere of the transformed aabb, `[center, radius]`
+    pub bounds: Vec4,
+}
+
+/// world space sphere enclosing every corner of `aabb` under `transform`, `[center, radius]`
+pub fn cloud_bounding_sphere(aabb: &Aabb, transform: &GlobalTransform) -> Vec4 {
+    let center = transform.transform_point(aabb.center.into());
+    let radius = (0..8)
+        .map(|corner| {
+            let sign = Vec3::new(
+                if corner & 1 == 0 { -1.0 } else { 1.0 },
+                if corner & 2 == 0 { -1.0 } else { 1.0 },
+                if corner & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            let local = Vec3::from(aabb.center) + sign * Vec3::from(aabb.half_extents);
+            transform.transform_point(local).distance(center)
+        })
+        .fold(0.0, f32::max);
+
+    center.extend(radius)
 }
 
 #[allow(clippy::type_complexity)]
@@ -1023,14 +1274,25 @@ pub fn extract_gaussians<R: PlanarSync>(
             &SortedEntriesHandle,
             &CloudSettings,
             &GlobalTransform,
+            Option<&GaussianLighting>,
+            Option<&PbrMaterialHandle>,
         )>,
     >,
 ) {
     let mut commands_list = Vec::with_capacity(*prev_commands_len);
     // let visible_gaussians = gaussians_query.iter().filter(|(_, vis, ..)| vis.is_visible());
 
-    for (entity, visibility, cloud_handle, aabb, sorted_entries, settings, transform) in
-        gaussians_query.iter()
+    for (
+        entity,
+        visibility,
+        cloud_handle,
+        aabb,
+        sorted_entries,
+        settings,
+        transform,
+        lighting,
+        pbr_material,
+    ) in gaussians_query.iter()
     {
         debug!("extracting gaussian cloud entity: {:?}", entity);
 
@@ -1053,6 +1315,17 @@ pub fn extract_gaussians<R: PlanarSync>(
 
         let cloud = gaussian_cloud_res.get(cloud_handle.handle()).unwrap();
 
+        let mut settings = settings.clone();
+        let rasterizer = settings.rasterizer.or_supported();
+        if rasterizer != settings.rasterizer {
+            warn_once!(
+                "{:?} rasterizer needs the `sort_radix` feature without `buffer_texture`, drawing {:?} instead",
+                settings.rasterizer,
+                rasterizer,
+            );
+            settings.rasterizer = rasterizer;
+        }
+
         let settings_uniform = CloudUniform {
             transform: transform.to_matrix(),
             global_opacity: settings.global_opacity,
@@ -1067,19 +1340,23 @@ pub fn extract_gaussians<R: PlanarSync>(
                 GaussianColorSpace::SrgbRec709Display => 0,
                 GaussianColorSpace::LinRec709Display => 1,
             },
+            depth_alpha_threshold: settings.depth_alpha_threshold,
             min: aabb.min().extend(1.0),
             max: aabb.max().extend(1.0),
+            bounds: cloud_bounding_sphere(aabb, transform),
         };
 
         commands_list.push((
             entity,
             GpuCloudBundle::<R> {
                 aabb: *aabb,
-                settings: settings.clone(),
+                settings,
                 settings_uniform,
                 sorted_entries: sorted_entries.clone(),
                 cloud_handle: cloud_handle.clone(),
                 transform: *transform,
+                lighting: lighting.copied().unwrap_or_default(),
+                pbr_material: pbr_material.cloned().unwrap_or_default(),
             },
         ));
     }
@@ -1486,6 +1763,20 @@ impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetGaussianUniformBindGr
     }
 }
 
+#[cfg(feature = "buffer_storage")]
+#[allow(type_alias_bounds)]
+/// per cloud bind group the draw reads the planar attributes from
+pub type CloudBindGroupQuery<R: bevy_interleave::prelude::PlanarSync> =
+    Read<PlanarStorageBindGroup<R>>;
+#[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+#[allow(type_alias_bounds)]
+/// per cloud bind groups the texture path may draw from, see `texture::cloud_bind_group`
+pub type CloudBindGroupQuery<R: bevy_interleave::prelude::PlanarSync> = (
+    Has<texture::ExtractedGaussianTextures>,
+    Option<Read<texture::GaussianTextureBindGroup<R>>>,
+    Option<Read<PlanarStorageBindGroup<R>>>,
+);
+
 pub struct DrawGaussianInstanced<R: PlanarSync> {
     phantom: std::marker::PhantomData<R>,
 }
@@ -1506,29 +1797,38 @@ where
     type ViewQuery = Read<SortTrigger>;
     type ItemQuery = (
         Read<R::PlanarTypeHandle>,
-        Read<PlanarStorageBindGroup<R>>,
+        CloudBindGroupQuery<R>,
         Read<SortBindGroup>,
+        Read<GaussianLighting>,
+        Option<Read<RelitBindGroup>>,
     );
 
     #[inline]
     fn render<'w>(
         _item: &P,
         view: &'w SortTrigger,
-        entity: Option<(
-            &'w R::PlanarTypeHandle,
-            &'w PlanarStorageBindGroup<R>,
-            &'w SortBindGroup,
-        )>,
+        entity: Option<ROQueryItem<'w, 'w, Self::ItemQuery>>,
         gaussian_clouds: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
         debug!("render call");
 
+        let Some((handle, cloud_bind_group, sort_bind_groups, lighting, relit_bind_group)) = entity
+        else {
+            debug!("gaussian cloud bind groups not found");
+            return RenderCommandResult::Skip;
+        };
+
+        #[cfg(feature = "buffer_storage")]
+        let cloud_bind_group = &cloud_bind_group.bind_group;
         #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
-        let _ = view;
+        let Some(cloud_bind_group) = texture::cloud_bind_group(cloud_bind_group) else {
+            debug!("gaussian texture bind group not found");
+            return RenderCommandResult::Skip;
+        };
 
-        let (handle, planar_bind_groups, sort_bind_groups) =
-            entity.expect("gaussian cloud entity not found");
+        #[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+        let _ = (view, lighting, relit_bind_group);
 
         let gpu_gaussian_cloud = match gaussian_clouds.into_inner().get(handle.handle()) {
             Some(gpu_gaussian_cloud) => gpu_gaussian_cloud,
@@ -1540,14 +1840,27 @@ where
 
         debug!("drawing indirect");
 
-        pass.set_bind_group(2, &planar_bind_groups.bind_group, &[]);
+        pass.set_bind_group(2, cloud_bind_group, &[]);
 
         #[cfg(feature = "buffer_storage")]
         {
+            // relit pipelines extend the sorted layout, see `queue_gaussians`
+            let sorted_bind_group = if lighting.is_relit() {
+                match relit_bind_group {
+                    Some(relit_bind_group) => &relit_bind_group.bind_group,
+                    None => {
+                        debug!("relit bind group not found");
+                        return RenderCommandResult::Skip;
+                    }
+                }
+            } else {
+                &sort_bind_groups.sorted_bind_group
+            };
+
             // TODO: align dynamic offset to `min_storage_buffer_offset_alignment`
             pass.set_bind_group(
                 3,
-                &sort_bind_groups.sorted_bind_group,
+                sorted_bind_group,
                 &[view.camera_index as u32
                     * std::mem::size_of::<SortEntry>() as u32
                     * gpu_gaussian_cloud.len() as u32],
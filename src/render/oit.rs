@@ -0,0 +1,515 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{load_internal_asset, uuid_handle},
+    camera::{MainPassResolutionOverride, Viewport},
+    core_pipeline::{
+        Core3d, Core3dSystems,
+        core_3d::{Transparent3d, main_opaque_pass_3d, main_transparent_pass_3d},
+    },
+    ecs::{
+        query::ROQueryItem,
+        system::{SystemParamItem, lifetimeless::*},
+    },
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSystems,
+        camera::ExtractedCamera,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+            RenderCommandResult, SetItemPipeline, SortedRenderPhase, TrackedRenderPass,
+            ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice, ViewQuery},
+        texture::{CachedTexture, TextureCache},
+        view::{ExtractedView, RetainedViewEntity, ViewDepthTexture},
+    },
+};
+use bevy_interleave::prelude::*;
+
+use crate::camera::GaussianCamera;
+
+pub(crate) const OIT_RESOLVE_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("3e8b7d52-91c4-4f0a-a6d1-7b5e2c9f0d83");
+
+/// per pixel sum of weighted premultiplied color and weighted alpha
+pub const OIT_ACCUM_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// per pixel product of `1 - alpha`
+pub const OIT_REVEALAGE_FORMAT: TextureFormat = TextureFormat::R16Float;
+
+/// accumulation target blending, matches the `fs_oit` outputs in `gaussian.wgsl`
+pub fn oit_color_targets() -> [Option<ColorTargetState>; 2] {
+    [
+        Some(ColorTargetState {
+            format: OIT_ACCUM_FORMAT,
+            blend: Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+            }),
+            write_mask: ColorWrites::ALL,
+        }),
+        Some(ColorTargetState {
+            format: OIT_REVEALAGE_FORMAT,
+            blend: Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Zero,
+                    dst_factor: BlendFactor::OneMinusSrc,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent::REPLACE,
+            }),
+            write_mask: ColorWrites::RED,
+        }),
+    ]
+}
+
+pub struct WeightedBlendedPlugin<R: PlanarSync> {
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> Default for WeightedBlendedPlugin<R> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: PlanarSync> Plugin for WeightedBlendedPlugin<R> {
+    fn build(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<OitAccumulationPhases<R>>()
+                .add_render_command::<Transparent3d, DrawOitResolve<R>>()
+                .add_systems(
+                    Render,
+                    (
+                        clear_oit_accumulation_phases::<R>.in_set(RenderSystems::PrepareViews),
+                        queue_oit_resolves::<R>.in_set(RenderSystems::QueueSweep),
+                        prepare_oit_targets::<R>.in_set(RenderSystems::PrepareResources),
+                        queue_oit_bind_groups::<R>.in_set(RenderSystems::PrepareBindGroups),
+                    ),
+                )
+                .add_systems(
+                    Core3d,
+                    run_oit_accumulation::<R>
+                        .after(main_opaque_pass_3d)
+                        .before(main_transparent_pass_3d)
+                        .in_set(Core3dSystems::MainPass),
+                );
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<OitResolvePipeline<R>>()
+                .init_resource::<SpecializedRenderPipelines<OitResolvePipeline<R>>>();
+        }
+    }
+}
+
+pub(crate) fn load_oit_shaders(app: &mut App) {
+    load_internal_asset!(
+        app,
+        OIT_RESOLVE_SHADER_HANDLE,
+        "oit_resolve.wgsl",
+        Shader::from_wgsl
+    );
+}
+
+/// weighted blended clouds of each view, drawn into the accumulation targets before the transparent pass
+#[derive(Resource, Deref, DerefMut)]
+pub struct OitAccumulationPhases<R: PlanarSync> {
+    #[deref]
+    pub phases: HashMap<RetainedViewEntity, SortedRenderPhase<Transparent3d>>,
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> Default for OitAccumulationPhases<R> {
+    fn default() -> Self {
+        Self {
+            phases: HashMap::new(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ViewOitTargets<R: PlanarSync> {
+    pub accum: CachedTexture,
+    pub revealage: CachedTexture,
+    // single sample copies of the accumulation targets when the view is multisampled
+    pub resolve: Option<(CachedTexture, CachedTexture)>,
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> ViewOitTargets<R> {
+    fn color_attachments(&self) -> [Option<RenderPassColorAttachment<'_>>; 2] {
+        let (accum_resolve, revealage_resolve) = match &self.resolve {
+            Some((accum, revealage)) => {
+                (Some(&*accum.default_view), Some(&*revealage.default_view))
+            }
+            None => (None, None),
+        };
+
+        let attachment = |view, resolve_target, clear| {
+            Some(RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target,
+                ops: Operations {
+                    load: LoadOp::Clear(clear),
+                    store: StoreOp::Store,
+                },
+            })
+        };
+
+        [
+            attachment(
+                &self.accum.default_view,
+                accum_resolve,
+                wgpu::Color::TRANSPARENT,
+            ),
+            attachment(
+                &self.revealage.default_view,
+                revealage_resolve,
+                wgpu::Color::WHITE,
+            ),
+        ]
+    }
+
+    fn sampled_views(&self) -> (&TextureView, &TextureView) {
+        match &self.resolve {
+            Some((accum, revealage)) => (&accum.default_view, &revealage.default_view),
+            None => (&self.accum.default_view, &self.revealage.default_view),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ViewOitBindGroup<R: PlanarSync> {
+    pub value: BindGroup,
+    phantom: std::marker::PhantomData<R>,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct OitResolveKey {
+    pub sample_count: u32,
+    pub hdr: bool,
+}
+
+#[derive(Resource)]
+pub struct OitResolvePipeline<R: PlanarSync> {
+    pub layout: BindGroupLayout,
+    layout_desc: BindGroupLayoutDescriptor,
+    phantom: std::marker::PhantomData<R>,
+}
+
+fn resolve_texture_entry(binding: u32) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: false },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+impl<R: PlanarSync> FromWorld for OitResolvePipeline<R> {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+
+        let layout_entries = [resolve_texture_entry(0), resolve_texture_entry(1)];
+        let layout_desc = BindGroupLayoutDescriptor::new("oit_resolve_layout", &layout_entries);
+        let layout =
+            render_device.create_bind_group_layout(Some("oit_resolve_layout"), &layout_entries);
+
+        OitResolvePipeline {
+            layout,
+            layout_desc,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: PlanarSync> SpecializedRenderPipeline for OitResolvePipeline<R> {
+    type Key = OitResolveKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let format = if key.hdr {
+            TextureFormat::Rgba16Float
+        } else {
+            TextureFormat::Rgba8UnormSrgb
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("gaussian oit resolve pipeline".into()),
+            layout: vec![self.layout_desc.clone()],
+            immediate_size: 0,
+            vertex: VertexState {
+                shader: OIT_RESOLVE_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: Some("vs_resolve".into()),
+                buffers: vec![],
+            },
+            fragment: Some(FragmentState {
+                shader: OIT_RESOLVE_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: Some("fs_resolve".into()),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            // occlusion was resolved against the opaque depth while accumulating
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: Some(false),
+                depth_compare: Some(CompareFunction::Always),
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState {
+                count: key.sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            zero_initialize_workgroup_memory: true,
+        }
+    }
+}
+
+fn clear_oit_accumulation_phases<R: PlanarSync>(mut phases: ResMut<OitAccumulationPhases<R>>) {
+    phases.clear();
+}
+
+/// one resolve per view, sorted against other transparent items by the nearest accumulated cloud
+#[allow(clippy::too_many_arguments)]
+fn queue_oit_resolves<R: PlanarSync>(
+    transparent_3d_draw_functions: Res<DrawFunctions<Transparent3d>>,
+    resolve_pipeline: Res<OitResolvePipeline<R>>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<OitResolvePipeline<R>>>,
+    pipeline_cache: Res<PipelineCache>,
+    accumulation_phases: Res<OitAccumulationPhases<R>>,
+    mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    views: Query<(&ExtractedView, Option<&Msaa>), With<GaussianCamera>>,
+) {
+    let draw_resolve = transparent_3d_draw_functions
+        .read()
+        .id::<DrawOitResolve<R>>();
+
+    for (view, msaa) in &views {
+        let Some(accumulation_phase) = accumulation_phases.get(&view.retained_view_entity) else {
+            continue;
+        };
+
+        let Some(nearest) = accumulation_phase
+            .items
+            .values()
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        else {
+            continue;
+        };
+
+        let Some(transparent_phase) = transparent_render_phases.get_mut(&view.retained_view_entity)
+        else {
+            continue;
+        };
+
+        let key = OitResolveKey {
+            sample_count: msaa.cloned().unwrap_or_default().samples(),
+            hdr: view.target_format == TextureFormat::Rgba16Float,
+        };
+        let pipeline = pipelines.specialize(&pipeline_cache, &resolve_pipeline, key);
+
+        transparent_phase.add_transient(Transparent3d {
+            sorting_info: nearest.sorting_info,
+            entity: nearest.entity,
+            draw_function: draw_resolve,
+            distance: nearest.distance,
+            pipeline,
+            batch_range: 0..1,
+            extra_index: PhaseItemExtraIndex::None,
+            indexed: false,
+        });
+    }
+}
+
+fn prepare_oit_targets<R: PlanarSync>(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    accumulation_phases: Res<OitAccumulationPhases<R>>,
+    views: Query<(Entity, &ExtractedView, &ExtractedCamera, Option<&Msaa>), With<GaussianCamera>>,
+) {
+    for (entity, view, camera, msaa) in &views {
+        let Some(size) = camera.physical_target_size else {
+            continue;
+        };
+
+        if !accumulation_phases.contains_key(&view.retained_view_entity) {
+            commands
+                .entity(entity)
+                .remove::<(ViewOitTargets<R>, ViewOitBindGroup<R>)>();
+            continue;
+        }
+
+        let sample_count = msaa.cloned().unwrap_or_default().samples();
+
+        let mut target = |label: &'static str, format: TextureFormat, sample_count: u32| {
+            texture_cache.get(
+                &render_device,
+                TextureDescriptor {
+                    label: Some(label),
+                    size: Extent3d {
+                        width: size.x,
+                        height: size.y,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            )
+        };
+
+        let accum = target("gaussian_oit_accum", OIT_ACCUM_FORMAT, sample_count);
+        let revealage = target("gaussian_oit_revealage", OIT_REVEALAGE_FORMAT, sample_count);
+        let resolve = (sample_count > 1).then(|| {
+            (
+                target("gaussian_oit_accum_resolve", OIT_ACCUM_FORMAT, 1),
+                target("gaussian_oit_revealage_resolve", OIT_REVEALAGE_FORMAT, 1),
+            )
+        });
+
+        commands.entity(entity).insert(ViewOitTargets::<R> {
+            accum,
+            revealage,
+            resolve,
+            phantom: std::marker::PhantomData,
+        });
+    }
+}
+
+fn queue_oit_bind_groups<R: PlanarSync>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    resolve_pipeline: Res<OitResolvePipeline<R>>,
+    views: Query<(Entity, &ViewOitTargets<R>)>,
+) {
+    for (entity, targets) in &views {
+        let (accum, revealage) = targets.sampled_views();
+
+        let value = render_device.create_bind_group(
+            "oit_resolve_bind_group",
+            &resolve_pipeline.layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(accum),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(revealage),
+                },
+            ],
+        );
+
+        commands.entity(entity).insert(ViewOitBindGroup::<R> {
+            value,
+            phantom: std::marker::PhantomData,
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn run_oit_accumulation<R: PlanarSync>(
+    world: &World,
+    view: ViewQuery<(
+        &ExtractedCamera,
+        &ExtractedView,
+        &ViewDepthTexture,
+        &ViewOitTargets<R>,
+        Option<&MainPassResolutionOverride>,
+    )>,
+    accumulation_phases: Res<OitAccumulationPhases<R>>,
+    mut ctx: RenderContext,
+) {
+    let view_entity = view.entity();
+    let (camera, extracted_view, depth, targets, resolution_override) = view.into_inner();
+
+    let Some(accumulation_phase) = accumulation_phases.get(&extracted_view.retained_view_entity)
+    else {
+        return;
+    };
+
+    let color_attachments = targets.color_attachments();
+    let mut render_pass = ctx.begin_tracked_render_pass(RenderPassDescriptor {
+        label: Some("gaussian_oit_accumulation_pass"),
+        color_attachments: &color_attachments,
+        depth_stencil_attachment: Some(depth.get_attachment(StoreOp::Store)),
+        timestamp_writes: None,
+        occlusion_query_set: None,
+        multiview_mask: None,
+    });
+
+    if let Some(viewport) =
+        Viewport::from_viewport_and_override(camera.viewport.as_ref(), resolution_override)
+    {
+        render_pass.set_camera_viewport(&viewport);
+    }
+
+    if let Err(err) = accumulation_phase.render(&mut render_pass, world, view_entity) {
+        error!("error encountered while accumulating gaussian clouds {err:?}");
+    }
+}
+
+#[allow(type_alias_bounds)]
+type DrawOitResolve<R: PlanarSync> = (SetItemPipeline, DrawOitResolveOutput<R>);
+
+pub struct DrawOitResolveOutput<R: PlanarSync> {
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<P: PhaseItem, R: PlanarSync> RenderCommand<P> for DrawOitResolveOutput<R> {
+    type Param = ();
+    type ViewQuery = Option<Read<ViewOitBindGroup<R>>>;
+    type ItemQuery = ();
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        bind_group: ROQueryItem<'w, 'w, Self::ViewQuery>,
+        _entity: Option<()>,
+        _: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = bind_group else {
+            return RenderCommandResult::Skip;
+        };
+
+        pass.set_bind_group(0, &bind_group.value, &[]);
+        pass.draw(0..3, 0..1);
+
+        RenderCommandResult::Success
+    }
+}
@@ -0,0 +1,39 @@
+@group(0) @binding(0) var oit_accum: texture_2d<f32>;
+@group(0) @binding(1) var oit_revealage: texture_2d<f32>;
+
+struct ResolveVertexOutput {
+    @builtin(position) position: vec4<f32>,
+};
+
+@vertex
+fn vs_resolve(
+    @builtin(vertex_index) vertex_index: u32,
+) -> ResolveVertexOutput {
+    var output: ResolveVertexOutput;
+
+    // fullscreen triangle
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+    output.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+
+    return output;
+}
+
+@fragment
+fn fs_resolve(input: ResolveVertexOutput) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(floor(input.position.xy));
+    let revealage = textureLoad(oit_revealage, pixel, 0).r;
+
+    // no splat covered this pixel
+    if (revealage >= 1.0) {
+        discard;
+    }
+
+    let accum = textureLoad(oit_accum, pixel, 0);
+    let color = accum.rgb / clamp(accum.a, 1e-4, 5e4);
+    let alpha = 1.0 - revealage;
+
+    return vec4<f32>(color * alpha, alpha);
+}
@@ -29,6 +29,21 @@
             scale_opacity,
         }
 
+        #ifdef PLANAR_QUANTIZED
+            #import bevy_gaussian_splatting::bindings::chunks
+        #endif
+
+        #ifdef PLANAR_CODEBOOK
+            #import bevy_gaussian_splatting::bindings::sh_codebook
+        #endif
+
+        #ifdef PLANAR_SPACETIME
+            #import bevy_gaussian_splatting::bindings::{
+                motion,
+                angular_velocity,
+            }
+        #endif
+
         #ifdef BINARY_GAUSSIAN_OP
             #import bevy_gaussian_splatting::bindings::{
                 rhs_position_visibility,
@@ -440,6 +455,170 @@ fn convert_sh_color_to_linear(color: vec3<f32>) -> vec3<f32> {
             #endif
 
         #endif
+    #else ifdef PLANAR_QUANTIZED
+        fn get_position(index: u32) -> vec3<f32> {
+            let chunk = chunks[index / #{QUANTIZED_CHUNK_SIZE}u];
+            let xy = unpack2x16unorm(position_visibility[index].x);
+            let zw = unpack2x16unorm(position_visibility[index].y);
+
+            return mix(chunk.position_min, chunk.position_max, vec3<f32>(xy, zw.x));
+        }
+
+        fn get_visibility(index: u32) -> f32 {
+            return unpack2x16unorm(position_visibility[index].y).y;
+        }
+
+        fn get_spherical_harmonics(index: u32) -> array<f32, #{SH_COEFF_COUNT}> {
+            let chunk = chunks[index / #{QUANTIZED_CHUNK_SIZE}u];
+            var coefficients: array<f32, #{SH_COEFF_COUNT}>;
+
+            let dc_rg = unpack2x16snorm(spherical_harmonics[index][0]) * chunk.sh_dc_scale;
+            let dc_b = unpack2x16snorm(spherical_harmonics[index][1]).x * chunk.sh_dc_scale;
+            coefficients[0] = dc_rg.x;
+            coefficients[1] = dc_rg.y;
+            coefficients[2] = dc_b;
+
+            for (var word = 2u; word < #{QUANTIZED_SH_WORDS}u; word = word + 1u) {
+                let values = unpack4x8snorm(spherical_harmonics[index][word]) * chunk.sh_rest_scale;
+
+                for (var i = 0u; i < 4u; i = i + 1u) {
+                    let coefficient = 3u + (word - 2u) * 4u + i;
+                    if coefficient < #{SH_COEFF_COUNT}u {
+                        coefficients[coefficient] = values[i];
+                    }
+                }
+            }
+
+            return coefficients;
+        }
+
+        fn get_color(
+            index: u32,
+            ray_direction: vec3<f32>,
+        ) -> vec3<f32> {
+            return planar_color_from_sh(ray_direction, get_spherical_harmonics(index));
+        }
+
+        // smallest-three: 2 bits for the largest component index, 3 x unorm10 components
+        fn get_rotation(index: u32) -> vec4<f32> {
+            let packed = rotation[index];
+            let largest = packed >> 30u;
+
+            var q = vec4<f32>(0.0);
+            var shift = 20u;
+            var sum_squares = 0.0;
+            for (var i = 0u; i < 4u; i = i + 1u) {
+                if i == largest {
+                    continue;
+                }
+
+                let value = (f32((packed >> shift) & 0x3ffu) / 1023.0 * 2.0 - 1.0) * 0.70710678;
+                q[i] = value;
+                sum_squares = sum_squares + value * value;
+                shift = shift - 10u;
+            }
+            q[largest] = sqrt(max(1.0 - sum_squares, 0.0));
+
+            return q;
+        }
+
+        fn get_scale(index: u32) -> vec3<f32> {
+            let log_scale = unpack4x8unorm(scale_opacity[index]).xyz * 255.0 / 16.0 - 10.0;
+            return exp(log_scale);
+        }
+
+        fn get_opacity(index: u32) -> f32 {
+            return unpack4x8unorm(scale_opacity[index]).w;
+        }
+    #else ifdef PLANAR_CODEBOOK
+        fn get_position(index: u32) -> vec3<f32> {
+            return planar_position(position_visibility[index]);
+        }
+
+        fn get_visibility(index: u32) -> f32 {
+            return planar_visibility(position_visibility[index]);
+        }
+
+        // f16 DC terms next to a u16 index into the rest-band codebook
+        fn get_spherical_harmonics(index: u32) -> array<f32, #{SH_COEFF_COUNT}> {
+            let packed = spherical_harmonics[index];
+            var coefficients = sh_codebook[packed.y >> 16u];
+
+            let dc_rg = unpack2x16float(packed.x);
+            coefficients[0] = dc_rg.x;
+            coefficients[1] = dc_rg.y;
+            coefficients[2] = unpack2x16float(packed.y).x;
+
+            return coefficients;
+        }
+
+        fn get_color(
+            index: u32,
+            ray_direction: vec3<f32>,
+        ) -> vec3<f32> {
+            return planar_color_from_sh(ray_direction, get_spherical_harmonics(index));
+        }
+
+        fn get_rotation(index: u32) -> vec4<f32> {
+            return rotation[index];
+        }
+
+        fn get_scale(index: u32) -> vec3<f32> {
+            return planar_scale_from(scale_opacity[index]);
+        }
+
+        fn get_opacity(index: u32) -> f32 {
+            return planar_opacity_from(scale_opacity[index]);
+        }
+    #else ifdef PLANAR_SPACETIME
+        fn spacetime_dt(index: u32) -> f32 {
+            return gaussian_uniforms.time - motion[index].trbf_center;
+        }
+
+        fn get_position(index: u32) -> vec3<f32> {
+            let m = motion[index].motion;
+            let dt = spacetime_dt(index);
+
+            return planar_position(position_visibility[index])
+                + vec3<f32>(m[0], m[1], m[2]) * dt
+                + vec3<f32>(m[3], m[4], m[5]) * dt * dt
+                + vec3<f32>(m[6], m[7], m[8]) * dt * dt * dt;
+        }
+
+        fn get_visibility(index: u32) -> f32 {
+            return planar_visibility(position_visibility[index]);
+        }
+
+        fn get_spherical_harmonics(index: u32) -> array<f32, #{SH_COEFF_COUNT}> {
+            return spherical_harmonics[index];
+        }
+
+        fn get_color(
+            index: u32,
+            ray_direction: vec3<f32>,
+        ) -> vec3<f32> {
+            return planar_color_from_sh(ray_direction, spherical_harmonics[index]);
+        }
+
+        fn get_rotation(index: u32) -> vec4<f32> {
+            let rotation = rotation[index] + angular_velocity[index] * spacetime_dt(index);
+            let length_squared = dot(rotation, rotation);
+            if length_squared <= 0.0 {
+                return vec4<f32>(1.0, 0.0, 0.0, 0.0);
+            }
+
+            return rotation * inverseSqrt(length_squared);
+        }
+
+        fn get_scale(index: u32) -> vec3<f32> {
+            return planar_scale_from(scale_opacity[index]);
+        }
+
+        // temporal radial basis, exp(-(dt / trbf_scale)^2)
+        fn get_opacity(index: u32) -> f32 {
+            let distance = spacetime_dt(index) / max(motion[index].trbf_scale, 1e-7);
+            return planar_opacity_from(scale_opacity[index]) * exp(-distance * distance);
+        }
     #endif
 #else ifdef GAUSSIAN_4D
     fn planar4d_color_from_sh(
@@ -568,5 +747,75 @@ fn convert_sh_color_to_linear(color: vec3<f32>) -> vec3<f32> {
         #endif
     #endif
 
-    // TODO: PLANAR_F16 for GAUSSIAN_4D
+    #ifdef PLANAR_F16
+        // even coefficients in the low half, see `HalfSpherindricalHarmonicCoefficients`
+        fn planar4d_f16_decode_sh(
+            raw: array<u32, #{HALF_SH_4D_COEFF_COUNT}>,
+        ) -> array<f32, #{SH_4D_COEFF_COUNT}> {
+            var coefficients: array<f32, #{SH_4D_COEFF_COUNT}>;
+
+            for (var i = 0u; i < #{HALF_SH_4D_COEFF_COUNT}u; i = i + 1u) {
+                let values = unpack2x16float(raw[i]);
+
+                if i * 2u + 1u < #{SH_4D_COEFF_COUNT}u {
+                    coefficients[i * 2u] = values[0];
+                    coefficients[i * 2u + 1u] = values[1];
+                }
+            }
+
+            return coefficients;
+        }
+
+        fn get_color(
+            index: u32,
+            dir_t: f32,
+            ray_direction: vec3<f32>,
+        ) -> vec3<f32> {
+            let sh = planar4d_f16_decode_sh(spherindrical_harmonics[index]);
+            return planar4d_color_from_sh(ray_direction, dir_t, sh);
+        }
+
+        // rotation, scale and time pairs keep the first value in the high half
+        fn get_isotropic_rotations(index: u32) -> mat2x4<f32> {
+            let raw = isotropic_rotations[index];
+            let r0 = unpack2x16float(raw.x);
+            let r1 = unpack2x16float(raw.y);
+            let r2 = unpack2x16float(raw.z);
+            let r3 = unpack2x16float(raw.w);
+
+            return mat2x4<f32>(
+                vec4<f32>(r0.yx, r1.yx),
+                vec4<f32>(r2.yx, r3.yx),
+            );
+        }
+
+        fn get_scale(index: u32) -> vec3<f32> {
+            let raw = scale_opacity[index];
+            return vec3<f32>(unpack2x16float(raw.x).yx, unpack2x16float(raw.y).y);
+        }
+
+        fn get_opacity(index: u32) -> f32 {
+            return unpack2x16float(scale_opacity[index].y).x;
+        }
+
+        fn get_position(index: u32) -> vec3<f32> {
+            return planar_position(position_visibility[index]);
+        }
+
+        fn get_visibility(index: u32) -> f32 {
+            return planar_visibility(position_visibility[index]);
+        }
+
+        fn get_spherindrical_harmonics(index: u32) -> array<f32, #{SH_4D_COEFF_COUNT}> {
+            return planar4d_f16_decode_sh(spherindrical_harmonics[index]);
+        }
+
+        fn get_timestamp(index: u32) -> f32 {
+            return unpack2x16float(timestamp_timescale[index]).y;
+        }
+
+        fn get_time_scale(index: u32) -> f32 {
+            return unpack2x16float(timestamp_timescale[index]).x;
+        }
+    #endif
 #endif
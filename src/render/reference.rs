@@ -0,0 +1,1205 @@
+// cpu port of `gaussian.wgsl`, lets headless tests check projection, covariance, color and blending without a gpu
+use bevy::{
+    asset::RenderAssetUsages,
+    camera::{Projection, primitives::Aabb},
+    math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4, Vec4Swizzles},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_interleave::prelude::Planar;
+
+use crate::{
+    gaussian::{
+        formats::{planar_3d::PlanarGaussian3d, planar_4d::PlanarGaussian4d},
+        interface::CommonCloud,
+        settings::{
+            CloudSettings, DepthMode, DrawMode, GaussianColorSpace, GaussianMode, OitWeight,
+            RasterizeMode, Rasterizer,
+        },
+    },
+    material::{
+        spherical_harmonics::{self, SH_DEGREE, clamp_sh_degree},
+        spherindrical_harmonics::SH_4D_DEGREE_TIME,
+    },
+    render::cloud_bounding_sphere,
+};
+
+#[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+use crate::render::tile::{
+    TILE_SIZE, TILE_TRANSMITTANCE_CUTOFF, tile_bounds, tile_depth_bits, tile_grid, tile_key,
+};
+
+const HIGHLIGHT_COLOR: Vec4 = Vec4::new(0.3, 1.0, 0.1, 1.0);
+
+/// camera the reference rasterizer renders from, the cpu side of the `view` bindings
+#[derive(Clone, Debug)]
+pub struct ReferenceView {
+    pub world_from_view: Mat4,
+    pub clip_from_view: Mat4,
+    pub width: u32,
+    pub height: u32,
+    /// `clip_from_world` of the previous frame, used by `RasterizeMode::OpticalFlow`
+    pub previous_clip_from_world: Option<Mat4>,
+    pub delta_time: f32,
+}
+
+impl ReferenceView {
+    pub fn new(camera: &GlobalTransform, projection: &Projection, width: u32, height: u32) -> Self {
+        let mut projection = projection.clone();
+        projection.update(width as f32, height as f32);
+
+        Self {
+            world_from_view: camera.to_matrix(),
+            clip_from_view: projection.get_clip_from_view(),
+            width,
+            height,
+            previous_clip_from_world: None,
+            delta_time: 1.0 / 60.0,
+        }
+    }
+
+    pub fn previous_clip_from_world(mut self, previous_clip_from_world: Mat4) -> Self {
+        self.previous_clip_from_world = Some(previous_clip_from_world);
+        self
+    }
+
+    pub fn delta_time(mut self, delta_time: f32) -> Self {
+        self.delta_time = delta_time;
+        self
+    }
+
+    pub fn view_from_world(&self) -> Mat4 {
+        self.world_from_view.inverse()
+    }
+
+    pub fn clip_from_world(&self) -> Mat4 {
+        self.clip_from_view * self.view_from_world()
+    }
+
+    pub fn world_position(&self) -> Vec3 {
+        self.world_from_view.w_axis.xyz()
+    }
+
+    fn viewport(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+}
+
+/// premultiplied linear rgba, row-major from the top left, as written to the view target before tonemapping
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReferenceImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Vec4>,
+    /// reversed ndc depth written by `DepthMode::AlphaThreshold`, zero where nothing was written
+    pub depth: Vec<f32>,
+}
+
+impl ReferenceImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Vec4::ZERO; width as usize * height as usize],
+            depth: vec![0.0; width as usize * height as usize],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Vec4 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// largest per-channel difference, infinite when the sizes differ
+    pub fn max_difference(&self, other: &ReferenceImage) -> f32 {
+        if (self.width, self.height) != (other.width, other.height) {
+            return f32::INFINITY;
+        }
+
+        self.pixels
+            .iter()
+            .zip(&other.pixels)
+            .map(|(a, b)| (*a - *b).abs().max_element())
+            .fold(0.0, f32::max)
+    }
+
+    /// 8-bit rgba with each channel clamped to the unit range
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| pixel.to_array())
+            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect()
+    }
+
+    pub fn to_image(&self) -> Image {
+        Image::new(
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            bytemuck::cast_slice(&self.pixels).to_vec(),
+            TextureFormat::Rgba32Float,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+/// clouds the cpu reference can rasterize, mirroring the `vs_points` and `fs_main` shader entry points
+pub trait ReferenceRasterize {
+    /// composites the cloud front to back onto a transparent image, `transform` is the cloud entity transform
+    ///
+    /// `Rasterizer::Tile` bins, sorts and blends the splats per screen tile like `tile.wgsl`,
+    /// `Rasterizer::WeightedBlended` accumulates them unsorted and resolves like `oit_resolve.wgsl`
+    fn rasterize_reference(
+        &self,
+        transform: &GlobalTransform,
+        settings: &CloudSettings,
+        view: &ReferenceView,
+    ) -> ReferenceImage;
+}
+
+impl ReferenceRasterize for PlanarGaussian3d {
+    fn rasterize_reference(
+        &self,
+        transform: &GlobalTransform,
+        settings: &CloudSettings,
+        view: &ReferenceView,
+    ) -> ReferenceImage {
+        let context = Context::new(self, transform, settings, view);
+
+        let splats = context
+            .sorted
+            .iter()
+            .filter_map(|&index| {
+                let gaussian = self.get(index);
+                let position = context
+                    .transform
+                    .transform_point3(Vec3::from(gaussian.position_visibility.position));
+                let visibility = gaussian.position_visibility.visibility;
+                if context.skip(visibility) {
+                    return None;
+                }
+
+                let projected = context.world_to_clip(position);
+                if !in_frustum(projected) {
+                    return None;
+                }
+
+                let rotation = Vec4::from(gaussian.rotation.rotation);
+                let scale = Vec3::from(gaussian.scale_opacity.scale);
+                let opacity = gaussian.scale_opacity.opacity;
+                let cutoff = context.cutoff(opacity);
+
+                let footprint = match settings.gaussian_mode {
+                    GaussianMode::Gaussian2d => {
+                        context.surfel_footprint(position, rotation, scale, cutoff)?
+                    }
+                    _ => {
+                        let cov3d = context.cov3d(rotation, scale);
+                        context.footprint(context.cov2d(position, cov3d), cutoff)
+                    }
+                };
+
+                let direction = context.local_ray_direction(position);
+                let sh = &gaussian.spherical_harmonic.coefficients;
+                let sh_color = context.linear_color(spherical_harmonics_lookup(direction, sh));
+
+                let color = context.shade(
+                    Shading {
+                        position,
+                        previous_position: position,
+                        visibility,
+                        rotation,
+                        scale,
+                        sh_color,
+                        velocity: None,
+                    },
+                    opacity,
+                );
+
+                Some(Splat {
+                    center: projected.xy(),
+                    depth: projected.z,
+                    distance: position.distance(view.world_position()),
+                    footprint,
+                    color,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        context.composite(&splats)
+    }
+}
+
+impl ReferenceRasterize for PlanarGaussian4d {
+    fn rasterize_reference(
+        &self,
+        transform: &GlobalTransform,
+        settings: &CloudSettings,
+        view: &ReferenceView,
+    ) -> ReferenceImage {
+        let context = Context::new(self, transform, settings, view);
+
+        let splats = context
+            .sorted
+            .iter()
+            .filter_map(|&index| {
+                let gaussian = self.get(index);
+                let position = Vec3::from(gaussian.position_visibility.position);
+                let previous_position = context.transform.transform_point3(position);
+                let visibility = gaussian.position_visibility.visibility;
+                if context.skip(visibility) {
+                    return None;
+                }
+
+                let rotations = &gaussian.isotropic_rotations;
+                let scale = Vec3::from(gaussian.scale_opacity.scale);
+                let timestamp = gaussian.timestamp_timescale.timestamp;
+                let time_scale = gaussian.timestamp_timescale.timescale;
+                let conditional = |time: f32| {
+                    conditional_cov3d(
+                        Vec4::from(rotations.rotation),
+                        Vec4::from(rotations.rotation_r),
+                        scale * settings.global_scale,
+                        time_scale,
+                        time - timestamp,
+                    )
+                };
+
+                let gaussian_4d = conditional(settings.time)?;
+                let position = context
+                    .transform
+                    .transform_point3(position + gaussian_4d.delta_mean);
+                let projected = context.world_to_clip(position);
+                if !in_frustum(projected) {
+                    return None;
+                }
+
+                let opacity = gaussian.scale_opacity.opacity;
+                let cutoff = context.cutoff(opacity);
+                let footprint =
+                    context.footprint(context.cov2d(position, gaussian_4d.cov3d), cutoff);
+
+                let velocity = settings.rasterize_mode == RasterizeMode::Velocity;
+                let velocity = velocity.then(|| {
+                    let time_delta = 1e-3;
+                    let future_delta_mean = conditional(settings.time + time_delta)
+                        .map_or(Vec3::ZERO, |future| future.delta_mean);
+                    (future_delta_mean - gaussian_4d.delta_mean) / time_delta
+                });
+
+                let direction = context.local_ray_direction(position);
+                let sh_color = context.linear_color(spherindrical_harmonics_lookup(
+                    direction,
+                    gaussian_4d.dir_t,
+                    settings.time_stop - settings.time_start,
+                    |index| gaussian.spherindrical_harmonic.get(index),
+                ));
+
+                // TODO: support rotation decomposition for 4d gaussians, normals use the left rotation
+                let color = context.shade(
+                    Shading {
+                        position,
+                        previous_position,
+                        visibility,
+                        rotation: Vec4::from(rotations.rotation),
+                        scale,
+                        sh_color,
+                        velocity,
+                    },
+                    opacity * gaussian_4d.opacity_modifier,
+                );
+
+                Some(Splat {
+                    center: projected.xy(),
+                    depth: projected.z,
+                    distance: position.distance(view.world_position()),
+                    footprint,
+                    color,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        context.composite(&splats)
+    }
+}
+
+/// screen space coverage of a splat, `uv` spans the rasterized quad in `[-1, 1]`
+#[derive(Clone, Copy, Debug)]
+enum Footprint {
+    /// `USE_AABB` 3dgs, the conic evaluated at `uv * radius` pixel offsets
+    Conic { conic: Vec3, radius: f32, ndc: Vec2 },
+    /// `USE_AABB` 2dgs, ray-splat intersection in pixel space
+    Surfel {
+        local_to_pixel: Mat3,
+        mean_2d: Vec2,
+        radius: f32,
+        ndc: Vec2,
+    },
+    /// `USE_OBB`, an isotropic falloff over the eigenvector aligned quad
+    Quad { axis_x: Vec2, axis_y: Vec2 },
+}
+
+impl Footprint {
+    fn axes(&self) -> (Vec2, Vec2) {
+        match *self {
+            Footprint::Conic { ndc, .. } | Footprint::Surfel { ndc, .. } => {
+                (Vec2::new(ndc.x, 0.0), Vec2::new(0.0, ndc.y))
+            }
+            Footprint::Quad { axis_x, axis_y } => (axis_x, axis_y),
+        }
+    }
+
+    fn power(&self, uv: Vec2, aspect: f32) -> Option<f32> {
+        let power = match *self {
+            Footprint::Conic { conic, radius, .. } => {
+                let d = -uv * radius;
+                -0.5 * (conic.x * d.x * d.x + conic.z * d.y * d.y) + conic.y * d.x * d.y
+            }
+            Footprint::Surfel {
+                local_to_pixel,
+                mean_2d,
+                radius,
+                ..
+            } => {
+                let pixel_coord = uv * radius * Vec2::new(1.0, aspect) + mean_2d;
+                surfel_fragment_power(local_to_pixel, pixel_coord, mean_2d)
+            }
+            Footprint::Quad { .. } => {
+                let sigma = 1.0 / 3.0;
+                let distance_squared = uv.length_squared();
+                if distance_squared > 3.0 * 3.0 {
+                    return None;
+                }
+                -distance_squared / (2.0 * sigma * sigma)
+            }
+        };
+
+        (power <= 0.0).then_some(power)
+    }
+}
+
+struct Splat {
+    center: Vec2,
+    /// reversed ndc depth of the flat quad
+    depth: f32,
+    /// world distance to the camera, the depth of the tile keys
+    distance: f32,
+    footprint: Footprint,
+    /// rgb and opacity written by the vertex stage
+    color: Vec4,
+}
+
+struct Shading {
+    position: Vec3,
+    previous_position: Vec3,
+    visibility: f32,
+    rotation: Vec4,
+    scale: Vec3,
+    sh_color: Vec3,
+    velocity: Option<Vec3>,
+}
+
+struct ConditionalGaussian {
+    cov3d: [f32; 6],
+    delta_mean: Vec3,
+    opacity_modifier: f32,
+    dir_t: f32,
+}
+
+/// per-cloud state shared by every splat, the cpu side of `gaussian_uniforms`
+struct Context<'a> {
+    settings: &'a CloudSettings,
+    view: &'a ReferenceView,
+    transform: Mat4,
+    view_from_world: Mat4,
+    clip_from_world: Mat4,
+    min: Vec3,
+    max: Vec3,
+    min_distance: f32,
+    max_distance: f32,
+    /// `max_cloud_distance` of `tile.wgsl`, bounds the depth of the tile keys
+    max_cloud_distance: f32,
+    /// splat indices from near to far, in cloud order for the unsorted `Rasterizer::WeightedBlended`
+    sorted: Vec<usize>,
+}
+
+impl<'a> Context<'a> {
+    fn new<C: CommonCloud>(
+        cloud: &C,
+        transform: &GlobalTransform,
+        settings: &'a CloudSettings,
+        view: &'a ReferenceView,
+    ) -> Self {
+        let (min, max) = cloud
+            .compute_aabb()
+            .map_or((Vec3::ZERO, Vec3::ONE), |aabb| {
+                (Vec3::from(aabb.min), Vec3::from(aabb.max))
+            });
+        let bounds = cloud_bounding_sphere(&Aabb::from_min_max(min, max), transform);
+
+        let transform = transform.to_matrix();
+        let camera_position = view.world_position();
+
+        // same key as the std sort, squared distance to the transformed center
+        let positions = cloud
+            .position_iter()
+            .map(|position| transform.transform_point3(Vec3::from(position)))
+            .collect::<Vec<_>>();
+        let mut sorted = (0..positions.len()).collect::<Vec<_>>();
+        sorted.sort_by(|&a, &b| {
+            let a = positions[a].distance_squared(camera_position);
+            let b = positions[b].distance_squared(camera_position);
+            a.total_cmp(&b)
+        });
+
+        // the depth shader samples the second farthest and the nearest sorted entries
+        let distance = |rank: Option<&usize>| {
+            rank.map_or(0.0, |&index| positions[index].distance(camera_position))
+        };
+        let max_distance = distance(sorted.len().checked_sub(2).and_then(|i| sorted.get(i)));
+        let min_distance = distance(sorted.first());
+
+        if settings.rasterizer == Rasterizer::WeightedBlended {
+            sorted.sort_unstable();
+        }
+
+        Self {
+            settings,
+            view,
+            transform,
+            view_from_world: view.view_from_world(),
+            clip_from_world: view.clip_from_world(),
+            min,
+            max,
+            min_distance,
+            max_distance,
+            max_cloud_distance: bounds.truncate().distance(camera_position) + bounds.w,
+            sorted,
+        }
+    }
+
+    fn skip(&self, visibility: f32) -> bool {
+        self.settings.draw_mode == DrawMode::Selected && visibility < 0.5
+    }
+
+    fn cutoff(&self, opacity: f32) -> f32 {
+        if self.settings.opacity_adaptive_radius {
+            (9.0 + 2.0 * opacity.ln()).max(0.000001).sqrt()
+        } else {
+            3.0
+        }
+    }
+
+    fn world_to_clip(&self, position: Vec3) -> Vec3 {
+        let clip = self.clip_from_world * position.extend(1.0);
+        clip.xyz() / (clip.w + 0.000000001)
+    }
+
+    fn local_ray_direction(&self, position: Vec3) -> Vec3 {
+        let ray_direction = (position - self.view.world_position()).normalize();
+        let basis = [
+            self.transform.x_axis,
+            self.transform.y_axis,
+            self.transform.z_axis,
+        ]
+        .map(|axis| axis.xyz().normalize().dot(ray_direction));
+
+        Vec3::from(basis).normalize()
+    }
+
+    fn linear_color(&self, color: Vec3) -> Vec3 {
+        match self.settings.color_space {
+            GaussianColorSpace::LinRec709Display => color,
+            GaussianColorSpace::SrgbRec709Display => color.map(srgb_to_linear),
+        }
+    }
+
+    fn rotation_scale(&self, rotation: Vec4, scale: Vec3) -> (Mat3, Mat3) {
+        (
+            get_rotation_matrix(rotation),
+            Mat3::from_diagonal(scale * self.settings.global_scale),
+        )
+    }
+
+    fn cov3d(&self, rotation: Vec4, scale: Vec3) -> [f32; 6] {
+        let (r, s) = self.rotation_scale(rotation, scale);
+        let t = Mat3::from_mat4(self.transform);
+
+        let m = s * r;
+        let sigma = m.transpose() * m;
+        let ts = t * sigma * t.transpose();
+
+        [
+            ts.x_axis.x,
+            ts.x_axis.y,
+            ts.x_axis.z,
+            ts.y_axis.y,
+            ts.y_axis.z,
+            ts.z_axis.z,
+        ]
+    }
+
+    /// `helpers.wgsl::cov2d`
+    fn cov2d(&self, position: Vec3, cov3d: [f32; 6]) -> Vec3 {
+        let vrk = Mat3::from_cols_array(&[
+            cov3d[0], cov3d[1], cov3d[2], cov3d[1], cov3d[3], cov3d[4], cov3d[2], cov3d[4],
+            cov3d[5],
+        ]);
+
+        let t = self.view_from_world * position.extend(1.0);
+
+        let viewport = self.view.viewport();
+        let focal = Vec2::new(
+            self.view.clip_from_view.x_axis.x * viewport.x,
+            self.view.clip_from_view.y_axis.y * viewport.y,
+        );
+
+        let s = 1.0 / (t.z * t.z);
+        let j = Mat3::from_cols_array(&[
+            focal.x / t.z,
+            0.0,
+            -(focal.x * t.x) * s,
+            0.0,
+            -focal.y / t.z,
+            (focal.y * t.y) * s,
+            0.0,
+            0.0,
+            0.0,
+        ]);
+
+        let w = Mat3::from_mat4(self.view_from_world).transpose();
+        let t = w * j;
+
+        let mut cov = t.transpose() * vrk.transpose() * t;
+        cov.x_axis.x += 0.3;
+        cov.y_axis.y += 0.3;
+
+        Vec3::new(cov.x_axis.x, cov.x_axis.y, cov.y_axis.y)
+    }
+
+    /// `helpers.wgsl::get_bounding_box_clip` and the conic from `vs_points`
+    fn footprint(&self, cov2d: Vec3, cutoff: f32) -> Footprint {
+        let viewport = self.view.viewport();
+
+        let det = cov2d.x * cov2d.z - cov2d.y * cov2d.y;
+        let trace = cov2d.x + cov2d.z;
+        let mid = 0.5 * trace;
+        let discriminant = (mid * mid - det).max(0.0);
+        let term = discriminant.sqrt();
+        let lambda1 = mid + term;
+        let lambda2 = (mid - term).max(0.0);
+
+        if self.settings.aabb {
+            let radius = cutoff * lambda1.sqrt().max(lambda2.sqrt());
+            let det_inv = 1.0 / det;
+            Footprint::Conic {
+                conic: Vec3::new(cov2d.z * det_inv, -cov2d.y * det_inv, cov2d.x * det_inv),
+                radius,
+                ndc: Vec2::splat(radius) / viewport,
+            }
+        } else {
+            let a = (cov2d.x - cov2d.z) * (cov2d.x - cov2d.z);
+            let b = (a + 4.0 * cov2d.y * cov2d.y).sqrt();
+            let major_radius = ((cov2d.x + cov2d.z + b) * 0.5).sqrt();
+            let minor_radius = ((cov2d.x + cov2d.z - b) * 0.5).sqrt();
+            let bounds = cutoff * Vec2::new(major_radius, minor_radius);
+
+            let eigvec1 = Vec2::new(-cov2d.y, lambda1 - cov2d.x).normalize();
+            let eigvec2 = Vec2::new(eigvec1.y, -eigvec1.x);
+
+            Footprint::Quad {
+                axis_x: eigvec1 * bounds.x / viewport,
+                axis_y: eigvec2 * bounds.y / viewport,
+            }
+        }
+    }
+
+    /// `gaussian_2d.wgsl::compute_cov2d_surfel` and `get_bounding_box_cov2d`
+    fn surfel_footprint(
+        &self,
+        position: Vec3,
+        rotation: Vec4,
+        scale: Vec3,
+        cutoff: f32,
+    ) -> Option<Footprint> {
+        let (r, s) = self.rotation_scale(rotation, scale);
+        let l = Mat3::from_mat4(self.transform) * r.transpose() * s;
+
+        let world_from_local = [
+            l.x_axis.extend(0.0),
+            l.y_axis.extend(0.0),
+            position.extend(1.0),
+        ]
+        .map(|column| self.clip_from_world * column);
+
+        let viewport = self.view.viewport();
+        let focal = Vec2::new(
+            self.view.clip_from_view.x_axis.x * viewport.x / 2.0,
+            self.view.clip_from_view.y_axis.y * viewport.y / 2.0,
+        );
+        let pixels_from_ndc = [
+            Vec4::new(focal.x, 0.0, 0.0, (viewport.x - 1.0) / 2.0),
+            Vec4::new(0.0, focal.y, 0.0, (viewport.y - 1.0) / 2.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        ];
+
+        let t = pixels_from_ndc.map(|column| {
+            Vec3::new(
+                world_from_local[0].dot(column),
+                world_from_local[1].dot(column),
+                world_from_local[2].dot(column),
+            )
+        });
+        let local_to_pixel = Mat3::from_cols(t[0], t[1], t[2]);
+
+        let test = Vec3::new(cutoff * cutoff, cutoff * cutoff, -1.0);
+        let d = (test * t[2]).dot(t[2]);
+        if d.abs() < 1.0e-4 {
+            return None;
+        }
+
+        let f = (1.0 / d) * test;
+        let mean_2d = Vec2::new(f.dot(t[0] * t[2]), f.dot(t[1] * t[2]));
+        let extent = mean_2d * mean_2d - Vec2::new((f * t[0]).dot(t[0]), (f * t[1]).dot(t[1]));
+
+        let filter_size = std::f32::consts::FRAC_1_SQRT_2;
+        if extent.x < 1.0e-4 || extent.y < 1.0e-4 {
+            return None;
+        }
+
+        let radius = extent.map(f32::sqrt);
+        let max_radius = radius.max_element().max(cutoff * filter_size);
+
+        let ndc = Vec2::splat(max_radius) / viewport;
+        Some(if self.settings.aabb {
+            Footprint::Surfel {
+                local_to_pixel,
+                mean_2d,
+                radius: max_radius,
+                ndc,
+            }
+        } else {
+            Footprint::Quad {
+                axis_x: Vec2::new(ndc.x, 0.0),
+                axis_y: Vec2::new(0.0, ndc.y),
+            }
+        })
+    }
+
+    /// the per-mode rgb and opacity `vs_points` writes to `output.color`
+    fn shade(&self, shading: Shading, mut opacity: f32) -> Vec4 {
+        let settings = self.settings;
+
+        let rgb = match settings.rasterize_mode {
+            RasterizeMode::Classification => {
+                class_to_rgb(shading.visibility, shading.sh_color, settings.num_classes)
+            }
+            RasterizeMode::Color => shading.sh_color,
+            RasterizeMode::Depth => {
+                let depth = shading.position.distance(self.view.world_position());
+                depth_to_rgb(depth, self.min_distance, self.max_distance)
+            }
+            RasterizeMode::Normal => {
+                let (r, s) = self.rotation_scale(shading.rotation, shading.scale);
+                let l = Mat3::from_mat4(self.transform) * s * r;
+
+                let world_normal = self.view_from_world * l.z_axis.extend(0.0);
+                let t = world_normal.normalize();
+
+                0.5 * (t.xyz() + 1.0)
+            }
+            RasterizeMode::OpticalFlow => {
+                let clip = self.clip_from_world * shading.position.extend(1.0);
+                let previous_clip_from_world = self
+                    .view
+                    .previous_clip_from_world
+                    .unwrap_or(self.clip_from_world);
+                let previous = previous_clip_from_world * shading.previous_position.extend(1.0);
+
+                let motion_vector =
+                    (clip.xy() / clip.w - previous.xy() / previous.w) * Vec2::new(0.5, -0.5);
+                optical_flow_to_rgb(motion_vector, self.view.delta_time)
+            }
+            RasterizeMode::Position => (shading.position - self.min) / (self.max - self.min),
+            RasterizeMode::Velocity => {
+                // velocity is only defined for 4d gaussians
+                let velocity = shading.velocity.unwrap_or(Vec3::ZERO);
+                let velocity_magnitude = velocity.length();
+
+                let min_magnitude = 1.0;
+                let max_magnitude = 2.0;
+                let scaled_mag = ((velocity_magnitude - min_magnitude)
+                    / (max_magnitude - min_magnitude))
+                    .clamp(0.0, 1.0);
+
+                if scaled_mag < 1e-2 {
+                    opacity = 0.0;
+                }
+
+                0.5 * (velocity.normalize_or_zero() + 1.0) * scaled_mag
+            }
+        };
+
+        if settings.draw_mode == DrawMode::HighlightSelected && shading.visibility > 0.5 {
+            return HIGHLIGHT_COLOR;
+        }
+
+        rgb.extend(opacity * settings.global_opacity)
+    }
+
+    /// inverse quad basis of a splat and the pixel rect the quad covers, `None` for degenerate quads
+    fn quad(&self, splat: &Splat) -> Option<(Mat2, Vec2, Vec2)> {
+        let viewport = self.view.viewport();
+
+        let (axis_x, axis_y) = splat.footprint.axes();
+        let basis = Mat2::from_cols(axis_x, axis_y);
+        let det = basis.determinant();
+        if !det.is_finite() || det.abs() < f32::EPSILON * 1e-6 {
+            return None;
+        }
+
+        let extent = axis_x.abs() + axis_y.abs();
+        let to_pixel = |ndc: Vec2| {
+            Vec2::new(
+                (ndc.x * 0.5 + 0.5) * viewport.x,
+                (0.5 - ndc.y * 0.5) * viewport.y,
+            )
+        };
+        let corner_a = to_pixel(splat.center - extent);
+        let corner_b = to_pixel(splat.center + extent);
+
+        Some((
+            basis.inverse(),
+            corner_a.min(corner_b),
+            corner_a.max(corner_b),
+        ))
+    }
+
+    /// premultiplied color and alpha `fs_main` writes at pixel `x, y`
+    fn fragment(&self, splat: &Splat, ndc_to_uv: Mat2, x: u32, y: u32) -> Option<Vec4> {
+        let viewport = self.view.viewport();
+        let ndc = Vec2::new(
+            (x as f32 + 0.5) / viewport.x * 2.0 - 1.0,
+            1.0 - (y as f32 + 0.5) / viewport.y * 2.0,
+        );
+        let uv = ndc_to_uv * (ndc - splat.center);
+        if uv.x.abs() > 1.0 || uv.y.abs() > 1.0 {
+            return None;
+        }
+
+        let power = splat.footprint.power(uv, viewport.x / viewport.y)?;
+
+        Some(
+            if self.settings.visualize_bounding_box && is_bounding_box_edge(uv) {
+                HIGHLIGHT_COLOR
+            } else {
+                let alpha = (power.exp() * splat.color.w).min(0.999);
+                (splat.color.xyz() * alpha).extend(alpha)
+            },
+        )
+    }
+
+    /// runs `fs_main` over the quad of a splat, `blend` receives the pixel index and fragment
+    fn rasterize(&self, splat: &Splat, mut blend: impl FnMut(usize, Vec4)) {
+        let view = self.view;
+        let Some((ndc_to_uv, corner_min, corner_max)) = self.quad(splat) else {
+            return;
+        };
+        let min = corner_min.floor().max(Vec2::ZERO);
+        let max = corner_max.ceil().min(view.viewport());
+        if !(min.cmplt(max).all()) {
+            return;
+        }
+
+        for y in min.y as u32..max.y as u32 {
+            for x in min.x as u32..max.x as u32 {
+                if let Some(fragment) = self.fragment(splat, ndc_to_uv, x, y) {
+                    blend((y * view.width + x) as usize, fragment);
+                }
+            }
+        }
+    }
+
+    /// rasterizes the quads of `fs_main` and blends them with premultiplied alpha, nearest first
+    fn composite(&self, splats: &[Splat]) -> ReferenceImage {
+        #[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+        if self.settings.rasterizer == Rasterizer::Tile {
+            return self.composite_tiles(splats);
+        }
+
+        if self.settings.rasterizer == Rasterizer::WeightedBlended {
+            return self.composite_weighted_blended(splats);
+        }
+
+        let view = self.view;
+        let mut image = ReferenceImage::new(view.width, view.height);
+        let mut transmittance = vec![1.0_f32; image.pixels.len()];
+
+        for splat in splats {
+            self.rasterize(splat, |index, fragment| {
+                image.pixels[index] += transmittance[index] * fragment.xyz().extend(0.0);
+                transmittance[index] *= 1.0 - fragment.w;
+            });
+        }
+
+        for (pixel, transmittance) in image.pixels.iter_mut().zip(transmittance) {
+            pixel.w = 1.0 - transmittance;
+        }
+
+        self.write_depth(splats, &mut image);
+
+        image
+    }
+
+    /// `fs_depth` prepass, keeps the nearest quad depth of the fragments above the alpha threshold
+    fn write_depth(&self, splats: &[Splat], image: &mut ReferenceImage) {
+        if self.settings.depth_mode != DepthMode::AlphaThreshold {
+            return;
+        }
+
+        for splat in splats {
+            self.rasterize(splat, |index, fragment| {
+                if fragment.w >= self.settings.depth_alpha_threshold {
+                    image.depth[index] = image.depth[index].max(splat.depth);
+                }
+            });
+        }
+    }
+
+    /// `fs_oit` accumulation followed by `fs_resolve`, the splats are blended in any order
+    fn composite_weighted_blended(&self, splats: &[Splat]) -> ReferenceImage {
+        let view = self.view;
+        let view_from_clip = view.clip_from_view.inverse();
+        let mut image = ReferenceImage::new(view.width, view.height);
+        let mut accum = vec![Vec4::ZERO; image.pixels.len()];
+        let mut revealage = vec![1.0_f32; image.pixels.len()];
+
+        for splat in splats {
+            let weight = oit_weight(self.settings.oit_weight, splat.depth, view_from_clip);
+            self.rasterize(splat, |index, fragment| {
+                accum[index] += fragment * weight;
+                revealage[index] *= 1.0 - fragment.w;
+            });
+        }
+
+        for (pixel, (accum, revealage)) in image
+            .pixels
+            .iter_mut()
+            .zip(accum.into_iter().zip(revealage))
+        {
+            *pixel = oit_resolve(accum, revealage);
+        }
+
+        self.write_depth(splats, &mut image);
+
+        image
+    }
+
+    /// `tile.wgsl`, bins the splats into tile keys, sorts the keys and blends every pixel of a tile
+    /// front to back until its transmittance drops below `TILE_TRANSMITTANCE_CUTOFF`
+    #[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+    fn composite_tiles(&self, splats: &[Splat]) -> ReferenceImage {
+        let view = self.view;
+        let size = UVec2::new(view.width, view.height);
+        let grid = tile_grid(size);
+        let depth_bits = tile_depth_bits(grid);
+
+        let quads = splats
+            .iter()
+            .map(|splat| self.quad(splat))
+            .collect::<Vec<_>>();
+
+        let mut entries = Vec::new();
+        for (index, (splat, quad)) in splats.iter().zip(&quads).enumerate() {
+            let Some((_, min_px, max_px)) = *quad else {
+                continue;
+            };
+            let Some((tile_min, tile_max)) = tile_bounds(min_px, max_px, size) else {
+                continue;
+            };
+
+            for y in tile_min.y..=tile_max.y {
+                for x in tile_min.x..=tile_max.x {
+                    let tile = x + y * grid.x;
+                    let key = tile_key(tile, splat.distance, self.max_cloud_distance, depth_bits);
+                    entries.push((key, index));
+                }
+            }
+        }
+        entries.sort_by_key(|&(key, _)| key);
+
+        let mut image = ReferenceImage::new(view.width, view.height);
+        for tile_entries in entries.chunk_by(|a, b| a.0 >> depth_bits == b.0 >> depth_bits) {
+            let tile = tile_entries[0].0 >> depth_bits;
+            let origin = UVec2::new(tile % grid.x, tile / grid.x) * TILE_SIZE;
+            let end = (origin + UVec2::splat(TILE_SIZE)).min(size);
+
+            for y in origin.y..end.y {
+                for x in origin.x..end.x {
+                    let mut color = Vec3::ZERO;
+                    let mut transmittance = 1.0;
+                    let mut depth = None;
+
+                    for &(_, index) in tile_entries {
+                        if transmittance < TILE_TRANSMITTANCE_CUTOFF {
+                            break;
+                        }
+
+                        let Some((ndc_to_uv, ..)) = quads[index] else {
+                            continue;
+                        };
+                        let Some(fragment) = self.fragment(&splats[index], ndc_to_uv, x, y) else {
+                            continue;
+                        };
+
+                        color += transmittance * fragment.xyz();
+                        transmittance *= 1.0 - fragment.w;
+
+                        if depth.is_none()
+                            && 1.0 - transmittance >= self.settings.depth_alpha_threshold
+                        {
+                            depth = Some(splats[index].depth);
+                        }
+                    }
+
+                    let pixel = (y * view.width + x) as usize;
+                    image.pixels[pixel] = color.extend(1.0 - transmittance);
+                    // coverage below the threshold composites but writes no depth
+                    if self.settings.depth_mode == DepthMode::AlphaThreshold {
+                        image.depth[pixel] = depth.unwrap_or(0.0);
+                    }
+                }
+            }
+        }
+
+        image
+    }
+}
+
+/// `oit_weight` of `gaussian.wgsl`, the weight of a fragment at reversed ndc depth `ndc_depth`
+pub fn oit_weight(weight: OitWeight, ndc_depth: f32, view_from_clip: Mat4) -> f32 {
+    match weight {
+        OitWeight::Constant => 1.0,
+        OitWeight::DepthFalloff => {
+            let view_position = view_from_clip * Vec4::new(0.0, 0.0, ndc_depth, 1.0);
+            let distance = (view_position.z / view_position.w).abs();
+            (10.0 / (1e-5 + (distance / 5.0).powi(2) + (distance / 200.0).powi(6))).clamp(1e-2, 3e3)
+        }
+        OitWeight::ProjectedDepth => (3e3 * ndc_depth.powi(3)).clamp(1e-2, 3e3),
+    }
+}
+
+/// `fs_resolve` of `oit_resolve.wgsl`, premultiplied color of the summed `accum` and the product of `revealage`
+pub fn oit_resolve(accum: Vec4, revealage: f32) -> Vec4 {
+    // no splat covered this pixel
+    if revealage >= 1.0 {
+        return Vec4::ZERO;
+    }
+
+    let color = accum.xyz() / accum.w.clamp(1e-4, 5e4);
+    let alpha = 1.0 - revealage;
+
+    (color * alpha).extend(alpha)
+}
+
+fn in_frustum(clip: Vec3) -> bool {
+    clip.x.abs() < 1.1 && clip.y.abs() < 1.1 && (clip.z - 0.5).abs() < 0.5
+}
+
+fn is_bounding_box_edge(uv: Vec2) -> bool {
+    let uv = uv * 0.5 + 0.5;
+    let edge_width = 0.08;
+    uv.x < edge_width || uv.x > 1.0 - edge_width || uv.y < edge_width || uv.y > 1.0 - edge_width
+}
+
+fn get_rotation_matrix(rotation: Vec4) -> Mat3 {
+    let r = rotation.x;
+    let x = rotation.y;
+    let y = rotation.z;
+    let z = rotation.w;
+
+    Mat3::from_cols_array(&[
+        1.0 - 2.0 * (y * y + z * z),
+        2.0 * (x * y - r * z),
+        2.0 * (x * z + r * y),
+        2.0 * (x * y + r * z),
+        1.0 - 2.0 * (x * x + z * z),
+        2.0 * (y * z - r * x),
+        2.0 * (x * z - r * y),
+        2.0 * (y * z + r * x),
+        1.0 - 2.0 * (x * x + y * y),
+    ])
+}
+
+/// `gaussian_4d.wgsl::conditional_cov3d`, `None` once the temporal marginal is masked
+fn conditional_cov3d(
+    rotation: Vec4,
+    rotation_r: Vec4,
+    scale: Vec3,
+    time_scale: f32,
+    dt: f32,
+) -> Option<ConditionalGaussian> {
+    let s = Mat4::from_diagonal(scale.extend(time_scale));
+
+    let [w, x, y, z] = rotation.to_array();
+    let [wr, xr, yr, zr] = rotation_r.to_array();
+
+    let m_l = Mat4::from_cols_array(&[w, -x, -y, -z, x, w, -z, y, y, z, w, -x, z, -y, x, w]);
+    let m_r = Mat4::from_cols_array(&[
+        wr, -xr, -yr, -zr, xr, wr, zr, -yr, yr, -zr, wr, xr, zr, yr, -xr, wr,
+    ]);
+
+    let r = m_r * m_l;
+    let m = r * s;
+    let sigma = m.transpose() * m;
+
+    let cov_t = sigma.w_axis.w;
+    let marginal_t = (-0.5 * dt * dt / cov_t).exp();
+
+    let mask = marginal_t > 0.05;
+    if !mask {
+        return None;
+    }
+
+    let cov11 = Mat3::from_mat4(sigma);
+    let cov12 = Vec3::new(sigma.x_axis.w, sigma.y_axis.w, sigma.z_axis.w);
+    let cov3d =
+        cov11 - Mat3::from_cols(cov12 * cov12.x, cov12 * cov12.y, cov12 * cov12.z) * (1.0 / cov_t);
+
+    Some(ConditionalGaussian {
+        cov3d: [
+            cov3d.x_axis.x,
+            cov3d.x_axis.y,
+            cov3d.x_axis.z,
+            cov3d.y_axis.y,
+            cov3d.y_axis.z,
+            cov3d.z_axis.z,
+        ],
+        delta_mean: cov12 / cov_t * dt,
+        opacity_modifier: marginal_t,
+        dir_t: dt,
+    })
+}
+
+/// `gaussian_2d.wgsl::surfel_fragment_power`
+fn surfel_fragment_power(local_to_pixel: Mat3, pixel_coord: Vec2, mean_2d: Vec2) -> f32 {
+    let deltas = mean_2d - pixel_coord;
+
+    let hu = pixel_coord.x * local_to_pixel.z_axis - local_to_pixel.x_axis;
+    let hv = pixel_coord.y * local_to_pixel.z_axis - local_to_pixel.y_axis;
+
+    let p = hu.cross(hv);
+
+    let us = p.x / p.z;
+    let vs = p.y / p.z;
+
+    let sigmas_3d = us * us + vs * vs;
+    let sigmas_2d = 2.0 * deltas.length_squared();
+
+    -0.5 * sigmas_3d.min(sigmas_2d)
+}
+
+/// real spherical harmonic basis in the order of `spherical_harmonics.wgsl`, up to degree 3
+fn sh_basis(direction: Vec3) -> [f32; 16] {
+    let basis = spherical_harmonics::sh_basis(direction);
+
+    std::array::from_fn(|i| basis[i])
+}
+
+/// basis functions the shaders evaluate, capped at degree 3
+fn sh_coefficient_count() -> usize {
+    (clamp_sh_degree(3) + 1).pow(2)
+}
+
+fn sh_term(basis: &[f32; 16], coefficient: impl Fn(usize) -> f32, offset: usize) -> Vec3 {
+    (0..sh_coefficient_count())
+        .map(|k| {
+            let index = offset + k * 3;
+            basis[k]
+                * Vec3::new(
+                    coefficient(index),
+                    coefficient(index + 1),
+                    coefficient(index + 2),
+                )
+        })
+        .sum()
+}
+
+fn spherical_harmonics_lookup(direction: Vec3, sh: &[f32]) -> Vec3 {
+    let basis = sh_basis(direction);
+    Vec3::splat(0.5) + sh_term(&basis, |index| sh[index], 0)
+}
+
+fn spherindrical_harmonics_lookup(
+    direction: Vec3,
+    dir_t: f32,
+    duration: f32,
+    sh: impl Fn(usize) -> f32,
+) -> Vec3 {
+    let basis = sh_basis(direction);
+    let mut color = Vec3::splat(0.5) + sh_term(&basis, &sh, 0);
+
+    let theta = dir_t / duration;
+    let stride = (SH_DEGREE + 1).pow(2) * 3;
+    for degree in 1..=SH_4D_DEGREE_TIME.min(2) {
+        let t = (2.0 * degree as f32 * std::f32::consts::PI * theta).cos();
+        color += t * sh_term(&basis, &sh, stride * degree);
+    }
+
+    color
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// `bevy_render::color_operations::hsv_to_rgb`, hue in radians
+fn hsv_to_rgb(hsv: Vec3) -> Vec3 {
+    let k = (Vec3::new(5.0, 3.0, 1.0) + hsv.x / std::f32::consts::FRAC_PI_3) % 6.0;
+    hsv.z - hsv.z * hsv.y * k.min((4.0 - k).min(Vec3::ONE)).max(Vec3::ZERO)
+}
+
+fn class_to_rgb(visualization: f32, sh_color: Vec3, num_classes: usize) -> Vec3 {
+    if visualization < 2.0 {
+        return sh_color;
+    }
+
+    let class_idx = visualization - 2.0;
+    let hue = (class_idx / num_classes as f32) * std::f32::consts::TAU;
+
+    sh_color.lerp(hsv_to_rgb(Vec3::new(hue, 1.0, 1.0)), 0.5)
+}
+
+fn smoothstep(low: f32, high: f32, x: f32) -> f32 {
+    let t = ((x - low) / (high - low)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn depth_to_rgb(depth: f32, min_depth: f32, max_depth: f32) -> Vec3 {
+    let normalized_depth = ((depth - min_depth) / (max_depth - min_depth)).clamp(0.0, 1.0);
+
+    Vec3::new(
+        smoothstep(0.5, 1.0, normalized_depth),
+        1.0 - (normalized_depth - 0.5).abs() * 2.0,
+        1.0 - smoothstep(0.0, 0.5, normalized_depth),
+    )
+}
+
+fn optical_flow_to_rgb(motion_vector: Vec2, delta_time: f32) -> Vec3 {
+    let flow = motion_vector / delta_time;
+
+    let radius = flow.length();
+    let mut angle = flow.y.atan2(flow.x);
+    if angle < 0.0 {
+        angle += std::f32::consts::TAU;
+    }
+
+    hsv_to_rgb(Vec3::new(angle, radius.clamp(0.0, 1.0), 1.0))
+}
@@ -1,29 +1,289 @@
+#![allow(dead_code)] // ShaderType derives emit unused check helpers
+use std::marker::PhantomData;
+
 use bevy::{
     prelude::*,
     render::{
-        render_resource::{
-            BindGroupLayout, BindGroupLayoutEntry, BindingType, ShaderStages, TextureSampleType,
-            TextureViewDimension,
-        },
-        renderer::RenderDevice,
+        Extract, ExtractSchedule, RenderApp,
+        render_asset::RenderAssets,
+        render_resource::*,
+        renderer::{RenderDevice, RenderQueue},
+        sync_world::RenderEntity,
+        texture::GpuImage,
     },
 };
+use bevy_interleave::prelude::*;
 use static_assertions::assert_cfg;
 
+use crate::{
+    gaussian::formats::planar_3d::PlanarGaussian3dHandle,
+    io::gcloud::texture::{
+        GAUSSIAN_TEXTURE_IMAGES_LABEL, GaussianTextureImages, GaussianTexturePlane,
+        GaussianTextureRanges,
+    },
+};
+
 assert_cfg!(
     feature = "planar",
     "texture rendering is only supported with the `planar` feature enabled",
 );
 
-/// Placeholder plugin for the buffer-texture render path.
+/// draws 3d clouds loaded from `.gtex` straight from their attribute planes
 ///
-/// Cloud data currently stays on the canonical planar storage path; the
-/// texture-specific bindings are used for sorted-entry indirection only.
+/// bind groups are queued per cloud type by `queue_gaussian_texture_bind_groups`, other f32 clouds
+/// draw from their planar storage bind group with `PLANAR_TEXTURE_F32`
 #[derive(Default)]
 pub struct BufferTexturePlugin;
 
 impl Plugin for BufferTexturePlugin {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, attach_gaussian_texture_images);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(ExtractSchedule, extract_gaussian_texture_images);
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<GaussianTextureLayout>();
+        }
+    }
+}
+
+/// plane images a cloud is drawn from, attached to clouds loaded from `.gtex`
+#[derive(Component, Clone, Debug, Default)]
+pub struct GaussianTextureImagesHandle(pub Handle<GaussianTextureImages>);
+
+fn attach_gaussian_texture_images(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    clouds: Query<(Entity, &PlanarGaussian3dHandle), Without<GaussianTextureImagesHandle>>,
+) {
+    for (entity, cloud_handle) in &clouds {
+        let Some(path) = asset_server.get_path(cloud_handle.0.id()) else {
+            continue;
+        };
+
+        if path.get_full_extension() != Some("gtex") {
+            continue;
+        }
+
+        let images = asset_server.load(path.into_owned().with_label(GAUSSIAN_TEXTURE_IMAGES_LABEL));
+        commands
+            .entity(entity)
+            .insert(GaussianTextureImagesHandle(images));
+    }
+}
+
+/// dequantization ranges of `GaussianTextureRanges`, see `texture_ranges` in `bindings.wgsl`
+#[derive(Clone, Copy, Debug, Default, ShaderType)]
+pub struct GaussianTextureUniform {
+    pub means_min: Vec3,
+    pub means_max: Vec3,
+    pub scales_min: Vec3,
+    pub scales_max: Vec3,
+    pub sh0_min: Vec3,
+    pub sh0_max: Vec3,
+    pub sh_rest_min: f32,
+    pub sh_rest_max: f32,
+}
+
+impl From<&GaussianTextureRanges> for GaussianTextureUniform {
+    fn from(ranges: &GaussianTextureRanges) -> Self {
+        Self {
+            means_min: ranges.means_min.into(),
+            means_max: ranges.means_max.into(),
+            scales_min: ranges.scales_min.into(),
+            scales_max: ranges.scales_max.into(),
+            sh0_min: ranges.sh0_min.into(),
+            sh0_max: ranges.sh0_max.into(),
+            sh_rest_min: ranges.sh_rest_min,
+            sh_rest_max: ranges.sh_rest_max,
+        }
+    }
+}
+
+/// plane images in binding order, `GaussianTexturePlane::ALL`
+#[derive(Component, Clone, Debug)]
+pub struct ExtractedGaussianTextures {
+    pub planes: [AssetId<Image>; GaussianTexturePlane::ALL.len()],
+    pub ranges: GaussianTextureUniform,
+}
+
+fn extract_gaussian_texture_images(
+    mut commands: Commands,
+    texture_images: Extract<Res<Assets<GaussianTextureImages>>>,
+    clouds: Extract<Query<(RenderEntity, &GaussianTextureImagesHandle)>>,
+) {
+    for (entity, handle) in clouds.iter() {
+        let Some(textures) = texture_images.get(&handle.0) else {
+            continue;
+        };
+
+        // clouds without rest bands bind `sh0` in place of the codebook, the shader never reads it
+        let plane = |plane| {
+            textures
+                .planes
+                .get(&plane)
+                .or_else(|| textures.planes.get(&GaussianTexturePlane::Sh0))
+                .map(Handle::id)
+        };
+        let Some(planes) = GaussianTexturePlane::ALL
+            .into_iter()
+            .map(plane)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|planes| planes.try_into().ok())
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert(ExtractedGaussianTextures {
+            planes,
+            ranges: (&textures.ranges).into(),
+        });
+    }
+}
+
+/// replaces the planar storage bind group of clouds drawn from `.gtex` planes
+#[derive(Component, Clone, Debug)]
+pub struct GaussianTextureBindGroup<R: PlanarSync> {
+    pub bind_group: BindGroup,
+    pub phantom: PhantomData<fn() -> R>,
+}
+
+/// planes of `.gtex` clouds, still uploading planes draw nothing rather than the planar storage
+pub fn cloud_bind_group<'w, R: PlanarSync>(
+    (gtex, texture_bind_group, planar_bind_group): (
+        bool,
+        Option<&'w GaussianTextureBindGroup<R>>,
+        Option<&'w PlanarStorageBindGroup<R>>,
+    ),
+) -> Option<&'w BindGroup> {
+    if gtex {
+        texture_bind_group.map(|bind_group| &bind_group.bind_group)
+    } else {
+        planar_bind_group.map(|bind_group| &bind_group.bind_group)
+    }
+}
+
+#[derive(Resource)]
+pub struct GaussianTextureLayout {
+    pub layout: BindGroupLayout,
+}
+
+impl FromWorld for GaussianTextureLayout {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+
+        Self {
+            layout: render_device.create_bind_group_layout(
+                Some("gaussian_texture_layout"),
+                &gaussian_texture_layout_entries(),
+            ),
+        }
+    }
+}
+
+/// one `Rgba8Unorm` texture per `GaussianTexturePlane` followed by the `GaussianTextureUniform`
+pub fn gaussian_texture_layout_entries() -> Vec<BindGroupLayoutEntry> {
+    let planes = GaussianTexturePlane::ALL.len() as u32;
+
+    (0..planes)
+        .map(|binding| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::VERTEX_FRAGMENT | ShaderStages::COMPUTE,
+            ty: BindingType::Texture {
+                view_dimension: TextureViewDimension::D2,
+                sample_type: TextureSampleType::Float { filterable: true },
+                multisampled: false,
+            },
+            count: None,
+        })
+        .chain(std::iter::once(BindGroupLayoutEntry {
+            binding: planes,
+            visibility: ShaderStages::VERTEX_FRAGMENT | ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: Some(GaussianTextureUniform::min_size()),
+            },
+            count: None,
+        }))
+        .collect()
+}
+
+pub fn gaussian_texture_layout_descriptor() -> BindGroupLayoutDescriptor {
+    BindGroupLayoutDescriptor::new(
+        "gaussian_texture_layout",
+        &gaussian_texture_layout_entries(),
+    )
+}
+
+// the plane images may finish uploading after the cloud, retried until they are all on the gpu
+#[allow(clippy::type_complexity)]
+pub fn queue_gaussian_texture_bind_groups<R: PlanarSync>(
+    mut commands: Commands,
+    layout: Res<GaussianTextureLayout>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    clouds: Query<
+        (
+            Entity,
+            &ExtractedGaussianTextures,
+            Option<&GaussianTextureBindGroup<R>>,
+        ),
+        With<R::PlanarTypeHandle>,
+    >,
+) {
+    for (entity, textures, existing_bind_group) in &clouds {
+        // reloaded planes are new images, so the ranges never change without `gpu_images`
+        if !gpu_images.is_changed() && existing_bind_group.is_some() {
+            continue;
+        }
+
+        let Some(views) = textures
+            .planes
+            .iter()
+            .map(|id| gpu_images.get(*id).map(|image| &image.texture_view))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        let mut ranges = UniformBuffer::from(textures.ranges);
+        ranges.write_buffer(&render_device, &render_queue);
+        let Some(ranges_binding) = ranges.binding() else {
+            continue;
+        };
+
+        let entries = views
+            .into_iter()
+            .enumerate()
+            .map(|(binding, view)| BindGroupEntry {
+                binding: binding as u32,
+                resource: BindingResource::TextureView(view),
+            })
+            .chain(std::iter::once(BindGroupEntry {
+                binding: GaussianTexturePlane::ALL.len() as u32,
+                resource: ranges_binding,
+            }))
+            .collect::<Vec<_>>();
+
+        let bind_group = render_device.create_bind_group(
+            "gaussian_texture_bind_group",
+            &layout.layout,
+            &entries,
+        );
+
+        commands
+            .entity(entity)
+            .insert(GaussianTextureBindGroup::<R> {
+                bind_group,
+                phantom: PhantomData,
+            });
+    }
 }
 
 pub fn get_sorted_bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
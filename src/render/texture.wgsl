@@ -1,6 +1,26 @@
 #define_import_path bevy_gaussian_splatting::texture
 
-#ifdef PRECOMPUTE_COVARIANCE_3D
+#ifdef PLANAR_TEXTURE_GTEX
+#import bevy_gaussian_splatting::bindings::{
+    gaussian_uniforms,
+    gtex_means_low,
+    gtex_means_high,
+    gtex_quats,
+    gtex_scales,
+    gtex_sh0,
+    gtex_sh_labels,
+    gtex_sh_centroids,
+    gtex_ranges,
+};
+#else ifdef PLANAR_TEXTURE_F32
+#import bevy_gaussian_splatting::bindings::{
+    gaussian_uniforms,
+    position_visibility,
+    spherical_harmonics,
+    rotation,
+    scale_opacity,
+};
+#else ifdef PRECOMPUTE_COVARIANCE_3D
 #import bevy_gaussian_splatting::bindings::{
     gaussian_uniforms,
     position_visibility,
@@ -365,7 +385,6 @@ fn get_visibility(index: u32) -> f32 {
 }
 #endif
 
-// TODO: support f32
 #ifdef PLANAR_TEXTURE_F32
 fn get_position(index: u32) -> vec3<f32> {
     return position_visibility[index].xyz;
@@ -375,6 +394,16 @@ fn get_spherical_harmonics(index: u32) -> array<f32, #{SH_COEFF_COUNT}> {
     return spherical_harmonics[index];
 }
 
+fn get_color(
+    index: u32,
+    ray_direction: vec3<f32>,
+) -> vec3<f32> {
+    let sh = get_spherical_harmonics(index);
+    let color = spherical_harmonics_lookup(ray_direction, sh);
+
+    return convert_sh_color_to_linear(color);
+}
+
 fn get_rotation(index: u32) -> vec4<f32> {
     return rotation[index];
 }
@@ -391,3 +420,118 @@ fn get_visibility(index: u32) -> f32 {
     return position_visibility[index].w;
 }
 #endif
+
+#ifdef PLANAR_TEXTURE_GTEX
+// planes are padded to 4x4 blocks, splat `index` is texel `index` of the padded width
+fn gtex_location(index: u32) -> vec2<i32> {
+    let width = textureDimensions(gtex_means_low).x;
+
+    return vec2<i32>(
+        i32(index % width),
+        i32(index / width),
+    );
+}
+
+// 16-bit codes of `sign(x) * ln(|x| + 1)` split across two planes
+fn get_position(index: u32) -> vec3<f32> {
+    let low = round(textureLoad(gtex_means_low, gtex_location(index), 0).xyz * 255.0);
+    let high = round(textureLoad(gtex_means_high, gtex_location(index), 0).xyz * 255.0);
+    let code = (low + high * 256.0) / 65535.0;
+    let mean = mix(gtex_ranges.means_min, gtex_ranges.means_max, code);
+
+    return sign(mean) * (exp(abs(mean)) - 1.0);
+}
+
+fn get_visibility(index: u32) -> f32 {
+    return 1.0;
+}
+
+fn get_spherical_harmonics(index: u32) -> array<f32, #{SH_COEFF_COUNT}> {
+    var coefficients: array<f32, #{SH_COEFF_COUNT}>;
+
+    let dc = mix(
+        gtex_ranges.sh0_min,
+        gtex_ranges.sh0_max,
+        textureLoad(gtex_sh0, gtex_location(index), 0).xyz,
+    );
+    coefficients[0] = dc.x;
+    coefficients[1] = dc.y;
+    coefficients[2] = dc.z;
+
+#if SH_COEFF_COUNT > 3
+    // u16 codebook label, an entry spans one texel per rest coefficient of a centroid row
+    let label_bytes = round(textureLoad(gtex_sh_labels, gtex_location(index), 0).xy * 255.0);
+    let label = u32(label_bytes.x) | (u32(label_bytes.y) << 8u);
+
+    let rest_per_channel = #{SH_COEFF_COUNT}u / 3u - 1u;
+    let entries_per_row = textureDimensions(gtex_sh_centroids).x / rest_per_channel;
+    let row = i32(label / entries_per_row);
+    let column = (label % entries_per_row) * rest_per_channel;
+
+    for (var coefficient = 0u; coefficient < rest_per_channel; coefficient = coefficient + 1u) {
+        let code = textureLoad(
+            gtex_sh_centroids,
+            vec2<i32>(i32(column + coefficient), row),
+            0,
+        ).xyz;
+        let value = mix(vec3<f32>(gtex_ranges.sh_rest_min), vec3<f32>(gtex_ranges.sh_rest_max), code);
+
+        let offset = 3u * (coefficient + 1u);
+        coefficients[offset] = value.x;
+        coefficients[offset + 1u] = value.y;
+        coefficients[offset + 2u] = value.z;
+    }
+#endif
+
+    return coefficients;
+}
+
+fn get_color(
+    index: u32,
+    ray_direction: vec3<f32>,
+) -> vec3<f32> {
+    let sh = get_spherical_harmonics(index);
+    let color = spherical_harmonics_lookup(ray_direction, sh);
+
+    return convert_sh_color_to_linear(color);
+}
+
+// smallest-three, 252 + the largest component index in alpha
+fn get_rotation(index: u32) -> vec4<f32> {
+    let texel = textureLoad(gtex_quats, gtex_location(index), 0);
+    let largest = min(u32(max(round(texel.w * 255.0) - 252.0, 0.0)), 3u);
+
+    var q = vec4<f32>(0.0);
+    var slot = 0u;
+    var sum_squares = 0.0;
+    for (var i = 0u; i < 4u; i = i + 1u) {
+        if i == largest {
+            continue;
+        }
+
+        let value = (texel[slot] * 2.0 - 1.0) * 0.70710678;
+        q[i] = value;
+        sum_squares = sum_squares + value * value;
+        slot = slot + 1u;
+    }
+    q[largest] = sqrt(max(1.0 - sum_squares, 0.0));
+
+    return q;
+}
+
+fn get_scale(index: u32) -> vec3<f32> {
+    let code = textureLoad(gtex_scales, gtex_location(index), 0).xyz;
+
+    return exp(mix(gtex_ranges.scales_min, gtex_ranges.scales_max, code));
+}
+
+// loader padding past the plane texels stays transparent
+fn get_opacity(index: u32) -> f32 {
+    let size = textureDimensions(gtex_sh0);
+    if (index >= size.x * size.y) {
+        return 0.0;
+    }
+
+    return textureLoad(gtex_sh0, gtex_location(index), 0).w;
+}
+#endif
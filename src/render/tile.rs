@@ -0,0 +1,1112 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+    },
+};
+
+use bevy::{
+    asset::{load_internal_asset, uuid_handle},
+    camera::primitives::Aabb,
+    core_pipeline::{
+        Core3d, Core3dSystems,
+        core_3d::{Transparent3d, TransparentSortingInfo3d},
+        prepass::PreviousViewUniformOffset,
+    },
+    ecs::{
+        query::ROQueryItem,
+        system::{SystemParamItem, lifetimeless::*},
+    },
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSystems,
+        extract_component::DynamicUniformIndex,
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+            RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice, ViewQuery},
+        view::{ExtractedView, RenderVisibleEntities, ViewUniformOffset},
+    },
+};
+use bevy_interleave::{interface::storage::PlanarStorageBindGroup, prelude::*};
+
+use crate::{
+    camera::GaussianCamera,
+    gaussian::{
+        cloud::CloudVisibilityClass,
+        interface::PlanarStorageFormat,
+        settings::{CloudSettings, DepthMode, RadixSortDepthBits, Rasterizer},
+    },
+    render::{
+        CloudPipeline, CloudPipelineKey, CloudUniform, GaussianComputeViewBindGroup,
+        GaussianUniformBindGroups, SetPreviousViewBindGroup, ShaderDefines,
+        depth::SplatDepthPhases, shader_defs, shader_defs_with_defines,
+    },
+    sort::{
+        GpuSortedEntry, SortEntry, SortTrigger, SortedEntriesHandle,
+        radix::{
+            GpuRadixBuffers, RADIX_PIPELINE_COUNT, RadixSortLabel, compute_pipelines_loaded,
+            encode_radix_sort, queue_radix_sort_pipelines, radix_sort_bind_groups,
+            radix_sort_layout_entries,
+        },
+    },
+};
+
+pub(crate) const TILE_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("5d0c5a0e-6f0b-4d41-9a3e-8f3f9b7c2e61");
+pub(crate) const TILE_COMPOSITE_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("c4f1e2b7-3a59-4e8d-b0c6-2d7e91a4f358");
+
+/// edge length of a screen tile in pixels, matches `TILE_SIZE` in `tile.wgsl`
+pub const TILE_SIZE: u32 = 16;
+
+/// average tile overlaps reserved per gaussian
+pub const TILE_ENTRIES_PER_GAUSSIAN: usize = 8;
+
+/// transmittance below which a pixel stops blending, matches `TRANSMITTANCE_CUTOFF` in `tile.wgsl`
+pub const TILE_TRANSMITTANCE_CUTOFF: f32 = 1e-4;
+
+const TILE_WORKGROUP_SIZE: u32 = 256;
+const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65535;
+
+const TILE_PIPELINE_PREPROCESS: usize = 0;
+const TILE_PIPELINE_RANGES: usize = 1;
+const TILE_PIPELINE_RASTERIZE: usize = 2;
+const TILE_PIPELINE_COUNT: usize = 3;
+
+/// tiles covering a viewport of `size` pixels
+pub fn tile_grid(size: UVec2) -> UVec2 {
+    (size + UVec2::splat(TILE_SIZE - 1)) / TILE_SIZE
+}
+
+/// bits of a tile key left for the depth, the tile id takes as few bits as the grid allows,
+/// matches `depth_bits` in `tile.wgsl`
+pub fn tile_depth_bits(grid: UVec2) -> u32 {
+    let tile_count = (grid.x * grid.y).max(1);
+    let tile_bits = (u32::BITS - (tile_count - 1).leading_zeros()).max(1);
+    u32::BITS - tile_bits
+}
+
+/// `tile << depth_bits | depth` key of a splat `distance` from the camera, matches `preprocess_splats`
+pub fn tile_key(tile: u32, distance: f32, max_distance: f32, depth_bits: u32) -> u32 {
+    let depth_mask = (1 << depth_bits) - 1;
+    let depth = (distance / max_distance.max(1e-6)).clamp(0.0, 1.0) * depth_mask as f32;
+    (tile << depth_bits) | (depth as u32).min(depth_mask)
+}
+
+/// first and last tile overlapped by the pixel rect `[min_px, max_px]` of a `size` viewport,
+/// `None` once the rect is off screen, matches `preprocess_splats`
+pub fn tile_bounds(min_px: Vec2, max_px: Vec2, size: UVec2) -> Option<(UVec2, UVec2)> {
+    if max_px.cmplt(Vec2::ZERO).any() || min_px.cmpge(size.as_vec2()).any() {
+        return None;
+    }
+
+    let last_tile = tile_grid(size) - UVec2::ONE;
+    Some((
+        (min_px.max(Vec2::ZERO).as_uvec2() / TILE_SIZE).min(last_tile),
+        (max_px.as_uvec2() / TILE_SIZE).min(last_tile),
+    ))
+}
+
+/// tile|depth entries reserved for `count` gaussians, grown to fit the `requested` entries
+/// read back from a previous frame and clamped to a single storage binding
+pub fn tile_entry_capacity(
+    count: usize,
+    requested: usize,
+    max_storage_buffer_binding_size: u64,
+) -> usize {
+    let max_entries = max_storage_buffer_binding_size as usize / std::mem::size_of::<SortEntry>();
+    let reserved = count * TILE_ENTRIES_PER_GAUSSIAN;
+    let capacity = if requested > reserved {
+        requested.next_power_of_two()
+    } else {
+        reserved
+    };
+    capacity.clamp(1, max_entries)
+}
+
+fn workgroup_grid(count: usize) -> (u32, u32) {
+    let workgroups = (count as u32).div_ceil(TILE_WORKGROUP_SIZE).max(1);
+    let x = workgroups.min(MAX_WORKGROUPS_PER_DIMENSION);
+    (x, workgroups.div_ceil(x))
+}
+
+/// gpu layout of `TileSplat` in `tile.wgsl`
+#[derive(ShaderType, Clone, Copy)]
+pub struct TileSplat {
+    pub center: Vec4,
+    pub ndc_to_uv: Vec4,
+    pub color: Vec4,
+    pub footprint: [Vec4; 3],
+}
+
+pub struct TileRasterizerPlugin<R: PlanarSync> {
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> Default for TileRasterizerPlugin<R> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: PlanarSync> Plugin for TileRasterizerPlugin<R>
+where
+    R::GpuPlanarType: GpuPlanarStorage,
+{
+    fn build(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .add_render_command::<Transparent3d, DrawTileComposite<R>>()
+                .add_systems(
+                    Render,
+                    (
+                        prepare_tile_buffers::<R>.in_set(RenderSystems::PrepareResources),
+                        queue_tile_bind_groups::<R>.in_set(RenderSystems::PrepareBindGroups),
+                        queue_tile_composites::<R>.in_set(RenderSystems::Queue),
+                        map_tile_entry_readbacks.in_set(RenderSystems::Cleanup),
+                    ),
+                )
+                .add_systems(
+                    Core3d,
+                    run_tile_rasterizer::<R>
+                        .after(RadixSortLabel)
+                        .before(Core3dSystems::Prepass),
+                );
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<TilePipeline<R>>()
+                .init_resource::<SpecializedRenderPipelines<TilePipeline<R>>>();
+        }
+    }
+}
+
+pub(crate) fn load_tile_shaders(app: &mut App) {
+    load_internal_asset!(app, TILE_SHADER_HANDLE, "tile.wgsl", Shader::from_wgsl);
+
+    load_internal_asset!(
+        app,
+        TILE_COMPOSITE_SHADER_HANDLE,
+        "tile_composite.wgsl",
+        Shader::from_wgsl
+    );
+}
+
+const READBACK_IDLE: u8 = 0;
+const READBACK_COPIED: u8 = 1;
+const READBACK_MAPPING: u8 = 2;
+
+/// `entry_count` of the last frame read back from the gpu, it keeps counting past the capacity
+#[derive(Default)]
+pub struct TileEntryReadback {
+    requested: AtomicUsize,
+    state: AtomicU8,
+}
+
+#[derive(Component)]
+pub struct GpuTileBuffers {
+    pub count: usize,
+    pub capacity: usize,
+    pub size: UVec2,
+    pub splat_buffer: Buffer,
+    pub entry_buffer: Buffer,
+    pub counter_buffer: Buffer,
+    pub readback_buffer: Buffer,
+    pub readback: Arc<TileEntryReadback>,
+    pub range_buffer: Buffer,
+    // the tile sort does not drive an indirect draw, radix writes its instance count here
+    pub draw_indirect_buffer: Buffer,
+    pub radix_buffers: GpuRadixBuffers,
+    pub color_view: TextureView,
+    pub depth_view: TextureView,
+}
+
+impl GpuTileBuffers {
+    pub fn new(count: usize, capacity: usize, size: UVec2, render_device: &RenderDevice) -> Self {
+        let splat_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("tile splat buffer"),
+            size: count.max(1) as u64 * TileSplat::min_size().get(),
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let entry_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("tile entry buffer"),
+            size: (capacity * std::mem::size_of::<SortEntry>()) as u64,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let counter_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("tile counter buffer"),
+            size: std::mem::size_of::<u32>() as u64,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let readback_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("tile counter readback buffer"),
+            size: std::mem::size_of::<u32>() as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let grid = tile_grid(size);
+        let range_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("tile range buffer"),
+            size: (grid.x * grid.y).max(1) as u64 * std::mem::size_of::<UVec2>() as u64,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let draw_indirect_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("tile sort draw indirect buffer"),
+            size: std::mem::size_of::<wgpu::util::DrawIndirectArgs>() as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let target = |label: &'static str, format: TextureFormat| {
+            render_device
+                .create_texture(&TextureDescriptor {
+                    label: Some(label),
+                    size: Extent3d {
+                        width: size.x.max(1),
+                        height: size.y.max(1),
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&TextureViewDescriptor::default())
+        };
+
+        GpuTileBuffers {
+            count,
+            capacity,
+            size,
+            splat_buffer,
+            entry_buffer,
+            counter_buffer,
+            readback_buffer,
+            readback: default(),
+            range_buffer,
+            draw_indirect_buffer,
+            radix_buffers: GpuRadixBuffers::new(capacity, render_device),
+            color_view: target("tile color texture", TextureFormat::Rgba16Float),
+            depth_view: target("tile depth texture", TextureFormat::R32Float),
+        }
+    }
+
+    /// tile entries requested by the last frame read back, including the dropped ones
+    pub fn requested_entries(&self) -> usize {
+        self.readback.requested.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Component)]
+pub struct TileBindGroup {
+    pub tile_bind_group: BindGroup,
+    pub radix_sort_bind_groups: [BindGroup; 8],
+    pub composite_bind_group: BindGroup,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct TileCompositeKey {
+    pub sample_count: u32,
+    pub hdr: bool,
+    pub write_depth: bool,
+}
+
+#[derive(Resource)]
+pub struct TilePipeline<R: PlanarSync> {
+    pub tile_layout: BindGroupLayout,
+    pub tile_sort_layout: BindGroupLayout,
+    pub composite_layout: BindGroupLayout,
+    compute_layout: Vec<BindGroupLayoutDescriptor>,
+    composite_pipeline_layout: Vec<BindGroupLayoutDescriptor>,
+    sort_defines: ShaderDefines,
+    sort_pipelines: [CachedComputePipelineId; RADIX_PIPELINE_COUNT],
+    variants: HashMap<CloudPipelineKey, [CachedComputePipelineId; TILE_PIPELINE_COUNT]>,
+    storage_format: PlanarStorageFormat,
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<R: PlanarSync> TilePipeline<R> {
+    // compute passes only depend on the splat appearance, not on the view target
+    fn key(&self, settings: &CloudSettings) -> CloudPipelineKey {
+        CloudPipelineKey {
+            aabb: settings.aabb,
+            visualize_bounding_box: settings.visualize_bounding_box,
+            opacity_adaptive_radius: settings.opacity_adaptive_radius,
+            draw_mode: settings.draw_mode,
+            gaussian_mode: settings.gaussian_mode,
+            rasterize_mode: settings.rasterize_mode,
+            storage_format: self.storage_format,
+            ..default()
+        }
+    }
+
+    fn variant(
+        &self,
+        settings: &CloudSettings,
+    ) -> Option<&[CachedComputePipelineId; TILE_PIPELINE_COUNT]> {
+        self.variants.get(&self.key(settings))
+    }
+
+    fn queue_variant(&mut self, pipeline_cache: &PipelineCache, settings: &CloudSettings) {
+        let key = self.key(settings);
+        if self.variants.contains_key(&key) {
+            return;
+        }
+
+        let shader_defs = shader_defs(key);
+        let entry_points = [
+            ("preprocess_splats", "tile_preprocess"),
+            ("identify_tile_ranges", "tile_ranges"),
+            ("rasterize_tiles", "tile_rasterize"),
+        ];
+
+        let pipelines = entry_points.map(|(entry_point, label)| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: self.compute_layout.clone(),
+                immediate_size: 0,
+                shader: TILE_SHADER_HANDLE,
+                shader_defs: shader_defs.clone(),
+                entry_point: Some(entry_point.into()),
+                zero_initialize_workgroup_memory: true,
+            })
+        });
+
+        self.variants.insert(key, pipelines);
+    }
+}
+
+fn storage_entry(binding: u32, read_only: bool, min_size: u64) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: BufferSize::new(min_size),
+        },
+        count: None,
+    }
+}
+
+fn storage_texture_entry(binding: u32, format: TextureFormat) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format,
+            view_dimension: TextureViewDimension::D2,
+        },
+        count: None,
+    }
+}
+
+fn composite_texture_entry(binding: u32) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: false },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+impl<R: PlanarSync> FromWorld for TilePipeline<R> {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+        let gaussian_cloud_pipeline = render_world.resource::<CloudPipeline<R>>();
+        let pipeline_cache = render_world.resource::<PipelineCache>();
+
+        let entry_size = std::mem::size_of::<SortEntry>() as u64;
+        let tile_layout_entries = [
+            BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: true,
+                    min_binding_size: BufferSize::new(entry_size),
+                },
+                count: None,
+            },
+            storage_entry(1, false, TileSplat::min_size().get()),
+            storage_entry(2, false, entry_size),
+            storage_entry(3, false, std::mem::size_of::<u32>() as u64),
+            storage_entry(4, false, std::mem::size_of::<UVec2>() as u64),
+            storage_texture_entry(5, TextureFormat::Rgba16Float),
+            storage_texture_entry(6, TextureFormat::R32Float),
+        ];
+        let tile_layout_desc = BindGroupLayoutDescriptor::new("tile_layout", &tile_layout_entries);
+        let tile_layout =
+            render_device.create_bind_group_layout(Some("tile_layout"), &tile_layout_entries);
+
+        // radix sort bindings with the gpu side entry count of `TILE_KEYS`
+        let mut tile_sort_layout_entries = radix_sort_layout_entries();
+        tile_sort_layout_entries.push(storage_entry(6, true, std::mem::size_of::<u32>() as u64));
+        let tile_sort_layout_desc =
+            BindGroupLayoutDescriptor::new("tile_sort_layout", &tile_sort_layout_entries);
+        let tile_sort_layout = render_device
+            .create_bind_group_layout(Some("tile_sort_layout"), &tile_sort_layout_entries);
+
+        let composite_layout_entries = [composite_texture_entry(0), composite_texture_entry(1)];
+        let composite_layout_desc =
+            BindGroupLayoutDescriptor::new("tile_composite_layout", &composite_layout_entries);
+        let composite_layout = render_device
+            .create_bind_group_layout(Some("tile_composite_layout"), &composite_layout_entries);
+
+        let compute_layout = vec![
+            gaussian_cloud_pipeline.compute_view_layout_desc.clone(),
+            gaussian_cloud_pipeline.gaussian_uniform_layout_desc.clone(),
+            gaussian_cloud_pipeline.gaussian_cloud_layout_desc.clone(),
+            tile_layout_desc,
+        ];
+
+        let sort_layout = vec![
+            gaussian_cloud_pipeline.compute_view_layout_desc.clone(),
+            gaussian_cloud_pipeline.gaussian_uniform_layout_desc.clone(),
+            gaussian_cloud_pipeline.gaussian_cloud_layout_desc.clone(),
+            tile_sort_layout_desc,
+        ];
+
+        let composite_pipeline_layout = vec![
+            gaussian_cloud_pipeline.view_layout_desc.clone(),
+            composite_layout_desc,
+        ];
+
+        // tile keys always use every bit
+        let sort_defines = ShaderDefines::for_radix_depth_bits(RadixSortDepthBits::Bits32);
+        let mut sort_shader_defs = shader_defs_with_defines(
+            CloudPipelineKey {
+                storage_format: gaussian_cloud_pipeline.storage_format,
+                ..default()
+            },
+            sort_defines,
+        );
+        sort_shader_defs.push("TILE_KEYS".into());
+
+        let sort_pipelines =
+            queue_radix_sort_pipelines(pipeline_cache, sort_layout, sort_shader_defs, "tile");
+
+        TilePipeline {
+            tile_layout,
+            tile_sort_layout,
+            composite_layout,
+            compute_layout,
+            composite_pipeline_layout,
+            sort_defines,
+            sort_pipelines,
+            variants: HashMap::new(),
+            storage_format: gaussian_cloud_pipeline.storage_format,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: PlanarSync> SpecializedRenderPipeline for TilePipeline<R> {
+    type Key = TileCompositeKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let format = if key.hdr {
+            TextureFormat::Rgba16Float
+        } else {
+            TextureFormat::Rgba8UnormSrgb
+        };
+
+        let (entry_point, targets) = if key.write_depth {
+            ("fs_depth", vec![])
+        } else {
+            (
+                "fs_composite",
+                vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            )
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("gaussian tile composite pipeline".into()),
+            layout: self.composite_pipeline_layout.clone(),
+            immediate_size: 0,
+            vertex: VertexState {
+                shader: TILE_COMPOSITE_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: Some("vs_composite".into()),
+                buffers: vec![],
+            },
+            fragment: Some(FragmentState {
+                shader: TILE_COMPOSITE_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: Some(entry_point.into()),
+                targets,
+            }),
+            primitive: PrimitiveState::default(),
+            // per pixel depth of the blended splats, tested like the instanced quads
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: Some(key.write_depth),
+                depth_compare: Some(CompareFunction::GreaterEqual),
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState {
+                count: key.sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            zero_initialize_workgroup_memory: true,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn prepare_tile_buffers<R: PlanarSync>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    gpu_planars: Res<RenderAssets<R::GpuPlanarType>>,
+    views: Query<&ExtractedView, With<GaussianCamera>>,
+    gaussian_clouds: Query<(
+        Entity,
+        &R::PlanarTypeHandle,
+        &CloudSettings,
+        Option<&GpuTileBuffers>,
+    )>,
+) where
+    R::GpuPlanarType: GpuPlanarStorage,
+{
+    // one target per cloud, shared by every view in turn
+    let size = views
+        .iter()
+        .fold(UVec2::ZERO, |size, view| size.max(view.viewport.zw()));
+
+    for (entity, cloud_handle, settings, tile_buffers) in &gaussian_clouds {
+        if settings.rasterizer != Rasterizer::Tile {
+            if tile_buffers.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<(GpuTileBuffers, TileBindGroup)>();
+            }
+            continue;
+        }
+
+        let Some(cloud) = gpu_planars.get(cloud_handle.handle()) else {
+            continue;
+        };
+
+        let requested = tile_buffers.map_or(0, GpuTileBuffers::requested_entries);
+        let capacity = tile_entry_capacity(
+            cloud.len(),
+            requested,
+            render_device.limits().max_storage_buffer_binding_size,
+        );
+        // grown capacities are kept, the entry count changes with every camera move
+        let capacity = tile_buffers
+            .filter(|buffers| buffers.count == cloud.len())
+            .map_or(capacity, |buffers| buffers.capacity.max(capacity));
+
+        if requested > capacity {
+            warn_once!(
+                "tile entries exceed the storage binding limit, {} of {} are dropped",
+                requested - capacity,
+                requested,
+            );
+        }
+
+        if tile_buffers.is_some_and(|buffers| {
+            buffers.count == cloud.len()
+                && buffers.capacity == capacity
+                && buffers.size.cmpge(size).all()
+        }) {
+            continue;
+        }
+
+        let size = tile_buffers.map_or(size, |buffers| buffers.size.max(size));
+        commands.entity(entity).insert(GpuTileBuffers::new(
+            cloud.len(),
+            capacity,
+            size,
+            &render_device,
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn queue_tile_bind_groups<R: PlanarSync>(
+    mut commands: Commands,
+    mut tile_pipeline: ResMut<TilePipeline<R>>,
+    pipeline_cache: Res<PipelineCache>,
+    render_device: Res<RenderDevice>,
+    gpu_planars: Res<RenderAssets<R::GpuPlanarType>>,
+    sorted_entries_res: Res<RenderAssets<GpuSortedEntry>>,
+    gaussian_clouds: Query<(
+        Entity,
+        &R::PlanarTypeHandle,
+        &SortedEntriesHandle,
+        &CloudSettings,
+        Ref<GpuTileBuffers>,
+        Option<&TileBindGroup>,
+    )>,
+) where
+    R::GpuPlanarType: GpuPlanarStorage,
+{
+    let sorted_entries_changed = sorted_entries_res.is_changed();
+
+    for (entity, cloud_handle, sorted_entries_handle, settings, tile_buffers, tile_bind_group) in
+        &gaussian_clouds
+    {
+        tile_pipeline.queue_variant(&pipeline_cache, settings);
+
+        if tile_bind_group.is_some() && !tile_buffers.is_changed() && !sorted_entries_changed {
+            continue;
+        }
+
+        let Some(cloud) = gpu_planars.get(cloud_handle.handle()) else {
+            continue;
+        };
+
+        let Some(sorted_entries) = sorted_entries_res.get(sorted_entries_handle) else {
+            continue;
+        };
+
+        let tile_bind_group = render_device.create_bind_group(
+            "tile_bind_group",
+            &tile_pipeline.tile_layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: &sorted_entries.sorted_entry_buffer,
+                        offset: 0,
+                        size: BufferSize::new(
+                            (cloud.len() * std::mem::size_of::<SortEntry>()) as u64,
+                        ),
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: tile_buffers.splat_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: tile_buffers.entry_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: tile_buffers.counter_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: tile_buffers.range_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: BindingResource::TextureView(&tile_buffers.color_view),
+                },
+                BindGroupEntry {
+                    binding: 6,
+                    resource: BindingResource::TextureView(&tile_buffers.depth_view),
+                },
+            ],
+        );
+
+        let radix_sort_bind_groups = radix_sort_bind_groups(
+            &render_device,
+            &tile_pipeline.tile_sort_layout,
+            &tile_buffers.radix_buffers,
+            &tile_buffers.entry_buffer,
+            &tile_buffers.draw_indirect_buffer,
+            tile_buffers.capacity,
+            &[BindGroupEntry {
+                binding: 6,
+                resource: tile_buffers.counter_buffer.as_entire_binding(),
+            }],
+        );
+
+        let composite_bind_group = render_device.create_bind_group(
+            "tile_composite_bind_group",
+            &tile_pipeline.composite_layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&tile_buffers.color_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&tile_buffers.depth_view),
+                },
+            ],
+        );
+
+        commands.entity(entity).insert(TileBindGroup {
+            tile_bind_group,
+            radix_sort_bind_groups,
+            composite_bind_group,
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_tile_composites<R: PlanarSync>(
+    transparent_3d_draw_functions: Res<DrawFunctions<Transparent3d>>,
+    tile_pipeline: Res<TilePipeline<R>>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<TilePipeline<R>>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    mut depth_phases: ResMut<SplatDepthPhases<R>>,
+    views: Query<(
+        &ExtractedView,
+        &GaussianCamera,
+        &RenderVisibleEntities,
+        Option<&Msaa>,
+    )>,
+    gaussian_clouds: Query<(&Aabb, &CloudSettings, &GlobalTransform), With<TileBindGroup>>,
+) {
+    if views.iter().any(|(_, camera, _, _)| camera.warmup) {
+        return;
+    }
+
+    let draw_composite = transparent_3d_draw_functions
+        .read()
+        .id::<DrawTileComposite<R>>();
+
+    for (view, _, visible_entities, msaa) in &views {
+        let Some(transparent_phase) = transparent_render_phases.get_mut(&view.retained_view_entity)
+        else {
+            continue;
+        };
+
+        let Some(visible_class) = visible_entities.get::<CloudVisibilityClass>() else {
+            continue;
+        };
+
+        for (render_entity, visible_entity) in &visible_class.entities_cpu_culling {
+            let Ok((aabb, settings, transform)) = gaussian_clouds.get(*render_entity) else {
+                continue;
+            };
+
+            if settings.rasterizer != Rasterizer::Tile {
+                continue;
+            }
+
+            let key = TileCompositeKey {
+                sample_count: msaa.cloned().unwrap_or_default().samples(),
+                hdr: view.target_format == TextureFormat::Rgba16Float,
+                write_depth: false,
+            };
+
+            let rangefinder = view.rangefinder3d();
+            let aabb_center = (aabb.min() + aabb.max()) / 2.0;
+            let aabb_size = aabb.max() - aabb.min();
+            let center = *transform
+                * GlobalTransform::from(
+                    Transform::from_translation(aabb_center.into()).with_scale(aabb_size.into()),
+                );
+            let distance = rangefinder.distance(&center.translation());
+
+            let item = |pipeline| Transparent3d {
+                sorting_info: TransparentSortingInfo3d::Sorted {
+                    mesh_center: center.translation(),
+                    depth_bias: 0.0,
+                },
+                entity: (*render_entity, *visible_entity),
+                draw_function: draw_composite,
+                distance,
+                pipeline,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: false,
+            };
+
+            // the composite depth passes its own occlusion test
+            if settings.depth_mode == DepthMode::AlphaThreshold {
+                let depth_key = TileCompositeKey {
+                    hdr: false,
+                    write_depth: true,
+                    ..key
+                };
+                let pipeline = pipelines.specialize(&pipeline_cache, &tile_pipeline, depth_key);
+
+                depth_phases
+                    .entry(view.retained_view_entity)
+                    .or_default()
+                    .add_transient(item(pipeline));
+            }
+
+            let pipeline = pipelines.specialize(&pipeline_cache, &tile_pipeline, key);
+            transparent_phase.add_transient(item(pipeline));
+        }
+    }
+}
+
+type TileViewQueryItem = (
+    &'static GaussianCamera,
+    &'static ExtractedView,
+    &'static SortTrigger,
+    &'static GaussianComputeViewBindGroup,
+    &'static ViewUniformOffset,
+    &'static PreviousViewUniformOffset,
+);
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn run_tile_rasterizer<R: PlanarSync>(
+    mut render_context: RenderContext,
+    pipeline_cache: Res<PipelineCache>,
+    tile_pipeline: Res<TilePipeline<R>>,
+    gaussian_uniforms: Res<GaussianUniformBindGroups>,
+    gpu_planars: Res<RenderAssets<R::GpuPlanarType>>,
+    view: ViewQuery<TileViewQueryItem>,
+    gaussian_clouds: Query<(
+        &'static <R as PlanarSync>::PlanarTypeHandle,
+        &'static PlanarStorageBindGroup<R>,
+        &'static DynamicUniformIndex<CloudUniform>,
+        &'static CloudSettings,
+        &'static GpuTileBuffers,
+        &'static TileBindGroup,
+    )>,
+) where
+    R::GpuPlanarType: GpuPlanarStorage,
+{
+    let (
+        _camera,
+        extracted_view,
+        sort_trigger,
+        view_bind_group,
+        view_uniform_offset,
+        previous_view_uniform_offset,
+    ) = view.into_inner();
+
+    let Some(uniform_bind_group) = gaussian_uniforms.base_bind_group.as_ref() else {
+        return;
+    };
+
+    if !compute_pipelines_loaded(&pipeline_cache, &tile_pipeline.sort_pipelines) {
+        return;
+    }
+
+    let viewport = extracted_view.viewport.zw();
+    let grid = tile_grid(viewport);
+
+    for (
+        cloud_handle,
+        cloud_bind_group,
+        cloud_uniform_index,
+        settings,
+        tile_buffers,
+        tile_bind_group,
+    ) in &gaussian_clouds
+    {
+        let Some(cloud) = gpu_planars.get(cloud_handle.handle()) else {
+            continue;
+        };
+
+        let Some(pipelines) = tile_pipeline.variant(settings) else {
+            continue;
+        };
+        if !compute_pipelines_loaded(&pipeline_cache, pipelines) {
+            continue;
+        }
+
+        // resized targets are prepared next frame
+        if grid.x == 0 || grid.y == 0 || viewport.cmpgt(tile_buffers.size).any() {
+            continue;
+        }
+
+        // TODO: align dynamic offset to `min_storage_buffer_offset_alignment`
+        let sorted_entries_offset = sort_trigger.camera_index as u32
+            * std::mem::size_of::<SortEntry>() as u32
+            * cloud.len() as u32;
+
+        let set_bind_groups = |pass: &mut ComputePass| {
+            pass.set_bind_group(
+                0,
+                &view_bind_group.value,
+                &[
+                    view_uniform_offset.offset,
+                    previous_view_uniform_offset.offset,
+                ],
+            );
+            pass.set_bind_group(1, uniform_bind_group, &[cloud_uniform_index.index()]);
+            pass.set_bind_group(2, &cloud_bind_group.bind_group, &[]);
+        };
+
+        let command_encoder = render_context.command_encoder();
+        command_encoder.clear_buffer(&tile_buffers.counter_buffer, 0, None);
+        command_encoder.clear_buffer(&tile_buffers.range_buffer, 0, None);
+
+        {
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor::default());
+
+            let preprocess = pipeline_cache
+                .get_compute_pipeline(pipelines[TILE_PIPELINE_PREPROCESS])
+                .unwrap();
+            pass.set_pipeline(preprocess);
+            set_bind_groups(&mut pass);
+            pass.set_bind_group(
+                3,
+                &tile_bind_group.tile_bind_group,
+                &[sorted_entries_offset],
+            );
+
+            let (x, y) = workgroup_grid(cloud.len());
+            pass.dispatch_workgroups(x, y, 1);
+        }
+
+        encode_radix_sort(
+            command_encoder,
+            &pipeline_cache,
+            &tile_pipeline.sort_pipelines,
+            tile_pipeline.sort_defines,
+            &tile_buffers.radix_buffers,
+            &tile_bind_group.radix_sort_bind_groups,
+            tile_buffers.capacity,
+            set_bind_groups,
+        );
+
+        {
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor::default());
+
+            set_bind_groups(&mut pass);
+            pass.set_bind_group(
+                3,
+                &tile_bind_group.tile_bind_group,
+                &[sorted_entries_offset],
+            );
+
+            let ranges = pipeline_cache
+                .get_compute_pipeline(pipelines[TILE_PIPELINE_RANGES])
+                .unwrap();
+            pass.set_pipeline(ranges);
+
+            let (x, y) = workgroup_grid(tile_buffers.capacity);
+            pass.dispatch_workgroups(x, y, 1);
+
+            let rasterize = pipeline_cache
+                .get_compute_pipeline(pipelines[TILE_PIPELINE_RASTERIZE])
+                .unwrap();
+            pass.set_pipeline(rasterize);
+            pass.dispatch_workgroups(grid.x, grid.y, 1);
+        }
+
+        // one readback in flight per cloud, mapped by `map_tile_entry_readbacks` after submit
+        if tile_buffers
+            .readback
+            .state
+            .compare_exchange(
+                READBACK_IDLE,
+                READBACK_COPIED,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            command_encoder.copy_buffer_to_buffer(
+                &tile_buffers.counter_buffer,
+                0,
+                &tile_buffers.readback_buffer,
+                0,
+                std::mem::size_of::<u32>() as u64,
+            );
+        }
+    }
+}
+
+// an overflowing `entry_count` grows the capacity in `prepare_tile_buffers`, the dropped
+// entries are rasterized again once the larger buffers are in place
+fn map_tile_entry_readbacks(tile_buffers: Query<&GpuTileBuffers>) {
+    for buffers in &tile_buffers {
+        if buffers
+            .readback
+            .state
+            .compare_exchange(
+                READBACK_COPIED,
+                READBACK_MAPPING,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            continue;
+        }
+
+        let readback = buffers.readback.clone();
+        let buffer = buffers.readback_buffer.clone();
+        buffers
+            .readback_buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| {
+                if result.is_ok() {
+                    let data = buffer.slice(..).get_mapped_range();
+                    if let Some(bytes) = data.get(..4).and_then(|bytes| bytes.try_into().ok()) {
+                        readback
+                            .requested
+                            .store(u32::from_le_bytes(bytes) as usize, Ordering::Relaxed);
+                    }
+                    drop(data);
+                    buffer.unmap();
+                }
+
+                readback.state.store(READBACK_IDLE, Ordering::Release);
+            });
+    }
+}
+
+#[allow(type_alias_bounds)]
+type DrawTileComposite<R: PlanarSync> = (
+    SetItemPipeline,
+    SetPreviousViewBindGroup<0>,
+    DrawTileOutput<R>,
+);
+
+pub struct DrawTileOutput<R: PlanarSync> {
+    phantom: std::marker::PhantomData<R>,
+}
+
+impl<P: PhaseItem, R: PlanarSync> RenderCommand<P> for DrawTileOutput<R> {
+    type Param = ();
+    type ViewQuery = ();
+    type ItemQuery = Read<TileBindGroup>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        entity: Option<ROQueryItem<'w, 'w, Self::ItemQuery>>,
+        _: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(tile_bind_group) = entity else {
+            return RenderCommandResult::Skip;
+        };
+
+        pass.set_bind_group(1, &tile_bind_group.composite_bind_group, &[]);
+        pass.draw(0..3, 0..1);
+
+        RenderCommandResult::Success
+    }
+}
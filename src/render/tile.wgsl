@@ -0,0 +1,606 @@
+#import bevy_gaussian_splatting::bindings::{
+    view,
+    gaussian_uniforms,
+    Entry,
+}
+#import bevy_gaussian_splatting::classification::class_to_rgb
+#import bevy_gaussian_splatting::depth::depth_to_rgb
+#import bevy_gaussian_splatting::optical_flow::{
+    calculate_motion_vector,
+    optical_flow_to_rgb,
+}
+#import bevy_gaussian_splatting::helpers::{
+    get_rotation_matrix,
+    get_scale_matrix,
+}
+#import bevy_gaussian_splatting::transform::{
+    world_to_clip,
+    in_frustum,
+}
+
+#ifdef GAUSSIAN_2D
+    #import bevy_gaussian_splatting::gaussian_2d::{
+        compute_cov2d_surfel,
+        get_bounding_box_cov2d,
+        surfel_fragment_power,
+    }
+#else ifdef GAUSSIAN_3D
+    #import bevy_gaussian_splatting::gaussian_3d::{
+        compute_cov2d_3dgs,
+    }
+    #import bevy_gaussian_splatting::helpers::{
+        get_bounding_box_clip,
+    }
+#else ifdef GAUSSIAN_4D
+    #import bevy_gaussian_splatting::gaussian_4d::{
+        conditional_cov3d,
+    }
+    #import bevy_gaussian_splatting::helpers::{
+        cov2d,
+        get_bounding_box_clip,
+    }
+#endif
+
+#ifdef PACKED
+    #ifdef PRECOMPUTE_COVARIANCE_3D
+        #import bevy_gaussian_splatting::packed::{
+            get_position,
+            get_color,
+            get_visibility,
+            get_opacity,
+            get_cov3d,
+        }
+    #else
+        #import bevy_gaussian_splatting::packed::{
+            get_position,
+            get_color,
+            get_visibility,
+            get_opacity,
+            get_rotation,
+            get_scale,
+        }
+    #endif
+#else ifdef BUFFER_STORAGE
+    #ifdef PRECOMPUTE_COVARIANCE_3D
+        #import bevy_gaussian_splatting::planar::{
+            get_position,
+            get_color,
+            get_visibility,
+            get_opacity,
+            get_cov3d,
+        }
+    #else
+        #import bevy_gaussian_splatting::planar::{
+            get_position,
+            get_color,
+            get_visibility,
+            get_opacity,
+            get_rotation,
+            get_scale,
+        }
+    #endif
+#endif
+
+const TILE_SIZE: u32 = 16u;
+const TILE_PIXELS: u32 = TILE_SIZE * TILE_SIZE;
+// 128 splats * 96 bytes stays below the 16KB workgroup storage minimum
+const TILE_BATCH_SIZE: u32 = 128u;
+const TRANSMITTANCE_CUTOFF: f32 = 1e-4;
+
+// screen space footprint of one gaussian, the quad of `vs_points` is `center + basis * uv`
+struct TileSplat {
+    // ndc xyz
+    center: vec4<f32>,
+    // columns of the inverse quad basis, maps ndc offsets to quad uv
+    ndc_to_uv: vec4<f32>,
+    color: vec4<f32>,
+    // conic: [conic.xyz, radius_px]
+    // surfel: [local_to_pixel[i], mean_2d.x | mean_2d.y | radius]
+    footprint: array<vec4<f32>, 3>,
+};
+
+struct TileCounters {
+    entry_count: atomic<u32>,
+};
+
+@group(3) @binding(0) var<storage, read> sorted_entries: array<Entry>;
+@group(3) @binding(1) var<storage, read_write> splats: array<TileSplat>;
+@group(3) @binding(2) var<storage, read_write> tile_entries: array<Entry>;
+@group(3) @binding(3) var<storage, read_write> tile_counters: TileCounters;
+@group(3) @binding(4) var<storage, read_write> tile_ranges: array<vec2<u32>>;
+@group(3) @binding(5) var tile_color: texture_storage_2d<rgba16float, write>;
+@group(3) @binding(6) var tile_depth: texture_storage_2d<r32float, write>;
+
+fn get_entry(index: u32) -> Entry {
+    return sorted_entries[index];
+}
+
+fn tile_grid() -> vec2<u32> {
+    let size = vec2<u32>(view.viewport.zw);
+    return (size + vec2<u32>(TILE_SIZE - 1u)) / TILE_SIZE;
+}
+
+// keys are `tile << depth_bits | depth`, the tile id takes as few bits as the grid allows
+fn depth_bits() -> u32 {
+    let grid = tile_grid();
+    let tile_count = max(grid.x * grid.y, 1u);
+    let tile_bits = max(32u - countLeadingZeros(tile_count - 1u), 1u);
+    return 32u - tile_bits;
+}
+
+// farthest any splat can be from the camera, bounded by the cpu side `cloud_bounding_sphere`
+fn max_cloud_distance() -> f32 {
+    return length(gaussian_uniforms.bounds.xyz - view.world_position) + gaussian_uniforms.bounds.w;
+}
+
+fn world_to_local_direction(ray_direction_world: vec3<f32>, transform: mat4x4<f32>) -> vec3<f32> {
+    let basis = mat3x3<f32>(
+        transform[0].xyz,
+        transform[1].xyz,
+        transform[2].xyz,
+    );
+    let basis_x = normalize(basis[0]);
+    let basis_y = normalize(basis[1]);
+    let basis_z = normalize(basis[2]);
+
+    let local = vec3<f32>(
+        dot(basis_x, ray_direction_world),
+        dot(basis_y, ray_direction_world),
+        dot(basis_z, ray_direction_world),
+    );
+
+    return normalize(local);
+}
+
+// mirrors `vs_points`, the quad corners are replaced by the quad basis
+@compute @workgroup_size(256)
+fn preprocess_splats(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let splat_index = global_id.x + global_id.y * num_workgroups.x * 256u;
+    if (splat_index >= gaussian_uniforms.count) {
+        return;
+    }
+
+    let position = vec4<f32>(get_position(splat_index), 1.0);
+
+    var transformed_position = (gaussian_uniforms.transform * position).xyz;
+    var previous_transformed_position = transformed_position;
+
+#ifdef DRAW_SELECTED
+    if (get_visibility(splat_index) < 0.5) {
+        return;
+    }
+#endif
+
+#ifdef GAUSSIAN_4D
+#else
+    let projected_position = world_to_clip(transformed_position);
+    if (!in_frustum(projected_position.xyz)) {
+        return;
+    }
+#endif
+
+    var opacity = get_opacity(splat_index);
+
+#ifdef OPACITY_ADAPTIVE_RADIUS
+    let cutoff = sqrt(max(9.0 + 2.0 * log(opacity), 0.000001));
+#else
+    let cutoff = 3.0;
+#endif
+
+    var splat: TileSplat;
+
+#ifdef GAUSSIAN_2D
+    let surfel = compute_cov2d_surfel(
+        transformed_position,
+        splat_index,
+        cutoff,
+    );
+
+    let bb_x = get_bounding_box_cov2d(surfel.extent, vec2<f32>(1.0, 0.0), cutoff);
+    let bb_y = get_bounding_box_cov2d(surfel.extent, vec2<f32>(0.0, 1.0), cutoff);
+
+    splat.footprint[0] = vec4<f32>(surfel.local_to_pixel[0], surfel.mean_2d.x);
+    splat.footprint[1] = vec4<f32>(surfel.local_to_pixel[1], surfel.mean_2d.y);
+    splat.footprint[2] = vec4<f32>(surfel.local_to_pixel[2], bb_x.z);
+#else
+    #ifdef GAUSSIAN_3D
+        let gaussian_cov2d = compute_cov2d_3dgs(
+            transformed_position,
+            splat_index,
+        );
+    #else ifdef GAUSSIAN_4D
+        let gaussian_4d = conditional_cov3d(
+            transformed_position,
+            splat_index,
+            gaussian_uniforms.time,
+        );
+
+        if !gaussian_4d.mask {
+            return;
+        }
+
+        let position_t = vec4<f32>(position.xyz + gaussian_4d.delta_mean, 1.0);
+        transformed_position = (gaussian_uniforms.transform * position_t).xyz;
+        let projected_position = world_to_clip(transformed_position);
+
+        if !in_frustum(projected_position.xyz) {
+            return;
+        }
+
+        opacity = opacity * gaussian_4d.opacity_modifier;
+
+        let gaussian_cov2d = cov2d(
+            transformed_position,
+            gaussian_4d.cov3d,
+        );
+    #endif
+
+    let bb_x = get_bounding_box_clip(gaussian_cov2d, vec2<f32>(1.0, 0.0), cutoff);
+    let bb_y = get_bounding_box_clip(gaussian_cov2d, vec2<f32>(0.0, 1.0), cutoff);
+
+    #ifdef USE_AABB
+        let det = gaussian_cov2d.x * gaussian_cov2d.z - gaussian_cov2d.y * gaussian_cov2d.y;
+        let det_inv = 1.0 / det;
+        let conic = vec3<f32>(
+            gaussian_cov2d.z * det_inv,
+            -gaussian_cov2d.y * det_inv,
+            gaussian_cov2d.x * det_inv
+        );
+        splat.footprint[0] = vec4<f32>(conic, bb_x.z);
+    #endif
+#endif
+
+    // quad basis in ndc, degenerate quads are never rasterized
+    let axis_x = bb_x.xy;
+    let axis_y = bb_y.xy;
+    let basis_det = axis_x.x * axis_y.y - axis_y.x * axis_x.y;
+    if (abs(basis_det) < 1e-12) {
+        return;
+    }
+
+    var rgb = vec3<f32>(0.0);
+
+// TODO: RASTERIZE_ACCELERATION
+#ifdef RASTERIZE_CLASSIFICATION
+    let ray_direction_world = normalize(transformed_position - view.world_position);
+    let ray_direction_local = world_to_local_direction(ray_direction_world, gaussian_uniforms.transform);
+
+    #ifdef GAUSSIAN_3D_STRUCTURE
+        rgb = get_color(splat_index, ray_direction_local);
+    #else ifdef GAUSSIAN_4D
+        rgb = get_color(splat_index, gaussian_4d.dir_t, ray_direction_local);
+    #endif
+
+    rgb = class_to_rgb(
+        get_visibility(splat_index),
+        rgb,
+    );
+#else ifdef RASTERIZE_DEPTH
+    // tile clouds skip the radix sort, the sorted entries hold no depth range
+    let depth = length(transformed_position - view.world_position);
+    rgb = depth_to_rgb(
+        depth,
+        0.0,
+        max_cloud_distance(),
+    );
+#else ifdef RASTERIZE_NORMAL
+    let R = get_rotation_matrix(get_rotation(splat_index));
+    let S = get_scale_matrix(get_scale(splat_index));
+    let T = mat3x3<f32>(
+        gaussian_uniforms.transform[0].xyz,
+        gaussian_uniforms.transform[1].xyz,
+        gaussian_uniforms.transform[2].xyz,
+    );
+    let L = T * S * R;
+
+    let local_normal = vec4<f32>(L[2], 0.0);
+    let world_normal = view.view_from_world * local_normal;
+
+    let t = normalize(world_normal);
+
+    rgb = vec3<f32>(
+        0.5 * (t.x + 1.0),
+        0.5 * (t.y + 1.0),
+        0.5 * (t.z + 1.0)
+    );
+#else ifdef RASTERIZE_OPTICAL_FLOW
+    let motion_vector = calculate_motion_vector(
+        transformed_position,
+        previous_transformed_position,
+    );
+
+    rgb = optical_flow_to_rgb(motion_vector);
+#else ifdef RASTERIZE_POSITION
+    rgb = (transformed_position - gaussian_uniforms.min.xyz) / (gaussian_uniforms.max.xyz - gaussian_uniforms.min.xyz);
+#else ifdef RASTERIZE_VELOCITY
+    let time_delta = 1e-3;
+    let future_gaussian_4d = conditional_cov3d(
+        transformed_position,
+        splat_index,
+        gaussian_uniforms.time + time_delta,
+    );
+    let position_delta = future_gaussian_4d.delta_mean - gaussian_4d.delta_mean;
+    let velocity = position_delta / time_delta;
+    let velocity_magnitude = length(velocity);
+    let velocity_normalized = normalize(velocity);
+
+    // TODO: magnitude normalization
+    let min_magnitude = 1.0;
+    let max_magnitude = 2.0;
+
+    let scaled_mag = clamp(
+        (velocity_magnitude - min_magnitude) / (max_magnitude - min_magnitude),
+        0.0,
+        1.0
+    );
+
+    if scaled_mag < 1e-2 {
+        opacity = 0.0;
+    }
+
+    let base_color = 0.5 * (velocity_normalized + vec3<f32>(1.0, 1.0, 1.0));
+    rgb = base_color * scaled_mag;
+#else ifdef RASTERIZE_COLOR
+    let ray_direction_world = normalize(transformed_position - view.world_position);
+    let ray_direction_local = world_to_local_direction(ray_direction_world, gaussian_uniforms.transform);
+
+    #ifdef GAUSSIAN_3D_STRUCTURE
+        rgb = get_color(splat_index, ray_direction_local);
+    #else ifdef GAUSSIAN_4D
+        rgb = get_color(splat_index, gaussian_4d.dir_t, ray_direction_local);
+    #endif
+#endif
+
+    splat.color = vec4<f32>(
+        rgb,
+        opacity * gaussian_uniforms.global_opacity,
+    );
+
+#ifdef HIGHLIGHT_SELECTED
+    if (get_visibility(splat_index) > 0.5) {
+        splat.color = vec4<f32>(0.3, 1.0, 0.1, 1.0);
+    }
+#endif
+
+    splat.center = vec4<f32>(projected_position.xyz, 0.0);
+    splat.ndc_to_uv = vec4<f32>(
+        axis_y.y, -axis_x.y,
+        -axis_y.x, axis_x.x,
+    ) / basis_det;
+    splats[splat_index] = splat;
+
+    // pixel rect of the quad, y grows downwards
+    let size = view.viewport.zw;
+    let extent = abs(axis_x) + abs(axis_y);
+    let min_px = vec2<f32>(
+        (projected_position.x - extent.x) * 0.5 + 0.5,
+        0.5 - (projected_position.y + extent.y) * 0.5,
+    ) * size;
+    let max_px = vec2<f32>(
+        (projected_position.x + extent.x) * 0.5 + 0.5,
+        0.5 - (projected_position.y - extent.y) * 0.5,
+    ) * size;
+
+    if (any(max_px < vec2<f32>(0.0)) || any(min_px >= size)) {
+        return;
+    }
+
+    let grid = tile_grid();
+    let tile_min = min(vec2<u32>(max(min_px, vec2<f32>(0.0))) / TILE_SIZE, grid - 1u);
+    let tile_max = min(vec2<u32>(max_px) / TILE_SIZE, grid - 1u);
+    let tile_span = tile_max - tile_min + 1u;
+
+    let key_depth_bits = depth_bits();
+    let depth_mask = (1u << key_depth_bits) - 1u;
+    let distance = length(transformed_position - view.world_position);
+    // `f32(depth_mask)` rounds up past 24 bits, the clamp keeps the depth out of the tile bits
+    let depth_key = min(
+        u32(clamp(distance / max(max_cloud_distance(), 1e-6), 0.0, 1.0) * f32(depth_mask)),
+        depth_mask,
+    );
+
+    // entries past the buffer capacity are dropped for this frame, `entry_count` keeps counting
+    // so the capacity grows to fit once it is read back, and clamps the sort until then
+    let capacity = arrayLength(&tile_entries);
+    var slot = atomicAdd(&tile_counters.entry_count, tile_span.x * tile_span.y);
+    for (var y = tile_min.y; y <= tile_max.y; y += 1u) {
+        for (var x = tile_min.x; x <= tile_max.x; x += 1u) {
+            if (slot >= capacity) {
+                return;
+            }
+
+            let tile = x + y * grid.x;
+            tile_entries[slot] = Entry((tile << key_depth_bits) | depth_key, splat_index);
+            slot += 1u;
+        }
+    }
+}
+
+// writes `[start, end)` of every tile present in the sorted entries
+@compute @workgroup_size(256)
+fn identify_tile_ranges(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let index = global_id.x + global_id.y * num_workgroups.x * 256u;
+    let count = min(atomicLoad(&tile_counters.entry_count), arrayLength(&tile_entries));
+    if (index >= count) {
+        return;
+    }
+
+    let key_depth_bits = depth_bits();
+    let tile = tile_entries[index].key >> key_depth_bits;
+
+    if (index == 0u || (tile_entries[index - 1u].key >> key_depth_bits) != tile) {
+        tile_ranges[tile].x = index;
+    }
+
+    if (index == count - 1u || (tile_entries[index + 1u].key >> key_depth_bits) != tile) {
+        tile_ranges[tile].y = index + 1u;
+    }
+}
+
+// premultiplied color and alpha of a splat at `ndc`, mirrors `fs_main`
+fn splat_fragment(splat: TileSplat, ndc: vec2<f32>) -> vec4<f32> {
+    let ndc_to_uv = mat2x2<f32>(splat.ndc_to_uv.xy, splat.ndc_to_uv.zw);
+    let uv = ndc_to_uv * (ndc - splat.center.xy);
+
+    // outside of the instanced quad
+    if (any(abs(uv) > vec2<f32>(1.0))) {
+        return vec4<f32>(0.0);
+    }
+
+#ifdef USE_AABB
+#ifdef GAUSSIAN_2D
+    let radius = splat.footprint[2].w;
+    let mean_2d = vec2<f32>(splat.footprint[0].w, splat.footprint[1].w);
+    let aspect = vec2<f32>(
+        1.0,
+        view.viewport.z / view.viewport.w,
+    );
+    let pixel_coord = uv * radius * aspect + mean_2d;
+
+    let power = surfel_fragment_power(
+        mat3x3<f32>(
+            splat.footprint[0].xyz,
+            splat.footprint[1].xyz,
+            splat.footprint[2].xyz,
+        ),
+        pixel_coord,
+        mean_2d,
+    );
+#else
+    let d = -uv * splat.footprint[0].w;
+    let conic = splat.footprint[0].xyz;
+    let power = -0.5 * (conic.x * d.x * d.x + conic.z * d.y * d.y) + conic.y * d.x * d.y;
+#endif
+
+    if (power > 0.0) {
+        return vec4<f32>(0.0);
+    }
+#endif
+
+#ifdef USE_OBB
+    let sigma = 1.0 / 3.0;
+    let sigma_squared = 2.0 * sigma * sigma;
+    let distance_squared = dot(uv, uv);
+
+    let power = -distance_squared / sigma_squared;
+
+    if (distance_squared > 3.0 * 3.0) {
+        return vec4<f32>(0.0);
+    }
+#endif
+
+#ifdef VISUALIZE_BOUNDING_BOX
+    let box_uv = uv * 0.5 + 0.5;
+    let edge_width = 0.08;
+    if (
+        (box_uv.x < edge_width || box_uv.x > 1.0 - edge_width) ||
+        (box_uv.y < edge_width || box_uv.y > 1.0 - edge_width)
+    ) {
+        return vec4<f32>(0.3, 1.0, 0.1, 1.0);
+    }
+#endif
+
+    let alpha = min(exp(power) * splat.color.a, 0.999);
+
+    return vec4<f32>(
+        splat.color.rgb * alpha,
+        alpha,
+    );
+}
+
+var<workgroup> batch_splats: array<TileSplat, TILE_BATCH_SIZE>;
+var<workgroup> tile_range: vec2<u32>;
+var<workgroup> done_count: atomic<u32>;
+var<workgroup> tile_done: bool;
+
+@compute @workgroup_size(16, 16)
+fn rasterize_tiles(
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32,
+) {
+    let size = vec2<u32>(view.viewport.zw);
+    let grid = tile_grid();
+    let pixel = workgroup_id.xy * TILE_SIZE + local_id.xy;
+    let inside = all(pixel < size);
+
+    if (local_index == 0u) {
+        tile_range = tile_ranges[workgroup_id.x + workgroup_id.y * grid.x];
+        atomicStore(&done_count, 0u);
+    }
+    let range = workgroupUniformLoad(&tile_range);
+
+    let ndc = vec2<f32>(
+        (f32(pixel.x) + 0.5) / f32(size.x) * 2.0 - 1.0,
+        1.0 - (f32(pixel.y) + 0.5) / f32(size.y) * 2.0,
+    );
+
+    var color = vec3<f32>(0.0);
+    var transmittance = 1.0;
+    // depth of the splat where the coverage reaches the depth alpha threshold
+    var depth = 0.0;
+    var depth_resolved = false;
+    // coverage weighted depth of every blended splat, stands in while the threshold is not reached
+    var weighted_depth = 0.0;
+
+    var done = !inside;
+    if (done) {
+        atomicAdd(&done_count, 1u);
+    }
+
+    for (var batch_start = range.x; batch_start < range.y; batch_start += TILE_BATCH_SIZE) {
+        workgroupBarrier();
+
+        let load_index = batch_start + local_index;
+        if (local_index < TILE_BATCH_SIZE && load_index < range.y) {
+            batch_splats[local_index] = splats[tile_entries[load_index].value];
+        }
+
+        if (local_index == 0u) {
+            tile_done = atomicLoad(&done_count) == TILE_PIXELS;
+        }
+        if (workgroupUniformLoad(&tile_done)) {
+            break;
+        }
+
+        let batch_count = min(TILE_BATCH_SIZE, range.y - batch_start);
+        for (var i = 0u; i < batch_count && !done; i += 1u) {
+            let splat = batch_splats[i];
+            let fragment = splat_fragment(splat, ndc);
+            if (fragment.a <= 0.0) {
+                continue;
+            }
+
+            weighted_depth += transmittance * fragment.a * splat.center.z;
+            color += transmittance * fragment.rgb;
+            transmittance *= 1.0 - fragment.a;
+
+            if (!depth_resolved && 1.0 - transmittance >= gaussian_uniforms.depth_alpha_threshold) {
+                depth = splat.center.z;
+                depth_resolved = true;
+            }
+
+            if (transmittance < TRANSMITTANCE_CUTOFF) {
+                done = true;
+                atomicAdd(&done_count, 1u);
+            }
+        }
+    }
+
+    if (inside) {
+        let alpha = 1.0 - transmittance;
+        if (!depth_resolved && alpha > 0.0) {
+            depth = weighted_depth / alpha;
+        }
+
+        textureStore(tile_color, pixel, vec4<f32>(color, alpha));
+        // unresolved depths are stored negated, they composite but never occlude
+        textureStore(tile_depth, pixel, vec4<f32>(select(-depth, depth, depth_resolved), 0.0, 0.0, 0.0));
+    }
+}
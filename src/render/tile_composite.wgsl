@@ -0,0 +1,63 @@
+#import bevy_render::view::View
+
+@group(0) @binding(0) var<uniform> view: View;
+
+@group(1) @binding(0) var tile_color: texture_2d<f32>;
+@group(1) @binding(1) var tile_depth: texture_2d<f32>;
+
+struct CompositeVertexOutput {
+    @builtin(position) position: vec4<f32>,
+};
+
+struct CompositeFragmentOutput {
+    @location(0) color: vec4<f32>,
+    @builtin(frag_depth) depth: f32,
+};
+
+@vertex
+fn vs_composite(
+    @builtin(vertex_index) vertex_index: u32,
+) -> CompositeVertexOutput {
+    var output: CompositeVertexOutput;
+
+    // fullscreen triangle
+    let uv = vec2<f32>(
+        f32((vertex_index << 1u) & 2u),
+        f32(vertex_index & 2u),
+    );
+    output.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+
+    return output;
+}
+
+@fragment
+fn fs_composite(input: CompositeVertexOutput) -> CompositeFragmentOutput {
+    var output: CompositeFragmentOutput;
+
+    let pixel = vec2<i32>(floor(input.position.xy - view.viewport.xy));
+    let color = textureLoad(tile_color, pixel, 0);
+
+    if (color.a <= 0.0) {
+        discard;
+    }
+
+    // tile colors are premultiplied and blended front-to-back, the pixel is tested at the depth
+    // where its coverage reaches the alpha threshold, or at its coverage weighted depth below it
+    output.color = color;
+    output.depth = abs(textureLoad(tile_depth, pixel, 0).r);
+
+    return output;
+}
+
+@fragment
+fn fs_depth(input: CompositeVertexOutput) -> @builtin(frag_depth) f32 {
+    let pixel = vec2<i32>(floor(input.position.xy - view.viewport.xy));
+    let depth = textureLoad(tile_depth, pixel, 0).r;
+
+    // coverage stayed below the depth alpha threshold
+    if (depth <= 0.0) {
+        discard;
+    }
+
+    return depth;
+}
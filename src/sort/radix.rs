@@ -2,6 +2,7 @@
 use std::any::TypeId;
 use std::collections::HashMap;
 
+use bevy::shader::ShaderDefVal;
 use bevy::{
     asset::{load_internal_asset, uuid_handle},
     core_pipeline::{Core3d, Core3dSystems, prepass::PreviousViewUniformOffset},
@@ -14,8 +15,8 @@ use bevy::{
             BindGroup, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
             BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBinding,
             BufferBindingType, BufferDescriptor, BufferInitDescriptor, BufferSize, BufferUsages,
-            CachedComputePipelineId, CachedPipelineState, ComputePassDescriptor,
-            ComputePipelineDescriptor, PipelineCache, ShaderStages,
+            CachedComputePipelineId, CachedPipelineState, CommandEncoder, ComputePass,
+            ComputePassDescriptor, ComputePipelineDescriptor, PipelineCache, ShaderStages,
         },
         renderer::{RenderContext, RenderDevice, ViewQuery},
         view::ViewUniformOffset,
@@ -28,7 +29,8 @@ use static_assertions::assert_cfg;
 use crate::{gaussian::formats::planar_3d::PlanarGaussian3d, morph::interpolate::InterpolateLabel};
 
 use crate::{
-    CloudSettings, GaussianCamera, RadixSortDepthBits,
+    CloudSettings, GaussianCamera, RadixSortDepthBits, Rasterizer,
+    gaussian::interface::PlanarStorageFormat,
     render::{
         CloudPipeline, CloudPipelineKey, CloudUniform, GaussianUniformBindGroups, ShaderDefines,
         shader_defs_with_defines,
@@ -50,7 +52,7 @@ const RADIX_PIPELINE_B: usize = 2;
 const RADIX_PIPELINE_C_COUNT: usize = 3;
 const RADIX_PIPELINE_C_SCAN: usize = 4;
 const RADIX_PIPELINE_C_SCATTER: usize = 5;
-const RADIX_PIPELINE_COUNT: usize = 6;
+pub(crate) const RADIX_PIPELINE_COUNT: usize = 6;
 const RADIX_DEPTH_BITS_VARIANT_COUNT: usize = 3;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
@@ -150,6 +152,11 @@ pub struct GpuRadixBuffers {
 }
 
 impl GpuRadixBuffers {
+    /// entries the ping-pong buffer holds, a grown cloud needs new buffers
+    pub fn capacity(&self) -> usize {
+        self.entry_buffer_b.size() as usize / std::mem::size_of::<SortEntry>()
+    }
+
     pub fn new(count: usize, render_device: &RenderDevice) -> Self {
         let sorting_global_buffer = render_device.create_buffer(&BufferDescriptor {
             label: Some("sorting global buffer"),
@@ -193,14 +200,18 @@ impl GpuRadixBuffers {
     }
 }
 
-fn update_sort_buffers<R: PlanarSync>(
+pub(crate) fn update_sort_buffers<R: PlanarSync>(
     gpu_gaussian_clouds: Res<RenderAssets<R::GpuPlanarType>>,
     mut sort_buffers: ResMut<RadixSortBuffers<R>>,
     render_device: Res<RenderDevice>,
 ) {
     for (asset_id, cloud) in gpu_gaussian_clouds.iter() {
-        // TODO: handle cloud resize operations and resolve leaked stale buffers
-        if sort_buffers.asset_map.contains_key(&asset_id) {
+        // TODO: resolve leaked stale buffers
+        if sort_buffers
+            .asset_map
+            .get(&asset_id)
+            .is_some_and(|buffers| buffers.capacity() >= cloud.len())
+        {
             continue;
         }
 
@@ -214,6 +225,7 @@ pub struct RadixSortPipeline<R: PlanarSync> {
     pub radix_sort_layout: BindGroupLayout,
     pub variants: [Option<RadixSortPipelineVariant>; RADIX_DEPTH_BITS_VARIANT_COUNT],
     sorting_layout: Vec<BindGroupLayoutDescriptor>,
+    storage_format: PlanarStorageFormat,
     phantom: std::marker::PhantomData<R>,
 }
 
@@ -225,15 +237,22 @@ pub struct RadixSortPipelineVariant {
 
 impl RadixSortPipelineVariant {
     fn is_loaded(&self, pipeline_cache: &PipelineCache) -> bool {
-        self.radix_sort_pipelines.iter().all(|sort_pipeline| {
-            matches!(
-                pipeline_cache.get_compute_pipeline_state(*sort_pipeline),
-                CachedPipelineState::Ok(_)
-            )
-        })
+        compute_pipelines_loaded(pipeline_cache, &self.radix_sort_pipelines)
     }
 }
 
+pub(crate) fn compute_pipelines_loaded(
+    pipeline_cache: &PipelineCache,
+    pipelines: &[CachedComputePipelineId],
+) -> bool {
+    pipelines.iter().all(|pipeline| {
+        matches!(
+            pipeline_cache.get_compute_pipeline_state(*pipeline),
+            CachedPipelineState::Ok(_)
+        )
+    })
+}
+
 impl<R: PlanarSync> RadixSortPipeline<R> {
     fn variant(
         &self,
@@ -255,6 +274,7 @@ impl<R: PlanarSync> RadixSortPipeline<R> {
         self.variants[index] = Some(queue_radix_sort_pipeline_variant(
             pipeline_cache,
             self.sorting_layout.clone(),
+            self.storage_format,
             radix_sort_depth_bits,
         ));
     }
@@ -265,80 +285,7 @@ impl<R: PlanarSync> FromWorld for RadixSortPipeline<R> {
         let render_device = render_world.resource::<RenderDevice>();
         let gaussian_cloud_pipeline = render_world.resource::<CloudPipeline<R>>();
 
-        let sorting_buffer_entry = BindGroupLayoutEntry {
-            binding: 1,
-            visibility: ShaderStages::COMPUTE,
-            ty: BindingType::Buffer {
-                ty: BufferBindingType::Storage { read_only: false },
-                has_dynamic_offset: false,
-                min_binding_size: BufferSize::new(
-                    ShaderDefines::default().sorting_buffer_size as u64,
-                ),
-            },
-            count: None,
-        };
-
-        let sorting_status_counters_buffer_entry = BindGroupLayoutEntry {
-            binding: 2,
-            visibility: ShaderStages::COMPUTE,
-            ty: BindingType::Buffer {
-                ty: BufferBindingType::Storage { read_only: false },
-                has_dynamic_offset: false,
-                min_binding_size: BufferSize::new(
-                    ShaderDefines::default().sorting_status_counters_buffer_size(1) as u64,
-                ),
-            },
-            count: None,
-        };
-
-        let draw_indirect_buffer_entry = BindGroupLayoutEntry {
-            binding: 3,
-            visibility: ShaderStages::COMPUTE,
-            ty: BindingType::Buffer {
-                ty: BufferBindingType::Storage { read_only: false },
-                has_dynamic_offset: false,
-                min_binding_size: BufferSize::new(
-                    std::mem::size_of::<wgpu::util::DrawIndirectArgs>() as u64,
-                ),
-            },
-            count: None,
-        };
-
-        let radix_sort_layout_entries = [
-            BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::COMPUTE,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: BufferSize::new(std::mem::size_of::<u32>() as u64),
-                },
-                count: None,
-            },
-            sorting_buffer_entry,
-            sorting_status_counters_buffer_entry,
-            draw_indirect_buffer_entry,
-            BindGroupLayoutEntry {
-                binding: 4,
-                visibility: ShaderStages::COMPUTE,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Storage { read_only: false },
-                    has_dynamic_offset: false,
-                    min_binding_size: BufferSize::new(std::mem::size_of::<SortEntry>() as u64),
-                },
-                count: None,
-            },
-            BindGroupLayoutEntry {
-                binding: 5,
-                visibility: ShaderStages::COMPUTE,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Storage { read_only: false },
-                    has_dynamic_offset: false,
-                    min_binding_size: BufferSize::new(std::mem::size_of::<SortEntry>() as u64),
-                },
-                count: None,
-            },
-        ];
+        let radix_sort_layout_entries = radix_sort_layout_entries();
         let radix_sort_layout_desc =
             BindGroupLayoutDescriptor::new("radix_sort_layout", &radix_sort_layout_entries);
         let radix_sort_layout = render_device
@@ -357,93 +304,140 @@ impl<R: PlanarSync> FromWorld for RadixSortPipeline<R> {
             radix_sort_layout,
             variants,
             sorting_layout,
+            storage_format: gaussian_cloud_pipeline.storage_format,
             phantom: std::marker::PhantomData,
         }
     }
 }
 
+/// bindings of the sort bind group at `@group(3)` in `radix.wgsl`
+pub(crate) fn radix_sort_layout_entries() -> Vec<BindGroupLayoutEntry> {
+    let sorting_buffer_entry = BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: BufferSize::new(ShaderDefines::default().sorting_buffer_size as u64),
+        },
+        count: None,
+    };
+
+    let sorting_status_counters_buffer_entry = BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: BufferSize::new(
+                ShaderDefines::default().sorting_status_counters_buffer_size(1) as u64,
+            ),
+        },
+        count: None,
+    };
+
+    let draw_indirect_buffer_entry = BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: BufferSize::new(
+                std::mem::size_of::<wgpu::util::DrawIndirectArgs>() as u64
+            ),
+        },
+        count: None,
+    };
+
+    vec![
+        BindGroupLayoutEntry {
+            binding: 0,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: BufferSize::new(std::mem::size_of::<u32>() as u64),
+            },
+            count: None,
+        },
+        sorting_buffer_entry,
+        sorting_status_counters_buffer_entry,
+        draw_indirect_buffer_entry,
+        BindGroupLayoutEntry {
+            binding: 4,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: BufferSize::new(std::mem::size_of::<SortEntry>() as u64),
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: 5,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: BufferSize::new(std::mem::size_of::<SortEntry>() as u64),
+            },
+            count: None,
+        },
+    ]
+}
+
 fn queue_radix_sort_pipeline_variant(
     pipeline_cache: &PipelineCache,
     sorting_layout: Vec<BindGroupLayoutDescriptor>,
+    storage_format: PlanarStorageFormat,
     radix_sort_depth_bits: RadixSortDepthBits,
 ) -> RadixSortPipelineVariant {
     let shader_defines = ShaderDefines::for_radix_depth_bits(radix_sort_depth_bits);
-    let shader_defs = shader_defs_with_defines(CloudPipelineKey::default(), shader_defines);
-    let label_suffix = radix_sort_depth_bits.bits();
-
-    let radix_reset = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-        label: Some(format!("radix_sort_reset_{label_suffix}bit").into()),
-        layout: sorting_layout.clone(),
-        immediate_size: 0,
-        shader: RADIX_SHADER_HANDLE,
-        shader_defs: shader_defs.clone(),
-        entry_point: Some("radix_reset".into()),
-        zero_initialize_workgroup_memory: true,
-    });
-
-    let radix_sort_a = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-        label: Some(format!("radix_sort_a_{label_suffix}bit").into()),
-        layout: sorting_layout.clone(),
-        immediate_size: 0,
-        shader: RADIX_SHADER_HANDLE,
-        shader_defs: shader_defs.clone(),
-        entry_point: Some("radix_sort_a".into()),
-        zero_initialize_workgroup_memory: true,
-    });
-
-    let radix_sort_b = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-        label: Some(format!("radix_sort_b_{label_suffix}bit").into()),
-        layout: sorting_layout.clone(),
-        immediate_size: 0,
-        shader: RADIX_SHADER_HANDLE,
-        shader_defs: shader_defs.clone(),
-        entry_point: Some("radix_sort_b".into()),
-        zero_initialize_workgroup_memory: true,
-    });
-
-    let radix_sort_c_count = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-        label: Some(format!("radix_sort_c_count_tiles_{label_suffix}bit").into()),
-        layout: sorting_layout.clone(),
-        immediate_size: 0,
-        shader: RADIX_SHADER_HANDLE,
-        shader_defs: shader_defs.clone(),
-        entry_point: Some("radix_sort_c_count_tiles".into()),
-        zero_initialize_workgroup_memory: true,
-    });
-
-    let radix_sort_c_scan = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-        label: Some(format!("radix_sort_c_scan_tiles_{label_suffix}bit").into()),
-        layout: sorting_layout.clone(),
-        immediate_size: 0,
-        shader: RADIX_SHADER_HANDLE,
-        shader_defs: shader_defs.clone(),
-        entry_point: Some("radix_sort_c_scan_tiles".into()),
-        zero_initialize_workgroup_memory: true,
-    });
-
-    let radix_sort_c_scatter = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-        label: Some(format!("radix_sort_c_scatter_{label_suffix}bit").into()),
-        layout: sorting_layout,
-        immediate_size: 0,
-        shader: RADIX_SHADER_HANDLE,
-        shader_defs,
-        entry_point: Some("radix_sort_c_scatter".into()),
-        zero_initialize_workgroup_memory: true,
-    });
+    let key = CloudPipelineKey {
+        storage_format,
+        ..Default::default()
+    };
+    let shader_defs = shader_defs_with_defines(key, shader_defines);
 
     RadixSortPipelineVariant {
         shader_defines,
-        radix_sort_pipelines: [
-            radix_reset,
-            radix_sort_a,
-            radix_sort_b,
-            radix_sort_c_count,
-            radix_sort_c_scan,
-            radix_sort_c_scatter,
-        ],
+        radix_sort_pipelines: queue_radix_sort_pipelines(
+            pipeline_cache,
+            sorting_layout,
+            shader_defs,
+            &format!("{}bit", radix_sort_depth_bits.bits()),
+        ),
     }
 }
 
+pub(crate) fn queue_radix_sort_pipelines(
+    pipeline_cache: &PipelineCache,
+    sorting_layout: Vec<BindGroupLayoutDescriptor>,
+    shader_defs: Vec<ShaderDefVal>,
+    label_suffix: &str,
+) -> [CachedComputePipelineId; RADIX_PIPELINE_COUNT] {
+    let entry_points = [
+        ("radix_reset", "radix_sort_reset"),
+        ("radix_sort_a", "radix_sort_a"),
+        ("radix_sort_b", "radix_sort_b"),
+        ("radix_sort_c_count_tiles", "radix_sort_c_count_tiles"),
+        ("radix_sort_c_scan_tiles", "radix_sort_c_scan_tiles"),
+        ("radix_sort_c_scatter", "radix_sort_c_scatter"),
+    ];
+
+    entry_points.map(|(entry_point, label)| {
+        pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some(format!("{label}_{label_suffix}").into()),
+            layout: sorting_layout.clone(),
+            immediate_size: 0,
+            shader: RADIX_SHADER_HANDLE,
+            shader_defs: shader_defs.clone(),
+            entry_point: Some(entry_point.into()),
+            zero_initialize_workgroup_memory: true,
+        })
+    })
+}
+
 #[derive(Component)]
 pub struct RadixBindGroup {
     // For each digit pass idx in 0..RADIX_DIGIT_PLACES, we create 2 bind groups (parity 0/1):
@@ -458,6 +452,15 @@ type RadixViewQueryItem = (
     &'static PreviousViewUniformOffset,
 );
 
+#[allow(type_alias_bounds)]
+type RadixCloudQueryItem<R: PlanarSync> = (
+    &'static <R as PlanarSync>::PlanarTypeHandle,
+    &'static PlanarStorageBindGroup<R>,
+    &'static RadixBindGroup,
+    &'static DynamicUniformIndex<CloudUniform>,
+    &'static CloudSettings,
+);
+
 #[allow(clippy::too_many_arguments)]
 pub fn queue_radix_bind_group<R: PlanarSync>(
     mut commands: Commands,
@@ -517,94 +520,15 @@ pub fn queue_radix_bind_group<R: PlanarSync>(
         let sorted_entries = sorted_entries_res.get(sorted_entries_handle).unwrap();
         let sorting_assets = &sort_buffers.asset_map[&cloud_handle.handle().id()];
 
-        let sorting_global_entry = BindGroupEntry {
-            binding: 1,
-            resource: BindingResource::Buffer(BufferBinding {
-                buffer: &sorting_assets.sorting_global_buffer,
-                offset: 0,
-                size: BufferSize::new(sorting_assets.sorting_global_buffer.size()),
-            }),
-        };
-
-        let sorting_status_counters_entry = BindGroupEntry {
-            binding: 2,
-            resource: BindingResource::Buffer(BufferBinding {
-                buffer: &sorting_assets.sorting_status_counter_buffer,
-                offset: 0,
-                size: BufferSize::new(sorting_assets.sorting_status_counter_buffer.size()),
-            }),
-        };
-
-        let draw_indirect_entry = BindGroupEntry {
-            binding: 3,
-            resource: BindingResource::Buffer(BufferBinding {
-                buffer: cloud.draw_indirect_buffer(),
-                offset: 0,
-                size: BufferSize::new(cloud.draw_indirect_buffer().size()),
-            }),
-        };
-
-        let radix_sort_bind_groups: [BindGroup; 8] = {
-            let mut groups: Vec<BindGroup> = Vec::with_capacity(8);
-            for pass_idx in 0..4 {
-                for parity in 0..=1 {
-                    let (input_buf, output_buf) = if parity == 0 {
-                        (
-                            &sorted_entries.sorted_entry_buffer,
-                            &sorting_assets.entry_buffer_b,
-                        )
-                    } else {
-                        (
-                            &sorting_assets.entry_buffer_b,
-                            &sorted_entries.sorted_entry_buffer,
-                        )
-                    };
-
-                    let group = render_device.create_bind_group(
-                        format!("radix_sort_bind_group pass={pass_idx} parity={parity}").as_str(),
-                        &radix_pipeline.radix_sort_layout,
-                        &[
-                            // sorting_pass_index (u32) == pass_idx regardless of parity
-                            BindGroupEntry {
-                                binding: 0,
-                                resource: BindingResource::Buffer(BufferBinding {
-                                    buffer: &sorting_assets.sorting_pass_buffers[pass_idx],
-                                    offset: 0,
-                                    size: BufferSize::new(std::mem::size_of::<u32>() as u64),
-                                }),
-                            },
-                            sorting_global_entry.clone(),
-                            sorting_status_counters_entry.clone(),
-                            draw_indirect_entry.clone(),
-                            // input_entries
-                            BindGroupEntry {
-                                binding: 4,
-                                resource: BindingResource::Buffer(BufferBinding {
-                                    buffer: input_buf,
-                                    offset: 0,
-                                    size: BufferSize::new(
-                                        (cloud.len() * std::mem::size_of::<SortEntry>()) as u64,
-                                    ),
-                                }),
-                            },
-                            // output_entries
-                            BindGroupEntry {
-                                binding: 5,
-                                resource: BindingResource::Buffer(BufferBinding {
-                                    buffer: output_buf,
-                                    offset: 0,
-                                    size: BufferSize::new(
-                                        (cloud.len() * std::mem::size_of::<SortEntry>()) as u64,
-                                    ),
-                                }),
-                            },
-                        ],
-                    );
-                    groups.push(group);
-                }
-            }
-            groups.try_into().unwrap()
-        };
+        let radix_sort_bind_groups = radix_sort_bind_groups(
+            &render_device,
+            &radix_pipeline.radix_sort_layout,
+            sorting_assets,
+            &sorted_entries.sorted_entry_buffer,
+            cloud.draw_indirect_buffer(),
+            cloud.len(),
+            &[],
+        );
 
         commands.entity(entity).insert(RadixBindGroup {
             radix_sort_bind_groups,
@@ -612,6 +536,99 @@ pub fn queue_radix_bind_group<R: PlanarSync>(
     }
 }
 
+/// one bind group per digit pass and parity, see `RadixBindGroup`
+pub(crate) fn radix_sort_bind_groups(
+    render_device: &RenderDevice,
+    layout: &BindGroupLayout,
+    sorting_assets: &GpuRadixBuffers,
+    entry_buffer: &Buffer,
+    draw_indirect_buffer: &Buffer,
+    len: usize,
+    extra_entries: &[BindGroupEntry],
+) -> [BindGroup; 8] {
+    let sorting_global_entry = BindGroupEntry {
+        binding: 1,
+        resource: BindingResource::Buffer(BufferBinding {
+            buffer: &sorting_assets.sorting_global_buffer,
+            offset: 0,
+            size: BufferSize::new(sorting_assets.sorting_global_buffer.size()),
+        }),
+    };
+
+    let sorting_status_counters_entry = BindGroupEntry {
+        binding: 2,
+        resource: BindingResource::Buffer(BufferBinding {
+            buffer: &sorting_assets.sorting_status_counter_buffer,
+            offset: 0,
+            size: BufferSize::new(sorting_assets.sorting_status_counter_buffer.size()),
+        }),
+    };
+
+    let draw_indirect_entry = BindGroupEntry {
+        binding: 3,
+        resource: BindingResource::Buffer(BufferBinding {
+            buffer: draw_indirect_buffer,
+            offset: 0,
+            size: BufferSize::new(draw_indirect_buffer.size()),
+        }),
+    };
+
+    let entries_size = BufferSize::new((len * std::mem::size_of::<SortEntry>()) as u64);
+
+    let mut groups: Vec<BindGroup> = Vec::with_capacity(8);
+    for pass_idx in 0..4 {
+        for parity in 0..=1 {
+            let (input_buf, output_buf) = if parity == 0 {
+                (entry_buffer, &sorting_assets.entry_buffer_b)
+            } else {
+                (&sorting_assets.entry_buffer_b, entry_buffer)
+            };
+
+            let mut entries = vec![
+                // sorting_pass_index (u32) == pass_idx regardless of parity
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: &sorting_assets.sorting_pass_buffers[pass_idx],
+                        offset: 0,
+                        size: BufferSize::new(std::mem::size_of::<u32>() as u64),
+                    }),
+                },
+                sorting_global_entry.clone(),
+                sorting_status_counters_entry.clone(),
+                draw_indirect_entry.clone(),
+                // input_entries
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: input_buf,
+                        offset: 0,
+                        size: entries_size,
+                    }),
+                },
+                // output_entries
+                BindGroupEntry {
+                    binding: 5,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: output_buf,
+                        offset: 0,
+                        size: entries_size,
+                    }),
+                },
+            ];
+            entries.extend_from_slice(extra_entries);
+
+            groups.push(render_device.create_bind_group(
+                format!("radix_sort_bind_group pass={pass_idx} parity={parity}").as_str(),
+                layout,
+                &entries,
+            ));
+        }
+    }
+
+    groups.try_into().unwrap()
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_radix_sort<R: PlanarSync>(
     mut render_context: RenderContext,
@@ -621,13 +638,7 @@ fn run_radix_sort<R: PlanarSync>(
     sort_buffers: Res<RadixSortBuffers<R>>,
     gpu_planars: Res<RenderAssets<R::GpuPlanarType>>,
     view_bind_group: ViewQuery<RadixViewQueryItem>,
-    gaussian_clouds: Query<(
-        &'static <R as PlanarSync>::PlanarTypeHandle,
-        &'static PlanarStorageBindGroup<R>,
-        &'static RadixBindGroup,
-        &'static DynamicUniformIndex<CloudUniform>,
-        &'static CloudSettings,
-    )>,
+    gaussian_clouds: Query<RadixCloudQueryItem<R>>,
 ) where
     R::GpuPlanarType: GpuPlanarStorage,
 {
@@ -642,6 +653,15 @@ fn run_radix_sort<R: PlanarSync>(
     for (cloud_handle, cloud_bind_group, radix_bind_group, cloud_uniform_index, cloud_settings) in
         &gaussian_clouds
     {
+        // tile clouds sort their own tile keys in `run_tile_rasterizer`, weighted blended clouds are
+        // order independent and draw every instance of the count written at upload
+        if matches!(
+            cloud_settings.rasterizer,
+            Rasterizer::Tile | Rasterizer::WeightedBlended
+        ) {
+            continue;
+        }
+
         let Some(cloud) = gpu_planars.get(cloud_handle.handle()) else {
             continue;
         };
@@ -658,99 +678,110 @@ fn run_radix_sort<R: PlanarSync>(
         }
 
         let command_encoder = render_context.command_encoder();
-        let shader_defines = pipeline_variant.shader_defines;
-        let radix_digit_places = shader_defines.radix_digit_places;
-        let initial_parity = shader_defines.radix_initial_parity();
-        let workgroup_entries_a = shader_defines.workgroup_entries_a;
-        let workgroup_entries_c = shader_defines.workgroup_entries_c;
-        let tile_workgroups = (cloud.len() as u32).div_ceil(workgroup_entries_c);
-
-        command_encoder.clear_buffer(&sorting_assets.sorting_global_buffer, 0, None);
-        command_encoder.clear_buffer(&sorting_assets.sorting_status_counter_buffer, 0, None);
         command_encoder.clear_buffer(cloud.draw_indirect_buffer(), 0, None);
 
-        {
-            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor::default());
-
-            // Reset per-frame counters/histograms
-            let radix_reset = pipeline_cache
-                .get_compute_pipeline(pipeline_variant.radix_sort_pipelines[RADIX_PIPELINE_RESET])
-                .unwrap();
-            pass.set_pipeline(radix_reset);
-            pass.set_bind_group(
-                0,
-                &view_bind_group.value,
-                &[
-                    view_uniform_offset.offset,
-                    previous_view_uniform_offset.offset,
-                ],
-            );
-            pass.set_bind_group(1, uniform_bind_group, &[cloud_uniform_index.index()]);
-            pass.set_bind_group(2, &cloud_bind_group.bind_group, &[]);
-            pass.set_bind_group(
-                3,
-                &radix_bind_group.radix_sort_bind_groups[initial_parity],
-                &[],
-            );
-            pass.dispatch_workgroups(1, 1, 1);
+        encode_radix_sort(
+            command_encoder,
+            &pipeline_cache,
+            &pipeline_variant.radix_sort_pipelines,
+            pipeline_variant.shader_defines,
+            sorting_assets,
+            &radix_bind_group.radix_sort_bind_groups,
+            cloud.len(),
+            |pass| {
+                pass.set_bind_group(
+                    0,
+                    &view_bind_group.value,
+                    &[
+                        view_uniform_offset.offset,
+                        previous_view_uniform_offset.offset,
+                    ],
+                );
+                pass.set_bind_group(1, uniform_bind_group, &[cloud_uniform_index.index()]);
+                pass.set_bind_group(2, &cloud_bind_group.bind_group, &[]);
+            },
+        );
+    }
+}
 
-            let radix_sort_a = pipeline_cache
-                .get_compute_pipeline(pipeline_variant.radix_sort_pipelines[RADIX_PIPELINE_A])
-                .unwrap();
-            pass.set_pipeline(radix_sort_a);
+/// records the reset, histogram and digit passes, `set_bind_groups` binds groups 0 to 2
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn encode_radix_sort(
+    command_encoder: &mut CommandEncoder,
+    pipeline_cache: &PipelineCache,
+    pipelines: &[CachedComputePipelineId; RADIX_PIPELINE_COUNT],
+    shader_defines: ShaderDefines,
+    sorting_assets: &GpuRadixBuffers,
+    radix_sort_bind_groups: &[BindGroup; 8],
+    len: usize,
+    set_bind_groups: impl Fn(&mut ComputePass),
+) {
+    let radix_digit_places = shader_defines.radix_digit_places;
+    let initial_parity = shader_defines.radix_initial_parity();
+    let workgroup_entries_a = shader_defines.workgroup_entries_a;
+    let workgroup_entries_c = shader_defines.workgroup_entries_c;
+    let tile_workgroups = (len as u32).div_ceil(workgroup_entries_c);
 
-            pass.dispatch_workgroups((cloud.len() as u32).div_ceil(workgroup_entries_a), 1, 1);
+    command_encoder.clear_buffer(&sorting_assets.sorting_global_buffer, 0, None);
+    command_encoder.clear_buffer(&sorting_assets.sorting_status_counter_buffer, 0, None);
 
-            let radix_sort_b = pipeline_cache
-                .get_compute_pipeline(pipeline_variant.radix_sort_pipelines[RADIX_PIPELINE_B])
-                .unwrap();
-            pass.set_pipeline(radix_sort_b);
+    {
+        let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor::default());
 
-            pass.dispatch_workgroups(1, radix_digit_places, 1);
-        }
+        // Reset per-frame counters/histograms
+        let radix_reset = pipeline_cache
+            .get_compute_pipeline(pipelines[RADIX_PIPELINE_RESET])
+            .unwrap();
+        pass.set_pipeline(radix_reset);
+        set_bind_groups(&mut pass);
+        pass.set_bind_group(3, &radix_sort_bind_groups[initial_parity], &[]);
+        pass.dispatch_workgroups(1, 1, 1);
 
-        // TODO: add options to only complete a fraction of the sorting process
-        for pass_idx in 0..radix_digit_places {
-            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor::default());
-
-            // Set common bind groups for view/uniforms and cloud storage
-            pass.set_bind_group(
-                0,
-                &view_bind_group.value,
-                &[
-                    view_uniform_offset.offset,
-                    previous_view_uniform_offset.offset,
-                ],
-            );
-            pass.set_bind_group(1, uniform_bind_group, &[cloud_uniform_index.index()]);
-            pass.set_bind_group(2, &cloud_bind_group.bind_group, &[]);
-
-            // Choose the initial parity so the final pass writes to sorted_entries.
-            let parity = ((pass_idx as usize) + initial_parity) % 2;
-            let bg_index = (pass_idx as usize) * 2 + parity;
-            pass.set_bind_group(3, &radix_bind_group.radix_sort_bind_groups[bg_index], &[]);
-
-            let radix_sort_c_count = pipeline_cache
-                .get_compute_pipeline(pipeline_variant.radix_sort_pipelines[RADIX_PIPELINE_C_COUNT])
-                .unwrap();
-            pass.set_pipeline(radix_sort_c_count);
-            pass.dispatch_workgroups(1, tile_workgroups, 1);
-
-            let radix_sort_c_scan = pipeline_cache
-                .get_compute_pipeline(pipeline_variant.radix_sort_pipelines[RADIX_PIPELINE_C_SCAN])
-                .unwrap();
-            pass.set_pipeline(radix_sort_c_scan);
-            // ONE workgroup of RADIX_BASE lanes (lane = digit), not RADIX_BASE single-lane
-            // workgroups -- see `radix_sort_c_scan_tiles`'s @workgroup_size in radix.wgsl.
-            pass.dispatch_workgroups(1, 1, 1);
-
-            let radix_sort_c_scatter = pipeline_cache
-                .get_compute_pipeline(
-                    pipeline_variant.radix_sort_pipelines[RADIX_PIPELINE_C_SCATTER],
-                )
-                .unwrap();
-            pass.set_pipeline(radix_sort_c_scatter);
-            pass.dispatch_workgroups(1, tile_workgroups, 1);
-        }
+        let radix_sort_a = pipeline_cache
+            .get_compute_pipeline(pipelines[RADIX_PIPELINE_A])
+            .unwrap();
+        pass.set_pipeline(radix_sort_a);
+
+        pass.dispatch_workgroups((len as u32).div_ceil(workgroup_entries_a), 1, 1);
+
+        let radix_sort_b = pipeline_cache
+            .get_compute_pipeline(pipelines[RADIX_PIPELINE_B])
+            .unwrap();
+        pass.set_pipeline(radix_sort_b);
+
+        pass.dispatch_workgroups(1, radix_digit_places, 1);
+    }
+
+    // TODO: add options to only complete a fraction of the sorting process
+    for pass_idx in 0..radix_digit_places {
+        let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor::default());
+
+        // Set common bind groups for view/uniforms and cloud storage
+        set_bind_groups(&mut pass);
+
+        // Choose the initial parity so the final pass writes to sorted_entries.
+        let parity = ((pass_idx as usize) + initial_parity) % 2;
+        let bg_index = (pass_idx as usize) * 2 + parity;
+        pass.set_bind_group(3, &radix_sort_bind_groups[bg_index], &[]);
+
+        let radix_sort_c_count = pipeline_cache
+            .get_compute_pipeline(pipelines[RADIX_PIPELINE_C_COUNT])
+            .unwrap();
+        pass.set_pipeline(radix_sort_c_count);
+        pass.dispatch_workgroups(1, tile_workgroups, 1);
+
+        let radix_sort_c_scan = pipeline_cache
+            .get_compute_pipeline(pipelines[RADIX_PIPELINE_C_SCAN])
+            .unwrap();
+        pass.set_pipeline(radix_sort_c_scan);
+        // ONE workgroup of RADIX_BASE lanes (lane = digit), not RADIX_BASE single-lane
+        // workgroups -- see `radix_sort_c_scan_tiles`'s @workgroup_size in radix.wgsl.
+        pass.dispatch_workgroups(1, 1, 1);
+
+        let radix_sort_c_scatter = pipeline_cache
+            .get_compute_pipeline(pipelines[RADIX_PIPELINE_C_SCATTER])
+            .unwrap();
+        pass.set_pipeline(radix_sort_c_scatter);
+        pass.dispatch_workgroups(1, tile_workgroups, 1);
     }
 }
@@ -45,6 +45,19 @@ struct SortingGlobal {
 @group(3) @binding(4) var<storage, read_write> input_entries: array<Entry>;
 @group(3) @binding(5) var<storage, read_write> output_entries: array<Entry>;
 
+#ifdef TILE_KEYS
+// tile|depth keys are emitted by `tile.wgsl`, the entry count is only known on the gpu
+@group(3) @binding(6) var<storage, read> tile_entry_count: u32;
+
+fn entry_count() -> u32 {
+    return min(tile_entry_count, arrayLength(&input_entries));
+}
+#else
+fn entry_count() -> u32 {
+    return gaussian_uniforms.count;
+}
+#endif
+
 //
 // The following three functions (`radix_reset`, `radix_sort_a`, `radix_sort_b`)
 // form a standard three-phase GPU sort setup and were already correct.
@@ -84,7 +97,10 @@ fn radix_sort_a(
     let end_entry_index = start_entry_index + #{ENTRIES_PER_INVOCATION_A}u;
 
     for (var entry_index = start_entry_index; entry_index < end_entry_index; entry_index += 1u) {
-        if (entry_index >= gaussian_uniforms.count) { continue; }
+        if (entry_index >= entry_count()) { continue; }
+#ifdef TILE_KEYS
+        let key = input_entries[entry_index].key;
+#else
         var key: u32 = 0xFFFFFFFFu;
         let position = vec4<f32>(get_position(entry_index), 1.0);
         let transformed_position = (gaussian_uniforms.transform * position).xyz;
@@ -99,6 +115,7 @@ fn radix_sort_a(
         key = key >> #{RADIX_KEY_SHIFT}u;
         input_entries[entry_index].key = key;
         input_entries[entry_index].value = entry_index;
+#endif
         for(var shift = 0u; shift < #{RADIX_DIGIT_PLACES}u; shift += 1u) {
             let digit = (key >> (shift * #{RADIX_BITS_PER_DIGIT}u)) & (#{RADIX_BASE}u - 1u);
             atomicAdd(&sorting.digit_histogram[shift][digit], 1u);
@@ -144,7 +161,7 @@ fn radix_sort_c_count_tiles(
 
     for (var i = tid; i < tile_size; i += threads) {
         let idx = global_entry_offset + i;
-        if (idx >= gaussian_uniforms.count) {
+        if (idx >= entry_count()) {
             continue;
         }
 
@@ -173,7 +190,7 @@ fn radix_sort_c_scan_tiles(
     }
 
     let tile_size = #{WORKGROUP_ENTRIES_C}u;
-    let tile_count = (gaussian_uniforms.count + tile_size - 1u) / tile_size;
+    let tile_count = (entry_count() + tile_size - 1u) / tile_size;
 
     var sum = atomicLoad(&sorting.digit_histogram[sorting_pass_index][digit]);
     for (var tile = 0u; tile < tile_count; tile += 1u) {
@@ -196,7 +213,7 @@ fn radix_sort_c_scatter(
     // Step 1: Parallel load.
     for (var i = tid; i < tile_size; i += threads) {
         let idx = global_entry_offset + i;
-        if (idx < gaussian_uniforms.count) {
+        if (idx < entry_count()) {
             tile_input_entries[i] = input_entries[idx];
         } else {
             tile_input_entries[i] = Entry(INVALID_KEY, INVALID_KEY);
@@ -268,7 +285,7 @@ fn radix_sort_c_scatter(
             let global_base = atomicLoad(&status_counters[workgroup_id.y][digit]);
             let dst = global_base + rank_in_bin;
 
-            if (dst < gaussian_uniforms.count) {
+            if (dst < entry_count()) {
                 output_entries[dst] = entry;
             }
         }
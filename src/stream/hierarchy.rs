@@ -1 +1,164 @@
+use std::collections::HashSet;
 
+use bevy::prelude::*;
+
+use crate::{
+    gaussian::{
+        formats::{
+            planar_4d::{Gaussian4d, PlanarGaussian4d, PlanarGaussian4dHandle},
+            planar_4d_hierarchy::TemporalGaussianHierarchy,
+        },
+        settings::{CloudSettings, GaussianMode},
+    },
+    stream::resident::{ResidentClouds, ResidentStreamPlugin, ResidentStreamQueue},
+};
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+#[require(TemporalStreaming, CloudSettings, Transform, Visibility)]
+pub struct TemporalGaussianHierarchyHandle(pub Handle<TemporalGaussianHierarchy>);
+
+/// keeps the levels overlapping `CloudSettings::time` resident in the entity's `PlanarGaussian4dHandle`
+///
+/// entering levels are streamed into the slots of leaving levels, the entity is drawn in `GaussianMode::Gaussian4d`
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TemporalStreaming {
+    /// seconds of playback ahead of `CloudSettings::time` to keep resident
+    pub lookahead: f32,
+    #[reflect(ignore)]
+    resident_levels: Vec<usize>,
+    /// flat cloud index held by each slot of the resident cloud
+    #[reflect(ignore)]
+    resident: Vec<u32>,
+    #[reflect(ignore)]
+    source: Option<AssetId<TemporalGaussianHierarchy>>,
+}
+
+impl Default for TemporalStreaming {
+    fn default() -> Self {
+        Self {
+            lookahead: 0.5,
+            resident_levels: Vec::new(),
+            resident: Vec::new(),
+            source: None,
+        }
+    }
+}
+
+impl TemporalStreaming {
+    pub fn resident_levels(&self) -> &[usize] {
+        &self.resident_levels
+    }
+}
+
+#[derive(Default)]
+pub struct TemporalHierarchyPlugin;
+
+impl Plugin for TemporalHierarchyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TemporalGaussianHierarchyHandle>();
+        app.register_type::<TemporalStreaming>();
+        app.init_asset::<TemporalGaussianHierarchy>();
+
+        app.add_plugins(ResidentStreamPlugin::<Gaussian4d>::default());
+        app.add_systems(Update, stream_temporal_levels);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn stream_temporal_levels(
+    mut hierarchy_events: MessageReader<AssetEvent<TemporalGaussianHierarchy>>,
+    hierarchies: Res<Assets<TemporalGaussianHierarchy>>,
+    mut clouds: ResMut<Assets<PlanarGaussian4d>>,
+    mut stream_queue: ResMut<ResidentStreamQueue<Gaussian4d>>,
+    mut resident_clouds: ResidentClouds,
+    mut streams: Query<(
+        Entity,
+        &TemporalGaussianHierarchyHandle,
+        &mut CloudSettings,
+        &mut TemporalStreaming,
+        Option<&PlanarGaussian4dHandle>,
+    )>,
+) {
+    // flat cloud indices of a modified hierarchy no longer match the resident slots
+    let modified = hierarchy_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    for (entity, hierarchy_handle, mut settings, mut streaming, cloud_handle) in streams.iter_mut()
+    {
+        // the resident cloud is 4d, draw it as such without the user opting in
+        if settings.gaussian_mode != GaussianMode::Gaussian4d {
+            settings.gaussian_mode = GaussianMode::Gaussian4d;
+        }
+
+        let Some(hierarchy) = hierarchies.get(&hierarchy_handle.0) else {
+            continue;
+        };
+
+        if hierarchy.levels.is_empty() {
+            continue;
+        }
+
+        let (window_start, window_stop) = if settings.time_scale < 0.0 {
+            (settings.time - streaming.lookahead, settings.time)
+        } else {
+            (settings.time, settings.time + streaming.lookahead)
+        };
+
+        let mut resident_levels = hierarchy.levels_overlapping(window_start, window_stop);
+
+        // never leave the cloud empty, fall back to the level closest in time
+        if resident_levels.is_empty() {
+            let distance = |index: usize| {
+                let level = &hierarchy.levels[index];
+                (level.time_start - settings.time)
+                    .max(settings.time - level.time_stop)
+                    .max(0.0)
+            };
+
+            let closest = (0..hierarchy.levels.len())
+                .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+                .unwrap();
+            resident_levels.push(closest);
+        }
+
+        let source = hierarchy_handle.0.id();
+        if resident_levels == streaming.resident_levels
+            && cloud_handle.is_some()
+            && !modified.contains(&source)
+        {
+            continue;
+        }
+
+        debug!(
+            ?entity,
+            levels = ?resident_levels,
+            "streaming resident temporal levels"
+        );
+        let target = resident_levels
+            .iter()
+            .flat_map(|&level| hierarchy.levels[level].instances())
+            .map(|instance| instance as u32)
+            .collect::<Vec<_>>();
+        streaming.resident_levels = resident_levels;
+
+        let streamed = streaming.source == Some(source) && !modified.contains(&source);
+        resident_clouds.restream(
+            &mut clouds,
+            &mut stream_queue,
+            entity,
+            cloud_handle,
+            &mut streaming.resident,
+            streamed,
+            target,
+            &hierarchy.flat_cloud,
+        );
+        streaming.source = Some(source);
+    }
+}
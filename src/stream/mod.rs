@@ -1,2 +1,3 @@
 pub mod hierarchy;
+pub mod resident;
 pub mod slice;
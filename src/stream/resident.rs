@@ -0,0 +1,555 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{camera::visibility::RenderLayers, ecs::system::SystemParam, prelude::*};
+use bevy_interleave::prelude::*;
+
+use crate::{
+    gaussian::{
+        formats::{
+            planar_3d::{Gaussian3d, PlanarGaussian3d, PlanarGaussian3dHandle},
+            planar_4d::{Gaussian4d, PlanarGaussian4d, PlanarGaussian4dHandle},
+        },
+        interface::CommonCloud,
+    },
+    sort::{SortTrigger, SortedEntries, SortedEntriesHandle},
+};
+
+#[cfg(feature = "buffer_storage")]
+use std::ops::Range;
+
+#[cfg(feature = "buffer_storage")]
+use bevy::render::{
+    ExtractSchedule, MainWorld, RenderApp,
+    render_asset::RenderAssets,
+    render_resource::{Buffer, BufferDescriptor, BufferUsages},
+    renderer::{RenderDevice, RenderQueue},
+};
+#[cfg(feature = "buffer_storage")]
+use bytemuck::Pod;
+
+#[cfg(feature = "buffer_storage")]
+use crate::{
+    gaussian::f32::{
+        IsotropicRotations, PositionVisibility, Rotation, ScaleOpacity, TimestampTimescale,
+    },
+    material::{
+        spherical_harmonics::SphericalHarmonicCoefficients,
+        spherindrical_harmonics::SpherindricalHarmonicCoefficients,
+    },
+    render::{PlanarStorageRebindQueue, extract_gaussians, extract_planar_storage_rebind_queue},
+};
+
+/// rewrites `resident` to hold the ids of `target`, ids kept by the target stay in their slot
+///
+/// entering ids fill the slots of leaving ids, the tail is moved into the remaining holes
+/// to keep the slots dense, returns the sorted slots whose id changed
+pub fn restream_slots(resident: &mut Vec<u32>, target: &[u32]) -> Vec<u32> {
+    let kept = target.iter().copied().collect::<HashSet<_>>();
+    let current = resident.iter().copied().collect::<HashSet<_>>();
+    let mut entering = target.iter().copied().filter(|id| !current.contains(id));
+
+    let mut dirty = Vec::new();
+    let mut holes = Vec::new();
+    for (slot, id) in resident.iter_mut().enumerate() {
+        if kept.contains(id) {
+            continue;
+        }
+
+        match entering.next() {
+            Some(next) => {
+                *id = next;
+                dirty.push(slot as u32);
+            }
+            None => holes.push(slot),
+        }
+    }
+
+    for id in entering {
+        dirty.push(resident.len() as u32);
+        resident.push(id);
+    }
+
+    let len = resident.len() - holes.len();
+    let (low, high) = holes.split_at(holes.partition_point(|&hole| hole < len));
+    let tail = (len..resident.len()).filter(|slot| high.binary_search(slot).is_err());
+    for (&hole, slot) in low.iter().zip(tail) {
+        resident[hole] = resident[slot];
+        dirty.push(hole as u32);
+    }
+
+    resident.truncate(len);
+    dirty.retain(|&slot| (slot as usize) < len);
+    dirty.sort_unstable();
+    dirty.dedup();
+
+    dirty
+}
+
+/// planar clouds whose gpu planes can be rewritten by slot instead of re-created
+pub trait ResidentPlanar: PlanarSync {
+    fn resize(cloud: &mut Self::PlanarType, len: usize);
+
+    fn planar_handle(handle: Handle<Self::PlanarType>) -> Self::PlanarTypeHandle;
+
+    /// gaussians the gpu planes hold
+    #[cfg(feature = "buffer_storage")]
+    fn capacity(gpu: &Self::GpuPlanarType) -> usize;
+
+    /// replaces the gpu planes with empty planes of `capacity` gaussians
+    #[cfg(feature = "buffer_storage")]
+    fn grow(gpu: &mut Self::GpuPlanarType, render_device: &RenderDevice, capacity: usize);
+
+    #[cfg(feature = "buffer_storage")]
+    fn write_runs(
+        gpu: &Self::GpuPlanarType,
+        cloud: &Self::PlanarType,
+        render_queue: &RenderQueue,
+        runs: &[Range<usize>],
+    );
+
+    /// sets the drawn gaussian count, including the instance count of the indirect draw
+    #[cfg(feature = "buffer_storage")]
+    fn set_len(gpu: &mut Self::GpuPlanarType, render_queue: &RenderQueue, len: usize);
+}
+
+impl ResidentPlanar for Gaussian3d {
+    fn resize(cloud: &mut PlanarGaussian3d, len: usize) {
+        cloud.position_visibility.resize(len, default());
+        cloud.spherical_harmonic.resize(len, default());
+        cloud.rotation.resize(len, default());
+        cloud.scale_opacity.resize(len, default());
+    }
+
+    fn planar_handle(handle: Handle<PlanarGaussian3d>) -> PlanarGaussian3dHandle {
+        PlanarGaussian3dHandle(handle)
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn capacity(gpu: &Self::GpuPlanarType) -> usize {
+        plane_capacity::<PositionVisibility>(&gpu.position_visibility)
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn grow(gpu: &mut Self::GpuPlanarType, render_device: &RenderDevice, capacity: usize) {
+        gpu.position_visibility = resident_plane::<PositionVisibility>(render_device, capacity);
+        gpu.spherical_harmonic =
+            resident_plane::<SphericalHarmonicCoefficients>(render_device, capacity);
+        gpu.rotation = resident_plane::<Rotation>(render_device, capacity);
+        gpu.scale_opacity = resident_plane::<ScaleOpacity>(render_device, capacity);
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn write_runs(
+        gpu: &Self::GpuPlanarType,
+        cloud: &PlanarGaussian3d,
+        render_queue: &RenderQueue,
+        runs: &[Range<usize>],
+    ) {
+        write_plane_runs(
+            render_queue,
+            &gpu.position_visibility,
+            &cloud.position_visibility,
+            runs,
+        );
+        write_plane_runs(
+            render_queue,
+            &gpu.spherical_harmonic,
+            &cloud.spherical_harmonic,
+            runs,
+        );
+        write_plane_runs(render_queue, &gpu.rotation, &cloud.rotation, runs);
+        write_plane_runs(render_queue, &gpu.scale_opacity, &cloud.scale_opacity, runs);
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn set_len(gpu: &mut Self::GpuPlanarType, render_queue: &RenderQueue, len: usize) {
+        gpu.count = len;
+        write_draw_count(render_queue, &gpu.draw_indirect_buffer, len);
+    }
+}
+
+impl ResidentPlanar for Gaussian4d {
+    fn resize(cloud: &mut PlanarGaussian4d, len: usize) {
+        cloud.position_visibility.resize(len, default());
+        cloud.spherindrical_harmonic.resize(len, default());
+        cloud.isotropic_rotations.resize(len, default());
+        cloud.scale_opacity.resize(len, default());
+        cloud.timestamp_timescale.resize(len, default());
+    }
+
+    fn planar_handle(handle: Handle<PlanarGaussian4d>) -> PlanarGaussian4dHandle {
+        PlanarGaussian4dHandle(handle)
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn capacity(gpu: &Self::GpuPlanarType) -> usize {
+        plane_capacity::<PositionVisibility>(&gpu.position_visibility)
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn grow(gpu: &mut Self::GpuPlanarType, render_device: &RenderDevice, capacity: usize) {
+        gpu.position_visibility = resident_plane::<PositionVisibility>(render_device, capacity);
+        gpu.spherindrical_harmonic =
+            resident_plane::<SpherindricalHarmonicCoefficients>(render_device, capacity);
+        gpu.isotropic_rotations = resident_plane::<IsotropicRotations>(render_device, capacity);
+        gpu.scale_opacity = resident_plane::<ScaleOpacity>(render_device, capacity);
+        gpu.timestamp_timescale = resident_plane::<TimestampTimescale>(render_device, capacity);
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn write_runs(
+        gpu: &Self::GpuPlanarType,
+        cloud: &PlanarGaussian4d,
+        render_queue: &RenderQueue,
+        runs: &[Range<usize>],
+    ) {
+        write_plane_runs(
+            render_queue,
+            &gpu.position_visibility,
+            &cloud.position_visibility,
+            runs,
+        );
+        write_plane_runs(
+            render_queue,
+            &gpu.spherindrical_harmonic,
+            &cloud.spherindrical_harmonic,
+            runs,
+        );
+        write_plane_runs(
+            render_queue,
+            &gpu.isotropic_rotations,
+            &cloud.isotropic_rotations,
+            runs,
+        );
+        write_plane_runs(render_queue, &gpu.scale_opacity, &cloud.scale_opacity, runs);
+        write_plane_runs(
+            render_queue,
+            &gpu.timestamp_timescale,
+            &cloud.timestamp_timescale,
+            runs,
+        );
+    }
+
+    #[cfg(feature = "buffer_storage")]
+    fn set_len(gpu: &mut Self::GpuPlanarType, render_queue: &RenderQueue, len: usize) {
+        gpu.count = len;
+        write_draw_count(render_queue, &gpu.draw_indirect_buffer, len);
+    }
+}
+
+/// moves the resident slots of `cloud` to the ids of `target`, entering gaussians are copied from `source`
+///
+/// returns the slots to upload, see `restream_slots`
+pub fn restream_cloud<R: ResidentPlanar>(
+    resident: &mut Vec<u32>,
+    target: &[u32],
+    cloud: &mut R::PlanarType,
+    source: &R::PlanarType,
+) -> Vec<u32> {
+    let dirty = restream_slots(resident, target);
+
+    R::resize(cloud, resident.len());
+    for &slot in &dirty {
+        let gaussian = source.get(resident[slot as usize] as usize);
+        Planar::set(cloud, slot as usize, gaussian);
+    }
+
+    dirty
+}
+
+/// clouds drawing a resident subset of a source cloud, e.g. a lod cut or streamed temporal levels
+#[derive(SystemParam)]
+pub struct ResidentClouds<'w, 's> {
+    commands: Commands<'w, 's>,
+    sorted_entries: Res<'w, Assets<SortedEntries>>,
+    entities: Query<
+        'w,
+        's,
+        (
+            Option<&'static SortedEntriesHandle>,
+            Option<&'static RenderLayers>,
+        ),
+    >,
+    sort_triggers: Query<'w, 's, (&'static mut SortTrigger, Option<&'static RenderLayers>)>,
+}
+
+impl ResidentClouds<'_, '_> {
+    /// moves the cloud drawn by `entity` to the `target` ids of `source`
+    ///
+    /// `resident` holds the ids of the current cloud, it is rewritten by slot when `streamed`
+    /// and replaced otherwise, the cameras that see `entity` re-sort it
+    #[allow(clippy::too_many_arguments)]
+    pub fn restream<R: ResidentPlanar>(
+        &mut self,
+        clouds: &mut Assets<R::PlanarType>,
+        stream_queue: &mut ResidentStreamQueue<R>,
+        entity: Entity,
+        cloud_handle: Option<&R::PlanarTypeHandle>,
+        resident: &mut Vec<u32>,
+        streamed: bool,
+        target: Vec<u32>,
+        source: &R::PlanarType,
+    ) where
+        R::PlanarType: Asset + CommonCloud,
+    {
+        // texture backed clouds are laid out by their exact length and are always re-uploaded
+        let resident_cloud = cloud_handle
+            .filter(|_| cfg!(feature = "buffer_storage") && streamed)
+            .and_then(|handle| {
+                clouds
+                    .get_mut_untracked(handle.handle())
+                    .map(|cloud| (handle, cloud))
+            });
+
+        let previous_len = resident.len();
+        match resident_cloud {
+            Some((handle, cloud)) => {
+                // the main world copy stays current for cpu sorts, the gpu copy is streamed by slot
+                let dirty = restream_cloud::<R>(resident, &target, cloud, source);
+                if dirty.is_empty() && resident.len() == previous_len {
+                    return;
+                }
+
+                stream_queue.push(handle.handle().id(), resident.len(), dirty);
+            }
+            None => {
+                let indices = target.iter().map(|&id| id as usize).collect::<Vec<_>>();
+                let cloud = source.subset(&indices);
+                *resident = target;
+
+                match cloud_handle {
+                    Some(handle) => {
+                        let _ = clouds.insert(handle.handle(), cloud);
+                    }
+                    None => {
+                        let handle = R::planar_handle(clouds.add(cloud));
+                        self.commands.entity(entity).insert(handle);
+                    }
+                }
+            }
+        }
+
+        let (sorted_entries_handle, render_layers) =
+            self.entities.get(entity).unwrap_or((None, None));
+
+        // sorted entries are sized to the cloud, texture entries are laid out by its exact length
+        let outgrown = sorted_entries_handle
+            .and_then(|handle| self.sorted_entries.get(&handle.0))
+            .is_some_and(|entries| entries.entry_count < resident.len());
+        let resized = resident.len() != previous_len;
+        if outgrown || (cfg!(feature = "buffer_texture") && resized) {
+            self.commands.entity(entity).remove::<SortedEntriesHandle>();
+        }
+
+        let render_layers = render_layers.unwrap_or_default();
+        for (mut trigger, camera_layers) in self.sort_triggers.iter_mut() {
+            if camera_layers.unwrap_or_default().intersects(render_layers) {
+                trigger.needs_sort = true;
+            }
+        }
+    }
+}
+
+/// cloud slots written in the main world since the last extraction
+#[derive(Clone, Debug, Default)]
+struct ResidentStream {
+    len: usize,
+    dirty: Vec<u32>,
+}
+
+impl ResidentStream {
+    fn merge(&mut self, other: ResidentStream) {
+        self.len = other.len;
+        self.dirty.extend(other.dirty);
+        self.dirty.sort_unstable();
+        self.dirty.dedup();
+    }
+}
+
+/// slot updates of resident clouds, applied to their gpu planes during extraction
+#[derive(Resource)]
+pub struct ResidentStreamQueue<R: PlanarSync> {
+    streams: HashMap<AssetId<R::PlanarType>, ResidentStream>,
+}
+
+impl<R: PlanarSync> Default for ResidentStreamQueue<R> {
+    fn default() -> Self {
+        Self {
+            streams: HashMap::new(),
+        }
+    }
+}
+
+impl<R: PlanarSync> ResidentStreamQueue<R> {
+    /// queues the `dirty` slots of a cloud now holding `len` gaussians
+    pub fn push(&mut self, id: AssetId<R::PlanarType>, len: usize, dirty: Vec<u32>) {
+        self.streams
+            .entry(id)
+            .or_default()
+            .merge(ResidentStream { len, dirty });
+    }
+}
+
+/// streams waiting on their gpu cloud, kept across frames
+#[cfg(feature = "buffer_storage")]
+#[derive(Resource)]
+struct PendingResidentStreams<R: PlanarSync> {
+    streams: HashMap<AssetId<R::PlanarType>, ResidentStream>,
+}
+
+#[cfg(feature = "buffer_storage")]
+impl<R: PlanarSync> Default for PendingResidentStreams<R> {
+    fn default() -> Self {
+        Self {
+            streams: HashMap::new(),
+        }
+    }
+}
+
+pub struct ResidentStreamPlugin<R: ResidentPlanar> {
+    phantom: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R: ResidentPlanar> Default for ResidentStreamPlugin<R> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<R: ResidentPlanar> Plugin for ResidentStreamPlugin<R>
+where
+    R::PlanarType: Asset,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResidentStreamQueue<R>>();
+
+        #[cfg(feature = "buffer_storage")]
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            let stream = stream_resident_slots::<R>
+                .before(extract_planar_storage_rebind_queue::<R>)
+                .before(extract_gaussians::<R>);
+
+            #[cfg(all(feature = "sort_radix", not(feature = "buffer_texture")))]
+            let stream = stream.before(crate::sort::radix::update_sort_buffers::<R>);
+
+            render_app
+                .init_resource::<PendingResidentStreams<R>>()
+                .add_systems(ExtractSchedule, stream);
+        }
+    }
+}
+
+/// applies the streamed slots to the resident gpu clouds, growing planes the cloud has outgrown
+#[cfg(feature = "buffer_storage")]
+fn stream_resident_slots<R: ResidentPlanar>(
+    mut main_world: ResMut<MainWorld>,
+    mut pending: ResMut<PendingResidentStreams<R>>,
+    mut gpu_clouds: ResMut<RenderAssets<R::GpuPlanarType>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) where
+    R::PlanarType: Asset,
+{
+    let queued = std::mem::take(&mut main_world.resource_mut::<ResidentStreamQueue<R>>().streams);
+    for (id, stream) in queued {
+        pending.streams.entry(id).or_default().merge(stream);
+    }
+
+    let mut rebinds = Vec::new();
+    let clouds = main_world.resource::<Assets<R::PlanarType>>();
+    pending.streams.retain(|id, stream| {
+        let Some(cloud) = clouds.get(*id) else {
+            return false;
+        };
+
+        // pending until the first upload of the cloud is prepared
+        let Some(gpu_cloud) = gpu_clouds.get_mut(*id) else {
+            return true;
+        };
+
+        let len = stream.len.min(cloud.len());
+        let runs = if len > R::capacity(gpu_cloud) {
+            R::grow(gpu_cloud, &render_device, len.next_power_of_two());
+            rebinds.push(*id);
+
+            std::iter::once(0..len).collect()
+        } else {
+            slot_runs(&stream.dirty, len)
+        };
+
+        R::write_runs(gpu_cloud, cloud, &render_queue, &runs);
+        R::set_len(gpu_cloud, &render_queue, len);
+
+        false
+    });
+
+    // the rebind queue is extracted after this system, grown planes are bound this frame
+    let mut rebind_queue = main_world.resource_mut::<PlanarStorageRebindQueue<R>>();
+    for id in rebinds {
+        rebind_queue.push_unique(id);
+    }
+}
+
+#[cfg(feature = "buffer_storage")]
+pub(crate) fn write_draw_count(
+    render_queue: &RenderQueue,
+    draw_indirect_buffer: &Buffer,
+    len: usize,
+) {
+    render_queue.write_buffer(
+        draw_indirect_buffer,
+        0,
+        bytemuck::cast_slice(&[4, len as u32, 0, 0]),
+    );
+}
+
+#[cfg(feature = "buffer_storage")]
+pub(crate) fn plane_capacity<T>(buffer: &Buffer) -> usize {
+    buffer.size() as usize / std::mem::size_of::<T>()
+}
+
+#[cfg(feature = "buffer_storage")]
+pub(crate) fn resident_plane<T>(render_device: &RenderDevice, capacity: usize) -> Buffer {
+    render_device.create_buffer(&BufferDescriptor {
+        label: Some("resident plane buffer"),
+        size: (capacity.max(1) * std::mem::size_of::<T>()) as u64,
+        usage: BufferUsages::COPY_DST | BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(feature = "buffer_storage")]
+pub(crate) fn write_plane_runs<T: Pod>(
+    render_queue: &RenderQueue,
+    buffer: &Buffer,
+    plane: &[T],
+    runs: &[Range<usize>],
+) {
+    for run in runs {
+        render_queue.write_buffer(
+            buffer,
+            (run.start * std::mem::size_of::<T>()) as u64,
+            bytemuck::cast_slice(&plane[run.clone()]),
+        );
+    }
+}
+
+/// contiguous ranges of the sorted dirty slots below `len`
+#[cfg(feature = "buffer_storage")]
+fn slot_runs(dirty: &[u32], len: usize) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for slot in dirty
+        .iter()
+        .map(|&slot| slot as usize)
+        .filter(|&slot| slot < len)
+    {
+        match runs.last_mut() {
+            Some(run) if run.end == slot => run.end += 1,
+            _ => runs.push(slot..slot + 1),
+        }
+    }
+
+    runs
+}
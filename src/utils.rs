@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use bevy_args::{Deserialize, Parser, Serialize};
 
-use crate::gaussian::settings::{GaussianMode, PlaybackMode, RadixSortDepthBits, RasterizeMode};
+use crate::gaussian::settings::{
+    DepthMode, GaussianMode, OitWeight, PlaybackMode, RadixSortDepthBits, RasterizeMode, Rasterizer,
+};
 
 #[derive(Debug, Resource, Serialize, Deserialize, Parser)]
 #[command(about = "bevy_gaussian_splatting viewer", version, long_about = None)]
@@ -70,6 +72,15 @@ pub struct GaussianSplattingViewer {
     #[arg(long, value_enum, default_value_t = RasterizeMode::Color)]
     pub rasterization_mode: RasterizeMode,
 
+    #[arg(long, value_enum, default_value_t = Rasterizer::Instanced)]
+    pub rasterizer: Rasterizer,
+
+    #[arg(long, value_enum, default_value_t = OitWeight::DepthFalloff)]
+    pub oit_weight: OitWeight,
+
+    #[arg(long, value_enum, default_value_t = DepthMode::Test)]
+    pub depth_mode: DepthMode,
+
     #[arg(long, value_enum, default_value_t = RadixSortDepthBits::Bits32)]
     pub radix_sort_depth_bits: RadixSortDepthBits,
 
@@ -100,6 +111,9 @@ impl Default for GaussianSplattingViewer {
             gaussian_mode: GaussianMode::Gaussian3d,
             playback_mode: PlaybackMode::Still,
             rasterization_mode: RasterizeMode::Color,
+            rasterizer: Rasterizer::Instanced,
+            oit_weight: OitWeight::DepthFalloff,
+            depth_mode: DepthMode::Test,
             radix_sort_depth_bits: RadixSortDepthBits::Bits32,
             particle_count: 0,
         }
@@ -0,0 +1,6 @@
+use bevy::shader::ShaderDefVal;
+
+pub fn has_def(defs: &[ShaderDefVal], name: &str) -> bool {
+    defs.iter()
+        .any(|def| matches!(def, ShaderDefVal::Bool(def, true) if def == name))
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use bevy::{camera::Projection, prelude::*};
+use bevy_gaussian_splatting::{
+    CloudSettings, DepthMode, Gaussian3d, PlanarGaussian3d, Rasterizer,
+    SphericalHarmonicCoefficients,
+    gaussian::f32::{PositionVisibility, Rotation, ScaleOpacity},
+    render::{
+        CloudPipelineKey,
+        reference::{ReferenceImage, ReferenceRasterize, ReferenceView},
+        shader_defs,
+    },
+};
+use bevy_interleave::prelude::Planar;
+use common::has_def;
+
+const SIZE: u32 = 65;
+const CENTER: u32 = SIZE / 2;
+const DISTANCE: f32 = 2.5;
+
+fn view() -> ReferenceView {
+    let camera = Transform::from_xyz(0.0, 0.0, DISTANCE).looking_at(Vec3::ZERO, Vec3::Y);
+    ReferenceView::new(
+        &GlobalTransform::from(camera),
+        &Projection::default(),
+        SIZE,
+        SIZE,
+    )
+}
+
+/// reversed ndc depth of a world position, the flat quad depth of a splat centered there
+fn ndc_depth(position: Vec3) -> f32 {
+    let view = view();
+    let clip = view.clip_from_view * view.world_from_view.inverse() * position.extend(1.0);
+    clip.z / clip.w
+}
+
+fn gaussian(z: f32, opacity: f32) -> Gaussian3d {
+    Gaussian3d {
+        position_visibility: PositionVisibility {
+            position: [0.0, 0.0, z],
+            visibility: 1.0,
+        },
+        spherical_harmonic: SphericalHarmonicCoefficients::default(),
+        rotation: Rotation {
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        },
+        scale_opacity: ScaleOpacity {
+            scale: [0.25; 3],
+            opacity,
+        },
+    }
+}
+
+fn render(
+    gaussians: Vec<Gaussian3d>,
+    rasterizer: Rasterizer,
+    depth_mode: DepthMode,
+) -> ReferenceImage {
+    let settings = CloudSettings {
+        aabb: true,
+        rasterizer,
+        depth_mode,
+        ..default()
+    };
+    PlanarGaussian3d::from_interleaved(gaussians).rasterize_reference(
+        &GlobalTransform::IDENTITY,
+        &settings,
+        &view(),
+    )
+}
+
+fn center_depth(image: &ReferenceImage) -> f32 {
+    image.depth[(CENTER * SIZE + CENTER) as usize]
+}
+
+#[test]
+fn depth_is_only_tested_by_default() {
+    let settings = CloudSettings::default();
+
+    assert_eq!(settings.depth_mode, DepthMode::Test);
+    assert_eq!(settings.depth_alpha_threshold, 0.5);
+}
+
+#[test]
+fn write_depth_specializes_depth_fragment() {
+    let color = shader_defs(CloudPipelineKey::default());
+    let depth = shader_defs(CloudPipelineKey {
+        write_depth: true,
+        ..Default::default()
+    });
+
+    assert!(!has_def(&color, "WRITE_DEPTH"));
+    assert!(has_def(&depth, "WRITE_DEPTH"));
+}
+
+#[test]
+fn depth_test_writes_no_depth() {
+    for rasterizer in [
+        Rasterizer::Instanced,
+        Rasterizer::Tile,
+        Rasterizer::WeightedBlended,
+    ] {
+        let image = render(vec![gaussian(0.0, 0.999)], rasterizer, DepthMode::Test);
+
+        assert!(image.pixel(CENTER, CENTER).w > 0.5);
+        assert!(
+            image.depth.iter().all(|&depth| depth == 0.0),
+            "{rasterizer:?}"
+        );
+    }
+}
+
+#[test]
+fn alpha_threshold_writes_nearest_opaque_quad_depth() {
+    let near = gaussian(0.5, 0.9);
+    let far = gaussian(-0.5, 0.9);
+    let expected = ndc_depth(Vec3::new(0.0, 0.0, 0.5));
+    assert!(expected > ndc_depth(Vec3::new(0.0, 0.0, -0.5)));
+
+    for rasterizer in [
+        Rasterizer::Instanced,
+        Rasterizer::Tile,
+        Rasterizer::WeightedBlended,
+    ] {
+        for gaussians in [vec![near, far], vec![far, near]] {
+            let image = render(gaussians, rasterizer, DepthMode::AlphaThreshold);
+
+            let depth = center_depth(&image);
+            assert!(
+                (depth - expected).abs() < 1e-6,
+                "{rasterizer:?} {depth} {expected}"
+            );
+            // the corners stay outside every quad
+            assert_eq!(image.depth[0], 0.0);
+        }
+    }
+}
+
+#[test]
+fn alpha_threshold_tiles_resolve_accumulated_coverage() {
+    let faint = vec![gaussian(0.5, 0.3), gaussian(-0.5, 0.3)];
+
+    // no single fragment reaches the threshold
+    for rasterizer in [Rasterizer::Instanced, Rasterizer::WeightedBlended] {
+        let image = render(faint.clone(), rasterizer, DepthMode::AlphaThreshold);
+        assert!(
+            image.depth.iter().all(|&depth| depth == 0.0),
+            "{rasterizer:?}"
+        );
+    }
+
+    // tiles resolve the depth of the splat whose coverage crosses the threshold
+    let image = render(faint, Rasterizer::Tile, DepthMode::AlphaThreshold);
+    let expected = ndc_depth(Vec3::new(0.0, 0.0, -0.5));
+    let depth = center_depth(&image);
+    assert!((depth - expected).abs() < 1e-6, "{depth} {expected}");
+}
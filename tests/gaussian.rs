@@ -8,8 +8,8 @@ fn test_codec_3d() {
     let count = 100;
 
     let gaussians = random_gaussians_3d(count);
-    let encoded = gaussians.encode();
-    let decoded = PlanarGaussian3d::decode(encoded.as_slice());
+    let encoded = gaussians.encode().unwrap();
+    let decoded = PlanarGaussian3d::decode(encoded.as_slice()).unwrap();
 
     assert_eq!(gaussians, decoded);
 }
@@ -19,8 +19,816 @@ fn test_codec_4d() {
     let count = 100;
 
     let gaussians = random_gaussians_4d(count);
-    let encoded = gaussians.encode();
-    let decoded = PlanarGaussian4d::decode(encoded.as_slice());
+    let encoded = gaussians.encode().unwrap();
+    let decoded = PlanarGaussian4d::decode(encoded.as_slice()).unwrap();
 
     assert_eq!(gaussians, decoded);
 }
+
+#[test]
+#[allow(deprecated)]
+fn test_visibility_mut_and_set_visibility() {
+    use bevy_gaussian_splatting::gaussian::interface::CommonCloud;
+
+    let mut gaussians = random_gaussians_3d(4);
+
+    *gaussians.visibility_mut(1) = 0.0;
+    gaussians.set_visibility(2, 0.5);
+
+    assert_eq!(gaussians.visibility(1), 0.0);
+    assert_eq!(gaussians.visibility(2), 0.5);
+}
+
+mod quantized {
+    use bevy_gaussian_splatting::{
+        Gaussian3d, PlanarGaussian3d, PlanarGaussian3dQuantized,
+        gaussian::{formats::planar_3d_quantized::QUANTIZED_CHUNK_SIZE, interface::CommonCloud},
+        io::codec::CloudCodec,
+        material::spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL},
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn chunk_extent(gaussians: &PlanarGaussian3d, chunk: usize) -> [f32; 3] {
+        let start = chunk * QUANTIZED_CHUNK_SIZE;
+        let end = (start + QUANTIZED_CHUNK_SIZE).min(gaussians.len());
+
+        std::array::from_fn(|axis| {
+            let values = gaussians.position_visibility[start..end]
+                .iter()
+                .map(|position_visibility| position_visibility.position[axis]);
+            let min = values.clone().fold(f32::INFINITY, f32::min);
+            let max = values.fold(f32::NEG_INFINITY, f32::max);
+            max - min
+        })
+    }
+
+    #[test]
+    fn test_quantized_error_bounds() {
+        let gaussians = random_gaussians_3d_seeded(1000, 42);
+        let quantized = PlanarGaussian3dQuantized::from(&gaussians);
+        let decoded = PlanarGaussian3d::from(&quantized);
+
+        assert_eq!(gaussians.len(), quantized.len());
+        assert_eq!(gaussians.len(), decoded.len());
+        assert_eq!(
+            quantized.chunk.len(),
+            gaussians.len().div_ceil(QUANTIZED_CHUNK_SIZE)
+        );
+
+        let sh_coefficients = SH_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS;
+
+        for (index, (expected, actual)) in gaussians.iter().zip(decoded.iter()).enumerate() {
+            let chunk = quantized.chunk[index / QUANTIZED_CHUNK_SIZE];
+            let extent = chunk_extent(&gaussians, index / QUANTIZED_CHUNK_SIZE);
+
+            for (axis, extent) in extent.iter().enumerate() {
+                let error = (expected.position_visibility.position[axis]
+                    - actual.position_visibility.position[axis])
+                    .abs();
+                assert!(error <= extent / 131070.0 + 1e-5, "position error {error}");
+
+                let expected_scale = expected.scale_opacity.scale[axis].ln().max(-10.0);
+                let actual_scale = actual.scale_opacity.scale[axis].ln();
+                let error = (expected_scale - actual_scale).abs();
+                assert!(error <= 0.5 / 16.0 + 1e-4, "log scale error {error}");
+            }
+
+            assert_eq!(
+                expected.position_visibility.visibility,
+                actual.position_visibility.visibility
+            );
+
+            let error = (expected.scale_opacity.opacity - actual.scale_opacity.opacity).abs();
+            assert!(error <= 0.5 / 255.0 + 1e-6, "opacity error {error}");
+
+            let norm = expected
+                .rotation
+                .rotation
+                .iter()
+                .map(|v| v * v)
+                .sum::<f32>()
+                .sqrt();
+            let dot = expected
+                .rotation
+                .rotation
+                .iter()
+                .zip(actual.rotation.rotation.iter())
+                .map(|(a, b)| a / norm * b)
+                .sum::<f32>();
+            assert!(dot.abs() >= 0.9999, "rotation dot {dot}");
+
+            for i in 0..sh_coefficients {
+                let tolerance = if i < SH_CHANNELS {
+                    chunk.sh_dc_scale / 65534.0
+                } else {
+                    chunk.sh_rest_scale / 254.0
+                };
+                let error = (expected.spherical_harmonic.coefficients[i]
+                    - actual.spherical_harmonic.coefficients[i])
+                    .abs();
+                assert!(error <= tolerance + 1e-6, "sh[{i}] error {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantized_positions_and_visibility() {
+        let gaussians = random_gaussians_3d_seeded(600, 7);
+        let mut quantized = PlanarGaussian3dQuantized::from(&gaussians);
+
+        for (index, position) in quantized.position_iter().enumerate() {
+            let expected = Gaussian3d::from(quantized.get(index))
+                .position_visibility
+                .position;
+            assert_eq!(expected, position);
+        }
+
+        quantized.set_visibility(300, 0.0);
+        assert_eq!(quantized.visibility(300), 0.0);
+        assert_eq!(quantized.visibility(301), 1.0);
+
+        let subset = quantized.subset(&[0, 300, 599]);
+        assert_eq!(subset.len(), 3);
+        assert_eq!(subset.chunk.len(), 1);
+        assert_eq!(subset.visibility(1), 0.0);
+    }
+
+    #[test]
+    fn test_quantized_codec() {
+        let gaussians = random_gaussians_3d_seeded(1000, 3);
+        let quantized = PlanarGaussian3dQuantized::from(&gaussians);
+
+        let encoded = quantized.encode().unwrap();
+        let decoded = PlanarGaussian3dQuantized::decode(encoded.as_slice()).unwrap();
+
+        assert_eq!(quantized, decoded);
+        assert_eq!(
+            PlanarGaussian3dQuantized::from_interleaved(quantized.to_interleaved()),
+            quantized
+        );
+    }
+}
+
+mod codebook {
+    use bevy_gaussian_splatting::{
+        Gaussian3d, PlanarGaussian3d, PlanarGaussian3dCodebook, ShCodebookBuilder,
+        gaussian::{formats::planar_3d_codebook::SH_CODEBOOK_REST_COUNT, interface::CommonCloud},
+        io::codec::CloudCodec,
+        material::spherical_harmonics::SH_CHANNELS,
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn rest_mse(original: &PlanarGaussian3d, decoded: &PlanarGaussian3d) -> f32 {
+        let rest = SH_CHANNELS..SH_CHANNELS + SH_CODEBOOK_REST_COUNT;
+        let squared_error = original
+            .spherical_harmonic
+            .iter()
+            .zip(&decoded.spherical_harmonic)
+            .flat_map(|(a, b)| {
+                a.coefficients[rest.clone()]
+                    .iter()
+                    .zip(&b.coefficients[rest.clone()])
+                    .map(|(a, b)| (a - b) * (a - b))
+            })
+            .sum::<f32>();
+
+        // sh0 builds have no rest coefficients to compare
+        let samples = original.len() * SH_CODEBOOK_REST_COUNT;
+        if samples == 0 {
+            return 0.0;
+        }
+
+        squared_error / samples as f32
+    }
+
+    #[test]
+    fn test_codebook_reconstruction_error() {
+        let gaussians = random_gaussians_3d_seeded(1000, 42);
+
+        let mut previous = f32::INFINITY;
+        for codebook_size in [16, 128, 1000] {
+            let codebook = ShCodebookBuilder::default()
+                .codebook_size(codebook_size)
+                .build(&gaussians);
+            let decoded = PlanarGaussian3d::from(&codebook);
+
+            assert!(codebook.codebook.len() <= codebook_size);
+            assert_eq!(decoded.position_visibility, gaussians.position_visibility);
+            assert_eq!(decoded.rotation, gaussians.rotation);
+            assert_eq!(decoded.scale_opacity, gaussians.scale_opacity);
+
+            for (original, decoded) in gaussians
+                .spherical_harmonic
+                .iter()
+                .zip(&decoded.spherical_harmonic)
+            {
+                for channel in 0..SH_CHANNELS {
+                    let expected = original.coefficients[channel];
+                    let error = (expected - decoded.coefficients[channel]).abs();
+                    assert!(error <= expected.abs() * 1e-3 + 1e-4);
+                }
+            }
+
+            let error = rest_mse(&gaussians, &decoded);
+            assert!(
+                error <= previous,
+                "{codebook_size} entries: {error} > {previous}"
+            );
+            previous = error;
+        }
+
+        // one entry per gaussian reproduces the coefficients exactly
+        assert_eq!(previous, 0.0);
+    }
+
+    #[test]
+    fn test_codebook_is_deterministic() {
+        let gaussians = random_gaussians_3d_seeded(500, 7);
+        let builder = ShCodebookBuilder::default()
+            .codebook_size(32)
+            .training_samples(256)
+            .seed(3);
+
+        let codebook = builder.build(&gaussians);
+        assert_eq!(codebook, builder.build(&gaussians));
+
+        // k-means never does worse than its initial centroids
+        let initial = builder.clone().iterations(0).build(&gaussians);
+        assert!(
+            rest_mse(&gaussians, &(&codebook).into()) <= rest_mse(&gaussians, &(&initial).into())
+        );
+    }
+
+    #[test]
+    fn test_codebook_planar() {
+        let gaussians = random_gaussians_3d_seeded(600, 11);
+        let mut codebook = ShCodebookBuilder::default()
+            .codebook_size(64)
+            .build(&gaussians);
+
+        codebook.set_visibility(300, 0.0);
+        assert_eq!(codebook.visibility(300), 0.0);
+        assert_eq!(codebook.visibility(301), 1.0);
+
+        let subset = codebook.subset(&[0, 300, 599]);
+        assert_eq!(subset.len(), 3);
+        assert_eq!(subset.codebook, codebook.codebook);
+        assert_eq!(subset.get(2), codebook.get(599));
+
+        // setting a gaussian with foreign coefficients snaps to the nearest entry
+        let entry = codebook.get(1);
+        let mut value = codebook.get(0);
+        value.codebook = entry.codebook;
+        value.codebook.coefficients[SH_CHANNELS] += 1e-6;
+        codebook.set(0, value);
+        assert_eq!(codebook.get(0).codebook, entry.codebook);
+
+        let decoded = Gaussian3d::from(codebook.get(0));
+        assert_eq!(decoded.position_visibility, value.position_visibility);
+    }
+
+    #[test]
+    fn test_codebook_codec() {
+        let gaussians = random_gaussians_3d_seeded(1000, 3);
+        let codebook = PlanarGaussian3dCodebook::from(&gaussians);
+
+        let encoded = codebook.encode().unwrap();
+        let decoded = PlanarGaussian3dCodebook::decode(encoded.as_slice()).unwrap();
+
+        assert_eq!(codebook, decoded);
+        assert_eq!(
+            PlanarGaussian3d::from(&PlanarGaussian3dCodebook::from_interleaved(
+                codebook.to_interleaved()
+            )),
+            PlanarGaussian3d::from(&codebook)
+        );
+    }
+}
+
+mod lod {
+    use bevy::math::{Mat3, Vec3, Vec4};
+    use bevy_gaussian_splatting::{
+        Gaussian3d, LodCloudBuilder, PlanarGaussian3dLod,
+        gaussian::{
+            covariance::{compute_covariance_3d, decompose_covariance_3d},
+            formats::planar_3d_lod::{LOD_NO_PARENT, merge_gaussians},
+        },
+        io::codec::CloudCodec,
+        random_gaussians_3d_seeded,
+        stream::resident::restream_slots,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn isotropic(position: [f32; 3], scale: f32) -> Gaussian3d {
+        let mut gaussian = Gaussian3d::default();
+        gaussian.position_visibility.position = position;
+        gaussian.rotation.rotation = [1.0, 0.0, 0.0, 0.0];
+        gaussian.scale_opacity.scale = [scale; 3];
+        gaussian.scale_opacity.opacity = 0.5;
+        gaussian
+    }
+
+    #[test]
+    fn test_lod_hierarchy_structure() {
+        let count = 1000;
+
+        let gaussians = random_gaussians_3d_seeded(count, 42);
+        let lod = LodCloudBuilder::default()
+            .branching_factor(4)
+            .build(&gaussians);
+
+        assert_eq!(lod.leaf_count(), count);
+        assert_eq!(lod.nodes.len(), lod.gaussians.len());
+        assert_eq!(lod.level_cut(lod.level_count() - 1).len(), 1);
+
+        for (index, node) in lod.nodes.iter().enumerate() {
+            if node.parent == LOD_NO_PARENT {
+                assert_eq!(node.level as usize, lod.level_count() - 1);
+                continue;
+            }
+
+            let parent = &lod.nodes[node.parent as usize];
+            assert!(parent.children().contains(&index));
+            assert_eq!(parent.level, node.level + 1);
+            assert!(parent.radius >= node.radius);
+        }
+
+        let mut leaves = lod.level_cut(0);
+        leaves.sort_unstable();
+        assert_eq!(leaves, (0..count as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_lod_moment_matching() {
+        let merged = merge_gaussians(&[
+            isotropic([-1.0, 0.0, 0.0], 0.5),
+            isotropic([1.0, 0.0, 0.0], 0.5),
+        ]);
+
+        let position = Vec3::from(merged.position_visibility.position);
+        assert!(position.length() < 1e-5, "mean {position}");
+
+        // variance along x is the child variance plus the squared offset
+        let mut scale = merged.scale_opacity.scale;
+        scale.sort_by(f32::total_cmp);
+        assert!(
+            (scale[2] - (0.25_f32 + 1.0).sqrt()).abs() < 1e-4,
+            "{scale:?}"
+        );
+        assert!((scale[0] - 0.5).abs() < 1e-4, "{scale:?}");
+        assert!((scale[1] - 0.5).abs() < 1e-4, "{scale:?}");
+
+        let identical = merge_gaussians(&[isotropic([0.0; 3], 0.5), isotropic([0.0; 3], 0.5)]);
+        assert!((identical.scale_opacity.opacity - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_covariance_decomposition_round_trip() {
+        let gaussians = random_gaussians_3d_seeded(100, 7);
+
+        for gaussian in gaussians.iter() {
+            let rotation = Vec4::from(gaussian.rotation.rotation).normalize();
+            let scale = Vec3::from(gaussian.scale_opacity.scale);
+            let sigma = compute_covariance_3d(rotation, scale);
+            let sigma = Mat3::from_cols_array(&[
+                sigma[0], sigma[1], sigma[2], sigma[1], sigma[3], sigma[4], sigma[2], sigma[4],
+                sigma[5],
+            ]);
+
+            let (rotation, scale) = decompose_covariance_3d(sigma);
+            let [a, b, c, d, e, f] = compute_covariance_3d(rotation, scale);
+            let reconstructed = Mat3::from_cols_array(&[a, b, c, b, d, e, c, e, f]);
+
+            for (expected, actual) in sigma
+                .to_cols_array()
+                .iter()
+                .zip(reconstructed.to_cols_array().iter())
+            {
+                assert!(
+                    (expected - actual).abs() < 1e-3,
+                    "{sigma} != {reconstructed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lod_screen_space_cut() {
+        // a dense cloud of large splats, their extent is not an error of the merged parents
+        let mut gaussians = random_gaussians_3d_seeded(2000, 3);
+        for position_visibility in gaussians.position_visibility.iter_mut() {
+            position_visibility.position = (Vec3::from(position_visibility.position) * 0.05).into();
+        }
+        let lod = PlanarGaussian3dLod::from(&gaussians);
+
+        let far = lod.screen_space_cut(Vec3::new(0.0, 0.0, 1000.0), 1000.0, 1.0);
+        let near = lod.screen_space_cut(Vec3::new(0.0, 0.0, 5.0), 1000.0, 1.0);
+        let exact = lod.screen_space_cut(Vec3::new(0.0, 0.0, 5.0), 1000.0, 0.0);
+
+        assert!(far.len() < near.len());
+        assert_eq!(exact.len(), lod.leaf_count());
+
+        // leaves carry no error, their extent alone never forces a refinement
+        assert!(
+            lod.level_cut(0)
+                .iter()
+                .all(|&leaf| lod.nodes[leaf as usize].error == 0.0)
+        );
+
+        // a cut covers every leaf exactly once
+        for cut in [&far, &near] {
+            let mut covered = vec![0; lod.leaf_count()];
+            let mut stack = cut.clone();
+            while let Some(index) = stack.pop() {
+                let node = &lod.nodes[index as usize];
+                if node.is_leaf() {
+                    covered[index as usize] += 1;
+                } else {
+                    stack.extend(node.children().map(|child| child as u32));
+                }
+            }
+            assert!(covered.iter().all(|&count| count == 1));
+        }
+
+        assert_eq!(lod.cut_cloud(&far).len(), far.len());
+    }
+
+    #[test]
+    fn test_restream_slots() {
+        let mut resident = vec![0, 1, 2, 3, 4];
+
+        // kept nodes stay in their slot, entering nodes take the leaving slots
+        let dirty = restream_slots(&mut resident, &[0, 5, 2, 6, 4]);
+        assert_eq!(resident, vec![0, 5, 2, 6, 4]);
+        assert_eq!(dirty, vec![1, 3]);
+
+        // a coarser cut moves the tail into the holes
+        let dirty = restream_slots(&mut resident, &[0, 4, 7]);
+        assert_eq!(resident, vec![0, 7, 4]);
+        assert_eq!(dirty, vec![1, 2]);
+
+        // a finer cut appends past the resident length
+        let dirty = restream_slots(&mut resident, &[0, 4, 7, 8, 9]);
+        assert_eq!(resident, vec![0, 7, 4, 8, 9]);
+        assert_eq!(dirty, vec![3, 4]);
+
+        assert!(restream_slots(&mut resident, &[9, 8, 7, 4, 0]).is_empty());
+    }
+
+    #[test]
+    fn test_lod_codec() {
+        let gaussians = random_gaussians_3d_seeded(500, 11);
+        let lod = PlanarGaussian3dLod::from(&gaussians);
+
+        let decoded = PlanarGaussian3dLod::decode(lod.encode().unwrap().as_slice()).unwrap();
+        assert_eq!(lod, decoded);
+    }
+}
+
+mod temporal_hierarchy {
+    use bevy::prelude::*;
+    use bevy_gaussian_splatting::{
+        CloudSettings, GaussianMode, PlanarGaussian4d, PlanarGaussian4dHandle,
+        TemporalGaussianHierarchy, TemporalGaussianHierarchyHandle, TemporalHierarchyBuilder,
+        TemporalStreaming, gaussian::formats::planar_4d_hierarchy::temporal_support,
+        io::codec::CloudCodec, random_gaussians_4d_seeded, sort::SortedEntries,
+        stream::hierarchy::TemporalHierarchyPlugin,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn test_cloud() -> PlanarGaussian4d {
+        // spread timestamps over ten seconds with short temporal support
+        random_gaussians_4d_seeded(2000, 42)
+            .to_interleaved()
+            .into_iter()
+            .map(|mut gaussian| {
+                gaussian.timestamp_timescale.timestamp *= 10.0;
+                gaussian.timestamp_timescale.timescale *= 0.1;
+                gaussian.scale_opacity.scale = gaussian.scale_opacity.scale.map(|s| s * 0.1);
+                gaussian
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_temporal_levels_partition_cloud() {
+        let cloud = test_cloud();
+        let hierarchy = TemporalHierarchyBuilder::default()
+            .level_duration(1.0)
+            .build(&cloud);
+
+        hierarchy.validate().expect("invalid hierarchy");
+        assert_eq!(hierarchy.flat_cloud.len(), cloud.len());
+        assert_eq!(hierarchy.levels.len(), 10);
+        assert_eq!(
+            hierarchy
+                .levels
+                .iter()
+                .map(|level| level.instance_count)
+                .sum::<usize>(),
+            cloud.len()
+        );
+    }
+
+    #[test]
+    fn test_temporal_levels_overlapping_time() {
+        let hierarchy = TemporalGaussianHierarchy::from(&test_cloud());
+
+        for time in [0.0, 2.5, 5.5, 9.9] {
+            let resident = hierarchy.levels_overlapping(time, time);
+            assert!(!resident.is_empty());
+            assert!(resident.len() < hierarchy.levels.len());
+
+            // every gaussian passing the shader's temporal mask at `time` is resident
+            for (index, level) in hierarchy.levels.iter().enumerate() {
+                for instance in level.instances() {
+                    let gaussian = hierarchy.flat_cloud.get(instance);
+                    let dt = (time - gaussian.timestamp_timescale.timestamp).abs();
+
+                    if dt <= temporal_support(&gaussian) {
+                        assert!(resident.contains(&index), "level {index} missing at {time}");
+                    }
+                }
+            }
+
+            let resident_cloud = hierarchy.resident_cloud(&resident);
+            let expected = resident
+                .iter()
+                .map(|&level| hierarchy.levels[level].instance_count)
+                .sum::<usize>();
+            assert_eq!(resident_cloud.len(), expected);
+        }
+    }
+
+    #[test]
+    fn test_temporal_level_validation() {
+        let hierarchy = TemporalGaussianHierarchy::from(&test_cloud());
+
+        let mut gap = hierarchy.clone();
+        gap.levels[1].instance_offset += 1;
+        assert!(gap.validate().is_err());
+
+        let mut uncovered = hierarchy.clone();
+        uncovered.levels.pop();
+        assert!(uncovered.validate().is_err());
+
+        let mut inverted = hierarchy.clone();
+        let level = &mut inverted.levels[0];
+        std::mem::swap(&mut level.time_start, &mut level.time_stop);
+        assert!(inverted.validate().is_err());
+
+        let mut narrowed = hierarchy.clone();
+        narrowed.levels[0].time_stop = narrowed.levels[0].time_start;
+        assert!(narrowed.validate().is_err());
+    }
+
+    #[derive(Resource, Default)]
+    struct ModifiedClouds(usize);
+
+    fn count_modified_clouds(
+        mut events: MessageReader<AssetEvent<PlanarGaussian4d>>,
+        mut modified: ResMut<ModifiedClouds>,
+    ) {
+        modified.0 += events
+            .read()
+            .filter(|event| matches!(event, AssetEvent::Modified { .. }))
+            .count();
+    }
+
+    fn resident_timestamps(app: &App, entity: Entity) -> Vec<f32> {
+        let handle = app.world().get::<PlanarGaussian4dHandle>(entity).unwrap();
+        let cloud = app
+            .world()
+            .resource::<Assets<PlanarGaussian4d>>()
+            .get(&handle.0)
+            .unwrap();
+
+        let mut timestamps = cloud
+            .timestamp_timescale
+            .iter()
+            .map(|timestamp_timescale| timestamp_timescale.timestamp)
+            .collect::<Vec<_>>();
+        timestamps.sort_by(f32::total_cmp);
+        timestamps
+    }
+
+    fn level_timestamps(hierarchy: &TemporalGaussianHierarchy, levels: &[usize]) -> Vec<f32> {
+        let mut timestamps = levels
+            .iter()
+            .flat_map(|&level| hierarchy.levels[level].instances())
+            .map(|instance| hierarchy.flat_cloud.timestamp_timescale[instance].timestamp)
+            .collect::<Vec<_>>();
+        timestamps.sort_by(f32::total_cmp);
+        timestamps
+    }
+
+    #[test]
+    fn test_temporal_streaming_in_place() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TemporalHierarchyPlugin,
+        ));
+        app.init_asset::<PlanarGaussian4d>()
+            .init_asset::<SortedEntries>()
+            .init_resource::<ModifiedClouds>()
+            .add_systems(Last, count_modified_clouds);
+
+        let hierarchy = TemporalGaussianHierarchy::from(&test_cloud());
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<TemporalGaussianHierarchy>>()
+            .add(hierarchy.clone());
+        let entity = app
+            .world_mut()
+            .spawn((
+                TemporalGaussianHierarchyHandle(handle),
+                CloudSettings {
+                    time: 0.5,
+                    ..default()
+                },
+            ))
+            .id();
+
+        app.update();
+
+        // the mode follows the hierarchy without opting in
+        let settings = app.world().get::<CloudSettings>(entity).unwrap();
+        assert_eq!(settings.gaussian_mode, GaussianMode::Gaussian4d);
+
+        let streaming = app.world().get::<TemporalStreaming>(entity).unwrap();
+        assert_eq!(
+            resident_timestamps(&app, entity),
+            level_timestamps(&hierarchy, streaming.resident_levels())
+        );
+
+        app.world_mut().resource_mut::<ModifiedClouds>().0 = 0;
+        app.world_mut()
+            .get_mut::<CloudSettings>(entity)
+            .unwrap()
+            .time = 5.5;
+        app.update();
+
+        let streaming = app.world().get::<TemporalStreaming>(entity).unwrap();
+        assert!(streaming.resident_levels().contains(&5));
+        assert_eq!(
+            resident_timestamps(&app, entity),
+            level_timestamps(&hierarchy, streaming.resident_levels())
+        );
+
+        // level changes stream into the resident cloud instead of replacing the asset
+        if cfg!(feature = "buffer_storage") {
+            assert_eq!(app.world().resource::<ModifiedClouds>().0, 0);
+        }
+    }
+
+    #[test]
+    fn test_temporal_hierarchy_codec() {
+        let hierarchy = TemporalGaussianHierarchy::from(&test_cloud());
+
+        let decoded =
+            TemporalGaussianHierarchy::decode(hierarchy.encode().unwrap().as_slice()).unwrap();
+        assert_eq!(hierarchy, decoded);
+    }
+}
+
+mod quantized_4d {
+    use bevy_gaussian_splatting::{
+        PlanarGaussian3d, PlanarGaussian4d, PlanarGaussian4dQuantized,
+        io::{
+            codec::{CloudCodec, CloudCodecError},
+            gcloud::header::{GcloudContainer, GcloudHeader, GcloudMetadata},
+        },
+        material::spherindrical_harmonics::SH_4D_COEFF_COUNT,
+        random_gaussians_3d_seeded, random_gaussians_4d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    // f16 keeps 11 significant bits
+    fn assert_half_close(expected: f32, actual: f32, label: &str) {
+        assert!(
+            (expected - actual).abs() <= expected.abs() * 1e-3 + 1e-4,
+            "{label}: expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_quantized_4d_error_bounds() {
+        let gaussians = random_gaussians_4d_seeded(500, 42);
+        let quantized = PlanarGaussian4dQuantized::from(&gaussians);
+        let decoded = PlanarGaussian4d::from(&quantized);
+
+        assert_eq!(gaussians.len(), quantized.len());
+        assert_eq!(gaussians.len(), decoded.len());
+
+        for index in 0..gaussians.len() {
+            let expected = gaussians.get(index);
+            let actual = decoded.get(index);
+
+            assert_eq!(expected.position_visibility, actual.position_visibility);
+
+            for i in 0..SH_4D_COEFF_COUNT {
+                assert_half_close(
+                    expected.spherindrical_harmonic.get(i),
+                    actual.spherindrical_harmonic.get(i),
+                    "spherindrical harmonic",
+                );
+            }
+
+            for i in 0..4 {
+                assert_half_close(
+                    expected.isotropic_rotations.rotation[i],
+                    actual.isotropic_rotations.rotation[i],
+                    "rotation",
+                );
+                assert_half_close(
+                    expected.isotropic_rotations.rotation_r[i],
+                    actual.isotropic_rotations.rotation_r[i],
+                    "rotation_r",
+                );
+            }
+
+            for i in 0..3 {
+                assert_half_close(
+                    expected.scale_opacity.scale[i],
+                    actual.scale_opacity.scale[i],
+                    "scale",
+                );
+            }
+            assert_half_close(
+                expected.scale_opacity.opacity,
+                actual.scale_opacity.opacity,
+                "opacity",
+            );
+            assert_half_close(
+                expected.timestamp_timescale.timestamp,
+                actual.timestamp_timescale.timestamp,
+                "timestamp",
+            );
+            assert_half_close(
+                expected.timestamp_timescale.timescale,
+                actual.timestamp_timescale.timescale,
+                "timescale",
+            );
+        }
+    }
+
+    #[test]
+    fn test_quantized_4d_codec() {
+        let quantized = PlanarGaussian4dQuantized::from(&random_gaussians_4d_seeded(500, 3));
+
+        let decoded =
+            PlanarGaussian4dQuantized::decode(quantized.encode().unwrap().as_slice()).unwrap();
+        assert_eq!(quantized, decoded);
+    }
+
+    #[test]
+    fn test_quantized_4d_container() {
+        let gaussians = random_gaussians_4d_seeded(500, 5);
+        let quantized = PlanarGaussian4dQuantized::from(&gaussians);
+
+        let mut encoded = Vec::new();
+        quantized
+            .encode_container(&mut encoded, &GcloudMetadata::new())
+            .unwrap();
+
+        // the full precision reader detects the quantized payload from the header
+        let (header, decoded) = PlanarGaussian4d::decode_container(encoded.as_slice()).unwrap();
+        assert!(header.unwrap().quantized);
+        assert_eq!(decoded, PlanarGaussian4d::from(&quantized));
+
+        let (_, decoded) = PlanarGaussian4dQuantized::decode_container(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, quantized);
+
+        let mut encoded = Vec::new();
+        gaussians
+            .encode_container(&mut encoded, &GcloudMetadata::new())
+            .unwrap();
+
+        let (header, decoded) =
+            PlanarGaussian4dQuantized::decode_container(encoded.as_slice()).unwrap();
+        assert!(!header.unwrap().quantized);
+        assert_eq!(decoded, quantized);
+    }
+
+    #[test]
+    fn test_quantized_flag_without_variant() {
+        let gaussians = random_gaussians_3d_seeded(100, 5);
+        let header = GcloudHeader {
+            quantized: true,
+            ..gaussians.header()
+        };
+
+        let mut encoded = Vec::new();
+        header.write_to(&mut encoded).unwrap();
+        gaussians.encode_to(&mut encoded).unwrap();
+
+        assert!(matches!(
+            PlanarGaussian3d::decode_container(encoded.as_slice()),
+            Err(CloudCodecError::Header(_)),
+        ));
+    }
+}
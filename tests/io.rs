@@ -8,8 +8,8 @@ fn test_codec_3d() {
     let count = 10000;
 
     let gaussians = random_gaussians_3d(count);
-    let encoded = gaussians.encode();
-    let decoded = PlanarGaussian3d::decode(encoded.as_slice());
+    let encoded = gaussians.encode().unwrap();
+    let decoded = PlanarGaussian3d::decode(encoded.as_slice()).unwrap();
 
     assert_eq!(gaussians, decoded);
 }
@@ -19,8 +19,2455 @@ fn test_codec_4d() {
     let count = 10000;
 
     let gaussians = random_gaussians_4d(count);
-    let encoded = gaussians.encode();
-    let decoded = PlanarGaussian4d::decode(encoded.as_slice());
+    let encoded = gaussians.encode().unwrap();
+    let decoded = PlanarGaussian4d::decode(encoded.as_slice()).unwrap();
 
     assert_eq!(gaussians, decoded);
 }
+
+#[test]
+fn test_codec_streamed_file() {
+    let gaussians = random_gaussians_3d(1000);
+    let path = std::env::temp_dir().join("bevy_gaussian_splatting_streamed.gcloud");
+
+    gaussians.write_to_file(&path).unwrap();
+    let decoded = PlanarGaussian3d::read_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(gaussians, decoded);
+}
+
+#[test]
+fn test_codec_corrupt_data() {
+    let gaussians = random_gaussians_4d(1000);
+    let encoded = gaussians.encode().unwrap();
+
+    assert!(PlanarGaussian4d::decode(&encoded[..encoded.len() / 2]).is_err());
+    assert!(PlanarGaussian4d::decode(&[0xde, 0xad, 0xbe, 0xef]).is_err());
+    assert!(PlanarGaussian4d::decode(&[]).is_err());
+}
+
+mod container {
+    use bevy_gaussian_splatting::{
+        PlanarGaussian3d, PlanarGaussian4d,
+        io::{
+            codec::{CloudCodec, CloudCodecError},
+            gcloud::header::{GcloudContainer, GcloudHeader, GcloudMetadata},
+            settings::GaussianLoaderSettings,
+        },
+        material::spherical_harmonics::SH_DEGREE,
+        random_gaussians_3d, random_gaussians_4d,
+    };
+    #[cfg(feature = "io_flexbuffers")]
+    use bevy_gaussian_splatting::{
+        gaussian::f32::{PositionVisibility, Rotation, ScaleOpacity},
+        material::spherical_harmonics::SH_CHANNELS,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    #[test]
+    fn test_container_roundtrip() {
+        let gaussians = random_gaussians_3d(1000);
+        let metadata = GcloudMetadata::from([("source".to_owned(), "test".to_owned())]);
+
+        let mut encoded = Vec::new();
+        gaussians.encode_container(&mut encoded, &metadata).unwrap();
+        let (header, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+        let header = header.expect("container header");
+
+        assert_eq!(gaussians, decoded);
+        assert_eq!(header.count, 1000);
+        assert_eq!(header.sh_degree as usize, SH_DEGREE);
+        assert_eq!(header.metadata, metadata);
+        assert!(header.aabb.is_some());
+    }
+
+    #[test]
+    fn test_container_default_settings_load_is_exact() {
+        let mut gaussians = random_gaussians_3d(1024).iter().collect::<Vec<_>>();
+        // a scale spread the raw ply clamp would narrow
+        gaussians[0].scale_opacity.scale = [1e-4, 1e-4, 10.0];
+        let gaussians = PlanarGaussian3d::from_interleaved(gaussians);
+
+        let mut encoded = Vec::new();
+        gaussians
+            .encode_container(&mut encoded, &GcloudMetadata::new())
+            .unwrap();
+        let (_, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+
+        let loaded = GaussianLoaderSettings::default().apply_3d(decoded);
+        assert_eq!(loaded, gaussians);
+
+        // unaligned clouds are only padded
+        let unaligned = PlanarGaussian3d::from_interleaved(gaussians.iter().take(1000).collect());
+        let loaded = GaussianLoaderSettings::default().apply_3d(unaligned.clone());
+        assert_eq!(loaded.len(), 1024);
+        for index in 0..1000 {
+            assert_eq!(loaded.get(index), unaligned.get(index));
+        }
+    }
+
+    #[test]
+    fn test_container_legacy_payload() {
+        let gaussians = random_gaussians_3d(1000);
+        let encoded = gaussians.encode().unwrap();
+
+        let (header, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+
+        assert!(header.is_none());
+        assert_eq!(gaussians, decoded);
+    }
+
+    #[test]
+    fn test_container_kind_mismatch() {
+        let gaussians = random_gaussians_3d(100);
+
+        let mut encoded = Vec::new();
+        gaussians
+            .encode_container(&mut encoded, &GcloudMetadata::new())
+            .unwrap();
+
+        assert!(matches!(
+            PlanarGaussian4d::decode_container(encoded.as_slice()),
+            Err(CloudCodecError::Header(_)),
+        ));
+    }
+
+    #[test]
+    fn test_container_sh_degree_mismatch() {
+        let foreign_degree = if SH_DEGREE == 0 { 3 } else { 0 };
+
+        let gaussians = random_gaussians_4d(100);
+        let header = GcloudHeader {
+            sh_degree: foreign_degree,
+            ..gaussians.header()
+        };
+        let mut encoded = Vec::new();
+        header.write_to(&mut encoded).unwrap();
+        gaussians.encode_to(&mut encoded).unwrap();
+
+        assert!(matches!(
+            PlanarGaussian4d::decode_container(encoded.as_slice()),
+            Err(CloudCodecError::ShDegreeMismatch { .. }),
+        ));
+    }
+
+    /// payload of a build compiled at sh degree 0, each gaussian stores its dc coefficients only
+    #[cfg(feature = "io_flexbuffers")]
+    #[derive(serde::Serialize)]
+    struct DegreeZeroCloud {
+        position_visibility: Vec<PositionVisibility>,
+        spherical_harmonic: Vec<DegreeZeroHarmonics>,
+        rotation: Vec<Rotation>,
+        scale_opacity: Vec<ScaleOpacity>,
+    }
+
+    #[cfg(feature = "io_flexbuffers")]
+    #[derive(serde::Serialize)]
+    struct DegreeZeroHarmonics {
+        coefficients: Vec<f32>,
+    }
+
+    #[cfg(feature = "io_flexbuffers")]
+    #[test]
+    fn test_container_sh_degree() {
+        let gaussians = random_gaussians_3d(100);
+        let payload = DegreeZeroCloud {
+            position_visibility: gaussians.position_visibility.clone(),
+            spherical_harmonic: gaussians
+                .spherical_harmonic
+                .iter()
+                .map(|harmonics| DegreeZeroHarmonics {
+                    coefficients: harmonics.coefficients[..SH_CHANNELS].to_vec(),
+                })
+                .collect(),
+            rotation: gaussians.rotation.clone(),
+            scale_opacity: gaussians.scale_opacity.clone(),
+        };
+
+        let header = GcloudHeader {
+            sh_degree: 0,
+            ..gaussians.header()
+        };
+        let mut encoded = Vec::new();
+        header.write_to(&mut encoded).unwrap();
+        encoded.extend(flexbuffers::to_vec(&payload).unwrap());
+
+        let (_, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+        assert_eq!(decoded.position_visibility, gaussians.position_visibility);
+        for (expected, actual) in gaussians
+            .spherical_harmonic
+            .iter()
+            .zip(decoded.spherical_harmonic.iter())
+        {
+            assert_eq!(
+                expected.coefficients[..SH_CHANNELS],
+                actual.coefficients[..SH_CHANNELS]
+            );
+            assert!(
+                actual.coefficients[SH_CHANNELS..]
+                    .iter()
+                    .all(|&value| value == 0.0)
+            );
+        }
+    }
+
+    // bincode and entropy payloads have a fixed coefficient count, a foreign degree cannot be read
+    #[cfg(feature = "io_bincode2")]
+    #[test]
+    fn test_container_sh_degree_bincode() {
+        use bevy_gaussian_splatting::io::gcloud::{
+            entropy::GcloudEntropySettings, header::GcloudCodecId,
+        };
+
+        let foreign_degree = if SH_DEGREE == 0 { 3 } else { 0 };
+        let gaussians = random_gaussians_3d(100);
+
+        let header = GcloudHeader {
+            sh_degree: foreign_degree,
+            codec: GcloudCodecId::Bincode2Gzip,
+            ..gaussians.header()
+        };
+        let mut encoded = Vec::new();
+        header.write_to(&mut encoded).unwrap();
+        encoded.extend(bincode2::serialize(&gaussians).unwrap());
+
+        assert!(matches!(
+            PlanarGaussian3d::decode_container(encoded.as_slice()),
+            Err(CloudCodecError::ShDegreeMismatch { .. }),
+        ));
+
+        let header = GcloudHeader {
+            sh_degree: foreign_degree,
+            codec: GcloudCodecId::Entropy,
+            ..gaussians.header()
+        };
+        let mut encoded = Vec::new();
+        header.write_to(&mut encoded).unwrap();
+        gaussians
+            .encode_entropy_to(&mut encoded, &GcloudEntropySettings::default())
+            .unwrap();
+
+        assert!(matches!(
+            PlanarGaussian3d::decode_container(encoded.as_slice()),
+            Err(CloudCodecError::ShDegreeMismatch { .. }),
+        ));
+    }
+
+    #[cfg(all(feature = "io_bincode2", feature = "io_flexbuffers"))]
+    #[test]
+    fn test_container_bincode_payload() {
+        use bevy_gaussian_splatting::io::gcloud::header::GcloudCodecId;
+
+        let gaussians = random_gaussians_3d(100);
+        let header = GcloudHeader {
+            codec: GcloudCodecId::Bincode2Gzip,
+            ..gaussians.header()
+        };
+        let mut encoded = Vec::new();
+        header.write_to(&mut encoded).unwrap();
+        encoded.extend(bincode2::serialize(&gaussians).unwrap());
+
+        let (_, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+        assert_eq!(gaussians, decoded);
+    }
+}
+
+#[cfg(feature = "io_spz")]
+mod spz {
+    use bevy_gaussian_splatting::{
+        Gaussian3d,
+        gaussian::formats::planar_3d_spz::{
+            PlanarGaussian3dSpz, SPZ_MAX_FRACTIONAL_BITS, SpzHeader,
+        },
+        io::{
+            settings::GaussianLoaderSettings,
+            spz::{decode_spz_3d, encode_spz_3d},
+        },
+        material::spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL},
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    #[test]
+    fn test_spz_round_trip() {
+        let count = 1000;
+
+        let gaussians = random_gaussians_3d_seeded(count, 42);
+        let encoded = encode_spz_3d(&gaussians).expect("failed to encode spz");
+        let decoded = decode_spz_3d(encoded.as_slice()).expect("failed to decode spz");
+
+        assert_eq!(gaussians.len(), decoded.len());
+
+        let sh_coefficients = SH_COEFF_COUNT_PER_CHANNEL.min(16) * SH_CHANNELS;
+        let min_log_scale = -10.0_f32;
+
+        for (expected, actual) in gaussians.iter().zip(decoded.iter()) {
+            for axis in 0..3 {
+                let error = (expected.position_visibility.position[axis]
+                    - actual.position_visibility.position[axis])
+                    .abs();
+                assert!(error <= 0.5 / 4096.0 + 1e-6, "position error {error}");
+
+                let expected_scale = expected.scale_opacity.scale[axis].ln().max(min_log_scale);
+                let actual_scale = actual.scale_opacity.scale[axis].ln();
+                let error = (expected_scale - actual_scale).abs();
+                assert!(error <= 0.5 / 16.0 + 1e-4, "log scale error {error}");
+            }
+
+            let error = (expected.scale_opacity.opacity - actual.scale_opacity.opacity).abs();
+            assert!(error <= 0.5 / 255.0 + 1e-6, "opacity error {error}");
+
+            let norm = expected
+                .rotation
+                .rotation
+                .iter()
+                .map(|v| v * v)
+                .sum::<f32>()
+                .sqrt();
+            let dot = expected
+                .rotation
+                .rotation
+                .iter()
+                .zip(actual.rotation.rotation.iter())
+                .map(|(a, b)| a / norm * b)
+                .sum::<f32>();
+            assert!(dot.abs() >= 0.999, "rotation dot {dot}");
+
+            for i in 0..sh_coefficients {
+                let tolerance = match i / SH_CHANNELS {
+                    0 => 0.5 / (255.0 * 0.15),
+                    1..=3 => 4.5 / 128.0,
+                    _ => 8.5 / 128.0,
+                };
+                let error = (expected.spherical_harmonic.coefficients[i]
+                    - actual.spherical_harmonic.coefficients[i])
+                    .abs();
+                assert!(error <= tolerance + 1e-4, "sh[{i}] error {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_spz_loader_padding() {
+        let count = 100;
+
+        let gaussians = random_gaussians_3d_seeded(count, 7);
+        let encoded = encode_spz_3d(&gaussians).expect("failed to encode spz");
+        let loaded = GaussianLoaderSettings::default()
+            .apply_3d(decode_spz_3d(encoded.as_slice()).expect("failed to decode spz"));
+
+        assert_eq!(loaded.len(), count.next_multiple_of(32));
+        assert!(
+            loaded
+                .iter()
+                .skip(count)
+                .all(|g| g == Gaussian3d::default())
+        );
+
+        let reencoded = encode_spz_3d(&loaded).expect("failed to encode spz");
+        assert_eq!(reencoded.len(), encoded.len());
+
+        let reloaded = decode_spz_3d(reencoded.as_slice()).expect("failed to decode spz");
+        assert_eq!(reloaded.len(), count);
+    }
+
+    #[test]
+    fn test_spz_rejects_invalid_data() {
+        assert!(decode_spz_3d(&[0u8; 32]).is_err());
+
+        let gaussians = random_gaussians_3d_seeded(64, 7);
+        let encoded = encode_spz_3d(&gaussians).expect("failed to encode spz");
+        assert!(decode_spz_3d(&encoded[..encoded.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_spz_rejects_out_of_range_fractional_bits() {
+        let header = SpzHeader {
+            fractional_bits: SPZ_MAX_FRACTIONAL_BITS + 1,
+            ..SpzHeader::default()
+        };
+        assert!(SpzHeader::from_bytes(&header.to_bytes()).is_err());
+
+        let header = SpzHeader {
+            fractional_bits: SPZ_MAX_FRACTIONAL_BITS,
+            ..SpzHeader::default()
+        };
+        assert_eq!(SpzHeader::from_bytes(&header.to_bytes()).unwrap(), header);
+
+        let gaussians = random_gaussians_3d_seeded(64, 7);
+        let mut payload = PlanarGaussian3dSpz::from(&gaussians).to_bytes();
+        payload[13] = 32;
+        assert!(PlanarGaussian3dSpz::from_bytes(&payload).is_err());
+    }
+}
+
+#[cfg(feature = "io_splat")]
+mod splat {
+    use bevy_gaussian_splatting::{
+        Gaussian3d, PlanarGaussian3d,
+        io::{
+            ksplat::decode_ksplat_3d,
+            settings::{GaussianLoaderSettings, MAX_SIZE_VARIANCE},
+            splat::{SPLAT_BYTES_PER_GAUSSIAN, decode_splat_3d, encode_splat_3d},
+        },
+        material::spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL},
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+    use half::f16;
+
+    const SH_C0: f32 = 0.282_094_8;
+
+    #[test]
+    fn test_splat_round_trip() {
+        let gaussians = random_gaussians_3d_seeded(256, 11);
+
+        let encoded = encode_splat_3d(&gaussians).expect("failed to encode splat");
+        assert_eq!(encoded.len(), gaussians.len() * SPLAT_BYTES_PER_GAUSSIAN);
+
+        let decoded = decode_splat_3d(encoded.as_slice()).expect("failed to decode splat");
+        assert_eq!(gaussians.len(), decoded.len());
+
+        for (expected, actual) in gaussians.iter().zip(decoded.iter()) {
+            assert_eq!(
+                expected.position_visibility.position,
+                actual.position_visibility.position
+            );
+            assert_eq!(expected.scale_opacity.scale, actual.scale_opacity.scale);
+
+            let error = (expected.scale_opacity.opacity - actual.scale_opacity.opacity).abs();
+            assert!(error <= 0.5 / 255.0 + 1e-6, "opacity error {error}");
+
+            for channel in 0..SH_CHANNELS {
+                let expected_color = 0.5 + SH_C0 * expected.spherical_harmonic.get(channel);
+                if !(0.0..=1.0).contains(&expected_color) {
+                    continue;
+                }
+
+                let error = (expected.spherical_harmonic.get(channel)
+                    - actual.spherical_harmonic.get(channel))
+                .abs();
+                assert!(error <= 0.5 / (255.0 * SH_C0) + 1e-4, "sh dc error {error}");
+            }
+
+            let norm = expected
+                .rotation
+                .rotation
+                .iter()
+                .map(|v| v * v)
+                .sum::<f32>()
+                .sqrt();
+            let dot = expected
+                .rotation
+                .rotation
+                .iter()
+                .zip(actual.rotation.rotation.iter())
+                .map(|(a, b)| a / norm * b)
+                .sum::<f32>();
+            assert!(dot.abs() >= 0.99, "rotation dot {dot}");
+        }
+    }
+
+    #[test]
+    fn test_splat_loader_settings() {
+        let mut gaussian = Gaussian3d::default();
+        gaussian.rotation.rotation = [1.0, 0.0, 0.0, 0.0];
+        gaussian.scale_opacity.scale = [0.01, 0.01, 10.0];
+        gaussian.scale_opacity.opacity = 1.0;
+        let cloud = PlanarGaussian3d::from_interleaved(vec![gaussian; 3]);
+
+        let decoded = decode_splat_3d(&encode_splat_3d(&cloud).expect("failed to encode splat"))
+            .expect("failed to decode splat");
+
+        // the default only pads, splat scales are stored activated
+        let settings = GaussianLoaderSettings::default();
+        let loaded = settings.apply_3d(decoded.clone());
+        assert_eq!(loaded.len(), 32);
+        assert_eq!(loaded.get(0), decoded.get(0));
+        assert_eq!(loaded.get(3), Gaussian3d::default());
+
+        // padding is redone, not stacked
+        assert_eq!(settings.apply_3d(loaded.clone()).len(), 32);
+
+        // an explicit variance clamps the log scale spread like the ply loader
+        let settings = GaussianLoaderSettings {
+            max_scale_variance: Some(MAX_SIZE_VARIANCE),
+            ..Default::default()
+        };
+        let loaded = settings.apply_3d(decoded.clone());
+        assert_eq!(loaded.len(), 32);
+        let [small, _, large] = loaded.get(0).scale_opacity.scale;
+        let mean = (2.0 * 0.01_f32.ln() + 10.0_f32.ln()) / 3.0;
+        assert!((small - 0.01).abs() < 1e-6, "{small}");
+        assert!((large - (mean + 4.0).exp()).abs() < 1e-4, "{large}");
+
+        // raw logits and log scales are activated
+        let settings = GaussianLoaderSettings {
+            activations_applied: Some(false),
+            max_scale_variance: Some(f32::INFINITY),
+            pad_to: 8,
+            ..Default::default()
+        };
+        let loaded = settings.apply_3d(decoded);
+        assert_eq!(loaded.len(), 8);
+        let activated = loaded.get(0).scale_opacity;
+        assert!((activated.opacity - 1.0 / (1.0 + (-1.0_f32).exp())).abs() < 1e-6);
+        assert!((activated.scale[2] - 10.0_f32.exp()).abs() < 1e-1);
+    }
+
+    #[test]
+    fn test_splat_loader_padding() {
+        let count = 100;
+
+        let gaussians = random_gaussians_3d_seeded(count, 7);
+        let encoded = encode_splat_3d(&gaussians).expect("failed to encode splat");
+        let loaded = GaussianLoaderSettings::default()
+            .apply_3d(decode_splat_3d(encoded.as_slice()).expect("failed to decode splat"));
+        assert_eq!(loaded.len(), count.next_multiple_of(32));
+
+        let reencoded = encode_splat_3d(&loaded).expect("failed to encode splat");
+        assert_eq!(reencoded.len(), count * SPLAT_BYTES_PER_GAUSSIAN);
+
+        let reloaded = decode_splat_3d(reencoded.as_slice()).expect("failed to decode splat");
+        assert_eq!(reloaded.len(), count);
+    }
+
+    #[test]
+    fn test_splat_rejects_invalid_data() {
+        assert!(decode_splat_3d(&[0u8; SPLAT_BYTES_PER_GAUSSIAN + 1]).is_err());
+    }
+
+    fn put(buffer: &mut [u8], offset: usize, bytes: &[u8]) {
+        buffer[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    struct KsplatSectionSpec {
+        splat_count: u32,
+        bucket_size: u32,
+        bucket_count: u32,
+        bucket_block_size: f32,
+        bucket_storage_size: u16,
+        full_bucket_count: u32,
+        partial_lengths: Vec<u32>,
+        sh_degree: u16,
+    }
+
+    fn ksplat_buffer(
+        compression_level: u16,
+        section: &KsplatSectionSpec,
+        buckets: &[[f32; 3]],
+        splats: &[u8],
+    ) -> Vec<u8> {
+        let mut buffer = vec![0u8; 4096 + 1024];
+
+        buffer[1] = 1;
+        put(&mut buffer, 4, &1u32.to_le_bytes());
+        put(&mut buffer, 8, &1u32.to_le_bytes());
+        put(&mut buffer, 12, &section.splat_count.to_le_bytes());
+        put(&mut buffer, 16, &section.splat_count.to_le_bytes());
+        put(&mut buffer, 20, &compression_level.to_le_bytes());
+
+        let base = 4096;
+        put(&mut buffer, base, &section.splat_count.to_le_bytes());
+        put(&mut buffer, base + 4, &section.splat_count.to_le_bytes());
+        put(&mut buffer, base + 8, &section.bucket_size.to_le_bytes());
+        put(&mut buffer, base + 12, &section.bucket_count.to_le_bytes());
+        put(
+            &mut buffer,
+            base + 16,
+            &section.bucket_block_size.to_le_bytes(),
+        );
+        put(
+            &mut buffer,
+            base + 20,
+            &section.bucket_storage_size.to_le_bytes(),
+        );
+        put(
+            &mut buffer,
+            base + 32,
+            &section.full_bucket_count.to_le_bytes(),
+        );
+        put(
+            &mut buffer,
+            base + 36,
+            &(section.partial_lengths.len() as u32).to_le_bytes(),
+        );
+        put(&mut buffer, base + 40, &section.sh_degree.to_le_bytes());
+
+        for length in section.partial_lengths.iter() {
+            buffer.extend_from_slice(&length.to_le_bytes());
+        }
+        for center in buckets.iter() {
+            for value in center.iter() {
+                buffer.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        buffer.extend_from_slice(splats);
+
+        buffer
+    }
+
+    #[test]
+    fn test_ksplat_uncompressed() {
+        let mut splats = Vec::new();
+        for index in 0..2 {
+            let offset = index as f32;
+            for value in [1.0 + offset, 2.0, 3.0, 0.1, 0.2, 0.3, 1.0, 0.0, 0.0, 0.0] {
+                splats.extend_from_slice(&f32::to_le_bytes(value));
+            }
+            splats.extend_from_slice(&[255, 128, 0, 51]);
+            for coefficient in 0..9 {
+                splats.extend_from_slice(&f32::to_le_bytes(coefficient as f32 * 0.1));
+            }
+        }
+
+        let section = KsplatSectionSpec {
+            splat_count: 2,
+            bucket_size: 0,
+            bucket_count: 0,
+            bucket_block_size: 0.0,
+            bucket_storage_size: 0,
+            full_bucket_count: 0,
+            partial_lengths: vec![],
+            sh_degree: 1,
+        };
+        let buffer = ksplat_buffer(0, &section, &[], &splats);
+
+        let decoded = decode_ksplat_3d(&buffer).expect("failed to decode ksplat");
+        assert_eq!(decoded.len(), 2);
+
+        let gaussian = decoded.get(1);
+        assert_eq!(gaussian.position_visibility.position, [2.0, 2.0, 3.0]);
+        assert_eq!(gaussian.scale_opacity.scale, [0.1, 0.2, 0.3]);
+        assert_eq!(gaussian.rotation.rotation, [1.0, 0.0, 0.0, 0.0]);
+        assert!((gaussian.scale_opacity.opacity - 0.2).abs() < 1e-6);
+        assert!((0.5 + SH_C0 * gaussian.spherical_harmonic.get(0) - 1.0).abs() < 1e-5);
+
+        if SH_COEFF_COUNT_PER_CHANNEL > 1 {
+            for index in 0..9 {
+                let actual = gaussian.spherical_harmonic.get(SH_CHANNELS + index);
+                assert!((actual - index as f32 * 0.1).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ksplat_compressed_buckets() {
+        let buckets = [[1.0, 2.0, 3.0], [-5.0, 0.0, 5.0]];
+        let offsets = [[0.5, -0.5, 0.0], [0.0, 0.25, -0.25], [1.0, -1.0, 0.5]];
+
+        let mut splats = Vec::new();
+        for offset in offsets.iter() {
+            for value in offset.iter() {
+                let quantized = (32767.0 + value * 32767.0_f32).round() as u16;
+                splats.extend_from_slice(&quantized.to_le_bytes());
+            }
+            for value in [0.5, 0.25, 0.125, 0.0, 1.0, 0.0, 0.0] {
+                splats.extend_from_slice(&f16::from_f32(value).to_bits().to_le_bytes());
+            }
+            splats.extend_from_slice(&[128, 128, 128, 255]);
+        }
+
+        let section = KsplatSectionSpec {
+            splat_count: 3,
+            bucket_size: 2,
+            bucket_count: 2,
+            bucket_block_size: 2.0,
+            bucket_storage_size: 12,
+            full_bucket_count: 1,
+            partial_lengths: vec![1],
+            sh_degree: 0,
+        };
+        let buffer = ksplat_buffer(1, &section, &buckets, &splats);
+
+        let decoded = decode_ksplat_3d(&buffer).expect("failed to decode ksplat");
+        assert_eq!(decoded.len(), 3);
+
+        for (index, offset) in offsets.iter().enumerate() {
+            let bucket = buckets[index / 2];
+            let gaussian = decoded.get(index);
+
+            for axis in 0..3 {
+                let expected = bucket[axis] + offset[axis];
+                let error = (gaussian.position_visibility.position[axis] - expected).abs();
+                assert!(error <= 1e-4, "position error {error}");
+            }
+
+            assert_eq!(gaussian.scale_opacity.scale, [0.5, 0.25, 0.125]);
+            assert_eq!(gaussian.rotation.rotation, [0.0, 1.0, 0.0, 0.0]);
+            assert_eq!(gaussian.scale_opacity.opacity, 1.0);
+        }
+
+        assert!(decode_ksplat_3d(&buffer[..buffer.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_ksplat_bucket_overflow() {
+        let section = KsplatSectionSpec {
+            splat_count: 1,
+            bucket_size: u32::MAX,
+            bucket_count: 1,
+            bucket_block_size: 2.0,
+            bucket_storage_size: 12,
+            full_bucket_count: u32::MAX,
+            partial_lengths: vec![u32::MAX; 3],
+            sh_degree: 0,
+        };
+        let buffer = ksplat_buffer(1, &section, &[[0.0; 3]], &[0; 24]);
+
+        let error = decode_ksplat_3d(&buffer).expect_err("overflowing buckets decoded");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[cfg(feature = "io_ply")]
+mod ply {
+    use bevy_gaussian_splatting::{
+        Gaussian3d, Gaussian4d, PlanarGaussian3d, PlanarGaussian4d,
+        io::ply::{PlyEncoding, parse_ply_3d, parse_ply_4d, write_ply_3d, write_ply_4d},
+        material::{
+            spherical_harmonics::{SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL},
+            spherindrical_harmonics::SH_4D_COEFF_COUNT_PER_CHANNEL,
+        },
+        random_gaussians_3d_seeded, random_gaussians_4d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+    use half::f16;
+
+    const SH_C0: f32 = 0.282_094_8;
+
+    fn assert_close(expected: f32, actual: f32, label: &str) {
+        let error = (expected - actual).abs();
+        assert!(
+            error <= 1e-4 * expected.abs().max(1.0),
+            "{label}: expected {expected}, got {actual}"
+        );
+    }
+
+    fn normalized(rotation: [f32; 4]) -> [f32; 4] {
+        let norm = rotation.iter().map(|v| v * v).sum::<f32>().sqrt();
+        rotation.map(|v| v / norm)
+    }
+
+    fn test_cloud_3d() -> PlanarGaussian3d {
+        // keep log scales within MAX_SIZE_VARIANCE of their mean so the loader does not clamp them
+        random_gaussians_3d_seeded(1000, 42)
+            .iter()
+            .map(|mut gaussian| {
+                gaussian.scale_opacity.scale = gaussian
+                    .scale_opacity
+                    .scale
+                    .map(|scale| 0.05 + 0.95 * scale);
+                gaussian
+            })
+            .collect()
+    }
+
+    fn assert_round_trip_3d(expected: &Gaussian3d, actual: &Gaussian3d) {
+        for axis in 0..3 {
+            assert_close(
+                expected.position_visibility.position[axis],
+                actual.position_visibility.position[axis],
+                "position",
+            );
+            assert_close(
+                expected.scale_opacity.scale[axis],
+                actual.scale_opacity.scale[axis],
+                "scale",
+            );
+        }
+
+        assert_close(
+            expected.scale_opacity.opacity,
+            actual.scale_opacity.opacity,
+            "opacity",
+        );
+
+        let rotation = normalized(expected.rotation.rotation);
+        for (expected, actual) in rotation.iter().zip(actual.rotation.rotation) {
+            assert_close(*expected, actual, "rotation");
+        }
+
+        for i in 0..SH_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS {
+            assert_close(
+                expected.spherical_harmonic.get(i),
+                actual.spherical_harmonic.get(i),
+                "spherical harmonic",
+            );
+        }
+    }
+
+    fn assert_round_trip_4d(expected: &Gaussian4d, actual: &Gaussian4d) {
+        for axis in 0..3 {
+            assert_close(
+                expected.position_visibility.position[axis],
+                actual.position_visibility.position[axis],
+                "position",
+            );
+            assert_close(
+                expected.scale_opacity.scale[axis],
+                actual.scale_opacity.scale[axis],
+                "scale",
+            );
+        }
+
+        assert_close(
+            expected.scale_opacity.opacity,
+            actual.scale_opacity.opacity,
+            "opacity",
+        );
+        assert_close(
+            expected.timestamp_timescale.timestamp,
+            actual.timestamp_timescale.timestamp,
+            "timestamp",
+        );
+        assert_close(
+            expected.timestamp_timescale.timescale,
+            actual.timestamp_timescale.timescale,
+            "timescale",
+        );
+
+        let rotation = normalized(expected.isotropic_rotations.rotation);
+        let rotation_r = normalized(expected.isotropic_rotations.rotation_r);
+        for i in 0..4 {
+            assert_close(
+                rotation[i],
+                actual.isotropic_rotations.rotation[i],
+                "rotation",
+            );
+            assert_close(
+                rotation_r[i],
+                actual.isotropic_rotations.rotation_r[i],
+                "rotation_r",
+            );
+        }
+
+        for i in 0..SH_4D_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS {
+            assert_close(
+                expected.spherindrical_harmonic.get(i),
+                actual.spherindrical_harmonic.get(i),
+                "spherindrical harmonic",
+            );
+        }
+    }
+
+    #[test]
+    fn test_ply_3d_round_trip() {
+        let gaussians = test_cloud_3d();
+
+        for encoding in [PlyEncoding::BinaryLittleEndian, PlyEncoding::Ascii] {
+            let write = |cloud: &PlanarGaussian3d| {
+                let mut encoded = Vec::new();
+                write_ply_3d(cloud, &mut encoded, encoding).expect("failed to write ply");
+                encoded
+            };
+            let parse = |encoded: Vec<u8>| {
+                parse_ply_3d(&mut std::io::Cursor::new(encoded)).expect("failed to parse ply")
+            };
+
+            let decoded = parse(write(&gaussians));
+
+            // the parser pads clouds to a multiple of 32 with empty gaussians
+            assert_eq!(decoded.len(), gaussians.len().next_multiple_of(32));
+            for index in 0..gaussians.len() {
+                assert_round_trip_3d(&gaussians.get(index), &decoded.get(index));
+            }
+            for index in gaussians.len()..decoded.len() {
+                assert_eq!(decoded.get(index), Gaussian3d::default());
+            }
+
+            // the padding is not written back, a load and write round trip keeps the cloud size
+            let rewritten = write(&decoded);
+            if encoding == PlyEncoding::BinaryLittleEndian {
+                assert_eq!(rewritten.len(), write(&gaussians).len());
+            }
+
+            let reloaded = parse(rewritten);
+            assert_eq!(reloaded.len(), decoded.len());
+            for index in 0..gaussians.len() {
+                assert_round_trip_3d(&decoded.get(index), &reloaded.get(index));
+            }
+            for index in gaussians.len()..reloaded.len() {
+                assert_eq!(reloaded.get(index), Gaussian3d::default());
+            }
+        }
+    }
+
+    #[test]
+    fn test_ply_3d_writes_logits_and_log_scales() {
+        let gaussian = Gaussian3d {
+            rotation: [1.0, 0.0, 0.0, 0.0].into(),
+            scale_opacity: [0.1, 0.2, 0.4, 0.75].into(),
+            ..Default::default()
+        };
+        let cloud: PlanarGaussian3d = vec![gaussian].into();
+
+        let mut encoded = Vec::new();
+        write_ply_3d(&cloud, &mut encoded, PlyEncoding::Ascii).expect("failed to write ply");
+        let text = String::from_utf8(encoded.clone()).expect("ascii ply should be utf8");
+
+        let (header, body) = text.split_once("end_header\n").expect("missing header");
+        let properties = header
+            .lines()
+            .filter_map(|line| line.strip_prefix("property float "))
+            .collect::<Vec<_>>();
+        let row = body
+            .split_whitespace()
+            .map(|value| value.parse::<f32>().expect("invalid ply value"))
+            .collect::<Vec<_>>();
+        let stored = |name: &str| row[properties.iter().position(|p| *p == name).unwrap()];
+
+        // the trainer convention read by `parse_ply_3d`
+        assert_close((0.75f32 / 0.25).ln(), stored("opacity"), "stored opacity");
+        for (axis, scale) in [0.1f32, 0.2, 0.4].into_iter().enumerate() {
+            assert_close(scale.ln(), stored(&format!("scale_{axis}")), "stored scale");
+        }
+
+        let decoded =
+            parse_ply_3d(&mut std::io::Cursor::new(encoded)).expect("failed to parse ply");
+        assert_round_trip_3d(&gaussian, &decoded.get(0));
+    }
+
+    #[test]
+    fn test_ply_4d_round_trip() {
+        let gaussians = random_gaussians_4d_seeded(1000, 42);
+
+        for encoding in [PlyEncoding::BinaryLittleEndian, PlyEncoding::Ascii] {
+            let write = |cloud: &PlanarGaussian4d| {
+                let mut encoded = Vec::new();
+                write_ply_4d(cloud, &mut encoded, encoding).expect("failed to write ply");
+                encoded
+            };
+            let parse = |encoded: Vec<u8>| {
+                parse_ply_4d(&mut std::io::Cursor::new(encoded)).expect("failed to parse ply")
+            };
+
+            let decoded = parse(write(&gaussians));
+
+            assert_eq!(decoded.len(), gaussians.len().next_multiple_of(32));
+            for index in 0..gaussians.len() {
+                assert_round_trip_4d(&gaussians.get(index), &decoded.get(index));
+            }
+            for index in gaussians.len()..decoded.len() {
+                assert_eq!(decoded.get(index), Gaussian4d::default());
+            }
+
+            let rewritten = write(&decoded);
+            if encoding == PlyEncoding::BinaryLittleEndian {
+                assert_eq!(rewritten.len(), write(&gaussians).len());
+            }
+
+            let reloaded = parse(rewritten);
+            assert_eq!(reloaded.len(), decoded.len());
+            for index in 0..gaussians.len() {
+                assert_round_trip_4d(&decoded.get(index), &reloaded.get(index));
+            }
+            for index in gaussians.len()..reloaded.len() {
+                assert_eq!(reloaded.get(index), Gaussian4d::default());
+            }
+        }
+    }
+
+    fn ascii_ply(properties: &[(&str, &str)], rows: &[Vec<String>]) -> Vec<u8> {
+        let mut ply = format!("ply\nformat ascii 1.0\nelement vertex {}\n", rows.len());
+        for (kind, name) in properties {
+            ply.push_str(&format!("property {kind} {name}\n"));
+        }
+        ply.push_str("end_header\n");
+        for row in rows {
+            ply.push_str(&row.join(" "));
+            ply.push('\n');
+        }
+
+        ply.into_bytes()
+    }
+
+    #[test]
+    fn test_ply_point_cloud_colors() {
+        let properties = [
+            ("double", "x"),
+            ("double", "y"),
+            ("double", "z"),
+            ("uchar", "red"),
+            ("uchar", "green"),
+            ("uchar", "blue"),
+        ];
+        let rows = [
+            vec!["0.5", "-1.25", "2", "255", "0", "128"],
+            vec!["1", "1", "1", "0", "255", "0"],
+        ]
+        .map(|row| row.into_iter().map(String::from).collect::<Vec<_>>());
+
+        let decoded = parse_ply_3d(&mut std::io::Cursor::new(ascii_ply(&properties, &rows)))
+            .expect("failed to parse point cloud ply");
+        let gaussian = decoded.get(0);
+
+        assert_eq!(gaussian.position_visibility.position, [0.5, -1.25, 2.0]);
+        assert_close(1.0, 0.5 + SH_C0 * gaussian.spherical_harmonic.get(0), "red");
+        assert_close(
+            0.0,
+            0.5 + SH_C0 * gaussian.spherical_harmonic.get(1),
+            "green",
+        );
+        assert_close(
+            128.0 / 255.0,
+            0.5 + SH_C0 * gaussian.spherical_harmonic.get(2),
+            "blue",
+        );
+        assert_eq!(gaussian.scale_opacity.opacity, 1.0);
+        assert!(
+            gaussian
+                .scale_opacity
+                .scale
+                .iter()
+                .all(|&scale| scale > 0.0)
+        );
+        assert_eq!(gaussian.rotation.rotation, [1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_ply_half_properties() {
+        let mut ply = b"ply\nformat binary_little_endian 1.0\nelement vertex 1\n\
+property half x\nproperty half y\nproperty float16 z\n\
+property uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n"
+            .to_vec();
+        for value in [0.5f32, -2.0, 1024.0] {
+            ply.extend_from_slice(&f16::from_f32(value).to_bits().to_le_bytes());
+        }
+        ply.extend_from_slice(&[255, 255, 255]);
+
+        let decoded = parse_ply_3d(&mut std::io::Cursor::new(ply)).expect("failed to parse ply");
+
+        assert_eq!(
+            decoded.get(0).position_visibility.position,
+            [0.5, -2.0, 1024.0]
+        );
+    }
+
+    #[test]
+    fn test_ply_half_properties_4d() {
+        let properties = [
+            ("x", 0.5f32),
+            ("y", -2.0),
+            ("z", 4.0),
+            ("t", 0.25),
+            ("st", 1.0),
+            ("sx", 1.0),
+            ("sy", 1.0),
+            ("sz", 1.0),
+            ("opacity", 1.0),
+            ("rot_x", 1.0),
+            ("rot_y", 0.0),
+            ("rot_z", 0.0),
+            ("rot_w", 0.0),
+            ("rot_r_x", 1.0),
+            ("rot_r_y", 0.0),
+            ("rot_r_z", 0.0),
+            ("rot_r_w", 0.0),
+        ];
+
+        let mut ply = b"ply\nformat binary_little_endian 1.0\nelement vertex 1\n".to_vec();
+        for (name, _) in properties {
+            ply.extend_from_slice(format!("property half {name}\n").as_bytes());
+        }
+        ply.extend_from_slice(b"end_header\n");
+        for (_, value) in properties {
+            ply.extend_from_slice(&f16::from_f32(value).to_bits().to_le_bytes());
+        }
+
+        let decoded = parse_ply_4d(&mut std::io::Cursor::new(ply)).expect("failed to parse ply");
+
+        let gaussian = decoded.get(0);
+        assert_eq!(gaussian.position_visibility.position, [0.5, -2.0, 4.0]);
+        assert_eq!(gaussian.timestamp_timescale.timestamp, 0.25);
+    }
+
+    #[test]
+    fn test_ply_2dgs() {
+        let gaussians = test_cloud_3d()
+            .iter()
+            .map(|mut gaussian| {
+                gaussian.scale_opacity.scale[2] = 0.0;
+                gaussian
+            })
+            .collect::<PlanarGaussian3d>();
+        assert!(gaussians.is_gaussian_2d());
+
+        let mut encoded = Vec::new();
+        write_ply_3d(&gaussians, &mut encoded, PlyEncoding::BinaryLittleEndian)
+            .expect("failed to write ply");
+
+        let decoded =
+            parse_ply_3d(&mut std::io::Cursor::new(encoded)).expect("failed to parse ply");
+
+        assert!(decoded.is_gaussian_2d());
+        assert!(!test_cloud_3d().is_gaussian_2d());
+        for index in 0..gaussians.len() {
+            assert_round_trip_3d(&gaussians.get(index), &decoded.get(index));
+        }
+    }
+
+    #[test]
+    fn test_ply_sh_degree_projection() {
+        // degree 3 file, 15 rest coefficients per channel stored channel major
+        let rest_count = 45;
+
+        let mut properties = [
+            "x", "y", "z", "f_dc_0", "f_dc_1", "f_dc_2", "opacity", "scale_0", "scale_1",
+            "scale_2", "rot_0", "rot_1", "rot_2", "rot_3",
+        ]
+        .map(|name| ("float", name.to_owned()))
+        .to_vec();
+        properties.extend((0..rest_count).map(|i| ("float", format!("f_rest_{i}"))));
+        let properties = properties
+            .iter()
+            .map(|(kind, name)| (*kind, name.as_str()))
+            .collect::<Vec<_>>();
+
+        let mut row = [
+            "0", "0", "0", "0.1", "0.2", "0.3", "0", "0", "0", "0", "1", "0", "0", "0",
+        ]
+        .map(String::from)
+        .to_vec();
+        row.extend((0..rest_count).map(|i| format!("{}", i as f32 * 0.01)));
+
+        let decoded = parse_ply_3d(&mut std::io::Cursor::new(ascii_ply(&properties, &[row])))
+            .expect("failed to parse ply");
+        let gaussian = decoded.get(0);
+
+        for coefficient in 1..SH_COEFF_COUNT_PER_CHANNEL.min(16) {
+            for channel in 0..SH_CHANNELS {
+                let expected = (channel * 15 + coefficient - 1) as f32 * 0.01;
+                assert_close(
+                    expected,
+                    gaussian
+                        .spherical_harmonic
+                        .get(coefficient * SH_CHANNELS + channel),
+                    "projected sh",
+                );
+            }
+        }
+    }
+}
+
+mod settings {
+    use bevy::math::{Quat, Vec3};
+    use bevy_gaussian_splatting::{
+        CameraConvention, Gaussian3d, Gaussian4d, GaussianLoaderSettings, Planar, UpAxis,
+        io::settings::isoclinic_spatial_rotation,
+        material::{
+            spherical_harmonics::{
+                SH_CHANNELS, SH_COEFF_COUNT_PER_CHANNEL, SH_DEGREE, num_sh_coefficients, sh_basis,
+            },
+            spherindrical_harmonics::SH_4D_DEGREE_TIME,
+        },
+    };
+
+    fn mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+        let (a, b) = (
+            Quat::from_xyzw(a[1], a[2], a[3], a[0]),
+            Quat::from_xyzw(b[1], b[2], b[3], b[0]),
+        );
+        let product = a * b;
+        [product.w, product.x, product.y, product.z]
+    }
+
+    fn conjugate([w, x, y, z]: [f32; 4]) -> [f32; 4] {
+        [w, -x, -y, -z]
+    }
+
+    // x -> l * x * conj(r), with (x, y, z, t) on the (1, i, j, k) axes
+    fn rotate_4d(left: [f32; 4], right: [f32; 4], point: [f32; 4]) -> [f32; 4] {
+        mul(mul(left, point), conjugate(right))
+    }
+
+    #[test]
+    fn test_axis_rotation() {
+        let z_up = GaussianLoaderSettings {
+            up_axis: UpAxis::Z,
+            ..Default::default()
+        };
+        assert!(
+            (z_up.axis_rotation() * Vec3::Z).abs_diff_eq(Vec3::Y, 1e-6),
+            "z up should map to y up"
+        );
+
+        let opencv = GaussianLoaderSettings {
+            camera_convention: CameraConvention::OpenCv,
+            ..Default::default()
+        };
+        assert!(
+            (opencv.axis_rotation() * Vec3::new(1.0, 2.0, 3.0))
+                .abs_diff_eq(Vec3::new(1.0, -2.0, -3.0), 1e-6)
+        );
+
+        assert!(GaussianLoaderSettings::default().is_passthrough());
+    }
+
+    #[test]
+    fn test_process_3d() {
+        let mut gaussian = Gaussian3d::default();
+        gaussian.position_visibility.position = [1.0, 2.0, 3.0];
+        gaussian.rotation.rotation = [1.0, 0.0, 0.0, 0.0];
+        gaussian.scale_opacity.scale = [0.1, 0.2, 0.3];
+        gaussian.scale_opacity.opacity = 0.9;
+        gaussian.spherical_harmonic.set(3, 1.0);
+
+        let mut faint = gaussian;
+        faint.scale_opacity.opacity = 0.01;
+
+        let settings = GaussianLoaderSettings {
+            up_axis: UpAxis::Z,
+            transform: bevy::prelude::Transform::from_xyz(0.0, 1.0, 0.0)
+                .with_scale(Vec3::splat(2.0)),
+            opacity_clamp: Some((0.0, 0.5)),
+            prune_opacity: Some(0.05),
+            sh_degree: Some(0),
+            ..Default::default()
+        };
+
+        let mut gaussians = vec![gaussian, faint];
+        settings.process_3d(&mut gaussians);
+
+        assert_eq!(gaussians.len(), 1);
+        let processed = gaussians[0];
+        assert!(
+            Vec3::from(processed.position_visibility.position)
+                .abs_diff_eq(Vec3::new(2.0, 7.0, -4.0), 1e-5)
+        );
+        assert!(
+            Vec3::from(processed.scale_opacity.scale).abs_diff_eq(Vec3::new(0.2, 0.4, 0.6), 1e-6)
+        );
+        assert_eq!(processed.scale_opacity.opacity, 0.5);
+        assert_eq!(processed.spherical_harmonic.get(3), 0.0);
+
+        let [w, x, y, z] = processed.rotation.rotation;
+        assert!(
+            Quat::from_xyzw(x, y, z, w).abs_diff_eq(settings.axis_rotation(), 1e-6),
+            "rotation should be composed with the axis conversion"
+        );
+    }
+
+    #[test]
+    fn test_process_4d_rotation() {
+        let rotation = Quat::from_euler(bevy::math::EulerRot::XYZ, 0.3, -1.1, 0.7);
+        let (left, right) = isoclinic_spatial_rotation(rotation);
+
+        let l = Quat::from_xyzw(0.1, 0.7, -0.2, 0.5).normalize();
+        let r = Quat::from_xyzw(-0.4, 0.1, 0.3, 0.8).normalize();
+        let (l, r) = ([l.w, l.x, l.y, l.z], [r.w, r.x, r.y, r.z]);
+
+        for axis in 0..4 {
+            let mut basis = [0.0; 4];
+            basis[axis] = 1.0;
+
+            let original = rotate_4d(l, r, basis);
+            let spatial = rotation * Vec3::new(original[0], original[1], original[2]);
+            let rotated = rotate_4d(mul(left, l), mul(right, r), basis);
+
+            assert!(
+                Vec3::new(rotated[0], rotated[1], rotated[2]).abs_diff_eq(spatial, 1e-5),
+                "spatial axes should follow the rotation"
+            );
+            assert!(
+                (rotated[3] - original[3]).abs() < 1e-5,
+                "time should be unchanged"
+            );
+        }
+
+        let mut gaussian = Gaussian4d::default();
+        gaussian.isotropic_rotations.rotation = l;
+        gaussian.isotropic_rotations.rotation_r = r;
+        gaussian.scale_opacity.opacity = 1.0;
+
+        let settings = GaussianLoaderSettings {
+            transform: bevy::prelude::Transform::from_rotation(rotation),
+            ..Default::default()
+        };
+        let mut gaussians = vec![gaussian];
+        settings.process_4d(&mut gaussians);
+
+        let rotations = gaussians[0].isotropic_rotations;
+        let expected = mul(left, l);
+        for (expected, actual) in expected.iter().zip(rotations.rotation) {
+            assert!((expected - actual).abs() < 1e-6);
+        }
+    }
+
+    fn sh_color(gaussian: &Gaussian3d, direction: Vec3) -> Vec3 {
+        let basis = sh_basis(direction);
+
+        (0..SH_COEFF_COUNT_PER_CHANNEL)
+            .map(|k| {
+                Vec3::from_array(std::array::from_fn(|channel| {
+                    gaussian.spherical_harmonic.get(k * SH_CHANNELS + channel)
+                })) * basis[k]
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_process_sh_rotation() {
+        let mut gaussian = Gaussian3d::default();
+        gaussian.rotation.rotation = [1.0, 0.0, 0.0, 0.0];
+        gaussian.scale_opacity.opacity = 1.0;
+        for index in 0..SH_COEFF_COUNT_PER_CHANNEL * SH_CHANNELS {
+            gaussian
+                .spherical_harmonic
+                .set(index, ((index * 7 % 11) as f32 - 5.0) * 0.1);
+        }
+
+        let settings = GaussianLoaderSettings {
+            up_axis: UpAxis::Z,
+            camera_convention: CameraConvention::OpenCv,
+            transform: bevy::prelude::Transform::from_rotation(Quat::from_euler(
+                bevy::math::EulerRot::XYZ,
+                0.3,
+                -1.1,
+                0.7,
+            )),
+            ..Default::default()
+        };
+        let rotation = settings.transform.rotation * settings.axis_rotation();
+
+        let mut gaussians = vec![gaussian];
+        settings.process_3d(&mut gaussians);
+
+        for direction in [
+            Vec3::X,
+            Vec3::new(0.3, -0.8, 0.5),
+            Vec3::new(-0.6, 0.2, -0.7),
+            Vec3::new(0.1, 0.9, -0.2),
+        ] {
+            let direction = direction.normalize();
+            assert!(
+                sh_color(&gaussians[0], rotation * direction)
+                    .abs_diff_eq(sh_color(&gaussian, direction), 1e-4),
+                "view dependent color should follow the baked rotation"
+            );
+        }
+
+        if SH_DEGREE == 0 {
+            return;
+        }
+
+        // 180 degrees about x only flips the y and z odd terms
+        let opencv = GaussianLoaderSettings {
+            camera_convention: CameraConvention::OpenCv,
+            ..Default::default()
+        };
+        let mut flipped = vec![gaussian];
+        opencv.process_3d(&mut flipped);
+        for (k, sign) in [(1, -1.0), (2, -1.0), (3, 1.0)] {
+            let index = k * SH_CHANNELS;
+            assert!(
+                (flipped[0].spherical_harmonic.get(index)
+                    - sign * gaussian.spherical_harmonic.get(index))
+                .abs()
+                    < 1e-5
+            );
+        }
+
+        let spatial = num_sh_coefficients(SH_DEGREE) * SH_CHANNELS;
+        let mut gaussian = Gaussian4d::default();
+        gaussian.isotropic_rotations.rotation = [1.0, 0.0, 0.0, 0.0];
+        gaussian.isotropic_rotations.rotation_r = [1.0, 0.0, 0.0, 0.0];
+        gaussian.scale_opacity.opacity = 1.0;
+        for time in 0..=SH_4D_DEGREE_TIME {
+            gaussian
+                .spherindrical_harmonic
+                .set(time * spatial + SH_CHANNELS, 1.0);
+        }
+
+        let mut gaussians = vec![gaussian];
+        opencv.process_4d(&mut gaussians);
+        for time in 0..=SH_4D_DEGREE_TIME {
+            let value = gaussians[0]
+                .spherindrical_harmonic
+                .get(time * spatial + SH_CHANNELS);
+            assert!(
+                (value + 1.0).abs() < 1e-5,
+                "every time band should be rotated"
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_3d_stored() {
+        use bevy_gaussian_splatting::{
+            PlanarGaussian3d, PlanarGaussian3dQuantized, random_gaussians_3d_seeded,
+        };
+
+        let cloud = random_gaussians_3d_seeded(100, 7);
+        let quantized = PlanarGaussian3dQuantized::from(&cloud);
+
+        // stored formats are not requantized for the padding alone
+        let unchanged = GaussianLoaderSettings::default()
+            .apply_3d_stored(quantized.clone(), |cloud, _| cloud.into());
+        assert_eq!(unchanged, quantized);
+
+        let settings = GaussianLoaderSettings {
+            transform: bevy::prelude::Transform::from_xyz(0.0, 1.0, 0.0),
+            prune_opacity: Some(0.5),
+            ..Default::default()
+        };
+        let processed = settings.apply_3d_stored(quantized.clone(), |cloud, _| cloud.into());
+
+        let kept = PlanarGaussian3d::from(&quantized)
+            .iter()
+            .filter(|gaussian| gaussian.scale_opacity.opacity >= 0.5)
+            .collect::<Vec<_>>();
+        let processed = PlanarGaussian3d::from(&processed);
+        assert_eq!(processed.len(), kept.len());
+        for (expected, actual) in kept.iter().zip(processed.iter()) {
+            let offset = Vec3::from(actual.position_visibility.position)
+                - Vec3::from(expected.position_visibility.position);
+            assert!(offset.abs_diff_eq(Vec3::Y, 1e-2), "offset {offset}");
+        }
+    }
+
+    #[test]
+    fn test_apply_temporal_hierarchy() {
+        use bevy_gaussian_splatting::{
+            Planar, PlanarGaussian4d, TemporalHierarchyBuilder, random_gaussians_4d_seeded,
+        };
+
+        let cloud = random_gaussians_4d_seeded(500, 11)
+            .to_interleaved()
+            .into_iter()
+            .map(|mut gaussian| {
+                gaussian.timestamp_timescale.timestamp *= 10.0;
+                gaussian
+            })
+            .collect::<PlanarGaussian4d>();
+        let hierarchy = TemporalHierarchyBuilder::default().build(&cloud);
+
+        let unchanged =
+            GaussianLoaderSettings::default().apply_temporal_hierarchy(hierarchy.clone());
+        assert_eq!(unchanged, hierarchy);
+
+        let settings = GaussianLoaderSettings {
+            transform: bevy::prelude::Transform::from_scale(Vec3::splat(2.0)),
+            prune_opacity: Some(0.5),
+            ..Default::default()
+        };
+        let processed = settings.apply_temporal_hierarchy(hierarchy.clone());
+
+        processed
+            .validate()
+            .expect("pruned levels should still partition the cloud");
+        let kept = (0..hierarchy.flat_cloud.len())
+            .filter(|&index| hierarchy.flat_cloud.get(index).scale_opacity.opacity >= 0.5)
+            .count();
+        assert_eq!(processed.flat_cloud.len(), kept);
+        assert!(processed.levels.len() <= hierarchy.levels.len());
+    }
+
+    #[cfg(feature = "io_ply")]
+    #[test]
+    fn test_ply_default_activations() {
+        use bevy_gaussian_splatting::io::ply::{MAX_SIZE_VARIANCE, parse_ply_3d};
+
+        let ply = b"ply\nformat ascii 1.0\nelement vertex 1\n\
+property float x\nproperty float y\nproperty float z\n\
+property float f_dc_0\nproperty float f_dc_1\nproperty float f_dc_2\n\
+property float opacity\nproperty float scale_0\nproperty float scale_1\nproperty float scale_2\n\
+property float rot_0\nproperty float rot_1\nproperty float rot_2\nproperty float rot_3\n\
+end_header\n\
+0 0 0 0 0 0 1 -1 -2 10 1 0 0 0\n"
+            .to_vec();
+
+        let decoded = parse_ply_3d(&mut std::io::Cursor::new(ply)).expect("failed to parse ply");
+        let gaussian = decoded.iter().next().unwrap();
+
+        // sigmoid on opacity, exp on log scales clamped around their mean, as loaded before the settings
+        let mean = (-1.0 - 2.0 + 10.0) / 3.0;
+        let expected_scale = Vec3::new(-1.0f32, -2.0, 10.0)
+            .clamp(
+                Vec3::splat(mean - MAX_SIZE_VARIANCE),
+                Vec3::splat(mean + MAX_SIZE_VARIANCE),
+            )
+            .exp();
+        assert!((gaussian.scale_opacity.opacity - 1.0 / (1.0 + (-1.0f32).exp())).abs() < 1e-6);
+        assert!(Vec3::from(gaussian.scale_opacity.scale).abs_diff_eq(expected_scale, 1e-4));
+        assert_eq!(decoded.len(), 32);
+    }
+
+    #[cfg(feature = "io_ply")]
+    #[test]
+    fn test_ply_activations_applied() {
+        use bevy_gaussian_splatting::io::ply::parse_ply_3d_with_settings;
+
+        let ply = b"ply\nformat ascii 1.0\nelement vertex 2\n\
+property float x\nproperty float y\nproperty float z\n\
+property float f_dc_0\nproperty float f_dc_1\nproperty float f_dc_2\n\
+property float opacity\nproperty float scale_0\nproperty float scale_1\nproperty float scale_2\n\
+property float rot_0\nproperty float rot_1\nproperty float rot_2\nproperty float rot_3\n\
+end_header\n\
+0 1 2 0 0 0 0.75 0.1 0.2 0.3 1 0 0 0\n\
+0 0 0 0 0 0 0.01 0.1 0.1 0.1 1 0 0 0\n"
+            .to_vec();
+
+        let settings = GaussianLoaderSettings {
+            camera_convention: CameraConvention::OpenCv,
+            activations_applied: Some(true),
+            prune_opacity: Some(0.05),
+            pad_to: 0,
+            ..Default::default()
+        };
+        let decoded = parse_ply_3d_with_settings(&mut std::io::Cursor::new(ply), &settings)
+            .expect("failed to parse ply");
+
+        assert_eq!(decoded.len(), 1);
+        let gaussian = decoded.iter().next().unwrap();
+        assert!(
+            Vec3::from(gaussian.position_visibility.position)
+                .abs_diff_eq(Vec3::new(0.0, -1.0, -2.0), 1e-6)
+        );
+        assert!((gaussian.scale_opacity.opacity - 0.75).abs() < 1e-6);
+        assert!(
+            Vec3::from(gaussian.scale_opacity.scale).abs_diff_eq(Vec3::new(0.1, 0.2, 0.3), 1e-6)
+        );
+    }
+}
+
+#[cfg(feature = "io_ply")]
+mod compressed_ply {
+    use bevy_gaussian_splatting::{
+        ChunkedCloudBuilder, PlanarGaussian3dChunked,
+        gaussian::formats::planar_3d_chunked::{CHUNK_SIZE, CHUNKED_MAX_SH_DEGREE},
+        io::{
+            compressed_ply::{parse_compressed_ply, write_compressed_ply},
+            ply::parse_ply_3d,
+        },
+        material::spherical_harmonics::{SH_CHANNELS, SH_DEGREE, clamp_sh_degree},
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    const SH_C0: f32 = 0.282_094_8;
+
+    #[test]
+    fn test_compressed_ply_round_trip() {
+        let count = 1000;
+
+        let gaussians = random_gaussians_3d_seeded(count, 42);
+        let chunked = ChunkedCloudBuilder::default()
+            .morton_order(false)
+            .build(&gaussians);
+        assert_eq!(chunked.len(), count);
+        assert_eq!(chunked.chunks.len(), count.div_ceil(CHUNK_SIZE));
+
+        let mut encoded = Vec::new();
+        write_compressed_ply(&chunked, &mut encoded).expect("failed to write compressed ply");
+        let decoded = parse_compressed_ply(&mut std::io::Cursor::new(encoded.as_slice()))
+            .expect("failed to parse compressed ply");
+        assert_eq!(chunked, decoded);
+
+        let sh_coefficients = (decoded.sh_degree() + 1).pow(2) * SH_CHANNELS;
+
+        for (index, (expected, actual)) in gaussians.iter().zip(decoded.iter()).enumerate() {
+            let chunk = &decoded.chunks[index / CHUNK_SIZE];
+
+            for axis in 0..3 {
+                let step = (chunk.max_position[axis] - chunk.min_position[axis]) / 1023.0;
+                let error = (expected.position_visibility.position[axis]
+                    - actual.position_visibility.position[axis])
+                    .abs();
+                assert!(error <= step * 0.5 + 1e-5, "position error {error}");
+
+                let step = (chunk.max_scale[axis] - chunk.min_scale[axis]) / 1023.0;
+                let error = (expected.scale_opacity.scale[axis].ln()
+                    - actual.scale_opacity.scale[axis].ln())
+                .abs();
+                assert!(error <= step * 0.5 + 1e-4, "log scale error {error}");
+
+                let step = (chunk.max_color[axis] - chunk.min_color[axis]) / 255.0 / SH_C0;
+                let error = (expected.spherical_harmonic.get(axis)
+                    - actual.spherical_harmonic.get(axis))
+                .abs();
+                assert!(error <= step * 0.5 + 1e-4, "sh dc error {error}");
+            }
+
+            let error = (expected.scale_opacity.opacity - actual.scale_opacity.opacity).abs();
+            assert!(error <= 0.5 / 255.0 + 1e-6, "opacity error {error}");
+
+            let norm = expected
+                .rotation
+                .rotation
+                .iter()
+                .map(|v| v * v)
+                .sum::<f32>()
+                .sqrt();
+            let dot = expected
+                .rotation
+                .rotation
+                .iter()
+                .zip(actual.rotation.rotation.iter())
+                .map(|(a, b)| a / norm * b)
+                .sum::<f32>();
+            assert!(dot.abs() >= 0.999, "rotation dot {dot}");
+
+            for i in SH_CHANNELS..sh_coefficients {
+                let error =
+                    (expected.spherical_harmonic.get(i) - actual.spherical_harmonic.get(i)).abs();
+                assert!(error <= 8.0 / 256.0 + 1e-4, "sh[{i}] error {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunked_morton_bounds() {
+        let count = 2000;
+
+        let gaussians = random_gaussians_3d_seeded(count, 7);
+        let chunked = PlanarGaussian3dChunked::from(&gaussians);
+        assert_eq!(chunked.len(), count);
+
+        let mut chunk_volume = 0.0;
+        for (index, chunk) in chunked.chunks.iter().enumerate() {
+            let aabb = chunk.aabb();
+            chunk_volume += (aabb.max - aabb.min).element_product();
+
+            for gaussian in chunked.chunk_range(index).map(|i| chunked.get(i)) {
+                for axis in 0..3 {
+                    let position = gaussian.position_visibility.position[axis];
+                    assert!(position >= chunk.min_position[axis] - 1e-5);
+                    assert!(position <= chunk.max_position[axis] + 1e-5);
+                }
+            }
+        }
+
+        // morton ordered chunks are spatially coherent, unordered chunks each span the whole cloud
+        let unordered = ChunkedCloudBuilder::default()
+            .morton_order(false)
+            .build(&gaussians);
+        let unordered_volume = unordered
+            .chunks
+            .iter()
+            .map(|chunk| {
+                let aabb = chunk.aabb();
+                (aabb.max - aabb.min).element_product()
+            })
+            .sum::<f32>();
+        assert!(chunk_volume < unordered_volume * 0.5);
+    }
+
+    #[test]
+    fn test_compressed_ply_rejects_missing_packed_properties() {
+        let chunk_properties = [
+            "min_x",
+            "min_y",
+            "min_z",
+            "max_x",
+            "max_y",
+            "max_z",
+            "min_scale_x",
+            "min_scale_y",
+            "min_scale_z",
+            "max_scale_x",
+            "max_scale_y",
+            "max_scale_z",
+        ]
+        .map(|name| format!("property float {name}\n"))
+        .concat();
+
+        let ply = format!(
+            "ply\nformat ascii 1.0\nelement chunk 1\n{chunk_properties}\
+element vertex 1\nproperty uint packed_position\nproperty uint packed_rotation\n\
+property uint packed_scale\nend_header\n\
+0 0 0 1 1 1 0 0 0 1 1 1\n\
+0 0 0\n"
+        );
+
+        let error = parse_compressed_ply(&mut std::io::Cursor::new(ply.into_bytes()))
+            .expect_err("truncated vertices must not load");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_chunked_builder_clamps_sh_degree() {
+        let gaussians = random_gaussians_3d_seeded(10, 5);
+        let chunked = ChunkedCloudBuilder {
+            sh_degree: SH_DEGREE + 4,
+            ..Default::default()
+        }
+        .build(&gaussians);
+
+        assert_eq!(chunked.len(), 10);
+        assert_eq!(chunked.sh_degree(), clamp_sh_degree(CHUNKED_MAX_SH_DEGREE));
+    }
+
+    #[test]
+    fn test_ply_loader_detects_compressed_layout() {
+        let count = 300;
+
+        let gaussians = random_gaussians_3d_seeded(count, 3);
+        let chunked = ChunkedCloudBuilder::default()
+            .morton_order(false)
+            .build(&gaussians);
+
+        let mut encoded = Vec::new();
+        write_compressed_ply(&chunked, &mut encoded).expect("failed to write compressed ply");
+
+        let decoded =
+            parse_ply_3d(&mut std::io::Cursor::new(encoded)).expect("failed to parse ply");
+        assert!(decoded.len() >= count);
+
+        for index in 0..count {
+            assert_eq!(chunked.get(index), decoded.get(index));
+        }
+    }
+}
+
+#[cfg(feature = "io_ply")]
+mod spacetime {
+    use bevy::math::{Vec3, Vec4};
+    use bevy_gaussian_splatting::io::{
+        ply::{parse_ply_3d, parse_ply_spacetime, parse_ply_spacetime_with_settings},
+        settings::{GaussianLoaderSettings, UpAxis},
+    };
+    use bevy_interleave::prelude::Planar;
+
+    const PROPERTIES: [&str; 32] = [
+        "x",
+        "y",
+        "z",
+        "trbf_center",
+        "trbf_scale",
+        "nx",
+        "ny",
+        "nz",
+        "motion_0",
+        "motion_1",
+        "motion_2",
+        "motion_3",
+        "motion_4",
+        "motion_5",
+        "motion_6",
+        "motion_7",
+        "motion_8",
+        "f_dc_0",
+        "f_dc_1",
+        "f_dc_2",
+        "opacity",
+        "scale_0",
+        "scale_1",
+        "scale_2",
+        "rot_0",
+        "rot_1",
+        "rot_2",
+        "rot_3",
+        "omega_0",
+        "omega_1",
+        "omega_2",
+        "omega_3",
+    ];
+
+    fn ascii_ply(properties: &[&str], rows: &[[f32; 32]]) -> Vec<u8> {
+        let mut ply = format!("ply\nformat ascii 1.0\nelement vertex {}\n", rows.len());
+        for property in properties {
+            ply.push_str(&format!("property float {property}\n"));
+        }
+        ply.push_str("end_header\n");
+
+        for row in rows {
+            let values = row[..properties.len()]
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>();
+            ply.push_str(&values.join(" "));
+            ply.push('\n');
+        }
+
+        ply.into_bytes()
+    }
+
+    #[test]
+    fn test_ply_spacetime_activations_and_motion() {
+        #[rustfmt::skip]
+        let row = [
+            1.0, 2.0, 3.0,
+            0.5, 0.2_f32.ln(),
+            0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0,
+            0.0, 2.0, 0.0,
+            0.0, 0.0, 4.0,
+            0.1, 0.2, 0.3,
+            0.0,
+            0.1_f32.ln(), 0.2_f32.ln(), 0.3_f32.ln(),
+            2.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+
+        let ply = ascii_ply(&PROPERTIES, &[row]);
+        let cloud =
+            parse_ply_spacetime(&mut std::io::Cursor::new(ply)).expect("failed to parse ply");
+        assert_eq!(cloud.len() % 32, 0);
+
+        let gaussian = cloud.get(0);
+        assert!((gaussian.scale_opacity.opacity - 0.5).abs() < 1e-6);
+        assert!((gaussian.motion.trbf_scale - 0.2).abs() < 1e-6);
+        assert!(
+            Vec3::from(gaussian.scale_opacity.scale).abs_diff_eq(Vec3::new(0.1, 0.2, 0.3), 1e-6)
+        );
+        assert_eq!(gaussian.spherical_harmonic.get(2), 0.3);
+
+        // at the rbf center the gaussian sits at its base position with full opacity
+        assert!(
+            gaussian
+                .position_at(0.5)
+                .abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), 1e-6)
+        );
+        assert!((gaussian.opacity_at(0.5) - 0.5).abs() < 1e-6);
+        assert!(gaussian.rotation_at(0.5).abs_diff_eq(Vec4::X, 1e-6));
+
+        // dt = 0.5: x + 0.5, y + 2 * 0.25, z + 4 * 0.125
+        assert!(
+            gaussian
+                .position_at(1.0)
+                .abs_diff_eq(Vec3::new(1.5, 2.5, 3.5), 1e-5)
+        );
+        let expected_opacity = 0.5 * (-(0.5_f32 / 0.2).powi(2)).exp();
+        assert!((gaussian.opacity_at(1.0) - expected_opacity).abs() < 1e-6);
+        assert!(
+            gaussian
+                .rotation_at(1.0)
+                .abs_diff_eq(Vec4::new(2.0, 0.0, 0.0, 0.5).normalize(), 1e-6)
+        );
+    }
+
+    #[test]
+    fn test_ply_spacetime_padding() {
+        // aligned clouds are not padded
+        let ply = ascii_ply(&PROPERTIES, &[[0.0; 32]; 32]);
+        let cloud =
+            parse_ply_spacetime(&mut std::io::Cursor::new(ply)).expect("failed to parse ply");
+        assert_eq!(cloud.len(), 32);
+
+        let ply = ascii_ply(&PROPERTIES, &[[0.0; 32]; 3]);
+        let settings = GaussianLoaderSettings {
+            pad_to: 0,
+            ..Default::default()
+        };
+        let cloud = parse_ply_spacetime_with_settings(&mut std::io::Cursor::new(ply), &settings)
+            .expect("failed to parse ply");
+        assert_eq!(cloud.len(), 3);
+    }
+
+    #[test]
+    fn test_ply_spacetime_settings() {
+        #[rustfmt::skip]
+        let row = [
+            1.0, 2.0, 3.0,
+            0.5, 0.2_f32.ln(),
+            0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0,
+            0.0, 2.0, 0.0,
+            0.0, 0.0, 4.0,
+            0.1, 0.2, 0.3,
+            0.0,
+            0.1_f32.ln(), 0.2_f32.ln(), 0.3_f32.ln(),
+            2.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+        let mut faint = row;
+        faint[20] = -5.0;
+
+        let settings = GaussianLoaderSettings {
+            up_axis: UpAxis::Z,
+            prune_opacity: Some(0.1),
+            pad_to: 0,
+            ..Default::default()
+        };
+        let ply = ascii_ply(&PROPERTIES, &[row, faint]);
+        let cloud = parse_ply_spacetime_with_settings(&mut std::io::Cursor::new(ply), &settings)
+            .expect("failed to parse ply");
+        assert_eq!(cloud.len(), 1);
+
+        // (x, y, z) -> (x, z, -y) for the base position and every motion coefficient
+        let gaussian = cloud.get(0);
+        assert!(
+            gaussian
+                .position_at(0.5)
+                .abs_diff_eq(Vec3::new(1.0, 3.0, -2.0), 1e-5)
+        );
+        assert!(
+            gaussian
+                .position_at(1.0)
+                .abs_diff_eq(Vec3::new(1.5, 3.5, -2.5), 1e-5)
+        );
+
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!(
+            gaussian
+                .rotation_at(0.5)
+                .abs_diff_eq(Vec4::new(half, -half, 0.0, 0.0), 1e-5)
+        );
+        assert!(
+            Vec4::from(gaussian.angular_velocity.omega)
+                .abs_diff_eq(Vec4::new(0.0, 0.0, half, half), 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_ply_spacetime_layout_detection() {
+        // spacetime plys are not read as static 3d clouds
+        let ply = ascii_ply(&PROPERTIES, &[[0.0; 32]]);
+        assert!(parse_ply_3d(&mut std::io::Cursor::new(ply)).is_err());
+
+        let ply = ascii_ply(&PROPERTIES[..3], &[[0.0; 32]]);
+        let error = parse_ply_spacetime(&mut std::io::Cursor::new(ply))
+            .expect_err("a ply without a temporal rbf is not a spacetime cloud");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_ply_spacetime_rejects_missing_properties() {
+        let properties = PROPERTIES
+            .iter()
+            .copied()
+            .filter(|property| !property.starts_with("omega_"))
+            .collect::<Vec<_>>();
+
+        let ply = ascii_ply(&properties, &[[0.0; 32]]);
+        assert!(parse_ply_spacetime(&mut std::io::Cursor::new(ply)).is_err());
+    }
+}
+
+mod meshopt {
+    use bevy_gaussian_splatting::io::meshopt::{
+        MeshoptFilter, decode_vertex_buffer, encode_vertex_buffer,
+    };
+
+    #[test]
+    fn test_meshopt_single_vertex_layout() {
+        let encoded = encode_vertex_buffer(&[1, 2, 3, 4], 4).unwrap();
+
+        // header, one zero group per byte lane, then the 32 byte tail ending in the first vertex
+        let mut expected = vec![0xa0, 0, 0, 0, 0];
+        expected.extend_from_slice(&[0; 28]);
+        expected.extend_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(encoded, expected);
+
+        assert_eq!(
+            decode_vertex_buffer(&encoded, 1, 4).unwrap(),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_meshopt_round_trip() {
+        let mut state = 0x2545_f491_u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+
+        for (vertex_count, vertex_size) in [(0, 4), (5, 8), (300, 12), (1000, 64), (40, 256)] {
+            let vertices: Vec<u8> = (0..vertex_count * vertex_size)
+                .map(|index| match index % 3 {
+                    // smooth, constant and noisy lanes exercise every group encoding
+                    0 => (index / vertex_size) as u8,
+                    1 => 7,
+                    _ => next() as u8,
+                })
+                .collect();
+
+            let encoded = encode_vertex_buffer(&vertices, vertex_size).unwrap();
+            let decoded = decode_vertex_buffer(&encoded, vertex_count, vertex_size).unwrap();
+            assert_eq!(decoded, vertices, "{vertex_count} x {vertex_size}");
+        }
+    }
+
+    #[test]
+    fn test_meshopt_rejects_invalid_input() {
+        assert!(encode_vertex_buffer(&[0; 6], 6).is_err());
+
+        let encoded = encode_vertex_buffer(&[9; 64], 8).unwrap();
+        assert!(decode_vertex_buffer(&encoded[..encoded.len() - 1], 8, 8).is_err());
+        assert!(decode_vertex_buffer(&encoded, 300, 8).is_err());
+
+        let mut corrupted = encoded.clone();
+        corrupted[0] = 0xa1;
+        assert!(decode_vertex_buffer(&corrupted, 8, 8).is_err());
+    }
+
+    fn i16_bytes(values: [i16; 4]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_meshopt_filters() {
+        // +z, and -z folded onto the (1, 1) corner, w passes through
+        let mut normals = vec![0, 0, 127, 9, 127, 127, 127, 9];
+        MeshoptFilter::Octahedral.decode(&mut normals, 4).unwrap();
+        assert_eq!(normals, vec![0, 0, 127, 9, 0, 0, (-127_i8) as u8, 9]);
+
+        let mut normals = i16_bytes([32767, 32767, 32767, 5]);
+        MeshoptFilter::Octahedral.decode(&mut normals, 8).unwrap();
+        assert_eq!(normals, i16_bytes([0, 0, -32767, 5]));
+
+        // 90 degrees about z with w dropped, z is stored at full scale
+        let mut quaternion = i16_bytes([0, 0, 32767, 32767]);
+        MeshoptFilter::Quaternion
+            .decode(&mut quaternion, 8)
+            .unwrap();
+        assert_eq!(quaternion, i16_bytes([0, 0, 23170, 23170]));
+
+        // 3 * 2^-1 and -5 * 2^2
+        let encode = |mantissa: i32, exponent: i32| {
+            (((exponent as u32) << 24) | (mantissa as u32 & 0x00ff_ffff)).to_le_bytes()
+        };
+        let mut words = [encode(3, -1), encode(-5, 2)].concat();
+        MeshoptFilter::Exponential.decode(&mut words, 8).unwrap();
+        assert_eq!(
+            words,
+            [1.5_f32.to_le_bytes(), (-20.0_f32).to_le_bytes()].concat()
+        );
+
+        assert!(MeshoptFilter::Quaternion.decode(&mut [0; 4], 4).is_err());
+        assert!(MeshoptFilter::Octahedral.decode(&mut [0; 12], 12).is_err());
+        assert_eq!(MeshoptFilter::from_name("COLOR"), None);
+    }
+}
+
+mod entropy {
+    use bevy_gaussian_splatting::{
+        Gaussian3d, PlanarGaussian3d, PlanarGaussian3dQuantized, PlanarGaussian4d,
+        io::{
+            codec::CloudCodecError,
+            gcloud::{
+                entropy::GcloudEntropySettings,
+                header::{GcloudCodecId, GcloudContainer, GcloudMetadata},
+            },
+            rans,
+        },
+        random_gaussians_3d_seeded, random_gaussians_4d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn encode<T: GcloudContainer>(cloud: &T, settings: &GcloudEntropySettings) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        cloud
+            .encode_entropy_container(&mut encoded, &GcloudMetadata::new(), settings)
+            .unwrap();
+        encoded
+    }
+
+    #[test]
+    fn test_rans_round_trip() {
+        let skewed = (0..10_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 28) as u8 % 5)
+            .collect::<Vec<_>>();
+        let encoded = rans::encode(&skewed);
+        assert!(encoded.len() < skewed.len() / 2);
+        assert_eq!(rans::decode(&encoded, skewed.len()).unwrap(), skewed);
+
+        for symbols in [vec![], vec![7u8; 1000], (0..=255).collect::<Vec<u8>>()] {
+            let encoded = rans::encode(&symbols);
+            assert_eq!(rans::decode(&encoded, symbols.len()).unwrap(), symbols);
+        }
+
+        assert!(rans::decode(&encoded[..encoded.len() - 1], skewed.len()).is_err());
+        assert!(rans::decode(&encoded, skewed.len() + 1).is_err());
+    }
+
+    #[test]
+    fn test_entropy_lossless_round_trip() {
+        let gaussians = random_gaussians_3d_seeded(1000, 5);
+        let encoded = encode(&gaussians, &GcloudEntropySettings::lossless());
+
+        let (header, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+        assert_eq!(header.unwrap().codec, GcloudCodecId::Entropy);
+        assert_eq!(gaussians, decoded);
+
+        let gaussians = random_gaussians_4d_seeded(500, 6);
+        let encoded = encode(&gaussians, &GcloudEntropySettings::lossless().level(1));
+        let (_, decoded) = PlanarGaussian4d::decode_container(encoded.as_slice()).unwrap();
+        assert_eq!(gaussians, decoded);
+    }
+
+    #[test]
+    fn test_entropy_quantization_error() {
+        let gaussians = random_gaussians_3d_seeded(2000, 9);
+        let original = gaussians.to_interleaved();
+        let words = bytemuck::cast_slice::<Gaussian3d, f32>(&original);
+        let stride = size_of::<Gaussian3d>() / 4;
+
+        for bits in [8u8, 12, 16] {
+            let settings = GcloudEntropySettings::default().bits(bits);
+            let encoded = encode(&gaussians, &settings);
+            assert!(encoded.len() < words.len() * 4);
+
+            let (_, decoded) = PlanarGaussian3d::decode_container(encoded.as_slice()).unwrap();
+            let decoded = decoded.to_interleaved();
+            let decoded = bytemuck::cast_slice::<Gaussian3d, f32>(&decoded);
+
+            // every component is quantized against its own range
+            for component in 0..stride {
+                let values = words.iter().skip(component).step_by(stride);
+                let min = values.clone().copied().fold(f32::INFINITY, f32::min);
+                let max = values.copied().fold(f32::NEG_INFINITY, f32::max);
+                let tolerance = (max - min) / ((1u32 << bits) - 1) as f32 * 0.5 + 1e-6;
+
+                for index in 0..gaussians.len() {
+                    let word = index * stride + component;
+                    let error = (words[word] - decoded[word]).abs();
+                    assert!(error <= tolerance, "{bits} bits: {error} > {tolerance}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_entropy_plane_bits_and_level() {
+        let gaussians = random_gaussians_3d_seeded(2000, 1);
+
+        let uniform = encode(&gaussians, &GcloudEntropySettings::default());
+        let coarse_sh = encode(
+            &gaussians,
+            &GcloudEntropySettings::default().plane_bits("spherical_harmonic", 6),
+        );
+        assert!(coarse_sh.len() < uniform.len());
+
+        let stored = encode(&gaussians, &GcloudEntropySettings::default().level(0));
+        assert!(uniform.len() < stored.len());
+
+        let (_, decoded) = PlanarGaussian3d::decode_container(stored.as_slice()).unwrap();
+        let (_, coded) = PlanarGaussian3d::decode_container(uniform.as_slice()).unwrap();
+        assert_eq!(decoded, coded);
+
+        let mut encoded = Vec::new();
+        assert!(matches!(
+            gaussians.encode_entropy_container(
+                &mut encoded,
+                &GcloudMetadata::new(),
+                &GcloudEntropySettings::default().bits(0),
+            ),
+            Err(CloudCodecError::Encode(_)),
+        ));
+    }
+
+    #[test]
+    fn test_entropy_unsupported_cloud() {
+        let quantized = PlanarGaussian3dQuantized::from(&random_gaussians_3d_seeded(10, 2));
+        let mut encoded = Vec::new();
+
+        assert!(matches!(
+            quantized.encode_entropy_container(
+                &mut encoded,
+                &GcloudMetadata::new(),
+                &GcloudEntropySettings::default(),
+            ),
+            Err(CloudCodecError::Encode(_)),
+        ));
+
+        let encoded = encode(
+            &random_gaussians_3d_seeded(10, 2),
+            &GcloudEntropySettings::default(),
+        );
+        assert!(PlanarGaussian4d::decode_container(encoded.as_slice()).is_err());
+        assert!(PlanarGaussian3dQuantized::decode_container(encoded.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_entropy_inflated_count() {
+        let encoded = encode(
+            &random_gaussians_3d_seeded(10, 3),
+            &GcloudEntropySettings::default(),
+        );
+
+        // magic, version and header size precede the header fields, the count follows 4 flag bytes
+        let header_size = u32::from_le_bytes(encoded[6..10].try_into().unwrap()) as usize;
+        let header_count = 14;
+        let payload_count = 10 + header_size;
+
+        let with_counts = |header: u64, payload: u64| {
+            let mut corrupt = encoded.clone();
+            corrupt[header_count..header_count + 8].copy_from_slice(&header.to_le_bytes());
+            corrupt[payload_count..payload_count + 8].copy_from_slice(&payload.to_le_bytes());
+            corrupt
+        };
+
+        for corrupt in [
+            with_counts(10, 11),
+            with_counts(10, u64::MAX),
+            with_counts(u64::MAX, u64::MAX),
+            encoded[..payload_count + 12].to_vec(),
+        ] {
+            let error = PlanarGaussian3d::decode_container(corrupt.as_slice())
+                .expect_err("inflated count decoded");
+            assert!(matches!(
+                error,
+                CloudCodecError::Decode(_) | CloudCodecError::Io(_)
+            ));
+        }
+
+        assert!(rans::decode(&rans::encode(&[7; 16]), usize::MAX).is_err());
+    }
+}
+
+mod texture {
+    use std::time::{Duration, Instant};
+
+    use bevy::{asset::AssetMetaCheck, prelude::*, render::render_resource::TextureFormat};
+    use bevy_gaussian_splatting::{
+        PlanarGaussian3d, PlanarGaussian4d, ShCodebookBuilder,
+        io::{
+            IoPlugin,
+            gcloud::texture::{
+                GAUSSIAN_TEXTURE_BLOCK_SIZE, GAUSSIAN_TEXTURE_IMAGES_LABEL, GaussianTextureImages,
+                GaussianTexturePlane, GaussianTextures, texture_size,
+            },
+        },
+        material::spherical_harmonics::SH_COEFF_COUNT_PER_CHANNEL,
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn assert_within(expected: f32, actual: f32, tolerance: f32, label: &str) {
+        assert!(
+            (expected - actual).abs() <= tolerance,
+            "{label}: expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_texture_size() {
+        for count in [0, 1, 15, 16, 17, 1000, 4097] {
+            let (width, height) = texture_size(count);
+            assert!(width as usize * height as usize >= count);
+            assert_eq!(width % GAUSSIAN_TEXTURE_BLOCK_SIZE, 0);
+            assert_eq!(height % GAUSSIAN_TEXTURE_BLOCK_SIZE, 0);
+        }
+    }
+
+    #[test]
+    fn test_texture_round_trip() {
+        let gaussians = random_gaussians_3d_seeded(200, 3);
+        let codebook = ShCodebookBuilder::default().codebook_size(256);
+        let textures = GaussianTextures::encode(&gaussians, &codebook);
+
+        let (width, height) = texture_size(gaussians.len());
+        assert_eq!(
+            (textures.quats.width, textures.quats.height),
+            (width, height)
+        );
+        assert_eq!(textures.sh_labels.is_some(), SH_COEFF_COUNT_PER_CHANNEL > 1);
+        for (plane, texture) in textures.planes() {
+            let image = texture.to_image();
+            assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+            assert_eq!(image.width(), texture.width, "{}", plane.name());
+            assert_eq!(GaussianTexturePlane::from_name(plane.name()), Some(plane));
+        }
+
+        let mut encoded = Vec::new();
+        textures.write_to(&mut encoded).unwrap();
+        let read = GaussianTextures::read_from(encoded.as_slice()).unwrap();
+        assert_eq!(textures, read);
+
+        let decoded = read.decode().unwrap();
+        assert_eq!(decoded.len(), gaussians.len());
+        for (expected, actual) in gaussians.iter().zip(decoded.iter()) {
+            for axis in 0..3 {
+                assert_within(
+                    expected.position_visibility.position[axis],
+                    actual.position_visibility.position[axis],
+                    0.01,
+                    "position",
+                );
+
+                let scale = expected.scale_opacity.scale[axis];
+                assert_within(
+                    scale,
+                    actual.scale_opacity.scale[axis],
+                    scale * 0.1,
+                    "scale",
+                );
+            }
+
+            assert_within(
+                expected.scale_opacity.opacity,
+                actual.scale_opacity.opacity,
+                0.5 / 255.0 + 1e-6,
+                "opacity",
+            );
+
+            // the codebook holds every splat, so only the 8-bit quantization remains
+            let coefficients = SH_COEFF_COUNT_PER_CHANNEL * 3;
+            for (&expected, &actual) in expected.spherical_harmonic.coefficients[..coefficients]
+                .iter()
+                .zip(&actual.spherical_harmonic.coefficients[..coefficients])
+            {
+                assert_within(expected, actual, 0.01, "spherical harmonic");
+            }
+
+            let norm = expected
+                .rotation
+                .rotation
+                .iter()
+                .map(|v| v * v)
+                .sum::<f32>()
+                .sqrt();
+            let dot = expected
+                .rotation
+                .rotation
+                .iter()
+                .zip(actual.rotation.rotation)
+                .map(|(a, b)| a / norm * b)
+                .sum::<f32>();
+            assert!(dot.abs() > 0.99, "rotation dot {dot}");
+        }
+    }
+
+    #[test]
+    fn test_texture_loader_labels_images() {
+        let gaussians = random_gaussians_3d_seeded(64, 5);
+        let textures =
+            GaussianTextures::encode(&gaussians, &ShCodebookBuilder::default().codebook_size(16));
+
+        let root = std::env::temp_dir().join("bevy_gaussian_splatting_gtex");
+        std::fs::create_dir_all(&root).unwrap();
+        textures
+            .write_to(std::fs::File::create(root.join("cloud.gtex")).unwrap())
+            .unwrap();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(AssetPlugin {
+            file_path: root.display().to_string(),
+            meta_check: AssetMetaCheck::Never,
+            ..default()
+        });
+        app.init_asset::<Image>();
+        app.init_asset::<PlanarGaussian3d>();
+        app.init_asset::<PlanarGaussian4d>();
+        app.add_plugins(IoPlugin);
+
+        let handle: Handle<GaussianTextureImages> = app
+            .world()
+            .resource::<AssetServer>()
+            .load(format!("cloud.gtex#{GAUSSIAN_TEXTURE_IMAGES_LABEL}"));
+
+        let deadline = Instant::now() + Duration::from_secs(15);
+        let images = loop {
+            app.update();
+
+            if let Some(images) = app
+                .world()
+                .resource::<Assets<GaussianTextureImages>>()
+                .get(&handle)
+            {
+                break images.clone();
+            }
+
+            assert!(Instant::now() < deadline, "gtex images failed to load");
+            std::thread::sleep(Duration::from_millis(1));
+        };
+
+        assert_eq!(images.count, gaussians.len());
+        assert_eq!(images.ranges, textures.ranges);
+
+        let image_assets = app.world().resource::<Assets<Image>>();
+        for (plane, texture) in textures.planes() {
+            let image = image_assets.get(&images.planes[&plane]).unwrap();
+            assert_eq!(image.data.as_deref(), Some(texture.data.as_slice()));
+        }
+        assert_eq!(images.planes.len(), textures.planes().count());
+    }
+
+    #[test]
+    fn test_texture_rejects_invalid_data() {
+        let gaussians = random_gaussians_3d_seeded(50, 4);
+        let textures =
+            GaussianTextures::encode(&gaussians, &ShCodebookBuilder::default().codebook_size(8));
+
+        let mut encoded = Vec::new();
+        textures.write_to(&mut encoded).unwrap();
+        assert!(GaussianTextures::read_from(&encoded[..encoded.len() - 1]).is_err());
+        assert!(GaussianTextures::read_from(&encoded[1..]).is_err());
+
+        let mut resized = textures.clone();
+        resized.scales.width += GAUSSIAN_TEXTURE_BLOCK_SIZE;
+        assert!(resized.decode().is_err());
+
+        let empty = GaussianTextures::encode(&Default::default(), &ShCodebookBuilder::default());
+        assert_eq!(empty.decode().unwrap().len(), 0);
+    }
+}
+
+mod plas {
+    use bevy_gaussian_splatting::{
+        PlanarGaussian3d, ShCodebookBuilder,
+        io::gcloud::{
+            plas::PlasSorter,
+            texture::{GaussianTextureEncoding, GaussianTextures},
+        },
+        random_gaussians_3d_seeded,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    fn sorted_positions(cloud: &PlanarGaussian3d) -> Vec<[u32; 3]> {
+        let mut bits = cloud
+            .position_visibility
+            .iter()
+            .map(|position_visibility| position_visibility.position.map(f32::to_bits))
+            .collect::<Vec<_>>();
+        bits.sort();
+        bits
+    }
+
+    #[test]
+    fn test_plas_reduces_neighbor_distance() {
+        let gaussians = random_gaussians_3d_seeded(1000, 21);
+        let sorter = PlasSorter::default();
+        let sorted = sorter.sort(&gaussians);
+
+        let before = sorter.neighbor_distance(&gaussians);
+        let after = sorter.neighbor_distance(&sorted);
+        assert!(after < before * 0.5, "{after} >= {before} / 2");
+
+        // sorting only permutes the splats
+        let mut permutation = sorter.permutation(&gaussians);
+        permutation.sort();
+        assert_eq!(permutation, (0..gaussians.len()).collect::<Vec<_>>());
+        assert_eq!(sorted_positions(&sorted), sorted_positions(&gaussians));
+
+        assert_eq!(sorted, sorter.sort(&gaussians));
+        assert_eq!(sorter.sort(&sorted.subset(&[3])), sorted.subset(&[3]));
+    }
+
+    #[test]
+    fn test_plas_png_container_is_exact() {
+        let gaussians = random_gaussians_3d_seeded(1000, 22);
+        let codebook = ShCodebookBuilder::default().codebook_size(64);
+
+        let unsorted = GaussianTextures::encode(&gaussians, &codebook);
+        let sorted = GaussianTextures::encode(&PlasSorter::default().sort(&gaussians), &codebook);
+
+        let png_size = |textures: &GaussianTextures| {
+            let mut encoded = Vec::new();
+            textures
+                .write_encoded_to(&mut encoded, GaussianTextureEncoding::Png)
+                .unwrap();
+            encoded
+        };
+
+        let encoded = png_size(&sorted);
+        let decoded = GaussianTextures::read_from(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, sorted);
+        assert_eq!(decoded.decode().unwrap(), sorted.decode().unwrap());
+
+        let mut raw = Vec::new();
+        sorted.write_to(&mut raw).unwrap();
+        assert!(encoded.len() < raw.len());
+        assert!(encoded.len() < png_size(&unsorted).len());
+    }
+}
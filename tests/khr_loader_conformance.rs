@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    path::PathBuf,
+    path::{Path, PathBuf},
     thread,
     time::{Duration, Instant},
 };
@@ -13,11 +13,15 @@ use bevy::{
     prelude::*,
 };
 use bevy_gaussian_splatting::{
-    GaussianKernel, GaussianProjection, GaussianSortingMethod, PlanarGaussian3d, SceneExportCloud,
+    GaussianKernel, GaussianMode, GaussianProjection, GaussianSortingMethod, Planar,
+    PlanarGaussian3d, PlanarGaussian4d, SceneCameraProjection, SceneExportCloud,
     gaussian::settings::GaussianColorSpace,
     io::{
         IoPlugin,
-        scene::{GaussianScene, encode_khr_gaussian_scene_gltf_bytes},
+        scene::{
+            GaussianScene, SceneCloudHandle, SceneExportOptions,
+            encode_khr_gaussian_scene_gltf_bytes, write_khr_gaussian_scene_glb_with_options,
+        },
     },
 };
 
@@ -52,10 +56,15 @@ fn max_supported_test_sh_degree() -> usize {
     }
 }
 
-fn try_load_fixture_scene(
-    path: &str,
-) -> Result<(GaussianScene, HashMap<String, PlanarGaussian3d>), String> {
-    let fixture_root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FIXTURE_ROOT);
+fn fixture_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FIXTURE_ROOT)
+}
+
+fn try_load_fixture_app(path: &str) -> Result<(App, GaussianScene), String> {
+    try_load_scene_app(&fixture_root(), path)
+}
+
+fn try_load_scene_app(fixture_root: &Path, path: &str) -> Result<(App, GaussianScene), String> {
     if !fixture_root.exists() {
         return Err(format!(
             "fixture root does not exist: {}",
@@ -73,6 +82,7 @@ fn try_load_fixture_scene(
         ..default()
     });
     app.init_asset::<PlanarGaussian3d>();
+    app.init_asset::<PlanarGaussian4d>();
     app.add_plugins(IoPlugin);
 
     let scene_handle: Handle<GaussianScene> = {
@@ -126,7 +136,15 @@ fn try_load_fixture_scene(
             last_states.0, last_states.1, last_states.2
         )
     });
-    let scene = scene?;
+
+    Ok((app, scene?))
+}
+
+fn try_load_scene(
+    fixture_root: &Path,
+    path: &str,
+) -> Result<(GaussianScene, HashMap<String, PlanarGaussian3d>), String> {
+    let (app, scene) = try_load_scene_app(fixture_root, path)?;
     let mut clouds_by_case = HashMap::new();
 
     for bundle in &scene.bundles {
@@ -140,7 +158,12 @@ fn try_load_fixture_scene(
         let cloud = app
             .world()
             .resource::<Assets<PlanarGaussian3d>>()
-            .get(&bundle.cloud)
+            .get(
+                bundle
+                    .cloud
+                    .as_3d()
+                    .ok_or_else(|| format!("case '{case_name}' should decode to a 3d cloud"))?,
+            )
             .cloned()
             .ok_or_else(|| format!("cloud asset for case '{case_name}' missing"))?;
         clouds_by_case.insert(case_name, cloud);
@@ -150,7 +173,7 @@ fn try_load_fixture_scene(
 }
 
 fn load_fixture_scene(path: &str) -> (GaussianScene, HashMap<String, PlanarGaussian3d>) {
-    try_load_fixture_scene(path).unwrap_or_else(|err| panic!("{err}"))
+    try_load_scene(&fixture_root(), path).unwrap_or_else(|err| panic!("{err}"))
 }
 
 fn expected_cases() -> HashMap<&'static str, ExpectedCase> {
@@ -343,6 +366,20 @@ fn assert_scene_cases(
     approx_eq(translation.y, 5.0, 1e-6);
     approx_eq(translation.z, 6.0, 1e-6);
 
+    let SceneCameraProjection::Perspective {
+        yfov,
+        aspect_ratio,
+        znear,
+        zfar,
+    } = scene_camera.projection
+    else {
+        panic!("fixture camera should be perspective");
+    };
+    approx_eq(yfov, std::f32::consts::FRAC_PI_4, 1e-6);
+    assert_eq!(aspect_ratio, None);
+    approx_eq(znear, 0.01, 1e-6);
+    assert_eq!(zfar, Some(1000.0));
+
     for bundle in &scene.bundles {
         let case_name = bundle
             .name
@@ -411,7 +448,7 @@ fn khr_loader_extensibility_and_color0_fallback() {
 
     let exported = encode_khr_gaussian_scene_gltf_bytes(
         &[SceneExportCloud {
-            cloud: cloud.clone(),
+            cloud: cloud.clone().into(),
             name: "extensible_unknown".to_owned(),
             settings: bundle.settings.clone(),
             transform: bundle.transform,
@@ -441,3 +478,221 @@ fn khr_loader_extensibility_and_color0_fallback() {
         exported_extension["extensions"]["EXT_gaussian_splatting_kernel_customShape"].is_object()
     );
 }
+
+#[test]
+fn khr_loader_mixed_3d_and_4d_scene() {
+    let (app, scene) =
+        try_load_fixture_app("khr_gaussian_4d.gltf").unwrap_or_else(|err| panic!("{err}"));
+    assert_eq!(scene.bundles.len(), 2);
+
+    let background = scene
+        .bundles
+        .iter()
+        .find(|bundle| bundle.name.starts_with("static_background"))
+        .expect("missing static background bundle");
+    assert_eq!(background.settings.gaussian_mode, GaussianMode::Gaussian3d);
+    let background_cloud = app
+        .world()
+        .resource::<Assets<PlanarGaussian3d>>()
+        .get(
+            background
+                .cloud
+                .as_3d()
+                .expect("background should be a 3d cloud"),
+        )
+        .expect("missing background cloud");
+    approx_eq(background_cloud.scale_opacity[0].opacity, 0.5, 1e-6);
+
+    let actor = scene
+        .bundles
+        .iter()
+        .find(|bundle| bundle.name.starts_with("dynamic_actor"))
+        .expect("missing dynamic actor bundle");
+    assert_eq!(actor.settings.gaussian_mode, GaussianMode::Gaussian4d);
+    approx_eq(actor.settings.time_start, 0.0, 1e-6);
+    approx_eq(actor.settings.time_stop, 2.0, 1e-6);
+    approx_eq(actor.transform.translation.y, 1.0, 1e-6);
+
+    let SceneCloudHandle::Gaussian4d(actor_handle) = &actor.cloud else {
+        panic!("dynamic actor should be a 4d cloud");
+    };
+    let actor_cloud = app
+        .world()
+        .resource::<Assets<PlanarGaussian4d>>()
+        .get(actor_handle)
+        .expect("missing dynamic actor cloud");
+    assert_eq!(actor_cloud.position_visibility.len(), 1);
+
+    let position = actor_cloud.position_visibility[0].position;
+    approx_eq(position[0], 4.0, 1e-6);
+    approx_eq(position[1], 5.0, 1e-6);
+    approx_eq(position[2], 6.0, 1e-6);
+
+    let rotations = actor_cloud.isotropic_rotations[0];
+    assert_eq!(rotations.rotation, [1.0, 0.0, 0.0, 0.0]);
+    assert_eq!(rotations.rotation_r, [0.0, 1.0, 0.0, 0.0]);
+
+    let scale_opacity = actor_cloud.scale_opacity[0];
+    approx_eq(scale_opacity.scale[0], 0.1f32.exp(), 1e-5);
+    approx_eq(scale_opacity.scale[2], 0.3f32.exp(), 1e-5);
+    approx_eq(scale_opacity.opacity, 0.75, 1e-6);
+
+    let timestamp_timescale = actor_cloud.timestamp_timescale[0];
+    approx_eq(timestamp_timescale.timestamp, 0.25, 1e-6);
+    approx_eq(timestamp_timescale.timescale, 0.5, 1e-6);
+
+    let sh = &actor_cloud.spherindrical_harmonic[0];
+    let spatial_count = (max_supported_test_sh_degree() + 1).pow(2);
+    for (time_degree, expected) in [[0.4, 0.5, 0.6], [1.1, 1.2, 1.3], [2.1, 2.2, 2.3]]
+        .into_iter()
+        .enumerate()
+    {
+        for (channel, expected) in expected.into_iter().enumerate() {
+            approx_eq(
+                sh.get(time_degree * spatial_count * 3 + channel),
+                expected,
+                1e-6,
+            );
+        }
+    }
+}
+
+#[test]
+fn khr_loader_quantized_and_meshopt_round_trip() {
+    let (scene, clouds) = load_fixture_scene("khr_conformance_matrix.gltf");
+    let mut case_names: Vec<_> = clouds.keys().cloned().collect();
+    case_names.sort();
+
+    let export_clouds: Vec<_> = case_names
+        .iter()
+        .map(|case_name| {
+            let bundle = scene
+                .bundles
+                .iter()
+                .find(|bundle| bundle.name.starts_with(case_name.as_str()))
+                .expect("missing bundle for case");
+            SceneExportCloud {
+                cloud: clouds[case_name].clone().into(),
+                name: case_name.clone(),
+                settings: bundle.settings.clone(),
+                transform: bundle.transform,
+                metadata: bundle.metadata.clone(),
+            }
+        })
+        .collect();
+
+    let export_root = std::env::temp_dir().join(format!(
+        "bevy_gaussian_splatting_khr_round_trip_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&export_root).unwrap();
+
+    for (file_name, options) in [
+        (
+            "quantized.glb",
+            SceneExportOptions {
+                quantize: true,
+                meshopt: false,
+            },
+        ),
+        (
+            "meshopt.glb",
+            SceneExportOptions {
+                quantize: false,
+                meshopt: true,
+            },
+        ),
+        (
+            "quantized_meshopt.glb",
+            SceneExportOptions {
+                quantize: true,
+                meshopt: true,
+            },
+        ),
+    ] {
+        write_khr_gaussian_scene_glb_with_options(
+            export_root.join(file_name),
+            &export_clouds,
+            None,
+            options,
+        )
+        .unwrap();
+
+        let (_, decoded_clouds) =
+            try_load_scene(&export_root, file_name).unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(decoded_clouds.len(), clouds.len());
+
+        // normalized i16 steps are ~3e-5, u8 opacity steps ~4e-3
+        let (rotation_epsilon, opacity_epsilon) = if options.quantize {
+            (1e-4, 2.5e-3)
+        } else {
+            (1e-6, 1e-6)
+        };
+
+        for (case_name, expected) in &clouds {
+            let decoded = &decoded_clouds[case_name];
+            assert_eq!(decoded.len(), expected.len(), "case '{case_name}'");
+
+            // sh accessors are fitted to the cloud's range, u8 for the dc term and i16 above it
+            let (dc_extent, rest_magnitude) = expected.spherical_harmonic.iter().fold(
+                ((f32::INFINITY, f32::NEG_INFINITY), 0.0_f32),
+                |((min, max), magnitude), sh| {
+                    let (dc, rest) = sh.coefficients.split_at(3);
+                    (
+                        dc.iter()
+                            .fold((min, max), |(min, max), &v| (min.min(v), max.max(v))),
+                        rest.iter()
+                            .fold(magnitude, |magnitude, v| magnitude.max(v.abs())),
+                    )
+                },
+            );
+            let (dc_epsilon, rest_epsilon) = if options.quantize {
+                (
+                    (dc_extent.1 - dc_extent.0) / 510.0 + 1e-5,
+                    rest_magnitude / 65534.0 + 1e-5,
+                )
+            } else {
+                (1e-6, 1e-6)
+            };
+
+            for (decoded, expected) in decoded.iter().zip(expected.iter()) {
+                for axis in 0..3 {
+                    approx_eq(
+                        decoded.position_visibility.position[axis],
+                        expected.position_visibility.position[axis],
+                        1e-6,
+                    );
+                    approx_eq(
+                        decoded.scale_opacity.scale[axis],
+                        expected.scale_opacity.scale[axis],
+                        1e-5,
+                    );
+                }
+                for component in 0..4 {
+                    approx_eq(
+                        decoded.rotation.rotation[component],
+                        expected.rotation.rotation[component],
+                        rotation_epsilon,
+                    );
+                }
+                approx_eq(
+                    decoded.scale_opacity.opacity,
+                    expected.scale_opacity.opacity,
+                    opacity_epsilon,
+                );
+                for (index, (decoded, expected)) in decoded
+                    .spherical_harmonic
+                    .coefficients
+                    .iter()
+                    .zip(expected.spherical_harmonic.coefficients.iter())
+                    .enumerate()
+                {
+                    let epsilon = if index < 3 { dc_epsilon } else { rest_epsilon };
+                    approx_eq(*decoded, *expected, epsilon);
+                }
+            }
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(&export_root);
+}
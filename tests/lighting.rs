@@ -0,0 +1,100 @@
+mod common;
+
+use bevy_gaussian_splatting::{
+    GaussianLighting,
+    render::{CloudPipelineKey, shader_defs},
+};
+use common::has_def;
+
+#[test]
+fn clouds_keep_baked_color_by_default() {
+    assert_eq!(GaussianLighting::default(), GaussianLighting::Baked);
+    assert!(!GaussianLighting::Baked.is_relit());
+}
+
+#[test]
+fn relight_selects_attribute_shading() {
+    let baked = shader_defs(CloudPipelineKey::default());
+    let relit = shader_defs(CloudPipelineKey {
+        relight: true,
+        ..Default::default()
+    });
+    let attributes = shader_defs(CloudPipelineKey {
+        relight: true,
+        pbr_attributes: true,
+        ..Default::default()
+    });
+
+    assert!(!has_def(&baked, "RELIGHT"));
+    assert!(has_def(&relit, "RELIGHT"));
+    assert!(!has_def(&relit, "PBR_ATTRIBUTES"));
+    assert!(has_def(&attributes, "PBR_ATTRIBUTES"));
+}
+
+#[cfg(feature = "io_ply")]
+mod ply {
+    use bevy_gaussian_splatting::{
+        GaussianLoaderSettings, PbrAttributes, io::ply::parse_ply_3d_relightable,
+    };
+    use bevy_interleave::prelude::Planar;
+
+    const HEADER: &str = "ply\nformat ascii 1.0\nelement vertex 3\n\
+property float x\nproperty float y\nproperty float z\n\
+property float f_dc_0\nproperty float f_dc_1\nproperty float f_dc_2\n\
+property float opacity\nproperty float scale_0\nproperty float scale_1\nproperty float scale_2\n\
+property float rot_0\nproperty float rot_1\nproperty float rot_2\nproperty float rot_3\n";
+
+    #[test]
+    fn relightable_attributes_follow_pruning() {
+        let ply = format!(
+            "{HEADER}property float base_color_0\nproperty float base_color_1\n\
+property float base_color_2\nproperty float roughness\nproperty float metallic\nend_header\n\
+0 0 0 0 0 0 5 0 0 0 1 0 0 0 0.9 0.5 0.1 0.25 1\n\
+1 0 0 0 0 0 -5 0 0 0 1 0 0 0 0.2 0.2 0.2 0.5 0\n\
+2 0 0 0 0 0 5 0 0 0 1 0 0 0 1.5 0 0 0.75 0\n"
+        );
+
+        let settings = GaussianLoaderSettings {
+            prune_opacity: Some(0.5),
+            pad_to: 4,
+            ..Default::default()
+        };
+        let (cloud, material) =
+            parse_ply_3d_relightable(&mut std::io::Cursor::new(ply.into_bytes()), &settings)
+                .expect("failed to parse ply");
+        let material = material.expect("missing relightable attributes");
+
+        assert_eq!(material.len(), cloud.len());
+        assert_eq!(
+            material.attributes[0],
+            PbrAttributes {
+                albedo: [0.9, 0.5, 0.1],
+                roughness: 0.25,
+                metallic: 1.0,
+            }
+        );
+        // the second gaussian is pruned, albedo is clamped to [0, 1]
+        assert_eq!(material.attributes[1].albedo, [1.0, 0.0, 0.0]);
+        assert_eq!(material.attributes[1].roughness, 0.75);
+        assert_eq!(material.attributes[2], PbrAttributes::default());
+    }
+
+    #[test]
+    fn plain_plys_have_no_material() {
+        let ply = format!(
+            "{HEADER}end_header\n\
+0 0 0 0 0 0 5 0 0 0 1 0 0 0\n\
+1 0 0 0 0 0 5 0 0 0 1 0 0 0\n\
+2 0 0 0 0 0 5 0 0 0 1 0 0 0\n"
+        );
+
+        let (cloud, material) = parse_ply_3d_relightable(
+            &mut std::io::Cursor::new(ply.into_bytes()),
+            &GaussianLoaderSettings::default(),
+        )
+        .expect("failed to parse ply");
+
+        assert!(cloud.len() >= 3);
+        assert!(material.is_none());
+    }
+}
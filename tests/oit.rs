@@ -0,0 +1,223 @@
+mod common;
+
+use bevy::{camera::Projection, prelude::*};
+use bevy_gaussian_splatting::{
+    CloudSettings, Gaussian3d, OitWeight, PlanarGaussian3d, Rasterizer,
+    SphericalHarmonicCoefficients,
+    gaussian::f32::{PositionVisibility, Rotation, ScaleOpacity},
+    random_gaussians_3d_seeded,
+    render::{
+        CloudPipelineKey,
+        oit::{OIT_ACCUM_FORMAT, OIT_REVEALAGE_FORMAT, oit_color_targets},
+        reference::{ReferenceImage, ReferenceRasterize, ReferenceView, oit_resolve, oit_weight},
+        shader_defs,
+    },
+};
+use bevy_interleave::prelude::Planar;
+use common::has_def;
+
+const SIZE: u32 = 65;
+const CENTER: u32 = SIZE / 2;
+const OIT_WEIGHTS: [OitWeight; 3] = [
+    OitWeight::Constant,
+    OitWeight::DepthFalloff,
+    OitWeight::ProjectedDepth,
+];
+
+fn view(distance: f32) -> ReferenceView {
+    let camera = Transform::from_xyz(0.0, 0.0, distance).looking_at(Vec3::ZERO, Vec3::Y);
+    ReferenceView::new(
+        &GlobalTransform::from(camera),
+        &Projection::default(),
+        SIZE,
+        SIZE,
+    )
+}
+
+fn gaussian(position: [f32; 3], scale: f32, opacity: f32, dc: [f32; 3]) -> Gaussian3d {
+    let mut spherical_harmonic = SphericalHarmonicCoefficients::default();
+    for (channel, value) in dc.into_iter().enumerate() {
+        spherical_harmonic.set(channel, value);
+    }
+
+    Gaussian3d {
+        position_visibility: PositionVisibility {
+            position,
+            visibility: 1.0,
+        },
+        spherical_harmonic,
+        rotation: Rotation {
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        },
+        scale_opacity: ScaleOpacity {
+            scale: [scale; 3],
+            opacity,
+        },
+    }
+}
+
+fn render(
+    cloud: &PlanarGaussian3d,
+    rasterizer: Rasterizer,
+    oit_weight: OitWeight,
+    distance: f32,
+) -> ReferenceImage {
+    let settings = CloudSettings {
+        aabb: true,
+        rasterizer,
+        oit_weight,
+        ..default()
+    };
+    cloud.rasterize_reference(&GlobalTransform::IDENTITY, &settings, &view(distance))
+}
+
+#[test]
+fn weighted_blended_selects_weight_function() {
+    let defs = shader_defs(CloudPipelineKey {
+        rasterizer: Rasterizer::WeightedBlended,
+        oit_weight: OitWeight::ProjectedDepth,
+        ..Default::default()
+    });
+
+    assert!(has_def(&defs, "WEIGHTED_BLENDED_OIT"));
+    assert!(has_def(&defs, "OIT_WEIGHT_PROJECTED_DEPTH"));
+    assert!(!has_def(&defs, "OIT_WEIGHT_DEPTH_FALLOFF"));
+}
+
+#[test]
+fn instanced_ignores_weight_function() {
+    let defs = shader_defs(CloudPipelineKey {
+        rasterizer: Rasterizer::Instanced,
+        oit_weight: OitWeight::Constant,
+        ..Default::default()
+    });
+
+    assert!(!has_def(&defs, "WEIGHTED_BLENDED_OIT"));
+    assert!(!has_def(&defs, "OIT_WEIGHT_CONSTANT"));
+}
+
+#[test]
+fn accumulation_targets_match_resolve_formats() {
+    let [accum, revealage] = oit_color_targets();
+
+    assert_eq!(accum.unwrap().format, OIT_ACCUM_FORMAT);
+    assert_eq!(revealage.unwrap().format, OIT_REVEALAGE_FORMAT);
+}
+
+#[test]
+fn oit_weights_favor_near_fragments() {
+    let view = view(5.0);
+    let view_from_clip = view.clip_from_view.inverse();
+    let ndc_depth = |distance: f32| {
+        let clip = view.clip_from_view * Vec4::new(0.0, 0.0, -distance, 1.0);
+        clip.z / clip.w
+    };
+
+    for distance in [0.5, 5.0, 500.0] {
+        assert_eq!(
+            oit_weight(OitWeight::Constant, ndc_depth(distance), view_from_clip),
+            1.0,
+        );
+    }
+
+    // `10 / (1e-5 + (d / 5)^2 + (d / 200)^6)` is 10 at five units
+    let falloff = oit_weight(OitWeight::DepthFalloff, ndc_depth(5.0), view_from_clip);
+    assert!((falloff - 10.0).abs() < 1e-2, "{falloff}");
+
+    for weight in [OitWeight::DepthFalloff, OitWeight::ProjectedDepth] {
+        let weights = [0.2, 1.0, 5.0, 50.0, 5000.0]
+            .map(|distance| oit_weight(weight, ndc_depth(distance), view_from_clip));
+
+        assert!(
+            weights.windows(2).all(|pair| pair[0] >= pair[1]),
+            "{weight:?} {weights:?}"
+        );
+        assert!(weights[0] > weights[4], "{weight:?} {weights:?}");
+        assert!(weights.iter().all(|weight| (1e-2..=3e3).contains(weight)));
+    }
+}
+
+#[test]
+fn oit_resolve_normalizes_accumulation() {
+    let color = Vec3::new(0.2, 0.4, 0.8);
+
+    let mut accum = Vec4::ZERO;
+    let mut revealage = 1.0;
+    for (alpha, weight) in [(0.5, 3.0), (0.25, 0.5)] {
+        accum += (color * alpha).extend(alpha) * weight;
+        revealage *= 1.0 - alpha;
+    }
+
+    // equal colors resolve to that color at the combined coverage, whatever their weights
+    let resolved = oit_resolve(accum, revealage);
+    assert!(
+        (resolved - (color * 0.625).extend(0.625))
+            .abs()
+            .max_element()
+            < 1e-6
+    );
+
+    assert_eq!(oit_resolve(Vec4::ZERO, 1.0), Vec4::ZERO);
+}
+
+#[test]
+fn weighted_blended_reference_is_order_independent() {
+    let cloud = random_gaussians_3d_seeded(64, 11);
+    let mut reversed = cloud.iter().collect::<Vec<_>>();
+    reversed.reverse();
+    let reversed = PlanarGaussian3d::from_interleaved(reversed);
+
+    for weight in OIT_WEIGHTS {
+        let image = render(&cloud, Rasterizer::WeightedBlended, weight, 40.0);
+        let swapped = render(&reversed, Rasterizer::WeightedBlended, weight, 40.0);
+
+        assert!(image.pixels.iter().filter(|pixel| pixel.w > 1e-3).count() > 64);
+        assert!(
+            image.max_difference(&swapped) < 1e-5,
+            "{weight:?} {}",
+            image.max_difference(&swapped),
+        );
+    }
+}
+
+#[test]
+fn weighted_blended_matches_sorted_compositing_for_opaque_splats() {
+    // splats that never share a pixel resolve to their own color, close enough to the camera for
+    // their weighted tails to stay above the accumulation clamp of the resolve
+    let separated = PlanarGaussian3d::from_interleaved(vec![
+        gaussian([-0.5, 0.0, 0.0], 0.05, 0.99, [2.0, -2.0, -2.0]),
+        gaussian([0.5, 0.0, 0.25], 0.05, 0.99, [-2.0, -2.0, 2.0]),
+    ]);
+    let sorted = render(&separated, Rasterizer::Instanced, OitWeight::default(), 2.5);
+
+    for weight in OIT_WEIGHTS {
+        let blended = render(&separated, Rasterizer::WeightedBlended, weight, 2.5);
+        assert!(
+            sorted.max_difference(&blended) < 1e-4,
+            "{weight:?} {}",
+            sorted.max_difference(&blended),
+        );
+    }
+
+    // stacked splats keep the sorted coverage, depth weights pull the color to the near splat
+    let near = gaussian([0.0, 0.0, 1.0], 0.2, 0.99, [2.0, -2.0, -2.0]);
+    let far = gaussian([0.0, 0.0, -1.0], 0.2, 0.99, [-2.0, -2.0, 2.0]);
+    let stacked = PlanarGaussian3d::from_interleaved(vec![far, near]);
+
+    let sorted = render(&stacked, Rasterizer::Instanced, OitWeight::default(), 5.0);
+    let sorted = sorted.pixel(CENTER, CENTER);
+    let error = |weight| {
+        let blended = render(&stacked, Rasterizer::WeightedBlended, weight, 5.0);
+        let blended = blended.pixel(CENTER, CENTER);
+
+        assert!(
+            (blended.w - sorted.w).abs() < 1e-6,
+            "{weight:?} {blended} {sorted}"
+        );
+        (blended - sorted).abs().max_element()
+    };
+
+    let constant = error(OitWeight::Constant);
+    assert!(error(OitWeight::DepthFalloff) < constant);
+    assert!(error(OitWeight::ProjectedDepth) < constant);
+}
@@ -0,0 +1,367 @@
+use bevy::{camera::Projection, prelude::*};
+use bevy_gaussian_splatting::{
+    CloudSettings, Gaussian3d, Gaussian4d, GaussianMode, PlanarGaussian3d, PlanarGaussian4d,
+    RasterizeMode, SphericalHarmonicCoefficients,
+    gaussian::f32::{
+        IsotropicRotations, PositionVisibility, Rotation, ScaleOpacity, TimestampTimescale,
+    },
+    material::spherindrical_harmonics::SpherindricalHarmonicCoefficients,
+    random_gaussians_3d_seeded, random_gaussians_4d_seeded,
+    render::reference::{ReferenceImage, ReferenceRasterize, ReferenceView},
+};
+use bevy_interleave::prelude::Planar;
+
+const SIZE: u32 = 65;
+const CENTER: u32 = SIZE / 2;
+
+fn view(distance: f32) -> ReferenceView {
+    let camera = Transform::from_xyz(0.0, 0.0, distance).looking_at(Vec3::ZERO, Vec3::Y);
+    ReferenceView::new(
+        &GlobalTransform::from(camera),
+        &Projection::default(),
+        SIZE,
+        SIZE,
+    )
+}
+
+fn gaussian(position: [f32; 3], scale: f32, opacity: f32, dc: [f32; 3]) -> Gaussian3d {
+    let mut spherical_harmonic = SphericalHarmonicCoefficients::default();
+    for (channel, value) in dc.into_iter().enumerate() {
+        spherical_harmonic.set(channel, value);
+    }
+
+    Gaussian3d {
+        position_visibility: PositionVisibility {
+            position,
+            visibility: 1.0,
+        },
+        spherical_harmonic,
+        rotation: Rotation {
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        },
+        scale_opacity: ScaleOpacity {
+            scale: [scale; 3],
+            opacity,
+        },
+    }
+}
+
+fn render(cloud: &impl ReferenceRasterize, settings: &CloudSettings) -> ReferenceImage {
+    cloud.rasterize_reference(&GlobalTransform::IDENTITY, settings, &view(5.0))
+}
+
+fn coverage(image: &ReferenceImage) -> usize {
+    image.pixels.iter().filter(|pixel| pixel.w > 1e-3).count()
+}
+
+fn assert_valid(image: &ReferenceImage, label: &str) {
+    assert_eq!(image.pixels.len(), (SIZE * SIZE) as usize, "{label}");
+    for pixel in &image.pixels {
+        assert!(pixel.is_finite(), "{label}: {pixel}");
+        assert!((0.0..=1.0).contains(&pixel.w), "{label}: {pixel}");
+    }
+}
+
+#[test]
+fn test_reference_single_splat() {
+    let cloud = PlanarGaussian3d::from_interleaved(vec![gaussian([0.0; 3], 0.1, 0.5, [0.0; 3])]);
+    let settings = CloudSettings {
+        aabb: true,
+        ..default()
+    };
+    let image = render(&cloud, &settings);
+    assert_valid(&image, "single");
+
+    // the peak of the kernel is the vertex opacity, a zero sh evaluates to srgb 0.5
+    let linear = ((0.5_f32 + 0.055) / 1.055).powf(2.4);
+    let center = image.pixel(CENTER, CENTER);
+    assert!((center.w - 0.5).abs() < 1e-4, "{center}");
+    assert!((center.x - linear * 0.5).abs() < 1e-4, "{center}");
+    assert_eq!(center.x, center.y);
+    assert_eq!(center.y, center.z);
+
+    assert_eq!(image.pixel(0, 0), Vec4::ZERO);
+    for offset in 1..6 {
+        let left = image.pixel(CENTER - offset, CENTER);
+        let right = image.pixel(CENTER + offset, CENTER);
+        let up = image.pixel(CENTER, CENTER - offset);
+        assert!((left - right).abs().max_element() < 1e-5);
+        assert!((left - up).abs().max_element() < 1e-5);
+        assert!(left.w < center.w);
+    }
+
+    let hidden = CloudSettings {
+        global_opacity: 0.0,
+        ..settings.clone()
+    };
+    assert_eq!(coverage(&render(&cloud, &hidden)), 0);
+}
+
+#[test]
+fn test_reference_front_to_back_compositing() {
+    let near = gaussian([0.0, 0.0, 1.0], 0.2, 0.99, [2.0, -2.0, -2.0]);
+    let far = gaussian([0.0, 0.0, -1.0], 0.2, 0.99, [-2.0, -2.0, 2.0]);
+
+    let settings = CloudSettings {
+        aabb: true,
+        ..default()
+    };
+    let image = render(
+        &PlanarGaussian3d::from_interleaved(vec![far, near]),
+        &settings,
+    );
+    let swapped = render(
+        &PlanarGaussian3d::from_interleaved(vec![near, far]),
+        &settings,
+    );
+    assert_eq!(image.max_difference(&swapped), 0.0);
+
+    // the near red splat covers the far blue one
+    let center = image.pixel(CENTER, CENTER);
+    assert!(center.x > 0.9, "{center}");
+    assert!(center.z < 0.05, "{center}");
+    assert!(center.w > 0.999, "{center}");
+}
+
+#[test]
+fn test_reference_modes() {
+    let cloud = random_gaussians_3d_seeded(64, 11);
+    let camera = view(40.0);
+
+    for gaussian_mode in [GaussianMode::Gaussian2d, GaussianMode::Gaussian3d] {
+        for aabb in [false, true] {
+            for rasterize_mode in [
+                RasterizeMode::Classification,
+                RasterizeMode::Color,
+                RasterizeMode::Depth,
+                RasterizeMode::Normal,
+                RasterizeMode::OpticalFlow,
+                RasterizeMode::Position,
+                RasterizeMode::Velocity,
+            ] {
+                let settings = CloudSettings {
+                    aabb,
+                    gaussian_mode,
+                    rasterize_mode,
+                    ..default()
+                };
+                let label = format!("{gaussian_mode:?} {rasterize_mode:?} aabb {aabb}");
+
+                let image =
+                    cloud.rasterize_reference(&GlobalTransform::IDENTITY, &settings, &camera);
+                assert_valid(&image, &label);
+
+                // velocity only exists for 4d gaussians
+                if rasterize_mode == RasterizeMode::Velocity {
+                    assert_eq!(coverage(&image), 0, "{label}");
+                } else {
+                    assert!(coverage(&image) > 64, "{label}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_reference_4d_matches_3d_at_timestamp() {
+    let gaussians_3d = random_gaussians_3d_seeded(32, 5)
+        .iter()
+        .map(|mut gaussian| {
+            gaussian.rotation.rotation = [1.0, 0.0, 0.0, 0.0];
+            gaussian.spherical_harmonic.coefficients[3..].fill(0.0);
+            gaussian
+        })
+        .collect::<Vec<_>>();
+
+    let gaussians_4d = gaussians_3d
+        .iter()
+        .map(|gaussian| {
+            let mut spherindrical_harmonic = SpherindricalHarmonicCoefficients::default();
+            for (channel, &value) in gaussian.spherical_harmonic.coefficients[..3]
+                .iter()
+                .enumerate()
+            {
+                spherindrical_harmonic.set(channel, value);
+            }
+
+            Gaussian4d {
+                position_visibility: gaussian.position_visibility,
+                spherindrical_harmonic,
+                isotropic_rotations: IsotropicRotations {
+                    rotation: [1.0, 0.0, 0.0, 0.0],
+                    rotation_r: [1.0, 0.0, 0.0, 0.0],
+                },
+                scale_opacity: gaussian.scale_opacity,
+                timestamp_timescale: TimestampTimescale {
+                    timestamp: 0.5,
+                    timescale: 1.0,
+                    _pad: [0.0; 2],
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let camera = view(40.0);
+    for aabb in [false, true] {
+        let settings = CloudSettings {
+            aabb,
+            time: 0.5,
+            ..default()
+        };
+        let image_3d = PlanarGaussian3d::from_interleaved(gaussians_3d.clone())
+            .rasterize_reference(&GlobalTransform::IDENTITY, &settings, &camera);
+        let image_4d = PlanarGaussian4d::from_interleaved(gaussians_4d.clone())
+            .rasterize_reference(&GlobalTransform::IDENTITY, &settings, &camera);
+
+        assert!(coverage(&image_3d) > 64);
+        assert!(image_3d.max_difference(&image_4d) < 1e-4, "aabb {aabb}");
+    }
+
+    // far from the timestamp the temporal marginal masks every splat
+    let late = CloudSettings {
+        time: 10.0,
+        ..default()
+    };
+    let image = PlanarGaussian4d::from_interleaved(gaussians_4d).rasterize_reference(
+        &GlobalTransform::IDENTITY,
+        &late,
+        &camera,
+    );
+    assert_eq!(coverage(&image), 0);
+}
+
+#[test]
+fn test_reference_4d_modes() {
+    let cloud = random_gaussians_4d_seeded(128, 3);
+    let camera = view(40.0);
+
+    for rasterize_mode in [RasterizeMode::Color, RasterizeMode::Velocity] {
+        for time in [0.0, 0.5, 1.0] {
+            let settings = CloudSettings {
+                rasterize_mode,
+                time,
+                ..default()
+            };
+            let image = cloud.rasterize_reference(&GlobalTransform::IDENTITY, &settings, &camera);
+            assert_valid(&image, &format!("{rasterize_mode:?} {time}"));
+        }
+    }
+}
+
+#[cfg(feature = "io_png")]
+mod golden {
+    use std::{io::Cursor, path::PathBuf};
+
+    use super::*;
+
+    // regenerate with `UPDATE_GOLDENS=1 cargo test --test reference`
+    fn assert_golden(name: &str, image: &ReferenceImage) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/reference")
+            .join(format!("{name}.png"));
+        let pixels = image.to_rgba8();
+
+        if std::env::var("UPDATE_GOLDENS").is_ok_and(|value| value == "1") {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let file = std::fs::File::create(&path).unwrap();
+            let mut encoder = png::Encoder::new(file, image.width, image.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&pixels).unwrap();
+            return;
+        }
+
+        let bytes = std::fs::read(&path)
+            .unwrap_or_else(|err| panic!("missing golden {}: {err}", path.display()));
+        let mut reader = png::Decoder::new(Cursor::new(bytes)).read_info().unwrap();
+        let mut golden = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut golden).unwrap();
+        assert_eq!(
+            (info.width, info.height),
+            (image.width, image.height),
+            "{name}"
+        );
+
+        let max_difference = golden[..info.buffer_size()]
+            .iter()
+            .zip(&pixels)
+            .map(|(&a, &b)| a.abs_diff(b))
+            .max()
+            .unwrap_or(0);
+        assert!(
+            max_difference <= 2,
+            "{name}: max difference {max_difference}"
+        );
+    }
+
+    fn scene() -> PlanarGaussian3d {
+        let mut gaussians = vec![
+            gaussian([0.0, 0.0, 0.0], 0.4, 0.9, [1.2, 0.2, -0.8]),
+            gaussian([0.6, 0.4, -0.5], 0.3, 0.7, [-0.8, 1.0, 0.1]),
+            gaussian([-0.7, -0.3, 0.4], 0.25, 0.8, [-0.5, -0.2, 1.3]),
+            gaussian([-0.2, 0.7, 0.8], 0.15, 0.6, [0.9, 0.9, -0.4]),
+        ];
+        let rotations = [
+            Quat::from_rotation_z(0.4),
+            Quat::from_euler(EulerRot::XYZ, 0.3, -0.5, 0.9),
+            Quat::from_rotation_y(1.1),
+            Quat::from_rotation_x(-0.7),
+        ];
+        let scales = [[1.5, 0.4, 0.6], [0.5, 1.2, 0.8], [1.0, 1.0, 0.2], [1.0; 3]];
+
+        for ((gaussian, rotation), scale) in gaussians.iter_mut().zip(rotations).zip(scales) {
+            gaussian.rotation.rotation = [rotation.w, rotation.x, rotation.y, rotation.z];
+            for (axis, factor) in scale.into_iter().enumerate() {
+                gaussian.scale_opacity.scale[axis] *= factor;
+            }
+        }
+
+        PlanarGaussian3d::from_interleaved(gaussians)
+    }
+
+    #[test]
+    fn test_reference_goldens() {
+        let cloud = scene();
+        let transform = GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_y(0.2)));
+
+        for (name, settings) in [
+            ("color_obb", CloudSettings::default()),
+            (
+                "color_aabb",
+                CloudSettings {
+                    aabb: true,
+                    ..default()
+                },
+            ),
+            (
+                "surfel",
+                CloudSettings {
+                    aabb: true,
+                    gaussian_mode: GaussianMode::Gaussian2d,
+                    ..default()
+                },
+            ),
+            (
+                "depth",
+                CloudSettings {
+                    aabb: true,
+                    rasterize_mode: RasterizeMode::Depth,
+                    ..default()
+                },
+            ),
+            (
+                "normal",
+                CloudSettings {
+                    aabb: true,
+                    rasterize_mode: RasterizeMode::Normal,
+                    ..default()
+                },
+            ),
+        ] {
+            let image = cloud.rasterize_reference(&transform, &settings, &view(4.0));
+            assert!(coverage(&image) > 64, "{name}");
+            assert_golden(name, &image);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+#[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+mod common;
+
+#[cfg(not(all(feature = "buffer_texture", not(feature = "buffer_storage"))))]
+#[test]
+fn texture_render_test_requires_buffer_texture_feature() {}
+
+#[cfg(all(feature = "buffer_texture", not(feature = "buffer_storage")))]
+mod buffer_texture {
+    use std::{
+        env,
+        time::{Duration, Instant},
+    };
+
+    use bevy::{
+        app::{AppExit, ScheduleRunnerPlugin},
+        camera::RenderTarget,
+        core_pipeline::tonemapping::Tonemapping,
+        prelude::*,
+        render::{
+            render_resource::{Extent3d, TextureFormat},
+            view::screenshot::{Screenshot, ScreenshotCaptured},
+        },
+        window::ExitCondition,
+        winit::WinitPlugin,
+    };
+    use bevy_gaussian_splatting::{
+        CloudSettings, Gaussian3d, GaussianCamera, GaussianMode, GaussianSplattingPlugin,
+        PlanarGaussian3d, PlanarGaussian3dHandle, SphericalHarmonicCoefficients,
+        gaussian::f32::Rotation,
+        render::{CloudPipelineKey, shader_defs},
+        sort::SortMode,
+    };
+
+    use crate::common::has_def;
+
+    const WIDTH: u32 = 128;
+    const HEIGHT: u32 = 128;
+    const WARMUP_FRAMES: u32 = 45;
+    const MAX_FRAMES: u32 = 180;
+    const NON_BLACK_MIN: usize = 64;
+
+    #[test]
+    fn planar_clouds_keep_planar_texture_bindings() {
+        let planar = shader_defs(CloudPipelineKey::default());
+        let gtex = shader_defs(CloudPipelineKey {
+            gtex: true,
+            ..Default::default()
+        });
+
+        assert!(has_def(&planar, "PLANAR_TEXTURE_F32"));
+        assert!(!has_def(&planar, "PLANAR_TEXTURE_GTEX"));
+        assert!(has_def(&gtex, "PLANAR_TEXTURE_GTEX"));
+        assert!(!has_def(&gtex, "PLANAR_TEXTURE_F32"));
+    }
+
+    // clouds built in memory carry no `.gtex` planes and draw from their planar storage
+    #[test]
+    fn planar_cloud_renders_with_buffer_texture() {
+        if env::var("RUN_GPU_RENDER_TESTS").ok().as_deref() != Some("1") {
+            eprintln!("skipping GPU texture render test; set RUN_GPU_RENDER_TESTS=1 to enable");
+            return;
+        }
+
+        let mut app = App::new();
+        app.insert_resource(ClearColor(Color::srgb_u8(0, 0, 0)))
+            .insert_resource(TextureRenderState::default());
+        app.add_plugins(
+            DefaultPlugins
+                .set(AssetPlugin {
+                    file_path: "assets".to_string(),
+                    processed_file_path: "assets".to_string(),
+                    meta_check: bevy::asset::AssetMetaCheck::Never,
+                    unapproved_path_mode: bevy::asset::UnapprovedPathMode::Allow,
+                    ..default()
+                })
+                .set(ImagePlugin::default_nearest())
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .disable::<WinitPlugin>()
+                .disable::<bevy::log::LogPlugin>(),
+        );
+        app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )));
+        app.add_plugins(GaussianSplattingPlugin);
+        app.add_systems(Startup, setup_texture_scene)
+            .add_systems(Update, drive_texture_capture)
+            .add_observer(on_screenshot_captured);
+
+        let exit = app.run();
+        assert!(exit.is_success(), "texture render app exited with {exit:?}");
+        assert!(
+            app.world().resource::<TextureRenderState>().verified,
+            "texture render app exited before the capture was checked"
+        );
+    }
+
+    #[derive(Debug, Resource)]
+    struct TextureRenderState {
+        frames: u32,
+        captured: bool,
+        verified: bool,
+        started_at: Instant,
+        target: Option<Handle<Image>>,
+    }
+
+    impl Default for TextureRenderState {
+        fn default() -> Self {
+            Self {
+                frames: 0,
+                captured: false,
+                verified: false,
+                started_at: Instant::now(),
+                target: None,
+            }
+        }
+    }
+
+    fn setup_texture_scene(
+        mut commands: Commands,
+        mut state: ResMut<TextureRenderState>,
+        mut gaussian_assets: ResMut<Assets<PlanarGaussian3d>>,
+        mut images: ResMut<Assets<Image>>,
+    ) {
+        let size = Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            ..default()
+        };
+        let render_target = images.add(Image::new_target_texture(
+            size.width,
+            size.height,
+            TextureFormat::Rgba8UnormSrgb,
+            None,
+        ));
+        state.target = Some(render_target.clone());
+
+        let cloud = gaussian_assets.add(texture_test_cloud());
+        commands.spawn((
+            PlanarGaussian3dHandle(cloud),
+            CloudSettings {
+                gaussian_mode: GaussianMode::Gaussian3d,
+                sort_mode: SortMode::None,
+                global_opacity: 2.0,
+                global_scale: 1.0,
+                opacity_adaptive_radius: false,
+                ..default()
+            },
+            Transform::default(),
+            Visibility::Visible,
+            Name::new("texture_test_cloud"),
+        ));
+
+        commands.spawn((
+            Camera3d::default(),
+            Camera::default(),
+            RenderTarget::Image(render_target.into()),
+            Transform::from_translation(Vec3::new(0.0, 0.0, 5.0)),
+            Tonemapping::None,
+            GaussianCamera::default(),
+        ));
+    }
+
+    fn drive_texture_capture(mut commands: Commands, mut state: ResMut<TextureRenderState>) {
+        state.frames += 1;
+
+        if state.frames > MAX_FRAMES {
+            panic!(
+                "texture render test timed out after {} frames ({:?} elapsed)",
+                state.frames,
+                state.started_at.elapsed()
+            );
+        }
+
+        if state.captured || state.frames < WARMUP_FRAMES {
+            return;
+        }
+
+        let Some(target) = state.target.clone() else {
+            return;
+        };
+        commands.spawn(Screenshot::image(target));
+        state.captured = true;
+    }
+
+    fn texture_test_cloud() -> PlanarGaussian3d {
+        let mut red = SphericalHarmonicCoefficients::default();
+        red.set(0, 6.0);
+
+        let rotation = Rotation {
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        };
+
+        let mut gaussians = Vec::new();
+        for x in [-0.35, 0.35] {
+            for y in [-0.35, 0.35] {
+                gaussians.push(Gaussian3d {
+                    position_visibility: [x, y, 0.0, 1.0].into(),
+                    rotation,
+                    scale_opacity: [0.22, 0.22, 0.22, 0.85].into(),
+                    spherical_harmonic: red,
+                });
+            }
+        }
+        gaussians.into()
+    }
+
+    fn on_screenshot_captured(
+        trigger: On<ScreenshotCaptured>,
+        mut state: ResMut<TextureRenderState>,
+        mut app_exit: MessageWriter<AppExit>,
+    ) {
+        let image = trigger
+            .image
+            .clone()
+            .try_into_dynamic()
+            .expect("failed to convert screenshot image")
+            .to_rgba8();
+
+        let non_black_pixels = image
+            .as_raw()
+            .chunks_exact(4)
+            .filter(|pixel| pixel[0].max(pixel[1]).max(pixel[2]) > 8)
+            .count();
+        assert!(
+            non_black_pixels >= NON_BLACK_MIN,
+            "planar cloud did not render under buffer_texture: {non_black_pixels} non-black pixels"
+        );
+
+        state.verified = true;
+        app_exit.write(AppExit::Success);
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::{
+    camera::{Projection, primitives::Aabb},
+    math::UVec2,
+    prelude::*,
+};
+use bevy_gaussian_splatting::{
+    CloudSettings, Gaussian3d, PlanarGaussian3d, Rasterizer, SphericalHarmonicCoefficients,
+    gaussian::f32::{PositionVisibility, Rotation, ScaleOpacity},
+    random_gaussians_3d_seeded,
+    render::{
+        cloud_bounding_sphere,
+        reference::{ReferenceImage, ReferenceRasterize, ReferenceView},
+        tile::{
+            TILE_ENTRIES_PER_GAUSSIAN, TILE_SIZE, tile_bounds, tile_depth_bits,
+            tile_entry_capacity, tile_grid, tile_key,
+        },
+    },
+};
+use bevy_interleave::prelude::Planar;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+fn view(distance: f32) -> ReferenceView {
+    let camera = Transform::from_xyz(0.0, 0.0, distance).looking_at(Vec3::ZERO, Vec3::Y);
+    ReferenceView::new(
+        &GlobalTransform::from(camera),
+        &Projection::default(),
+        WIDTH,
+        HEIGHT,
+    )
+}
+
+fn gaussian(position: [f32; 3], scale: f32, opacity: f32, dc: [f32; 3]) -> Gaussian3d {
+    let mut spherical_harmonic = SphericalHarmonicCoefficients::default();
+    for (channel, value) in dc.into_iter().enumerate() {
+        spherical_harmonic.set(channel, value);
+    }
+
+    Gaussian3d {
+        position_visibility: PositionVisibility {
+            position,
+            visibility: 1.0,
+        },
+        spherical_harmonic,
+        rotation: Rotation {
+            rotation: [1.0, 0.0, 0.0, 0.0],
+        },
+        scale_opacity: ScaleOpacity {
+            scale: [scale; 3],
+            opacity,
+        },
+    }
+}
+
+fn render(cloud: &PlanarGaussian3d, rasterizer: Rasterizer, distance: f32) -> ReferenceImage {
+    let settings = CloudSettings {
+        aabb: true,
+        rasterizer,
+        ..default()
+    };
+    cloud.rasterize_reference(&GlobalTransform::IDENTITY, &settings, &view(distance))
+}
+
+#[test]
+fn tile_grid_covers_partial_tiles() {
+    assert_eq!(tile_grid(UVec2::new(1920, 1080)), UVec2::new(120, 68));
+    assert_eq!(tile_grid(UVec2::splat(TILE_SIZE)), UVec2::ONE);
+    assert_eq!(tile_grid(UVec2::splat(TILE_SIZE + 1)), UVec2::splat(2));
+}
+
+#[test]
+fn tile_entry_capacity_is_clamped_to_storage_binding() {
+    let max_binding_size = 128 << 20;
+
+    assert_eq!(
+        tile_entry_capacity(1000, 0, max_binding_size),
+        1000 * TILE_ENTRIES_PER_GAUSSIAN,
+    );
+    assert_eq!(tile_entry_capacity(0, 0, max_binding_size), 1);
+    assert_eq!(
+        tile_entry_capacity(100_000_000, 0, max_binding_size),
+        (max_binding_size / 8) as usize,
+    );
+}
+
+#[test]
+fn tile_entry_capacity_grows_to_requested_entries() {
+    let max_binding_size = 128 << 20;
+
+    assert_eq!(
+        tile_entry_capacity(1000, 1000 * TILE_ENTRIES_PER_GAUSSIAN, max_binding_size),
+        1000 * TILE_ENTRIES_PER_GAUSSIAN,
+    );
+    assert_eq!(
+        tile_entry_capacity(1000, 20_000, max_binding_size),
+        20_000usize.next_power_of_two(),
+    );
+    assert_eq!(
+        tile_entry_capacity(1000, 100_000_000, max_binding_size),
+        (max_binding_size / 8) as usize,
+    );
+}
+
+#[test]
+fn cloud_bounding_sphere_encloses_transformed_aabb() {
+    let aabb = Aabb::from_min_max(Vec3::new(-1.0, 0.0, -2.0), Vec3::new(3.0, 1.0, 2.0));
+    let transform = GlobalTransform::from(
+        Transform::from_xyz(5.0, -2.0, 1.0)
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.3, -1.1, 0.7))
+            .with_scale(Vec3::new(2.0, 0.5, 1.5)),
+    );
+
+    let bounds = cloud_bounding_sphere(&aabb, &transform);
+    let center = bounds.truncate();
+
+    assert!(center.distance(transform.transform_point(Vec3::new(1.0, 0.5, 0.0))) < 1e-4);
+    for corner in 0..8 {
+        let local = Vec3::new(
+            if corner & 1 == 0 { -1.0 } else { 3.0 },
+            if corner & 2 == 0 { 0.0 } else { 1.0 },
+            if corner & 4 == 0 { -2.0 } else { 2.0 },
+        );
+        assert!(transform.transform_point(local).distance(center) <= bounds.w + 1e-4);
+    }
+}
+
+#[test]
+fn tile_rasterizer_is_kept_when_supported() {
+    assert_eq!(Rasterizer::Tile.or_supported(), Rasterizer::Tile);
+    assert_eq!(Rasterizer::Instanced.or_supported(), Rasterizer::Instanced);
+    assert_eq!(
+        Rasterizer::WeightedBlended.or_supported(),
+        Rasterizer::WeightedBlended,
+    );
+}
+
+#[test]
+fn tile_keys_sort_by_tile_then_front_to_back() {
+    let grid = tile_grid(UVec2::new(1920, 1080));
+    let depth_bits = tile_depth_bits(grid);
+    assert_eq!(depth_bits, 32 - 13);
+
+    let key = |tile, distance| tile_key(tile, distance, 10.0, depth_bits);
+    assert!(key(3, 1.0) < key(3, 2.0));
+    assert!(key(3, 9.9) < key(4, 0.0));
+    assert_eq!(key(3, 0.0) >> depth_bits, 3);
+    assert_eq!(key(3, 20.0) >> depth_bits, 3);
+
+    // a single tile leaves more depth bits than an f32 mantissa holds
+    let depth_bits = tile_depth_bits(UVec2::ONE);
+    assert_eq!(depth_bits, 31);
+    assert_eq!(tile_key(0, 10.0, 10.0, depth_bits) >> depth_bits, 0);
+}
+
+#[test]
+fn tile_bounds_bin_pixel_rects() {
+    let size = UVec2::new(WIDTH, HEIGHT);
+
+    assert_eq!(
+        tile_bounds(Vec2::new(10.0, 10.0), Vec2::new(20.0, 12.0), size),
+        Some((UVec2::ZERO, UVec2::new(1, 0))),
+    );
+    assert_eq!(
+        tile_bounds(Vec2::new(-30.0, -5.0), Vec2::new(100.0, 100.0), size),
+        Some((UVec2::ZERO, tile_grid(size) - UVec2::ONE)),
+    );
+    assert_eq!(
+        tile_bounds(Vec2::new(-30.0, 0.0), Vec2::new(-1.0, 5.0), size),
+        None,
+    );
+    assert_eq!(
+        tile_bounds(Vec2::new(WIDTH as f32, 0.0), Vec2::new(70.0, 5.0), size),
+        None,
+    );
+}
+
+#[test]
+fn tile_reference_matches_sorted_compositing() {
+    let cloud = random_gaussians_3d_seeded(64, 11);
+
+    let sorted = render(&cloud, Rasterizer::Instanced, 40.0);
+    let tiled = render(&cloud, Rasterizer::Tile, 40.0);
+
+    assert!(sorted.pixels.iter().filter(|pixel| pixel.w > 1e-3).count() > 64);
+    assert!(
+        sorted.max_difference(&tiled) < 1e-3,
+        "{}",
+        sorted.max_difference(&tiled),
+    );
+
+    // keys are generated per splat, the cloud order never reaches the tiles
+    let mut reversed = cloud.iter().collect::<Vec<_>>();
+    reversed.reverse();
+    let reversed = render(
+        &PlanarGaussian3d::from_interleaved(reversed),
+        Rasterizer::Tile,
+        40.0,
+    );
+    assert_eq!(tiled.max_difference(&reversed), 0.0);
+}
+
+#[test]
+fn tile_reference_terminates_saturated_pixels() {
+    let near = [
+        gaussian([0.0, 0.0, 1.0], 0.5, 0.999, [1.0, -2.0, -2.0]),
+        gaussian([0.0, 0.0, 0.5], 0.5, 0.999, [1.0, -2.0, -2.0]),
+    ];
+    let far = gaussian([0.0, 0.0, -1.0], 0.5, 0.995, [100.0, 100.0, 100.0]);
+
+    let occluded = PlanarGaussian3d::from_interleaved(near.into_iter().chain([far]).collect());
+    let front = PlanarGaussian3d::from_interleaved(near.to_vec());
+
+    let center = |image: &ReferenceImage| image.pixel(WIDTH / 2, HEIGHT / 2);
+
+    // the far splat still bleeds through the sorted quads
+    let sorted = center(&render(&occluded, Rasterizer::Instanced, 5.0));
+    assert!((sorted - center(&render(&front, Rasterizer::Instanced, 5.0))).max_element() > 1e-2);
+
+    // the saturated pixel stops blending before the far splat
+    let tiled = center(&render(&occluded, Rasterizer::Tile, 5.0));
+    assert_eq!(tiled, center(&render(&front, Rasterizer::Tile, 5.0)));
+    assert!(tiled.w > 1.0 - 1e-4, "{tiled}");
+}
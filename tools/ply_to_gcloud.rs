@@ -1,6 +1,9 @@
 use byte_unit::{Byte, UnitType};
 
-use bevy_gaussian_splatting::io::{codec::CloudCodec, ply::parse_ply_3d};
+use bevy_gaussian_splatting::io::{
+    gcloud::header::{GcloudContainer, GcloudMetadata},
+    ply::parse_ply_3d,
+};
 
 #[cfg(feature = "query_sparse")]
 use bevy_gaussian_splatting::query::sparse::SparseSelect;
@@ -74,7 +77,10 @@ fn main() {
         .to_string();
     let gcloud_filename = base_filename + ".gcloud";
 
-    cloud.write_to_file(&gcloud_filename);
+    let metadata = GcloudMetadata::from([("source".to_owned(), filename.clone())]);
+    cloud
+        .write_container_file(&gcloud_filename, &metadata)
+        .expect("failed to write gcloud file");
 
     let post_encode_bytes = Byte::from_u64(
         std::fs::metadata(&gcloud_filename)
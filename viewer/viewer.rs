@@ -37,7 +37,9 @@ use bevy_gaussian_splatting::{
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-use bevy_gaussian_splatting::{SceneExportCamera, SceneExportCloud, write_khr_gaussian_scene_glb};
+use bevy_gaussian_splatting::{
+    SceneCameraProjection, SceneExportCamera, SceneExportCloud, write_khr_gaussian_scene_glb,
+};
 
 #[cfg(feature = "morph_interpolate")]
 use bevy_gaussian_splatting::{Gaussian3d, morph::interpolate::GaussianInterpolate};
@@ -75,8 +77,17 @@ type ExportCloudQuery = (
 );
 
 #[cfg(not(target_arch = "wasm32"))]
-type ExportCameraQuery = (&'static GlobalTransform, Option<&'static Name>);
-type SceneCameraApplyQuery = (Entity, &'static mut Transform, &'static mut PanOrbitCamera);
+type ExportCameraQuery = (
+    &'static GlobalTransform,
+    Option<&'static Name>,
+    Option<&'static Projection>,
+);
+type SceneCameraApplyQuery = (
+    Entity,
+    &'static mut Transform,
+    &'static mut Projection,
+    &'static mut PanOrbitCamera,
+);
 type SceneRenderModeQuery = (Entity, &'static Children);
 type SceneRenderModeFilter = (With<GaussianSceneLoaded>, Without<SceneRenderModeApplied>);
 
@@ -212,6 +223,9 @@ fn setup_gaussian_cloud(
                             gaussian_mode: args.gaussian_mode,
                             playback_mode: args.playback_mode,
                             rasterize_mode: args.rasterization_mode,
+                            rasterizer: args.rasterizer,
+                            oit_weight: args.oit_weight,
+                            depth_mode: args.depth_mode,
                             radix_sort_depth_bits: args.radix_sort_depth_bits,
                             ..default()
                         },
@@ -229,6 +243,9 @@ fn setup_gaussian_cloud(
                             gaussian_mode: args.gaussian_mode,
                             playback_mode: args.playback_mode,
                             rasterize_mode: args.rasterization_mode,
+                            rasterizer: args.rasterizer,
+                            oit_weight: args.oit_weight,
+                            depth_mode: args.depth_mode,
                             radix_sort_depth_bits: args.radix_sort_depth_bits,
                             ..default()
                         },
@@ -247,6 +264,9 @@ fn setup_gaussian_cloud(
                         gaussian_mode: args.gaussian_mode,
                         playback_mode: args.playback_mode,
                         rasterize_mode: args.rasterization_mode,
+                        rasterizer: args.rasterizer,
+                        oit_weight: args.oit_weight,
+                        depth_mode: args.depth_mode,
                         radix_sort_depth_bits: args.radix_sort_depth_bits,
                         ..default()
                     },
@@ -280,6 +300,9 @@ fn setup_gaussian_cloud(
                     gaussian_mode: args.gaussian_mode,
                     playback_mode: args.playback_mode,
                     rasterize_mode: args.rasterization_mode,
+                    rasterizer: args.rasterizer,
+                    oit_weight: args.oit_weight,
+                    depth_mode: args.depth_mode,
                     radix_sort_depth_bits: args.radix_sort_depth_bits,
                     ..default()
                 },
@@ -310,7 +333,7 @@ fn apply_scene_camera_spawn(
         };
 
         if let Some(scene_camera) = scene.cameras.first()
-            && let Ok((camera_entity, mut camera_transform, mut pan_orbit_camera)) =
+            && let Ok((camera_entity, mut camera_transform, mut projection, mut pan_orbit_camera)) =
                 cameras.single_mut()
         {
             let orbit_radius = pan_orbit_camera
@@ -335,6 +358,18 @@ fn apply_scene_camera_spawn(
             };
             *camera_transform = corrected_transform;
 
+            // keep the window driven aspect ratio, the viewer resizes freely
+            let aspect_ratio = match &*projection {
+                Projection::Perspective(perspective) => Some(perspective.aspect_ratio),
+                _ => None,
+            };
+            *projection = scene_camera.projection.to_projection();
+            if let (Projection::Perspective(perspective), Some(aspect_ratio)) =
+                (&mut *projection, aspect_ratio)
+            {
+                perspective.aspect_ratio = aspect_ratio;
+            }
+
             let focus = scene_translation + camera_transform.forward() * orbit_radius;
 
             let (yaw, pitch, radius) = orbit_from_translation_and_focus(
@@ -376,6 +411,9 @@ fn apply_scene_render_mode_override(
             let child: Entity = child;
             if let Ok(mut settings) = cloud_settings.get_mut(child) {
                 settings.rasterize_mode = args.rasterization_mode;
+                settings.rasterizer = args.rasterizer;
+                settings.oit_weight = args.oit_weight;
+                settings.depth_mode = args.depth_mode;
                 settings.radix_sort_depth_bits = args.radix_sort_depth_bits;
             }
         }
@@ -628,7 +666,7 @@ fn press_g_save_gltf_scene(
         };
 
         export_clouds.push(SceneExportCloud {
-            cloud: cloud.clone(),
+            cloud: cloud.clone().into(),
             name: name
                 .map(|value| value.as_str().to_owned())
                 .unwrap_or_else(|| format!("gaussian_cloud_{index}")),
@@ -646,12 +684,14 @@ fn press_g_save_gltf_scene(
     let export_camera = cameras
         .iter()
         .next()
-        .map(|(global_transform, name)| SceneExportCamera {
+        .map(|(global_transform, name, projection)| SceneExportCamera {
             name: name
                 .map(|value| value.as_str().to_owned())
                 .unwrap_or_else(|| "viewer_camera".to_owned()),
             transform: Transform::from_matrix(global_transform.to_matrix()),
-            ..default()
+            projection: projection
+                .and_then(SceneCameraProjection::from_projection)
+                .unwrap_or_default(),
         });
 
     let output_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("exports");